@@ -0,0 +1,255 @@
+//! Row differences between the document and another version of the file
+//! (`:diff`), and the report written by `:diff export`.
+//!
+//! Rows are matched by a key column when one is given, otherwise by
+//! position. Cells are compared by header name, so reordered columns
+//! don't count as changes; columns only one side has are ignored.
+
+use crate::csv::Document;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// How a row differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only in the document
+    Added,
+    /// Only in the other file
+    Removed,
+    /// In both, with different values
+    Changed,
+}
+
+impl DiffKind {
+    /// Name used in the report
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffKind::Added => "added",
+            DiffKind::Removed => "removed",
+            DiffKind::Changed => "changed",
+        }
+    }
+}
+
+/// A row that differs
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowDiff {
+    pub kind: DiffKind,
+    /// Row in the document (the other file's row for removed rows)
+    pub row: usize,
+    /// Changed columns of the document (empty unless changed)
+    pub columns: Vec<usize>,
+    /// Values of the row, laid out like the document's columns
+    pub values: Vec<String>,
+}
+
+/// Differences between the document and another file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    /// Headers of the document
+    pub headers: Vec<String>,
+    /// Differing rows, document rows first in order, then removed rows
+    pub rows: Vec<RowDiff>,
+}
+
+impl Diff {
+    /// Compare `document` with `other`, matching rows on the `key` column
+    /// of the document (or by position)
+    pub fn compare(document: &Document, other: &Document, key: Option<usize>) -> Self {
+        // Where each document column is in the other file
+        let other_columns: Vec<Option<usize>> = document
+            .headers
+            .iter()
+            .map(|header| other.headers.iter().position(|h| h == header))
+            .collect();
+        fn cell(row: &[String], col: usize) -> &str {
+            row.get(col).map_or("", String::as_str)
+        }
+
+        let matches: Vec<Option<usize>> = match key.map(|key| (key, other_columns[key])) {
+            Some((key, Some(other_key))) => {
+                let mut by_key: HashMap<&str, usize> = HashMap::new();
+                for (i, row) in other.rows.iter().enumerate().rev() {
+                    by_key.insert(cell(row, other_key), i);
+                }
+                document
+                    .rows
+                    .iter()
+                    .map(|row| by_key.remove(cell(row, key)))
+                    .collect()
+            }
+            // A key the other file lacks matches nothing
+            Some((_, None)) => vec![None; document.rows.len()],
+            None => (0..document.rows.len())
+                .map(|i| (i < other.rows.len()).then_some(i))
+                .collect(),
+        };
+
+        let mut rows = Vec::new();
+        for (i, (row, matched)) in document.rows.iter().zip(&matches).enumerate() {
+            let Some(other_row) = matched.map(|m| &other.rows[m]) else {
+                rows.push(RowDiff {
+                    kind: DiffKind::Added,
+                    row: i,
+                    columns: Vec::new(),
+                    values: row.clone(),
+                });
+                continue;
+            };
+            let columns: Vec<usize> = other_columns
+                .iter()
+                .enumerate()
+                .filter_map(|(col, other_col)| other_col.map(|other_col| (col, other_col)))
+                .filter(|&(col, other_col)| cell(row, col) != cell(other_row, other_col))
+                .map(|(col, _)| col)
+                .collect();
+            if !columns.is_empty() {
+                rows.push(RowDiff {
+                    kind: DiffKind::Changed,
+                    row: i,
+                    columns,
+                    values: row.clone(),
+                });
+            }
+        }
+
+        let mut matched = vec![false; other.rows.len()];
+        for &m in matches.iter().flatten() {
+            matched[m] = true;
+        }
+        for (i, other_row) in other.rows.iter().enumerate() {
+            if !matched[i] {
+                rows.push(RowDiff {
+                    kind: DiffKind::Removed,
+                    row: i,
+                    columns: Vec::new(),
+                    values: other_columns
+                        .iter()
+                        .map(|c| c.map_or("", |c| cell(other_row, c)).to_string())
+                        .collect(),
+                });
+            }
+        }
+
+        Self {
+            headers: document.headers.clone(),
+            rows,
+        }
+    }
+
+    /// Number of rows that differ in one way
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.rows.iter().filter(|r| r.kind == kind).count()
+    }
+
+    /// "2 added, 1 removed, 3 changed"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} changed",
+            self.count(DiffKind::Added),
+            self.count(DiffKind::Removed),
+            self.count(DiffKind::Changed)
+        )
+    }
+
+    /// The differences as a table: change type, row number, changed
+    /// columns, then the values of the row
+    pub fn report(&self) -> Document {
+        let mut headers = vec![
+            "change".to_string(),
+            "row".to_string(),
+            "changed_columns".to_string(),
+        ];
+        headers.extend(self.headers.iter().cloned());
+        let rows = self
+            .rows
+            .iter()
+            .map(|diff| {
+                let columns: Vec<&str> = diff
+                    .columns
+                    .iter()
+                    .map(|&col| self.headers[col].as_str())
+                    .collect();
+                let mut row = vec![
+                    diff.kind.label().to_string(),
+                    (diff.row + 1).to_string(),
+                    columns.join(";"),
+                ];
+                row.extend(diff.values.iter().cloned());
+                row
+            })
+            .collect();
+        Document {
+            headers,
+            rows,
+            ..Default::default()
+        }
+    }
+
+    /// Write [`Diff::report`] to `path` (`.tsv` and `.gz` paths work as
+    /// for saving)
+    pub fn export(&self, path: &Path) -> Result<()> {
+        self.report().write_to(path, true)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(headers: &[&str], rows: &[&[&str]]) -> Document {
+        Document {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_by_position() {
+        let new = document(&["id", "name"], &[&["1", "a"], &["2", "B"], &["3", "c"]]);
+        let old = document(&["id", "name"], &[&["1", "a"], &["2", "b"]]);
+        let diff = Diff::compare(&new, &old, None);
+        assert_eq!(diff.summary(), "1 added, 0 removed, 1 changed");
+        assert_eq!(diff.rows[0].kind, DiffKind::Changed);
+        assert_eq!(diff.rows[0].columns, vec![1]);
+        assert_eq!(diff.rows[1].kind, DiffKind::Added);
+        assert_eq!(diff.rows[1].row, 2);
+    }
+
+    #[test]
+    fn test_compare_by_key_ignores_column_order() {
+        let new = document(&["id", "name"], &[&["2", "b"], &["3", "c"]]);
+        let old = document(&["name", "id"], &[&["a", "1"], &["bee", "2"]]);
+        let diff = Diff::compare(&new, &old, Some(0));
+        assert_eq!(diff.summary(), "1 added, 1 removed, 1 changed");
+        let removed = &diff.rows[2];
+        assert_eq!(removed.kind, DiffKind::Removed);
+        // Removed values follow the document's column order
+        assert_eq!(removed.values, vec!["1", "a"]);
+    }
+
+    #[test]
+    fn test_report() {
+        let new = document(&["id", "name", "city"], &[&["1", "A", "X"]]);
+        let old = document(
+            &["id", "name", "city"],
+            &[&["1", "a", "x"], &["2", "b", "y"]],
+        );
+        let report = Diff::compare(&new, &old, Some(0)).report();
+        assert_eq!(
+            report.headers,
+            vec!["change", "row", "changed_columns", "id", "name", "city"]
+        );
+        assert_eq!(
+            report.rows[0],
+            vec!["changed", "1", "name;city", "1", "A", "X"]
+        );
+        assert_eq!(report.rows[1], vec!["removed", "2", "", "2", "b", "y"]);
+    }
+}
@@ -0,0 +1,337 @@
+//! Cell expressions over the columns of a row (`:fill total = price * qty`).
+//!
+//! Supported: numbers, quoted text (`"pending"` or `'pending'`), column
+//! names (`` `unit price` `` for names with spaces), `+ - * /` on numbers,
+//! `&` to join text, unary minus and parentheses.
+
+use crate::csv::Document;
+
+/// A value produced while evaluating an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn number(&self) -> Result<f64, String> {
+        match self {
+            Value::Number(n) => Ok(*n),
+            Value::Text(text) => text
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{:?} is not a number", text)),
+        }
+    }
+
+    /// Text written to a cell (whole numbers without a fraction)
+    pub fn into_cell(self) -> String {
+        match self {
+            Value::Text(text) => text,
+            Value::Number(n) => format_number(n),
+        }
+    }
+}
+
+/// Format a number for a cell: `3` rather than `3.0`, otherwise the
+/// shortest form that reads back as the same number
+pub fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Binary operators
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Join,
+}
+
+/// A parsed expression with column names resolved to indexes
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Text(String),
+    Column(usize),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    /// Parse an expression, resolving column names against the document
+    pub fn parse(text: &str, document: &Document) -> Result<Expr, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            document,
+        };
+        let expr = parser.join()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected {}", token.describe())),
+        }
+    }
+
+    /// Columns the expression reads, in order of first use
+    pub fn columns(&self) -> Vec<usize> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<usize>) {
+        match self {
+            Expr::Number(_) | Expr::Text(_) => {}
+            Expr::Column(col) => {
+                if !columns.contains(col) {
+                    columns.push(*col);
+                }
+            }
+            Expr::Neg(inner) => inner.collect_columns(columns),
+            Expr::Binary(left, _, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    /// Evaluate against one row of the document
+    pub fn eval(&self, row: &[String]) -> Result<Value, String> {
+        match self {
+            Expr::Number(n) => Ok(Value::Number(*n)),
+            Expr::Text(text) => Ok(Value::Text(text.clone())),
+            Expr::Column(col) => Ok(Value::Text(row.get(*col).cloned().unwrap_or_default())),
+            Expr::Neg(inner) => Ok(Value::Number(-inner.eval(row)?.number()?)),
+            Expr::Binary(left, Op::Join, right) => Ok(Value::Text(
+                left.eval(row)?.into_cell() + &right.eval(row)?.into_cell(),
+            )),
+            Expr::Binary(left, op, right) => {
+                let (a, b) = (left.eval(row)?.number()?, right.eval(row)?.number()?);
+                let result = match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div if b == 0.0 => return Err("division by zero".to_string()),
+                    Op::Div => a / b,
+                    Op::Join => unreachable!("handled above"),
+                };
+                Ok(Value::Number(result))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Name(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(n) => format!("number {}", format_number(*n)),
+            Token::Text(text) => format!("text {:?}", text),
+            Token::Name(name) => format!("name {}", name),
+            Token::Op(_) => "operator".to_string(),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '&' => {
+                chars.next();
+                tokens.push(Token::Op(match c {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    '/' => Op::Div,
+                    _ => Op::Join,
+                }));
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '"' | '\'' | '`' => {
+                chars.next();
+                let quoted: String = chars.by_ref().take_while(|&ch| ch != c).collect();
+                tokens.push(if c == '`' {
+                    Token::Name(quoted)
+                } else {
+                    Token::Text(quoted)
+                });
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.') {
+                    number.push(d);
+                    chars.next();
+                }
+                let value = number
+                    .parse()
+                    .map_err(|_| format!("Invalid number {}", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&d) = chars
+                    .peek()
+                    .filter(|d| d.is_alphanumeric() || matches!(d, '_' | '.'))
+                {
+                    name.push(d);
+                    chars.next();
+                }
+                tokens.push(Token::Name(name));
+            }
+            other => return Err(format!("Unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent: `&` binds loosest, then `+ -`, then `* /`
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    document: &'a Document,
+}
+
+impl Parser<'_> {
+    fn binary(
+        &mut self,
+        ops: &[Op],
+        next: fn(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        let mut left = next(self)?;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos) {
+            if !ops.contains(op) {
+                break;
+            }
+            self.pos += 1;
+            left = Expr::Binary(Box::new(left), *op, Box::new(next(self)?));
+        }
+        Ok(left)
+    }
+
+    fn join(&mut self) -> Result<Expr, String> {
+        self.binary(&[Op::Join], Self::sum)
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        self.binary(&[Op::Add, Op::Sub], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        self.binary(&[Op::Mul, Op::Div], Self::factor)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or("Expression ends too early")?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Number(*n)),
+            Token::Text(text) => Ok(Expr::Text(text.clone())),
+            Token::Name(name) => match self.document.find_column(name) {
+                Some(col) => Ok(Expr::Column(col.get())),
+                None => Err(format!(
+                    "No column named {} (quote text: \"{}\")",
+                    name, name
+                )),
+            },
+            Token::Op(Op::Sub) => Ok(Expr::Neg(Box::new(self.factor()?))),
+            Token::Open => {
+                let inner = self.join()?;
+                match self.tokens.get(self.pos) {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err("Missing ')'".to_string()),
+                }
+            }
+            other => Err(format!("Unexpected {}", other.describe())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: ["price", "qty", "unit name", "first"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: vec![vec![
+                "2.5".to_string(),
+                "4".to_string(),
+                "kg".to_string(),
+                "Ada".to_string(),
+            ]],
+            ..Default::default()
+        }
+    }
+
+    fn eval(text: &str) -> Result<String, String> {
+        let document = document();
+        let expr = Expr::parse(text, &document)?;
+        expr.eval(&document.rows[0]).map(Value::into_cell)
+    }
+
+    #[test]
+    fn test_arithmetic_and_precedence() {
+        assert_eq!(eval("price * qty"), Ok("10".to_string()));
+        assert_eq!(eval("1 + 2 * 3"), Ok("7".to_string()));
+        assert_eq!(eval("(1 + 2) * 3"), Ok("9".to_string()));
+        assert_eq!(eval("-price / 2"), Ok("-1.25".to_string()));
+        assert_eq!(eval("qty / 0"), Err("division by zero".to_string()));
+    }
+
+    #[test]
+    fn test_text_and_columns() {
+        assert_eq!(eval("\"pending\""), Ok("pending".to_string()));
+        assert_eq!(eval("first & ' ' & `unit name`"), Ok("Ada kg".to_string()));
+        assert_eq!(eval("qty & 'x'"), Ok("4x".to_string()));
+        assert_eq!(
+            eval("first * 2"),
+            Err("\"Ada\" is not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(eval("pending")
+            .unwrap_err()
+            .starts_with("No column named pending"));
+        assert_eq!(eval("(1 + 2"), Err("Missing ')'".to_string()));
+        assert_eq!(eval("1 +"), Err("Expression ends too early".to_string()));
+        assert_eq!(eval("1 2"), Err("Unexpected number 2".to_string()));
+        assert_eq!(eval("1 % 2"), Err("Unexpected character '%'".to_string()));
+    }
+}
@@ -0,0 +1,50 @@
+//! Value counts of a column.
+
+use crate::csv::Document;
+use crate::domain::position::ColIndex;
+use std::collections::HashMap;
+
+/// Count each distinct value of a column, most frequent first (ties in value
+/// order). Empty cells are counted as the empty string.
+pub fn value_counts(document: &Document, column: ColIndex) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for row in &document.rows {
+        let value = row.get(column.get()).map(String::as_str).unwrap_or("");
+        *counts.entry(value).or_insert(0) += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_counts_sorted_by_count() {
+        let document = Document {
+            headers: vec!["x".to_string()],
+            rows: ["b", "a", "b", "", "c", "b", "a"]
+                .iter()
+                .map(|v| vec![v.to_string()])
+                .collect(),
+            ..Default::default()
+        };
+
+        let counts = value_counts(&document, ColIndex::new(0));
+        assert_eq!(
+            counts,
+            vec![
+                ("b".to_string(), 3),
+                ("a".to_string(), 2),
+                (String::new(), 1),
+                ("c".to_string(), 1),
+            ]
+        );
+    }
+}
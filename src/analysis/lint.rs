@@ -0,0 +1,353 @@
+//! RFC 4180 conformance check of a file as it is on disk (`:lint`).
+//!
+//! The parser is forgiving, so problems like a byte order mark, mixed line
+//! endings or a stray quote don't stop a file from loading; this reads the
+//! raw bytes to report them. Saving rewrites the file with consistent
+//! quoting, the first line's line endings throughout and without a BOM, so
+//! most problems are fixed by `:lint fix` followed by `:w`.
+
+/// UTF-8 byte order mark
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Kind of problem found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintRule {
+    /// The file starts with a UTF-8 byte order mark
+    Bom,
+    /// CRLF, LF and CR line endings are mixed
+    LineEndings,
+    /// A quote inside an unquoted field, text after a closing quote, or a
+    /// quote that is never closed
+    Quoting,
+    /// A record with a different number of fields than the first one
+    FieldCount,
+    /// Control characters other than tab and line breaks
+    ControlChars,
+}
+
+impl LintRule {
+    /// Name used in the report
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::Bom => "bom",
+            LintRule::LineEndings => "line endings",
+            LintRule::Quoting => "quoting",
+            LintRule::FieldCount => "field count",
+            LintRule::ControlChars => "control characters",
+        }
+    }
+}
+
+/// A problem at a line of the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub rule: LintRule,
+    /// 1-based line (None for problems with the whole file)
+    pub line: Option<usize>,
+    /// 0-based record, counting the header line (None for the whole file)
+    pub record: Option<usize>,
+    pub message: String,
+}
+
+/// Line endings counted outside quoted fields
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LineEndings {
+    pub crlf: usize,
+    pub lf: usize,
+    pub cr: usize,
+}
+
+impl LineEndings {
+    fn is_mixed(&self) -> bool {
+        [self.crlf, self.lf, self.cr]
+            .iter()
+            .filter(|&&n| n > 0)
+            .count()
+            > 1
+    }
+}
+
+/// Outcome of linting a file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+    pub line_endings: LineEndings,
+    /// Records read, counting the header line
+    pub records: usize,
+}
+
+impl LintReport {
+    /// Check whether the file conforms
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues of one kind
+    pub fn count(&self, rule: LintRule) -> usize {
+        self.issues.iter().filter(|i| i.rule == rule).count()
+    }
+
+    /// One-line summary, e.g. "BOM, 2 quoting, 1 control characters"
+    pub fn summary(&self) -> String {
+        if self.is_clean() {
+            return format!("No problems in {} records", self.records);
+        }
+        let mut rules: Vec<LintRule> = self.issues.iter().map(|i| i.rule).collect();
+        rules.sort();
+        rules.dedup();
+        let parts: Vec<String> = rules
+            .iter()
+            .map(|&rule| match rule {
+                LintRule::Bom => "BOM".to_string(),
+                LintRule::LineEndings => format!(
+                    "mixed line endings ({} CRLF, {} LF, {} CR)",
+                    self.line_endings.crlf, self.line_endings.lf, self.line_endings.cr
+                ),
+                _ => format!("{} {}", self.count(rule), rule.name()),
+            })
+            .collect();
+        parts.join(", ")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    FieldStart,
+    Unquoted,
+    Quoted,
+    /// A quote inside a quoted field: closing, or the first of `""`
+    QuoteInQuoted,
+}
+
+/// Check the raw bytes of a file separated by `delimiter`
+pub fn lint(bytes: &[u8], delimiter: u8) -> LintReport {
+    let mut report = LintReport::default();
+    let bytes = match bytes.strip_prefix(BOM) {
+        Some(rest) => {
+            report.issues.push(LintIssue {
+                rule: LintRule::Bom,
+                line: None,
+                record: None,
+                message: "file starts with a UTF-8 byte order mark".to_string(),
+            });
+            rest
+        }
+        None => bytes,
+    };
+
+    let mut state = State::FieldStart;
+    let mut line = 1;
+    let mut record = 0;
+    let mut record_line = 1;
+    let mut fields = 1;
+    let mut record_empty = true;
+    let mut expected_fields = None;
+    let mut quote_line = 1;
+    let mut issue = |rule, line, record, message: String| {
+        report.issues.push(LintIssue {
+            rule,
+            line: Some(line),
+            record: Some(record),
+            message,
+        })
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        let is_break = byte == b'\n' || byte == b'\r';
+        i += 1;
+
+        if byte == b'\n' && state == State::Quoted {
+            line += 1;
+            continue;
+        }
+        if byte == b'\r' && state == State::Quoted {
+            if bytes.get(i) != Some(&b'\n') {
+                line += 1;
+            }
+            continue;
+        }
+
+        if is_break && state != State::Quoted {
+            // End of record: count the line ending and the fields
+            if byte == b'\r' && bytes.get(i) == Some(&b'\n') {
+                i += 1;
+                report.line_endings.crlf += 1;
+            } else if byte == b'\r' {
+                report.line_endings.cr += 1;
+            } else {
+                report.line_endings.lf += 1;
+            }
+            // Blank lines are skipped by the parser
+            if !record_empty {
+                match expected_fields {
+                    None => expected_fields = Some(fields),
+                    Some(expected) if expected != fields => issue(
+                        LintRule::FieldCount,
+                        record_line,
+                        record,
+                        format!("{} fields, expected {}", fields, expected),
+                    ),
+                    _ => {}
+                }
+                record += 1;
+            }
+            line += 1;
+            record_line = line;
+            fields = 1;
+            record_empty = true;
+            state = State::FieldStart;
+            continue;
+        }
+
+        if (byte < 0x20 && byte != b'\t' && byte != delimiter) || byte == 0x7F {
+            issue(
+                LintRule::ControlChars,
+                line,
+                record,
+                format!("control character 0x{:02X}", byte),
+            );
+        }
+        record_empty = false;
+
+        state = match (state, byte) {
+            (State::FieldStart | State::Unquoted | State::QuoteInQuoted, b) if b == delimiter => {
+                fields += 1;
+                State::FieldStart
+            }
+            (State::FieldStart, b'"') => {
+                quote_line = line;
+                State::Quoted
+            }
+            (State::FieldStart, _) => State::Unquoted,
+            (State::Unquoted, b'"') => {
+                issue(
+                    LintRule::Quoting,
+                    line,
+                    record,
+                    "quote inside an unquoted field".to_string(),
+                );
+                State::Unquoted
+            }
+            (State::Unquoted, _) => State::Unquoted,
+            (State::Quoted, b'"') => State::QuoteInQuoted,
+            (State::Quoted, _) => State::Quoted,
+            (State::QuoteInQuoted, b'"') => State::Quoted,
+            (State::QuoteInQuoted, _) => {
+                issue(
+                    LintRule::Quoting,
+                    line,
+                    record,
+                    "text after a closing quote".to_string(),
+                );
+                State::Unquoted
+            }
+        };
+    }
+
+    if state == State::Quoted {
+        issue(
+            LintRule::Quoting,
+            quote_line,
+            record,
+            "quote never closed".to_string(),
+        );
+    }
+    if !record_empty {
+        if let Some(expected) = expected_fields.filter(|&e| e != fields) {
+            issue(
+                LintRule::FieldCount,
+                record_line,
+                record,
+                format!("{} fields, expected {}", fields, expected),
+            );
+        }
+        record += 1;
+    }
+    report.records = record;
+
+    if report.line_endings.is_mixed() {
+        report.issues.push(LintIssue {
+            rule: LintRule::LineEndings,
+            line: None,
+            record: None,
+            message: format!(
+                "mixed line endings: {} CRLF, {} LF, {} CR",
+                report.line_endings.crlf, report.line_endings.lf, report.line_endings.cr
+            ),
+        });
+    }
+    report.issues.sort_by_key(|i| (i.line.unwrap_or(0), i.rule));
+    report
+}
+
+/// A cell without its stray control characters (tabs and line breaks stay)
+pub fn strip_control_chars(value: &str) -> Option<String> {
+    let stray = |c: char| (c < ' ' && !matches!(c, '\t' | '\n' | '\r')) || c == '\u{7f}';
+    value
+        .contains(stray)
+        .then(|| value.chars().filter(|&c| !stray(c)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(report: &LintReport) -> Vec<(LintRule, Option<usize>)> {
+        report.issues.iter().map(|i| (i.rule, i.line)).collect()
+    }
+
+    #[test]
+    fn test_clean_file() {
+        let report = lint(b"a,b\n1,\"x,\"\"y\"\"\nz\"\n2,3\n", b',');
+        assert!(report.is_clean(), "{:?}", report.issues);
+        assert_eq!(report.records, 3);
+        // The line break inside the quoted field is not a line ending
+        assert_eq!(report.line_endings.lf, 3);
+        assert_eq!(report.summary(), "No problems in 3 records");
+    }
+
+    #[test]
+    fn test_bom_line_endings_and_field_counts() {
+        let report = lint(b"\xEF\xBB\xBFa,b\r\n1,2\n3\r\n\n4,5,6", b',');
+        assert_eq!(
+            rules(&report),
+            vec![
+                (LintRule::Bom, None),
+                (LintRule::LineEndings, None),
+                (LintRule::FieldCount, Some(3)),
+                (LintRule::FieldCount, Some(5)),
+            ]
+        );
+        assert_eq!(report.records, 4);
+        assert_eq!(
+            report.summary(),
+            "BOM, mixed line endings (2 CRLF, 2 LF, 0 CR), 2 field count"
+        );
+    }
+
+    #[test]
+    fn test_quoting_and_control_characters() {
+        let report = lint(b"a,b\nx\"y,\"ok\"z\n\x07,\"open\n", b',');
+        assert_eq!(
+            rules(&report),
+            vec![
+                (LintRule::Quoting, Some(2)),
+                (LintRule::Quoting, Some(2)),
+                (LintRule::Quoting, Some(3)),
+                (LintRule::ControlChars, Some(3)),
+            ]
+        );
+        assert_eq!(report.issues[2].message, "quote never closed");
+        assert_eq!(report.issues[3].record, Some(2));
+        // Tabs are fine, also as the delimiter
+        assert!(lint(b"a\tb\n1\t2\n", b'\t').is_clean());
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        assert_eq!(strip_control_chars("a\u{7}b\tc"), Some("ab\tc".to_string()));
+        assert_eq!(strip_control_chars("line\nbreak"), None);
+    }
+}
@@ -0,0 +1,15 @@
+//! Data analysis over document columns (summaries shown in overlays,
+//! schema validation) and conformance checks of the file itself.
+
+pub mod diff;
+pub mod expression;
+pub mod frequency;
+pub mod lint;
+pub mod stats;
+pub mod validation;
+
+pub use diff::Diff;
+pub use expression::Expr;
+pub use frequency::value_counts;
+pub use stats::{ColumnStats, NumericSummary, StatsReport};
+pub use validation::{Report, Schema};
@@ -0,0 +1,373 @@
+//! Per-column summary statistics, for the stats panel and the
+//! `lazycsv --stats` report.
+
+use crate::csv::{ColumnType, Document, Locale};
+use crate::domain::position::ColIndex;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Number of most frequent values listed in a summary
+pub const TOP_VALUE_COUNT: usize = 5;
+
+/// Cell values treated as null (compared case-insensitively)
+pub const NULL_TOKENS: &[&str] = &["null", "nil", "none", "na", "n/a"];
+
+/// Check whether a cell value spells a null
+pub fn is_null_token(value: &str) -> bool {
+    let value = value.trim();
+    NULL_TOKENS.iter().any(|t| value.eq_ignore_ascii_case(t))
+}
+
+/// Summary of a column whose non-empty values are all numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericSummary {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+}
+
+/// Summary statistics of a single column
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub column: ColIndex,
+    pub header: String,
+    /// Number of rows
+    pub count: usize,
+    /// Empty or whitespace-only cells
+    pub empty: usize,
+    /// Cells spelling a null (see [`NULL_TOKENS`])
+    pub nulls: usize,
+    /// Distinct non-empty, non-null values
+    pub distinct: usize,
+    /// Type of all non-empty, non-null values
+    pub kind: ColumnType,
+    /// Present when the column is numeric
+    pub numeric: Option<NumericSummary>,
+    /// Earliest and latest value of a date column
+    pub date_range: Option<(String, String)>,
+    /// Most frequent values with their counts, most frequent first
+    pub top_values: Vec<(String, usize)>,
+}
+
+impl ColumnStats {
+    /// Compute statistics for a column of the document, reading numbers and
+    /// dates as `locale` writes them
+    pub fn compute(document: &Document, column: ColIndex, locale: &Locale) -> Self {
+        let mut empty = 0;
+        let mut nulls = 0;
+        let mut frequencies: HashMap<&str, usize> = HashMap::new();
+
+        for row in &document.rows {
+            let value = row.get(column.get()).map(String::as_str).unwrap_or("");
+            if value.trim().is_empty() {
+                empty += 1;
+            } else if is_null_token(value) {
+                nulls += 1;
+            } else {
+                *frequencies.entry(value).or_insert(0) += 1;
+            }
+        }
+
+        let kind = ColumnType::infer(frequencies.keys().copied(), locale);
+        let numeric = kind
+            .is_numeric()
+            .then(|| {
+                frequencies
+                    .iter()
+                    .filter_map(|(value, &n)| {
+                        let number = locale.parse_number(value)?;
+                        Some(std::iter::repeat_n(number, n))
+                    })
+                    .flatten()
+                    .collect()
+            })
+            .and_then(summarize);
+        let date_range = (kind == ColumnType::Date)
+            .then(|| {
+                let earliest = frequencies
+                    .keys()
+                    .min_by(|a, b| kind.compare(a, b, locale))?;
+                let latest = frequencies
+                    .keys()
+                    .max_by(|a, b| kind.compare(a, b, locale))?;
+                Some((earliest.trim().to_string(), latest.trim().to_string()))
+            })
+            .flatten();
+
+        let mut top_values: Vec<(String, usize)> = frequencies
+            .iter()
+            .map(|(value, &n)| (value.to_string(), n))
+            .collect();
+        // Most frequent first, ties in value order so the list is stable
+        top_values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_values.truncate(TOP_VALUE_COUNT);
+
+        Self {
+            column,
+            header: document.get_header(column).to_string(),
+            count: document.rows.len(),
+            empty,
+            nulls,
+            distinct: frequencies.len(),
+            kind,
+            numeric,
+            date_range,
+            top_values,
+        }
+    }
+}
+
+/// Statistics of every column of a document (`lazycsv --stats`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsReport {
+    pub rows: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+impl StatsReport {
+    pub fn compute(document: &Document, locale: &Locale) -> Self {
+        Self {
+            rows: document.row_count(),
+            columns: (0..document.column_count())
+                .map(|col| ColumnStats::compute(document, ColIndex::new(col), locale))
+                .collect(),
+        }
+    }
+
+    /// Smallest and largest value of a column, for numbers and dates
+    fn range(stats: &ColumnStats, locale: &Locale) -> Option<(String, String)> {
+        match (&stats.numeric, &stats.date_range) {
+            (Some(numeric), _) => Some((
+                locale.format_number(numeric.min),
+                locale.format_number(numeric.max),
+            )),
+            (None, Some(range)) => Some(range.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// The report as an aligned table, a line per column
+    pub fn format_text(&self, file: &str, locale: &Locale) -> String {
+        let mut lines = vec![[
+            "col", "name", "type", "empty", "null", "distinct", "min", "max",
+        ]
+        .map(String::from)];
+        for stats in &self.columns {
+            let (min, max) =
+                Self::range(stats, locale).unwrap_or_else(|| ("-".to_string(), "-".to_string()));
+            lines.push([
+                crate::ui::column_to_excel_letter(stats.column.get()).into_owned(),
+                stats.header.clone(),
+                stats.kind.label().to_string(),
+                stats.empty.to_string(),
+                stats.nulls.to_string(),
+                stats.distinct.to_string(),
+                min,
+                max,
+            ]);
+        }
+        let mut widths = [0; 8];
+        for line in &lines {
+            for (width, cell) in widths.iter_mut().zip(line) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}: {} rows, {} columns",
+            file,
+            crate::ui::group_thousands(self.rows),
+            self.columns.len()
+        );
+        for line in &lines {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(widths)
+                .enumerate()
+                // Counts are right-aligned
+                .map(|(i, (cell, width))| match i {
+                    3..=5 => format!("{:>width$}", cell),
+                    _ => format!("{:<width$}", cell),
+                })
+                .collect();
+            let _ = writeln!(out, "{}", cells.join("  ").trim_end());
+        }
+        out
+    }
+
+    /// The report as a JSON object; numeric columns also get their mean
+    /// and median
+    pub fn to_json(&self, file: &str) -> String {
+        let columns: Vec<_> = self
+            .columns
+            .iter()
+            .map(|stats| {
+                let mut column = serde_json::json!({
+                    "column": crate::ui::column_to_excel_letter(stats.column.get()),
+                    "name": stats.header,
+                    "type": stats.kind.label(),
+                    "empty": stats.empty,
+                    "nulls": stats.nulls,
+                    "distinct": stats.distinct,
+                    "min": null,
+                    "max": null,
+                });
+                if let Some(numeric) = &stats.numeric {
+                    column["min"] = numeric.min.into();
+                    column["max"] = numeric.max.into();
+                    column["mean"] = numeric.mean.into();
+                    column["median"] = numeric.median.into();
+                } else if let Some((min, max)) = &stats.date_range {
+                    column["min"] = min.as_str().into();
+                    column["max"] = max.as_str().into();
+                }
+                column
+            })
+            .collect();
+        serde_json::json!({
+            "file": file,
+            "rows": self.rows,
+            "columns": columns,
+        })
+        .to_string()
+    }
+}
+
+fn summarize(mut numbers: Vec<f64>) -> Option<NumericSummary> {
+    if numbers.is_empty() || numbers.iter().any(|n| n.is_nan()) {
+        return None;
+    }
+    numbers.sort_by(f64::total_cmp);
+
+    let len = numbers.len();
+    let median = if len.is_multiple_of(2) {
+        (numbers[len / 2 - 1] + numbers[len / 2]) / 2.0
+    } else {
+        numbers[len / 2]
+    };
+
+    Some(NumericSummary {
+        min: numbers[0],
+        max: numbers[len - 1],
+        mean: numbers.iter().sum::<f64>() / len as f64,
+        median,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(values: &[&str]) -> Document {
+        Document {
+            headers: vec!["value".to_string()],
+            rows: values.iter().map(|v| vec![v.to_string()]).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_numeric_column_summary() {
+        let stats = ColumnStats::compute(
+            &document(&["3", "1", "", "4", "NULL", "2"]),
+            ColIndex::new(0),
+            &Locale::default(),
+        );
+
+        assert_eq!(stats.count, 6);
+        assert_eq!(stats.empty, 1);
+        assert_eq!(stats.nulls, 1);
+        assert_eq!(stats.distinct, 4);
+
+        let numeric = stats.numeric.unwrap();
+        assert_eq!(numeric.min, 1.0);
+        assert_eq!(numeric.max, 4.0);
+        assert_eq!(numeric.mean, 2.5);
+        assert_eq!(numeric.median, 2.5);
+    }
+
+    #[test]
+    fn test_stats_report() {
+        let document = Document {
+            headers: vec!["id".to_string(), "city".to_string(), "joined".to_string()],
+            rows: vec![
+                vec![
+                    "1".to_string(),
+                    "Oslo".to_string(),
+                    "2024-03-01".to_string(),
+                ],
+                vec!["12".to_string(), "".to_string(), "2023-12-24".to_string()],
+                vec!["3".to_string(), "n/a".to_string(), "2024-01-02".to_string()],
+            ],
+            ..Default::default()
+        };
+        let report = StatsReport::compute(&document, &Locale::default());
+        assert_eq!(
+            report.format_text("people.csv", &Locale::default()),
+            "people.csv: 3 rows, 3 columns\n\
+             col  name    type  empty  null  distinct  min         max\n\
+             A    id      int       0     0         3  1           12\n\
+             B    city    text      1     1         1  -           -\n\
+             C    joined  date      0     0         3  2023-12-24  2024-03-01\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json("people.csv")).unwrap();
+        assert_eq!(json["rows"], 3);
+        assert_eq!(json["columns"][0]["max"], 12.0);
+        assert_eq!(json["columns"][0]["median"], 3.0);
+        assert_eq!(json["columns"][1]["min"], serde_json::Value::Null);
+        assert_eq!(json["columns"][2]["min"], "2023-12-24");
+    }
+
+    #[test]
+    fn test_text_column_has_no_numeric_summary() {
+        let stats = ColumnStats::compute(
+            &document(&["1", "two", "3"]),
+            ColIndex::new(0),
+            &Locale::default(),
+        );
+        assert_eq!(stats.kind, ColumnType::Text);
+        assert!(stats.numeric.is_none());
+    }
+
+    #[test]
+    fn test_date_column_range() {
+        let stats = ColumnStats::compute(
+            &document(&["2024-05-01", "2023/12/24", "", "2024-01-02"]),
+            ColIndex::new(0),
+            &Locale::default(),
+        );
+        assert_eq!(stats.kind, ColumnType::Date);
+        assert!(stats.numeric.is_none());
+        assert_eq!(
+            stats.date_range,
+            Some(("2023/12/24".to_string(), "2024-05-01".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_top_values_ordered_by_frequency() {
+        let stats = ColumnStats::compute(
+            &document(&["b", "a", "c", "a", "b", "a", "d", "e", "f"]),
+            ColIndex::new(0),
+            &Locale::default(),
+        );
+
+        assert_eq!(stats.top_values.len(), TOP_VALUE_COUNT);
+        assert_eq!(stats.top_values[0], ("a".to_string(), 3));
+        assert_eq!(stats.top_values[1], ("b".to_string(), 2));
+        assert_eq!(stats.top_values[2], ("c".to_string(), 1));
+    }
+
+    #[test]
+    fn test_short_rows_count_as_empty() {
+        let mut doc = document(&["x"]);
+        doc.headers.push("other".to_string());
+        let stats = ColumnStats::compute(&doc, ColIndex::new(1), &Locale::default());
+        assert_eq!(stats.empty, 1);
+        assert_eq!(stats.header, "other");
+    }
+}
@@ -0,0 +1,566 @@
+//! Schema validation of document columns.
+//!
+//! A schema is a TOML file with one table per column:
+//!
+//! ```toml
+//! [columns.id]
+//! type = "integer"
+//! required = true
+//! unique = true
+//!
+//! [columns.email]
+//! pattern = '[^@]+@[^@]+'
+//! ```
+//!
+//! The same engine backs `:validate` in the TUI (violations become error
+//! marks) and `lazycsv validate` in CI (violations set the exit code).
+
+use crate::csv::Document;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Kind of value a column must hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// ISO 8601 calendar date (YYYY-MM-DD)
+    Date,
+}
+
+impl ValueType {
+    /// Check whether a (trimmed, non-empty) value has this type
+    pub fn matches(self, value: &str) -> bool {
+        match self {
+            ValueType::String => true,
+            ValueType::Integer => value.parse::<i64>().is_ok(),
+            ValueType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            ValueType::Boolean => BOOLEAN_VALUES.iter().any(|b| value.eq_ignore_ascii_case(b)),
+            ValueType::Date => is_iso_date(value),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ValueType::String => "string",
+            ValueType::Integer => "integer",
+            ValueType::Number => "number",
+            ValueType::Boolean => "boolean",
+            ValueType::Date => "date",
+        }
+    }
+}
+
+/// Spellings accepted for `type = "boolean"` (case-insensitive)
+const BOOLEAN_VALUES: &[&str] = &["true", "false", "yes", "no", "1", "0"];
+
+/// Rules for one column. Surrounding whitespace is ignored, and empty cells
+/// are only checked by `required`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnRule {
+    /// Kind of value
+    #[serde(rename = "type")]
+    pub value_type: Option<ValueType>,
+    /// No empty cells
+    pub required: bool,
+    /// No value appears twice
+    pub unique: bool,
+    /// The only values accepted
+    pub allowed: Option<Vec<String>>,
+    /// Regular expression the whole value must match
+    pub pattern: Option<String>,
+    /// Smallest number accepted
+    pub min: Option<f64>,
+    /// Largest number accepted
+    pub max: Option<f64>,
+    /// Fewest characters accepted
+    pub min_length: Option<usize>,
+    /// Most characters accepted
+    pub max_length: Option<usize>,
+}
+
+/// Column rules keyed by header name (matched like `--col`: exactly first,
+/// then ignoring case)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Schema {
+    pub columns: BTreeMap<String, ColumnRule>,
+}
+
+impl Schema {
+    /// Parse a schema from TOML text
+    pub fn parse(text: &str) -> Result<Self> {
+        let schema: Self = toml::from_str(text).context("Invalid schema")?;
+        for (name, rule) in &schema.columns {
+            if let Some(pattern) = &rule.pattern {
+                compile_pattern(pattern)
+                    .with_context(|| format!("Invalid pattern for column {}", name))?;
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Schema with a single pattern rule, for `:check <column> <pattern>`
+    pub fn pattern(column: &str, pattern: &str) -> Result<Self> {
+        compile_pattern(pattern).context("Invalid pattern")?;
+        let rule = ColumnRule {
+            pattern: Some(pattern.to_string()),
+            ..Default::default()
+        };
+        Ok(Self {
+            columns: BTreeMap::from([(column.to_string(), rule)]),
+        })
+    }
+
+    /// Load a schema file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| path.display().to_string())
+    }
+
+    /// Check every row of a document against the schema
+    pub fn validate(&self, document: &Document) -> Result<Report> {
+        let mut violations = Vec::new();
+
+        for (name, rule) in &self.columns {
+            let Some(col) = document.find_column(name).map(|c| c.get()) else {
+                violations.push(Violation {
+                    row: None,
+                    column: None,
+                    column_name: name.clone(),
+                    rule: Rule::MissingColumn,
+                    message: "column not found in the header".to_string(),
+                });
+                continue;
+            };
+            let pattern = match &rule.pattern {
+                Some(pattern) => Some(
+                    compile_pattern(pattern)
+                        .with_context(|| format!("Invalid pattern for column {}", name))?,
+                ),
+                None => None,
+            };
+            check_column(document, col, rule, pattern.as_ref(), &mut violations);
+        }
+
+        // Report in reading order: header problems first, then by row and column
+        violations.sort_by_key(|v| (v.row, v.column));
+        Ok(Report {
+            rows: document.row_count(),
+            violations,
+        })
+    }
+}
+
+/// Patterns must match the whole value
+fn compile_pattern(pattern: &str) -> Result<Regex> {
+    Ok(Regex::new(&format!("^(?:{})$", pattern))?)
+}
+
+fn check_column(
+    document: &Document,
+    col: usize,
+    rule: &ColumnRule,
+    pattern: Option<&Regex>,
+    violations: &mut Vec<Violation>,
+) {
+    let column_name = document.headers[col].clone();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for (row, values) in document.rows.iter().enumerate() {
+        let value = values.get(col).map_or("", |v| v.trim());
+        let mut report = |rule: Rule, message: String| {
+            violations.push(Violation {
+                row: Some(row),
+                column: Some(col),
+                column_name: column_name.clone(),
+                rule,
+                message,
+            })
+        };
+
+        if value.is_empty() {
+            if rule.required {
+                report(Rule::Required, "value is required".to_string());
+            }
+            continue;
+        }
+
+        if let Some(value_type) = rule.value_type.filter(|t| !t.matches(value)) {
+            report(
+                Rule::Type,
+                format!("{:?} is not a {}", value, value_type.name()),
+            );
+        }
+        if let Some(allowed) = rule
+            .allowed
+            .as_ref()
+            .filter(|a| !a.iter().any(|v| v == value))
+        {
+            report(
+                Rule::Allowed,
+                format!("{:?} is not one of {}", value, allowed.join(", ")),
+            );
+        }
+        if let Some(pattern) = pattern.filter(|p| !p.is_match(value)) {
+            let shown = rule.pattern.as_deref().unwrap_or(pattern.as_str());
+            report(
+                Rule::Pattern,
+                format!("{:?} does not match {}", value, shown),
+            );
+        }
+        if rule.min.is_some() || rule.max.is_some() {
+            match value.parse::<f64>() {
+                Err(_) => report(Rule::Range, format!("{:?} is not a number", value)),
+                Ok(number) => {
+                    if let Some(min) = rule.min.filter(|&min| number < min) {
+                        report(
+                            Rule::Range,
+                            format!("{} is below the minimum {}", value, min),
+                        );
+                    } else if let Some(max) = rule.max.filter(|&max| number > max) {
+                        report(
+                            Rule::Range,
+                            format!("{} is above the maximum {}", value, max),
+                        );
+                    }
+                }
+            }
+        }
+        let length = value.chars().count();
+        if let Some(min) = rule.min_length.filter(|&min| length < min) {
+            report(
+                Rule::Length,
+                format!("{:?} is shorter than {} characters", value, min),
+            );
+        }
+        if let Some(max) = rule.max_length.filter(|&max| length > max) {
+            report(
+                Rule::Length,
+                format!("{:?} is longer than {} characters", value, max),
+            );
+        }
+        if rule.unique {
+            if let Some(first) = seen.get(value) {
+                report(
+                    Rule::Unique,
+                    format!("{:?} already appears in row {}", value, first + 1),
+                );
+            } else {
+                seen.insert(value, row);
+            }
+        }
+    }
+}
+
+/// Check a YYYY-MM-DD date, including the number of days in the month
+fn is_iso_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Rule a value broke
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    MissingColumn,
+    Required,
+    Type,
+    Allowed,
+    Pattern,
+    Range,
+    Length,
+    Unique,
+}
+
+impl Rule {
+    /// Name used in reports
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::MissingColumn => "missing_column",
+            Rule::Required => "required",
+            Rule::Type => "type",
+            Rule::Allowed => "allowed",
+            Rule::Pattern => "pattern",
+            Rule::Range => "range",
+            Rule::Length => "length",
+            Rule::Unique => "unique",
+        }
+    }
+}
+
+/// A value (or the header) breaking a rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Document row (None for problems with the header)
+    pub row: Option<usize>,
+    /// Document column (None when the column is missing)
+    pub column: Option<usize>,
+    /// Column name as written in the header (or the schema, if missing)
+    pub column_name: String,
+    pub rule: Rule,
+    pub message: String,
+}
+
+impl Violation {
+    /// Where the violation is, as shown in reports ("row 4" or "header")
+    fn location(&self) -> String {
+        match self.row {
+            Some(row) => (row + 1).to_string(),
+            None => "header".to_string(),
+        }
+    }
+}
+
+/// Outcome of validating a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Rows checked
+    pub rows: usize,
+    /// Violations in reading order
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    /// Check whether the document passed
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Number of rows with at least one violation
+    pub fn rows_with_violations(&self) -> usize {
+        let mut rows: Vec<usize> = self.violations.iter().filter_map(|v| v.row).collect();
+        rows.dedup();
+        rows.len()
+    }
+
+    /// Violation counts per rule name
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for violation in &self.violations {
+            *counts.entry(violation.rule.name()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// One-line summary, e.g. "3 violations in 2 of 100 rows"
+    pub fn summary(&self) -> String {
+        if self.is_valid() {
+            return format!("No violations in {} rows", self.rows);
+        }
+        format!(
+            "{} violation{} in {} of {} rows",
+            self.violations.len(),
+            if self.violations.len() == 1 { "" } else { "s" },
+            self.rows_with_violations(),
+            self.rows
+        )
+    }
+
+    /// One line per violation (`file:row:column: rule: message`, 1-based
+    /// rows as shown in the TUI), then the summary
+    pub fn format_text(&self, file: &str) -> String {
+        let mut out = String::new();
+        for v in &self.violations {
+            let _ = writeln!(
+                out,
+                "{}:{}:{}: {}: {}",
+                file,
+                v.location(),
+                v.column_name,
+                v.rule.name(),
+                v.message
+            );
+        }
+        let _ = writeln!(out, "{}: {}", file, self.summary());
+        out
+    }
+
+    /// The report as a JSON object
+    pub fn to_json(&self, file: &str) -> String {
+        let violations: Vec<_> = self
+            .violations
+            .iter()
+            .map(|v| {
+                serde_json::json!({
+                    "row": v.row.map(|row| row + 1),
+                    "column": v.column_name,
+                    "rule": v.rule.name(),
+                    "message": v.message,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "file": file,
+            "valid": self.is_valid(),
+            "rows": self.rows,
+            "rows_with_violations": self.rows_with_violations(),
+            "violation_count": self.violations.len(),
+            "counts": self.counts(),
+            "violations": violations,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["id".to_string(), "email".to_string(), "status".to_string()],
+            rows: [
+                ("1", "a@example.com", "open"),
+                ("2", "not-an-email", "closed"),
+                ("2", "", "pending"),
+                ("x", "d@example.com", "open"),
+            ]
+            .iter()
+            .map(|(a, b, c)| vec![a.to_string(), b.to_string(), c.to_string()])
+            .collect(),
+            ..Default::default()
+        }
+    }
+
+    const SCHEMA: &str = r#"
+        [columns.id]
+        type = "integer"
+        unique = true
+
+        [columns.email]
+        required = true
+        pattern = '[^@]+@[^@]+'
+
+        [columns.status]
+        allowed = ["open", "closed"]
+
+        [columns.created]
+        type = "date"
+    "#;
+
+    #[test]
+    fn test_validate_reports_each_rule() {
+        let report = Schema::parse(SCHEMA)
+            .unwrap()
+            .validate(&document())
+            .unwrap();
+        let found: Vec<(Option<usize>, &str)> = report
+            .violations
+            .iter()
+            .map(|v| (v.row, v.rule.name()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (None, "missing_column"),
+                (Some(1), "pattern"),
+                (Some(2), "unique"),
+                (Some(2), "required"),
+                (Some(2), "allowed"),
+                (Some(3), "type"),
+            ]
+        );
+        assert!(!report.is_valid());
+        assert_eq!(report.summary(), "6 violations in 3 of 4 rows");
+        assert_eq!(
+            report.format_text("data.csv").lines().nth(1),
+            Some("data.csv:2:email: pattern: \"not-an-email\" does not match [^@]+@[^@]+")
+        );
+    }
+
+    #[test]
+    fn test_json_report() {
+        let report = Schema::parse(SCHEMA)
+            .unwrap()
+            .validate(&document())
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&report.to_json("data.csv")).unwrap();
+
+        assert_eq!(json["valid"], false);
+        assert_eq!(json["violation_count"], 6);
+        assert_eq!(json["counts"]["unique"], 1);
+        assert_eq!(json["violations"][0]["row"], serde_json::Value::Null);
+        assert_eq!(json["violations"][1]["row"], 2);
+    }
+
+    #[test]
+    fn test_ranges_lengths_and_dates() {
+        let schema = Schema::parse(
+            r#"
+            [columns.id]
+            min = 2
+            max_length = 1
+            "#,
+        )
+        .unwrap();
+        let report = schema.validate(&document()).unwrap();
+        let rules: Vec<&str> = report.violations.iter().map(|v| v.rule.name()).collect();
+        assert_eq!(rules, vec!["range", "range"]);
+
+        assert!(is_iso_date("2024-02-29"));
+        assert!(!is_iso_date("2023-02-29"));
+        assert!(!is_iso_date("2024-13-01"));
+        assert!(!is_iso_date("24-01-01"));
+    }
+
+    #[test]
+    fn test_single_pattern_check() {
+        let report = Schema::pattern("EMAIL", r"^\S+@\S+$")
+            .unwrap()
+            .validate(&document())
+            .unwrap();
+        // Empty cells are not checked
+        assert_eq!(
+            report
+                .violations
+                .iter()
+                .map(|v| (v.row, v.column))
+                .collect::<Vec<_>>(),
+            vec![(Some(1), Some(1))]
+        );
+        assert!(Schema::pattern("email", "(").is_err());
+    }
+
+    #[test]
+    fn test_invalid_schema_is_an_error() {
+        assert!(Schema::parse("[columns.id]\ntype = \"uuid\"").is_err());
+        assert!(Schema::parse("[columns.id]\nunknown = true").is_err());
+        assert!(Schema::parse("[columns.id]\npattern = '('").is_err());
+        assert!(Schema::parse("")
+            .unwrap()
+            .validate(&document())
+            .unwrap()
+            .is_valid());
+    }
+}
@@ -0,0 +1,133 @@
+//! `:ls` and `:b`: the session's files by number, like vim's buffer list,
+//! to jump straight to one instead of cycling with `[` and `]`.
+
+use super::App;
+use crate::ui::file_picker::file_label;
+use crate::ui::DIRTY_MARKER;
+
+impl App {
+    /// The session's files numbered from 1 (`:ls`), `%` marking the open
+    /// one and `[+]` those with unsaved changes
+    pub fn buffer_list(&self) -> String {
+        let active = self.session.active_file_index();
+        self.session
+            .files()
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let current = if index == active { "%" } else { "" };
+                let dirty = if self.file_is_dirty(index) {
+                    DIRTY_MARKER
+                } else {
+                    ""
+                };
+                format!("{} {}{}{}", index + 1, current, file_label(path), dirty)
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    /// The file `:b` names: its number in `:ls`, or part of its path (a
+    /// number past the last file, like `2023`, is matched as a name). A
+    /// file whose name is exactly the one given wins over others containing
+    /// it; case only matters when the name given has capitals.
+    pub fn find_buffer(&self, name: &str) -> Result<usize, String> {
+        let files = self.session.files();
+        let number = name.parse::<usize>().ok();
+        if let Some(number @ 1..) = number.filter(|&number| number <= files.len()) {
+            return Ok(number - 1);
+        }
+
+        let ignore_case = !name.chars().any(char::is_uppercase);
+        let fold = |text: String| {
+            if ignore_case {
+                text.to_lowercase()
+            } else {
+                text
+            }
+        };
+        let wanted = fold(name.to_string());
+        let labels: Vec<String> = files.iter().map(|path| fold(file_label(path))).collect();
+        let exact: Vec<usize> = files
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| {
+                path.file_name()
+                    .is_some_and(|file| fold(file.to_string_lossy().into_owned()) == wanted)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let matching: Vec<usize> = if exact.is_empty() {
+            (0..files.len())
+                .filter(|&index| labels[index].contains(&wanted))
+                .collect()
+        } else {
+            exact
+        };
+        match matching.as_slice() {
+            [] => Err(match number {
+                Some(_) => format!("No file {} (there are {})", name, files.len()),
+                None => format!("No file matching {}", name),
+            }),
+            [index] => Ok(*index),
+            several => {
+                let names: Vec<String> = several
+                    .iter()
+                    .map(|&index| file_label(&files[index]))
+                    .collect();
+                Err(format!(
+                    "More than one file matches {}: {}",
+                    name,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::Document;
+    use crate::session::FileConfig;
+    use std::path::PathBuf;
+
+    fn app() -> App {
+        let files = vec![
+            PathBuf::from("sales_2023.csv"),
+            PathBuf::from("sales_2024.csv"),
+            PathBuf::from("Customers.csv"),
+            PathBuf::from("customers.csv.bak.csv"),
+        ];
+        App::new(Document::default(), files, 1, FileConfig::new())
+    }
+
+    #[test]
+    fn test_buffer_list() {
+        let mut app = app();
+        app.document.is_dirty = true;
+        assert_eq!(
+            app.buffer_list(),
+            "1 sales_2023.csv  2 %sales_2024.csv[+]  3 Customers.csv  4 customers.csv.bak.csv"
+        );
+    }
+
+    #[test]
+    fn test_find_buffer() {
+        let app = app();
+        assert_eq!(app.find_buffer("3"), Ok(2));
+        assert!(app.find_buffer("0").is_err());
+        assert!(app.find_buffer("5").is_err());
+        assert_eq!(app.find_buffer("2023"), Ok(0));
+        assert_eq!(
+            app.find_buffer("sales"),
+            Err("More than one file matches sales: sales_2023.csv, sales_2024.csv".to_string())
+        );
+        // An exact name wins over names containing it
+        assert_eq!(app.find_buffer("customers.csv"), Ok(2));
+        assert_eq!(app.find_buffer("bak"), Ok(3));
+        // Capitals make the match case-sensitive
+        assert_eq!(app.find_buffer("Cust"), Ok(2));
+        assert!(app.find_buffer("orders").is_err());
+    }
+}
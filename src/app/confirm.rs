@@ -0,0 +1,46 @@
+//! Yes/no questions asked before commands that change a lot at once.
+//!
+//! While a question is open the status bar shows it and the next key
+//! answers it: `y` goes ahead, anything else cancels. Saving sorted rows
+//! also takes `o` to keep the order of the file, and a tab-separated paste
+//! takes `n` to keep it in one cell.
+
+use super::Change;
+use crate::session::CellPosition;
+
+/// What happens when the question is answered with `y`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    /// Apply an undoable change, then show `message`
+    Commit { change: Change, message: String },
+    /// Save the sorted rows (`y`) or the rows in the order they were read
+    /// (`o`), as `:w` would with these arguments, quitting after `:wq`
+    Write {
+        path: Option<String>,
+        force: bool,
+        quit: bool,
+    },
+    /// Spread pasted tab-separated lines across the cells right of and
+    /// below the cursor (`y`), or paste the text into the current cell (`n`)
+    Paste { text: String },
+    /// Jump to the file's review resume point
+    Resume(CellPosition),
+    /// Load the open file's swap file (`y`) or delete it (`d`)
+    Recover,
+    /// Overwrite the file with only these rows and columns (`:w --columns`,
+    /// `:w --visible`)
+    WriteSelection {
+        rows: Vec<usize>,
+        columns: Vec<usize>,
+    },
+    /// Make these changes to the files at these session indexes and write
+    /// them (`:replaceall`)
+    ReplaceAll { changes: Vec<(usize, Change)> },
+}
+
+/// An open question
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirm {
+    pub question: String,
+    pub action: ConfirmAction,
+}
@@ -0,0 +1,308 @@
+//! `:copy [csv|tsv|sql [table]]`: copy the current row, or a `'<,'>` Visual
+//! selection, to the clipboard as CSV, TSV or SQL `INSERT` statements.
+//!
+//! Also the text a spreadsheet exchanges through the clipboard: one value
+//! per line for a column (`yc`), and tab-separated lines read back when
+//! pasted.
+
+use crate::csv::Document;
+use std::ops::RangeInclusive;
+
+/// Words that can't be used as a bare SQL identifier
+const SQL_KEYWORDS: &[&str] = &[
+    "all",
+    "and",
+    "as",
+    "asc",
+    "by",
+    "check",
+    "column",
+    "date",
+    "default",
+    "desc",
+    "from",
+    "group",
+    "index",
+    "insert",
+    "into",
+    "key",
+    "limit",
+    "not",
+    "null",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "time",
+    "to",
+    "user",
+    "values",
+    "where",
+];
+
+/// How copied rows are written
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyFormat {
+    Csv,
+    /// Tab-separated, which spreadsheets paste into cells
+    Tsv,
+    /// One `INSERT INTO <table>` per row
+    Sql {
+        table: String,
+    },
+}
+
+impl CopyFormat {
+    /// Parse `[csv|tsv|sql [table]]`; the SQL table defaults to the file
+    /// name without extensions
+    pub fn parse(args: &str, document: &Document) -> Result<Self, String> {
+        let mut words = args.split_whitespace();
+        let format = match words.next().map(str::to_lowercase).as_deref() {
+            None | Some("tsv") => Self::Tsv,
+            Some("csv") => Self::Csv,
+            Some("sql") => {
+                let table = match words.next() {
+                    Some(table) => table.to_string(),
+                    None => default_table(&document.filename),
+                };
+                Self::Sql { table }
+            }
+            Some(other) => {
+                return Err(format!(
+                    "Unknown copy format {} (use csv, tsv or sql)",
+                    other
+                ))
+            }
+        };
+        match words.next() {
+            Some(_) => Err("Usage: :copy [csv|tsv|sql [table]]".to_string()),
+            None => Ok(format),
+        }
+    }
+
+    /// Name shown in messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Tsv => "TSV",
+            Self::Sql { .. } => "SQL",
+        }
+    }
+
+    /// Text for `rows` of the document; CSV and TSV start with the headers
+    pub fn format(&self, document: &Document, rows: &[usize]) -> String {
+        let cells = rows.iter().filter_map(|&row| document.rows.get(row));
+        match self {
+            Self::Csv | Self::Tsv => {
+                let delimiter = if *self == Self::Csv { b',' } else { b'\t' };
+                let mut writer = csv::WriterBuilder::new()
+                    .delimiter(delimiter)
+                    .flexible(true)
+                    .from_writer(Vec::new());
+                let records = std::iter::once(&document.headers).chain(cells);
+                for record in records {
+                    // Writing to a Vec can't fail
+                    let _ = writer.write_record(record);
+                }
+                let bytes = writer.into_inner().unwrap_or_default();
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+            Self::Sql { table } => {
+                // `schema.table` quotes each part
+                let table: Vec<String> = table.split('.').map(sql_identifier).collect();
+                let table = table.join(".");
+                let columns: Vec<String> =
+                    document.headers.iter().map(|h| sql_identifier(h)).collect();
+                cells
+                    .map(|cells| {
+                        let values: Vec<String> = (0..columns.len())
+                            .map(|col| sql_value(cells.get(col).map_or("", String::as_str)))
+                            .collect();
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({});\n",
+                            table,
+                            columns.join(", "),
+                            values.join(", ")
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Values of a column in `rows`, one per line (quoted like TSV where a
+/// value holds a line break, tab or quote), for pasting into a spreadsheet
+pub fn column_text(document: &Document, rows: &[usize], col: usize) -> String {
+    block_text(document, rows, col..=col)
+}
+
+/// Cells of `columns` in `rows` as tab-separated lines, quoted like
+/// [`column_text`]
+pub fn block_text(document: &Document, rows: &[usize], columns: RangeInclusive<usize>) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_writer(Vec::new());
+    for row in rows.iter().filter_map(|&row| document.rows.get(row)) {
+        let cells = columns
+            .clone()
+            .map(|col| row.get(col).map_or("", String::as_str));
+        // Writing to a Vec can't fail
+        let _ = writer.write_record(cells);
+    }
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Cells of pasted tab-separated text, a row per line (values a
+/// spreadsheet quoted keep their line breaks and tabs)
+pub fn parse_cells(text: &str) -> Vec<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    reader
+        .records()
+        // A malformed line is taken as it is
+        .map(|record| match record {
+            Ok(record) => record.iter().map(str::to_string).collect(),
+            Err(_) => vec![String::new()],
+        })
+        .collect()
+}
+
+/// Table named after the file: `orders.csv.gz` is `orders`
+fn default_table(filename: &str) -> String {
+    let stem = filename.split('.').next().unwrap_or_default();
+    if stem.is_empty() {
+        "data".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// A name as a SQL identifier, double-quoted unless it is a plain word
+pub fn sql_identifier(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !SQL_KEYWORDS.contains(&name.to_lowercase().as_str());
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// A cell as a SQL literal: empty cells are NULL, plain numbers stay bare
+/// (but not ones like `007` that would lose their zeros), anything else is
+/// a quoted string
+pub fn sql_value(cell: &str) -> String {
+    if cell.is_empty() {
+        return "NULL".to_string();
+    }
+    let digits = cell.strip_prefix('-').unwrap_or(cell);
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits, None),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let number = is_digits(whole)
+        && (whole == "0" || !whole.starts_with('0'))
+        && fraction.is_none_or(is_digits);
+    if number {
+        cell.to_string()
+    } else {
+        format!("'{}'", cell.replace('\'', "''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec![
+                "id".to_string(),
+                "full name".to_string(),
+                "order".to_string(),
+            ],
+            rows: vec![
+                vec!["1".to_string(), "O'Brien".to_string(), "007".to_string()],
+                vec!["2".to_string(), String::new(), "-1.5".to_string()],
+            ],
+            filename: "customers.csv.gz".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_column_text_and_pasted_cells() {
+        let mut document = document();
+        document.rows[1][1] = "two\nlines".to_string();
+        let text = column_text(&document, &[0, 1], 1);
+        assert_eq!(text, "O'Brien\n\"two\nlines\"\n");
+        assert_eq!(
+            parse_cells(&text),
+            vec![vec!["O'Brien".to_string()], vec!["two\nlines".to_string()]]
+        );
+        assert_eq!(
+            parse_cells("a\tb\r\nc"),
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["c".to_string()]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sql_inserts() {
+        let document = document();
+        let format = CopyFormat::parse("sql", &document).unwrap();
+        assert_eq!(
+            format.format(&document, &[0, 1]),
+            "INSERT INTO customers (id, \"full name\", \"order\") VALUES (1, 'O''Brien', '007');\n\
+             INSERT INTO customers (id, \"full name\", \"order\") VALUES (2, NULL, -1.5);\n"
+        );
+        let format = CopyFormat::parse("SQL staging.user", &document).unwrap();
+        assert!(format
+            .format(&document, &[1])
+            .starts_with("INSERT INTO staging.\"user\" ("));
+    }
+
+    #[test]
+    fn test_sql_escaping() {
+        assert_eq!(sql_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(sql_identifier("_x1"), "_x1");
+        assert_eq!(sql_identifier("1x"), "\"1x\"");
+        assert_eq!(sql_value("0.5"), "0.5");
+        assert_eq!(sql_value("1e3"), "'1e3'");
+        assert_eq!(sql_value("1."), "'1.'");
+        assert_eq!(sql_value("'); DROP TABLE x; --"), "'''); DROP TABLE x; --'");
+    }
+
+    #[test]
+    fn test_csv_tsv_and_errors() {
+        let document = document();
+        assert_eq!(
+            CopyFormat::parse("", &document)
+                .unwrap()
+                .format(&document, &[0]),
+            "id\tfull name\torder\n1\tO'Brien\t007\n"
+        );
+        assert_eq!(
+            CopyFormat::parse("csv", &document)
+                .unwrap()
+                .format(&document, &[1]),
+            "id,full name,order\n2,,-1.5\n"
+        );
+        assert!(CopyFormat::parse("json", &document).is_err());
+        assert!(CopyFormat::parse("csv extra", &document).is_err());
+    }
+}
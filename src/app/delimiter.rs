@@ -0,0 +1,129 @@
+//! `:delimiter ;`: read the open file split at another delimiter than the
+//! one detected, without changing how the session's other files are read.
+//!
+//! The choice is kept for the file while the session lasts (and written by
+//! `:session save`), so switching away and back reads it the same way.
+
+use super::App;
+use crate::session::FileConfig;
+
+/// Parse a `:delimiter` argument: a single character, a name (`tab`,
+/// `comma`, ...) or `auto` (None) to detect it again
+pub fn parse_delimiter(arg: &str) -> Result<Option<u8>, String> {
+    match arg {
+        "auto" => Ok(None),
+        "tab" | "\\t" => Ok(Some(b'\t')),
+        "space" => Ok(Some(b' ')),
+        "comma" => Ok(Some(b',')),
+        "semicolon" => Ok(Some(b';')),
+        "pipe" => Ok(Some(b'|')),
+        _ => match arg.as_bytes() {
+            [c] if c.is_ascii() && *c != b'"' && *c != b'\n' => Ok(Some(*c)),
+            _ => Err(format!(
+                "Invalid delimiter: {} (use one character, tab, space or auto)",
+                arg
+            )),
+        },
+    }
+}
+
+/// A delimiter as shown in messages
+pub fn delimiter_name(delimiter: u8) -> String {
+    match delimiter {
+        b'\t' => "tab".to_string(),
+        b' ' => "space".to_string(),
+        c => format!("'{}'", c as char),
+    }
+}
+
+impl App {
+    /// The delimiter of the open file and where it came from (`:delimiter`
+    /// without an argument)
+    pub fn describe_delimiter(&self) -> String {
+        let path = self.get_current_file();
+        let source = if self.session.file_config(path).is_some() {
+            "set with :delimiter"
+        } else if self.session.default_config().delimiter.is_some() {
+            "set with --delimiter"
+        } else {
+            "detected"
+        };
+        format!(
+            "Delimiter {} ({})",
+            delimiter_name(self.document.delimiter),
+            source
+        )
+    }
+
+    /// Read the open file again split at `delimiter` (None: detect it, or
+    /// use `--delimiter`); returns the message to show
+    pub fn set_file_delimiter(&mut self, delimiter: Option<u8>) -> Result<String, String> {
+        if self.document.is_dirty {
+            return Err("Unsaved changes! Save with :w before reading the file again".to_string());
+        }
+        let path = self.get_current_file().clone();
+        let previous = self.session.file_config(&path).cloned();
+        let config = delimiter.map(|delimiter| FileConfig {
+            delimiter: Some(delimiter),
+            ..self.session.default_config().clone()
+        });
+        self.session.set_file_config(&path, config);
+        if let Err(err) = self.reread_current_file() {
+            self.session.set_file_config(&path, previous);
+            return Err(format!("{:#}", err));
+        }
+        Ok(format!(
+            "Reading {} split at {}: {} columns",
+            self.document.filename,
+            delimiter_name(self.document.delimiter),
+            self.document.column_count()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::Document;
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter(";"), Ok(Some(b';')));
+        assert_eq!(parse_delimiter("tab"), Ok(Some(b'\t')));
+        assert_eq!(parse_delimiter("auto"), Ok(None));
+        assert!(parse_delimiter(";;").is_err());
+        assert!(parse_delimiter("\"").is_err());
+        assert!(parse_delimiter("é").is_err());
+    }
+
+    #[test]
+    fn test_delimiter_is_kept_per_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let commas = dir.path().join("a.csv");
+        let semicolons = dir.path().join("b.csv");
+        std::fs::write(&commas, "name,city\nann,Oslo\n").unwrap();
+        // The notes have commas in them
+        std::fs::write(&semicolons, "name;note\nann;a,b\n").unwrap();
+        let files = vec![commas.clone(), semicolons.clone()];
+        let document = Document::from_file(&semicolons, None, false, None).unwrap();
+        let mut app = App::new(document, files, 1, FileConfig::new());
+
+        let message = app.set_file_delimiter(Some(b';')).unwrap();
+        assert_eq!(message, "Reading b.csv split at ';': 2 columns");
+        assert_eq!(app.document.rows[0], vec!["ann", "a,b"]);
+        assert_eq!(
+            app.describe_delimiter(),
+            "Delimiter ';' (set with :delimiter)"
+        );
+
+        // The other file is still read with its own
+        assert_eq!(app.session.config_for(&commas).delimiter, None);
+        assert_eq!(app.session.config_for(&semicolons).delimiter, Some(b';'));
+
+        app.document.is_dirty = true;
+        assert!(app.set_file_delimiter(None).is_err());
+        app.document.is_dirty = false;
+        app.set_file_delimiter(None).unwrap();
+        assert!(app.session.file_config(&semicolons).is_none());
+    }
+}
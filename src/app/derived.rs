@@ -0,0 +1,223 @@
+//! Derived columns (`:derive total = price * qty`): columns computed from
+//! others by an expression and kept up to date as their source cells
+//! change. `:deps` lists them, `:underive` turns a column back into plain
+//! data.
+
+use super::undo::Edit;
+use crate::analysis::expression::Expr;
+use crate::csv::Document;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A column computed by an expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedColumn {
+    /// Column written
+    pub column: usize,
+    /// The expression as typed
+    pub text: String,
+    pub expr: Expr,
+}
+
+impl DerivedColumn {
+    /// Columns the expression reads
+    pub fn sources(&self) -> Vec<usize> {
+        self.expr.columns()
+    }
+}
+
+/// The derived columns of a document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DerivedColumns {
+    columns: Vec<DerivedColumn>,
+}
+
+impl DerivedColumns {
+    /// Derive `column` from `expr` (replacing what it was derived from), or
+    /// an error if the column would depend on itself
+    pub fn set(&mut self, column: usize, text: &str, expr: Expr) -> Result<(), String> {
+        if self.reaches(&expr.columns(), column) {
+            return Err("A column can't be derived from itself".to_string());
+        }
+        self.remove(column);
+        self.columns.push(DerivedColumn {
+            column,
+            text: text.trim().to_string(),
+            expr,
+        });
+        Ok(())
+    }
+
+    /// Make `column` plain data again; false if it wasn't derived
+    pub fn remove(&mut self, column: usize) -> bool {
+        let before = self.columns.len();
+        self.columns.retain(|derived| derived.column != column);
+        self.columns.len() != before
+    }
+
+    /// Check whether a column is derived
+    pub fn contains(&self, column: usize) -> bool {
+        self.columns.iter().any(|derived| derived.column == column)
+    }
+
+    /// Check whether no column is derived
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Derived columns in the order they were defined
+    pub fn iter(&self) -> impl Iterator<Item = &DerivedColumn> {
+        self.columns.iter()
+    }
+
+    /// Whether `target` is among `columns` or what they are derived from
+    fn reaches(&self, columns: &[usize], target: usize) -> bool {
+        let mut pending = columns.to_vec();
+        let mut seen = BTreeSet::new();
+        while let Some(column) = pending.pop() {
+            if column == target {
+                return true;
+            }
+            if seen.insert(column) {
+                if let Some(derived) = self.columns.iter().find(|d| d.column == column) {
+                    pending.extend(derived.sources());
+                }
+            }
+        }
+        false
+    }
+
+    /// Edits bringing the derived cells of the rows `edits` changed up to
+    /// date, including columns derived from other derived columns; cells
+    /// the expression fails on are left alone
+    pub fn recompute(&self, document: &Document, edits: &[Edit]) -> Vec<Edit> {
+        if self.columns.is_empty() {
+            return Vec::new();
+        }
+        // Changed columns per row; None for a whole new row
+        let mut changed: BTreeMap<usize, Option<BTreeSet<usize>>> = BTreeMap::new();
+        for edit in edits {
+            match edit {
+                Edit::Cell { row, col, .. } => {
+                    if let Some(columns) = changed.entry(*row).or_insert(Some(BTreeSet::new())) {
+                        columns.insert(*col);
+                    }
+                }
+                Edit::InsertRow { at, .. } => {
+                    changed.insert(*at, None);
+                }
+                Edit::DeleteRow { .. } | Edit::Reorder { .. } => {}
+            }
+        }
+
+        let mut recomputed = Vec::new();
+        for (row, mut columns) in changed {
+            let Some(mut cells) = document.rows.get(row).cloned() else {
+                continue;
+            };
+            // Each pass can only feed columns derived one step further
+            for _ in 0..self.columns.len() {
+                let mut updated = false;
+                for derived in &self.columns {
+                    let affected = columns.as_ref().is_none_or(|columns| {
+                        derived.sources().iter().any(|col| columns.contains(col))
+                    });
+                    if !affected || derived.column >= cells.len() {
+                        continue;
+                    }
+                    let Ok(value) = derived.expr.eval(&cells) else {
+                        continue;
+                    };
+                    let new = value.into_cell();
+                    if cells[derived.column] == new {
+                        continue;
+                    }
+                    let old = std::mem::replace(&mut cells[derived.column], new.clone());
+                    recomputed.push(Edit::Cell {
+                        row,
+                        col: derived.column,
+                        old,
+                        new,
+                    });
+                    if let Some(columns) = columns.as_mut() {
+                        columns.insert(derived.column);
+                    }
+                    updated = true;
+                }
+                if !updated {
+                    break;
+                }
+            }
+        }
+        recomputed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: ["price", "qty", "total", "taxed"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: vec![["2", "3", "6", "7.2"]
+                .iter()
+                .map(|c| c.to_string())
+                .collect()],
+            ..Default::default()
+        }
+    }
+
+    fn derive(derived: &mut DerivedColumns, document: &Document, column: usize, text: &str) {
+        let expr = Expr::parse(text, document).unwrap();
+        derived.set(column, text, expr).unwrap();
+    }
+
+    #[test]
+    fn test_recompute_follows_chains() {
+        let mut document = document();
+        let mut derived = DerivedColumns::default();
+        derive(&mut derived, &document, 2, "price * qty");
+        derive(&mut derived, &document, 3, "total * 1.2");
+
+        document.rows[0][1] = "5".to_string();
+        let edit = Edit::Cell {
+            row: 0,
+            col: 1,
+            old: "3".to_string(),
+            new: "5".to_string(),
+        };
+        let cells: Vec<(usize, String)> = derived
+            .recompute(&document, &[edit])
+            .into_iter()
+            .map(|edit| match edit {
+                Edit::Cell { col, new, .. } => (col, new),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(cells, vec![(2, "10".to_string()), (3, "12".to_string())]);
+
+        // Editing a column nothing is derived from changes nothing
+        let edit = Edit::Cell {
+            row: 0,
+            col: 3,
+            old: String::new(),
+            new: String::new(),
+        };
+        assert!(derived.recompute(&document, &[edit]).is_empty());
+    }
+
+    #[test]
+    fn test_cycles_are_rejected() {
+        let document = document();
+        let mut derived = DerivedColumns::default();
+        derive(&mut derived, &document, 2, "price * qty");
+        let expr = Expr::parse("total + 1", &document).unwrap();
+        assert!(derived.set(0, "total + 1", expr.clone()).is_err());
+        assert!(derived.set(2, "total + 1", expr).is_err());
+        assert!(derived.remove(2));
+        assert!(!derived.contains(2));
+    }
+}
@@ -0,0 +1,121 @@
+//! How an interactive session ends: the exit code, and what `--print`
+//! writes to stdout, so scripts can use lazycsv as a picker.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Quit normally (`q`, `:q`, `:wq`) |
+//! | 1 | Error while running, or `:cq` |
+//! | 2 | Quit discarding unsaved changes (`:q!`) |
+//! | 3 | The file could not be loaded |
+//!
+//! With `--pick`, only Enter quits with 0; any other way out counts as
+//! cancelling, like fzf.
+
+use super::App;
+use crate::cli::PrintOnExit;
+use crate::domain::position::{ColIndex, RowIndex};
+use anyhow::Result;
+
+/// Exit code when the file could not be loaded
+pub const LOAD_ERROR_CODE: i32 = 3;
+
+/// How the user quit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Quit with nothing unsaved
+    #[default]
+    Normal,
+    /// `:cq`: quit with an error code, like vim (a picker's "cancel")
+    Cancelled,
+    /// `:q!` with unsaved changes
+    Discarded,
+}
+
+impl ExitStatus {
+    /// Process exit code
+    pub fn code(self) -> i32 {
+        match self {
+            ExitStatus::Normal => 0,
+            ExitStatus::Cancelled => 1,
+            ExitStatus::Discarded => 2,
+        }
+    }
+}
+
+/// What Enter prints in `--pick` mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    /// The cursor row, tab-separated
+    Row,
+    /// The cursor cell
+    Cell,
+    /// The cursor row's value in one column
+    Column(ColIndex),
+}
+
+impl Pick {
+    /// Parse `--pick row`, `--pick cell` or a column name or letter
+    pub fn parse(app: &App, what: &str) -> Result<Pick> {
+        match what.to_ascii_lowercase().as_str() {
+            "row" => Ok(Pick::Row),
+            "cell" => Ok(Pick::Cell),
+            _ => crate::input::handler::column_by_name_or_letter(app, what)
+                .map(Pick::Column)
+                .ok_or_else(|| anyhow::anyhow!("--pick: no column named {}", what)),
+        }
+    }
+}
+
+impl App {
+    /// Enter `--pick` mode: until Enter picks a row, quitting counts as
+    /// cancelling (exit code 1)
+    pub fn start_picking(&mut self, pick: Pick) {
+        self.pick = Some(pick);
+        self.exit_status = ExitStatus::Cancelled;
+        if self.status_messages.is_empty() {
+            self.status_messages.push("Enter picks a row, q cancels");
+        }
+    }
+
+    /// Text Enter picked in `--pick` mode (None without a cursor)
+    pub fn picked_output(&self, pick: Pick) -> Option<String> {
+        let row = self.get_selected_row()?;
+        Some(match pick {
+            Pick::Row => {
+                let last = self.document.column_count().checked_sub(1)?;
+                let text = super::copy::block_text(&self.document, &[row.get()], 0..=last);
+                text.strip_suffix('\n').unwrap_or(&text).to_string()
+            }
+            Pick::Cell => self.exit_output(PrintOnExit::Cell)?,
+            Pick::Column(col) => self.document.get_cell(row, col).to_string(),
+        })
+    }
+
+    /// Text `--print` writes on exit (None without a cursor)
+    pub fn exit_output(&self, print: PrintOnExit) -> Option<String> {
+        let row = self.get_selected_row()?.get();
+        let col = self.view_state.selected_column.get();
+        let cell = |row: usize, col: usize| {
+            self.document
+                .get_cell(RowIndex::new(row), ColIndex::new(col))
+                .to_string()
+        };
+        Some(match print {
+            PrintOnExit::Cell => cell(row, col),
+            PrintOnExit::Address => {
+                format!("{}{}", crate::ui::column_to_excel_letter(col), row + 1)
+            }
+            PrintOnExit::Selection => match (
+                &self.view_state.visual_rows,
+                &self.view_state.visual_columns,
+            ) {
+                (Some(rows), Some(columns)) => {
+                    let rows = self.fill_rows(Some(rows.clone()));
+                    let text = super::copy::block_text(&self.document, &rows, columns.clone());
+                    text.strip_suffix('\n').unwrap_or(&text).to_string()
+                }
+                _ => cell(row, col),
+            },
+        })
+    }
+}
@@ -0,0 +1,351 @@
+//! `:fill <column> = <expression>`: overwrite a column with a constant or
+//! an expression computed from each row.
+//!
+//! `:'<,'>fill down` copies the first selected cell of the current column
+//! down the selection and `:'<,'>fill series [step]` counts up from it
+//! instead (`f` and `F` in Visual mode do the same for the selected
+//! columns).
+
+use super::undo::{Change, Edit};
+use crate::analysis::expression::{Expr, Value};
+use crate::csv::Document;
+use crate::domain::position::ColIndex;
+
+/// Values shown in the command line preview
+pub const PREVIEW_VALUES: usize = 3;
+
+/// Cell text or error message per document row
+pub type RowResults = Vec<(usize, String)>;
+
+/// Parsed `<column> = <expression>` spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillSpec {
+    pub column: ColIndex,
+    pub expr: Expr,
+}
+
+impl FillSpec {
+    /// Parse the text after `:fill`
+    pub fn parse(spec: &str, document: &Document) -> Result<Self, String> {
+        let (name, expr) = spec
+            .split_once('=')
+            .ok_or("Usage: :fill <column> = <value or expression>")?;
+        let name = name.trim().trim_matches('`');
+        let column = document
+            .find_column(name)
+            .ok_or_else(|| format!("No column named {}", name))?;
+        let expr = Expr::parse(expr, document)?;
+        Ok(Self { column, expr })
+    }
+
+    /// Compute new values for `rows`; rows the expression fails on are
+    /// reported as (row, error) and left alone
+    pub fn values(
+        &self,
+        document: &Document,
+        rows: impl IntoIterator<Item = usize>,
+    ) -> (RowResults, RowResults) {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for row in rows {
+            let Some(cells) = document.rows.get(row) else {
+                continue;
+            };
+            match self.expr.eval(cells) {
+                Ok(value) => values.push((row, value.into_cell())),
+                Err(err) => errors.push((row, err)),
+            }
+        }
+        (values, errors)
+    }
+
+    /// Edits writing `values`, skipping cells that already hold them
+    pub fn change(&self, document: &Document, values: RowResults) -> Change {
+        let col = self.column.get();
+        let name = document.headers.get(col).cloned().unwrap_or_default();
+        let edits = values
+            .into_iter()
+            .filter_map(|(row, new)| {
+                let old = document.rows[row].get(col).cloned().unwrap_or_default();
+                (old != new).then_some(Edit::Cell { row, col, old, new })
+            })
+            .collect();
+        Change {
+            edits,
+            label: format!("fill {}", name),
+        }
+    }
+}
+
+/// `fill down` or `fill series [step]`: continue the first cell of a
+/// selection down the rest of it
+#[derive(Debug, Clone, PartialEq)]
+pub enum FillDown {
+    /// Copy the first value
+    Copy,
+    /// Add `step` (as typed, to keep its decimals) per row
+    Series { step: String },
+}
+
+impl FillDown {
+    /// Parse the text after `:fill`; None if it is a `<column> = ...` spec
+    pub fn parse(spec: &str) -> Option<Result<Self, String>> {
+        const USAGE: &str = "Usage: :fill down or :fill series [step]";
+        let mut words = spec.split_whitespace();
+        let fill = match words.next()? {
+            "down" => Self::Copy,
+            "series" => Self::Series {
+                step: words.next().unwrap_or("1").to_string(),
+            },
+            _ => return None,
+        };
+        if words.next().is_some() {
+            return Some(Err(USAGE.to_string()));
+        }
+        if let Self::Series { step } = &fill {
+            if step.parse::<f64>().map_or(true, |s| !s.is_finite()) {
+                return Some(Err(format!("Step {} is not a number", step)));
+            }
+        }
+        Some(Ok(fill))
+    }
+
+    /// Values for `count` rows, starting with `first`
+    pub fn values(&self, first: &str, count: usize) -> Result<Vec<String>, String> {
+        match self {
+            Self::Copy => Ok(vec![first.to_string(); count]),
+            Self::Series { step } => series(first, step, count),
+        }
+    }
+
+    /// Edits continuing the first of `rows` down the rest, in each of
+    /// `columns`
+    pub fn change(
+        &self,
+        document: &Document,
+        rows: &[usize],
+        columns: impl IntoIterator<Item = usize>,
+    ) -> Result<Change, String> {
+        let cell =
+            |row: usize, col: usize| document.rows[row].get(col).cloned().unwrap_or_default();
+        let mut edits = Vec::new();
+        let Some(&first) = rows.first() else {
+            return Err("No rows selected".to_string());
+        };
+        for col in columns {
+            let values = self.values(&cell(first, col), rows.len())?;
+            for (&row, new) in rows.iter().zip(values).skip(1) {
+                let old = cell(row, col);
+                if old != new {
+                    edits.push(Edit::Cell { row, col, old, new });
+                }
+            }
+        }
+        let label = match self {
+            Self::Copy => "fill down",
+            Self::Series { .. } => "fill series",
+        };
+        Ok(Change {
+            edits,
+            label: label.to_string(),
+        })
+    }
+}
+
+/// `first`, then `first + step`, `first + 2 * step`, ... for numbers, or
+/// for a number at the end of text (`item 9`, `item 10`); leading zeros
+/// and decimals are kept
+fn series(first: &str, step: &str, count: usize) -> Result<Vec<String>, String> {
+    let decimals = |number: &str| number.split_once('.').map_or(0, |(_, d)| d.len());
+    let trimmed = first.trim();
+    let step_value: f64 = step
+        .parse()
+        .map_err(|_| format!("Step {} is not a number", step))?;
+    let whole_step = (step_value.fract() == 0.0).then_some(step_value as i64);
+
+    if let (Ok(start), Some(step)) = (trimmed.parse::<i64>(), whole_step) {
+        return Ok((0..count as i64)
+            .map(|i| (start + i * step).to_string())
+            .collect());
+    }
+    if let Some(start) = trimmed.parse::<f64>().ok().filter(|n| n.is_finite()) {
+        let places = decimals(trimmed).max(decimals(step));
+        return Ok((0..count)
+            .map(|i| format!("{:.*}", places, start + i as f64 * step_value))
+            .collect());
+    }
+
+    let prefix = trimmed.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = &trimmed[prefix.len()..];
+    let (Ok(start), Some(step)) = (digits.parse::<i64>(), whole_step) else {
+        return Err(format!("{:?} doesn't end in a whole number", first));
+    };
+    (0..count as i64)
+        .map(|i| match start + i * step {
+            n if n < 0 => Err(format!("Series from {:?} goes below zero", first)),
+            n => Ok(format!("{}{:0width$}", prefix, n, width = digits.len())),
+        })
+        .collect()
+}
+
+/// Command line preview of `:fill`: the first few values, or why the
+/// spec can't be used yet (`column` is the one `fill down` continues)
+pub fn preview(spec: &str, document: &Document, rows: &[usize], column: ColIndex) -> String {
+    if let Some(fill) = FillDown::parse(spec) {
+        let first = rows
+            .first()
+            .and_then(|&row| document.rows.get(row)?.get(column.get()))
+            .map_or("", String::as_str);
+        return match fill.and_then(|fill| fill.values(first, rows.len().min(PREVIEW_VALUES))) {
+            Ok(values) => {
+                let shown: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
+                let more = if rows.len() > PREVIEW_VALUES {
+                    ", …"
+                } else {
+                    ""
+                };
+                format!("→ {}{} ({} rows)", shown.join(", "), more, rows.len())
+            }
+            Err(err) => err,
+        };
+    }
+    let spec = match FillSpec::parse(spec, document) {
+        Ok(spec) => spec,
+        Err(err) => return err,
+    };
+    let shown = rows.iter().copied().take(PREVIEW_VALUES);
+    let values: Vec<String> = shown
+        .filter_map(|row| document.rows.get(row))
+        .map(|cells| match spec.expr.eval(cells) {
+            Ok(Value::Text(text)) => format!("{:?}", text),
+            Ok(value) => value.into_cell(),
+            Err(err) => format!("<{}>", err),
+        })
+        .collect();
+    let more = if rows.len() > PREVIEW_VALUES {
+        ", …"
+    } else {
+        ""
+    };
+    format!("→ {}{} ({} rows)", values.join(", "), more, rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["price".to_string(), "qty".to_string(), "total".to_string()],
+            rows: vec![
+                vec!["2".to_string(), "3".to_string(), String::new()],
+                vec!["x".to_string(), "1".to_string(), String::new()],
+                vec!["1.5".to_string(), "2".to_string(), "3".to_string()],
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fill_change_skips_errors_and_unchanged_cells() {
+        let document = document();
+        let spec = FillSpec::parse("total = price * qty", &document).unwrap();
+        let (values, errors) = spec.values(&document, 0..3);
+        assert_eq!(errors, vec![(1, "\"x\" is not a number".to_string())]);
+
+        let change = spec.change(&document, values);
+        assert_eq!(change.label, "fill total");
+        // Row 2 already holds 3
+        assert_eq!(
+            change.edits,
+            vec![Edit::Cell {
+                row: 0,
+                col: 2,
+                old: String::new(),
+                new: "6".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fill_spec_errors() {
+        let document = document();
+        assert!(FillSpec::parse("total", &document)
+            .unwrap_err()
+            .starts_with("Usage"));
+        assert_eq!(
+            FillSpec::parse("cost = 1", &document),
+            Err("No column named cost".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fill_down_and_series() {
+        assert_eq!(FillDown::parse("total = 1"), None);
+        assert_eq!(FillDown::parse("down"), Some(Ok(FillDown::Copy)));
+        assert!(FillDown::parse("series x").unwrap().is_err());
+        let series = |first: &str, step: &str| {
+            FillDown::parse(&format!("series {}", step))
+                .unwrap()
+                .unwrap()
+                .values(first, 3)
+        };
+        assert_eq!(
+            series("9", ""),
+            Ok(vec!["9".into(), "10".into(), "11".into()])
+        );
+        assert_eq!(
+            series("1.50", "0.25"),
+            Ok(vec!["1.50".into(), "1.75".into(), "2.00".into()])
+        );
+        assert_eq!(
+            series("INV-098", "2"),
+            Ok(vec!["INV-098".into(), "INV-100".into(), "INV-102".into()])
+        );
+        assert!(series("item", "1").is_err());
+        assert!(series("a1", "-1").is_err());
+
+        let document = document();
+        let change = FillDown::Copy.change(&document, &[0, 2], 1..=2).unwrap();
+        assert_eq!(change.label, "fill down");
+        assert_eq!(
+            change.edits,
+            vec![
+                Edit::Cell {
+                    row: 2,
+                    col: 1,
+                    old: "2".to_string(),
+                    new: "3".to_string(),
+                },
+                Edit::Cell {
+                    row: 2,
+                    col: 2,
+                    old: "3".to_string(),
+                    new: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let document = document();
+        assert_eq!(
+            preview("total = \"n/a\"", &document, &[0, 1], ColIndex::new(0)),
+            "→ \"n/a\", \"n/a\" (2 rows)"
+        );
+        assert_eq!(
+            preview(
+                "total = price * 2",
+                &document,
+                &[0, 1, 2, 0],
+                ColIndex::new(0)
+            ),
+            "→ 4, <\"x\" is not a number>, 3, … (4 rows)"
+        );
+        assert_eq!(
+            preview("series 2", &document, &[0, 1, 2, 0], ColIndex::new(1)),
+            "→ \"3\", \"5\", \"7\", … (4 rows)"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! `:fmt`: show the numbers of a column grouped by thousands, rounded to
+//! a number of decimals or in scientific notation, without changing the
+//! cells themselves.
+
+use super::App;
+use crate::domain::position::ColIndex;
+use crate::ui::{column_to_excel_letter, NumberFormat};
+
+impl App {
+    /// `:fmt [column] [words]`: change how a column's numbers are shown
+    /// (the selected column's unless one is named before the words), or
+    /// say how they are without words; returns the message to show
+    pub fn format_column(&mut self, args: &str) -> Result<String, String> {
+        let words: Vec<&str> = args.split_whitespace().collect();
+        // Format words come last, so a column name may have spaces
+        let name_words = words
+            .iter()
+            .rposition(|word| !NumberFormat::is_word(word))
+            .map_or(0, |at| at + 1);
+        let (name, format_words) = words.split_at(name_words);
+        let col = match name.join(" ").as_str() {
+            "" => self.view_state.selected_column,
+            name => crate::input::handler::column_by_name_or_letter(self, name)
+                .ok_or_else(|| format!("No column named {}", name))?,
+        };
+        let label = format!(
+            "{} ({})",
+            self.document.get_header(col),
+            column_to_excel_letter(col.get())
+        );
+
+        let mut format = self.number_format(col);
+        if format_words.is_empty() {
+            return Ok(format!("Number format of {}: {}", label, format));
+        }
+        for word in format_words {
+            format.apply(word)?;
+        }
+        if format == NumberFormat::default() {
+            self.view_state.number_formats.remove(&col.get());
+            Ok(format!("Showing {} as written", label))
+        } else {
+            self.view_state.number_formats.insert(col.get(), format);
+            Ok(format!("Showing {} as {}", label, format))
+        }
+    }
+
+    /// How the numbers of a column are shown
+    pub fn number_format(&self, col: ColIndex) -> NumberFormat {
+        self.view_state
+            .number_formats
+            .get(&col.get())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::Document;
+    use crate::session::FileConfig;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_column() {
+        let document = Document {
+            headers: vec!["item".to_string(), "unit price".to_string()],
+            rows: vec![vec!["tea".to_string(), "1234.5".to_string()]],
+            ..Default::default()
+        };
+        let mut app = App::new(document, vec![PathBuf::from("a.csv")], 0, FileConfig::new());
+
+        assert_eq!(
+            app.format_column("unit price , .2"),
+            Ok("Showing unit price (B) as , .2".to_string())
+        );
+        assert_eq!(
+            app.number_format(ColIndex::new(1)).to_string(),
+            ", .2".to_string()
+        );
+        // Without words: the selected column's format
+        app.view_state.selected_column = ColIndex::new(1);
+        assert_eq!(
+            app.format_column(""),
+            Ok("Number format of unit price (B): , .2".to_string())
+        );
+        app.format_column("sci").unwrap();
+        assert_eq!(app.number_format(ColIndex::new(1)).to_string(), ", .2 sci");
+        // The cells are left as they were
+        assert_eq!(app.document.rows[0][1], "1234.5");
+
+        assert!(app.format_column("price ,").is_err());
+        assert!(app.format_column(".x").is_err());
+        assert_eq!(
+            app.format_column("B off"),
+            Ok("Showing unit price (B) as written".to_string())
+        );
+        assert!(app.view_state.number_formats.is_empty());
+    }
+}
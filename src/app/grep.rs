@@ -0,0 +1,266 @@
+//! `:grepall <pattern>`: search every file of the session and list the
+//! matching cells, like vim's quickfix list.
+//!
+//! The open document is searched as it is, unsaved edits included; other
+//! files are read from disk and searched a record at a time, without
+//! building a document.
+
+use super::search::SearchPattern;
+use super::App;
+use crate::csv::{delimiter_for_path, read_file, sniff_delimiter, Document};
+use crate::session::FileConfig;
+use crate::ui::{column_to_excel_letter, GrepView};
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+
+/// Matches listed before the search stops
+pub const MAX_MATCHES: usize = 1000;
+
+/// Characters of a cell shown around a match
+const SNIPPET_CHARS: usize = 60;
+
+/// Characters shown before the match when the cell is cut
+const SNIPPET_LEAD: usize = 20;
+
+/// A matching cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrepMatch {
+    /// Index of the file in the session
+    pub file: usize,
+    /// Document row and column
+    pub row: usize,
+    pub col: usize,
+    /// Column letter and header (e.g. "B city")
+    pub column: String,
+    /// The cell around the first match, on one line
+    pub snippet: String,
+}
+
+/// The part of `cell` around the match at byte `range`
+fn snippet(cell: &str, range: Range<usize>) -> String {
+    let start = cell[..range.start].chars().count();
+    let skip = start.saturating_sub(SNIPPET_LEAD);
+    let mut snippet: String = cell
+        .chars()
+        .skip(skip)
+        .take(SNIPPET_CHARS)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if skip > 0 {
+        snippet.insert(0, '…');
+    }
+    if cell.chars().count() > skip + SNIPPET_CHARS {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Column letter and header
+fn column_label(headers: &[String], col: usize) -> String {
+    match headers.get(col).filter(|h| !h.is_empty()) {
+        Some(header) => format!("{} {}", column_to_excel_letter(col), header),
+        None => column_to_excel_letter(col).to_string(),
+    }
+}
+
+/// Add the matches of one row, stopping at `MAX_MATCHES`
+fn grep_row<'a>(
+    file: usize,
+    row: usize,
+    cells: impl Iterator<Item = Cow<'a, str>>,
+    headers: &[String],
+    pattern: &SearchPattern,
+    matches: &mut Vec<GrepMatch>,
+) {
+    for (col, cell) in cells.enumerate() {
+        if matches.len() >= MAX_MATCHES {
+            return;
+        }
+        if let Some(found) = pattern.find_iter(&cell).next() {
+            matches.push(GrepMatch {
+                file,
+                row,
+                col,
+                column: column_label(headers, col),
+                snippet: snippet(&cell, found),
+            });
+        }
+    }
+}
+
+/// Add the matches in a loaded document
+pub fn grep_document(
+    document: &Document,
+    file: usize,
+    pattern: &SearchPattern,
+    matches: &mut Vec<GrepMatch>,
+) {
+    for (row, cells) in document.rows.iter().enumerate() {
+        let cells = cells.iter().map(|cell| Cow::Borrowed(cell.as_str()));
+        grep_row(file, row, cells, &document.headers, pattern, matches);
+        if matches.len() >= MAX_MATCHES {
+            return;
+        }
+    }
+}
+
+/// Add the matches in a file on disk, read as `config` says
+pub fn grep_file(
+    path: &Path,
+    config: &FileConfig,
+    file: usize,
+    pattern: &SearchPattern,
+    matches: &mut Vec<GrepMatch>,
+) -> Result<()> {
+    // Decoded before parsing, as when the file is opened: in UTF-16 the
+    // delimiter and newline aren't single bytes
+    let content = Document::decode_file_bytes(&read_file(path)?, config.encoding.clone())?;
+    let (_, table) = config.preamble.split(&content);
+    let delimiter = config
+        .delimiter
+        .or_else(|| delimiter_for_path(path))
+        .or_else(|| sniff_delimiter(table))
+        .unwrap_or(b',');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(!config.no_headers)
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(table.as_bytes());
+    let headers: Vec<String> = if config.no_headers {
+        Vec::new()
+    } else {
+        reader.headers()?.iter().map(String::from).collect()
+    };
+    let mut record = csv::StringRecord::new();
+    let mut row = 0;
+    while reader
+        .read_record(&mut record)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+    {
+        let cells = record.iter().map(Cow::Borrowed);
+        grep_row(file, row, cells, &headers, pattern, matches);
+        if matches.len() >= MAX_MATCHES {
+            break;
+        }
+        row += 1;
+    }
+    Ok(())
+}
+
+impl App {
+    /// Search every file of the session; files that can't be read are
+    /// listed in the view's errors
+    pub fn grep_all(&self, pattern: &SearchPattern) -> GrepView {
+        let mut matches = Vec::new();
+        let mut errors = Vec::new();
+        for (file, path) in self.session.files().iter().enumerate() {
+            if matches.len() >= MAX_MATCHES {
+                break;
+            }
+            if file == self.session.active_file_index() {
+                grep_document(&self.document, file, pattern, &mut matches);
+            } else if let Err(err) = grep_file(
+                path,
+                self.session.config_for(path),
+                file,
+                pattern,
+                &mut matches,
+            ) {
+                errors.push(format!("{}: {:#}", path.display(), err));
+            }
+        }
+        GrepView::new(&pattern.query, matches, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pattern(query: &str) -> SearchPattern {
+        SearchPattern::new(query, &Default::default()).unwrap()
+    }
+
+    #[test]
+    fn test_grep_file_streams_records() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cities.csv");
+        std::fs::write(&path, "name;city\na;Oslo\nb;Rome\nc;\"New\nOslo\"\n").unwrap();
+        let mut matches = Vec::new();
+        grep_file(&path, &FileConfig::new(), 3, &pattern("oslo"), &mut matches).unwrap();
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| (m.file, m.row, m.column.as_str(), m.snippet.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(3, 0, "B city", "Oslo"), (3, 2, "B city", "New Oslo")]
+        );
+
+        let config = FileConfig::with_options(None, true, None);
+        matches.clear();
+        grep_file(&path, &config, 0, &pattern("name"), &mut matches).unwrap();
+        assert_eq!((matches[0].row, matches[0].column.as_str()), (0, "A"));
+    }
+
+    #[test]
+    fn test_grep_file_skips_preamble() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("export.csv");
+        std::fs::write(
+            &path,
+            "Exported from Oslo
+# by admin
+name,city
+b,Oslo
+#c,Oslo
+",
+        )
+        .unwrap();
+        let config = FileConfig::new().with_preamble(crate::csv::Preamble {
+            skip_lines: 1,
+            comment: Some(b'#'),
+        });
+        let mut matches = Vec::new();
+        grep_file(&path, &config, 0, &pattern("oslo"), &mut matches).unwrap();
+        let found: Vec<_> = matches.iter().map(|m| (m.row, m.column.as_str())).collect();
+        // Past the header, a row starting with `#` is searched too
+        assert_eq!(found, vec![(0, "B city"), (1, "B city")]);
+    }
+
+    #[test]
+    fn test_grep_file_decodes_utf16() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("export.csv");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(
+            "name,city\nb,Oslo\nc,Rome\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        std::fs::write(&path, bytes).unwrap();
+        let mut config = FileConfig::new();
+        config.encoding = Some("utf-16le".to_string());
+
+        let mut matches = Vec::new();
+        grep_file(&path, &config, 0, &pattern("rome"), &mut matches).unwrap();
+        let found: Vec<_> = matches.iter().map(|m| (m.row, m.column.as_str())).collect();
+        assert_eq!(found, vec![(1, "B city")]);
+        // As the file reads once opened
+        let document = config.read_document(&path).unwrap();
+        assert_eq!(document.rows[1][1], "Rome");
+    }
+
+    #[test]
+    fn test_snippet_is_cut_around_the_match() {
+        let cell = format!("{}needle{}", "x".repeat(100), "y".repeat(100));
+        let text = snippet(&cell, 100..106);
+        assert!(text.starts_with(&format!("…{}needle", "x".repeat(20))));
+        assert!(text.ends_with('…'));
+        assert_eq!(text.chars().count(), SNIPPET_CHARS + 2);
+    }
+}
@@ -0,0 +1,155 @@
+//! `:set headers` / `:set noheaders`: read the first line of the file as
+//! column names or as a row of data, without opening the file again.
+//!
+//! The file is written back the same either way, so the document's dirty
+//! flag stays as it is. Row indexes move by one, which the undo history
+//! can't follow, so it is cleared.
+
+use super::App;
+use crate::csv::synthesized_header;
+use crate::domain::position::RowIndex;
+use crate::ui::MarkKind;
+
+impl App {
+    /// Read the first line as headers (`true`) or as data; returns the
+    /// message to show
+    pub fn set_headers(&mut self, headers: bool) -> Result<String, String> {
+        let no_headers = self.session.config().no_headers;
+        if headers != no_headers {
+            return Ok(if headers {
+                "The first line is already read as headers".to_string()
+            } else {
+                "The first line is already read as data".to_string()
+            });
+        }
+        if self.split.is_some() {
+            return Err("Close the other pane first (Ctrl+w o)".to_string());
+        }
+        if !self.view_state.derived_columns.is_empty() {
+            return Err("Remove the derived columns first (:underive)".to_string());
+        }
+
+        let current = self.get_selected_row().map(|row| row.get());
+        let selected = if headers {
+            // The row read from the file's first line, wherever a sort put it
+            let rows = self.document.row_count();
+            let Some(at) = (0..rows)
+                .find(|&row| self.row_order.file_row(row) == Some(0))
+                .or((rows > 0).then_some(0))
+            else {
+                return Err("No row to read as headers".to_string());
+            };
+            let count = self.document.column_count();
+            let mut names = self.document.rows.remove(at);
+            names.resize(count.max(names.len()), String::new());
+            self.document.headers = names;
+            self.view_state.marks.row_deleted(at);
+            self.view_state.row_groups.row_deleted(at);
+            self.row_order.row_became_header(at);
+            current.map(|row| match row.cmp(&at) {
+                std::cmp::Ordering::Greater => row - 1,
+                _ => row.min(self.document.row_count().saturating_sub(1)),
+            })
+        } else {
+            let count = self.document.column_count();
+            let names = (0..count).map(synthesized_header).collect();
+            let first = std::mem::replace(&mut self.document.headers, names);
+            self.document.rows.insert(0, first);
+            self.view_state.marks.row_inserted(0);
+            self.view_state.row_groups.row_inserted(0);
+            self.row_order.header_became_row();
+            current.map(|row| row + 1)
+        };
+
+        self.session.set_no_headers(!headers);
+        self.history.clear();
+        self.last_edit_position = None;
+        self.view_state.marks.clear_kind(MarkKind::Changed);
+        self.view_state.row_filter = self
+            .view_state
+            .row_filter
+            .take()
+            .map(|filter| filter.reapplied(&self.document));
+        self.refresh_row_groups();
+        self.refresh_column_types();
+        self.store_bookmarks();
+        if !selected.is_some_and(|row| self.select_document_row(RowIndex::new(row))) {
+            self.view_state.table_state.select(Some(0));
+        }
+
+        Ok(if headers {
+            format!(
+                "Reading the first line as headers ({} rows; undo history cleared)",
+                self.document.row_count()
+            )
+        } else {
+            format!(
+                "Reading the first line as data ({} rows; undo history cleared)",
+                self.document.row_count()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::Document;
+    use crate::session::FileConfig;
+    use std::path::PathBuf;
+
+    fn app() -> App {
+        let document = Document {
+            headers: vec!["id".to_string(), "name".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "ann".to_string()],
+                vec!["2".to_string(), "bob".to_string()],
+            ],
+            ..Default::default()
+        };
+        App::new(
+            document,
+            vec![PathBuf::from("people.csv")],
+            0,
+            FileConfig::new(),
+        )
+    }
+
+    #[test]
+    fn test_headers_become_a_row_and_back() {
+        let mut app = app();
+        app.select_document_row(RowIndex::new(1));
+        app.view_state.marks.add(1, None, MarkKind::Bookmark);
+
+        app.set_headers(false).unwrap();
+        assert_eq!(app.document.headers, vec!["Column 1", "Column 2"]);
+        assert_eq!(app.document.rows[0], vec!["id", "name"]);
+        assert_eq!(app.document.row_count(), 3);
+        assert!(app.session.config().no_headers);
+        assert!(!app.document.is_dirty);
+        // The cursor and marks stay on their rows
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+        assert_eq!(app.bookmarks(), vec![2]);
+
+        app.set_headers(true).unwrap();
+        assert_eq!(app.document.headers, vec!["id", "name"]);
+        assert_eq!(app.document.row_count(), 2);
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        assert_eq!(app.bookmarks(), vec![1]);
+        assert!(app.set_headers(true).unwrap().contains("already"));
+    }
+
+    #[test]
+    fn test_headers_come_from_the_first_line_after_a_sort() {
+        let mut app = app();
+        app.set_headers(false).unwrap();
+        // Sorted descending, the header line is now the last row
+        app.document.rows.reverse();
+        app.row_order.rows_reordered(0, &[2, 1, 0]);
+
+        app.set_headers(true).unwrap();
+        assert_eq!(app.document.headers, vec!["id", "name"]);
+        assert_eq!(app.document.rows[0], vec!["2", "bob"]);
+        assert_eq!(app.row_order.file_row(0), Some(1));
+    }
+}
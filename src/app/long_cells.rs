@@ -0,0 +1,150 @@
+//! `:long <length> [truncate]`: find cells longer than a limit, such as the
+//! size of the database column they are headed for, and optionally cut
+//! them down to it.
+
+use super::undo::{Change, Edit};
+use crate::csv::Document;
+
+/// Cells listed in the status message
+pub const LISTED_CELLS: usize = 3;
+
+/// Parsed `:long` arguments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongCells {
+    /// Longest allowed cell, in characters
+    pub limit: usize,
+    /// Cut the cells to `limit` (after confirmation)
+    pub truncate: bool,
+}
+
+/// A cell over the limit: document row, column and length in characters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongCell {
+    pub row: usize,
+    pub col: usize,
+    pub chars: usize,
+}
+
+impl LongCells {
+    /// Parse `<length> [truncate]`
+    pub fn parse(args: &str) -> Result<Self, String> {
+        const USAGE: &str = "Usage: :long <length> [truncate]";
+        let mut words = args.split_whitespace();
+        let limit = words
+            .next()
+            .and_then(|w| w.parse::<usize>().ok())
+            .ok_or(USAGE)?;
+        let truncate = match words.next() {
+            None => false,
+            Some("truncate") => true,
+            Some(_) => return Err(USAGE.to_string()),
+        };
+        match words.next() {
+            Some(_) => Err(USAGE.to_string()),
+            None => Ok(Self { limit, truncate }),
+        }
+    }
+
+    /// Cells of `rows` longer than the limit, in row order
+    pub fn find(&self, document: &Document, rows: &[usize]) -> Vec<LongCell> {
+        rows.iter()
+            .filter_map(|&row| document.rows.get(row).map(|cells| (row, cells)))
+            .flat_map(|(row, cells)| {
+                cells.iter().enumerate().filter_map(move |(col, cell)| {
+                    // Only count characters of cells that could be too long
+                    (cell.len() > self.limit)
+                        .then(|| cell.chars().count())
+                        .filter(|&chars| chars > self.limit)
+                        .map(|chars| LongCell { row, col, chars })
+                })
+            })
+            .collect()
+    }
+
+    /// Undoable change cutting `cells` to the limit
+    pub fn change(&self, document: &Document, cells: &[LongCell]) -> Change {
+        let edits = cells
+            .iter()
+            .map(|cell| {
+                let old = document.rows[cell.row][cell.col].clone();
+                let new = old.chars().take(self.limit).collect();
+                Edit::Cell {
+                    row: cell.row,
+                    col: cell.col,
+                    old,
+                    new,
+                }
+            })
+            .collect();
+        Change {
+            edits,
+            label: format!("truncate to {} chars", self.limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["id".to_string(), "note".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "short".to_string()],
+                vec!["2".to_string(), "ünïcödé".to_string()],
+                vec!["3".to_string(), "much too long".to_string()],
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            LongCells::parse("256"),
+            Ok(LongCells {
+                limit: 256,
+                truncate: false
+            })
+        );
+        assert!(LongCells::parse("10 truncate").unwrap().truncate);
+        assert!(LongCells::parse("").is_err());
+        assert!(LongCells::parse("ten").is_err());
+        assert!(LongCells::parse("10 cut").is_err());
+    }
+
+    #[test]
+    fn test_find_counts_characters() {
+        let document = document();
+        let long = LongCells::parse("7").unwrap();
+        // ünïcödé is 7 characters but more bytes
+        assert_eq!(
+            long.find(&document, &[0, 1, 2]),
+            vec![LongCell {
+                row: 2,
+                col: 1,
+                chars: 13
+            }]
+        );
+        assert!(long.find(&document, &[0, 1]).is_empty());
+    }
+
+    #[test]
+    fn test_truncate_change() {
+        let document = document();
+        let long = LongCells::parse("4 truncate").unwrap();
+        let cells = long.find(&document, &[0, 1, 2]);
+        let change = long.change(&document, &cells);
+        assert_eq!(change.edits.len(), 3);
+        assert_eq!(
+            change.edits[1],
+            Edit::Cell {
+                row: 1,
+                col: 1,
+                old: "ünïcödé".to_string(),
+                new: "ünïc".to_string()
+            }
+        );
+    }
+}
@@ -13,11 +13,20 @@ pub const UNSAVED_CHANGES: &str = "Unsaved changes! Use :q! to force quit";
 // Navigation messages
 pub const JUMPED_TO_FIRST_ROW: &str = "Jumped to first row";
 
+// Empty document messages
+pub const NO_DATA_ROWS: &str = "No data rows — press o to add one";
+pub const NO_COLUMNS: &str = "No columns";
+
 /// Format a "jumped to line" message
 pub fn jumped_to_line(line: usize) -> String {
     format!("Jumped to line {}", line)
 }
 
+/// Format a row count ("1 row", "3 rows")
+pub fn row_count(rows: usize) -> String {
+    format!("{} row{}", rows, if rows == 1 { "" } else { "s" })
+}
+
 // Viewport positioning messages
 pub const VIEW_TOP: &str = "View: top";
 pub const VIEW_CENTER: &str = "View: center";
@@ -28,6 +37,14 @@ pub fn unknown_command(cmd1: &str, cmd2: &str) -> String {
     format!("Unknown command: {} {}", cmd1, cmd2)
 }
 
+// Config messages
+pub const CONFIG_RELOADED: &str = "Config reloaded";
+
+/// Format a config error (the previous config stays active)
+pub fn config_error(err: &str) -> String {
+    format!("Config error: {} (run `lazycsv doctor`)", err)
+}
+
 // Error messages
 pub const NO_PATH_PROVIDED: &str = "No path provided";
 
@@ -36,6 +53,14 @@ pub fn no_csv_files_found(path: &std::path::Path) -> String {
     format!("No CSV files found in directory: {}", path.display())
 }
 
+/// Format the warning shown when discovery stopped at `max` files
+pub fn too_many_files_found(max: usize) -> String {
+    format!(
+        "Found more than {} files; only the first {} were opened (narrow it with --glob)",
+        max, max
+    )
+}
+
 /// Format an "invalid path" error
 pub fn invalid_path(path: &std::path::Path) -> String {
     format!("Invalid path: {}", path.display())
@@ -50,3 +75,9 @@ pub fn failed_to_load_csv(path: &std::path::Path) -> String {
 pub fn failed_to_reload_file(path: &std::path::Path) -> String {
     format!("Failed to reload file: {}", path.display())
 }
+
+/// Format the warning shown when the open file is modified on disk
+pub fn file_changed_on_disk(filename: &str, dirty: bool) -> String {
+    let lost = if dirty { ", dropping your edits" } else { "" };
+    format!("{} changed on disk - :e! to reload{}", filename, lost)
+}
@@ -1,12 +1,57 @@
+pub mod buffers;
+pub mod confirm;
+pub mod copy;
+pub mod delimiter;
+pub mod derived;
+pub mod exit;
+pub mod fill;
+pub mod formats;
+pub mod grep;
+pub mod headers;
+pub mod long_cells;
 pub mod messages;
-
+pub mod options;
+pub mod registers;
+pub mod repeat;
+pub mod replace;
+pub mod row_order;
+pub mod save_preview;
+pub mod search;
+pub mod setup;
+pub mod sort;
+pub mod split;
+pub mod swap;
+pub mod undo;
+
+pub use confirm::{Confirm, ConfirmAction};
+pub use derived::{DerivedColumn, DerivedColumns};
+pub use exit::{ExitStatus, Pick, LOAD_ERROR_CODE};
+pub use options::{Options, PageSize};
+pub use registers::{RegisterRows, Registers};
+pub use repeat::{CellEdit, LastChange};
+pub use row_order::RowOrder;
+pub use split::{Split, SplitDirection};
+pub use undo::{Change, Edit, History};
+
+/// Command line range for the rows of the last Visual selection
+pub const VISUAL_RANGE: &str = "'<,'>";
+
+use crate::analysis::Diff;
+use crate::config::{Config, ConfigWatcher};
+use crate::csv::{infer_column_types, ColumnType, Compression, Locale};
 use crate::domain::position::{ColIndex, RowIndex};
-use crate::input::{InputResult, InputState, StatusMessage};
-use crate::session::Session;
-use crate::ui::ViewState;
+use crate::input::{InputResult, InputState, Keymap, StatusMessage, StatusMessages};
+use crate::session::SessionSnapshot;
+use crate::session::{CellPosition, FileTail, Follower, Leader, Session, SharedView, TailEvent};
+use crate::ui::{
+    ColumnGroups, GrepView, Magnifier, MarkKind, RowFilter, RowGroups, Theme, ViewState,
+};
 use crate::Document;
 use anyhow::{Context, Result};
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
+use search::SearchPattern;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
 /// Application modes (vim-style modal editing)
@@ -16,7 +61,7 @@ pub enum Mode {
     Normal,
     /// Quick single-cell editing (entered via i, a, s)
     Insert,
-    /// Full vim editor for cell content (entered via Enter)
+    /// Read-only view of the whole cell, with search (entered via M)
     Magnifier,
     /// Edit column header names (entered via gh)
     HeaderEdit,
@@ -35,6 +80,8 @@ pub struct EditBuffer {
     pub cursor: usize,
     /// Original content (for cancel/undo)
     pub original: String,
+    /// The edit fills a row just added below (`o`) or above (`O`)
+    pub new_row: Option<bool>,
 }
 
 /// Main application state (v0.2.0 Phase 2: Refactored for separation of concerns)
@@ -46,181 +93,3154 @@ pub struct App {
     /// View/UI state (renamed from ui, moved to ui module)
     pub view_state: ViewState,
 
-    /// Input handling state (extracted from App)
-    pub input_state: InputState,
+    /// Input handling state (extracted from App)
+    pub input_state: InputState,
+
+    /// Multi-file session state (extracted from App)
+    pub session: Session,
+
+    /// Current application mode
+    pub mode: Mode,
+
+    /// Optional status message to display
+    pub status_messages: StatusMessages,
+
+    /// Edit buffer for cell editing (None when not editing)
+    pub edit_buffer: Option<EditBuffer>,
+
+    /// Cell shown in full in Magnifier mode
+    pub magnifier: Option<Magnifier>,
+
+    /// Results of the last `:grepall`, kept across files
+    pub grep: Option<GrepView>,
+
+    /// A second file shown beside the current one (`Ctrl+w s`/`v`)
+    pub split: Option<Split>,
+
+    /// Inferred type of each column (refreshed on load and after edits)
+    pub column_types: Vec<ColumnType>,
+
+    /// Question the next key answers (see `ask`)
+    pub confirm: Option<Confirm>,
+
+    /// Result of the last `:diff`, kept for `:diff export`
+    pub diff: Option<Diff>,
+
+    /// Position each row was read at, to save sorted rows in file order
+    pub row_order: RowOrder,
+
+    /// Last change, made again by `.`
+    pub last_change: Option<LastChange>,
+
+    /// Last edited cell position (also remembered for `g;`, see `record_edit`)
+    pub last_edit_position: Option<(RowIndex, ColIndex)>,
+
+    /// Undo/redo history of the current document (cleared on reload)
+    pub history: History,
+
+    /// Rows yanked or deleted last, pasted by `p` (the unnamed register)
+    pub row_clipboard: Option<RegisterRows>,
+
+    /// Named registers `"a`-`"z`
+    pub registers: Registers,
+
+    /// Cell register for x/P (cut a cell, place it elsewhere)
+    pub cell_register: Option<String>,
+
+    /// User configuration (defaults when no config file exists)
+    pub config: Config,
+
+    /// Mode colors and cursor shapes (from config)
+    pub theme: Theme,
+
+    /// Display options (`:set`, initialized from config)
+    pub options: Options,
+
+    /// Watches the config file for live reloading (None when not loaded from disk)
+    pub config_watcher: Option<ConfigWatcher>,
+
+    /// Follows the open file for appended rows (`--watch`, `:watch`)
+    pub tail: Option<FileTail>,
+
+    /// Sends the view to read-only followers (`:share`)
+    pub leader: Option<Leader>,
+
+    /// Mirrors another instance's view, read-only (`--follow`)
+    pub follower: Option<Follower>,
+
+    /// Swap files of files with unsaved changes
+    pub swap: swap::SwapFiles,
+
+    /// Flag to quit application
+    pub should_quit: bool,
+
+    /// How the user quit, which decides the exit code
+    pub exit_status: ExitStatus,
+
+    /// What Enter prints and quits with in `--pick` mode
+    pub pick: Option<Pick>,
+}
+
+impl App {
+    /// Create a new `App` instance from CLI arguments.
+    /// This function handles file scanning, initial data loading, and App creation.
+    pub fn from_cli(mut cli_args: crate::cli::CliArgs) -> Result<Self> {
+        // A follower opens whatever file the leader has open
+        let follow = match &cli_args.follow {
+            Some(socket) => {
+                let mut follower = Follower::connect(socket)?;
+                let view = follower.first_view(crate::session::share::FOLLOW_TIMEOUT)?;
+                cli_args.path = Some(view.file.clone());
+                Some((follower, view))
+            }
+            None => None,
+        };
+        // A saved session names its own files
+        let snapshot = match &cli_args.session {
+            Some(path) => Some(SessionSnapshot::load_from(path)?),
+            None => None,
+        };
+        let path = cli_args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+
+        // Extensions recognized during discovery (CLI list overrides defaults)
+        let extensions: Vec<String> = if cli_args.extensions.is_empty() {
+            crate::file_system::DEFAULT_EXTENSIONS
+                .iter()
+                .map(|e| e.to_string())
+                .collect()
+        } else {
+            cli_args.extensions.clone()
+        };
+
+        // `--recursive` and `--glob` look through the directory tree
+        let glob = match &cli_args.glob {
+            Some(pattern) => Some(crate::file_system::Glob::new(pattern)?),
+            None => None,
+        };
+        let mut truncated = false;
+        let mut scan_tree = |dir: &std::path::Path| -> Result<Vec<PathBuf>> {
+            let scan =
+                crate::file_system::scan_tree(dir, &extensions, cli_args.recursive, glob.as_ref())?;
+            truncated = scan.truncated;
+            Ok(scan.files)
+        };
+        let tree = cli_args.recursive || glob.is_some();
+        // Started on a directory: the file browser lets the user pick the
+        // file instead of showing whichever sorts first
+        let browse_dir = (path.is_dir() && !tree && snapshot.is_none()).then(|| path.clone());
+
+        // Determine the CSV file to load and scan directory for others
+        let (file_path, csv_files, current_file_index) = if let Some(snapshot) = &snapshot {
+            let files: Vec<PathBuf> = snapshot.files.iter().map(|f| f.path.clone()).collect();
+            (files[snapshot.current].clone(), files, snapshot.current)
+        } else if path.is_file() {
+            let mut csv_files = if tree {
+                let dir = match path.parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir,
+                    _ => std::path::Path::new("."),
+                };
+                scan_tree(dir)?
+            } else {
+                crate::file_system::scan_directory_for_csvs_with_extensions(&path, &extensions)?
+            };
+            // The file asked for is in the session even if the glob
+            // doesn't match it
+            let current_file_index = match csv_files.iter().position(|p| p == &path) {
+                Some(index) => index,
+                None => {
+                    csv_files.insert(0, path.clone());
+                    0
+                }
+            };
+            (path, csv_files, current_file_index)
+        } else if path.is_dir() {
+            let csv_files = if tree {
+                scan_tree(&path)?
+            } else {
+                crate::file_system::scan_directory_with_extensions(&path, &extensions)?
+            };
+            if csv_files.is_empty() {
+                anyhow::bail!("{}", messages::no_csv_files_found(&path));
+            }
+            let file_path = csv_files[0].clone();
+            (file_path, csv_files, 0)
+        } else {
+            anyhow::bail!("{}", messages::invalid_path(&path));
+        };
+
+        // Create file configuration (a saved session's, unless overridden)
+        let file_config = match &snapshot {
+            Some(snapshot) => {
+                let saved = snapshot.config();
+                crate::session::FileConfig::with_options(
+                    cli_args.delimiter.or(saved.delimiter),
+                    cli_args.no_headers || saved.no_headers,
+                    cli_args.encoding.clone().or(saved.encoding),
+                )
+                .with_preamble(cli_args.preamble().unwrap_or(saved.preamble))
+            }
+            None => crate::session::FileConfig::with_options(
+                cli_args.delimiter,
+                cli_args.no_headers,
+                cli_args.encoding.clone(),
+            )
+            .with_preamble(cli_args.preamble().unwrap_or_default()),
+        };
+
+        // Files a saved session read with their own delimiter
+        let file_configs = snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.file_configs(&file_config))
+            .unwrap_or_default();
+
+        // Load CSV data
+        let csv_data = file_configs
+            .iter()
+            .find(|(path, _)| *path == file_path)
+            .map_or(&file_config, |(_, config)| config)
+            .read_document(&file_path)
+            .context(messages::failed_to_load_csv(&file_path))?;
+
+        // Create the App
+        let mut app = Self::new(csv_data, csv_files, current_file_index, file_config);
+        app.session.set_extensions(extensions.clone());
+        for (path, config) in file_configs {
+            app.session.set_file_config(&path, Some(config));
+        }
+        if truncated {
+            app.status_messages
+                .push(StatusMessage::warning(messages::too_many_files_found(
+                    crate::file_system::MAX_DISCOVERED_FILES,
+                )));
+        }
+
+        // Earlier `:` commands for Up/Down in Command mode
+        if let Some(path) = crate::input::history::history_path() {
+            match crate::input::CommandHistory::load_from(&path) {
+                Ok(history) => app.input_state.history = history,
+                Err(e) => app
+                    .status_messages
+                    .push(StatusMessage::error(format!("{:#}", e))),
+            }
+        }
+
+        // Edit and close positions from earlier sessions (`g;`, `'"`); an
+        // unreadable store only costs the history
+        if let Some(path) = crate::session::positions::positions_path() {
+            if let Err(e) = app.session.load_positions(path) {
+                app.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+            }
+            app.load_bookmarks();
+        }
+        if let Some(path) = crate::session::resume::resume_path() {
+            if let Err(e) = app.session.load_resume_points(path) {
+                app.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+            }
+        }
+
+        // Apply user configuration; a broken config is reported in the
+        // status bar instead of preventing startup
+        app.config_watcher = crate::config::config_path().map(ConfigWatcher::new);
+        app.reload_config();
+        app.set_watch(cli_args.watch);
+        if let Some((follower, view)) = follow {
+            app.apply_shared_view(&view);
+            app.follower = Some(follower);
+        }
+
+        // Open at the position given on the command line; config errors win
+        // the status bar
+        let (row, col) = match cli_args.cell {
+            Some(cell) => (Some(cell.row), Some(cell.column)),
+            None => (cli_args.row, cli_args.col),
+        };
+        let session_given = snapshot.is_some();
+        if let Some(snapshot) = snapshot {
+            app.restore_session(snapshot);
+        }
+        let config_message = app.status_messages.pop_current();
+        let start_given = row.is_some() || col.is_some() || session_given;
+        app.goto_start_position(row, col.as_deref());
+        app.run_startup_commands(&cli_args.commands);
+        if let Some(message) = config_message {
+            app.status_messages.push(message);
+        }
+        let browse_dir = browse_dir.filter(|_| {
+            !start_given
+                && cli_args.commands.is_empty()
+                && cli_args.pick.is_none()
+                && app.follower.is_none()
+        });
+        if !app.offer_recovery() {
+            if let Some(dir) = browse_dir {
+                app.browse(&dir);
+            } else if !start_given && cli_args.commands.is_empty() {
+                app.offer_resume_point();
+            }
+        }
+        if let Some(what) = &cli_args.pick {
+            app.start_picking(Pick::parse(&app, what)?);
+        }
+        Ok(app)
+    }
+
+    /// Run the `:` commands given with `--cmd`, in order, reporting what
+    /// each one did after any start position message
+    pub fn run_startup_commands(&mut self, commands: &[String]) {
+        let mut messages: Vec<String> = self
+            .status_messages
+            .pop_current()
+            .map(|message| message.as_str().to_string())
+            .into_iter()
+            .collect();
+        for command in commands {
+            let command = command.trim();
+            let command = command.strip_prefix(':').unwrap_or(command);
+            if let Err(e) = crate::input::run_command(self, command) {
+                messages.push(format!("{:#}", e));
+            }
+            messages.extend(
+                self.status_messages
+                    .pop_current()
+                    .map(|message| message.as_str().to_string()),
+            );
+        }
+        if !messages.is_empty() {
+            self.status_messages.push(messages.join("; "));
+        }
+    }
+
+    /// Move the cursor to a starting row and column (`--row`, `--col`,
+    /// `--cell`), reporting where it landed or what was wrong
+    pub fn goto_start_position(&mut self, row: Option<usize>, col: Option<&str>) {
+        let mut messages = Vec::new();
+        if let Some(col) = col {
+            crate::navigation::commands::goto_column_spec(self, col);
+            messages.extend(self.status_messages.pop_current());
+        }
+        if let Some(row) = row {
+            crate::navigation::commands::goto_line(self, row);
+            messages.extend(self.status_messages.pop_current());
+        }
+        if !messages.is_empty() {
+            let text: Vec<&str> = messages.iter().map(StatusMessage::as_str).collect();
+            self.status_messages.push(text.join("; "));
+        }
+    }
+
+    /// Remember an edit of a cell for `g;` (in this and later sessions)
+    pub fn record_edit(&mut self, row: RowIndex, col: ColIndex) {
+        self.last_edit_position = Some((row, col));
+        self.view_state.edit_jump = None;
+        self.session.record_edit(CellPosition {
+            row: row.get(),
+            col: col.get(),
+        });
+    }
+
+    /// Remember the cursor position of the active file for `'"` and its
+    /// bookmarks (call before the file is closed)
+    pub fn remember_position(&mut self) {
+        if let Some(row) = self.get_selected_row() {
+            self.session.set_last_position(CellPosition {
+                row: row.get(),
+                col: self.view_state.selected_column.get(),
+            });
+        }
+        self.store_bookmarks();
+    }
+
+    /// Rows bookmarked with `mB`, top to bottom
+    pub fn bookmarks(&self) -> Vec<usize> {
+        self.view_state
+            .marks
+            .positions(&[MarkKind::Bookmark])
+            .into_iter()
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    /// Keep the bookmarks of the active file in the session, which saves
+    /// them with the positions
+    pub fn store_bookmarks(&mut self) {
+        let rows = self.bookmarks();
+        self.session.set_bookmarks(rows);
+    }
+
+    /// Mark the rows bookmarked in an earlier session (call once the file is
+    /// read)
+    pub fn load_bookmarks(&mut self) {
+        let rows = self.document.row_count();
+        for &row in self.session.bookmarks().iter().filter(|&&row| row < rows) {
+            self.view_state.marks.add(row, None, MarkKind::Bookmark);
+        }
+    }
+
+    /// Remember the cursor position and save all positions (call on exit)
+    pub fn save_positions(&mut self) -> Result<()> {
+        self.remember_position();
+        self.session.save_positions()
+    }
+
+    /// Reload the config file from disk, reporting errors in the status bar.
+    /// Returns true if the new config was applied.
+    pub fn reload_config(&mut self) -> bool {
+        let Some(path) = self.config_watcher.as_ref().map(|w| w.path().to_path_buf()) else {
+            return false;
+        };
+
+        match Config::load_from(&path).and_then(|config| self.apply_config(config)) {
+            Ok(()) => true,
+            Err(e) => {
+                self.status_messages
+                    .push(StatusMessage::error(messages::config_error(&format!(
+                        "{:#}",
+                        e
+                    ))));
+                false
+            }
+        }
+    }
+
+    /// Reload the config if the file changed on disk. Returns true if anything
+    /// needs redrawing (new config applied or an error reported).
+    pub fn check_config_changed(&mut self) -> bool {
+        let changed = self
+            .config_watcher
+            .as_mut()
+            .is_some_and(|watcher| watcher.poll_changed());
+        if !changed {
+            return false;
+        }
+
+        if self.reload_config() {
+            self.status_messages.push(messages::CONFIG_RELOADED);
+        }
+        true
+    }
+
+    /// Warn once when another program modifies the open file; returns true
+    /// if the status bar changed
+    pub fn check_file_changed(&mut self) -> bool {
+        if !self.session.poll_file_changed() {
+            return false;
+        }
+        // A follower can't edit, so it just shows the new contents
+        if self.follower.is_some() {
+            if let Err(e) = self.reread_current_file() {
+                self.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+            }
+            return true;
+        }
+        self.status_messages
+            .push(StatusMessage::warning(messages::file_changed_on_disk(
+                &self.document.filename,
+                self.document.is_dirty,
+            )));
+        true
+    }
+
+    /// Start or stop following the open file for appended rows
+    pub fn set_watch(&mut self, watch: bool) {
+        self.tail = watch.then(|| FileTail::new(self.get_current_file()));
+    }
+
+    /// Add rows another program appended to the open file while watching,
+    /// following them when the cursor is on the last row; returns true if
+    /// anything changed
+    pub fn check_file_appended(&mut self) -> bool {
+        let Some(event) = self.tail.as_mut().map(FileTail::poll) else {
+            return false;
+        };
+        // New bytes of a compressed file can't be parsed on their own
+        let compressed = Compression::from_path(self.get_current_file()).is_some();
+        let bytes = match event {
+            TailEvent::Unchanged => return false,
+            TailEvent::Grew(bytes) if !compressed => bytes,
+            // Truncated or rewritten: start over unless that loses edits
+            // (the changed-on-disk warning covers that case)
+            _ if self.document.is_dirty => return false,
+            _ => {
+                if let Err(e) = self.reread_current_file() {
+                    self.status_messages
+                        .push(StatusMessage::error(format!("{:#}", e)));
+                }
+                return true;
+            }
+        };
+        // What we read is what's on disk
+        self.session.mark_file_loaded();
+        if bytes.is_empty() {
+            return false;
+        }
+
+        let at_end = self.view_state.table_state.selected().map(|row| row + 1)
+            == Some(self.display_row_count());
+        let encoding = self.session.config().encoding.clone();
+        match self.document.append_records(&bytes, encoding) {
+            Ok(0) => return false,
+            Ok(added) => self.row_order.rows_appended(added),
+            Err(e) => {
+                self.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+                return true;
+            }
+        }
+        self.view_state.row_filter = self
+            .view_state
+            .row_filter
+            .take()
+            .map(|f| f.reapplied(&self.document));
+        self.refresh_row_groups();
+        self.refresh_column_types();
+        if at_end || self.view_state.table_state.selected().is_none() {
+            let last = self.display_row_count().checked_sub(1);
+            self.view_state.table_state.select(last);
+        }
+        true
+    }
+
+    /// What followers mirror of this instance
+    pub fn shared_view(&self) -> SharedView {
+        SharedView {
+            file: self
+                .get_current_file()
+                .canonicalize()
+                .unwrap_or_else(|_| self.get_current_file().clone()),
+            row: self.get_selected_row().map_or(0, |row| row.get()),
+            column: self.view_state.selected_column.get(),
+            filter: self
+                .view_state
+                .row_filter
+                .as_ref()
+                .map(|filter| (filter.column.get(), filter.value.clone())),
+            filter_pattern: self
+                .view_state
+                .row_filter
+                .as_ref()
+                .is_some_and(|filter| filter.pattern.is_some()),
+        }
+    }
+
+    /// Show the leader's file, filter and cursor
+    pub fn apply_shared_view(&mut self, view: &SharedView) {
+        if !self.session.is_current_file(&view.file) {
+            self.session.set_current_file(view.file.clone());
+            if let Err(e) = self.reload_current_file() {
+                self.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+                return;
+            }
+        }
+        self.view_state.row_filter = view.filter.as_ref().map(|(col, value)| {
+            let column = ColIndex::new(*col);
+            // The pattern is compiled with this instance's case options
+            match SearchPattern::new(value, &self.options) {
+                Ok(pattern) if view.filter_pattern => {
+                    RowFilter::matching_pattern(&self.document, column, pattern)
+                }
+                _ => RowFilter::matching(&self.document, column, value),
+            }
+        });
+        if !self.select_document_row(RowIndex::new(view.row)) {
+            self.view_state.table_state.select(Some(0));
+        }
+        let column = view
+            .column
+            .min(self.document.column_count().saturating_sub(1));
+        crate::navigation::commands::select_column(self, column);
+    }
+
+    /// Send the view to followers, or follow the leader's; returns true if
+    /// anything on screen changed
+    pub fn sync_shared_view(&mut self) -> bool {
+        if self.leader.is_some() {
+            let view = self.shared_view();
+            let Some(leader) = self.leader.as_mut() else {
+                return false;
+            };
+            if leader.publish(&view) {
+                self.status_messages.push(match leader.follower_count() {
+                    1 => "1 follower".to_string(),
+                    n => format!("{} followers", n),
+                });
+                return true;
+            }
+            return false;
+        }
+
+        let Some(follower) = self.follower.as_mut() else {
+            return false;
+        };
+        match follower.poll() {
+            Ok(Some(view)) => {
+                self.apply_shared_view(&view);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                self.follower = None;
+                self.status_messages
+                    .push(format!("{:#} - the view is yours now", e));
+                true
+            }
+        }
+    }
+
+    /// Read the open file again from disk (`:e!`), dropping unsaved edits
+    /// but keeping the cursor, filter and marks
+    pub fn reread_current_file(&mut self) -> Result<()> {
+        self.session.save_view_state(self.view_state.clone());
+        let result = self.reload_current_file();
+        if result.is_err() {
+            self.session.take_view_state();
+        }
+        result
+    }
+
+    /// Apply a loaded config (key bindings, theme), keeping the old one on error
+    pub fn apply_config(&mut self, config: Config) -> Result<()> {
+        let keymap = Keymap::from_bindings(&config.keys).map_err(anyhow::Error::msg)?;
+        let theme = Theme::from_config(&config.theme).map_err(anyhow::Error::msg)?;
+        self.input_state.keymap = keymap;
+        self.theme = theme;
+        self.options = config.options.clone();
+        self.refresh_column_types();
+        if config.column_groups != self.config.column_groups {
+            self.view_state.column_groups =
+                ColumnGroups::from_config(&self.document.headers, &config.column_groups);
+            self.view_state.selected_column = ColIndex::new(
+                self.view_state
+                    .column_groups
+                    .visible_column(self.view_state.selected_column.get()),
+            );
+        }
+        self.config = config;
+        self.refresh_row_groups();
+        self.sort_files();
+        Ok(())
+    }
+
+    /// Put the session's files in the order of the `fileorder` option,
+    /// keeping the files of the other pane and of `:grepall` matches
+    pub fn sort_files(&mut self) {
+        let Some(order) = self.options.file_order else {
+            return;
+        };
+        let Some(new_index) = self.session.sort_files(order) else {
+            return;
+        };
+        if let Some(split) = self.split.as_mut() {
+            split.pane.file = new_index[split.pane.file];
+        }
+        if let Some(grep) = self.grep.as_mut() {
+            for found in &mut grep.matches {
+                found.file = new_index[found.file];
+            }
+        }
+        self.view_state.file_picker = None;
+    }
+
+    /// Show the file browser on `dir`
+    pub fn browse(&mut self, dir: &std::path::Path) {
+        match crate::ui::FileBrowser::open(dir, self.session.extensions()) {
+            Ok(browser) => self.view_state.file_browser = Some(browser),
+            Err(e) => self
+                .status_messages
+                .push(StatusMessage::error(format!("{:#}", e))),
+        }
+    }
+
+    /// Create new App from loaded CSV data, file list, and file configuration
+    pub fn new(
+        csv_data: Document,
+        csv_files: Vec<PathBuf>,
+        current_file_index: usize,
+        file_config: crate::session::FileConfig,
+    ) -> Self {
+        // Initialize view state with first row selected
+        let mut view_state = ViewState::default();
+        view_state.table_state.select(Some(0));
+
+        // Create session
+        let session = Session::new(csv_files, current_file_index, file_config);
+
+        // Create input state
+        let input_state = InputState::new();
+
+        Self {
+            column_types: infer_column_types(&csv_data, &Locale::from_env()),
+            row_order: RowOrder::new(csv_data.row_count()),
+            document: csv_data,
+            view_state,
+            input_state,
+            session,
+            mode: Mode::Normal,
+            status_messages: StatusMessages::default(),
+            edit_buffer: None,
+            magnifier: None,
+            grep: None,
+            split: None,
+            confirm: None,
+            diff: None,
+            last_change: None,
+            last_edit_position: None,
+            history: History::default(),
+            row_clipboard: None,
+            registers: Registers::default(),
+            cell_register: None,
+            config: Config::default(),
+            theme: Theme::default(),
+            options: Options::default(),
+            config_watcher: None,
+            tail: None,
+            leader: None,
+            follower: None,
+            swap: swap::SwapFiles::default(),
+            should_quit: false,
+            exit_status: ExitStatus::Normal,
+            pick: None,
+        }
+    }
+
+    /// Handle keyboard input events
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<InputResult> {
+        let before = self.cursor_cell();
+        let result = crate::input::handle_key(self, key);
+        self.note_column_switch(before);
+        result
+    }
+
+    /// Handle mouse events; returns None if the event changed nothing
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<InputResult> {
+        let before = self.cursor_cell();
+        let result = crate::input::handle_mouse(self, mouse);
+        self.note_column_switch(before);
+        result
+    }
+
+    /// File, document row and column under the cursor
+    fn cursor_cell(&self) -> Option<(usize, usize, usize)> {
+        let row = self.get_selected_row()?;
+        Some((
+            self.session.active_file_index(),
+            row.get(),
+            self.view_state.selected_column.get(),
+        ))
+    }
+
+    /// Remember the row left behind when the cursor moved to another
+    /// column of the same file, for `gl`
+    fn note_column_switch(&mut self, before: Option<(usize, usize, usize)>) {
+        let Some((file, row, col)) = before else {
+            return;
+        };
+        if file == self.session.active_file_index() && self.view_state.selected_column.get() != col
+        {
+            self.view_state.column_rows.insert(col, row);
+            self.view_state.previous_column = Some(col);
+        }
+    }
+
+    /// Handle text pasted into the terminal
+    pub fn handle_paste(&mut self, text: &str) -> Option<InputResult> {
+        crate::input::handle_paste(self, text)
+    }
+
+    /// Document rows and columns covered by the Visual mode selection
+    pub fn visual_selection(&self) -> Option<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+        if self.mode != Mode::Visual {
+            return None;
+        }
+        let (anchor_row, anchor_col) = self.view_state.visual_anchor?;
+        let row = self.get_selected_row()?.get();
+        let col = self.view_state.selected_column.get();
+        let (anchor_row, anchor_col) = (anchor_row.get(), anchor_col.get());
+        Some((
+            anchor_row.min(row)..=anchor_row.max(row),
+            anchor_col.min(col)..=anchor_col.max(col),
+        ))
+    }
+
+    /// Split a `'<,'>` range off a command line; the range covers the rows
+    /// of the last Visual selection
+    pub fn command_range<'a>(
+        &self,
+        cmd: &'a str,
+    ) -> Result<(Option<RangeInclusive<usize>>, &'a str), &'static str> {
+        match cmd.strip_prefix(VISUAL_RANGE) {
+            None => Ok((None, cmd)),
+            Some(rest) => match &self.view_state.visual_rows {
+                Some(rows) => Ok((Some(rows.clone()), rest.trim_start())),
+                None => Err("No Visual selection for '<,'>"),
+            },
+        }
+    }
+
+    /// Document rows a `:fill` applies to: the range if given, else the rows
+    /// shown by the filter, else all rows
+    pub fn fill_rows(&self, range: Option<RangeInclusive<usize>>) -> Vec<usize> {
+        let visible = |row: &usize| {
+            self.view_state.row_filter.is_none() || self.display_row(RowIndex::new(*row)).is_some()
+        };
+        match (range, &self.view_state.row_filter) {
+            (Some(rows), _) => rows
+                .filter(|&row| row < self.document.row_count())
+                .filter(visible)
+                .collect(),
+            (None, Some(filter)) => (0..filter.len())
+                .filter_map(|display| filter.document_row(display))
+                .collect(),
+            (None, None) => (0..self.document.row_count()).collect(),
+        }
+    }
+
+    /// Get the document row under the cursor (for status display and edits)
+    pub fn get_selected_row(&self) -> Option<RowIndex> {
+        self.view_state
+            .table_state
+            .selected()
+            .and_then(|display| self.document_row(display))
+    }
+
+    /// Number of rows shown in the table (all rows unless filtered or
+    /// collapsed into a row group)
+    pub fn display_row_count(&self) -> usize {
+        match &self.view_state.row_filter {
+            Some(filter) => filter.len(),
+            None => self
+                .view_state
+                .row_groups
+                .display_row_count(self.document.row_count()),
+        }
+    }
+
+    /// Rows moved by one page (Ctrl+d/Ctrl+u, PageDown/PageUp), following
+    /// the `page` option
+    pub fn page_rows(&self) -> usize {
+        match self.view_state.table_height {
+            // Not drawn yet: a half page has nothing to measure against
+            0 => crate::navigation::PAGE_SIZE,
+            height => self.options.page.rows(height),
+        }
+        .max(1)
+    }
+
+    /// Rows moved by a full screen (Ctrl+f/Ctrl+b), keeping two rows of
+    /// context like vim
+    pub fn full_page_rows(&self) -> usize {
+        match self.view_state.table_height {
+            0 => crate::navigation::PAGE_SIZE,
+            height => height.saturating_sub(2).max(1),
+        }
+    }
+
+    /// Document row shown at a display position
+    pub fn document_row(&self, display: usize) -> Option<RowIndex> {
+        match &self.view_state.row_filter {
+            Some(filter) => filter.document_row(display),
+            None => self
+                .view_state
+                .row_groups
+                .document_row(display, self.document.row_count()),
+        }
+        .map(RowIndex::new)
+    }
+
+    /// Display position of a document row (None if it is filtered out or
+    /// inside a collapsed row group)
+    pub fn display_row(&self, row: RowIndex) -> Option<usize> {
+        match &self.view_state.row_filter {
+            Some(filter) => filter.display_row(row.get()),
+            None => self.view_state.row_groups.display_row(row.get()),
+        }
+    }
+
+    /// Strongest mark of each marked row, keyed by display position
+    pub fn display_row_marks(&self) -> BTreeMap<usize, MarkKind> {
+        self.view_state
+            .marks
+            .rows()
+            .into_iter()
+            .filter_map(|(row, kind)| {
+                self.display_row(RowIndex::new(row))
+                    .map(|display| (display, kind))
+            })
+            .collect()
+    }
+
+    /// Move the selection to a document row; returns false if it is hidden
+    pub fn select_document_row(&mut self, row: RowIndex) -> bool {
+        let display = self.display_row(row);
+        if display.is_some() {
+            self.view_state.table_state.select(display);
+        }
+        display.is_some()
+    }
+
+    /// Set a cell value as an undoable edit
+    pub fn set_cell(&mut self, row: RowIndex, col: ColIndex, value: String, label: &str) {
+        let old = self.document.get_cell(row, col).to_string();
+        self.commit_change(Change {
+            edits: vec![Edit::Cell {
+                row: row.get(),
+                col: col.get(),
+                old,
+                new: value,
+            }],
+            label: label.to_string(),
+        });
+    }
+
+    /// Apply a change to the document and record it for undo and `g;`;
+    /// derived columns of the changed rows are recomputed as part of it
+    pub fn commit_change(&mut self, mut change: Change) {
+        let Some(first) = change.edits.first() else {
+            return;
+        };
+        let (row, col) = self.edit_location(first);
+        self.apply_change(&change);
+        let recomputed = Change {
+            edits: self
+                .view_state
+                .derived_columns
+                .recompute(&self.document, &change.edits),
+            label: change.label.clone(),
+        };
+        self.apply_change(&recomputed);
+        change.edits.extend(recomputed.edits);
+        self.record_edit(row, col);
+        self.history.record(change);
+    }
+
+    /// Let status messages shown for `message_time` seconds go; returns
+    /// whether the status bar changed
+    pub fn expire_messages(&mut self) -> bool {
+        let after = std::time::Duration::from_secs(self.options.message_time);
+        self.status_messages
+            .expire(std::time::Instant::now(), after)
+    }
+
+    /// Ask `question` in the status bar; `y` runs `action`, any other key
+    /// cancels it
+    pub fn ask(&mut self, question: String, action: ConfirmAction) {
+        self.status_messages.clear();
+        self.confirm = Some(Confirm { question, action });
+    }
+
+    /// Answer the open question, if any
+    pub fn answer(&mut self, yes: bool) {
+        let Some(confirm) = self.confirm.take() else {
+            return;
+        };
+        if !yes {
+            self.status_messages.push("Cancelled");
+            return;
+        }
+        match confirm.action {
+            ConfirmAction::Commit { change, message } => {
+                self.commit_change(change);
+                self.status_messages.push(message);
+            }
+            // Answered by the input handler, which does the writing or
+            // pasting
+            ConfirmAction::Write { .. } | ConfirmAction::Paste { .. } => {}
+            ConfirmAction::Resume(position) => self.goto_resume_point(position),
+            ConfirmAction::Recover => self.recover_swap_file(),
+            ConfirmAction::ReplaceAll { changes } => {
+                let message = self.replace_all(changes);
+                self.status_messages.push(message);
+            }
+            ConfirmAction::WriteSelection { rows, columns } => {
+                let message = match self.write_selection(&rows, &columns) {
+                    Ok(()) => format!(
+                        "Wrote {} ({} columns, {} rows)",
+                        self.get_current_file().display(),
+                        self.document.column_count(),
+                        self.document.row_count()
+                    ),
+                    Err(e) => format!("{:#}", e),
+                };
+                self.status_messages.push(message);
+            }
+        }
+    }
+
+    /// Ask whether to jump to the file's resume point (`:resume-point set`),
+    /// the first time the file is opened this session
+    pub fn offer_resume_point(&mut self) {
+        if self.follower.is_some() {
+            return;
+        }
+        let Some(position) = self.session.take_resume_offer() else {
+            return;
+        };
+        let here = self.get_selected_row().map(|row| row.get());
+        if here == Some(position.row) {
+            return;
+        }
+        self.ask(
+            format!(
+                "Resume at row {}? (y/n)",
+                crate::ui::group_thousands(position.row + 1)
+            ),
+            ConfirmAction::Resume(position),
+        );
+    }
+
+    /// Move to the file's resume point (clamped to the document)
+    pub fn goto_resume_point(&mut self, position: CellPosition) {
+        let row = position
+            .row
+            .min(self.document.row_count().saturating_sub(1));
+        let message = if self.select_document_row(RowIndex::new(row)) {
+            let col = position
+                .col
+                .min(self.document.column_count().saturating_sub(1));
+            crate::navigation::commands::select_column(self, col);
+            self.view_state.viewport_mode = crate::ui::ViewportMode::Auto;
+            format!("Resumed at row {}", crate::ui::group_thousands(row + 1))
+        } else {
+            format!("Row {} is hidden by the filter", row + 1)
+        };
+        self.status_messages.push(message);
+    }
+
+    /// Overwrite the current file with only `rows` and `columns` of the
+    /// document, then read it back
+    fn write_selection(&mut self, rows: &[usize], columns: &[usize]) -> Result<()> {
+        let path = self.get_current_file().clone();
+        let write_headers = !self.session.config().no_headers;
+        self.document
+            .select(rows, columns)
+            .write_to(&path, write_headers)?;
+        self.reload_current_file()
+    }
+
+    /// Undo the most recent change; returns false if there was none
+    pub fn undo(&mut self) -> bool {
+        self.replay(History::undo, "Undid")
+    }
+
+    /// Redo the most recently undone change; returns false if there was none
+    pub fn redo(&mut self) -> bool {
+        self.replay(History::redo, "Redid")
+    }
+
+    fn replay(&mut self, take: fn(&mut History) -> Option<Change>, verb: &str) -> bool {
+        let Some(change) = take(&mut self.history) else {
+            return false;
+        };
+        self.apply_change(&change);
+        if let Some(first) = change.edits.first() {
+            let (row, col) = self.edit_location(first);
+            self.select_document_row(row);
+            crate::navigation::commands::select_column(self, col.get());
+        }
+        self.status_messages
+            .push(format!("{} {}", verb, change.label));
+        true
+    }
+
+    /// Cell an edit is about (for the cursor and `g;`)
+    fn edit_location(&self, edit: &Edit) -> (RowIndex, ColIndex) {
+        match edit {
+            Edit::Cell { row, col, .. } => (RowIndex::new(*row), ColIndex::new(*col)),
+            Edit::InsertRow { at, .. }
+            | Edit::DeleteRow { at, .. }
+            | Edit::Reorder { start: at, .. } => {
+                let last = self.document.row_count().saturating_sub(1);
+                (
+                    RowIndex::new((*at).min(last)),
+                    self.view_state.selected_column,
+                )
+            }
+        }
+    }
+
+    /// Apply edits to the document, keeping the filter, marks and row groups
+    /// in sync (changed cells and rows are marked)
+    fn apply_change(&mut self, change: &Change) {
+        for edit in &change.edits {
+            match edit {
+                Edit::Cell { row, col, new, .. } => {
+                    self.document
+                        .set_cell(RowIndex::new(*row), ColIndex::new(*col), new.clone());
+                    self.view_state
+                        .marks
+                        .add(*row, Some(*col), MarkKind::Changed);
+                }
+                Edit::InsertRow { at, values } => {
+                    self.document.insert_row(RowIndex::new(*at));
+                    if !values.is_empty() {
+                        self.document.rows[*at] = values.clone();
+                    }
+                    if let Some(filter) = self.view_state.row_filter.as_mut() {
+                        filter.row_inserted(*at);
+                    }
+                    self.view_state.marks.row_inserted(*at);
+                    self.view_state.marks.add(*at, None, MarkKind::Changed);
+                    self.row_order.row_inserted(*at);
+                    self.view_state.row_groups.row_inserted(*at);
+                }
+                Edit::DeleteRow { at, .. } => {
+                    self.document.delete_row(RowIndex::new(*at));
+                    if let Some(filter) = self.view_state.row_filter.as_mut() {
+                        filter.row_deleted(*at);
+                    }
+                    self.view_state.marks.row_deleted(*at);
+                    self.view_state.row_groups.row_deleted(*at);
+                    self.row_order.row_deleted(*at);
+                }
+                Edit::Reorder { start, order } => {
+                    self.document.reorder_rows(RowIndex::new(*start), order);
+                    self.view_state.marks.rows_reordered(*start, order);
+                    self.row_order.rows_reordered(*start, order);
+                    for (i, _) in order.iter().enumerate().filter(|(i, &from)| *i != from) {
+                        self.view_state
+                            .marks
+                            .add(start + i, None, MarkKind::Changed);
+                    }
+                    self.view_state.row_filter = self
+                        .view_state
+                        .row_filter
+                        .take()
+                        .map(|f| f.reapplied(&self.document));
+                }
+            }
+        }
+
+        // Keep the cursor on a row that still exists
+        let rows = self.display_row_count();
+        let selected = self.view_state.table_state.selected().unwrap_or(0);
+        if rows == 0 {
+            self.view_state.table_state.select(None);
+        } else if selected >= rows {
+            self.view_state.table_state.select(Some(rows - 1));
+        }
+        self.refresh_row_groups();
+        self.refresh_column_types();
+    }
+
+    /// Infer the column types again after the document changed
+    pub fn refresh_column_types(&mut self) {
+        self.column_types = infer_column_types(&self.document, &self.options.locale());
+    }
+
+    /// Inferred type of a column (text for columns not seen yet)
+    pub fn column_type(&self, col: ColIndex) -> ColumnType {
+        self.column_types
+            .get(col.get())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Detect row groups again after the document or the `rowgroups`
+    /// options changed, keeping the cursor on the same document row
+    pub fn refresh_row_groups(&mut self) {
+        let current = self.get_selected_row();
+        if self.options.row_groups {
+            self.view_state
+                .row_groups
+                .refresh(&self.document, &self.options.row_separator);
+        } else {
+            self.view_state.row_groups = RowGroups::default();
+        }
+
+        // A row hidden by an edit moves the cursor to its group's header
+        if let Some(row) = current {
+            let target = match self.view_state.row_groups.group_of(row.get()) {
+                Some(group) if self.view_state.row_groups.is_hidden(row.get()) => {
+                    RowIndex::new(group.header)
+                }
+                _ => row,
+            };
+            self.select_document_row(target);
+        }
+    }
+
+    /// Get current file path
+    pub fn get_current_file(&self) -> &PathBuf {
+        self.session.get_current_file()
+    }
+
+    /// Reload CSV data from current file
+    pub fn reload_current_file(&mut self) -> Result<()> {
+        let file_path = self.get_current_file().clone();
+
+        // Reuse the parsed document if the file hasn't changed since we left it
+        self.document = match self.session.take_cached_document() {
+            Some(document) => document,
+            None => {
+                let config = self.session.config();
+                config
+                    .read_document(&file_path)
+                    .context(messages::failed_to_reload_file(&file_path))?
+            }
+        };
+
+        // Edits of another document cannot be undone here
+        self.history.clear();
+        if let Some(change) = self.session.take_pending_change() {
+            self.history.record(change);
+        }
+        self.diff = None;
+        self.row_order = RowOrder::new(self.document.row_count());
+        self.refresh_column_types();
+        self.session.mark_file_loaded();
+        if self.tail.is_some() {
+            self.tail = Some(FileTail::new(&file_path));
+        }
+
+        // Restore where we left off in this file, or start at the top
+        let row_groups = |mut groups: RowGroups| {
+            if self.options.row_groups {
+                groups.refresh(&self.document, &self.options.row_separator);
+                groups
+            } else {
+                RowGroups::default()
+            }
+        };
+        self.view_state = match self.session.take_view_state() {
+            Some(mut view_state) => {
+                view_state.row_groups = row_groups(std::mem::take(&mut view_state.row_groups));
+                // The file may have changed on disk, so match the filter again
+                view_state.row_filter = view_state.row_filter.map(|f| f.reapplied(&self.document));
+                // Unsaved edits are not kept across reloads
+                view_state.marks.clear_kind(MarkKind::Changed);
+                let rows = match &view_state.row_filter {
+                    Some(filter) => filter.len(),
+                    None => view_state
+                        .row_groups
+                        .display_row_count(self.document.row_count()),
+                };
+                view_state.clamp_to(rows, self.document.column_count());
+                view_state
+            }
+            None => {
+                let mut view_state = ViewState::default();
+                view_state.table_state.select(Some(0));
+                view_state.column_groups =
+                    ColumnGroups::from_config(&self.document.headers, &self.config.column_groups);
+                view_state.row_groups = row_groups(RowGroups::default());
+                view_state
+            }
+        };
+        if !self
+            .view_state
+            .marks
+            .iter()
+            .any(|m| m.kind == MarkKind::Bookmark)
+        {
+            self.load_bookmarks();
+        }
+        self.apply_file_setup();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::position::{ColIndex, RowIndex};
+    use crate::input::{InputResult, PendingCommand};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::num::NonZeroUsize;
+    use std::path::PathBuf;
+
+    fn create_test_csv_data() -> Document {
+        Document {
+            headers: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "2".to_string(), "3".to_string()],
+                vec!["4".to_string(), "5".to_string(), "6".to_string()],
+                vec!["7".to_string(), "8".to_string(), "9".to_string()],
+            ],
+            filename: "test.csv".to_string(),
+            is_dirty: false,
+            ..Default::default()
+        }
+    }
+
+    fn key_event(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_app_initialization() {
+        let csv_data = create_test_csv_data();
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(0));
+        assert!(!app.should_quit);
+        assert!(!app.view_state.help_overlay_visible);
+    }
+
+    #[test]
+    fn test_config_reload_applies_keys_and_reports_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[keys]\nnext_file = \"n\"\n").unwrap();
+
+        let mut app = App::new(
+            create_test_csv_data(),
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.config_watcher = Some(ConfigWatcher::new(path.clone()));
+
+        assert!(app.reload_config());
+        assert_eq!(app.config.keys.len(), 1);
+        assert!(!app.input_state.keymap.is_empty());
+
+        // Invalid config keeps the previous bindings and reports the error
+        std::fs::write(&path, "[keys]\nnext_file = \"Hyper+n\"\n").unwrap();
+        assert!(!app.reload_config());
+        assert_eq!(
+            app.config.keys.get("next_file").map(String::as_str),
+            Some("n")
+        );
+        assert!(app
+            .status_messages
+            .current()
+            .unwrap()
+            .as_str()
+            .starts_with("Config error"));
+    }
+
+    #[test]
+    fn test_config_command_reload() {
+        let mut app = App::new(
+            create_test_csv_data(),
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in "config reload".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No config file location"
+        );
+    }
+
+    fn run_command(app: &mut App, command: &str) {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    }
+
+    #[test]
+    fn test_column_group_collapse_skips_hidden_columns() {
+        let document = Document {
+            headers: ["id", "cpu.user", "cpu.sys", "cpu.idle", "host"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            rows: vec![vec!["1", "2", "3", "4", "5"]
+                .into_iter()
+                .map(String::from)
+                .collect()],
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "group auto");
+        assert_eq!(app.view_state.column_groups.groups().len(), 1);
+
+        // zc on a member collapses the group and moves to its placeholder
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('c'))).unwrap();
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+
+        // l skips the hidden columns
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.view_state.selected_column, ColIndex::new(4));
+
+        // Jumping to a hidden column expands its group
+        run_command(&mut app, "c C");
+        assert_eq!(app.view_state.selected_column, ColIndex::new(2));
+        assert!(!app.view_state.column_groups.is_hidden(3));
+
+        run_command(&mut app, "ungroup");
+        assert!(app.view_state.column_groups.is_empty());
+    }
+
+    #[test]
+    fn test_stats_panel_follows_column() {
+        let mut app = App::new(
+            create_test_csv_data(),
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "stats");
+        let stats = app.view_state.stats_panel.as_ref().unwrap();
+        assert_eq!(stats.column, ColIndex::new(0));
+        assert_eq!(stats.count, app.document.row_count());
+
+        // l moves the selection and recomputes; j is swallowed by the panel
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        assert_eq!(
+            app.view_state.stats_panel.as_ref().unwrap().column,
+            ColIndex::new(1)
+        );
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert!(app.view_state.stats_panel.is_none());
+    }
+
+    #[test]
+    fn test_validate_marks_error_cells() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let schema = dir.path().join("schema.toml");
+        std::fs::write(&schema, "[columns.city]\nallowed = [\"Oslo\"]\n").unwrap();
+        let mut app = create_city_app();
+
+        run_command(&mut app, &format!("validate {}", schema.display()));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "1 violation in 1 of 4 rows (first: row 2 city: \"Rome\" is not one of Oslo)"
+        );
+        let errors: Vec<_> = app
+            .view_state
+            .marks
+            .iter()
+            .filter(|m| m.kind == MarkKind::Error)
+            .map(|m| (m.row, m.column))
+            .collect();
+        assert_eq!(errors, vec![(1, Some(1))]);
+
+        // Fixing the data and validating again clears the old marks
+        app.document.rows[1][1] = "Oslo".to_string();
+        run_command(&mut app, &format!("validate {}", schema.display()));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No violations in 4 rows"
+        );
+        assert!(app
+            .view_state
+            .marks
+            .iter()
+            .all(|m| m.kind != MarkKind::Error));
+
+        run_command(&mut app, "validate");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Usage: :validate <schema.toml>"
+        );
+    }
+
+    fn city_column(app: &App) -> Vec<&str> {
+        app.document.rows.iter().map(|r| r[1].as_str()).collect()
+    }
+
+    #[test]
+    fn test_fill_column_and_undo() {
+        let mut app = create_city_app();
+
+        run_command(&mut app, "fill city = \"Bergen\"");
+        assert_eq!(city_column(&app), vec!["Bergen"; 4]);
+        assert!(app.document.is_dirty);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Filled city in 4 rows - u to undo"
+        );
+
+        // The whole fill is undone and redone as one change
+        app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+        assert_eq!(city_column(&app), vec!["Oslo", "Rome", "Oslo", "Oslo"]);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Undid fill city"
+        );
+        app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert_eq!(city_column(&app), vec!["Bergen"; 4]);
+
+        run_command(&mut app, "fill city = name & '-' & city");
+        assert_eq!(app.document.rows[0][1], "a-Bergen");
+
+        run_command(&mut app, "fill town = 1");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No column named town"
+        );
+    }
+
+    #[test]
+    fn test_fill_visual_selection_and_filter() {
+        let mut app = create_city_app();
+
+        // Select rows 2-3 with `v` `j`, then open the command line
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('v'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        assert_eq!(app.mode, Mode::Command);
+        assert_eq!(app.input_state.command_buffer, "'<,'>");
+        for c in "fill city = 'x'".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(city_column(&app), vec!["Oslo", "x", "x", "Oslo"]);
+
+        // With a filter only the shown rows are filled
+        app.view_state.row_filter =
+            Some(RowFilter::matching(&app.document, ColIndex::new(1), "Oslo"));
+        run_command(&mut app, "fill name = 'z'");
+        let names: Vec<_> = app.document.rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(names, vec!["z", "b", "c", "z"]);
+    }
+
+    #[test]
+    fn test_fill_down_and_series_from_visual_selection() {
+        let mut app = create_city_app();
+        app.document.rows[0][0] = "id-08".to_string();
+
+        // `f` copies the first selected row down both selected columns
+        app.handle_key(key_event(KeyCode::Char('v'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('f'))).unwrap();
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.document.rows[1], vec!["id-08", "Oslo"]);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Filled 2 cells (fill down) - u to undo"
+        );
+
+        // `:'<,'>fill series` counts up in the current column
+        app.handle_key(key_event(KeyCode::Char('h'))).unwrap();
+        app.view_state.visual_rows = Some(0..=3);
+        run_command(&mut app, "'<,'>fill series 2");
+        let ids: Vec<_> = app.document.rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(ids, vec!["id-08", "id-10", "id-12", "id-14"]);
+
+        run_command(&mut app, "fill series x");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Step x is not a number"
+        );
+        assert!(app.undo());
+        assert_eq!(app.document.rows[3][0], "d");
+    }
+
+    #[test]
+    fn test_dot_repeats_last_change() {
+        let mut app = create_city_app();
+        app.handle_key(key_event(KeyCode::Char('.'))).unwrap();
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No change to repeat"
+        );
+
+        // An edit that appended text appends it again to the next cell
+        app.handle_key(key_event(KeyCode::Char('i'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('!'))).unwrap();
+        // Enter saves and moves down
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        app.handle_key(key_event(KeyCode::Char('.'))).unwrap();
+        assert_eq!(app.document.rows[0][0], "a!");
+        assert_eq!(app.document.rows[1][0], "b!");
+
+        // `dd` then `2.` deletes three rows
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('2'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('.'))).unwrap();
+        assert_eq!(app.document.row_count(), 1);
+
+        // A ranged `:fill` repeats on as many rows from the cursor
+        let mut app = create_city_app();
+        app.view_state.visual_rows = Some(0..=1);
+        run_command(&mut app, "'<,'>fill city = 'Paris'");
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('.'))).unwrap();
+        let cities: Vec<_> = app.document.rows.iter().map(|r| r[1].as_str()).collect();
+        assert_eq!(cities, vec!["Paris", "Paris", "Oslo", "Paris"]);
+    }
+
+    #[test]
+    fn test_cut_place_and_swap_cells() {
+        let mut app = create_city_app();
+        app.handle_key(key_event(KeyCode::Char('P'))).unwrap();
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Nothing to paste"
+        );
+
+        // x on Oslo, P on Rome's row
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('x'))).unwrap();
+        assert_eq!(app.document.rows[0][1], "");
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('P'))).unwrap();
+        assert_eq!(city_column(&app), vec!["", "Oslo", "Oslo", "Oslo"]);
+
+        // :swap marks B2, then exchanges it with A4
+        run_command(&mut app, "swap");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Marked B2; :swap on another cell exchanges them"
+        );
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('h'))).unwrap();
+        run_command(&mut app, "swap");
+        assert_eq!(app.document.rows[3], vec!["Oslo", "Oslo"]);
+        assert_eq!(app.document.rows[1], vec!["b", "d"]);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Swapped B2 and A4"
+        );
+        // One undo puts both back
+        assert!(app.undo());
+        assert_eq!(app.document.rows[1], vec!["b", "Oslo"]);
+        assert_eq!(app.document.rows[3], vec!["d", "Oslo"]);
+    }
+
+    #[test]
+    fn test_marks_and_jump_back() {
+        let mut app = create_city_app();
+        let keys = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+        let row = |app: &App| app.get_selected_row().unwrap().get();
+
+        keys(&mut app, "''");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No jump to return from"
+        );
+        keys(&mut app, "jlma");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Mark a set at B2"
+        );
+        keys(&mut app, "G");
+        assert_eq!(row(&app), 3);
+        // '' goes back to before G, and again to where that jump started
+        keys(&mut app, "''");
+        assert_eq!(row(&app), 1);
+        keys(&mut app, "''");
+        assert_eq!(row(&app), 3);
+
+        keys(&mut app, "gg'a");
+        assert_eq!((row(&app), app.view_state.selected_column.get()), (1, 1));
+        keys(&mut app, "''");
+        assert_eq!(row(&app), 0);
+        keys(&mut app, "'b");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Mark b not set"
+        );
+    }
+
+    #[test]
+    fn test_paste_tab_separated_fields_across_columns() {
+        let mut app = create_city_app();
+        app.handle_paste("x\tParis\n");
+        assert_eq!(
+            app.confirm.as_ref().unwrap().question,
+            "Paste 2 tab-separated fields into A1:B1 (y), into this cell (n) or cancel?"
+        );
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(app.document.rows[0], vec!["x", "Paris"]);
+
+        // Past the last column, the rest is dropped
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_paste("Bern\tCH");
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(app.document.rows[0], vec!["x", "Bern"]);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Pasted 1 cell; 1 field past the last column left out"
+        );
+
+        // n keeps the text in one cell, in the edit while typing
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('i'))).unwrap();
+        app.handle_paste("\t1\t2");
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert_eq!(app.mode, Mode::Insert);
+        assert_eq!(app.edit_buffer.as_ref().unwrap().content, "Rome\t1\t2");
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+
+        // Plain text replaces the cell
+        app.handle_paste("Milan");
+        assert_eq!(app.document.rows[1][1], "Milan");
+
+        // Lines fill the column down, adding rows past the last one
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        app.handle_paste("Lima\r\nQuito\r\n");
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            city_column(&app),
+            vec!["Bern", "Milan", "Oslo", "Lima", "Quito"]
+        );
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Pasted 2 cells, adding 1 row"
+        );
+        assert!(app.undo());
+        assert_eq!(app.document.row_count(), 4);
+    }
+
+    #[test]
+    fn test_command_line_history_and_cursor() {
+        let mut app = create_city_app();
+        run_command(&mut app, "sort city");
+        run_command(&mut app, "swap");
+
+        // Up recalls the newest command starting with what was typed
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('s'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('o'))).unwrap();
+        app.handle_key(key_event(KeyCode::Up)).unwrap();
+        assert_eq!(app.input_state.command_buffer, "sort city");
+        app.handle_key(key_event(KeyCode::Down)).unwrap();
+        assert_eq!(app.input_state.command_buffer, "so");
+        app.handle_key(key_event(KeyCode::Up)).unwrap();
+
+        // Edit within the line: "sort city" -> "sort name"
+        for _ in 0..4 {
+            app.handle_key(key_event(KeyCode::Backspace)).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Home)).unwrap();
+        app.handle_key(key_event(KeyCode::Right)).unwrap();
+        app.handle_key(key_event(KeyCode::Delete)).unwrap();
+        app.handle_key(key_event(KeyCode::Char('O'))).unwrap();
+        app.handle_key(key_event(KeyCode::End)).unwrap();
+        for c in "name".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        assert_eq!(app.input_state.command_buffer, "sOrt name");
+        app.handle_key(key_event(KeyCode::Left)).unwrap();
+        app.handle_key(key_event(KeyCode::Backspace)).unwrap();
+        assert_eq!(app.input_state.command_buffer, "sOrt nae");
+    }
+
+    #[test]
+    fn test_review_mode_marks_rows_until_apply_deletes() {
+        let mut app = create_city_app();
+        run_command(&mut app, "set review");
+
+        // dd marks a and b (moving down each time); dd on b again keeps it
+        for key in ['d', 'd', 'd', 'd', 'k', 'd', 'd'] {
+            app.handle_key(key_event(KeyCode::Char(key))).unwrap();
+        }
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Row 2 kept (1 pending)"
+        );
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        assert_eq!(app.document.row_count(), 4);
+        assert!(!app.document.is_dirty);
+
+        // :clear-deletes keeps the rows
+        run_command(&mut app, "clear-deletes");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Kept 2 rows marked for deletion"
+        );
+        run_command(&mut app, "apply-deletes");
+        assert_eq!(app.document.row_count(), 4);
+
+        // Rows 1 and 3 go in one undoable step
+        app.view_state.table_state.select(Some(0));
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        run_command(&mut app, "apply-deletes");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "2 rows deleted"
+        );
+        assert_eq!(city_column(&app), vec!["Rome", "Oslo"]);
+        app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+        assert_eq!(city_column(&app), vec!["Oslo", "Rome", "Oslo", "Oslo"]);
+
+        // Without review mode dd deletes at once
+        run_command(&mut app, "set noreview");
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        assert_eq!(app.document.row_count(), 3);
+    }
+
+    #[test]
+    fn test_gl_returns_to_last_row_of_previous_column() {
+        let mut app = create_city_app();
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+        press(&mut app, "gl");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No column to return to"
+        );
+
+        // Row 4 in A, row 1 in B, then gl back and forth
+        press(&mut app, "jjjlkkk");
+        press(&mut app, "gl");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Back to A4"
+        );
+        press(&mut app, "gl");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Back to B1"
+        );
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+
+        // Moving within a column doesn't touch the other column's row
+        press(&mut app, "jgl");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Back to A4"
+        );
+        press(&mut app, "gl");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Back to B2"
+        );
+    }
+
+    #[test]
+    fn test_counted_yank_and_named_registers() {
+        let mut app = create_city_app();
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+
+        // "a2yy holds Oslo and Rome in a; "byy holds Oslo in b
+        press(&mut app, "\"a2yy");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "2 rows yanked into \"a"
+        );
+        press(&mut app, "jj\"byy");
+        run_command(&mut app, "registers");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "\"\" 1 row  \"a 2 rows  \"b 1 row"
+        );
+
+        // "ap pastes both rows below the cursor as one change
+        press(&mut app, "G\"ap");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Pasted 2 rows"
+        );
+        assert_eq!(
+            city_column(&app),
+            vec!["Oslo", "Rome", "Oslo", "Oslo", "Oslo", "Rome"]
+        );
+        press(&mut app, "u");
+        assert_eq!(city_column(&app), vec!["Oslo", "Rome", "Oslo", "Oslo"]);
+
+        // "A appends; 2dd deletes into the unnamed register, which p pastes
+        press(&mut app, "gg\"Ayy");
+        assert_eq!(app.registers.get('a').unwrap().len(), 3);
+        press(&mut app, "2dd");
+        assert_eq!(city_column(&app), vec!["Oslo", "Oslo"]);
+        press(&mut app, "p");
+        assert_eq!(city_column(&app), vec!["Oslo", "Oslo", "Rome", "Oslo"]);
+        press(&mut app, "\"cp");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Register c is empty"
+        );
+
+        // Visual mode yanks the selected rows
+        press(&mut app, "ggvj\"zy");
+        assert_eq!(app.registers.get('z').unwrap().len(), 2);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_exit_status_and_printed_output() {
+        use crate::cli::PrintOnExit;
+
+        let mut app = create_city_app();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.exit_output(PrintOnExit::Cell).as_deref(), Some("Rome"));
+        assert_eq!(app.exit_output(PrintOnExit::Address).as_deref(), Some("B2"));
+        // Without a Visual selection, the cursor cell
+        assert_eq!(
+            app.exit_output(PrintOnExit::Selection).as_deref(),
+            Some("Rome")
+        );
+        for c in "vhj".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert_eq!(
+            app.exit_output(PrintOnExit::Selection).as_deref(),
+            Some("b\tRome\nc\tOslo")
+        );
+
+        // :q! only counts as discarding when there is something to discard
+        run_command(&mut app, "q!");
+        assert!(app.should_quit);
+        assert_eq!(app.exit_status.code(), 0);
+        app.handle_key(key_event(KeyCode::Delete)).unwrap();
+        run_command(&mut app, "q!");
+        assert_eq!(app.exit_status, ExitStatus::Discarded);
+        assert_eq!(app.exit_status.code(), 2);
+        run_command(&mut app, "cq");
+        assert_eq!(app.exit_status.code(), 1);
+    }
+
+    #[test]
+    fn test_paste_above_and_paste_over() {
+        let mut app = create_city_app();
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+
+        // P pastes the yanked row above the cursor
+        press(&mut app, "jyyGP");
+        assert_eq!(
+            city_column(&app),
+            vec!["Oslo", "Rome", "Oslo", "Rome", "Oslo"]
+        );
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+
+        // gp replaces the current row instead of inserting, as one change
+        press(&mut app, "uggjjgp");
+        assert_eq!(app.document.rows[2], vec!["b", "Rome"]);
+        assert_eq!(app.document.row_count(), 4);
+        assert!(app.document.is_dirty);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Replaced 1 row"
+        );
+        press(&mut app, "u");
+        assert_eq!(app.document.rows[2], vec!["c", "Oslo"]);
+
+        // Rows past the end of the file are left out
+        press(&mut app, "gg2yyGgp");
+        assert_eq!(app.document.rows[3], vec!["a", "Oslo"]);
+        assert_eq!(app.document.row_count(), 4);
+
+        // After x, P places the cut cell; after yy it pastes rows again
+        press(&mut app, "gghxjP");
+        assert_eq!(app.document.rows[1], vec!["a", "Rome"]);
+        press(&mut app, "yyP");
+        assert_eq!(app.document.row_count(), 5);
+    }
+
+    #[test]
+    fn test_pick_mode() {
+        let mut app = create_city_app();
+        assert!(Pick::parse(&app, "country").is_err());
+        assert_eq!(
+            Pick::parse(&app, "B").unwrap(),
+            Pick::Column(ColIndex::new(1))
+        );
+        app.start_picking(Pick::parse(&app, "row").unwrap());
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Enter picks a row, q cancels"
+        );
+        // Quitting without a pick cancels
+        assert_eq!(app.exit_status.code(), 1);
+
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        let result = app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert!(matches!(result, InputResult::Quit));
+        assert_eq!(app.exit_status, ExitStatus::Normal);
+        assert_eq!(app.picked_output(Pick::Row).as_deref(), Some("b\tRome"));
+        assert_eq!(app.picked_output(Pick::Cell).as_deref(), Some("b"));
+        assert_eq!(
+            app.picked_output(Pick::Column(ColIndex::new(1))).as_deref(),
+            Some("Rome")
+        );
+    }
+
+    #[test]
+    fn test_resize_mode_keeps_or_reverts() {
+        let mut app = create_city_app();
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+        let auto =
+            crate::ui::column_width::column_width(&app.document, &app.view_state, 0, &app.options);
+
+        // h/l resize live; other keys don't move the cursor meanwhile
+        press(&mut app, "zrllj");
+        assert_eq!(app.view_state.column_widths.get(&0), Some(&(auto + 4)));
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            format!("Column A width {} (Enter keeps, Esc reverts)", auto + 4)
+        );
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert!(app.view_state.column_widths.is_empty());
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Column A width automatic (reverted)"
+        );
+
+        // Enter keeps the new width, and j moves again
+        press(&mut app, "zrL");
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(app.view_state.column_widths.get(&0), Some(&(auto + 8)));
+        assert!(app.view_state.resizing.is_none());
+        press(&mut app, "j");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+    }
+
+    #[test]
+    fn test_errors_outlast_keypresses() {
+        let mut app = create_city_app();
+        run_command(&mut app, "bogus");
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        let message = app.status_messages.current().unwrap();
+        assert_eq!(message.as_str(), "Unknown command: :bogus");
+        assert_eq!(message.severity(), crate::input::Severity::Error);
+
+        // An info message shows over it and clears on the next key
+        run_command(&mut app, "set mt=0");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "messagetime=0"
+        );
+        app.handle_key(key_event(KeyCode::Char('k'))).unwrap();
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Unknown command: :bogus"
+        );
+        // messagetime=0 never lets it expire
+        assert!(!app.expire_messages());
+    }
+
+    #[test]
+    fn test_header_only_and_empty_documents() {
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+        let message = |app: &App| app.status_messages.current().unwrap().as_str().to_string();
+
+        let document = Document {
+            headers: vec!["name".to_string(), "city".to_string()],
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("a.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        press(&mut app, "i");
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(message(&app), "No data rows — press o to add one");
+        run_command(&mut app, "sort");
+        assert_eq!(message(&app), "0 rows already sorted by name");
+
+        // o adds the first row and edits it
+        press(&mut app, "o");
+        assert_eq!(app.mode, Mode::Insert);
+        press(&mut app, "x");
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            app.document.rows,
+            vec![vec!["x".to_string(), String::new()]]
+        );
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+
+        // With no columns at all there is no cell to edit or sort by
+        let mut app = App::new(
+            Document::default(),
+            vec![PathBuf::from("b.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        for keys in ["i", "o", "x", "v", "M", "zr"] {
+            press(&mut app, keys);
+            assert_eq!(app.mode, Mode::Normal, "{}", keys);
+            assert_eq!(message(&app), "No columns", "{}", keys);
+        }
+        run_command(&mut app, "sort");
+        assert_eq!(message(&app), "No columns");
+        assert_eq!(app.document.row_count(), 0);
+    }
+
+    #[test]
+    fn test_column_search() {
+        let mut app = create_city_app();
+        let press = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
+        press(&mut app, "l\\oslo");
+        assert_eq!(app.input_state.command_buffer, "csearch oslo");
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "3 matches for \"oslo\" in column B (city) (n/N to step through)"
+        );
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+        press(&mut app, "nn");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+
+        // Only the selected column is searched
+        press(&mut app, "h");
+        run_command(&mut app, "csearch oslo");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No \"oslo\" in column A (name)"
+        );
+        press(&mut app, "n");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No matches"
+        );
+
+        // Queries are regular expressions; bad ones are reported
+        press(&mut app, "l");
+        run_command(&mut app, "csearch ^r|^o");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "4 matches for \"^r|^o\" in column B (city) (n/N to step through)"
+        );
+        run_command(&mut app, "csearch Rome[");
+        assert_eq!(
+            app.status_messages.current().unwrap().severity(),
+            crate::input::Severity::Error
+        );
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Invalid pattern \"Rome[\": unclosed character class"
+        );
+    }
+
+    #[test]
+    fn test_filter_by_pattern() {
+        let mut app = create_city_app();
+        run_command(&mut app, "filter city~^r");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Filter: B ~ \"^r\" (1 of 4 rows, :nofilter to clear)"
+        );
+        // Smart case: capitals match case, \\c ignores it again
+        run_command(&mut app, "filter city~^R");
+        assert_eq!(app.display_row_count(), 1);
+        run_command(&mut app, "filter city~OSLO");
+        assert_eq!(app.display_row_count(), 0);
+        run_command(&mut app, "filter city~OSLO\\c");
+        assert_eq!(app.display_row_count(), 3);
+        run_command(&mut app, "set noignorecase");
+        run_command(&mut app, "filter city~oslo");
+        assert_eq!(app.display_row_count(), 0);
+
+        // A bad pattern leaves the filter as it was
+        run_command(&mut app, "filter name~[ab]");
+        assert_eq!(app.display_row_count(), 2);
+        run_command(&mut app, "filter name~(");
+        assert_eq!(
+            app.status_messages.current().unwrap().severity(),
+            crate::input::Severity::Error
+        );
+        assert_eq!(app.display_row_count(), 2);
+    }
+
+    #[test]
+    fn test_filter_by_comparison() {
+        let document = Document {
+            headers: vec!["when".to_string()],
+            rows: ["10/1/2024", "2/1/2024", "12/25/2023"]
+                .iter()
+                .map(|when| vec![when.to_string()])
+                .collect(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("dates.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        run_command(&mut app, "filter when>=2024-01-01");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Filter: A >= \"2024-01-01\" (2 of 3 rows, :nofilter to clear)"
+        );
+        run_command(&mut app, "filter when<3/1/2024");
+        assert_eq!(app.display_row_count(), 2);
+        // A value the column can't be compared with keeps the filter
+        run_command(&mut app, "filter when>soon");
+        assert_eq!(
+            app.status_messages.current().unwrap().severity(),
+            crate::input::Severity::Error
+        );
+        assert_eq!(app.display_row_count(), 2);
+    }
+
+    #[test]
+    fn test_derived_column_follows_its_sources() {
+        let document = Document {
+            headers: ["price", "qty", "total"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: vec![
+                vec!["2".to_string(), "3".to_string(), String::new()],
+                vec!["5".to_string(), "1".to_string(), String::new()],
+            ],
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("orders.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        run_command(&mut app, "derive total = price * qty");
+        assert_eq!(app.document.rows[0][2], "6");
+        assert_eq!(app.document.rows[1][2], "5");
+        assert!(app.view_state.derived_columns.contains(2));
+
+        // Editing a source cell recomputes the total in the same change
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('i'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('0'))).unwrap();
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(app.document.rows[0][1], "30");
+        assert_eq!(app.document.rows[0][2], "60");
+        app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+        assert_eq!(app.document.rows[0][1], "3");
+        assert_eq!(app.document.rows[0][2], "6");
+
+        run_command(&mut app, "deps");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "C total = price * qty (from A price, B qty)"
+        );
+        run_command(&mut app, "derive price = total + 1");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "A column can't be derived from itself"
+        );
+
+        run_command(&mut app, "underive total");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "total is plain data again"
+        );
+        run_command(&mut app, "deps");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No derived columns (:derive <column> = <expression>)"
+        );
+    }
+
+    #[test]
+    fn test_copy_command_arguments() {
+        let mut app = create_city_app();
+        run_command(&mut app, "copy json");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Unknown copy format json (use csv, tsv or sql)"
+        );
+
+        // :copy takes a Visual range like :sort and :fill
+        run_command(&mut app, "'<,'>copy sql");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No Visual selection for '<,'>"
+        );
+        app.view_state.visual_rows = Some(1..=2);
+        run_command(&mut app, "'<,'>copy sql users extra");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Usage: :copy [csv|tsv|sql [table]]"
+        );
+    }
+
+    #[test]
+    fn test_sort_visual_range_only() {
+        let mut app = create_city_app();
+        app.document.rows[0][0] = "z".to_string();
+
+        run_command(&mut app, "'<,'>sort");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No Visual selection for '<,'>"
+        );
+
+        // Select rows 1-3 and sort them by city, descending
+        app.handle_key(key_event(KeyCode::Char('v'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in "sort city desc".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        let names: Vec<_> = app.document.rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(names, vec!["b", "z", "c", "d"]);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Sorted rows 1-3 by city, descending"
+        );
+
+        // The whole document by name; one undo restores the previous order
+        run_command(&mut app, "sort name");
+        let names: Vec<_> = app.document.rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(names, vec!["b", "c", "d", "z"]);
+        app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+        let names: Vec<_> = app.document.rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(names, vec!["b", "z", "c", "d"]);
+
+        run_command(&mut app, "'<,'>freq");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            ":freq doesn't take a range"
+        );
+    }
+
+    #[test]
+    fn test_undo_row_delete_and_edit() {
+        let mut app = create_city_app();
+
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        assert_eq!(app.document.row_count(), 3);
+        app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+        assert_eq!(app.document.row_count(), 4);
+        assert_eq!(app.document.rows[0], vec!["a", "Oslo"]);
+
+        app.set_cell(
+            RowIndex::new(1),
+            ColIndex::new(1),
+            "Paris".to_string(),
+            "edit",
+        );
+        assert!(app.undo());
+        assert_eq!(app.document.rows[1][1], "Rome");
+        assert!(!app.undo());
+        assert!(app.redo());
+        assert_eq!(app.document.rows[1][1], "Paris");
+    }
+
+    #[test]
+    fn test_column_types_follow_edits() {
+        let mut app = create_city_app();
+        app.document.headers.push("pop".to_string());
+        for (i, row) in app.document.rows.iter_mut().enumerate() {
+            row.push((i * 100).to_string());
+        }
+        app.refresh_column_types();
+        assert_eq!(app.column_type(ColIndex::new(2)), ColumnType::Integer);
+
+        app.set_cell(
+            RowIndex::new(0),
+            ColIndex::new(2),
+            "1.5".to_string(),
+            "edit",
+        );
+        assert_eq!(app.column_type(ColIndex::new(2)), ColumnType::Float);
+        app.set_cell(
+            RowIndex::new(0),
+            ColIndex::new(2),
+            "many".to_string(),
+            "edit",
+        );
+        assert_eq!(app.column_type(ColIndex::new(2)), ColumnType::Text);
+        assert!(app.undo());
+        assert_eq!(app.column_type(ColIndex::new(2)), ColumnType::Float);
+        assert_eq!(app.column_type(ColIndex::new(9)), ColumnType::Text);
+    }
+
+    #[test]
+    fn test_long_cells_step_and_truncate_after_confirmation() {
+        let mut app = create_city_app();
+        app.document.rows[1][1] = "Roma".to_string();
+
+        run_command(&mut app, "long 3");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "4 cells longer than 3 chars: B1 (4), B2 (4), B3 (4), ... (n/N to step through)"
+        );
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        // Backwards past the first match wraps to the last
+        app.handle_key(key_event(KeyCode::Char('2'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('N'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Match 4 of 4"
+        );
+
+        run_command(&mut app, "long 3 truncate");
+        assert_eq!(
+            app.confirm.as_ref().unwrap().question,
+            "Truncate 4 cells to 3 chars? (y/n)"
+        );
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert!(app.confirm.is_none());
+        assert_eq!(app.document.rows[0][1], "Oslo");
+
+        run_command(&mut app, "long 3 truncate");
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(app.document.rows[0][1], "Osl");
+        assert_eq!(app.document.rows[1][1], "Rom");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Truncated 4 cells to 3 chars"
+        );
+        assert!(app.undo());
+        assert_eq!(app.document.rows[0][1], "Oslo");
+
+        run_command(&mut app, "long 10");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No cells longer than 10 chars"
+        );
+    }
+
+    #[test]
+    fn test_save_after_sort_asks_which_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sorted.csv");
+        std::fs::write(&path, "name\nc\na\nb\n").unwrap();
+        let document = Document::from_file(&path, None, false, None).unwrap();
+        let mut app = App::new(
+            document,
+            vec![path.clone()],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "sort name");
+        run_command(&mut app, "wq");
+        assert_eq!(
+            app.confirm.as_ref().unwrap().question,
+            "Rows were sorted: save sorted (y), in the file's order (o) or cancel?"
+        );
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert!(!app.should_quit);
+        assert!(app.document.is_dirty);
+
+        // A row added after sorting stays below the row it was added under
+        app.commit_change(Change {
+            edits: vec![Edit::InsertRow {
+                at: 1,
+                values: vec!["new".to_string()],
+            }],
+            label: "insert row".to_string(),
+        });
+        run_command(&mut app, "w");
+        app.handle_key(key_event(KeyCode::Char('o'))).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name\nc\na\nnew\nb\n"
+        );
+        assert_eq!(app.document.rows[0], vec!["a"]);
+
+        // Saving the sorted order makes it the file's order
+        run_command(&mut app, "w");
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name\na\nnew\nb\nc\n"
+        );
+        run_command(&mut app, "wq");
+        assert!(app.confirm.is_none());
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_wdiff_summarizes_what_saving_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "name,city\r\na,Oslo\r\nb,Rome\r\n").unwrap();
+        let document = Document::from_file(&path, None, false, None).unwrap();
+        let mut app = App::new(
+            document,
+            vec![path.clone()],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        app.commit_change(Change {
+            edits: vec![
+                Edit::InsertRow {
+                    at: 0,
+                    values: vec!["z".to_string(), "Bern".to_string()],
+                },
+                Edit::Cell {
+                    row: 2,
+                    col: 1,
+                    old: "Rome".to_string(),
+                    new: "Roma".to_string(),
+                },
+            ],
+            label: "edit".to_string(),
+        });
+        run_command(&mut app, "wdiff");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            format!("Saving {}: 1 cell modified, 1 row added", path.display())
+        );
+
+        run_command(&mut app, "w");
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("name,city\r\nz,Bern\r\n"));
+        run_command(&mut app, "wdiff");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            format!("Saving would not change {}", path.display())
+        );
+    }
+
+    #[test]
+    fn test_diff_marks_changes_and_exports_report() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old = dir.path().join("old.csv");
+        std::fs::write(&old, "name,city\na,Oslo\nb,Paris\nc,Oslo\ne,Bern\n").unwrap();
+        let mut app = create_city_app();
+
+        run_command(&mut app, "diff export report.csv");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Nothing to export (run :diff <file> first)"
+        );
+
+        run_command(&mut app, &format!("diff {} name", old.display()));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            format!(
+                "1 added, 1 removed, 1 changed vs {}; n/N to step through, :diff export <file> to save",
+                old.display()
+            )
+        );
+        assert_eq!(
+            app.view_state.marks.positions(&[MarkKind::SearchHit]),
+            vec![(1, Some(1)), (3, None)]
+        );
+
+        let report = dir.path().join("report.csv");
+        run_command(&mut app, &format!("diff export {}", report.display()));
+        assert_eq!(
+            std::fs::read_to_string(&report).unwrap(),
+            "change,row,changed_columns,name,city\n\
+             changed,2,city,b,Rome\n\
+             added,4,,d,Oslo\n\
+             removed,4,,e,Bern\n"
+        );
+    }
+
+    #[test]
+    fn test_write_columns_and_visible_rows_over_the_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("people.csv");
+        std::fs::write(&path, "id,name,email\n1,Ann,a@x\n2,Bo,b@x\n3,Cy,a@x\n").unwrap();
+        let document = Document::from_file(&path, None, false, None).unwrap();
+        let mut app = App::new(
+            document,
+            vec![path.clone()],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "w --columns name,phone");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No column named phone"
+        );
+        run_command(&mut app, "w --columns email,name");
+        assert_eq!(
+            app.confirm.as_ref().unwrap().question,
+            "Overwrite people.csv without 1 column? (y/n)"
+        );
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("id,"));
+
+        // Only the rows the filter shows
+        app.view_state.row_filter =
+            Some(RowFilter::matching(&app.document, ColIndex::new(2), "a@x"));
+        run_command(&mut app, "w --visible --columns id,email");
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "id,email\n1,a@x\n3,a@x\n"
+        );
+        assert_eq!(app.document.headers, vec!["id", "email"]);
+        assert!(!app.document.is_dirty);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            format!("Wrote {} (2 columns, 2 rows)", path.display())
+        );
+    }
+
+    #[test]
+    fn test_resume_point_offered_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("review.csv");
+        std::fs::write(&path, "id\n1\n2\n3\n4\n").unwrap();
+        let document = Document::from_file(&path, None, false, None).unwrap();
+        let mut app = App::new(
+            document,
+            vec![path.clone()],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "resume-point");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No resume point for this file (:resume-point set)"
+        );
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        run_command(&mut app, "resume-point set");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Resume point set at row 4"
+        );
+
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.offer_resume_point();
+        assert_eq!(
+            app.confirm.as_ref().unwrap().question,
+            "Resume at row 4? (y/n)"
+        );
+        app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+
+        // Only the first time the file is opened
+        app.offer_resume_point();
+        assert!(app.confirm.is_none());
+        run_command(&mut app, "resume-point clear");
+        assert_eq!(app.session.resume_point(), None);
+    }
+
+    #[test]
+    fn test_lint_reports_and_fix_repairs_on_save() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("lint.csv");
+        std::fs::write(&path, "\u{feff}name,city\r\na,Oslo\r\nb,Ro\u{7}me\n").unwrap();
+        let document = Document::from_file(&path, None, false, None).unwrap();
+        let mut app = App::new(
+            document,
+            vec![path.clone()],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        run_command(&mut app, "lint");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "BOM, mixed line endings (2 CRLF, 1 LF, 0 CR), 1 control characters; :lint fix to repair"
+        );
+        assert_eq!(
+            app.view_state.marks.positions(&[MarkKind::Error]),
+            vec![(1, None)]
+        );
+
+        run_command(&mut app, "lint fix");
+        assert_eq!(app.document.rows[1][1], "Rome");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Lint: stripped control characters from 1 cells; :w rewrites quoting, line endings and BOM"
+        );
+        run_command(&mut app, "w");
+        run_command(&mut app, "lint");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No problems in 3 records"
+        );
+    }
+
+    #[test]
+    fn test_check_marks_cells_not_matching_pattern() {
+        let mut app = create_city_app();
+        app.document.rows[2][1] = String::new();
+
+        // Like schema patterns, the whole value has to match
+        run_command(&mut app, "check city 'O[a-z]+'");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "1 of 3 city values don't match O[a-z]+ (first: row 2); n/N to step through"
+        );
+        assert_eq!(
+            app.view_state.marks.positions(&[MarkKind::Error]),
+            vec![(1, Some(1))]
+        );
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+
+        // Column letters work too, and a passing check clears the marks
+        run_command(&mut app, "check B /^[A-Z][a-z]+$/");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "All 3 city values match ^[A-Z][a-z]+$"
+        );
+        assert!(app
+            .view_state
+            .marks
+            .positions(&[MarkKind::Error])
+            .is_empty());
+
+        run_command(&mut app, "check city (");
+        assert!(app
+            .status_messages
+            .current()
+            .unwrap()
+            .as_str()
+            .starts_with("Invalid pattern"));
+        run_command(&mut app, "check nowhere x");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No column named nowhere"
+        );
+    }
+
+    #[test]
+    fn test_record_view_moves_fields_and_rows() {
+        let mut app = create_city_app();
+
+        run_command(&mut app, "record");
+        assert!(app.view_state.record_view);
+
+        // j/k walk the fields, h/l the rows; G stops at the last field
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+
+        // Esc lands on the same cell in the table
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert!(!app.view_state.record_view);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+    }
+
+    #[test]
+    fn test_visual_mode_extends_with_motions() {
+        let mut app = create_city_app();
+
+        app.handle_key(key_event(KeyCode::Char('v'))).unwrap();
+        assert_eq!(app.mode, Mode::Visual);
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.visual_selection(), Some((0..=1, 0..=1)));
 
-    /// Multi-file session state (extracted from App)
-    pub session: Session,
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.visual_selection().is_none());
+        assert!(app.view_state.visual_anchor.is_none());
+    }
 
-    /// Current application mode
-    pub mode: Mode,
+    #[test]
+    fn test_previous_edit_cycles_through_edits() {
+        let mut app = create_city_app();
+        let keys = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+            }
+        };
 
-    /// Optional status message to display
-    pub status_message: Option<StatusMessage>,
+        keys(&mut app, "g;");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No edits yet"
+        );
 
-    /// Edit buffer for cell editing (None when not editing)
-    pub edit_buffer: Option<EditBuffer>,
+        // Clear b (row 2, column A), then Oslo in row 4 (column B)
+        keys(&mut app, "j");
+        app.handle_key(key_event(KeyCode::Delete)).unwrap();
+        keys(&mut app, "jjl");
+        app.handle_key(key_event(KeyCode::Delete)).unwrap();
+        keys(&mut app, "gg0");
 
-    /// Last edited cell position (for `gi` command)
-    pub last_edit_position: Option<(RowIndex, ColIndex)>,
+        keys(&mut app, "g;");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
 
-    /// Row clipboard for yy/p operations
-    pub row_clipboard: Option<Vec<String>>,
+        keys(&mut app, "g;");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(0));
 
-    /// Flag to quit application
-    pub should_quit: bool,
-}
+        keys(&mut app, "g;");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "At oldest edit"
+        );
 
-impl App {
-    /// Create a new `App` instance from CLI arguments.
-    /// This function handles file scanning, initial data loading, and App creation.
-    pub fn from_cli(cli_args: crate::cli::CliArgs) -> Result<Self> {
-        let path = cli_args.path.unwrap_or_else(|| PathBuf::from("."));
+        // A new edit starts again from the newest
+        keys(&mut app, "jj");
+        app.handle_key(key_event(KeyCode::Delete)).unwrap();
+        keys(&mut app, "gg2g;");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+    }
 
-        // Determine the CSV file to load and scan directory for others
-        let (file_path, csv_files, current_file_index) = if path.is_file() {
-            let csv_files = crate::file_system::scan_directory_for_csvs(&path)?;
-            let current_file_index = csv_files.iter().position(|p| p == &path).unwrap_or(0);
-            (path, csv_files, current_file_index)
-        } else if path.is_dir() {
-            let csv_files = crate::file_system::scan_directory(&path)?;
-            if csv_files.is_empty() {
-                anyhow::bail!("{}", messages::no_csv_files_found(&path));
+    #[test]
+    fn test_quote_returns_to_position_at_last_close() {
+        let mut app = create_city_app();
+        let keys = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                app.handle_key(key_event(KeyCode::Char(c))).unwrap();
             }
-            let file_path = csv_files[0].clone();
-            (file_path, csv_files, 0)
-        } else {
-            anyhow::bail!("{}", messages::invalid_path(&path));
         };
 
-        // Create file configuration
-        let file_config = crate::session::FileConfig::with_options(
-            cli_args.delimiter,
-            cli_args.no_headers,
-            cli_args.encoding.clone(),
+        keys(&mut app, "'\"");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No position saved for this file"
         );
 
-        // Load CSV data
-        let csv_data = crate::csv::Document::from_file(
-            &file_path,
-            cli_args.delimiter,
-            cli_args.no_headers,
-            cli_args.encoding.clone(),
-        )
-        .context(messages::failed_to_load_csv(&file_path))?;
+        keys(&mut app, "jjl");
+        app.remember_position();
+        keys(&mut app, "gg0'\"");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Returned to B3"
+        );
+    }
 
-        // Create and return the App
-        Ok(Self::new(
-            csv_data,
-            csv_files,
-            current_file_index,
-            file_config,
-        ))
+    fn create_city_app() -> App {
+        let document = Document {
+            headers: vec!["name".to_string(), "city".to_string()],
+            rows: [("a", "Oslo"), ("b", "Rome"), ("c", "Oslo"), ("d", "Oslo")]
+                .iter()
+                .map(|(n, c)| vec![n.to_string(), c.to_string()])
+                .collect(),
+            filename: "cities.csv".to_string(),
+            ..Default::default()
+        };
+        App::new(
+            document,
+            vec![PathBuf::from("cities.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        )
     }
 
-    /// Create new App from loaded CSV data, file list, and file configuration
-    pub fn new(
-        csv_data: Document,
-        csv_files: Vec<PathBuf>,
-        current_file_index: usize,
-        file_config: crate::session::FileConfig,
-    ) -> Self {
-        // Initialize view state with first row selected
-        let mut view_state = ViewState::default();
-        view_state.table_state.select(Some(0));
+    #[test]
+    fn test_frequency_view_enter_filters_rows() {
+        let mut app = create_city_app();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
 
-        // Create session
-        let session = Session::new(csv_files, current_file_index, file_config);
+        run_command(&mut app, "freq");
+        let view = app.view_state.frequency_view.as_ref().unwrap();
+        assert_eq!(view.counts[0], ("Oslo".to_string(), 3));
 
-        // Create input state
-        let input_state = InputState::new();
+        // Keys drive the view, not the table
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('k'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
 
-        Self {
-            document: csv_data,
-            view_state,
-            input_state,
-            session,
-            mode: Mode::Normal,
-            status_message: None,
-            edit_buffer: None,
-            last_edit_position: None,
-            row_clipboard: None,
-            should_quit: false,
-        }
-    }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert!(app.view_state.frequency_view.is_none());
+        assert_eq!(app.display_row_count(), 3);
 
-    /// Handle keyboard input events
-    pub fn handle_key(&mut self, key: KeyEvent) -> Result<InputResult> {
-        crate::input::handle_key(self, key)
+        // Navigation walks the matching rows only
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
+
+        // Clearing keeps the cursor on the same document row
+        run_command(&mut app, "nofilter");
+        assert_eq!(app.display_row_count(), 4);
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(3)));
     }
 
-    /// Get current selected row index (for status display)
-    pub fn get_selected_row(&self) -> Option<RowIndex> {
-        self.view_state.table_state.selected().map(RowIndex::new)
+    #[test]
+    fn test_row_operations_keep_filter_in_sync() {
+        let mut app = create_city_app();
+        app.view_state.row_filter =
+            Some(RowFilter::matching(&app.document, ColIndex::new(1), "Oslo"));
+
+        // dd on the first match removes document row 0
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        assert_eq!(app.document.row_count(), 3);
+        assert_eq!(app.display_row_count(), 2);
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        assert_eq!(
+            app.document.get_cell(RowIndex::new(1), ColIndex::new(0)),
+            "c"
+        );
+
+        // o adds a row below that stays visible
+        app.handle_key(key_event(KeyCode::Char('o'))).unwrap();
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert_eq!(app.display_row_count(), 3);
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+
+        // A row hidden by the filter can't be jumped to
+        run_command(&mut app, "1");
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
     }
 
-    /// Get current file path
-    pub fn get_current_file(&self) -> &PathBuf {
-        self.session.get_current_file()
+    #[test]
+    fn test_edits_and_new_rows_are_marked_changed() {
+        let mut app = create_city_app();
+
+        // Edit B1
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('s'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('X'))).unwrap();
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert_eq!(app.view_state.marks.columns().get(&1), None);
+        app.handle_key(key_event(KeyCode::Char('i'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('Y'))).unwrap();
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            app.view_state.marks.columns().get(&1),
+            Some(&MarkKind::Changed)
+        );
+
+        // Inserting a row above shifts the mark down with its row
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('O'))).unwrap();
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        let rows: Vec<usize> = app.view_state.marks.rows().into_keys().collect();
+        assert_eq!(rows, vec![0, 1]);
     }
 
-    /// Reload CSV data from current file
-    pub fn reload_current_file(&mut self) -> Result<()> {
-        let file_path = self.get_current_file().clone();
-        let config = self.session.config();
+    #[test]
+    fn test_freeze_command() {
+        let mut app = create_city_app();
+
+        run_command(&mut app, "freeze A");
+        assert_eq!(app.view_state.frozen_columns, 1);
+
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        run_command(&mut app, "freeze");
+        assert_eq!(app.view_state.frozen_columns, 2);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Froze columns A-B"
+        );
+
+        run_command(&mut app, "unfreeze");
+        assert_eq!(app.view_state.frozen_columns, 0);
 
-        self.document = Document::from_file(
-            &file_path,
-            config.delimiter,
-            config.no_headers,
-            config.encoding.clone(),
+        run_command(&mut app, "freeze ?");
+        assert_eq!(app.view_state.frozen_columns, 0);
+    }
+
+    /// Rows: 0 a, 1 blank, 2 b, 3 c, 4 "---", 5 d
+    fn create_grouped_app() -> App {
+        let document = Document {
+            headers: vec!["name".to_string(), "n".to_string()],
+            rows: [
+                ("a", "1"),
+                ("", ""),
+                ("b", "2"),
+                ("c", "3"),
+                ("---", ""),
+                ("d", "4"),
+            ]
+            .iter()
+            .map(|(n, v)| vec![n.to_string(), v.to_string()])
+            .collect(),
+            filename: "groups.csv".to_string(),
+            ..Default::default()
+        };
+        App::new(
+            document,
+            vec![PathBuf::from("groups.csv")],
+            0,
+            crate::session::FileConfig::new(),
         )
-        .context(messages::failed_to_reload_file(&file_path))?;
+    }
 
-        // Reset view state
-        self.view_state = ViewState::default();
-        self.view_state.table_state.select(Some(0));
+    #[test]
+    fn test_row_group_motions_and_folding() {
+        let mut app = create_grouped_app();
 
-        Ok(())
+        app.handle_key(key_event(KeyCode::Char('}'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+
+        run_command(&mut app, "set rowgroups");
+        run_command(&mut app, "set rowsep=---");
+        assert_eq!(app.view_state.row_groups.len(), 2);
+
+        app.handle_key(key_event(KeyCode::Char('}'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        app.handle_key(key_event(KeyCode::Char('}'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(4)));
+        app.handle_key(key_event(KeyCode::Char('{'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+
+        // Folding the group on its header hides its two rows
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('c'))).unwrap();
+        assert_eq!(app.display_row_count(), 4);
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Collapsed 2 rows"
+        );
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(4)));
+
+        // Turning the option off shows every row again
+        run_command(&mut app, "set norowgroups");
+        assert_eq!(app.display_row_count(), 6);
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(4)));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::position::{ColIndex, RowIndex};
-    use crate::input::{InputResult, PendingCommand};
-    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-    use std::num::NonZeroUsize;
-    use std::path::PathBuf;
+    #[test]
+    fn test_row_groups_follow_row_edits() {
+        let mut app = create_grouped_app();
+        run_command(&mut app, "set rowgroups");
+        assert_eq!(app.view_state.row_groups.len(), 1);
 
-    fn create_test_csv_data() -> Document {
-        Document {
-            headers: vec!["A".to_string(), "B".to_string(), "C".to_string()],
-            rows: vec![
-                vec!["1".to_string(), "2".to_string(), "3".to_string()],
-                vec!["4".to_string(), "5".to_string(), "6".to_string()],
-                vec!["7".to_string(), "8".to_string(), "9".to_string()],
-            ],
-            filename: "test.csv".to_string(),
-            is_dirty: false,
+        // Collapse the blank-row group, then delete the first row above it
+        app.handle_key(key_event(KeyCode::Char('}'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('a'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('d'))).unwrap();
+
+        let group = app.view_state.row_groups.header_at(0).unwrap();
+        assert!(group.collapsed);
+        assert_eq!(app.display_row_count(), 1);
+    }
+
+    #[test]
+    fn test_magnifier_search_wrap_and_close() {
+        let mut app = create_city_app();
+        let lines: Vec<String> = (0..200).map(|i| format!("entry {}", i)).collect();
+        app.document.rows[1][1] = lines.join("\n");
+        app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+
+        app.handle_key(key_event(KeyCode::Char('M'))).unwrap();
+        assert_eq!(app.mode, Mode::Magnifier);
+        let magnifier = app.magnifier.as_ref().unwrap();
+        assert_eq!((magnifier.row, magnifier.col), (1, 1));
+
+        for c in "/entry 150".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
         }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Match 1 of 1"
+        );
+        assert!(app.magnifier.as_ref().unwrap().scroll > 100);
+
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('g'))).unwrap();
+        assert_eq!(app.magnifier.as_ref().unwrap().scroll, 0);
+        app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+        assert!(app.magnifier.as_ref().unwrap().scroll > 100);
+        app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+        assert_eq!(app.magnifier.as_ref().unwrap().scroll, 200 - 20);
+
+        app.handle_key(key_event(KeyCode::Char('w'))).unwrap();
+        assert!(!app.magnifier.as_ref().unwrap().wrap);
+
+        // Esc closes the magnifier on the same cell
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.magnifier.is_none());
+        assert_eq!(app.view_state.selected_column.get(), 1);
     }
 
-    fn key_event(code: KeyCode) -> KeyEvent {
-        KeyEvent::new(code, KeyModifiers::NONE)
+    #[test]
+    fn test_preview_toggle_and_scroll_keys() {
+        let mut app = create_city_app();
+
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.view_state.preview_scroll, 0);
+
+        app.handle_key(key_event(KeyCode::Char('K'))).unwrap();
+        assert!(app.options.preview);
+        app.handle_key(key_event(KeyCode::Char('3'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+        assert_eq!(app.view_state.preview_scroll, 3);
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('h'))).unwrap();
+        assert_eq!(app.view_state.preview_scroll, 2);
+
+        app.handle_key(key_event(KeyCode::Char('K'))).unwrap();
+        assert!(!app.options.preview);
     }
 
     #[test]
-    fn test_app_initialization() {
-        let csv_data = create_test_csv_data();
-        let csv_files = vec![PathBuf::from("test.csv")];
-        let app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+    fn test_group_command_with_range() {
+        let mut app = App::new(
+            create_test_csv_data(),
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
 
-        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
-        assert_eq!(app.view_state.selected_column, ColIndex::new(0));
-        assert!(!app.should_quit);
-        assert!(!app.view_state.help_overlay_visible);
+        run_command(&mut app, "group ab A:B");
+        let group = &app.view_state.column_groups.groups()[0];
+        assert_eq!((group.start, group.end), (0, 2));
+
+        run_command(&mut app, "group bad C:Z");
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Column out of range"
+        );
     }
 
     #[test]
@@ -343,7 +3363,7 @@ mod tests {
 
         app.handle_key(key_event(KeyCode::Char('q'))).unwrap();
         assert!(!app.should_quit); // Should not quit
-        assert!(app.status_message.is_some()); // Should show warning
+        assert!(!app.status_messages.is_empty()); // Should show warning
     }
 
     #[test]
@@ -399,6 +3419,52 @@ mod tests {
         assert_eq!(app.session.active_file_index(), 0);
     }
 
+    #[test]
+    fn test_file_picker_opens_matching_file() {
+        let csv_files = vec![
+            PathBuf::from("sales.csv"),
+            PathBuf::from("customers.csv"),
+            PathBuf::from("orders.csv"),
+        ];
+        let mut app = App::new(
+            create_test_csv_data(),
+            csv_files,
+            0,
+            crate::session::FileConfig::new(),
+        );
+        let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+
+        // Esc closes without switching
+        app.handle_key(ctrl_p).unwrap();
+        assert!(app.view_state.file_picker.is_some());
+        app.handle_key(key_event(KeyCode::Esc)).unwrap();
+        assert!(app.view_state.file_picker.is_none());
+
+        // Typed keys filter the list instead of moving the cursor
+        app.handle_key(ctrl_p).unwrap();
+        for c in "ord".chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+        let picker = app.view_state.file_picker.as_ref().unwrap();
+        assert_eq!(picker.query, "ord");
+        assert_eq!(picker.selected_file(), Some(2));
+
+        let result = app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(result, InputResult::ReloadFile);
+        assert_eq!(app.session.active_file_index(), 2);
+        assert!(app.view_state.file_picker.is_none());
+
+        // Unsaved changes keep the picker open
+        app.document.is_dirty = true;
+        app.handle_key(ctrl_p).unwrap();
+        app.handle_key(key_event(KeyCode::Char('s'))).unwrap();
+        let result = app.handle_key(key_event(KeyCode::Enter)).unwrap();
+        assert_eq!(result, InputResult::Continue);
+        assert_eq!(app.session.active_file_index(), 2);
+        assert!(app.view_state.file_picker.is_some());
+    }
+
     #[test]
     fn test_file_switching_previous() {
         let csv_data = create_test_csv_data();
@@ -797,6 +3863,7 @@ mod tests {
             ],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -900,6 +3967,7 @@ mod tests {
             ],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -920,6 +3988,46 @@ mod tests {
         // Should have moved or stayed at boundary
     }
 
+    #[test]
+    fn test_full_page_and_column_page_keys() {
+        let headers: Vec<String> = (0..30).map(|c| format!("Col{}", c)).collect();
+        let rows: Vec<Vec<String>> = (0..40)
+            .map(|r| (0..30).map(|c| format!("R{}C{}", r, c)).collect())
+            .collect();
+        let csv_data = Document {
+            headers,
+            rows,
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.table_height = 10;
+        app.view_state.table_width = 100;
+
+        // 2 Ctrl+f moves two screens of 8 rows
+        app.handle_key(key_event(KeyCode::Char('2'))).unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(16)));
+        app.handle_key(KeyEvent::new(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(8)));
+
+        // Alt+l moves a window of ten columns, zH half a window back
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::ALT))
+            .unwrap();
+        assert_eq!(app.view_state.column_scroll_offset, 10);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(10));
+        app.handle_key(key_event(KeyCode::Char('z'))).unwrap();
+        app.handle_key(key_event(KeyCode::Char('H'))).unwrap();
+        assert_eq!(app.view_state.column_scroll_offset, 5);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(5));
+    }
+
     #[test]
     fn test_home_end_keys() {
         // Setup: Create app at middle
@@ -1013,6 +4121,7 @@ mod tests {
             rows: vec![vec!["1".to_string(), "2".to_string()]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -1032,6 +4141,7 @@ mod tests {
             rows: vec![vec!["1".to_string()]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -1060,8 +4170,8 @@ mod tests {
         // Position should not change when out of bounds
         assert_eq!(app.get_selected_row(), initial_row);
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("does not exist"));
     }
 
@@ -1088,6 +4198,7 @@ mod tests {
             rows: vec![vec!["1".to_string()]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -1328,8 +4439,8 @@ mod tests {
         // Column should not have changed (X doesn't exist, shows error)
         assert_eq!(app.view_state.selected_column, ColIndex::new(0));
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("does not exist"));
     }
 
@@ -1372,10 +4483,10 @@ mod tests {
         app.handle_key(key_event(KeyCode::Char('t'))).unwrap();
 
         assert_eq!(app.view_state.viewport_mode, crate::ui::ViewportMode::Top);
-        assert!(app.status_message.is_some());
+        assert!(!app.status_messages.is_empty());
         assert!(app
-            .status_message
-            .as_ref()
+            .status_messages
+            .current()
             .unwrap()
             .as_str()
             .contains("top"));
@@ -1399,10 +4510,10 @@ mod tests {
             app.view_state.viewport_mode,
             crate::ui::ViewportMode::Center
         );
-        assert!(app.status_message.is_some());
+        assert!(!app.status_messages.is_empty());
         assert!(app
-            .status_message
-            .as_ref()
+            .status_messages
+            .current()
             .unwrap()
             .as_str()
             .contains("center"));
@@ -1426,10 +4537,10 @@ mod tests {
             app.view_state.viewport_mode,
             crate::ui::ViewportMode::Bottom
         );
-        assert!(app.status_message.is_some());
+        assert!(!app.status_messages.is_empty());
         assert!(app
-            .status_message
-            .as_ref()
+            .status_messages
+            .current()
             .unwrap()
             .as_str()
             .contains("bottom"));
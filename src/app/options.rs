@@ -0,0 +1,519 @@
+//! Runtime display options, changed with `:set` or the `[options]` config section.
+//!
+//! Boolean options follow vim conventions: `:set name` turns an option on,
+//! `:set noname` turns it off, `:set name!` toggles and `:set name?` shows it.
+//! Value options are set with `:set name=value`.
+
+use crate::csv::Locale;
+use crate::session::FileOrder;
+use crate::ui::column_width::{MAX_COLUMN_WIDTH, MIN_COLUMN_WIDTH};
+use serde::Deserialize;
+use std::fmt;
+
+/// Default `page` size in rows
+pub const DEFAULT_PAGE_ROWS: usize = 20;
+
+/// Default `scrolloff`: large enough to keep the cursor row centered
+pub const DEFAULT_SCROLLOFF: usize = 999;
+
+/// Default `message_time`: seconds a status message stays up
+pub const DEFAULT_MESSAGE_TIME: u64 = 5;
+
+/// Default `ellipsis`
+pub const DEFAULT_ELLIPSIS: &str = "…";
+
+/// User-adjustable options
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    /// Echo recent keys and the last `:` command in the corner of the screen
+    pub show_keys: bool,
+    /// Show a strip of all columns below the title bar
+    pub minimap: bool,
+    /// Show scrollbars along the right edge of the table (rows, with
+    /// marks) and below it (columns)
+    pub scrollbar: bool,
+    /// Show the full content of the selected cell above the status bar
+    pub preview: bool,
+    /// Split rows into groups at blank (or `row_separator`) rows
+    pub row_groups: bool,
+    /// First-cell value that also separates row groups (empty for none)
+    pub row_separator: String,
+    /// Capture the mouse for clicks, scrolling and drag selection
+    pub mouse: bool,
+    /// Line up the decimal points of numeric columns
+    pub align_decimals: bool,
+    /// Shade empty and null cells, and count them in the status bar
+    pub show_nulls: bool,
+    /// Shade every other row
+    pub zebra: bool,
+    /// Shade the whole cursor row and column
+    pub crosshair: bool,
+    /// Wrap the cells of the cursor row over as many lines as they need
+    pub wrap: bool,
+    /// `dd` marks rows for deletion (struck through) until `:apply-deletes`
+    pub review: bool,
+    /// Show a line of the keys that matter in the current mode
+    pub hints: bool,
+    /// Number rows by their distance from the cursor row
+    pub relative_number: bool,
+    /// With `relative_number`, show the cursor row's own number instead of 0
+    pub number: bool,
+    /// Searches and `:filter col~pattern` ignore case
+    pub ignore_case: bool,
+    /// With `ignore_case`, a query with capitals matches case
+    pub smart_case: bool,
+    /// Marks the end of a cell cut off at its column width
+    pub ellipsis: String,
+    /// Widest a column is made to fit its content (columns widened by hand
+    /// can go past it)
+    pub max_width: u16,
+    /// Rows moved by Ctrl+d/Ctrl+u and PageDown/PageUp
+    pub page: PageSize,
+    /// Rows of context kept above and below the cursor while scrolling
+    pub scrolloff: usize,
+    /// Seconds before a status message goes away by itself (0: never)
+    pub message_time: u64,
+    /// How numbers and dates are written (`None`: the locale of `LANG`)
+    pub locale: Option<Locale>,
+    /// Order of the files in the switcher (`None`: as found or given)
+    pub file_order: Option<FileOrder>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            show_keys: false,
+            minimap: false,
+            scrollbar: false,
+            preview: false,
+            row_groups: false,
+            row_separator: String::new(),
+            mouse: true,
+            align_decimals: false,
+            show_nulls: false,
+            zebra: false,
+            crosshair: false,
+            wrap: false,
+            review: false,
+            hints: false,
+            relative_number: false,
+            number: true,
+            ignore_case: true,
+            smart_case: true,
+            ellipsis: DEFAULT_ELLIPSIS.to_string(),
+            max_width: MAX_COLUMN_WIDTH,
+            page: PageSize::Rows(DEFAULT_PAGE_ROWS),
+            scrolloff: DEFAULT_SCROLLOFF,
+            message_time: DEFAULT_MESSAGE_TIME,
+            locale: None,
+            file_order: None,
+        }
+    }
+}
+
+/// Page size: half the visible rows (like vim's Ctrl+d) or a fixed count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "PageSizeValue")]
+pub enum PageSize {
+    Half,
+    Rows(usize),
+}
+
+impl PageSize {
+    /// Rows to move, given how many rows are visible
+    pub fn rows(self, visible_rows: usize) -> usize {
+        match self {
+            PageSize::Half => (visible_rows / 2).max(1),
+            PageSize::Rows(rows) => rows,
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "half" => Ok(PageSize::Half),
+            _ => match value.parse::<usize>() {
+                Ok(rows) if rows > 0 => Ok(PageSize::Rows(rows)),
+                _ => Err(format!(
+                    "Invalid page size: {} (use half or a number)",
+                    value
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for PageSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PageSize::Half => write!(f, "half"),
+            PageSize::Rows(rows) => write!(f, "{}", rows),
+        }
+    }
+}
+
+/// `page` as written in the config file: `"half"` or a number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PageSizeValue {
+    Rows(usize),
+    Name(String),
+}
+
+impl TryFrom<PageSizeValue> for PageSize {
+    type Error = String;
+
+    fn try_from(value: PageSizeValue) -> Result<Self, String> {
+        match value {
+            PageSizeValue::Rows(rows) => PageSize::parse(&rows.to_string()),
+            PageSizeValue::Name(name) => PageSize::parse(&name),
+        }
+    }
+}
+
+/// Accessor for a boolean option's field
+type BoolField = fn(&mut Options) -> &mut bool;
+
+/// Boolean options accepted by `:set` (full name, short name, field)
+const BOOL_OPTIONS: &[(&str, &str, BoolField)] = &[
+    ("showkeys", "sk", |o| &mut o.show_keys),
+    ("minimap", "mm", |o| &mut o.minimap),
+    ("scrollbar", "sb", |o| &mut o.scrollbar),
+    ("preview", "pv", |o| &mut o.preview),
+    ("rowgroups", "rg", |o| &mut o.row_groups),
+    ("mouse", "mo", |o| &mut o.mouse),
+    ("aligndecimals", "ad", |o| &mut o.align_decimals),
+    ("shownulls", "sn", |o| &mut o.show_nulls),
+    ("zebra", "zb", |o| &mut o.zebra),
+    ("crosshair", "cx", |o| &mut o.crosshair),
+    ("wrap", "wr", |o| &mut o.wrap),
+    ("review", "rv", |o| &mut o.review),
+    ("hints", "hi", |o| &mut o.hints),
+    ("relativenumber", "rnu", |o| &mut o.relative_number),
+    ("number", "nu", |o| &mut o.number),
+    ("ignorecase", "ic", |o| &mut o.ignore_case),
+    ("smartcase", "scs", |o| &mut o.smart_case),
+];
+
+/// Current value of a value option, as shown by `:set`
+type ValueGet = fn(&Options) -> String;
+
+/// Parse and store a value option
+type ValueSet = fn(&mut Options, &str) -> Result<(), String>;
+
+/// Value options accepted by `:set name=value` (full name, short name,
+/// getter, setter)
+const VALUE_OPTIONS: &[(&str, &str, ValueGet, ValueSet)] = &[
+    (
+        "page",
+        "pg",
+        |o| o.page.to_string(),
+        |o, v| {
+            o.page = PageSize::parse(v)?;
+            Ok(())
+        },
+    ),
+    (
+        "scrolloff",
+        "so",
+        |o| o.scrolloff.to_string(),
+        |o, v| {
+            o.scrolloff = v
+                .parse()
+                .map_err(|_| format!("Invalid scrolloff: {} (use a number)", v))?;
+            Ok(())
+        },
+    ),
+    (
+        "messagetime",
+        "mt",
+        |o| o.message_time.to_string(),
+        |o, v| {
+            o.message_time = v
+                .parse()
+                .map_err(|_| format!("Invalid messagetime: {} (use seconds)", v))?;
+            Ok(())
+        },
+    ),
+    (
+        "locale",
+        "lc",
+        |o| o.locale().to_string(),
+        |o, v| {
+            o.locale = match v {
+                "auto" => None,
+                name => Some(Locale::parse(name)?),
+            };
+            Ok(())
+        },
+    ),
+    (
+        "fileorder",
+        "fo",
+        |o| {
+            o.file_order
+                .map_or("none".to_string(), |order| order.to_string())
+        },
+        |o, v| {
+            o.file_order = match v {
+                "none" => None,
+                name => Some(FileOrder::parse(name)?),
+            };
+            Ok(())
+        },
+    ),
+    (
+        "ellipsis",
+        "el",
+        |o| o.ellipsis.clone(),
+        |o, v| {
+            o.ellipsis = v.to_string();
+            Ok(())
+        },
+    ),
+    (
+        "maxwidth",
+        "mw",
+        |o| o.max_width.to_string(),
+        |o, v| {
+            o.max_width = v
+                .parse()
+                .ok()
+                .filter(|width| (MIN_COLUMN_WIDTH..=MAX_COLUMN_WIDTH).contains(width))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid maxwidth: {} (use {} to {})",
+                        v, MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH
+                    )
+                })?;
+            Ok(())
+        },
+    ),
+    (
+        "rowseparator",
+        "rowsep",
+        |o| o.row_separator.clone(),
+        |o, v| {
+            o.row_separator = v.to_string();
+            Ok(())
+        },
+    ),
+];
+
+impl Options {
+    /// The locale numbers and dates are read and shown in
+    pub fn locale(&self) -> Locale {
+        self.locale.clone().unwrap_or_else(Locale::from_env)
+    }
+
+    /// Apply a `:set` argument, returning a message describing the new value
+    pub fn set(&mut self, spec: &str) -> Result<String, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Ok(self.describe());
+        }
+
+        if let Some((name, value)) = spec.split_once('=') {
+            let Some(&(full_name, _, get, set)) = find_value_option(name.trim()) else {
+                return Err(format!("Unknown option: {}", name.trim()));
+            };
+            set(self, value.trim())?;
+            return Ok(format_value(full_name, &get(self)));
+        }
+        if let Some(&(full_name, _, get, _)) = find_value_option(spec.trim_end_matches('?')) {
+            return Ok(format_value(full_name, &get(self)));
+        }
+
+        let (name, op) = if let Some(name) = spec.strip_suffix('!') {
+            (name, BoolOp::Toggle)
+        } else if let Some(name) = spec.strip_suffix('?') {
+            (name, BoolOp::Query)
+        } else if let Some(name) = spec.strip_prefix("no").filter(|n| is_bool_option(n)) {
+            (name, BoolOp::Off)
+        } else if let Some(name) = spec.strip_prefix("inv").filter(|n| is_bool_option(n)) {
+            (name, BoolOp::Toggle)
+        } else {
+            (spec, BoolOp::On)
+        };
+
+        let Some(&(full_name, _, field)) = find_bool_option(name) else {
+            return Err(format!("Unknown option: {}", name));
+        };
+
+        let value = field(self);
+        match op {
+            BoolOp::On => *value = true,
+            BoolOp::Off => *value = false,
+            BoolOp::Toggle => *value = !*value,
+            BoolOp::Query => {}
+        }
+        Ok(format_bool(full_name, *value))
+    }
+
+    /// Summary of all options (`:set` with no argument)
+    pub fn describe(&self) -> String {
+        let mut copy = self.clone();
+        let mut parts: Vec<String> = BOOL_OPTIONS
+            .iter()
+            .map(|(name, _, field)| format_bool(name, *field(&mut copy)))
+            .collect();
+        parts.extend(
+            VALUE_OPTIONS
+                .iter()
+                .map(|(name, _, get, _)| format_value(name, &get(self))),
+        );
+        parts.join("  ")
+    }
+}
+
+enum BoolOp {
+    On,
+    Off,
+    Toggle,
+    Query,
+}
+
+fn find_bool_option(name: &str) -> Option<&'static (&'static str, &'static str, BoolField)> {
+    BOOL_OPTIONS
+        .iter()
+        .find(|(full, short, _)| *full == name || *short == name)
+}
+
+fn find_value_option(
+    name: &str,
+) -> Option<&'static (&'static str, &'static str, ValueGet, ValueSet)> {
+    VALUE_OPTIONS
+        .iter()
+        .find(|(full, short, _, _)| *full == name || *short == name)
+}
+
+fn is_bool_option(name: &str) -> bool {
+    find_bool_option(name).is_some()
+}
+
+fn format_bool(name: &str, value: bool) -> String {
+    if value {
+        name.to_string()
+    } else {
+        format!("no{}", name)
+    }
+}
+
+fn format_value(name: &str, value: &str) -> String {
+    format!("{}={}", name, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_bool_option_on_off_toggle() {
+        let mut options = Options::default();
+
+        assert_eq!(options.set("showkeys").unwrap(), "showkeys");
+        assert!(options.show_keys);
+
+        assert_eq!(options.set("noshowkeys").unwrap(), "noshowkeys");
+        assert!(!options.show_keys);
+
+        assert_eq!(options.set("showkeys!").unwrap(), "showkeys");
+        assert_eq!(options.set("invsk").unwrap(), "noshowkeys");
+    }
+
+    #[test]
+    fn test_set_query_does_not_change() {
+        let mut options = Options::default();
+        assert_eq!(options.set("showkeys?").unwrap(), "noshowkeys");
+        assert!(!options.show_keys);
+    }
+
+    #[test]
+    fn test_set_unknown_option() {
+        let mut options = Options::default();
+        assert!(options.set("bogus").is_err());
+        assert!(options.set("nobogus").is_err());
+    }
+
+    #[test]
+    fn test_set_without_argument_describes_all() {
+        let mut options = Options {
+            // The default follows LANG
+            locale: Some(Locale::default()),
+            ..Options::default()
+        };
+        assert_eq!(
+            options.set("").unwrap(),
+            "noshowkeys  nominimap  noscrollbar  nopreview  norowgroups  mouse  noaligndecimals  noshownulls  nozebra  nocrosshair  nowrap  noreview  nohints  norelativenumber  number  ignorecase  smartcase  page=20  scrolloff=999  messagetime=5  locale=C  fileorder=none  ellipsis=…  maxwidth=100  rowseparator="
+        );
+    }
+
+    #[test]
+    fn test_set_locale() {
+        let mut options = Options::default();
+        assert_eq!(options.set("lc=de_DE.UTF-8").unwrap(), "locale=de_DE.UTF-8");
+        assert_eq!(options.locale().decimal(), ',');
+        assert!(options.set("locale=???").is_err());
+        assert_eq!(
+            options.set("locale=auto").unwrap(),
+            format!("locale={}", Locale::from_env())
+        );
+        assert_eq!(options.locale, None);
+    }
+
+    #[test]
+    fn test_set_value_option() {
+        let mut options = Options::default();
+        assert_eq!(options.set("rowsep=---").unwrap(), "rowseparator=---");
+        assert_eq!(options.set("mw=30").unwrap(), "maxwidth=30");
+        assert!(options.set("maxwidth=2").is_err());
+        assert_eq!(options.max_width, 30);
+        assert_eq!(options.row_separator, "---");
+        assert_eq!(options.set("rowseparator?").unwrap(), "rowseparator=---");
+        assert_eq!(options.set("rowsep=").unwrap(), "rowseparator=");
+        assert!(options.set("bogus=1").is_err());
+    }
+
+    #[test]
+    fn test_set_page_and_scrolloff() {
+        let mut options = Options::default();
+        assert_eq!(options.set("page=half").unwrap(), "page=half");
+        assert_eq!(options.page.rows(30), 15);
+        assert_eq!(options.set("pg=5").unwrap(), "page=5");
+        assert_eq!(options.page.rows(30), 5);
+        assert!(options.set("page=0").is_err());
+        assert!(options.set("page=most").is_err());
+        assert_eq!(options.page, PageSize::Rows(5));
+
+        assert_eq!(options.set("so=3").unwrap(), "scrolloff=3");
+        assert_eq!(options.scrolloff, 3);
+        assert!(options.set("scrolloff=-1").is_err());
+
+        assert_eq!(options.set("mt=0").unwrap(), "messagetime=0");
+        assert_eq!(options.message_time, 0);
+        assert!(options.set("messagetime=soon").is_err());
+    }
+
+    #[test]
+    fn test_page_size_from_config() {
+        let options: Options = toml::from_str("page = \"half\"\nscrolloff = 2").unwrap();
+        assert_eq!(options.page, PageSize::Half);
+        assert_eq!(options.scrolloff, 2);
+
+        let options: Options = toml::from_str("page = 40").unwrap();
+        assert_eq!(options.page, PageSize::Rows(40));
+        assert!(toml::from_str::<Options>("page = \"lots\"").is_err());
+    }
+
+    #[test]
+    fn test_set_file_order() {
+        let mut options = Options::default();
+        assert_eq!(options.set("fo=mtime").unwrap(), "fileorder=mtime");
+        assert_eq!(options.file_order, Some(FileOrder::Modified));
+        assert!(options.set("fileorder=random").is_err());
+        assert_eq!(options.set("fileorder=none").unwrap(), "fileorder=none");
+        assert_eq!(options.file_order, None);
+        let options: Options = toml::from_str("file_order = \"size\"").unwrap();
+        assert_eq!(options.file_order, Some(FileOrder::Size));
+    }
+}
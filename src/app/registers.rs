@@ -0,0 +1,67 @@
+//! Named row registers: a `"a` prefix makes `yy`, `dd` and `p` use
+//! register `a` instead of the unnamed one, so several snippets can be held
+//! at once, like vim. An uppercase name (`"A`) adds to the register instead
+//! of replacing it.
+
+use std::collections::BTreeMap;
+
+/// Rows held by a register, top to bottom
+pub type RegisterRows = Vec<Vec<String>>;
+
+/// Registers `a` to `z`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Registers {
+    named: BTreeMap<char, RegisterRows>,
+}
+
+impl Registers {
+    /// Check whether a character names a register
+    pub fn is_name(name: char) -> bool {
+        name.is_ascii_alphabetic()
+    }
+
+    /// Put rows in a register (`A`-`Z` add them to `a`-`z`), returning
+    /// everything it holds afterwards
+    pub fn store(&mut self, name: char, rows: RegisterRows) -> &RegisterRows {
+        let register = self.named.entry(name.to_ascii_lowercase()).or_default();
+        if !name.is_ascii_uppercase() {
+            register.clear();
+        }
+        register.extend(rows);
+        register
+    }
+
+    /// Rows held by a register
+    pub fn get(&self, name: char) -> Option<&RegisterRows> {
+        self.named.get(&name.to_ascii_lowercase())
+    }
+
+    /// Registers holding rows, by name
+    pub fn iter(&self) -> impl Iterator<Item = (char, &RegisterRows)> {
+        self.named.iter().map(|(&name, rows)| (name, rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(values: &[&str]) -> RegisterRows {
+        values.iter().map(|v| vec![v.to_string()]).collect()
+    }
+
+    #[test]
+    fn test_store_replaces_and_uppercase_appends() {
+        let mut registers = Registers::default();
+        registers.store('a', rows(&["1", "2"]));
+        registers.store('b', rows(&["x"]));
+        assert_eq!(registers.store('A', rows(&["3"])), &rows(&["1", "2", "3"]));
+        assert_eq!(registers.get('A'), Some(&rows(&["1", "2", "3"])));
+
+        registers.store('a', rows(&["4"]));
+        assert_eq!(registers.get('a'), Some(&rows(&["4"])));
+        assert_eq!(registers.get('c'), None);
+        let names: Vec<char> = registers.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!['a', 'b']);
+    }
+}
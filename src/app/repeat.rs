@@ -0,0 +1,83 @@
+//! `.`: make the last change again at the cursor, like vim.
+
+use super::fill::FillDown;
+use super::RegisterRows;
+
+/// The last change, as `.` makes it again
+#[derive(Debug, Clone, PartialEq)]
+pub enum LastChange {
+    /// An Insert mode edit or a cleared cell
+    Edit(CellEdit),
+    /// Add a row below (`o`) or above (`O`), with the text typed into it
+    AddRow { below: bool, text: Option<String> },
+    /// Delete this many rows (`dd`, `3dd`, `d` in Visual mode)
+    DeleteRows(usize),
+    /// Paste these rows below (`p`) or above (`P`)
+    PasteRows { rows: RegisterRows, below: bool },
+    /// Paste these rows over the rows from the cursor (`gp`)
+    PasteOver(RegisterRows),
+    /// `:fill <spec>` again; after a Visual selection, on as many rows
+    /// from the cursor
+    Fill { spec: String, rows: Option<usize> },
+    /// `f`/`F` in Visual mode, on as many rows and columns from the cursor
+    FillSelection {
+        fill: FillDown,
+        rows: usize,
+        columns: usize,
+    },
+}
+
+/// What an Insert mode edit did to a cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellEdit {
+    /// Replaced the value
+    Set(String),
+    /// Typed text after the value
+    Append(String),
+    /// Typed text before the value
+    Prepend(String),
+}
+
+impl CellEdit {
+    /// The edit that turned `original` into `content`
+    pub fn between(original: &str, content: &str) -> Self {
+        if !original.is_empty() {
+            if let Some(added) = content.strip_prefix(original) {
+                return Self::Append(added.to_string());
+            }
+            if let Some(added) = content.strip_suffix(original) {
+                return Self::Prepend(added.to_string());
+            }
+        }
+        Self::Set(content.to_string())
+    }
+
+    /// `value` with the edit made to it
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            Self::Set(text) => text.clone(),
+            Self::Append(text) => format!("{}{}", value, text),
+            Self::Prepend(text) => format!("{}{}", text, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_edit_between() {
+        let append = CellEdit::between("abc", "abc!");
+        assert_eq!(append, CellEdit::Append("!".to_string()));
+        assert_eq!(append.apply("x"), "x!");
+        let prepend = CellEdit::between("abc", "> abc");
+        assert_eq!(prepend.apply("x"), "> x");
+        assert_eq!(CellEdit::between("abc", "abd").apply("x"), "abd");
+        // Typing into an empty cell sets the value
+        assert_eq!(
+            CellEdit::between("", "new"),
+            CellEdit::Set("new".to_string())
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! `:replaceall /pattern/replacement/`: find and replace across every file
+//! of the session.
+//!
+//! The replacements are counted per file and only written once confirmed.
+//! Each file's replacement is one undoable change: right away in the open
+//! file, and in the others once they are shown.
+
+use super::search::SearchPattern;
+use super::{App, Change, Edit};
+use crate::csv::Document;
+use crate::domain::position::{ColIndex, RowIndex};
+use crate::ui::MarkKind;
+
+/// Split `/pattern/replacement/` into its parts. Any punctuation can stand
+/// in for `/` (`:replaceall #a/b#c#`); `\/` is a literal `/`, and the last
+/// delimiter may be left off.
+pub fn parse_replacement(arg: &str) -> Result<(String, String), String> {
+    let usage = "Usage: :replaceall /pattern/replacement/";
+    let mut chars = arg.trim().chars();
+    let delimiter = chars
+        .next()
+        .filter(|c| c.is_ascii_punctuation() && *c != '\\')
+        .ok_or(usage)?;
+    let mut parts = vec![String::new()];
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().unwrap();
+        match c {
+            '\\' => match chars.next() {
+                Some(next) if next == delimiter => part.push(next),
+                Some(next) => {
+                    part.push('\\');
+                    part.push(next);
+                }
+                None => part.push('\\'),
+            },
+            c if c == delimiter => parts.push(String::new()),
+            c => part.push(c),
+        }
+    }
+    if parts.last().is_some_and(String::is_empty) && parts.len() == 3 {
+        parts.pop();
+    }
+    match <[String; 2]>::try_from(parts) {
+        Ok([pattern, replacement]) if !pattern.is_empty() => Ok((pattern, replacement)),
+        _ => Err(usage.to_string()),
+    }
+}
+
+/// The cell edits replacing the matches of `pattern` in a document
+pub fn replacement_change(
+    document: &Document,
+    pattern: &SearchPattern,
+    replacement: &str,
+) -> Change {
+    let mut edits = Vec::new();
+    for (row, cells) in document.rows.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            let new = pattern.replace_all(cell, replacement);
+            if new != cell.as_str() {
+                edits.push(Edit::Cell {
+                    row,
+                    col,
+                    old: cell.clone(),
+                    new: new.into_owned(),
+                });
+            }
+        }
+    }
+    Change {
+        edits,
+        label: format!("replaceall {}", pattern.query),
+    }
+}
+
+impl App {
+    /// The replacements `:replaceall` would make in each file with any,
+    /// and the files that could not be read
+    pub fn plan_replace_all(
+        &self,
+        pattern: &SearchPattern,
+        replacement: &str,
+    ) -> (Vec<(usize, Change)>, Vec<String>) {
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        for (file, path) in self.session.files().iter().enumerate() {
+            let change = if file == self.session.active_file_index() {
+                replacement_change(&self.document, pattern, replacement)
+            } else if let Some(split) = self.split.as_ref().filter(|split| split.pane.file == file)
+            {
+                replacement_change(&split.pane.document, pattern, replacement)
+            } else {
+                match self.session.config_for(path).read_document(path) {
+                    Ok(document) => replacement_change(&document, pattern, replacement),
+                    Err(err) => {
+                        errors.push(format!("{}: {:#}", path.display(), err));
+                        continue;
+                    }
+                }
+            };
+            if !change.edits.is_empty() {
+                changes.push((file, change));
+            }
+        }
+        (changes, errors)
+    }
+
+    /// Make a replacement in the open document, undoably, and write it
+    fn write_replacement(
+        &mut self,
+        path: &std::path::Path,
+        change: Change,
+        write_headers: bool,
+    ) -> anyhow::Result<()> {
+        self.commit_change(change);
+        let delimiter = self.document.write_to(path, write_headers)?;
+        self.document.delimiter = delimiter;
+        self.document.is_dirty = false;
+        self.view_state.marks.clear_kind(MarkKind::Changed);
+        self.session.mark_file_loaded();
+        Ok(())
+    }
+
+    /// Make and write the planned replacements; returns the message to
+    /// show
+    pub fn replace_all(&mut self, changes: Vec<(usize, Change)>) -> String {
+        let mut written = 0;
+        let mut cells = 0;
+        let mut errors = Vec::new();
+        for (file, change) in changes {
+            let Some(path) = self.session.files().get(file).cloned() else {
+                continue;
+            };
+            let config = self.session.config_for(&path).clone();
+            let count = change.edits.len();
+            let in_pane = self.pane_file() == Some(file);
+            let result = if in_pane || file == self.session.active_file_index() {
+                if in_pane {
+                    self.switch_pane();
+                }
+                let result = self.write_replacement(&path, change, !config.no_headers);
+                if in_pane {
+                    self.switch_pane();
+                }
+                result
+            } else {
+                config
+                    .read_document(&path)
+                    .and_then(|mut document| {
+                        for edit in &change.edits {
+                            if let Edit::Cell { row, col, new, .. } = edit {
+                                document.set_cell(
+                                    RowIndex::new(*row),
+                                    ColIndex::new(*col),
+                                    new.clone(),
+                                );
+                            }
+                        }
+                        document.write_to(&path, !config.no_headers)
+                    })
+                    .map(|_| self.session.set_pending_change(&path, change))
+            };
+            match result {
+                Ok(()) => {
+                    written += 1;
+                    cells += count;
+                }
+                Err(err) => errors.push(format!("{}: {:#}", path.display(), err)),
+            }
+        }
+        let mut message = format!(
+            "Replaced {} cell{} in {} file{} (u undoes a file's replacements while it is open)",
+            cells,
+            if cells == 1 { "" } else { "s" },
+            written,
+            if written == 1 { "" } else { "s" }
+        );
+        if !errors.is_empty() {
+            message = format!("{}; failed: {}", message, errors.join("; "));
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_replacement() {
+        let parse = |arg: &str| parse_replacement(arg).ok();
+        let pair = |a: &str, b: &str| Some((a.to_string(), b.to_string()));
+        assert_eq!(parse("/oslo/Oslo/"), pair("oslo", "Oslo"));
+        assert_eq!(parse("/oslo/Oslo"), pair("oslo", "Oslo"));
+        assert_eq!(parse("/ +$//"), pair(" +$", ""));
+        assert_eq!(parse("#a/b#c#"), pair("a/b", "c"));
+        assert_eq!(parse(r"/a\/b/\d/"), pair("a/b", r"\d"));
+        assert_eq!(parse("/oslo/"), pair("oslo", ""));
+        assert_eq!(parse("oslo"), None);
+        assert_eq!(parse("//x/"), None);
+        assert_eq!(parse("/a/b/c/"), None);
+    }
+
+    #[test]
+    fn test_replacement_change_uses_groups() {
+        let document = Document {
+            headers: vec!["date".to_string()],
+            rows: vec![vec!["2024-03-01".to_string()], vec!["n/a".to_string()]],
+            ..Default::default()
+        };
+        let pattern = SearchPattern::new(r"(\d+)-(\d+)-(\d+)", &Default::default()).unwrap();
+        let change = replacement_change(&document, &pattern, "$3.$2.$1");
+        assert_eq!(
+            change.edits,
+            vec![Edit::Cell {
+                row: 0,
+                col: 0,
+                old: "2024-03-01".to_string(),
+                new: "01.03.2024".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Where each row is in the file on disk, so a sorted document can still
+//! be saved in the file's order and `:wdiff` can tell edited rows from
+//! added ones.
+//!
+//! Every row read from the file carries its position there; inserted rows
+//! carry none, and in the file's order they follow the row above them.
+
+/// Position in the file of each document row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowOrder {
+    ids: Vec<Option<usize>>,
+}
+
+impl RowOrder {
+    /// Rows in the order they are in the file
+    pub fn new(rows: usize) -> Self {
+        Self {
+            ids: (0..rows).map(Some).collect(),
+        }
+    }
+
+    /// Rows as saved in `order` (document rows in file order)
+    pub fn saved(order: &[usize]) -> Self {
+        let mut ids = vec![None; order.len()];
+        for (position, &row) in order.iter().enumerate() {
+            ids[row] = Some(position);
+        }
+        Self { ids }
+    }
+
+    /// A row was inserted at `at`
+    pub fn row_inserted(&mut self, at: usize) {
+        if at <= self.ids.len() {
+            self.ids.insert(at, None);
+        }
+    }
+
+    /// The row at `at` was deleted
+    pub fn row_deleted(&mut self, at: usize) {
+        if at < self.ids.len() {
+            self.ids.remove(at);
+        }
+    }
+
+    /// Rows from `start` were reordered so that row `start + i` is the one
+    /// that was at `start + order[i]`
+    pub fn rows_reordered(&mut self, start: usize, order: &[usize]) {
+        let end = start + order.len();
+        if end > self.ids.len() {
+            return;
+        }
+        let old = self.ids[start..end].to_vec();
+        for (i, &from) in order.iter().enumerate() {
+            self.ids[start + i] = old[from];
+        }
+    }
+
+    /// The header line is now read as the first row (`:set noheaders`)
+    pub fn header_became_row(&mut self) {
+        for id in self.ids.iter_mut().flatten() {
+            *id += 1;
+        }
+        self.ids.insert(0, Some(0));
+    }
+
+    /// The row at `at` is now read as the header line (`:set headers`)
+    pub fn row_became_header(&mut self, at: usize) {
+        self.row_deleted(at);
+        for id in self.ids.iter_mut().flatten() {
+            *id = id.saturating_sub(1);
+        }
+    }
+
+    /// `count` rows were appended to the file
+    pub fn rows_appended(&mut self, count: usize) {
+        let next = self.ids.iter().flatten().max().map_or(0, |&max| max + 1);
+        self.ids.extend((next..next + count).map(Some));
+    }
+
+    /// Check whether the rows were tracked since the file was read (rows
+    /// replaced wholesale aren't)
+    pub fn tracks(&self, rows: usize) -> bool {
+        self.ids.len() == rows
+    }
+
+    /// Position in the file of a row (None for inserted rows)
+    pub fn file_row(&self, row: usize) -> Option<usize> {
+        self.ids.get(row).copied().flatten()
+    }
+
+    /// Check whether rows are out of the order they have in the file
+    pub fn is_reordered(&self) -> bool {
+        let mut ids = self.ids.iter().flatten();
+        let Some(mut last) = ids.next() else {
+            return false;
+        };
+        ids.any(|id| {
+            let out_of_order = id < last;
+            last = id;
+            out_of_order
+        })
+    }
+
+    /// Current rows in the file's order (None if the rows changed without
+    /// being tracked)
+    pub fn original(&self, rows: usize) -> Option<Vec<usize>> {
+        if !self.tracks(rows) {
+            return None;
+        }
+        // Inserted rows sort with the row above them
+        let mut above = 0;
+        let keys: Vec<usize> = self
+            .ids
+            .iter()
+            .map(|id| {
+                above = id.unwrap_or(above);
+                above
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..rows).collect();
+        order.sort_by_key(|&row| keys[row]);
+        Some(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorder_and_restore() {
+        let mut order = RowOrder::new(4);
+        assert!(!order.is_reordered());
+        // Reverse all rows, like a descending sort
+        order.rows_reordered(0, &[3, 2, 1, 0]);
+        assert!(order.is_reordered());
+        assert_eq!(order.original(4), Some(vec![3, 2, 1, 0]));
+        order.rows_reordered(0, &[3, 2, 1, 0]);
+        assert!(!order.is_reordered());
+        assert_eq!(order.original(5), None);
+    }
+
+    #[test]
+    fn test_inserted_rows_follow_the_row_above() {
+        let mut order = RowOrder::new(3);
+        order.rows_reordered(0, &[2, 1, 0]);
+        // Below the first row, which is last in the file
+        order.row_inserted(1);
+        order.row_deleted(2);
+        order.rows_appended(1);
+        assert_eq!(order.file_row(1), None);
+        assert_eq!(order.file_row(3), Some(3));
+        let original = order.original(4).unwrap();
+        assert_eq!(original, vec![2, 0, 1, 3]);
+
+        // Once saved that way, rows are where that order put them
+        let saved = RowOrder::saved(&original);
+        assert_eq!(saved.file_row(0), Some(1));
+        assert_eq!(saved.file_row(1), Some(2));
+        assert!(saved.is_reordered());
+    }
+}
@@ -0,0 +1,207 @@
+//! `:wdiff`: what saving would change in the file on disk, before it is
+//! overwritten.
+
+use super::RowOrder;
+use crate::analysis::lint::{self, LintRule};
+use crate::csv::Document;
+
+/// Changes a save would make to the file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SavePreview {
+    /// Cells of rows read from the file that now hold other values
+    pub cells_modified: usize,
+    pub rows_added: usize,
+    pub rows_deleted: usize,
+    /// Rows are written in another order than the file has
+    pub reordered: bool,
+    /// The header line changes
+    pub headers_changed: bool,
+    /// Changes to how the file is written (delimiter, encoding, line
+    /// endings, byte order mark, quoting)
+    pub format: Vec<String>,
+}
+
+impl SavePreview {
+    /// Compare the document with the file as it is on disk: `on_disk`
+    /// parsed, `bytes` raw (decompressed), and `encoding` the one it was
+    /// read with
+    pub fn compute(
+        document: &Document,
+        row_order: &RowOrder,
+        on_disk: &Document,
+        bytes: &[u8],
+        encoding: Option<&str>,
+    ) -> Self {
+        // Rows whose edits weren't tracked are compared by position
+        let tracked = row_order.tracks(document.row_count());
+        let file_row = |row: usize| {
+            if tracked {
+                row_order.file_row(row)
+            } else {
+                Some(row)
+            }
+        };
+
+        let mut preview = Self {
+            headers_changed: document.headers != on_disk.headers,
+            reordered: tracked && row_order.is_reordered(),
+            ..Default::default()
+        };
+        let mut kept = vec![false; on_disk.row_count()];
+        for (row, cells) in document.rows.iter().enumerate() {
+            let Some(old_row) = file_row(row).filter(|&r| r < on_disk.row_count()) else {
+                preview.rows_added += 1;
+                continue;
+            };
+            kept[old_row] = true;
+            let old = &on_disk.rows[old_row];
+            preview.cells_modified += (0..cells.len().max(old.len()))
+                .filter(|&col| cell(cells, col) != cell(old, col))
+                .count();
+        }
+        preview.rows_deleted = kept.iter().filter(|&&kept| !kept).count();
+
+        if document.delimiter != on_disk.delimiter {
+            preview.format.push(format!(
+                "delimiter {} → {}",
+                delimiter_name(on_disk.delimiter),
+                delimiter_name(document.delimiter)
+            ));
+        }
+        if let Some(encoding) = encoding.filter(|e| !e.eq_ignore_ascii_case("utf-8")) {
+            preview
+                .format
+                .push(format!("encoding {} → UTF-8", encoding));
+        }
+        let report = lint::lint(bytes, on_disk.delimiter);
+        // Saving keeps the line endings of the first line
+        let endings = report.line_endings;
+        let (kept, to) = if document.write_style.crlf {
+            (endings.crlf, "CRLF")
+        } else {
+            (endings.lf, "LF")
+        };
+        if endings.crlf + endings.lf + endings.cr > kept {
+            let from = match (endings.crlf, endings.lf, endings.cr) {
+                (_, 0, 0) => "CRLF",
+                (0, _, 0) => "LF",
+                (0, 0, _) => "CR",
+                _ => "mixed",
+            };
+            preview
+                .format
+                .push(format!("line endings {} → {}", from, to));
+        }
+        if report.count(LintRule::Bom) > 0 {
+            preview.format.push("BOM removed".to_string());
+        }
+        if report.count(LintRule::Quoting) > 0 {
+            preview.format.push("quoting fixed".to_string());
+        }
+        preview
+    }
+
+    /// Check whether saving would leave the file as it is
+    pub fn is_empty(&self) -> bool {
+        self.cells_modified == 0
+            && self.rows_added == 0
+            && self.rows_deleted == 0
+            && !self.reordered
+            && !self.headers_changed
+            && self.format.is_empty()
+    }
+
+    /// Comma-separated list of the changes
+    pub fn summary(&self) -> String {
+        let count = |n: usize, what: &str, done: &str| match n {
+            0 => None,
+            1 => Some(format!("1 {} {}", what, done)),
+            n => Some(format!("{} {}s {}", n, what, done)),
+        };
+        let parts: Vec<String> = [
+            count(self.cells_modified, "cell", "modified"),
+            count(self.rows_added, "row", "added"),
+            count(self.rows_deleted, "row", "deleted"),
+            self.reordered.then(|| "rows reordered".to_string()),
+            self.headers_changed.then(|| "header changed".to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.format.iter().cloned())
+        .collect();
+        parts.join(", ")
+    }
+}
+
+fn cell(row: &[String], col: usize) -> &str {
+    row.get(col).map_or("", String::as_str)
+}
+
+/// Readable name of a delimiter
+fn delimiter_name(delimiter: u8) -> String {
+    match delimiter {
+        b'\t' => "tab".to_string(),
+        b' ' => "space".to_string(),
+        d => format!("'{}'", d as char),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(rows: &[&[&str]]) -> Document {
+        Document {
+            headers: vec!["id".to_string(), "name".to_string()],
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edits_inserts_and_deletes() {
+        let on_disk = document(&[&["1", "a"], &["2", "b"], &["3", "c"]]);
+        let mut order = RowOrder::new(3);
+        // Delete row 2, insert a row at the top, edit row 3
+        order.row_deleted(1);
+        order.row_inserted(0);
+        let current = document(&[&["0", "z"], &["1", "a"], &["3", "C"]]);
+
+        let preview = SavePreview::compute(&current, &order, &on_disk, b"id,name\n", None);
+        assert_eq!(preview.cells_modified, 1);
+        assert_eq!(preview.rows_added, 1);
+        assert_eq!(preview.rows_deleted, 1);
+        assert!(!preview.reordered);
+        assert_eq!(
+            preview.summary(),
+            "1 cell modified, 1 row added, 1 row deleted"
+        );
+    }
+
+    #[test]
+    fn test_format_changes() {
+        let on_disk = document(&[&["1", "a"]]);
+        let mut current = document(&[&["1", "a"]]);
+        let order = RowOrder::new(1);
+        let bytes = b"\xEF\xBB\xBFid,name\r\n1,a\n";
+        assert!(
+            SavePreview::compute(&current, &order, &on_disk, b"id,name\n1,a\n", None).is_empty()
+        );
+
+        current.delimiter = b'\t';
+        let preview = SavePreview::compute(&current, &order, &on_disk, bytes, Some("latin1"));
+        assert_eq!(
+            preview.summary(),
+            "delimiter ',' → tab, encoding latin1 → UTF-8, line endings mixed → LF, BOM removed"
+        );
+
+        // A CRLF file stays CRLF
+        current.delimiter = b',';
+        current.write_style.crlf = true;
+        let bytes = b"id,name\r\n1,a\r\n";
+        assert!(SavePreview::compute(&current, &order, &on_disk, bytes, None).is_empty());
+    }
+}
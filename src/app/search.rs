@@ -0,0 +1,181 @@
+//! Search patterns, and `:csearch <text>` (`\`): find text in the selected
+//! column only, for wide files where a search of every column hits the
+//! wrong ones.
+//!
+//! Queries are regular expressions. Like vim with `ignorecase` and
+//! `smartcase`, they ignore case unless they have capitals; `\c` in a
+//! query ignores case and `\C` matches it regardless of the options.
+
+use super::Options;
+use crate::csv::Document;
+use crate::domain::position::ColIndex;
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// A compiled search query
+#[derive(Debug, Clone)]
+pub struct SearchPattern {
+    /// The query as typed
+    pub query: String,
+    regex: Regex,
+    ignore_case: bool,
+}
+
+impl PartialEq for SearchPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.query == other.query && self.ignore_case == other.ignore_case
+    }
+}
+
+impl SearchPattern {
+    /// Compile `query`, ignoring case as the options and its `\c`/`\C`
+    /// say; an invalid regular expression is an error for the status bar
+    pub fn new(query: &str, options: &Options) -> Result<Self, String> {
+        let mut pattern = String::new();
+        let mut explicit = None;
+        let mut capitals = false;
+        let mut chars = query.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                capitals |= c.is_uppercase();
+                pattern.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('c') => explicit = Some(true),
+                Some('C') => explicit = Some(false),
+                // Escapes like \S are not capitals
+                Some(next) => {
+                    pattern.push('\\');
+                    pattern.push(next);
+                }
+                None => pattern.push('\\'),
+            }
+        }
+        let ignore_case =
+            explicit.unwrap_or(options.ignore_case && !(options.smart_case && capitals));
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| {
+                // Syntax errors end with a line like "error: unclosed group"
+                let err = err.to_string();
+                let reason = err.lines().last().unwrap_or_default();
+                format!(
+                    "Invalid pattern \"{}\": {}",
+                    query,
+                    reason.trim_start_matches("error: ")
+                )
+            })?;
+        Ok(Self {
+            query: query.to_string(),
+            regex,
+            ignore_case,
+        })
+    }
+
+    /// Check whether the pattern matches anywhere in `text`
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// `text` with every match replaced; `$1` or `${name}` in
+    /// `replacement` stand for what a group matched
+    pub fn replace_all<'a>(&self, text: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.regex.replace_all(text, replacement)
+    }
+
+    /// Byte ranges of the non-empty matches in `text`
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.regex
+            .find_iter(text)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+    }
+}
+
+/// Document rows whose cell in `column` matches `pattern`
+pub fn find_in_column(
+    document: &Document,
+    column: ColIndex,
+    pattern: &SearchPattern,
+) -> Vec<usize> {
+    document
+        .rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            row.get(column.get())
+                .is_some_and(|cell| pattern.is_match(cell))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(query: &str) -> SearchPattern {
+        SearchPattern::new(query, &Options::default()).unwrap()
+    }
+
+    #[test]
+    fn test_find_in_column_only() {
+        let document = Document {
+            headers: vec!["name".to_string(), "city".to_string()],
+            rows: [("Oslo Bakery", "Rome"), ("Pizzeria", "Oslo"), ("x", "OSLO")]
+                .iter()
+                .map(|(n, c)| vec![n.to_string(), c.to_string()])
+                .collect(),
+            ..Default::default()
+        };
+        let oslo = pattern("oslo");
+        assert_eq!(
+            find_in_column(&document, ColIndex::new(1), &oslo),
+            vec![1, 2]
+        );
+        assert_eq!(find_in_column(&document, ColIndex::new(0), &oslo), vec![0]);
+        assert!(find_in_column(&document, ColIndex::new(5), &oslo).is_empty());
+        assert_eq!(
+            find_in_column(&document, ColIndex::new(1), &pattern("^O.*o$")),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_smart_case_and_case_escapes() {
+        assert!(pattern("oslo").is_match("OSLO"));
+        assert!(!pattern("Oslo").is_match("OSLO"));
+        assert!(pattern("Oslo\\c").is_match("OSLO"));
+        assert!(!pattern("\\Coslo").is_match("Oslo"));
+        // Escapes aren't capitals
+        assert!(pattern("\\Sslo").is_match("OSLO"));
+
+        let mut options = Options {
+            smart_case: false,
+            ..Options::default()
+        };
+        assert!(SearchPattern::new("Oslo", &options)
+            .unwrap()
+            .is_match("OSLO"));
+        options.ignore_case = false;
+        assert!(!SearchPattern::new("oslo", &options)
+            .unwrap()
+            .is_match("Oslo"));
+        assert!(SearchPattern::new("oslo\\c", &options)
+            .unwrap()
+            .is_match("Oslo"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        assert_eq!(
+            SearchPattern::new("(oslo", &Options::default()).unwrap_err(),
+            "Invalid pattern \"(oslo\": unclosed group"
+        );
+        let ranges: Vec<_> = pattern("o*").find_iter("xoox").collect();
+        assert_eq!(ranges, vec![1..3]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Saving and restoring how files are set up (`:session save`,
+//! `lazycsv --session`): each file's filter, sorts, frozen and derived
+//! columns and number formats become the `:` commands that recreate them.
+
+use super::App;
+use crate::csv::Document;
+use crate::domain::position::{ColIndex, RowIndex};
+use crate::session::{FileSetup, SessionSnapshot};
+use crate::ui::{column_to_excel_letter, MarkKind, ViewState};
+use std::path::Path;
+
+/// How a command names a column: its header, or its letter when the
+/// header is empty or would not read back as one name
+fn column_name(document: &Document, col: usize) -> String {
+    match document.headers.get(col) {
+        Some(header)
+            if !header.trim().is_empty()
+                && header.trim() == header
+                && !header.contains(['=', '~', '`', ',']) =>
+        {
+            header.clone()
+        }
+        _ => column_to_excel_letter(col).to_string(),
+    }
+}
+
+/// The setup of a file shown with `view_state`
+pub fn file_setup(path: &Path, document: &Document, view_state: &ViewState) -> FileSetup {
+    let mut commands = Vec::new();
+    for sort in &view_state.sorts {
+        commands.push(format!(
+            "sort {}{}",
+            column_name(document, sort.column.get()),
+            if sort.descending { " desc" } else { "" }
+        ));
+    }
+    for derived in view_state.derived_columns.iter() {
+        commands.push(format!(
+            "derive `{}` = {}",
+            document
+                .headers
+                .get(derived.column)
+                .map_or("", String::as_str),
+            derived.text
+        ));
+    }
+    for (&col, format) in &view_state.number_formats {
+        commands.push(format!("fmt {} {}", column_name(document, col), format));
+    }
+    // Before freezing, which counts columns in display order
+    if view_state.is_reordered() {
+        let columns: Vec<String> = view_state
+            .display_columns(document.column_count())
+            .into_iter()
+            .map(|col| column_name(document, col))
+            .collect();
+        commands.push(format!("colorder {}", columns.join(",")));
+    }
+    if view_state.frozen_columns > 0 {
+        commands.push(format!("freeze {}", view_state.frozen_columns));
+    }
+    if view_state.frozen_last {
+        commands.push("freeze last".to_string());
+    }
+    if let Some(filter) = &view_state.row_filter {
+        commands.push(format!(
+            "filter {}{}\"{}\"",
+            column_name(document, filter.column.get()),
+            filter.operator(),
+            filter.value
+        ));
+    }
+
+    let row = view_state
+        .table_state
+        .selected()
+        .and_then(|display| match &view_state.row_filter {
+            Some(filter) => filter.document_row(display),
+            None => Some(display),
+        })
+        .unwrap_or(0);
+    FileSetup {
+        path: path.to_path_buf(),
+        row,
+        column: view_state.selected_column.get(),
+        commands,
+        widths: view_state
+            .column_widths
+            .iter()
+            .map(|(&col, &width)| (col, width))
+            .collect(),
+        delimiter: None,
+    }
+}
+
+impl App {
+    /// Everything `:session save` writes: the files, how they are parsed
+    /// and how each one is set up. Files that were left are read again to
+    /// name their columns.
+    pub fn session_snapshot(&self) -> SessionSnapshot {
+        let mut files: Vec<FileSetup> = self
+            .session
+            .files()
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                if i == self.session.active_file_index() {
+                    let mut setup = file_setup(path, &self.document, &self.view_state);
+                    if let Some(row) = self.get_selected_row() {
+                        setup.row = row.get();
+                    }
+                    return setup;
+                }
+                if let Some(split) = self.split.as_ref().filter(|split| split.pane.file == i) {
+                    return file_setup(path, &split.pane.document, &split.pane.view_state);
+                }
+                if let Some(setup) = self.session.setup(path) {
+                    return setup.clone();
+                }
+                let document = self.session.view_state(path).and_then(|view_state| {
+                    self.session
+                        .config_for(path)
+                        .read_document(path)
+                        .ok()
+                        .map(|document| (document, view_state))
+                });
+                match document {
+                    Some((document, view_state)) => file_setup(path, &document, view_state),
+                    None => FileSetup {
+                        path: path.clone(),
+                        ..Default::default()
+                    },
+                }
+            })
+            .collect();
+        // Files given their own delimiter are read with it again
+        for setup in &mut files {
+            setup.delimiter = self
+                .session
+                .file_config(&setup.path)
+                .and_then(|config| config.delimiter)
+                .map(char::from);
+        }
+        let mut snapshot = SessionSnapshot {
+            current: self.session.active_file_index(),
+            files,
+            ..Default::default()
+        };
+        snapshot.set_config(self.session.default_config());
+        snapshot
+    }
+
+    /// Set the files up as a saved session says: the current file now,
+    /// the others when they are first shown
+    pub fn restore_session(&mut self, snapshot: SessionSnapshot) {
+        for setup in snapshot.files {
+            self.session.set_setup(setup);
+        }
+        self.apply_file_setup();
+    }
+
+    /// Run the saved-session setup of the current file, if it has one
+    pub fn apply_file_setup(&mut self) {
+        let Some(setup) = self.session.take_setup() else {
+            return;
+        };
+        self.run_startup_commands(&setup.commands);
+        self.view_state.column_widths.extend(setup.widths);
+        self.view_state.selected_column = ColIndex::new(
+            setup
+                .column
+                .min(self.document.column_count().saturating_sub(1)),
+        );
+        if !self.select_document_row(RowIndex::new(setup.row)) {
+            self.view_state.table_state.select(Some(0));
+        }
+        // Sorting and deriving are edits, but not ones to save or undo
+        self.history.clear();
+        self.document.is_dirty = false;
+        self.view_state.marks.clear_kind(MarkKind::Changed);
+    }
+}
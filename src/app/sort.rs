@@ -0,0 +1,236 @@
+//! `:sort [column] [asc|desc]`: reorder rows by a column, either the whole
+//! document or a `'<,'>` Visual selection.
+
+use super::undo::{Change, Edit};
+use crate::csv::{ColumnType, Document, Locale};
+use crate::domain::position::ColIndex;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Parsed `:sort` arguments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortSpec {
+    pub column: ColIndex,
+    pub descending: bool,
+}
+
+impl SortSpec {
+    /// Parse `[column] [asc|desc]`; the column is a header name or letter
+    /// and defaults to `current`
+    pub fn parse(args: &str, document: &Document, current: ColIndex) -> Result<Self, String> {
+        let mut words: Vec<&str> = args.split_whitespace().collect();
+        let descending = match words.last().map(|w| w.to_lowercase()) {
+            Some(w) if w == "desc" => true,
+            Some(w) if w == "asc" => false,
+            _ => {
+                words.push("asc");
+                false
+            }
+        };
+        words.pop();
+
+        let name = words.join(" ");
+        let column = if name.is_empty() {
+            current
+        } else {
+            document
+                .find_column(&name)
+                .or_else(|| {
+                    crate::ui::utils::excel_letter_to_column(&name.to_uppercase())
+                        .ok()
+                        .filter(|&col| col < document.column_count())
+                        .map(ColIndex::new)
+                })
+                .ok_or_else(|| format!("No column named {}", name))?
+        };
+        Ok(Self { column, descending })
+    }
+
+    /// Row order for `rows` (see `Edit::Reorder`), reading numbers and
+    /// dates as `locale` writes them; ties keep their order
+    pub fn order(&self, document: &Document, rows: Range<usize>, locale: &Locale) -> Vec<usize> {
+        let col = self.column.get();
+        let key = |i: usize| {
+            document.rows[rows.start + i]
+                .get(col)
+                .map_or("", |cell| cell.trim())
+        };
+        // Compare as the type the sorted values share (dates by date, yes/no
+        // as booleans); mixed columns fall back to numbers before text
+        let locale = &locale.for_column((0..rows.len()).map(key));
+        let kind = ColumnType::infer((0..rows.len()).map(key), locale);
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (key(a), key(b));
+            match (a.is_empty(), b.is_empty()) {
+                // Empty cells go last either way
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) if self.descending => kind.compare(b, a, locale),
+                (false, false) => kind.compare(a, b, locale),
+            }
+        });
+        order
+    }
+
+    /// Undoable change sorting `rows` (no edits if they are already in order)
+    pub fn change(&self, document: &Document, rows: Range<usize>, locale: &Locale) -> Change {
+        let start = rows.start;
+        let order = self.order(document, rows, locale);
+        let sorted = order.iter().enumerate().all(|(i, &from)| i == from);
+        let name = document
+            .headers
+            .get(self.column.get())
+            .cloned()
+            .unwrap_or_default();
+        Change {
+            edits: if sorted {
+                Vec::new()
+            } else {
+                vec![Edit::Reorder { start, order }]
+            },
+            label: format!("sort by {}", name),
+        }
+    }
+}
+
+/// Numbers by value and before text; text case-insensitively
+pub fn compare_values(a: &str, b: &str, locale: &Locale) -> Ordering {
+    match (locale.parse_number(a), locale.parse_number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.to_lowercase().cmp(&b.to_lowercase()).then(a.cmp(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["name".to_string(), "amount".to_string()],
+            rows: [
+                ("b", "10"),
+                ("a", "9"),
+                ("C", ""),
+                ("d", "n/a"),
+                ("e", "-1.5"),
+            ]
+            .iter()
+            .map(|(n, a)| vec![n.to_string(), a.to_string()])
+            .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn sorted(document: &Document, args: &str, rows: Range<usize>) -> Vec<String> {
+        let spec = SortSpec::parse(args, document, ColIndex::new(0)).unwrap();
+        let start = rows.start;
+        spec.order(document, rows, &Locale::default())
+            .into_iter()
+            .map(|i| document.rows[start + i][0].clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_numbers_text_and_empty() {
+        let document = document();
+        assert_eq!(
+            sorted(&document, "amount", 0..5),
+            vec!["e", "a", "b", "d", "C"]
+        );
+        assert_eq!(
+            sorted(&document, "amount desc", 0..5),
+            vec!["d", "b", "a", "e", "C"]
+        );
+        // Current column, case-insensitive text
+        assert_eq!(sorted(&document, "", 0..5), vec!["a", "b", "C", "d", "e"]);
+    }
+
+    #[test]
+    fn test_sort_dates_and_booleans_by_type() {
+        let document = Document {
+            headers: vec!["when".to_string(), "done".to_string()],
+            rows: [
+                ("2024/03/01", "yes"),
+                ("2024-01-15", "no"),
+                ("2023-12-31", "true"),
+            ]
+            .iter()
+            .map(|(w, d)| vec![w.to_string(), d.to_string()])
+            .collect(),
+            ..Default::default()
+        };
+        let sorted = |args| {
+            let spec = SortSpec::parse(args, &document, ColIndex::new(0)).unwrap();
+            spec.order(&document, 0..3, &Locale::default())
+        };
+        assert_eq!(sorted("when"), vec![2, 1, 0]);
+        assert_eq!(sorted("done"), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_sort_by_locale() {
+        let document = Document {
+            headers: vec!["amount".to_string(), "when".to_string()],
+            rows: [
+                ("1.234,5", "02/01/2024"),
+                ("99,5", "01/02/2024"),
+                ("-3", "15/01/2024"),
+            ]
+            .iter()
+            .map(|(a, w)| vec![a.to_string(), w.to_string()])
+            .collect(),
+            ..Default::default()
+        };
+        let german = Locale::parse("de_DE").unwrap();
+        let sorted = |args, locale: &Locale| {
+            let spec = SortSpec::parse(args, &document, ColIndex::new(0)).unwrap();
+            spec.order(&document, 0..3, locale)
+        };
+        assert_eq!(sorted("amount", &german), vec![2, 1, 0]);
+        // Day first: 2 January, 15 January, 1 February
+        assert_eq!(sorted("when", &german), vec![0, 2, 1]);
+        // The C locale reads them day first too, as 15/01 shows
+        assert_eq!(sorted("when", &Locale::default()), vec![0, 2, 1]);
+        // Ambiguous slashed dates are read month first, not as text
+        let document = Document {
+            headers: vec!["when".to_string()],
+            rows: [["10/1/2024"], ["2/1/2024"], ["1/15/2023"]]
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+            ..Default::default()
+        };
+        let spec = SortSpec::parse("when", &document, ColIndex::new(0)).unwrap();
+        assert_eq!(
+            spec.order(&document, 0..3, &Locale::default()),
+            vec![2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_sort_range_only() {
+        let document = document();
+        assert_eq!(sorted(&document, "B", 1..3), vec!["a", "C"]);
+        assert_eq!(sorted(&document, "B desc", 0..2), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_sort_spec_errors_and_no_op() {
+        let document = document();
+        assert_eq!(
+            SortSpec::parse("total desc", &document, ColIndex::new(0)),
+            Err("No column named total".to_string())
+        );
+        let spec = SortSpec::parse("name", &document, ColIndex::new(1)).unwrap();
+        assert!(spec.change(&document, 0..2, &Locale::default()).edits.len() == 1);
+        assert!(spec
+            .change(&document, 1..2, &Locale::default())
+            .edits
+            .is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Split view (`Ctrl+w s` / `Ctrl+w v`): a second file of the session shown
+//! above or beside the current one, to compare them. Each pane keeps its
+//! own document, cursor and undo history; `Ctrl+w w` moves the focus.
+//!
+//! The focused pane is the app's own document and view state. The other
+//! pane is parked in a `Pane` and swapped in to be drawn or focused.
+
+use super::{App, History, RowOrder};
+use crate::csv::{ColumnType, Document};
+use crate::session::FileTail;
+use crate::ui::ViewState;
+
+/// How the panes are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// One above the other (`Ctrl+w s`)
+    Horizontal,
+    /// Side by side (`Ctrl+w v`)
+    Vertical,
+}
+
+/// The pane without the focus
+#[derive(Debug, Default)]
+pub struct Pane {
+    /// Index of its file in the session
+    pub file: usize,
+    pub document: Document,
+    pub view_state: ViewState,
+    pub row_order: RowOrder,
+    pub column_types: Vec<ColumnType>,
+    pub history: History,
+}
+
+/// Two files shown at once
+#[derive(Debug)]
+pub struct Split {
+    pub direction: SplitDirection,
+    pub pane: Pane,
+    /// Whether the focused pane is the bottom or right one
+    pub focus_second: bool,
+}
+
+impl App {
+    /// Show the next file of the session in a second pane, keeping the
+    /// focus on the current one
+    pub fn open_split(&mut self, direction: SplitDirection) -> Result<(), String> {
+        if let Some(split) = self.split.as_mut() {
+            split.direction = direction;
+            return Ok(());
+        }
+        if !self.session.has_multiple_files() {
+            return Err("Splitting needs a second file in the session".to_string());
+        }
+        let file = self.session.active_file_index();
+        let next = (file + 1) % self.session.file_count();
+        self.split = Some(Split {
+            direction,
+            pane: Pane {
+                file,
+                history: std::mem::take(&mut self.history),
+                ..Default::default()
+            },
+            focus_second: true,
+        });
+        // The current file moves to the parked pane while the next one is
+        // read as usual, then the two trade places
+        self.swap_pane();
+        self.session.select_file(next);
+        if let Err(err) = self.reload_current_file() {
+            self.swap_pane();
+            self.session.select_file(file);
+            self.session.mark_file_loaded();
+            if let Some(split) = self.split.take() {
+                self.history = split.pane.history;
+            }
+            return Err(format!("{:#}", err));
+        }
+        self.switch_pane();
+        Ok(())
+    }
+
+    /// Index of the file in the pane without the focus
+    pub fn pane_file(&self) -> Option<usize> {
+        self.split.as_ref().map(|split| split.pane.file)
+    }
+
+    /// Exchange the focused pane's document and view state with the other
+    /// pane's, to draw it. Undo history and the session stay as they are.
+    pub fn swap_pane(&mut self) {
+        let Some(split) = self.split.as_mut() else {
+            return;
+        };
+        let pane = &mut split.pane;
+        std::mem::swap(&mut self.document, &mut pane.document);
+        std::mem::swap(&mut self.view_state, &mut pane.view_state);
+        std::mem::swap(&mut self.row_order, &mut pane.row_order);
+        std::mem::swap(&mut self.column_types, &mut pane.column_types);
+    }
+
+    /// Move the focus to the other pane
+    pub fn switch_pane(&mut self) {
+        self.swap_pane();
+        let Some(split) = self.split.as_mut() else {
+            return;
+        };
+        split.focus_second = !split.focus_second;
+        std::mem::swap(&mut self.history, &mut split.pane.history);
+        let file = std::mem::replace(&mut split.pane.file, self.session.active_file_index());
+        self.session.select_file(file);
+        self.session.mark_file_loaded();
+        self.diff = None;
+        if self.tail.is_some() {
+            self.tail = Some(FileTail::new(self.get_current_file()));
+        }
+    }
+
+    /// Close the pane without the focus; refused while it has unsaved
+    /// changes
+    pub fn close_other_pane(&mut self) -> Result<(), String> {
+        match &self.split {
+            None => Err("Only one pane".to_string()),
+            Some(split) if split.pane.document.is_dirty => Err(format!(
+                "{} has unsaved changes (Ctrl+w w to go there and :w)",
+                split.pane.document.filename
+            )),
+            Some(_) => {
+                self.split = None;
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the pane without the focus has unsaved changes
+    pub fn pane_is_dirty(&self) -> bool {
+        self.split
+            .as_ref()
+            .is_some_and(|split| split.pane.document.is_dirty)
+    }
+
+    /// Whether the session's file at `index` has unsaved changes (only the
+    /// open file and the other pane's can have any)
+    pub fn file_is_dirty(&self, index: usize) -> bool {
+        (index == self.session.active_file_index() && self.document.is_dirty)
+            || (self.pane_file() == Some(index) && self.pane_is_dirty())
+    }
+}
@@ -0,0 +1,239 @@
+//! Swap files: a file with unsaved changes is written every few seconds to
+//! a hidden file next to it (`.data.csv.lazycsv.swp`), so a crashed
+//! terminal or a dropped SSH connection doesn't lose the edits. Opening the
+//! file again offers to recover them.
+//!
+//! The swap file holds the document as `:w` would write it. It goes away
+//! once the file is saved or the edits are undone, and when lazycsv quits.
+
+use super::{App, RowOrder};
+use crate::input::StatusMessage;
+use crate::ui::MarkKind;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Time between writes of a swap file while editing
+pub const SWAP_INTERVAL: Duration = Duration::from_secs(4);
+
+/// The swap file of `file`: hidden, in the same directory
+pub fn swap_path(file: &Path) -> PathBuf {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    file.with_file_name(format!(".{}.lazycsv.swp", name))
+}
+
+/// Swap files this instance has written
+#[derive(Debug, Default)]
+pub struct SwapFiles {
+    /// Files with a swap file, and the undo history version it holds
+    written: BTreeMap<PathBuf, u64>,
+    last_write: Option<Instant>,
+    /// Writing failed for this file; tried again once it is saved
+    failed: Option<PathBuf>,
+}
+
+impl App {
+    /// Write the open file's swap file if its edits changed since the last
+    /// write, or remove it once there are none; returns true if the status
+    /// bar changed
+    pub fn update_swap_file(&mut self, now: Instant) -> bool {
+        if self.follower.is_some() {
+            return false;
+        }
+        let file = self.get_current_file().clone();
+        if !self.document.is_dirty {
+            if self.swap.failed.as_ref() == Some(&file) {
+                self.swap.failed = None;
+            }
+            if self.swap.written.remove(&file).is_some() {
+                let _ = std::fs::remove_file(swap_path(&file));
+            }
+            return false;
+        }
+        let version = self.history.version();
+        let recent = self
+            .swap
+            .last_write
+            .is_some_and(|at| now.duration_since(at) < SWAP_INTERVAL);
+        if recent
+            || self.swap.written.get(&file) == Some(&version)
+            || self.swap.failed.as_ref() == Some(&file)
+        {
+            return false;
+        }
+        self.swap.last_write = Some(now);
+        let swap = swap_path(&file);
+        match self
+            .document
+            .write_to(&swap, !self.session.config().no_headers)
+        {
+            Ok(_) => {
+                self.swap.written.insert(file, version);
+                false
+            }
+            Err(err) => {
+                self.swap.failed = Some(file);
+                self.status_messages.push(StatusMessage::warning(format!(
+                    "No swap file, edits are lost if lazycsv crashes: {:#}",
+                    err
+                )));
+                true
+            }
+        }
+    }
+
+    /// Remove the swap files written this session (on quitting)
+    pub fn remove_swap_files(&mut self) {
+        for file in std::mem::take(&mut self.swap.written).into_keys() {
+            let _ = std::fs::remove_file(swap_path(&file));
+        }
+    }
+
+    /// Ask whether to recover the edits in the open file's swap file, left
+    /// behind by a session that didn't quit; returns true if asked
+    pub fn offer_recovery(&mut self) -> bool {
+        let file = self.get_current_file().clone();
+        if self.follower.is_some()
+            || self.document.is_dirty
+            || self.swap.written.contains_key(&file)
+            || !swap_path(&file).is_file()
+        {
+            return false;
+        }
+        self.ask(
+            format!(
+                "Recover unsaved edits to {} from its swap file? (y/n, d deletes it)",
+                self.document.filename
+            ),
+            super::ConfirmAction::Recover,
+        );
+        true
+    }
+
+    /// Replace the open document with its swap file, as unsaved edits
+    pub fn recover_swap_file(&mut self) {
+        let file = self.get_current_file().clone();
+        let swap = swap_path(&file);
+        // Read as the file was, whatever the swap file's name suggests
+        let mut config = self.session.config().clone();
+        config.delimiter = Some(self.document.delimiter);
+        let recovered = match config.read_document(&swap) {
+            Ok(recovered) => recovered,
+            Err(err) => {
+                self.status_messages.push(StatusMessage::error(format!(
+                    "Could not recover {}: {:#}",
+                    swap.display(),
+                    err
+                )));
+                return;
+            }
+        };
+        let filename = std::mem::take(&mut self.document.filename);
+        self.document = recovered;
+        self.document.filename = filename;
+        self.document.is_dirty = true;
+        // The rows can't be matched to the file's any more, nor undone
+        self.history.clear();
+        self.row_order = RowOrder::default();
+        self.refresh_column_types();
+        self.view_state.row_filter = None;
+        self.view_state.marks.clear_kind(MarkKind::Changed);
+        self.view_state
+            .clamp_to(self.document.row_count(), self.document.column_count());
+        // Swap file is written again with the next edit
+        self.swap.written.insert(file, self.history.version());
+        self.status_messages.push(format!(
+            "Recovered {} rows from {} (:w keeps them, :e! drops them)",
+            self.document.row_count(),
+            swap.display()
+        ));
+    }
+
+    /// Delete the open file's swap file without recovering it
+    pub fn discard_swap_file(&mut self) {
+        let swap = swap_path(self.get_current_file());
+        let message = match std::fs::remove_file(&swap) {
+            Ok(()) => format!("Deleted {}", swap.display()),
+            Err(err) => format!("Could not delete {}: {}", swap.display(), err),
+        };
+        self.status_messages.push(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::Document;
+    use crate::domain::position::{ColIndex, RowIndex};
+    use crate::session::FileConfig;
+
+    fn open(path: &Path) -> App {
+        let document = Document::from_file(path, None, false, None).unwrap();
+        App::new(document, vec![path.to_path_buf()], 0, FileConfig::new())
+    }
+
+    #[test]
+    fn test_swap_path() {
+        assert_eq!(
+            swap_path(Path::new("/data/cities.csv")),
+            PathBuf::from("/data/.cities.csv.lazycsv.swp")
+        );
+    }
+
+    #[test]
+    fn test_swap_file_is_written_and_recovered() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cities.csv");
+        std::fs::write(&path, "name,city\na,Oslo\nb,Rome\n").unwrap();
+        let swap = swap_path(&path);
+
+        let mut app = open(&path);
+        let start = Instant::now();
+        assert!(!app.update_swap_file(start));
+        assert!(!swap.exists());
+
+        app.set_cell(RowIndex::new(0), ColIndex::new(1), "Bergen".into(), "edit");
+        app.update_swap_file(start);
+        assert_eq!(
+            std::fs::read_to_string(&swap).unwrap(),
+            "name,city\na,Bergen\nb,Rome\n"
+        );
+        // At most one write per interval
+        app.set_cell(RowIndex::new(1), ColIndex::new(1), "Roma".into(), "edit");
+        app.update_swap_file(start + Duration::from_secs(1));
+        assert!(!std::fs::read_to_string(&swap).unwrap().contains("Roma"));
+        app.update_swap_file(start + SWAP_INTERVAL);
+        assert!(std::fs::read_to_string(&swap).unwrap().contains("Roma"));
+
+        // The crashed session's swap file is offered the next time
+        let mut app = open(&path);
+        assert!(app.offer_recovery());
+        app.recover_swap_file();
+        assert!(app.document.is_dirty);
+        assert_eq!(app.document.rows[1], vec!["b", "Roma"]);
+        assert_eq!(app.document.filename, "cities.csv");
+
+        // Saving removes it
+        app.document.write_to(&path, true).unwrap();
+        app.document.is_dirty = false;
+        app.update_swap_file(start + SWAP_INTERVAL * 2);
+        assert!(!swap.exists());
+        assert!(!app.offer_recovery());
+    }
+
+    #[test]
+    fn test_swap_files_are_removed_on_quit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cities.csv");
+        std::fs::write(&path, "name,city\na,Oslo\n").unwrap();
+        let mut app = open(&path);
+        app.set_cell(RowIndex::new(0), ColIndex::new(1), "Bergen".into(), "edit");
+        app.update_swap_file(Instant::now());
+        assert!(swap_path(&path).exists());
+        app.remove_swap_files();
+        assert!(!swap_path(&path).exists());
+    }
+}
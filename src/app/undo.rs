@@ -0,0 +1,215 @@
+//! Undo/redo history of document edits.
+//!
+//! Every change to the document is recorded as a `Change` (one or more
+//! edits made by a single command, e.g. all cells written by `:fill`), so
+//! `u` undoes the whole command at once.
+
+/// Changes kept for undo
+pub const MAX_UNDO: usize = 100;
+
+/// A single edit to the document, with enough detail to reverse it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    /// A cell value changed
+    Cell {
+        row: usize,
+        col: usize,
+        old: String,
+        new: String,
+    },
+    /// A row was inserted at `at`
+    InsertRow { at: usize, values: Vec<String> },
+    /// The row at `at` was deleted
+    DeleteRow { at: usize, values: Vec<String> },
+    /// Rows from `start` were reordered: row `start + i` is the one that
+    /// was at `start + order[i]`
+    Reorder { start: usize, order: Vec<usize> },
+}
+
+impl Edit {
+    /// The edit that reverses this one
+    pub fn inverse(&self) -> Edit {
+        match self {
+            Edit::Cell { row, col, old, new } => Edit::Cell {
+                row: *row,
+                col: *col,
+                old: new.clone(),
+                new: old.clone(),
+            },
+            Edit::InsertRow { at, values } => Edit::DeleteRow {
+                at: *at,
+                values: values.clone(),
+            },
+            Edit::DeleteRow { at, values } => Edit::InsertRow {
+                at: *at,
+                values: values.clone(),
+            },
+            Edit::Reorder { start, order } => {
+                let mut inverse = vec![0; order.len()];
+                for (to, &from) in order.iter().enumerate() {
+                    inverse[from] = to;
+                }
+                Edit::Reorder {
+                    start: *start,
+                    order: inverse,
+                }
+            }
+        }
+    }
+}
+
+/// Edits made by one command, applied in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub edits: Vec<Edit>,
+    /// Short description for status messages (e.g. "fill total")
+    pub label: String,
+}
+
+impl Change {
+    /// The change that reverses this one (inverse edits in reverse order)
+    pub fn inverse(&self) -> Change {
+        Change {
+            edits: self.edits.iter().rev().map(Edit::inverse).collect(),
+            label: self.label.clone(),
+        }
+    }
+}
+
+/// Undo and redo stacks
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+    /// Changes recorded, undone or redone so far
+    version: u64,
+}
+
+impl History {
+    /// Record a new change (clears the redo stack)
+    pub fn record(&mut self, change: Change) {
+        if change.edits.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push(change);
+        self.version += 1;
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Take the most recent change to undo; returns the change to apply
+    pub fn undo(&mut self) -> Option<Change> {
+        let change = self.undo.pop()?;
+        let inverse = change.inverse();
+        self.redo.push(change);
+        self.version += 1;
+        Some(inverse)
+    }
+
+    /// Take the most recently undone change to apply again
+    pub fn redo(&mut self) -> Option<Change> {
+        let change = self.redo.pop()?;
+        self.undo.push(change.clone());
+        self.version += 1;
+        Some(change)
+    }
+
+    /// Counter that moves with every change made, undone or redone, to
+    /// tell whether the document changed since it was last looked at
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Forget all changes (the document was reloaded)
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(row: usize, old: &str, new: &str) -> Change {
+        Change {
+            edits: vec![Edit::Cell {
+                row,
+                col: 0,
+                old: old.to_string(),
+                new: new.to_string(),
+            }],
+            label: "edit".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_order() {
+        let mut history = History::default();
+        history.record(cell(0, "a", "b"));
+        history.record(cell(1, "c", "d"));
+
+        assert_eq!(history.undo(), Some(cell(1, "d", "c")));
+        assert_eq!(history.undo(), Some(cell(0, "b", "a")));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo(), Some(cell(0, "a", "b")));
+
+        // A new change drops what could be redone
+        history.record(cell(2, "e", "f"));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn test_inverse_reverses_edit_order() {
+        let change = Change {
+            edits: vec![
+                Edit::InsertRow {
+                    at: 3,
+                    values: vec![],
+                },
+                Edit::Cell {
+                    row: 3,
+                    col: 1,
+                    old: String::new(),
+                    new: "x".to_string(),
+                },
+            ],
+            label: "paste".to_string(),
+        };
+        let inverse = change.inverse();
+        assert!(matches!(inverse.edits[0], Edit::Cell { .. }));
+        assert!(matches!(inverse.edits[1], Edit::DeleteRow { at: 3, .. }));
+    }
+
+    #[test]
+    fn test_reorder_inverse() {
+        let reorder = Edit::Reorder {
+            start: 2,
+            order: vec![2, 0, 1],
+        };
+        assert_eq!(
+            reorder.inverse(),
+            Edit::Reorder {
+                start: 2,
+                order: vec![1, 2, 0],
+            }
+        );
+        assert_eq!(reorder.inverse().inverse(), reorder);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = History::default();
+        for row in 0..MAX_UNDO + 10 {
+            history.record(cell(row, "a", "b"));
+        }
+        let mut count = 0;
+        while history.undo().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_UNDO);
+    }
+}
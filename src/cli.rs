@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -17,6 +17,24 @@ pub struct CliArgs {
     #[arg(long, help = "Treat the first row as data, not headers.")]
     pub no_headers: bool,
 
+    /// Number of lines before the header to keep out of the table.
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Skip N metadata lines before the header (kept when saving)"
+    )]
+    pub skip_lines: Option<usize>,
+
+    /// Lines before the header starting with this character are comments,
+    /// not rows.
+    #[arg(
+        long,
+        value_name = "CHAR",
+        value_parser = parse_comment_char,
+        help = "Skip lines before the header starting with this character, e.g. '#' (kept when saving)"
+    )]
+    pub comment_char: Option<u8>,
+
     /// Specify the character encoding of the file.
     #[arg(
         short,
@@ -24,6 +42,177 @@ pub struct CliArgs {
         help = "File encoding (e.g., 'utf-8', 'latin1', 'utf-16le')"
     )]
     pub encoding: Option<String>,
+
+    /// File extensions to include when scanning a directory for files.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Comma-separated file extensions to discover (default: csv,tsv,psv,txt)"
+    )]
+    pub extensions: Vec<String>,
+
+    /// Also look for files in subdirectories.
+    #[arg(
+        short,
+        long,
+        help = "Also discover files in subdirectories (hidden ones are skipped)"
+    )]
+    pub recursive: bool,
+
+    /// Discover the files matching a pattern instead of by extension.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Discover files matching a pattern, e.g. '**/*2024*.csv' (** crosses directories)"
+    )]
+    pub glob: Option<String>,
+
+    /// Row to open at (1-based, as shown in the row numbers).
+    #[arg(long, value_name = "N", conflicts_with = "cell")]
+    pub row: Option<usize>,
+
+    /// Column to open at: header name, letter or number.
+    #[arg(long, value_name = "COLUMN", conflicts_with = "cell")]
+    pub col: Option<String>,
+
+    /// Cell to open at, as column letters and row number (e.g., C120).
+    #[arg(long, visible_alias = "goto", value_name = "CELL", value_parser = parse_cell)]
+    pub cell: Option<CellAddress>,
+
+    /// `:` commands to run after the file is loaded, in order.
+    #[arg(
+        short = 'c',
+        long = "cmd",
+        value_name = "COMMAND",
+        help = "Run a : command after loading, e.g. --cmd ':sort time desc' (repeatable)"
+    )]
+    pub commands: Vec<String>,
+
+    /// Print statistics of every column and exit, without the TUI
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        help = "Print row/column counts and per-column types, nulls and min/max, then exit (--stats=json for JSON)"
+    )]
+    pub stats: Option<StatsFormat>,
+
+    /// Write the cursor cell or the selection to stdout after quitting
+    /// normally (exit code 0).
+    #[arg(long, value_enum, value_name = "WHAT")]
+    pub print: Option<PrintOnExit>,
+
+    /// Picker mode: Enter prints the cursor row (tab-separated), cell or
+    /// the row's value in a column, and quits; `q` quits with exit code 1.
+    #[arg(
+        long,
+        value_name = "WHAT",
+        num_args = 0..=1,
+        default_missing_value = "row",
+        conflicts_with = "print",
+        help = "Enter prints the row and quits, like fzf (also cell, or a column name/letter)"
+    )]
+    pub pick: Option<String>,
+
+    /// Follow the file as another program appends rows to it.
+    #[arg(
+        long,
+        help = "Show rows appended to the file live (toggle with :watch)"
+    )]
+    pub watch: bool,
+
+    /// Mirror the view of a lazycsv that ran `:share`, read-only.
+    #[arg(long, value_name = "SOCKET", conflicts_with_all = ["path", "watch"])]
+    pub follow: Option<PathBuf>,
+
+    /// Open the files of a session saved with `:session save`, set up as
+    /// they were.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["path", "follow"])]
+    pub session: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// A spreadsheet-style cell address like `C120`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellAddress {
+    /// Column letters (e.g., "C")
+    pub column: String,
+    /// 1-based row number
+    pub row: usize,
+}
+
+/// Subcommands that run instead of the TUI
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Check terminal capabilities, config file and state directory
+    Doctor,
+
+    /// Check a file against a schema; exits 1 if there are violations,
+    /// 2 if the file or schema cannot be read
+    Validate {
+        /// CSV file to check
+        file: PathBuf,
+
+        /// Schema file (TOML, one [columns.<name>] table per column)
+        #[arg(long, short)]
+        schema: PathBuf,
+
+        /// Report format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+
+    /// Convert a file to another format without opening it; the format
+    /// comes from the output's extension (.csv, .tsv, .psv, .json, .md,
+    /// .xlsx) or --to
+    Convert {
+        /// File to read, parsed like the TUI would (--delimiter,
+        /// --no-headers, --encoding, --skip-lines, --comment-char)
+        input: PathBuf,
+
+        /// File to write (`-` for stdout)
+        output: PathBuf,
+
+        /// Output format: csv, tsv, psv, json, markdown or xlsx
+        #[arg(long, value_parser = crate::csv::ExportFormat::parse)]
+        to: Option<crate::csv::ExportFormat>,
+    },
+}
+
+/// What `--print` writes to stdout after a normal quit
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintOnExit {
+    /// Value of the cell under the cursor
+    Cell,
+    /// Address of the cell under the cursor (e.g. C120)
+    Address,
+    /// Cells of the last Visual selection as tab-separated lines (the
+    /// cursor cell without one)
+    Selection,
+}
+
+/// Output format of `lazycsv validate`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// One `file:row:column: rule: message` line per violation, then a summary
+    Text,
+    /// A single JSON object
+    Json,
+}
+
+/// Output format of `lazycsv --stats`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    /// A `file: N rows, M columns` line, then an aligned table with a line
+    /// per column (type, empty, null and distinct counts, min and max)
+    Text,
+    /// A single JSON object; numeric columns also get their mean and median
+    Json,
 }
 
 fn parse_delimiter(s: &str) -> Result<u8, String> {
@@ -34,8 +223,105 @@ fn parse_delimiter(s: &str) -> Result<u8, String> {
     }
 }
 
+fn parse_comment_char(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [c] if c.is_ascii_graphic() => Ok(*c),
+        _ => Err(format!(
+            "Comment character must be a single character, got '{}'",
+            s
+        )),
+    }
+}
+
+impl CliArgs {
+    /// Lines before the header given on the command line (None for the
+    /// defaults)
+    pub fn preamble(&self) -> Option<crate::csv::Preamble> {
+        (self.skip_lines.is_some() || self.comment_char.is_some()).then(|| crate::csv::Preamble {
+            skip_lines: self.skip_lines.unwrap_or(0),
+            comment: self.comment_char,
+        })
+    }
+}
+
+fn parse_cell(s: &str) -> Result<CellAddress, String> {
+    let split = s
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(s.len());
+    let (letters, digits) = s.split_at(split);
+    match digits.parse::<usize>() {
+        Ok(row) if !letters.is_empty() && row > 0 => Ok(CellAddress {
+            column: letters.to_ascii_uppercase(),
+            row,
+        }),
+        _ => Err(format!(
+            "Cell must be column letters and a row number (e.g., C120), got '{}'",
+            s
+        )),
+    }
+}
+
 pub fn parse_args() -> CliArgs {
-    CliArgs::parse()
+    CliArgs::parse_from(expand_row_shorthand(std::env::args_os()))
+}
+
+/// Options whose value can be the next argument, as `--col` in
+/// `--col +1`, with their aliases
+fn options_taking_values() -> Vec<String> {
+    let command = CliArgs::command();
+    std::iter::once(&command)
+        .chain(command.get_subcommands())
+        .flat_map(|command| command.get_arguments())
+        .filter(|arg| {
+            !arg.is_positional() && arg.get_action().takes_values() && !arg.is_require_equals_set()
+        })
+        .flat_map(|arg| {
+            let longs = arg
+                .get_long()
+                .into_iter()
+                .chain(arg.get_all_aliases().unwrap_or_default())
+                .map(|long| format!("--{}", long));
+            let shorts = arg
+                .get_short()
+                .into_iter()
+                .map(|short| format!("-{}", short));
+            longs.chain(shorts).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Turn vim's `+N` (open at line N) into `--row N`; a file named like that
+/// can still be opened as `./+N`. Only arguments that aren't an option's
+/// value are turned, so `--cmd +5` keeps its `+5`.
+pub fn expand_row_shorthand<I, T>(args: I) -> Vec<std::ffi::OsString>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString>,
+{
+    let takes_value = options_taking_values();
+    let mut expanded: Vec<std::ffi::OsString> = Vec::new();
+    let mut options_ended = false;
+    for arg in args.into_iter().map(Into::into) {
+        let is_value = expanded
+            .last()
+            .and_then(|previous| previous.to_str())
+            .is_some_and(|previous| takes_value.iter().any(|option| option == previous));
+        let row = arg
+            .to_str()
+            .and_then(|arg| arg.strip_prefix('+'))
+            .filter(|row| !row.is_empty() && row.bytes().all(|b| b.is_ascii_digit()));
+        match row {
+            Some(row) if !options_ended && !is_value && !expanded.is_empty() => {
+                expanded.push("--row".into());
+                expanded.push(row.into());
+            }
+            _ => {
+                options_ended |= arg == "--" && !is_value;
+                expanded.push(arg);
+            }
+        }
+    }
+    expanded
 }
 
 #[cfg(test)]
@@ -52,6 +338,145 @@ mod tests {
         assert_eq!(args.delimiter, None);
         assert!(!args.no_headers);
         assert_eq!(args.encoding, None);
+        assert!(args.extensions.is_empty());
+        assert_eq!(args.command, None);
+        assert_eq!(args.row, None);
+        assert_eq!(args.col, None);
+        assert_eq!(args.cell, None);
+        assert!(args.commands.is_empty());
+        assert_eq!(args.pick, None);
+    }
+
+    #[test]
+    fn test_cli_pick() {
+        let args = CliArgs::try_parse_from(["lazycsv", "hosts.csv", "--pick"]).unwrap();
+        assert_eq!(args.pick.as_deref(), Some("row"));
+        assert_eq!(args.path, Some(PathBuf::from("hosts.csv")));
+        let args = CliArgs::try_parse_from(["lazycsv", "--pick=email", "users.csv"]).unwrap();
+        assert_eq!(args.pick.as_deref(), Some("email"));
+        assert!(CliArgs::try_parse_from(["lazycsv", "--pick", "--print", "cell"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_start_position() {
+        let args = CliArgs::try_parse_from(["lazycsv", "--row", "500", "--col", "email"]).unwrap();
+        assert_eq!(args.row, Some(500));
+        assert_eq!(args.col.as_deref(), Some("email"));
+
+        let args = CliArgs::try_parse_from(["lazycsv", "--cell", "c120"]).unwrap();
+        assert_eq!(
+            args.cell,
+            Some(CellAddress {
+                column: "C".to_string(),
+                row: 120
+            })
+        );
+
+        for bad in ["120", "C", "C0", "C12x"] {
+            assert!(CliArgs::try_parse_from(["lazycsv", "--cell", bad]).is_err());
+        }
+        assert!(CliArgs::try_parse_from(["lazycsv", "--cell", "A1", "--row", "2"]).is_err());
+
+        let args = CliArgs::try_parse_from(["lazycsv", "--goto", "B150"]).unwrap();
+        assert_eq!(args.cell.map(|cell| cell.row), Some(150));
+    }
+
+    #[test]
+    fn test_cli_plus_row_shorthand() {
+        let parse = |args: &[&str]| CliArgs::try_parse_from(expand_row_shorthand(args));
+        let args = parse(&["lazycsv", "data.csv", "+150"]).unwrap();
+        assert_eq!(args.path, Some(PathBuf::from("data.csv")));
+        assert_eq!(args.row, Some(150));
+        let args = parse(&["lazycsv", "+7", "data.csv", "--col", "email"]).unwrap();
+        assert_eq!((args.row, args.col.as_deref()), (Some(7), Some("email")));
+
+        // Not a row number, or after `--`
+        assert_eq!(
+            expand_row_shorthand(["lazycsv", "+x", "--", "+3"]),
+            vec!["lazycsv", "+x", "--", "+3"]
+        );
+        assert!(parse(&["lazycsv", "+1", "--cell", "A1"]).is_err());
+
+        // Option values are left alone
+        let args = parse(&["lazycsv", "data.csv", "--cmd", "+5"]).unwrap();
+        assert_eq!((args.row, args.commands), (None, vec!["+5".to_string()]));
+        let args = parse(&["lazycsv", "data.csv", "--col", "+1", "+3"]).unwrap();
+        assert_eq!((args.row, args.col.as_deref()), (Some(3), Some("+1")));
+        assert_eq!(
+            expand_row_shorthand(["lazycsv", "-c", "+2", "--goto", "+4"]),
+            vec!["lazycsv", "-c", "+2", "--goto", "+4"]
+        );
+    }
+
+    #[test]
+    fn test_cli_validate_subcommand() {
+        let args = CliArgs::try_parse_from([
+            "lazycsv",
+            "--delimiter",
+            ";",
+            "validate",
+            "data.csv",
+            "--schema",
+            "schema.toml",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(args.delimiter, Some(b';'));
+        assert_eq!(
+            args.command,
+            Some(Command::Validate {
+                file: PathBuf::from("data.csv"),
+                schema: PathBuf::from("schema.toml"),
+                format: ReportFormat::Json,
+            })
+        );
+
+        // The schema is required
+        assert!(CliArgs::try_parse_from(["lazycsv", "validate", "data.csv"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_recursive_and_glob() {
+        let args =
+            CliArgs::try_parse_from(["lazycsv", "data/", "-r", "--glob", "**/*2024*.csv"]).unwrap();
+        assert!(args.recursive);
+        assert_eq!(args.glob.as_deref(), Some("**/*2024*.csv"));
+    }
+
+    #[test]
+    fn test_cli_stats() {
+        let args = CliArgs::try_parse_from(["lazycsv", "--stats", "data.csv"]).unwrap();
+        assert_eq!(args.stats, Some(StatsFormat::Text));
+        assert_eq!(args.path, Some(PathBuf::from("data.csv")));
+        let args = CliArgs::try_parse_from(["lazycsv", "data.csv", "--stats=json"]).unwrap();
+        assert_eq!(args.stats, Some(StatsFormat::Json));
+        assert!(CliArgs::try_parse_from(["lazycsv", "--stats=yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_convert_subcommand() {
+        let args =
+            CliArgs::try_parse_from(["lazycsv", "convert", "data.csv", "-", "--to", "md"]).unwrap();
+        assert_eq!(
+            args.command,
+            Some(Command::Convert {
+                input: PathBuf::from("data.csv"),
+                output: PathBuf::from("-"),
+                to: Some(crate::csv::ExportFormat::Markdown),
+            })
+        );
+        assert!(
+            CliArgs::try_parse_from(["lazycsv", "convert", "a.csv", "b", "--to", "yaml"]).is_err()
+        );
+        assert!(CliArgs::try_parse_from(["lazycsv", "convert", "a.csv"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_doctor_subcommand() {
+        let args = CliArgs::try_parse_from(["lazycsv", "doctor"]).unwrap();
+        assert_eq!(args.command, Some(Command::Doctor));
+        assert_eq!(args.path, None);
     }
 
     #[test]
@@ -96,6 +521,47 @@ mod tests {
         assert!(args.no_headers);
     }
 
+    #[test]
+    fn test_cli_skip_lines_and_comment_char() {
+        let args = CliArgs::try_parse_from(["lazycsv"]).unwrap();
+        assert_eq!(args.preamble(), None);
+
+        let args = CliArgs::try_parse_from(["lazycsv", "--skip-lines", "2", "--comment-char", "#"])
+            .unwrap();
+        assert_eq!(
+            args.preamble(),
+            Some(crate::csv::Preamble {
+                skip_lines: 2,
+                comment: Some(b'#'),
+            })
+        );
+        assert!(CliArgs::try_parse_from(["lazycsv", "--comment-char", "//"]).is_err());
+        assert!(CliArgs::try_parse_from(["lazycsv", "--skip-lines", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_session() {
+        let args = CliArgs::try_parse_from(["lazycsv", "--session", "review.lazysession"]).unwrap();
+        assert_eq!(args.session, Some(PathBuf::from("review.lazysession")));
+        assert!(
+            CliArgs::try_parse_from(["lazycsv", "a.csv", "--session", "r.lazysession"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_follow() {
+        let args = CliArgs::try_parse_from(["lazycsv", "--follow", "/tmp/share.sock"]).unwrap();
+        assert_eq!(args.follow, Some(PathBuf::from("/tmp/share.sock")));
+        assert!(CliArgs::try_parse_from(["lazycsv", "data.csv", "--follow", "x.sock"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_with_watch() {
+        let args = CliArgs::try_parse_from(["lazycsv", "log.csv", "--watch"]).unwrap();
+        assert!(args.watch);
+        assert!(!CliArgs::try_parse_from(["lazycsv"]).unwrap().watch);
+    }
+
     #[test]
     fn test_cli_all_args_combined() {
         let temp_dir = TempDir::new().unwrap();
@@ -142,4 +608,10 @@ mod tests {
         let args = args.unwrap();
         assert_eq!(args.encoding, Some("latin1".to_string()));
     }
+
+    #[test]
+    fn test_cli_with_extensions() {
+        let args = CliArgs::try_parse_from(["lazycsv", "--extensions", "tsv,dat"]).unwrap();
+        assert_eq!(args.extensions, vec!["tsv".to_string(), "dat".to_string()]);
+    }
 }
@@ -0,0 +1,106 @@
+//! Copy text to the system clipboard through a helper program, falling back
+//! to the OSC 52 escape sequence the terminal can forward.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::ffi::OsStr;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Clipboard helper programs and their arguments, in order of preference
+pub const TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+    ("clip.exe", &[]),
+];
+
+/// Names of the clipboard helpers, for messages
+pub fn tool_names() -> String {
+    TOOLS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The preferred clipboard helper found on `path_var` (a `$PATH` value)
+pub fn find_tool(path_var: Option<&OsStr>) -> Option<&'static str> {
+    let paths = path_var?;
+    std::env::split_paths(paths).find_map(|dir| {
+        TOOLS
+            .iter()
+            .map(|(name, _)| *name)
+            .find(|name| dir.join(name).is_file())
+    })
+}
+
+/// Copy `text`, returning how it was copied (the helper's name or
+/// "OSC 52")
+pub fn copy(text: &str) -> Result<&'static str> {
+    if let Some(tool) = find_tool(std::env::var_os("PATH").as_deref()) {
+        run_tool(tool, text)?;
+        return Ok(tool);
+    }
+    let mut stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        bail!(
+            "No clipboard helper found (install one of {})",
+            tool_names()
+        );
+    }
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()?;
+    Ok("OSC 52")
+}
+
+fn run_tool(tool: &'static str, text: &str) -> Result<()> {
+    let args = TOOLS
+        .iter()
+        .find(|(name, _)| *name == tool)
+        .map_or(&[][..], |(_, args)| *args);
+    let mut child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", tool))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", tool))?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("{} failed ({})", tool, status);
+    }
+    Ok(())
+}
+
+/// Escape sequence asking the terminal to set its clipboard to `text`
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_tool_prefers_earlier_tools() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(find_tool(Some(dir.path().as_os_str())), None);
+        std::fs::write(dir.path().join("xsel"), "").unwrap();
+        std::fs::write(dir.path().join("xclip"), "").unwrap();
+        assert_eq!(find_tool(Some(dir.path().as_os_str())), Some("xclip"));
+        assert_eq!(find_tool(None), None);
+    }
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("a,b"), "\x1b]52;c;YSxi\x07");
+    }
+}
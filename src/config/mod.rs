@@ -0,0 +1,201 @@
+//! User configuration file and standard directory locations.
+//!
+//! The config file is optional TOML at `$LAZYCSV_CONFIG`,
+//! `$XDG_CONFIG_HOME/lazycsv/config.toml` or `~/.config/lazycsv/config.toml`.
+//! A missing file means "all defaults"; an invalid file is an error.
+
+use crate::app::Options;
+use crate::file_system::modified_time;
+use crate::ui::{ColumnGroupsConfig, ThemeConfig};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Parsed contents of the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Key rebindings: action name -> key (e.g. `next_file = "n"`)
+    pub keys: BTreeMap<String, String>,
+
+    /// Mode colors and cursor shapes
+    pub theme: ThemeConfig,
+
+    /// Initial values of `:set` options
+    pub options: Options,
+
+    /// Column groups created when a file is opened
+    pub column_groups: ColumnGroupsConfig,
+}
+
+impl Config {
+    /// Parse config from TOML text
+    pub fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).context("Invalid config file")
+    }
+
+    /// Load config from a specific path (missing file = defaults)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).with_context(|| path.display().to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Load config from the standard location
+    pub fn load() -> Result<Self> {
+        match config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Tracks the config file's modification time to detect edits
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// Start watching a config file (which may not exist yet)
+    pub fn new(path: PathBuf) -> Self {
+        let last_modified = modified_time(&path);
+        Self {
+            path,
+            last_modified,
+        }
+    }
+
+    /// Path of the watched config file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the file was created, modified or removed since the last poll
+    pub fn poll_changed(&mut self) -> bool {
+        let modified = modified_time(&self.path);
+        if modified != self.last_modified {
+            self.last_modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Non-empty environment variable as a path
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Location of the config file
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env_path("LAZYCSV_CONFIG") {
+        return Some(path);
+    }
+    env_path("XDG_CONFIG_HOME")
+        .or_else(|| env_path("HOME").map(|home| home.join(".config")))
+        .map(|dir| dir.join("lazycsv").join("config.toml"))
+}
+
+/// Directory for persistent state (history, positions, etc.)
+pub fn state_dir() -> Option<PathBuf> {
+    env_path("XDG_STATE_HOME")
+        .or_else(|| env_path("HOME").map(|home| home.join(".local").join("state")))
+        .map(|dir| dir.join("lazycsv"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_empty_config() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_parse_keys_section() {
+        let config = Config::parse("[keys]\nnext_file = \"n\"\nquit = \"Ctrl+q\"\n").unwrap();
+        assert_eq!(config.keys.get("next_file").map(String::as_str), Some("n"));
+        assert_eq!(config.keys.get("quit").map(String::as_str), Some("Ctrl+q"));
+    }
+
+    #[test]
+    fn test_parse_theme_section() {
+        let config = Config::parse("[theme]\ninsert = \"green\"\ncursor_shapes = false\n").unwrap();
+        assert_eq!(config.theme.insert.as_deref(), Some("green"));
+        assert_eq!(config.theme.cursor_shapes, Some(false));
+    }
+
+    #[test]
+    fn test_parse_options_section() {
+        let config = Config::parse("[options]\nshow_keys = true\n").unwrap();
+        assert!(config.options.show_keys);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_sections() {
+        assert!(Config::parse("[colours]\nfg = \"red\"\n").is_err());
+        assert!(Config::parse("keys = 5\n").is_err());
+    }
+
+    #[test]
+    fn test_load_from_missing_file_is_default() {
+        let config = Config::load_from(Path::new("/nonexistent/lazycsv/config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_load_from_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[keys]\nhelp = \"F1\"").unwrap();
+
+        let config = Config::load_from(file.path()).unwrap();
+        assert_eq!(config.keys.get("help").map(String::as_str), Some("F1"));
+    }
+
+    #[test]
+    fn test_watcher_detects_creation_and_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut watcher = ConfigWatcher::new(path.clone());
+        assert!(!watcher.poll_changed());
+
+        std::fs::write(&path, "").unwrap();
+        assert!(watcher.poll_changed());
+        assert!(!watcher.poll_changed());
+
+        // Bump the mtime explicitly; filesystem timestamps can be coarse
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(watcher.poll_changed());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.poll_changed());
+    }
+
+    #[test]
+    fn test_load_from_invalid_file_mentions_path() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[keys").unwrap();
+
+        let err = Config::load_from(file.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains(&file.path().display().to_string()));
+    }
+}
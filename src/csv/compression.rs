@@ -0,0 +1,119 @@
+//! Transparent gzip/zstd support for `.gz` and `.zst` files.
+
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Compression format, picked by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Compression of a file named `*.gz` or `*.zst`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Some(Self::Gzip),
+            Some("zst") => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Decompress a whole file
+    pub fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        match self {
+            Self::Gzip => flate2::read::MultiGzDecoder::new(bytes)
+                .read_to_end(&mut out)
+                .context("Failed to decompress gzip data")?,
+            Self::Zstd => zstd::stream::read::Decoder::new(bytes)
+                .and_then(|mut decoder| decoder.read_to_end(&mut out))
+                .context("Failed to decompress zstd data")?,
+        };
+        Ok(out)
+    }
+
+    /// Compress a whole file
+    pub fn compress(self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                Ok(encoder.finish()?)
+            }
+            Self::Zstd => Ok(zstd::stream::encode_all(bytes, 0)?),
+        }
+    }
+}
+
+/// Read a whole file, decompressing `.gz` and `.zst` files
+pub fn read_file(path: &Path) -> Result<Vec<u8>> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    match Compression::from_path(path) {
+        Some(compression) => compression
+            .decompress(&bytes)
+            .with_context(|| format!("Failed to read file: {}", path.display())),
+        None => Ok(bytes),
+    }
+}
+
+/// Open a file to read as a stream, decompressing `.gz` and `.zst` files
+/// as it is read
+pub fn open_file(path: &Path) -> Result<Box<dyn Read>> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    Ok(match Compression::from_path(path) {
+        Some(Compression::Gzip) => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Some(Compression::Zstd) => Box::new(
+            zstd::stream::read::Decoder::new(file)
+                .with_context(|| format!("Failed to read file: {}", path.display()))?,
+        ),
+        None => Box::new(file),
+    })
+}
+
+/// The path without its compression extension (`data.tsv.gz` is a
+/// `data.tsv`), used to pick delimiters and match discovered files
+pub fn uncompressed_path(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = b"a,b\n1,2\n";
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let packed = compression.compress(data).unwrap();
+            assert_ne!(packed.as_slice(), data);
+            assert_eq!(compression.decompress(&packed).unwrap(), data);
+        }
+        assert!(Compression::Gzip.decompress(b"a,b\n").is_err());
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            Compression::from_path(Path::new("x.csv.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("x.zst")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_path(Path::new("x.csv")), None);
+        assert_eq!(
+            uncompressed_path(Path::new("dir/x.tsv.zst")),
+            PathBuf::from("dir/x.tsv")
+        );
+    }
+}
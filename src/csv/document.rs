@@ -5,8 +5,144 @@ use anyhow::{Context, Result};
 use csv;
 use encoding_rs::Encoding;
 use std::fs;
+use std::io::BufRead;
 use std::path::Path;
 
+use super::compression::{read_file, uncompressed_path, Compression};
+use super::sniff::sniff_delimiter;
+
+/// Pick the conventional delimiter for a file based on its extension
+///
+/// `.tsv` files are tab-separated and `.psv` files are pipe-separated (also
+/// when compressed, as in `.tsv.gz`). Returns None for everything else so
+/// the parser falls back to its default.
+pub fn delimiter_for_path(path: &Path) -> Option<u8> {
+    match uncompressed_path(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+    {
+        Some("tsv") => Some(b'\t'),
+        Some("psv") => Some(b'|'),
+        _ => None,
+    }
+}
+
+/// Name given to column `col` of a file read without a header line
+pub fn synthesized_header(col: usize) -> String {
+    format!("Column {}", col + 1)
+}
+
+/// Lines before the header that aren't part of the table: a number of
+/// metadata lines (`--skip-lines`), then any lines starting with a comment
+/// character (`--comment-char`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Preamble {
+    pub skip_lines: usize,
+    pub comment: Option<u8>,
+}
+
+impl Preamble {
+    /// Whether a line of the preamble, after the skipped ones, is a comment
+    fn is_comment(&self, line: &[u8]) -> bool {
+        self.comment.is_some_and(|c| line.first() == Some(&c))
+    }
+
+    /// Split file content into its preamble and the table
+    pub fn split<'a>(&self, content: &'a str) -> (&'a str, &'a str) {
+        let mut offset = 0;
+        let mut lines = 0;
+        while offset < content.len() {
+            let rest = &content[offset..];
+            let end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if lines >= self.skip_lines && !self.is_comment(rest.as_bytes()) {
+                break;
+            }
+            offset += end;
+            lines += 1;
+        }
+        content.split_at(offset)
+    }
+
+    /// Read the preamble off the start of a file
+    pub fn skip_in(&self, reader: &mut impl BufRead) -> std::io::Result<()> {
+        let mut line = Vec::new();
+        for _ in 0..self.skip_lines {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+        }
+        while self.is_comment(reader.fill_buf()?) {
+            line.clear();
+            reader.read_until(b'\n', &mut line)?;
+        }
+        Ok(())
+    }
+}
+
+/// How records are written back: detected from the first record when a
+/// file is read, so saving leaves the rest of the file's lines as they were
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteStyle {
+    /// Lines end with `\r\n` instead of `\n`
+    pub crlf: bool,
+    /// Every field is quoted, not only those that need it
+    pub quote_all: bool,
+}
+
+impl WriteStyle {
+    /// The style of the first record of `table`
+    pub fn detect(table: &str, delimiter: u8) -> Self {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(table.as_bytes());
+        let mut record = csv::ByteRecord::new();
+        if !reader.read_byte_record(&mut record).unwrap_or(false) {
+            return Self::default();
+        }
+        let start = record.position().map_or(0, |pos| pos.byte() as usize);
+        let end = reader.position().byte() as usize;
+        let Some(line) = table.get(start..end) else {
+            return Self::default();
+        };
+        // The reader may stop between the `\r` and the `\n`
+        let crlf =
+            line.ends_with("\r\n") || (line.ends_with('\r') && table[end..].starts_with('\n'));
+        let line = line.trim_end_matches(['\r', '\n']);
+        // Quoted throughout if quoting every field gives the line back
+        let mut writer = Self {
+            crlf: false,
+            quote_all: true,
+        }
+        .writer(delimiter, Vec::new());
+        let quote_all = writer.write_byte_record(&record).is_ok()
+            && writer
+                .into_inner()
+                .is_ok_and(|bytes| bytes.strip_suffix(b"\n") == Some(line.as_bytes()));
+        Self { crlf, quote_all }
+    }
+
+    /// A CSV writer in this style
+    pub fn writer<W: std::io::Write>(&self, delimiter: u8, output: W) -> csv::Writer<W> {
+        csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .terminator(if self.crlf {
+                csv::Terminator::CRLF
+            } else {
+                csv::Terminator::Any(b'\n')
+            })
+            .quote_style(if self.quote_all {
+                csv::QuoteStyle::Always
+            } else {
+                csv::QuoteStyle::Necessary
+            })
+            .from_writer(output)
+    }
+}
+
 /// Holds parsed CSV document in memory
 #[derive(Debug)]
 pub struct Document {
@@ -21,6 +157,30 @@ pub struct Document {
 
     /// Track unsaved changes (Phase 2)
     pub is_dirty: bool,
+
+    /// Field delimiter the file was parsed with (explicit, by extension or sniffed)
+    pub delimiter: u8,
+
+    /// Lines before the header kept out of the table (see `Preamble`),
+    /// written back as they were
+    pub leading_lines: String,
+
+    /// Line endings and quoting the file was written with
+    pub write_style: WriteStyle,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            rows: Vec::new(),
+            filename: String::new(),
+            is_dirty: false,
+            delimiter: b',',
+            leading_lines: String::new(),
+            write_style: WriteStyle::default(),
+        }
+    }
 }
 
 impl Document {
@@ -30,6 +190,24 @@ impl Document {
         delimiter: Option<u8>,
         no_headers: bool,
         encoding_label: Option<String>,
+    ) -> Result<Self> {
+        Self::from_file_with(
+            path,
+            delimiter,
+            no_headers,
+            encoding_label,
+            Preamble::default(),
+        )
+    }
+
+    /// Load CSV from file path, keeping the lines of `preamble` out of the
+    /// table
+    pub fn from_file_with(
+        path: &Path,
+        delimiter: Option<u8>,
+        no_headers: bool,
+        encoding_label: Option<String>,
+        preamble: Preamble,
     ) -> Result<Self> {
         let filename = path
             .file_name()
@@ -37,22 +215,36 @@ impl Document {
             .unwrap_or("unknown")
             .to_string();
 
-        let file_bytes =
-            fs::read(path).context(format!("Failed to read file: {}", path.display()))?;
+        let file_bytes = read_file(path)?;
 
         let decoded_content = Self::decode_file_bytes(&file_bytes, encoding_label)?;
-        let (headers, rows) = Self::parse_csv_content(&decoded_content, delimiter, no_headers)?;
+        let (leading_lines, table) = preamble.split(&decoded_content);
+        // Explicit delimiter wins, then the file extension, then sniffing
+        let delimiter = delimiter
+            .or_else(|| delimiter_for_path(path))
+            .or_else(|| sniff_delimiter(table))
+            .unwrap_or(b',');
+        // Only the preamble's comments are skipped: a later row starting
+        // with the comment character is data
+        let (headers, rows) = Self::parse_csv_content(table, Some(delimiter), no_headers)?;
+        let write_style = WriteStyle::detect(table, delimiter);
 
         Ok(Document {
             headers,
             rows,
             filename,
             is_dirty: false,
+            delimiter,
+            leading_lines: leading_lines.to_string(),
+            write_style,
         })
     }
 
     /// Decodes file bytes into a UTF-8 string using the specified encoding.
-    fn decode_file_bytes(file_bytes: &[u8], encoding_label: Option<String>) -> Result<String> {
+    pub(crate) fn decode_file_bytes(
+        file_bytes: &[u8],
+        encoding_label: Option<String>,
+    ) -> Result<String> {
         if let Some(label) = &encoding_label {
             let encoding = Encoding::for_label(label.as_bytes())
                 .ok_or_else(|| anyhow::anyhow!("Unsupported encoding: {}", label))?;
@@ -64,7 +256,7 @@ impl Document {
         }
     }
 
-    /// Parses CSV content from a string.
+    /// Parses CSV content from a string
     fn parse_csv_content(
         content: &str,
         delimiter: Option<u8>,
@@ -87,11 +279,7 @@ impl Document {
 
         let final_headers = if no_headers {
             rows.first()
-                .map(|first_row| {
-                    (1..=first_row.len())
-                        .map(|i| format!("Column {}", i))
-                        .collect()
-                })
+                .map(|first_row| (0..first_row.len()).map(synthesized_header).collect())
                 .unwrap_or_default()
         } else {
             headers_from_csv.iter().map(String::from).collect()
@@ -100,6 +288,77 @@ impl Document {
         Ok((final_headers, rows))
     }
 
+    /// Append rows from CSV lines read off the end of the file (no header
+    /// line, the document's delimiter). Returns the number of rows added.
+    pub fn append_records(
+        &mut self,
+        bytes: &[u8],
+        encoding_label: Option<String>,
+    ) -> Result<usize> {
+        let text = Self::decode_file_bytes(bytes, encoding_label)?;
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .from_reader(text.as_bytes());
+        let mut rows = Vec::new();
+        for result in reader.records() {
+            let record = result.context("Failed to parse appended rows")?;
+            rows.push(record.iter().map(String::from).collect());
+        }
+        let added = rows.len();
+        self.rows.extend(rows);
+        Ok(added)
+    }
+
+    /// Write the document to a file (UTF-8). New `.tsv`/`.psv` paths take
+    /// their conventional delimiter, anything else keeps the current one;
+    /// `.gz`/`.zst` paths are compressed. Synthesized headers of a file
+    /// opened with `--no-headers` are skipped. Line endings and quoting
+    /// follow `write_style`. Returns the delimiter used.
+    pub fn write_to(&self, path: &Path, write_headers: bool) -> Result<u8> {
+        let delimiter = delimiter_for_path(path).unwrap_or(self.delimiter);
+        let mut writer = self
+            .write_style
+            .writer(delimiter, self.leading_lines.as_bytes().to_vec());
+        if write_headers {
+            writer.write_record(&self.headers)?;
+        }
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+        // Serialize fully before touching the file so an error cannot truncate it
+        let mut bytes = writer.into_inner().context("Failed to serialize CSV")?;
+        if let Some(compression) = Compression::from_path(path) {
+            bytes = compression.compress(&bytes)?;
+        }
+        fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(delimiter)
+    }
+
+    /// The given rows and columns, in that order, as a new document
+    pub fn select(&self, rows: &[usize], columns: &[usize]) -> Document {
+        let pick = |values: &[String]| {
+            columns
+                .iter()
+                .map(|&col| values.get(col).cloned().unwrap_or_default())
+                .collect()
+        };
+        Document {
+            headers: pick(&self.headers),
+            rows: rows
+                .iter()
+                .filter_map(|&row| self.rows.get(row))
+                .map(|row| pick(row))
+                .collect(),
+            filename: self.filename.clone(),
+            is_dirty: false,
+            delimiter: self.delimiter,
+            leading_lines: self.leading_lines.clone(),
+            write_style: self.write_style,
+        }
+    }
+
     /// Get total row count (excluding headers)
     pub fn row_count(&self) -> usize {
         self.rows.len()
@@ -128,6 +387,19 @@ impl Document {
             .unwrap_or("")
     }
 
+    /// Find a column by header name (exact match first, then ignoring case)
+    pub fn find_column(&self, name: &str) -> Option<ColIndex> {
+        self.headers
+            .iter()
+            .position(|h| h == name)
+            .or_else(|| {
+                self.headers
+                    .iter()
+                    .position(|h| h.eq_ignore_ascii_case(name))
+            })
+            .map(ColIndex::new)
+    }
+
     /// Set a cell value (returns old value, sets is_dirty = true)
     pub fn set_cell(
         &mut self,
@@ -162,6 +434,20 @@ impl Document {
             None
         }
     }
+
+    /// Reorder the rows from `start`: row `start + i` becomes the row that
+    /// was at `start + order[i]`
+    pub fn reorder_rows(&mut self, start: RowIndex, order: &[usize]) {
+        let start = start.get();
+        let end = start + order.len();
+        if end > self.rows.len() {
+            return;
+        }
+        let mut old: Vec<Option<Vec<String>>> = self.rows.drain(start..end).map(Some).collect();
+        let reordered = order.iter().filter_map(|&from| old.get_mut(from)?.take());
+        self.rows.splice(start..start, reordered);
+        self.is_dirty = true;
+    }
 }
 
 #[cfg(test)]
@@ -201,6 +487,32 @@ mod tests {
         assert_eq!(csv_data.row_count(), 0);
     }
 
+    #[test]
+    fn test_select_rows_and_columns() {
+        let document = Document {
+            headers: vec!["id".to_string(), "name".to_string(), "email".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "Ann".to_string(), "a@x".to_string()],
+                vec!["2".to_string(), "Bo".to_string()],
+            ],
+            ..Default::default()
+        };
+        let selected = document.select(&[1, 0], &[2, 0]);
+        assert_eq!(selected.headers, vec!["email", "id"]);
+        assert_eq!(selected.rows, vec![vec!["", "2"], vec!["a@x", "1"]]);
+    }
+
+    #[test]
+    fn test_find_column_by_name() {
+        let document = Document {
+            headers: vec!["id".to_string(), "Email".to_string(), "email".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(document.find_column("email"), Some(ColIndex::new(2)));
+        assert_eq!(document.find_column("EMAIL"), Some(ColIndex::new(1)));
+        assert_eq!(document.find_column("phone"), None);
+    }
+
     #[test]
     fn test_get_cell_out_of_bounds() {
         let mut file = NamedTempFile::new().unwrap();
@@ -637,6 +949,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_csv_semicolon_delimiter_sniffed() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "Name;Price;City").unwrap();
+        writeln!(file, "Alice;1,50;NYC").unwrap();
+        writeln!(file, "Bob;2,75;LA").unwrap();
+
+        let csv_data = Document::from_file(file.path(), None, false, None).unwrap();
+
+        assert_eq!(csv_data.delimiter, b';');
+        assert_eq!(csv_data.column_count(), 3);
+        assert_eq!(
+            csv_data.get_cell(RowIndex::new(0), ColIndex::new(1)),
+            "1,50"
+        );
+    }
+
+    #[test]
+    fn test_explicit_delimiter_overrides_sniffing() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "a;b,c").unwrap();
+        writeln!(file, "1;2,3").unwrap();
+
+        let csv_data = Document::from_file(file.path(), Some(b','), false, None).unwrap();
+
+        assert_eq!(csv_data.delimiter, b',');
+        assert_eq!(csv_data.get_header(ColIndex::new(0)), "a;b");
+    }
+
     #[test]
     fn test_csv_pipe_delimiter() {
         let mut file = NamedTempFile::new().unwrap();
@@ -845,4 +1186,187 @@ mod tests {
         assert_eq!(csv_data.row_count(), 1);
         assert!(csv_data.filename.len() > 100);
     }
+
+    #[test]
+    fn test_delimiter_for_path() {
+        assert_eq!(delimiter_for_path(Path::new("data.tsv")), Some(b'\t'));
+        assert_eq!(delimiter_for_path(Path::new("data.psv")), Some(b'|'));
+        assert_eq!(delimiter_for_path(Path::new("data.csv")), None);
+        assert_eq!(delimiter_for_path(Path::new("data.txt")), None);
+        assert_eq!(delimiter_for_path(Path::new("data")), None);
+    }
+
+    #[test]
+    fn test_tsv_extension_uses_tab_delimiter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.tsv");
+        std::fs::write(&file_path, "Name\tAge\nAlice\t30\n").unwrap();
+
+        let csv_data = Document::from_file(&file_path, None, false, None).unwrap();
+
+        assert_eq!(csv_data.column_count(), 2);
+        assert_eq!(csv_data.get_cell(RowIndex::new(0), ColIndex::new(1)), "30");
+    }
+
+    #[test]
+    fn test_psv_extension_uses_pipe_delimiter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.psv");
+        std::fs::write(&file_path, "Name|Age\nAlice|30\n").unwrap();
+
+        let csv_data = Document::from_file(&file_path, None, false, None).unwrap();
+
+        assert_eq!(csv_data.column_count(), 2);
+        assert_eq!(
+            csv_data.get_cell(RowIndex::new(0), ColIndex::new(0)),
+            "Alice"
+        );
+    }
+
+    #[test]
+    fn test_explicit_delimiter_overrides_extension() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("data.tsv");
+        std::fs::write(&file_path, "Name;Age\nAlice;30\n").unwrap();
+
+        let csv_data = Document::from_file(&file_path, Some(b';'), false, None).unwrap();
+
+        assert_eq!(csv_data.column_count(), 2);
+    }
+
+    #[test]
+    fn test_write_to_round_trips() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("data.csv");
+        std::fs::write(&source, "Name;Note\nAlice;\"a;b\"\n").unwrap();
+        let csv_data = Document::from_file(&source, None, false, None).unwrap();
+
+        // Same delimiter for .csv, tab for .tsv; quoting survives
+        let copy = temp_dir.path().join("copy.csv");
+        assert_eq!(csv_data.write_to(&copy, true).unwrap(), b';');
+        assert_eq!(
+            std::fs::read_to_string(&copy).unwrap(),
+            "Name;Note\nAlice;\"a;b\"\n"
+        );
+        let tsv = temp_dir.path().join("copy.tsv");
+        assert_eq!(csv_data.write_to(&tsv, false).unwrap(), b'\t');
+        assert_eq!(std::fs::read_to_string(&tsv).unwrap(), "Alice\ta;b\n");
+    }
+
+    #[test]
+    fn test_write_to_keeps_line_endings_and_quoting() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("data.csv");
+        let content = "\"Name\",\"Note\"\r\n\"Alice\",\"a, \"\"b\"\"\"\r\n\"Bob\",\"\"\r\n";
+        std::fs::write(&source, content).unwrap();
+        let csv_data = Document::from_file(&source, None, false, None).unwrap();
+        assert_eq!(
+            csv_data.write_style,
+            WriteStyle {
+                crlf: true,
+                quote_all: true
+            }
+        );
+        csv_data.write_to(&source, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), content);
+
+        let detect = |table: &str| WriteStyle::detect(table, b',');
+        assert_eq!(detect("a,\"b,c\"\n\"x\",\"y\"\n"), WriteStyle::default());
+        assert!(detect("\"a\"\r\n").crlf);
+        assert_eq!(detect("\"multi\nline\",\"b\"\r\n"), detect("\"a\"\r\n"));
+        assert_eq!(detect(""), WriteStyle::default());
+    }
+
+    #[test]
+    fn test_preamble_is_skipped_and_kept() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("export.csv");
+        std::fs::write(
+            &source,
+            "Report; generated 2024\nrows: 2\n# exported by admin\nName,City\nAlice,Oslo\n#Bob,Rome\n",
+        )
+        .unwrap();
+        let preamble = Preamble {
+            skip_lines: 2,
+            comment: Some(b'#'),
+        };
+        let csv_data = Document::from_file_with(&source, None, false, None, preamble).unwrap();
+        assert_eq!(csv_data.headers, vec!["Name", "City"]);
+        // Past the header, a row starting with `#` is data
+        assert_eq!(
+            csv_data.rows,
+            vec![vec!["Alice", "Oslo"], vec!["#Bob", "Rome"]]
+        );
+        // The metadata lines don't decide the delimiter
+        assert_eq!(csv_data.delimiter, b',');
+
+        // Lines before the header and every row survive a save
+        let copy = temp_dir.path().join("copy.csv");
+        csv_data.write_to(&copy, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&copy).unwrap(),
+            std::fs::read_to_string(&source).unwrap()
+        );
+
+        // Read as a table the metadata lines don't line up with the rows
+        assert!(Document::from_file(&source, None, false, None).is_err());
+    }
+
+    #[test]
+    fn test_preamble_split() {
+        let skip = |skip_lines, comment| Preamble {
+            skip_lines,
+            comment,
+        };
+        assert_eq!(skip(0, None).split("a,b\n"), ("", "a,b\n"));
+        assert_eq!(skip(1, None).split("x\na,b\n"), ("x\n", "a,b\n"));
+        assert_eq!(skip(5, None).split("x\n"), ("x\n", ""));
+        assert_eq!(
+            skip(0, Some(b'#')).split("#x\n# y\na,b\n#z\n"),
+            ("#x\n# y\n", "a,b\n#z\n")
+        );
+
+        let mut reader = std::io::Cursor::new(b"x\n#y\na,b\n".to_vec());
+        skip(1, Some(b'#')).skip_in(&mut reader).unwrap();
+        let mut rest = String::new();
+        std::io::Read::read_to_string(&mut reader, &mut rest).unwrap();
+        assert_eq!(rest, "a,b\n");
+    }
+
+    #[test]
+    fn test_append_records() {
+        let mut document = Document {
+            headers: vec!["a".to_string(), "b".to_string()],
+            delimiter: b';',
+            ..Default::default()
+        };
+        let added = document.append_records(b"1;2\n\"x;y\";3\n", None).unwrap();
+        assert_eq!(added, 2);
+        assert_eq!(document.rows[1], vec!["x;y", "3"]);
+        // Rows read from the file are not unsaved changes
+        assert!(!document.is_dirty);
+    }
+
+    #[test]
+    fn test_compressed_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let csv_data = Document {
+            headers: vec!["a".to_string(), "b".to_string()],
+            rows: vec![vec!["1".to_string(), "2".to_string()]],
+            ..Default::default()
+        };
+
+        for name in ["data.csv.gz", "data.tsv.zst"] {
+            let path = temp_dir.path().join(name);
+            csv_data.write_to(&path, true).unwrap();
+            assert!(!std::fs::read(&path).unwrap().starts_with(b"a,b"));
+
+            let loaded = Document::from_file(&path, None, false, None).unwrap();
+            assert_eq!(loaded.headers, csv_data.headers);
+            assert_eq!(loaded.rows, csv_data.rows);
+            assert_eq!(loaded.filename, name);
+        }
+        let tsv = Document::from_file(&temp_dir.path().join("data.tsv.zst"), None, false, None);
+        assert_eq!(tsv.unwrap().delimiter, b'\t');
+    }
 }
@@ -0,0 +1,284 @@
+//! Writing a document in other formats (`lazycsv convert`): delimited text,
+//! JSON, Markdown and Excel workbooks.
+//!
+//! Delimited text goes through the same writer as `:w`; the other formats
+//! are built from the parsed document, so every format sees the file the
+//! way the TUI does.
+
+use super::compression::{uncompressed_path, Compression};
+use super::{synthesized_header, xlsx, Document};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Format of a converted file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    /// Pipe-separated
+    Psv,
+    /// An array with an object per row, keyed by header
+    Json,
+    /// A GitHub-flavored Markdown table
+    Markdown,
+    /// An Excel workbook with one sheet
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Names accepted by `--to`
+    pub const NAMES: &'static str = "csv, tsv, psv, json, markdown or xlsx";
+
+    /// Parse a format name (`--to json`)
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "psv" => Ok(Self::Psv),
+            "json" => Ok(Self::Json),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "xlsx" => Ok(Self::Xlsx),
+            _ => Err(format!("Unknown format {} (use {})", name, Self::NAMES)),
+        }
+    }
+
+    /// The format a file name asks for, by extension (`out.json.gz` is JSON)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = uncompressed_path(path).extension()?.to_str()?.to_string();
+        Self::parse(&extension).ok()
+    }
+
+    /// The document in this format; `write_headers` is false for a file
+    /// read with `--no-headers`, whose synthesized names are only used
+    /// where the format needs names (JSON keys, the Markdown header)
+    pub fn render(&self, document: &Document, write_headers: bool) -> Result<Vec<u8>> {
+        match self {
+            Self::Csv => delimited(document, b',', write_headers),
+            Self::Tsv => delimited(document, b'\t', write_headers),
+            Self::Psv => delimited(document, b'|', write_headers),
+            Self::Json => Ok(json(document).into_bytes()),
+            Self::Markdown => Ok(markdown(document).into_bytes()),
+            Self::Xlsx => xlsx::workbook(document, write_headers),
+        }
+    }
+
+    /// Write the document to `path` in this format, compressed if the
+    /// name ends in `.gz` or `.zst`
+    pub fn write(&self, document: &Document, path: &Path, write_headers: bool) -> Result<()> {
+        let mut bytes = self.render(document, write_headers)?;
+        if let Some(compression) = Compression::from_path(path) {
+            bytes = compression.compress(&bytes)?;
+        }
+        std::fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Delimited text as `:w` writes it, keeping line endings and quoting
+fn delimited(document: &Document, delimiter: u8, write_headers: bool) -> Result<Vec<u8>> {
+    let mut writer = document.write_style.writer(delimiter, Vec::new());
+    if write_headers {
+        writer.write_record(&document.headers)?;
+    }
+    for row in &document.rows {
+        writer.write_record(row)?;
+    }
+    writer.into_inner().context("Failed to serialize CSV")
+}
+
+/// Column names, made up for columns without one
+fn column_names(document: &Document) -> Vec<String> {
+    let columns = document
+        .rows
+        .iter()
+        .map(Vec::len)
+        .fold(document.column_count(), usize::max);
+    (0..columns)
+        .map(|col| match document.headers.get(col) {
+            Some(header) if !header.is_empty() => header.clone(),
+            _ => synthesized_header(col),
+        })
+        .collect()
+}
+
+/// An array of objects, one per row, with the columns in file order and
+/// every value a string
+fn json(document: &Document) -> String {
+    let names: Vec<String> = column_names(document)
+        .iter()
+        .map(|name| serde_json::Value::from(name.as_str()).to_string())
+        .collect();
+    let mut out = String::from("[");
+    for (i, row) in document.rows.iter().enumerate() {
+        out.push_str(if i == 0 { "\n  {" } else { ",\n  {" });
+        let fields: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(col, name)| {
+                let value = row.get(col).map_or("", String::as_str);
+                format!("{}: {}", name, serde_json::Value::from(value))
+            })
+            .collect();
+        out.push_str(&fields.join(", "));
+        out.push('}');
+    }
+    out.push_str(if document.rows.is_empty() {
+        "]\n"
+    } else {
+        "\n]\n"
+    });
+    out
+}
+
+/// A Markdown table cell: pipes escaped, line breaks as `<br>`
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+/// A GitHub-flavored Markdown table
+fn markdown(document: &Document) -> String {
+    let names = column_names(document);
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let mut out = line(names.iter().map(|name| markdown_cell(name)).collect());
+    out.push_str(&line(vec!["---".to_string(); names.len()]));
+    for row in &document.rows {
+        out.push_str(&line(
+            (0..names.len())
+                .map(|col| markdown_cell(row.get(col).map_or("", String::as_str)))
+                .collect(),
+        ));
+    }
+    out
+}
+
+/// Convert `input` to `output`, reading it as `config` says; the format is
+/// `format`, else the one `output`'s extension names
+pub fn convert(
+    input: &Path,
+    output: &Path,
+    format: Option<ExportFormat>,
+    config: &crate::session::FileConfig,
+) -> Result<usize> {
+    let format = format
+        .or_else(|| ExportFormat::from_path(output))
+        .with_context(|| {
+            format!(
+                "Can't tell the format of {} from its name; use --to {}",
+                output.display(),
+                ExportFormat::NAMES
+            )
+        })?;
+    // Lines skipped with --skip-lines or --comment-char are left out
+    let document = config
+        .read_document(input)
+        .with_context(|| format!("Failed to load {}", input.display()))?;
+    let write_headers = !config.no_headers;
+    if output == Path::new("-") {
+        let bytes = format.render(&document, write_headers)?;
+        std::io::Write::write_all(&mut std::io::stdout(), &bytes)
+            .context("Failed to write to stdout")?;
+    } else {
+        format.write(&document, output, write_headers)?;
+    }
+    Ok(document.row_count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["name".to_string(), "note".to_string()],
+            rows: vec![
+                vec!["Alice".to_string(), "a|b".to_string()],
+                vec!["Bob".to_string(), "say \"hi\"\nthere".to_string()],
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        let format = |name: &str| ExportFormat::from_path(Path::new(name));
+        assert_eq!(format("out.json"), Some(ExportFormat::Json));
+        assert_eq!(format("out.JSON.gz"), Some(ExportFormat::Json));
+        assert_eq!(format("out.md"), Some(ExportFormat::Markdown));
+        assert_eq!(format("out.xlsx"), Some(ExportFormat::Xlsx));
+        assert_eq!(format("out.tsv"), Some(ExportFormat::Tsv));
+        assert_eq!(format("out.txt"), None);
+        assert_eq!(format("out"), None);
+        assert!(ExportFormat::parse("yaml").is_err());
+    }
+
+    #[test]
+    fn test_render_json_and_markdown() {
+        let json = ExportFormat::Json.render(&document(), true).unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "[\n  {\"name\": \"Alice\", \"note\": \"a|b\"},\n  {\"name\": \"Bob\", \"note\": \"say \\\"hi\\\"\\nthere\"}\n]\n"
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_slice(&ExportFormat::Json.render(&document(), true).unwrap()).unwrap();
+        assert_eq!(parsed[1]["note"], "say \"hi\"\nthere");
+        let empty = Document::default();
+        assert_eq!(ExportFormat::Json.render(&empty, true).unwrap(), b"[]\n");
+
+        let markdown = ExportFormat::Markdown.render(&document(), true).unwrap();
+        assert_eq!(
+            String::from_utf8(markdown).unwrap(),
+            "| name | note |\n| --- | --- |\n| Alice | a\\|b |\n| Bob | say \"hi\"<br>there |\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_picks_format_from_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.tsv");
+        std::fs::write(&input, "name\tcity\nAlice\tOslo, Norway\n").unwrap();
+        let config = crate::session::FileConfig::new();
+
+        let csv = dir.path().join("data.csv");
+        assert_eq!(convert(&input, &csv, None, &config).unwrap(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&csv).unwrap(),
+            "name,city\nAlice,\"Oslo, Norway\"\n"
+        );
+        let json = dir.path().join("data.json.gz");
+        convert(&input, &json, None, &config).unwrap();
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut crate::csv::open_file(&json).unwrap(), &mut text)
+            .unwrap();
+        assert!(text.contains("\"city\": \"Oslo, Norway\""));
+
+        let unknown = dir.path().join("data.txt");
+        let err = convert(&input, &unknown, None, &config).unwrap_err();
+        assert!(format!("{:#}", err).contains("use --to"));
+        convert(&input, &unknown, Some(ExportFormat::Markdown), &config).unwrap();
+        assert!(std::fs::read_to_string(&unknown)
+            .unwrap()
+            .starts_with("| name | city |"));
+    }
+
+    #[test]
+    fn test_convert_keeps_rows_starting_with_comment_char() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.csv");
+        std::fs::write(&input, "# generated\nid,tag\n1,a\n#2,b\n3,c\n").unwrap();
+        let config = crate::session::FileConfig::new().with_preamble(crate::csv::Preamble {
+            skip_lines: 0,
+            comment: Some(b'#'),
+        });
+
+        // Only the comment before the header is left out
+        let output = dir.path().join("out.csv");
+        assert_eq!(convert(&input, &output, None, &config).unwrap(), 3);
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "id,tag\n1,a\n#2,b\n3,c\n"
+        );
+    }
+}
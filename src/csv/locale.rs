@@ -0,0 +1,566 @@
+//! How a locale writes numbers and dates (the `locale` option).
+//!
+//! `1.234,5` is a number in German and `1,234.5` in US English; `03/04/2024`
+//! is the 3rd of April in Britain and March 4th in the US. Type inference,
+//! `:sort` and the statistics read values the way the locale writes them,
+//! and the statistics panel and `aligndecimals` show them that way. Plain
+//! machine numbers (`1234.5`) and ISO dates are understood in every locale.
+
+use serde::Deserialize;
+use std::fmt;
+
+/// Languages writing a decimal comma
+const COMMA_DECIMAL: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "is", "it", "lt",
+    "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+/// Of those, the languages grouping thousands with a space instead of `.`
+const SPACE_GROUPING: &[&str] = &[
+    "bg", "cs", "et", "fi", "fr", "hu", "lt", "lv", "nb", "nn", "no", "pl", "ru", "sk", "sv", "uk",
+];
+
+/// Languages writing dates year first (`2024/03/01`)
+const YEAR_FIRST: &[&str] = &["ja", "ko", "zh"];
+
+/// English-speaking countries writing the month first (`03/01/2024`)
+const MONTH_FIRST: &[&str] = &["", "us", "ph", "ca"];
+
+/// Order of the parts of a date written with a two-digit day and month
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// Only year-first dates (`2024-03-01`)
+    YearFirst,
+    /// `01/03/2024` and `01.03.2024`
+    DayFirst,
+    /// `03/01/2024`
+    MonthFirst,
+}
+
+/// Number and date conventions of a locale
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Locale {
+    name: String,
+    decimal: char,
+    grouping: Option<char>,
+    dates: DateOrder,
+}
+
+impl Default for Locale {
+    /// The `C` locale: machine numbers and ISO dates only
+    fn default() -> Self {
+        Self {
+            name: "C".to_string(),
+            decimal: '.',
+            grouping: None,
+            dates: DateOrder::YearFirst,
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    /// A locale name, or `auto` for the environment's
+    fn try_from(name: String) -> Result<Self, String> {
+        if name.eq_ignore_ascii_case("auto") {
+            Ok(Self::from_env())
+        } else {
+            Self::parse(&name)
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Locale {
+    /// Conventions of a locale name as in `LANG`: `de_DE.UTF-8`, `en-GB`,
+    /// `fr`, `C` or `POSIX`
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        let base = name.split(['.', '@']).next().unwrap_or("");
+        if base.is_empty() || base.eq_ignore_ascii_case("c") || base.eq_ignore_ascii_case("posix") {
+            return Ok(Self {
+                name: if name.is_empty() { "C" } else { name }.to_string(),
+                ..Self::default()
+            });
+        }
+
+        let (language, country) = base.split_once(['_', '-']).unwrap_or((base, ""));
+        let valid = (2..=3).contains(&language.len())
+            && language.chars().all(|c| c.is_ascii_alphabetic())
+            && country.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid {
+            return Err(format!(
+                "Invalid locale: {} (use a name like en_US or de_DE)",
+                name
+            ));
+        }
+        let language = language.to_ascii_lowercase();
+        let country = country.to_ascii_lowercase();
+
+        let (decimal, grouping) = if country == "ch" {
+            ('.', '\'')
+        } else if !COMMA_DECIMAL.contains(&language.as_str()) {
+            ('.', ',')
+        } else if SPACE_GROUPING.contains(&language.as_str()) {
+            (',', ' ')
+        } else {
+            (',', '.')
+        };
+        let dates = if YEAR_FIRST.contains(&language.as_str()) {
+            DateOrder::YearFirst
+        } else if language == "en" && MONTH_FIRST.contains(&country.as_str()) {
+            DateOrder::MonthFirst
+        } else {
+            DateOrder::DayFirst
+        };
+        Ok(Self {
+            name: name.to_string(),
+            decimal,
+            grouping: Some(grouping),
+            dates,
+        })
+    }
+
+    /// The locale of the environment (`LC_ALL`, `LC_NUMERIC`, then `LANG`),
+    /// `C` if none is set or it can't be read
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|name| Self::parse(&name).ok())
+            .unwrap_or_default()
+    }
+
+    /// The decimal separator
+    pub fn decimal(&self) -> char {
+        self.decimal
+    }
+
+    /// How dates with a two-digit day and month are read
+    pub fn date_order(&self) -> DateOrder {
+        self.dates
+    }
+
+    /// The locale reading the dates of a column holding `values`: day or
+    /// month first as the values show it (only the day can be over 12),
+    /// else as the locale writes them; in a year-first locale, ambiguous
+    /// dates are read month first with `/` and day first with `.`
+    pub fn for_column<'a>(&self, values: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut day_first = false;
+        let mut month_first = false;
+        let mut separator = None;
+        for (first, second, sep) in values.into_iter().filter_map(short_date_parts) {
+            day_first |= first > 12;
+            month_first |= second > 12;
+            separator.get_or_insert(sep);
+        }
+        let dates = match (day_first, month_first) {
+            (true, false) => DateOrder::DayFirst,
+            (false, true) => DateOrder::MonthFirst,
+            _ if self.dates != DateOrder::YearFirst => return self.clone(),
+            (false, false) => match separator {
+                Some('.') => DateOrder::DayFirst,
+                Some(_) => DateOrder::MonthFirst,
+                None => return self.clone(),
+            },
+            (true, true) => return self.clone(),
+        };
+        Self {
+            dates,
+            ..self.clone()
+        }
+    }
+
+    /// A finite number written the locale's way (`1.234,5` in German) or
+    /// as a plain machine number (`1234.5`)
+    pub fn parse_number(&self, value: &str) -> Option<f64> {
+        let value = value.trim();
+        self.canonical_number(value)
+            .and_then(|number| number.parse::<f64>().ok())
+            .or_else(|| plain_number(value))
+            .filter(|n| n.is_finite())
+    }
+
+    /// A number written without a fraction or exponent (`1.234` in German,
+    /// `1,234` in US English, `-42` anywhere)
+    pub fn parse_integer(&self, value: &str) -> Option<i64> {
+        let value = value.trim();
+        value.parse::<i64>().ok().or_else(|| {
+            self.canonical_number(value)
+                .filter(|number| !number.contains(['.', 'e']))
+                .and_then(|number| number.parse().ok())
+        })
+    }
+
+    /// `value` as written the locale's way, rewritten as a machine number
+    /// (`1.234,5` becomes `1234.5`)
+    fn canonical_number(&self, value: &str) -> Option<String> {
+        let (sign, unsigned) = match value.strip_prefix(['-', '+']) {
+            Some(rest) => (&value[..1], rest),
+            None => ("", value),
+        };
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+            None => (unsigned, None),
+        };
+        let (int, fraction) = match mantissa.split_once(self.decimal) {
+            Some((int, fraction)) => (int, Some(fraction)),
+            None => (mantissa, None),
+        };
+
+        let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        let int_digits: String = if int.is_empty() {
+            fraction.filter(|f| all_digits(f))?;
+            "0".to_string()
+        } else if all_digits(int) {
+            int.to_string()
+        } else {
+            self.ungroup(int)?
+        };
+        if fraction.is_some_and(|f| !all_digits(f)) {
+            return None;
+        }
+        if exponent.is_some_and(|e| !all_digits(e.strip_prefix(['-', '+']).unwrap_or(e))) {
+            return None;
+        }
+
+        let mut number = format!("{}{}", sign, int_digits);
+        if let Some(fraction) = fraction {
+            number.push('.');
+            number.push_str(fraction);
+        }
+        if let Some(exponent) = exponent {
+            number.push('e');
+            number.push_str(exponent);
+        }
+        Some(number)
+    }
+
+    /// Digits of an integer part grouped by thousands (`1.234.567`)
+    fn ungroup(&self, int: &str) -> Option<String> {
+        let grouping = self.grouping?;
+        let is_separator =
+            |c: char| c == grouping || (grouping == ' ' && matches!(c, '\u{a0}' | '\u{202f}'));
+        let mut groups = int.split(is_separator);
+        let first = groups.next()?;
+        if first.is_empty() || first.len() > 3 || !first.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut digits = first.to_string();
+        for group in groups {
+            if group.len() != 3 || !group.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            digits.push_str(group);
+        }
+        Some(digits)
+    }
+
+    /// A number the way the locale writes it, with at most four decimals
+    /// (`1.234,5` in German)
+    pub fn format_number(&self, value: f64) -> String {
+        let text = if value.fract() == 0.0 && value.abs() < 1e15 {
+            format!("{}", value as i64)
+        } else {
+            let text = format!("{:.4}", value);
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        };
+        self.localize(&text, self.grouping)
+    }
+
+    /// A number cell shown with `decimals` places (else the ones it has)
+    /// and, with `group`, thousands grouped the locale's way (`,` in the
+    /// `C` locale); None unless the cell is a number
+    pub fn format_fixed(
+        &self,
+        value: &str,
+        decimals: Option<usize>,
+        group: bool,
+    ) -> Option<String> {
+        let number = self.parse_number(value)?;
+        let text = match decimals {
+            Some(decimals) => format!("{:.*}", decimals, number),
+            // Keep the digits written (`1.50` stays `1.50`)
+            None => self
+                .canonical_number(value.trim())
+                .filter(|text| !text.contains('e') && text.parse::<f64>().is_ok())
+                .map(|text| text.trim_start_matches('+').to_string())
+                .unwrap_or_else(|| number.to_string()),
+        };
+        let grouping = group.then(|| {
+            self.grouping
+                .unwrap_or(if self.decimal == ',' { '.' } else { ',' })
+        });
+        Some(self.localize(&text, grouping))
+    }
+
+    /// A number cell in scientific notation with `decimals` places
+    /// (`1.23e9`); None unless the cell is a number
+    pub fn format_scientific(&self, value: &str, decimals: usize) -> Option<String> {
+        let number = self.parse_number(value)?;
+        Some(format!("{:.*e}", decimals, number).replace('.', &self.decimal.to_string()))
+    }
+
+    /// A machine number (`-1234.5`) with the locale's decimal point and
+    /// `grouping` between thousands
+    fn localize(&self, text: &str, grouping: Option<char>) -> String {
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text),
+        };
+        let (int, fraction) = match unsigned.split_once('.') {
+            Some((int, fraction)) => (int, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut formatted = sign.to_string();
+        for (i, digit) in int.chars().enumerate() {
+            if let Some(grouping) = grouping {
+                if i > 0 && (int.len() - i).is_multiple_of(3) {
+                    formatted.push(grouping);
+                }
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// A date as `YYYY-MM-DD`, followed by its time if it has one, so dates
+    /// sort as text: ISO dates (`2024-03-01`, `2024/03/01`) in any locale,
+    /// and day or month first (`01.03.2024`, `03/01/2024`) as the locale
+    /// writes them
+    pub fn date_key(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        iso_date_key(value).or_else(|| self.local_date_key(value))
+    }
+
+    fn local_date_key(&self, value: &str) -> Option<String> {
+        let (date, time) = match value.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
+        let separator = date.chars().find(|c| matches!(c, '/' | '.' | '-'))?;
+        let parts: Vec<&str> = date.split(separator).collect();
+        let [first, second, year] = parts.as_slice() else {
+            return None;
+        };
+        let (day, month) = match self.dates {
+            DateOrder::YearFirst => return None,
+            DateOrder::DayFirst => (first, second),
+            DateOrder::MonthFirst => (second, first),
+        };
+        let number = |part: &str, max_len: usize| -> Option<u32> {
+            (!part.is_empty() && part.len() <= max_len && part.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| part.parse().ok())
+                .flatten()
+        };
+        let day = number(day, 2).filter(|day| (1..=31).contains(day))?;
+        let month = number(month, 2).filter(|month| (1..=12).contains(month))?;
+        let year = number(year, 4).filter(|_| year.len() == 4)?;
+        if time.is_some_and(|time| !is_time(time)) {
+            return None;
+        }
+        let mut key = format!("{:04}-{:02}-{:02}", year, month, day);
+        if let Some(time) = time {
+            key.push(' ');
+            key.push_str(time);
+        }
+        Some(key)
+    }
+}
+
+/// A plain machine number (`1234.5`, `-2e3`); `inf` and `nan` are not
+fn plain_number(value: &str) -> Option<f64> {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E'))
+        .then(|| value.parse::<f64>().ok())
+        .flatten()
+}
+
+/// `YYYY-MM-DD` or `YYYY/MM/DD`, optionally followed by a time after a
+/// space or `T`, with `/` turned into `-`
+fn iso_date_key(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 10 || !bytes[..10].is_ascii() {
+        return None;
+    }
+    let (date, time) = value.split_at(10);
+    let separator = bytes[4];
+    let digits = |range: std::ops::Range<usize>| -> Option<u32> {
+        let part = &date[range];
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())
+            .flatten()
+    };
+    let valid_date = matches!(separator, b'-' | b'/')
+        && bytes[7] == separator
+        && digits(0..4).is_some()
+        && digits(5..7).is_some_and(|month| (1..=12).contains(&month))
+        && digits(8..10).is_some_and(|day| (1..=31).contains(&day));
+    let valid_time = time.is_empty() || time.strip_prefix([' ', 'T']).is_some_and(is_time);
+    (valid_date && valid_time).then(|| value.replace('/', "-"))
+}
+
+/// The first two numbers and the separator of a date written with the
+/// year last (`25/12/2024`, `3.1.2024 10:30`)
+fn short_date_parts(value: &str) -> Option<(u32, u32, char)> {
+    let date = value.trim().split([' ', 'T']).next()?;
+    let separator = date.chars().find(|c| matches!(c, '/' | '.' | '-'))?;
+    let parts: Vec<&str> = date.split(separator).collect();
+    let [first, second, year] = parts.as_slice() else {
+        return None;
+    };
+    let number = |part: &str| -> Option<u32> {
+        ((1..=2).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| part.parse().ok())
+            .flatten()
+    };
+    let is_year = year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit());
+    Some((number(first)?, number(second)?, separator)).filter(|_| is_year)
+}
+
+/// Starts like `HH:MM`
+fn is_time(time: &str) -> bool {
+    time.len() >= 5 && time.as_bytes()[2] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(name: &str) -> Locale {
+        Locale::parse(name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_locale_names() {
+        assert_eq!(locale("de_DE.UTF-8").decimal(), ',');
+        assert_eq!(locale("de_DE.UTF-8").to_string(), "de_DE.UTF-8");
+        assert_eq!(locale("de_CH").decimal(), '.');
+        assert_eq!(locale("en-GB").date_order(), DateOrder::DayFirst);
+        assert_eq!(locale("en_US").date_order(), DateOrder::MonthFirst);
+        assert_eq!(locale("ja_JP").date_order(), DateOrder::YearFirst);
+        assert_eq!(
+            locale("POSIX"),
+            Locale {
+                name: "POSIX".to_string(),
+                ..Locale::default()
+            }
+        );
+        assert!(Locale::parse("not a locale").is_err());
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        let german = locale("de_DE");
+        assert_eq!(german.parse_number("1.234,5"), Some(1234.5));
+        assert_eq!(german.parse_number("-0,25"), Some(-0.25));
+        // Plain machine numbers read the same everywhere
+        assert_eq!(german.parse_number("3.5"), Some(3.5));
+        assert_eq!(german.parse_integer("1.234"), Some(1234));
+        assert_eq!(german.parse_integer("1,5"), None);
+
+        let us = locale("en_US");
+        assert_eq!(us.parse_number("1,234.5"), Some(1234.5));
+        assert_eq!(us.parse_integer("1,234,567"), Some(1234567));
+        assert_eq!(us.parse_number("1,5"), None);
+
+        let french = locale("fr_FR");
+        assert_eq!(french.parse_number("1 234,5"), Some(1234.5));
+        assert_eq!(french.parse_number("1\u{202f}234"), Some(1234.0));
+
+        let c = Locale::default();
+        assert_eq!(c.parse_number("1,234"), None);
+        assert_eq!(c.parse_number("1e3"), Some(1000.0));
+        assert_eq!(c.parse_number("inf"), None);
+        assert_eq!(c.parse_number("NaN"), None);
+    }
+
+    #[test]
+    fn test_format_numbers() {
+        assert_eq!(locale("de_DE").format_number(-1234567.5), "-1.234.567,5");
+        assert_eq!(locale("en_US").format_number(1234.0), "1,234");
+        assert_eq!(locale("fr_FR").format_number(0.125), "0,125");
+        assert_eq!(Locale::default().format_number(1.0 / 3.0), "0.3333");
+        assert_eq!(Locale::default().format_number(1234.0), "1234");
+    }
+
+    #[test]
+    fn test_format_number_cells() {
+        let c = Locale::default();
+        assert_eq!(
+            c.format_fixed("1234567.50", None, true).unwrap(),
+            "1,234,567.50"
+        );
+        assert_eq!(
+            c.format_fixed("-1234.567", Some(2), false).unwrap(),
+            "-1234.57"
+        );
+        assert_eq!(c.format_fixed("+42", None, false).unwrap(), "42");
+        assert_eq!(c.format_fixed("1.5e3", None, true).unwrap(), "1,500");
+        assert_eq!(c.format_fixed("n/a", None, true), None);
+        let german = locale("de_DE");
+        assert_eq!(
+            german.format_fixed("1234,5", Some(2), true).unwrap(),
+            "1.234,50"
+        );
+        assert_eq!(c.format_scientific("1234567", 2).unwrap(), "1.23e6");
+        assert_eq!(german.format_scientific("0,00012", 1).unwrap(), "1,2e-4");
+    }
+
+    #[test]
+    fn test_date_order_read_from_column() {
+        let c = Locale::default();
+        // A day over 12 gives the order away
+        let british = c.for_column(["02/01/2024", "25/12/2024"]);
+        assert_eq!(british.date_order(), DateOrder::DayFirst);
+        assert_eq!(british.date_key("02/01/2024").unwrap(), "2024-01-02");
+        assert_eq!(
+            locale("en_US").for_column(["13/01/2024"]).date_order(),
+            DateOrder::DayFirst
+        );
+        let american = locale("en_GB").for_column(["12/31/2024 10:30"]);
+        assert_eq!(american.date_order(), DateOrder::MonthFirst);
+        // Ambiguous: as the locale writes them, else by the separator
+        assert_eq!(
+            locale("en_GB").for_column(["02/01/2024"]).date_order(),
+            DateOrder::DayFirst
+        );
+        assert_eq!(
+            c.for_column(["2/1/2024"]).date_order(),
+            DateOrder::MonthFirst
+        );
+        assert_eq!(c.for_column(["2.1.2024"]).date_order(), DateOrder::DayFirst);
+        assert_eq!(c.for_column(["2024-01-02", "x"]), c);
+        assert_eq!(c.for_column(["13/13/2024"]), c);
+    }
+
+    #[test]
+    fn test_date_keys() {
+        let british = locale("en_GB");
+        assert_eq!(british.date_key("03/04/2024").unwrap(), "2024-04-03");
+        assert_eq!(
+            locale("en_US").date_key("03/04/2024 10:30").unwrap(),
+            "2024-03-04 10:30"
+        );
+        assert_eq!(locale("de").date_key("1.3.2024").unwrap(), "2024-03-01");
+        assert_eq!(british.date_key("2024/03/01").unwrap(), "2024-03-01");
+        assert_eq!(british.date_key("31/13/2024"), None);
+        assert_eq!(Locale::default().date_key("03/04/2024"), None);
+        assert_eq!(Locale::default().date_key("2024-13-01"), None);
+    }
+}
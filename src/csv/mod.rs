@@ -1,8 +1,20 @@
 //! CSV document parsing and representation
 //!
-//! Handles loading CSV files from disk, parsing with configurable
-//! delimiters and encoding, and providing in-memory document access.
+//! Handles loading CSV files from disk (optionally gzip/zstd-compressed),
+//! parsing with configurable delimiters and encoding, and providing
+//! in-memory document access.
 
+pub mod compression;
 pub mod document;
+pub mod export;
+pub mod locale;
+pub mod sniff;
+pub mod types;
+mod xlsx;
 
-pub use document::Document;
+pub use compression::{open_file, read_file, Compression};
+pub use document::{delimiter_for_path, synthesized_header, Document, Preamble, WriteStyle};
+pub use export::ExportFormat;
+pub use locale::Locale;
+pub use sniff::{delimiter_label, sniff_delimiter};
+pub use types::{infer_column_types, ColumnType};
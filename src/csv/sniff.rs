@@ -0,0 +1,144 @@
+//! Delimiter detection by content sniffing
+//!
+//! Used when no delimiter was given on the command line and the file
+//! extension doesn't imply one, so semicolon, tab and pipe separated files
+//! open correctly without `--delimiter`.
+
+/// Delimiters considered when sniffing, in order of preference for ties
+pub const CANDIDATE_DELIMITERS: &[u8] = b",;\t|";
+
+/// Number of lines sampled from the start of the file
+pub const SNIFF_SAMPLE_LINES: usize = 50;
+
+/// Detect the delimiter from the first lines of `content`
+///
+/// Each candidate is scored by how consistently it splits the sample into
+/// the same number of fields (more than one), then by that field count.
+/// Returns None when no candidate splits any line into multiple fields.
+pub fn sniff_delimiter(content: &str) -> Option<u8> {
+    let sample_end = content
+        .match_indices('\n')
+        .nth(SNIFF_SAMPLE_LINES - 1)
+        .map(|(idx, _)| idx)
+        .unwrap_or(content.len());
+    let sample = &content[..sample_end];
+
+    let mut best: Option<(u8, usize, usize)> = None; // (delimiter, consistent rows, fields)
+    for &delimiter in CANDIDATE_DELIMITERS {
+        let counts = field_counts(sample, delimiter);
+        let Some((fields, consistent)) = most_common(&counts) else {
+            continue;
+        };
+        if fields < 2 {
+            continue;
+        }
+
+        let better = match best {
+            None => true,
+            Some((_, best_consistent, best_fields)) => {
+                (consistent, fields) > (best_consistent, best_fields)
+            }
+        };
+        if better {
+            best = Some((delimiter, consistent, fields));
+        }
+    }
+
+    best.map(|(delimiter, ..)| delimiter)
+}
+
+/// Field count of each record in the sample (quote-aware)
+fn field_counts(sample: &str, delimiter: u8) -> Vec<usize> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(sample.as_bytes());
+
+    reader
+        .records()
+        .map_while(Result::ok)
+        .filter(|record| !(record.len() == 1 && record[0].is_empty()))
+        .map(|record| record.len())
+        .collect()
+}
+
+/// Most common value and how often it occurs (larger value wins ties)
+fn most_common(counts: &[usize]) -> Option<(usize, usize)> {
+    let mut sorted = counts.to_vec();
+    sorted.sort_unstable();
+
+    let mut best: Option<(usize, usize)> = None;
+    for chunk in sorted.chunk_by(|a, b| a == b) {
+        let candidate = (chunk[0], chunk.len());
+        if best.is_none_or(|(_, n)| candidate.1 >= n) {
+            best = Some(candidate);
+        }
+    }
+    best
+}
+
+/// Short human-readable name for a delimiter (for the status bar)
+pub fn delimiter_label(delimiter: u8) -> String {
+    match delimiter {
+        b'\t' => "tab".to_string(),
+        b' ' => "space".to_string(),
+        d => (d as char).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_comma() {
+        let content = "a,b,c\n1,2,3\n4,5,6\n";
+        assert_eq!(sniff_delimiter(content), Some(b','));
+    }
+
+    #[test]
+    fn test_sniff_semicolon_with_decimal_commas() {
+        // European CSV: semicolon separated, comma as decimal separator
+        let content = "name;price;qty\nWidget;1,50;3\nGadget;12,00;1\nThing;0,99;10\n";
+        assert_eq!(sniff_delimiter(content), Some(b';'));
+    }
+
+    #[test]
+    fn test_sniff_tab_and_pipe() {
+        assert_eq!(sniff_delimiter("a\tb\n1\t2\n"), Some(b'\t'));
+        assert_eq!(sniff_delimiter("a|b|c\n1|2|3\n"), Some(b'|'));
+    }
+
+    #[test]
+    fn test_sniff_ignores_delimiters_inside_quotes() {
+        let content = "name,note\n\"Smith; John\",ok\n\"Doe; Jane\",fine\n";
+        assert_eq!(sniff_delimiter(content), Some(b','));
+    }
+
+    #[test]
+    fn test_sniff_single_column_returns_none() {
+        assert_eq!(sniff_delimiter("name\nalice\nbob\n"), None);
+        assert_eq!(sniff_delimiter(""), None);
+    }
+
+    #[test]
+    fn test_sniff_only_samples_first_lines() {
+        let mut content = String::from("a;b\n");
+        for _ in 0..SNIFF_SAMPLE_LINES {
+            content.push_str("1;2\n");
+        }
+        // Lines past the sample window don't affect the result
+        for _ in 0..(SNIFF_SAMPLE_LINES * 2) {
+            content.push_str("1,2,3,4\n");
+        }
+        assert_eq!(sniff_delimiter(&content), Some(b';'));
+    }
+
+    #[test]
+    fn test_delimiter_label() {
+        assert_eq!(delimiter_label(b','), ",");
+        assert_eq!(delimiter_label(b'\t'), "tab");
+        assert_eq!(delimiter_label(b';'), ";");
+    }
+}
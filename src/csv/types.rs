@@ -0,0 +1,229 @@
+//! Column type inference.
+//!
+//! Each column gets the narrowest type all of its non-empty, non-null
+//! values fit: integers widen to floats, anything else mixed is text.
+//! Numbers and dates are read as the [`Locale`] writes them. Large files
+//! are inferred from a sample spread across the rows.
+
+use super::{Document, Locale};
+use crate::analysis::stats::is_null_token;
+use std::cmp::Ordering;
+
+/// Rows looked at when inferring the types of a large file
+pub const TYPE_SAMPLE_ROWS: usize = 1000;
+
+/// Inferred type of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    /// ISO dates (`2024-03-01`, `2024/03/01`) or dates as the locale writes
+    /// them (`01.03.2024`), optionally with a time
+    Date,
+    /// true/false or yes/no
+    Bool,
+    #[default]
+    Text,
+}
+
+impl ColumnType {
+    /// Short name shown in the header and stats
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Integer => "int",
+            Self::Float => "float",
+            Self::Date => "date",
+            Self::Bool => "bool",
+            Self::Text => "text",
+        }
+    }
+
+    /// Integers and floats, which are right-aligned and summarized
+    pub fn is_numeric(self) -> bool {
+        matches!(self, Self::Integer | Self::Float)
+    }
+
+    /// Type of a single cell; `None` for empty and null cells, which fit
+    /// any type
+    pub fn of_value(value: &str, locale: &Locale) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() || is_null_token(value) {
+            return None;
+        }
+        let kind = if locale.parse_integer(value).is_some() {
+            Self::Integer
+        } else if locale.parse_number(value).is_some() {
+            Self::Float
+        } else if locale.date_key(value).is_some() {
+            Self::Date
+        } else if parse_bool(value).is_some() {
+            Self::Bool
+        } else {
+            Self::Text
+        };
+        Some(kind)
+    }
+
+    /// Narrowest type fitting values of both types
+    pub fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+
+    /// Type of a column holding `values` (text if they are all empty)
+    pub fn infer<'a>(values: impl IntoIterator<Item = &'a str>, locale: &Locale) -> Self {
+        let mut kind: Option<Self> = None;
+        for value in values {
+            if let Some(value_kind) = Self::of_value(value, locale) {
+                let widened = kind.map_or(value_kind, |kind| kind.widen(value_kind));
+                if widened == Self::Text {
+                    return Self::Text;
+                }
+                kind = Some(widened);
+            }
+        }
+        kind.unwrap_or_default()
+    }
+
+    /// Order two non-empty values of a column of this type
+    pub fn compare(self, a: &str, b: &str, locale: &Locale) -> Ordering {
+        match self {
+            // Mixed text still puts numbers first, by value
+            Self::Integer | Self::Float | Self::Text => {
+                crate::app::sort::compare_values(a, b, locale)
+            }
+            Self::Bool => parse_bool(a).cmp(&parse_bool(b)).then(a.cmp(b)),
+            // Dates sort as text once written year first
+            Self::Date => locale.date_key(a).cmp(&locale.date_key(b)).then(a.cmp(b)),
+        }
+    }
+}
+
+/// Types of every column of the document, inferred from up to
+/// [`TYPE_SAMPLE_ROWS`] rows spread across the file (each column's dates
+/// read day or month first as its values show)
+pub fn infer_column_types(document: &Document, locale: &Locale) -> Vec<ColumnType> {
+    let step = document.rows.len().div_ceil(TYPE_SAMPLE_ROWS).max(1);
+    (0..document.column_count())
+        .map(|col| {
+            let values = document
+                .rows
+                .iter()
+                .step_by(step)
+                .map(|row| row.get(col).map_or("", String::as_str));
+            ColumnType::infer(values.clone(), &locale.for_column(values))
+        })
+        .collect()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_types() {
+        let c = Locale::default();
+        assert_eq!(ColumnType::of_value("-42", &c), Some(ColumnType::Integer));
+        assert_eq!(ColumnType::of_value(" 3.5 ", &c), Some(ColumnType::Float));
+        assert_eq!(ColumnType::of_value("1e3", &c), Some(ColumnType::Float));
+        assert_eq!(
+            ColumnType::of_value("2024-02-29", &c),
+            Some(ColumnType::Date)
+        );
+        assert_eq!(
+            ColumnType::of_value("2024/02/29T10:30:00Z", &c),
+            Some(ColumnType::Date)
+        );
+        assert_eq!(
+            ColumnType::of_value("2024-13-01", &c),
+            Some(ColumnType::Text)
+        );
+        assert_eq!(ColumnType::of_value("Yes", &c), Some(ColumnType::Bool));
+        assert_eq!(ColumnType::of_value("inf", &c), Some(ColumnType::Text));
+        assert_eq!(ColumnType::of_value("NaN", &c), Some(ColumnType::Text));
+        assert_eq!(ColumnType::of_value("", &c), None);
+        assert_eq!(ColumnType::of_value("N/A", &c), None);
+    }
+
+    #[test]
+    fn test_value_types_by_locale() {
+        let german = Locale::parse("de_DE").unwrap();
+        assert_eq!(
+            ColumnType::of_value("1.234", &german),
+            Some(ColumnType::Integer)
+        );
+        assert_eq!(
+            ColumnType::of_value("1.234,5", &german),
+            Some(ColumnType::Float)
+        );
+        assert_eq!(
+            ColumnType::of_value("31.12.2024", &german),
+            Some(ColumnType::Date)
+        );
+        let c = Locale::default();
+        assert_eq!(ColumnType::of_value("1.234,5", &c), Some(ColumnType::Text));
+        assert_eq!(
+            ColumnType::of_value("31.12.2024", &c),
+            Some(ColumnType::Text)
+        );
+    }
+
+    #[test]
+    fn test_infer_widens() {
+        let c = Locale::default();
+        assert_eq!(ColumnType::infer(["1", "", "2"], &c), ColumnType::Integer);
+        assert_eq!(ColumnType::infer(["1", "2.5"], &c), ColumnType::Float);
+        assert_eq!(ColumnType::infer(["1", "true"], &c), ColumnType::Text);
+        assert_eq!(ColumnType::infer(["", "null"], &c), ColumnType::Text);
+    }
+
+    #[test]
+    fn test_compare_by_type() {
+        let c = Locale::default();
+        assert_eq!(
+            ColumnType::Integer.compare("9", "10", &c),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!(
+            ColumnType::Text.compare("b", "A", &c),
+            std::cmp::Ordering::Greater
+        );
+        assert_eq!(
+            ColumnType::Bool.compare("yes", "false", &c),
+            std::cmp::Ordering::Greater
+        );
+        assert_eq!(
+            ColumnType::Date.compare("2024/01/02", "2024-01-10", &c),
+            std::cmp::Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_infer_column_types() {
+        let c = Locale::default();
+        let document = Document {
+            headers: vec!["id".to_string(), "when".to_string(), "note".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "2024-01-01".to_string(), "x".to_string()],
+                // Short rows count as empty cells
+                vec!["2".to_string()],
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            infer_column_types(&document, &c),
+            vec![ColumnType::Integer, ColumnType::Date, ColumnType::Text]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! A minimal Excel workbook writer for `lazycsv convert out.xlsx`: one
+//! sheet, cells as inline strings, and numbers written as numbers so
+//! spreadsheets can sum them.
+
+use super::Document;
+use crate::ui::column_to_excel_letter;
+use anyhow::{bail, Context, Result};
+use flate2::write::DeflateEncoder;
+use regex::Regex;
+use std::io::Write;
+use std::sync::OnceLock;
+
+/// Parts of the workbook other than the sheet
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+/// Longest sheet name Excel accepts
+const MAX_SHEET_NAME: usize = 31;
+
+/// Most rows and columns an Excel sheet holds
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+
+/// The document as an `.xlsx` file. Fails when it has more rows or
+/// columns than a sheet holds, as Excel won't open such a workbook.
+pub fn workbook(document: &Document, write_headers: bool) -> Result<Vec<u8>> {
+    let rows = document.rows.len() + usize::from(write_headers);
+    if rows > MAX_ROWS {
+        bail!(
+            "{} rows don't fit in an Excel sheet (at most {})",
+            rows,
+            MAX_ROWS
+        );
+    }
+    let columns = document
+        .rows
+        .iter()
+        .chain(write_headers.then_some(&document.headers))
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    if columns > MAX_COLUMNS {
+        bail!(
+            "{} columns don't fit in an Excel sheet (at most {})",
+            columns,
+            MAX_COLUMNS
+        );
+    }
+
+    let mut zip = ZipWriter::default();
+    zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+    zip.add("_rels/.rels", ROOT_RELS.as_bytes())?;
+    zip.add(
+        "xl/workbook.xml",
+        workbook_xml(&document.filename).as_bytes(),
+    )?;
+    zip.add("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes())?;
+    zip.add(
+        "xl/worksheets/sheet1.xml",
+        sheet_xml(document, write_headers).as_bytes(),
+    )?;
+    zip.finish()
+}
+
+/// The workbook part, naming the sheet after the file
+fn workbook_xml(filename: &str) -> String {
+    let stem = filename.split('.').next().unwrap_or_default();
+    // Characters Excel doesn't allow in sheet names
+    let name: String = stem
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(MAX_SHEET_NAME)
+        .collect();
+    let name = if name.is_empty() { "Sheet1" } else { &name };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        escape(name)
+    )
+}
+
+/// Whether a cell is a number Excel should see as one. Leading zeros (ZIP
+/// codes, IDs), exponents and more digits than Excel keeps stay text.
+fn is_number(value: &str) -> bool {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    NUMBER
+        .get_or_init(|| Regex::new(r"^-?(0|[1-9][0-9]{0,14})(\.[0-9]+)?$").unwrap())
+        .is_match(value)
+}
+
+/// The worksheet part
+fn sheet_xml(document: &Document, write_headers: bool) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let records = write_headers
+        .then_some(&document.headers)
+        .into_iter()
+        .chain(&document.rows);
+    for (row, cells) in records.enumerate() {
+        xml.push_str(&format!(r#"<row r="{}">"#, row + 1));
+        for (col, value) in cells.iter().enumerate() {
+            if value.is_empty() {
+                continue;
+            }
+            let address = format!("{}{}", column_to_excel_letter(col), row + 1);
+            // Headers stay text even when they look like numbers
+            if is_number(value) && !(write_headers && row == 0) {
+                xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, address, value));
+            } else {
+                xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    address,
+                    escape(value)
+                ));
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Text escaped for XML, without the control characters XML can't hold
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Just enough of the ZIP format for a workbook: deflated entries, no
+/// ZIP64, so under 4 GiB
+#[derive(Default)]
+struct ZipWriter {
+    bytes: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    /// DOS date of 1980-01-01, the earliest ZIP can hold
+    const DATE: u16 = 0x0021;
+
+    fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let mut crc = flate2::Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        let (crc, compressed_size, size) = (crc.sum(), fits(compressed.len())?, fits(data.len())?);
+        let offset = fits(self.bytes.len())?;
+
+        // Fields shared by the local header and the central directory entry
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        common.extend_from_slice(&0u16.to_le_bytes()); // flags
+        common.extend_from_slice(&8u16.to_le_bytes()); // deflate
+        common.extend_from_slice(&0u16.to_le_bytes()); // time
+        common.extend_from_slice(&Self::DATE.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&compressed_size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+
+        self.bytes.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.bytes.extend_from_slice(&common);
+        self.bytes.extend_from_slice(name.as_bytes());
+        self.bytes.extend_from_slice(&compressed);
+
+        self.central
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        self.central.extend_from_slice(&common);
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        self.central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>> {
+        let offset = fits(self.bytes.len())?;
+        let size = fits(self.central.len())?;
+        self.bytes.append(&mut self.central);
+        self.bytes.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.bytes.extend_from_slice(&0u16.to_le_bytes()); // this disk
+        self.bytes.extend_from_slice(&0u16.to_le_bytes()); // central directory disk
+        self.bytes.extend_from_slice(&self.entries.to_le_bytes());
+        self.bytes.extend_from_slice(&self.entries.to_le_bytes());
+        self.bytes.extend_from_slice(&size.to_le_bytes());
+        self.bytes.extend_from_slice(&offset.to_le_bytes());
+        self.bytes.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        Ok(self.bytes)
+    }
+}
+
+/// A size or offset as the 32 bits ZIP without ZIP64 stores
+fn fits(len: usize) -> Result<u32> {
+    u32::try_from(len).context("Workbook is too large for .xlsx")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    /// Entries of a ZIP file written by `ZipWriter`, read back from the
+    /// local headers
+    fn entries(zip: &[u8]) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        let mut at = 0;
+        let u16_at = |at: usize| u16::from_le_bytes([zip[at], zip[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes(zip[at..at + 4].try_into().unwrap()) as usize;
+        while u32_at(at) == 0x0403_4b50 {
+            let compressed = u32_at(at + 18);
+            let name_len = u16_at(at + 26);
+            let name = String::from_utf8(zip[at + 30..at + 30 + name_len].to_vec()).unwrap();
+            let start = at + 30 + name_len;
+            let mut data = String::new();
+            DeflateDecoder::new(&zip[start..start + compressed])
+                .read_to_string(&mut data)
+                .unwrap();
+            assert_eq!(u32_at(at + 22), data.len());
+            entries.push((name, data));
+            at = start + compressed;
+        }
+        entries
+    }
+
+    #[test]
+    fn test_workbook_parts() {
+        let document = Document {
+            headers: vec!["zip".to_string(), "total".to_string()],
+            rows: vec![
+                vec!["02139".to_string(), "12.50".to_string()],
+                vec!["<none> & co".to_string(), String::new()],
+            ],
+            filename: "orders.csv".to_string(),
+            ..Default::default()
+        };
+        let zip = workbook(&document, true).unwrap();
+        let entries = entries(&zip);
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/workbook.xml",
+                "xl/_rels/workbook.xml.rels",
+                "xl/worksheets/sheet1.xml"
+            ]
+        );
+        assert!(entries[2].1.contains(r#"<sheet name="orders""#));
+        let sheet = &entries[4].1;
+        assert!(sheet.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">02139</t>"#));
+        assert!(sheet.contains(r#"<c r="B2"><v>12.50</v></c>"#));
+        assert!(sheet.contains("&lt;none&gt; &amp; co"));
+        assert!(!sheet.contains(r#"r="B3""#));
+        // The central directory lists every entry
+        assert_eq!(
+            u16::from_le_bytes([zip[zip.len() - 12], zip[zip.len() - 11]]),
+            5
+        );
+    }
+
+    #[test]
+    fn test_workbook_past_sheet_limits() {
+        let wide = Document {
+            headers: (0..=MAX_COLUMNS).map(|col| col.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(workbook(&wide, true).is_err());
+        // Without the header row, there are no cells past the limit
+        assert!(workbook(&wide, false).is_ok());
+
+        let long = Document {
+            headers: vec!["id".to_string()],
+            rows: vec![Vec::new(); MAX_ROWS],
+            ..Default::default()
+        };
+        // The header row is the one too many
+        assert!(workbook(&long, true).is_err());
+    }
+
+    #[test]
+    fn test_is_number() {
+        assert!(is_number("12"));
+        assert!(is_number("-0.5"));
+        assert!(!is_number("007"));
+        assert!(!is_number("1e5"));
+        assert!(!is_number("1,000"));
+        assert!(!is_number(""));
+    }
+}
@@ -0,0 +1,392 @@
+//! `lazycsv doctor` - environment health checks.
+//!
+//! Each check returns a `Finding` with a severity and, when something is
+//! off, a hint telling the user what to do about it. The checks take their
+//! inputs as arguments so they can be tested without a real terminal.
+
+use crate::clipboard;
+use crate::config::{self, Config};
+use crate::input::keymap;
+use crate::ui::Theme;
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Ok => "ok",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: &'static str,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Ok,
+            check,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            check,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn error(check: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            check,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Check truecolor support from the value of `$COLORTERM`
+pub fn check_truecolor(colorterm: Option<&str>) -> Finding {
+    match colorterm {
+        Some("truecolor") | Some("24bit") => Finding::ok("truecolor", "24-bit color supported"),
+        _ => Finding::warning(
+            "truecolor",
+            "COLORTERM does not advertise 24-bit color",
+            "Colors fall back to the 256-color palette; set COLORTERM=truecolor if your terminal supports it",
+        ),
+    }
+}
+
+/// Check the kitty keyboard protocol (None = could not query, e.g. not a TTY)
+pub fn check_keyboard_protocol(supported: Option<bool>) -> Finding {
+    match supported {
+        Some(true) => Finding::ok("keyboard", "Kitty keyboard protocol supported"),
+        Some(false) => Finding::warning(
+            "keyboard",
+            "Kitty keyboard protocol not supported",
+            "Some modifier combinations (e.g. Ctrl+Shift) may not be distinguishable",
+        ),
+        None => Finding::warning(
+            "keyboard",
+            "Could not query keyboard protocol (stdout is not a terminal)",
+            "Run `lazycsv doctor` directly in the terminal you use lazycsv from",
+        ),
+    }
+}
+
+/// Check for a clipboard helper on `$PATH`
+pub fn check_clipboard(path_var: Option<&OsStr>) -> Finding {
+    match clipboard::find_tool(path_var) {
+        Some(tool) => Finding::ok("clipboard", format!("Using {}", tool)),
+        None => Finding::warning(
+            "clipboard",
+            "No clipboard helper found on PATH",
+            format!(
+                "Install one of {} (OSC 52 is used as a fallback where the terminal allows it)",
+                clipboard::tool_names()
+            ),
+        ),
+    }
+}
+
+/// Check that the config file parses and its key bindings are consistent
+pub fn check_config(path: Option<&Path>) -> Vec<Finding> {
+    let Some(path) = path else {
+        return vec![Finding::warning(
+            "config",
+            "Could not determine config location",
+            "Set HOME, XDG_CONFIG_HOME or LAZYCSV_CONFIG",
+        )];
+    };
+
+    if !path.exists() {
+        return vec![Finding::ok(
+            "config",
+            format!("No config file at {} (using defaults)", path.display()),
+        )];
+    }
+
+    let config = match Config::load_from(path) {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![Finding::error(
+                "config",
+                format!("{:#}", e),
+                format!("Fix or remove {}", path.display()),
+            )]
+        }
+    };
+
+    let mut findings = vec![Finding::ok("config", format!("Loaded {}", path.display()))];
+
+    if let Err(e) = Theme::from_config(&config.theme) {
+        findings.push(Finding::error(
+            "theme",
+            e,
+            "Use color names (\"green\"), indexes (\"42\") or hex (\"#8a2be2\")",
+        ));
+    }
+
+    let problems = keymap::check_bindings(&config.keys);
+    if problems.is_empty() {
+        findings.push(Finding::ok(
+            "keys",
+            format!("{} custom key binding(s), no conflicts", config.keys.len()),
+        ));
+    }
+    for problem in problems {
+        let hint = match &problem {
+            keymap::BindingProblem::UnknownAction(_) => format!(
+                "Valid actions: {}",
+                keymap::ACTIONS
+                    .iter()
+                    .map(|a| a.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            keymap::BindingProblem::InvalidKey { .. } => {
+                "Use keys like \"n\", \"Ctrl+d\", \"F2\" or \"Enter\"".to_string()
+            }
+            keymap::BindingProblem::Conflict { .. } => {
+                "Give each action in [keys] a different key".to_string()
+            }
+            keymap::BindingProblem::ShadowsDefault { shadowed, .. } => {
+                format!("Also bind '{}' to another key in [keys]", shadowed)
+            }
+            keymap::BindingProblem::ShadowsBuiltIn { .. } => {
+                "Pick a key lazycsv doesn't already use".to_string()
+            }
+        };
+        if problem.is_error() {
+            findings.push(Finding::error("keys", problem.to_string(), hint));
+        } else {
+            findings.push(Finding::warning("keys", problem.to_string(), hint));
+        }
+    }
+
+    findings
+}
+
+/// Check that the state directory exists (or can be created) and is writable
+pub fn check_state_dir(dir: Option<&Path>) -> Finding {
+    let Some(dir) = dir else {
+        return Finding::warning(
+            "state",
+            "Could not determine state directory",
+            "Set HOME or XDG_STATE_HOME",
+        );
+    };
+
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return Finding::error(
+            "state",
+            format!("Cannot create {}: {}", dir.display(), e),
+            "Check permissions of the parent directory or set XDG_STATE_HOME",
+        );
+    }
+
+    let probe = dir.join(".doctor-probe");
+    match std::fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Finding::ok("state", format!("{} is writable", dir.display()))
+        }
+        Err(e) => Finding::error(
+            "state",
+            format!("{} is not writable: {}", dir.display(), e),
+            format!("Run `chmod u+w {}` or set XDG_STATE_HOME", dir.display()),
+        ),
+    }
+}
+
+/// Run all checks against the real environment
+pub fn run_checks() -> Vec<Finding> {
+    let colorterm = std::env::var("COLORTERM").ok();
+    let keyboard = if std::io::stdout().is_terminal() {
+        crossterm::terminal::supports_keyboard_enhancement().ok()
+    } else {
+        None
+    };
+    let path_var = std::env::var_os("PATH");
+
+    let mut findings = vec![
+        check_truecolor(colorterm.as_deref()),
+        check_keyboard_protocol(keyboard),
+        check_clipboard(path_var.as_deref()),
+    ];
+    findings.extend(check_config(config::config_path().as_deref()));
+    findings.push(check_state_dir(config::state_dir().as_deref()));
+    findings
+}
+
+/// Format findings as a human-readable report
+pub fn format_report(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let _ = writeln!(
+            out,
+            "[{:>5}] {:<10} {}",
+            finding.severity.label(),
+            finding.check,
+            finding.message
+        );
+        if let Some(hint) = &finding.hint {
+            let _ = writeln!(out, "{:19}-> {}", "", hint);
+        }
+    }
+
+    let errors = count(findings, Severity::Error);
+    let warnings = count(findings, Severity::Warning);
+    let _ = writeln!(out, "\n{} error(s), {} warning(s)", errors, warnings);
+    out
+}
+
+fn count(findings: &[Finding], severity: Severity) -> usize {
+    findings.iter().filter(|f| f.severity == severity).count()
+}
+
+/// Whether any finding is an error (doctor exits non-zero)
+pub fn has_errors(findings: &[Finding]) -> bool {
+    findings.iter().any(|f| f.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_truecolor() {
+        assert_eq!(check_truecolor(Some("truecolor")).severity, Severity::Ok);
+        assert_eq!(check_truecolor(Some("24bit")).severity, Severity::Ok);
+        assert_eq!(check_truecolor(None).severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_keyboard_protocol() {
+        assert_eq!(check_keyboard_protocol(Some(true)).severity, Severity::Ok);
+        assert_eq!(
+            check_keyboard_protocol(Some(false)).severity,
+            Severity::Warning
+        );
+        assert!(check_keyboard_protocol(None).hint.is_some());
+    }
+
+    #[test]
+    fn test_clipboard_found_on_path() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("xclip"), "").unwrap();
+
+        let finding = check_clipboard(Some(dir.path().as_os_str()));
+        assert_eq!(finding.severity, Severity::Ok);
+        assert!(finding.message.contains("xclip"));
+    }
+
+    #[test]
+    fn test_clipboard_missing() {
+        let dir = TempDir::new().unwrap();
+        let finding = check_clipboard(Some(dir.path().as_os_str()));
+        assert_eq!(finding.severity, Severity::Warning);
+        assert_eq!(check_clipboard(None).severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_config_missing_is_ok() {
+        let dir = TempDir::new().unwrap();
+        let findings = check_config(Some(&dir.path().join("config.toml")));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Ok);
+    }
+
+    #[test]
+    fn test_config_invalid_toml_is_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[keys\n").unwrap();
+
+        let findings = check_config(Some(&path));
+        assert!(has_errors(&findings));
+    }
+
+    #[test]
+    fn test_config_reports_key_conflicts() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[keys]\nnext_file = \"n\"\nprev_file = \"n\"\n").unwrap();
+
+        let findings = check_config(Some(&path));
+        assert!(has_errors(&findings));
+        assert!(findings
+            .iter()
+            .any(|f| f.check == "keys" && f.message.contains("'n'")));
+    }
+
+    #[test]
+    fn test_config_reports_invalid_theme_color() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[theme]\ninsert = \"greenish\"\n").unwrap();
+
+        let findings = check_config(Some(&path));
+        assert!(findings
+            .iter()
+            .any(|f| f.check == "theme" && f.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_state_dir_created_and_writable() {
+        let dir = TempDir::new().unwrap();
+        let state = dir.path().join("state").join("lazycsv");
+
+        let finding = check_state_dir(Some(&state));
+        assert_eq!(finding.severity, Severity::Ok);
+        assert!(state.is_dir());
+    }
+
+    #[test]
+    fn test_state_dir_blocked_by_file() {
+        let dir = TempDir::new().unwrap();
+        let blocker = dir.path().join("file");
+        std::fs::write(&blocker, "").unwrap();
+
+        let finding = check_state_dir(Some(&blocker.join("lazycsv")));
+        assert_eq!(finding.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_report_includes_hints_and_summary() {
+        let findings = vec![
+            Finding::ok("config", "Loaded"),
+            Finding::error("state", "Not writable", "chmod it"),
+        ];
+
+        let report = format_report(&findings);
+        assert!(report.contains("[error] state"));
+        assert!(report.contains("-> chmod it"));
+        assert!(report.contains("1 error(s), 0 warning(s)"));
+    }
+}
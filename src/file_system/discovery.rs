@@ -1,10 +1,176 @@
-//! Directory scanning and CSV file discovery
+//! Directory scanning and tabular file discovery
 
+use crate::csv::compression::uncompressed_path;
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::path::{Path, PathBuf};
 
-/// Scan a specific directory for CSV files
+/// File extensions recognized as tabular data when no custom list is given
+pub const DEFAULT_EXTENSIONS: &[&str] = &["csv", "tsv", "psv", "txt"];
+
+/// Files a recursive or glob scan stops at, so pointing lazycsv at a huge
+/// tree (or `/`) doesn't fill the switcher with thousands of tabs
+pub const MAX_DISCOVERED_FILES: usize = 1000;
+
+/// A shell-style pattern for `--glob`: `*` and `?` within a path
+/// component, `**` across directories, `[abc]` and `{a,b}`
+#[derive(Debug, Clone)]
+pub struct Glob {
+    regex: Regex,
+    /// Whether the pattern names directories, so it is matched against the
+    /// path under the scanned directory rather than the file name
+    has_path: bool,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        let mut in_braces = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' => {
+                    regex.push('[');
+                    if chars.peek() == Some(&'!') {
+                        chars.next();
+                        regex.push('^');
+                    }
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                        if c == '\\' || c == '[' {
+                            regex.push('\\');
+                        }
+                        regex.push(c);
+                    }
+                    regex.push(']');
+                }
+                '{' if !in_braces => {
+                    in_braces = true;
+                    regex.push_str("(?:");
+                }
+                '}' if in_braces => {
+                    in_braces = false;
+                    regex.push(')');
+                }
+                ',' if in_braces => regex.push('|'),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        let regex =
+            Regex::new(&regex).with_context(|| format!("Invalid glob pattern {}", pattern))?;
+        Ok(Self {
+            regex,
+            has_path: pattern.contains('/'),
+        })
+    }
+
+    /// Check a file, given by its path under the scanned directory
+    pub fn matches(&self, relative: &Path) -> bool {
+        let text = if self.has_path {
+            relative.to_string_lossy().replace('\\', "/")
+        } else {
+            relative
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        self.regex.is_match(&text)
+    }
+}
+
+/// Files found by [`scan_tree`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeScan {
+    pub files: Vec<PathBuf>,
+    /// The scan stopped at `MAX_DISCOVERED_FILES`
+    pub truncated: bool,
+}
+
+/// Scan `dir` for tabular files, or for files matching `glob` when given,
+/// looking in subdirectories too when `recursive` or the glob names
+/// directories (`**/*.csv`, `2024/*.csv`). Hidden and symlinked
+/// directories are skipped, as are those that can't be read; the scan
+/// stops at `MAX_DISCOVERED_FILES` files.
+pub fn scan_tree<S: AsRef<str>>(
+    dir: &Path,
+    extensions: &[S],
+    recursive: bool,
+    glob: Option<&Glob>,
+) -> Result<TreeScan> {
+    let recursive = recursive || glob.is_some_and(|glob| glob.has_path);
+    let mut scan = TreeScan::default();
+    let mut pending = vec![dir.to_path_buf()];
+    let mut first = true;
+    while let Some(current) = pending.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(entries) => entries,
+            // Only the directory asked for has to be readable
+            Err(e) if first => return Err(e).context("Failed to read directory"),
+            Err(_) => continue,
+        };
+        first = false;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(kind) = entry.file_type() else {
+                continue;
+            };
+            if kind.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if recursive && !hidden {
+                    pending.push(path);
+                }
+                continue;
+            }
+            if !path.is_file() {
+                continue;
+            }
+            let relative = path.strip_prefix(dir).unwrap_or(&path);
+            let wanted = match glob {
+                Some(glob) => glob.matches(relative),
+                None => has_extension(&path, extensions),
+            };
+            if wanted {
+                if scan.files.len() == MAX_DISCOVERED_FILES {
+                    scan.truncated = true;
+                    break;
+                }
+                scan.files.push(path);
+            }
+        }
+        if scan.truncated {
+            break;
+        }
+    }
+    scan.files.sort();
+    Ok(scan)
+}
+
+/// Scan a specific directory for tabular files with the default extensions
 pub fn scan_directory(dir: &Path) -> Result<Vec<PathBuf>> {
+    scan_directory_with_extensions(dir, DEFAULT_EXTENSIONS)
+}
+
+/// Scan a specific directory for files matching one of the given extensions
+///
+/// Extensions are given without the leading dot and matched case-sensitively.
+pub fn scan_directory_with_extensions<S: AsRef<str>>(
+    dir: &Path,
+    extensions: &[S],
+) -> Result<Vec<PathBuf>> {
     let mut csv_files = Vec::new();
 
     // Read directory entries
@@ -12,13 +178,9 @@ pub fn scan_directory(dir: &Path) -> Result<Vec<PathBuf>> {
         let entry = entry.context("Failed to read directory entry")?;
         let path = entry.path();
 
-        // Check if it's a CSV file
-        if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext.to_str() == Some("csv") {
-                    csv_files.push(path);
-                }
-            }
+        // Check if it's a recognized tabular file
+        if path.is_file() && has_extension(&path, extensions) {
+            csv_files.push(path);
         }
     }
 
@@ -28,8 +190,16 @@ pub fn scan_directory(dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(csv_files)
 }
 
-/// Scan directory for CSV files (given a file path, scans its parent directory)
+/// Scan directory for tabular files (given a file path, scans its parent directory)
 pub fn scan_directory_for_csvs(file_path: &Path) -> Result<Vec<PathBuf>> {
+    scan_directory_for_csvs_with_extensions(file_path, DEFAULT_EXTENSIONS)
+}
+
+/// Scan the parent directory of `file_path` for files matching the given extensions
+pub fn scan_directory_for_csvs_with_extensions<S: AsRef<str>>(
+    file_path: &Path,
+    extensions: &[S],
+) -> Result<Vec<PathBuf>> {
     // Get the directory containing the file
     // If parent is None or empty, use current directory
     let dir = match file_path.parent() {
@@ -37,9 +207,9 @@ pub fn scan_directory_for_csvs(file_path: &Path) -> Result<Vec<PathBuf>> {
         _ => Path::new("."),
     };
 
-    let mut csv_files = scan_directory(dir)?;
+    let mut csv_files = scan_directory_with_extensions(dir, extensions)?;
 
-    // If no CSV files found (shouldn't happen), at least include the current file
+    // If no matching files found (shouldn't happen), at least include the current file
     if csv_files.is_empty() {
         csv_files.push(file_path.to_path_buf());
     }
@@ -47,6 +217,17 @@ pub fn scan_directory_for_csvs(file_path: &Path) -> Result<Vec<PathBuf>> {
     Ok(csv_files)
 }
 
+/// Check whether the path ends with one of the given extensions, looking
+/// through a compression extension (`data.csv.gz` counts as `csv`)
+pub fn has_extension<S: AsRef<str>>(path: &Path, extensions: &[S]) -> bool {
+    let matches = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|e| e.as_ref() == ext))
+    };
+    matches(path) || matches(&uncompressed_path(path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,7 +272,7 @@ mod tests {
     fn test_scan_directory_mixed_files() {
         let temp_dir = TempDir::new().unwrap();
         File::create(temp_dir.path().join("data.csv")).unwrap();
-        File::create(temp_dir.path().join("notes.txt")).unwrap();
+        File::create(temp_dir.path().join("notes.md")).unwrap();
         File::create(temp_dir.path().join("config.json")).unwrap();
         File::create(temp_dir.path().join("other.csv")).unwrap();
 
@@ -100,11 +281,27 @@ mod tests {
         assert!(result.is_ok());
 
         let csv_files = result.unwrap();
-        // Should only include CSV files
+        // Should only include tabular files
         assert_eq!(csv_files.len(), 2);
         assert!(csv_files.iter().all(|p| p.extension().unwrap() == "csv"));
     }
 
+    #[test]
+    fn test_scan_directory_includes_compressed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("data.csv")).unwrap();
+        File::create(temp_dir.path().join("export.csv.gz")).unwrap();
+        File::create(temp_dir.path().join("log.tsv.zst")).unwrap();
+        File::create(temp_dir.path().join("backup.tar.gz")).unwrap();
+
+        let csv_files = scan_directory_for_csvs(&temp_dir.path().join("data.csv")).unwrap();
+        let names: Vec<_> = csv_files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["data.csv", "export.csv.gz", "log.tsv.zst"]);
+    }
+
     #[test]
     fn test_scan_directory_with_subdirectories() {
         let temp_dir = TempDir::new().unwrap();
@@ -305,7 +502,7 @@ mod tests {
     #[test]
     fn test_direct_scan_directory_no_csv_files() {
         let temp_dir = TempDir::new().unwrap();
-        File::create(temp_dir.path().join("data.txt")).unwrap();
+        File::create(temp_dir.path().join("data.md")).unwrap();
         File::create(temp_dir.path().join("config.json")).unwrap();
 
         let result = scan_directory(temp_dir.path());
@@ -319,7 +516,7 @@ mod tests {
     fn test_direct_scan_directory_mixed_files() {
         let temp_dir = TempDir::new().unwrap();
         File::create(temp_dir.path().join("data.csv")).unwrap();
-        File::create(temp_dir.path().join("notes.txt")).unwrap();
+        File::create(temp_dir.path().join("notes.md")).unwrap();
         File::create(temp_dir.path().join("other.csv")).unwrap();
         File::create(temp_dir.path().join("config.json")).unwrap();
 
@@ -521,14 +718,14 @@ mod tests {
 
         // Create files without .csv extension
         std::fs::write(temp_dir.path().join("data"), "a,b\n1,2").unwrap();
-        std::fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+        std::fs::write(temp_dir.path().join("file.md"), "content").unwrap();
         std::fs::write(temp_dir.path().join("file.csv"), "a\n1").unwrap();
 
         let result = scan_directory(temp_dir.path());
         assert!(result.is_ok());
 
         let csv_files = result.unwrap();
-        // Should only find .csv file, not files without extension or .txt
+        // Should only find .csv file, not files without extension or .md
         assert_eq!(csv_files.len(), 1);
         assert!(csv_files[0].ends_with("file.csv"));
     }
@@ -572,4 +769,118 @@ mod tests {
         // Should handle gracefully (likely to fail as empty path is invalid)
         assert!(result.is_ok() || result.is_err());
     }
+
+    #[test]
+    fn test_scan_directory_includes_delimited_text_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("a.csv")).unwrap();
+        File::create(temp_dir.path().join("b.tsv")).unwrap();
+        File::create(temp_dir.path().join("c.psv")).unwrap();
+        File::create(temp_dir.path().join("d.txt")).unwrap();
+        File::create(temp_dir.path().join("e.json")).unwrap();
+
+        let files = scan_directory(temp_dir.path()).unwrap();
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["a.csv", "b.tsv", "c.psv", "d.txt"]);
+    }
+
+    #[test]
+    fn test_scan_directory_with_custom_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("a.csv")).unwrap();
+        File::create(temp_dir.path().join("b.tsv")).unwrap();
+        File::create(temp_dir.path().join("c.dat")).unwrap();
+
+        let files = scan_directory_with_extensions(temp_dir.path(), &["tsv", "dat"]).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("b.tsv"));
+        assert!(files[1].ends_with("c.dat"));
+    }
+
+    #[test]
+    fn test_scan_directory_for_csvs_with_custom_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("a.csv")).unwrap();
+        File::create(temp_dir.path().join("b.psv")).unwrap();
+
+        let target = temp_dir.path().join("b.psv");
+        let files = scan_directory_for_csvs_with_extensions(&target, &["psv".to_string()]).unwrap();
+
+        assert_eq!(files, vec![target]);
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let matches =
+            |pattern: &str, path: &str| Glob::new(pattern).unwrap().matches(Path::new(path));
+        assert!(matches("*.csv", "sales.csv"));
+        assert!(matches("*.csv", "2024/sales.csv"));
+        assert!(!matches("*.csv", "sales.csv.gz"));
+        assert!(matches("**/*2024*.csv", "sales-2024.csv"));
+        assert!(matches("**/*2024*.csv", "eu/q1/sales-2024-01.csv"));
+        assert!(!matches("**/*2024*.csv", "eu/sales-2023.csv"));
+        assert!(matches("eu/*.csv", "eu/a.csv"));
+        assert!(!matches("eu/*.csv", "eu/q1/a.csv"));
+        assert!(matches("q[12]_?.{csv,tsv}", "q2_a.tsv"));
+        assert!(!matches("q[!12]_?.csv", "q1_a.csv"));
+        assert!(matches("a+b(1).csv", "a+b(1).csv"));
+    }
+
+    #[test]
+    fn test_scan_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("eu/q1")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        for file in [
+            "top.csv",
+            "eu/sales-2024.csv",
+            "eu/q1/sales-2024-01.tsv",
+            "eu/q1/notes.md",
+            ".git/objects.csv",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+        let relative = |scan: TreeScan| -> Vec<String> {
+            scan.files
+                .iter()
+                .map(|p| {
+                    p.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+
+        let flat = scan_tree(root, DEFAULT_EXTENSIONS, false, None).unwrap();
+        assert_eq!(relative(flat), vec!["top.csv"]);
+        let tree = scan_tree(root, DEFAULT_EXTENSIONS, true, None).unwrap();
+        assert_eq!(
+            relative(tree),
+            vec!["eu/q1/sales-2024-01.tsv", "eu/sales-2024.csv", "top.csv"]
+        );
+        let glob = Glob::new("**/*2024*").unwrap();
+        let globbed = scan_tree(root, DEFAULT_EXTENSIONS, true, Some(&glob)).unwrap();
+        assert_eq!(
+            relative(globbed),
+            vec!["eu/q1/sales-2024-01.tsv", "eu/sales-2024.csv"]
+        );
+    }
+
+    #[test]
+    fn test_scan_tree_stops_at_the_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..=MAX_DISCOVERED_FILES {
+            File::create(temp_dir.path().join(format!("{}.csv", i))).unwrap();
+        }
+        let scan = scan_tree(temp_dir.path(), DEFAULT_EXTENSIONS, true, None).unwrap();
+        assert!(scan.truncated);
+        assert_eq!(scan.files.len(), MAX_DISCOVERED_FILES);
+    }
 }
@@ -1,7 +1,19 @@
-//! File system operations for CSV file discovery
+//! File system operations for tabular file discovery
 //!
-//! Scans directories to find CSV files, used for multi-file navigation.
+//! Scans directories to find CSV/TSV/PSV files, used for multi-file navigation.
 
 pub mod discovery;
 
-pub use discovery::{scan_directory, scan_directory_for_csvs};
+pub use discovery::{
+    has_extension, scan_directory, scan_directory_for_csvs,
+    scan_directory_for_csvs_with_extensions, scan_directory_with_extensions, scan_tree, Glob,
+    TreeScan, DEFAULT_EXTENSIONS, MAX_DISCOVERED_FILES,
+};
+
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Modification time of a file (None if it can't be stat'ed)
+pub(crate) fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
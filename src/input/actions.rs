@@ -91,6 +91,15 @@ pub enum PendingCommand {
     D,
     /// Waiting for second 'y' (for yy - yank row)
     Y,
+    /// Waiting for the position after a quote (for '" - position at last
+    /// close, 'a - mark a, '' - before the last jump)
+    Quote,
+    /// Waiting for the mark letter after 'm' (for ma - set mark a)
+    Mark,
+    /// Waiting for the register name after '"' (for "ayy - yank into a)
+    Register,
+    /// Waiting for the window command after Ctrl+w (s/v split, w switch)
+    Window,
 }
 
 impl PendingCommand {
@@ -101,6 +110,9 @@ impl PendingCommand {
             KeyCode::Char('z') => Some(Self::Z),
             KeyCode::Char('d') => Some(Self::D),
             KeyCode::Char('y') => Some(Self::Y),
+            KeyCode::Char('\'') => Some(Self::Quote),
+            KeyCode::Char('m') => Some(Self::Mark),
+            KeyCode::Char('"') => Some(Self::Register),
             _ => None,
         }
     }
@@ -126,11 +138,24 @@ impl PendingCommand {
     }
 }
 
+/// How serious a status message is, which decides its color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// What a command did
+    #[default]
+    Info,
+    /// Something the user should notice, such as a refused `:q`
+    Warning,
+    /// A command or file operation failed
+    Error,
+}
+
 /// Newtype wrapper for status messages
 #[derive(Debug, Clone, PartialEq)]
 pub struct StatusMessage {
     content: Cow<'static, str>,
     clear_on_keypress: bool,
+    severity: Severity,
 }
 
 impl StatusMessage {
@@ -139,6 +164,7 @@ impl StatusMessage {
         Self {
             content: Cow::Borrowed(msg),
             clear_on_keypress: true,
+            severity: Severity::Info,
         }
     }
 
@@ -147,6 +173,7 @@ impl StatusMessage {
         Self {
             content: Cow::Owned(msg),
             clear_on_keypress: true,
+            severity: Severity::Info,
         }
     }
 
@@ -155,9 +182,33 @@ impl StatusMessage {
         Self {
             content: Cow::Owned(msg),
             clear_on_keypress: false,
+            severity: Severity::Info,
         }
     }
 
+    /// Create a warning, which stays through keypresses until it expires
+    pub fn warning(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            content: msg.into(),
+            clear_on_keypress: false,
+            severity: Severity::Warning,
+        }
+    }
+
+    /// Create an error, which stays through keypresses until it expires
+    pub fn error(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            content: msg.into(),
+            clear_on_keypress: false,
+            severity: Severity::Error,
+        }
+    }
+
+    /// How serious the message is
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
     /// Get the message as a string slice
     pub fn as_str(&self) -> &str {
         &self.content
@@ -254,6 +305,17 @@ mod tests {
         assert_eq!(msg.as_str(), "Hello");
     }
 
+    #[test]
+    fn test_status_message_severity() {
+        let msg = StatusMessage::from("Saved");
+        assert_eq!(msg.severity(), Severity::Info);
+        assert!(msg.should_clear_on_keypress());
+        let msg = StatusMessage::error(format!("Failed to save {}", "a.csv"));
+        assert_eq!(msg.severity(), Severity::Error);
+        assert!(!msg.should_clear_on_keypress());
+        assert!(Severity::Warning < Severity::Error);
+    }
+
     #[test]
     fn test_status_message_from_string() {
         let msg: StatusMessage = String::from("World").into();
@@ -0,0 +1,113 @@
+//! Path completion for `:e <path>` in Command mode.
+//!
+//! Tab completes the path typed so far as far as it is unambiguous, like a
+//! shell: a directory gets a trailing `/`, and when several entries remain
+//! they are listed so the next letters can be typed.
+
+use std::path::{Path, PathBuf};
+
+/// What Tab did to a partial path
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathCompletion {
+    /// The path completed as far as it can be
+    pub completed: String,
+    /// Entries still matching when more than one does (directories end
+    /// with `/`)
+    pub candidates: Vec<String>,
+}
+
+/// `~/...` with the home directory in place of `~`
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Complete `partial` against the entries of its directory. Hidden
+/// entries are only offered when the name typed starts with a dot.
+pub fn complete_path(partial: &str) -> PathCompletion {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(at) => partial.split_at(at + 1),
+        None => ("", partial),
+    };
+    let search_dir = if dir.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir)
+    };
+
+    let mut names: Vec<String> = std::fs::read_dir(&search_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            Some(if is_dir { format!("{}/", name) } else { name })
+        })
+        .collect();
+    names.sort();
+
+    let common = match names.as_slice() {
+        [] => prefix.to_string(),
+        [first, rest @ ..] => rest.iter().fold(first.clone(), |common, name| {
+            common
+                .chars()
+                .zip(name.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c)
+                .collect()
+        }),
+    };
+    PathCompletion {
+        completed: format!("{}{}", dir, common),
+        candidates: if names.len() > 1 { names } else { Vec::new() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().display().to_string();
+        std::fs::write(dir.path().join("sales_2023.csv"), "").unwrap();
+        std::fs::write(dir.path().join("sales_2024.csv"), "").unwrap();
+        std::fs::write(dir.path().join(".hidden.csv"), "").unwrap();
+        std::fs::create_dir(dir.path().join("archive")).unwrap();
+
+        let completion = complete_path(&format!("{}/sa", root));
+        assert_eq!(completion.completed, format!("{}/sales_202", root));
+        assert_eq!(
+            completion.candidates,
+            vec!["sales_2023.csv".to_string(), "sales_2024.csv".to_string()]
+        );
+
+        let completion = complete_path(&format!("{}/sales_2024", root));
+        assert_eq!(completion.completed, format!("{}/sales_2024.csv", root));
+        assert!(completion.candidates.is_empty());
+
+        // Directories end with a slash, so Tab can go on into them
+        assert_eq!(
+            complete_path(&format!("{}/ar", root)).completed,
+            format!("{}/archive/", root)
+        );
+        // Hidden files only when asked for
+        assert_eq!(complete_path(&format!("{}/", root)).candidates.len(), 3);
+        assert_eq!(
+            complete_path(&format!("{}/.h", root)).completed,
+            format!("{}/.hidden.csv", root)
+        );
+        // Nothing matches: left as typed
+        assert_eq!(
+            complete_path(&format!("{}/zz", root)).completed,
+            format!("{}/zz", root)
+        );
+    }
+}
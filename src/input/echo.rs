@@ -0,0 +1,90 @@
+//! Echo of recently pressed keys and the last `:` command.
+//!
+//! Shown in a corner of the screen when the `showkeys` option is on, which
+//! helps viewers follow along in screencasts and pairing sessions.
+
+use super::keymap::{self, ActionBinding};
+use crossterm::event::KeyEvent;
+use std::collections::VecDeque;
+
+/// Number of recent keys kept for display
+pub const KEY_ECHO_LENGTH: usize = 10;
+
+/// Recent keys, the action the last key triggered, and the last command
+#[derive(Debug, Default)]
+pub struct KeyEcho {
+    keys: VecDeque<String>,
+    last_action: Option<&'static str>,
+    last_command: Option<String>,
+}
+
+impl KeyEcho {
+    /// Record a key press and the registered action it triggered, if any
+    pub fn record_key(&mut self, key: KeyEvent, action: Option<&'static ActionBinding>) {
+        if self.keys.len() == KEY_ECHO_LENGTH {
+            self.keys.pop_front();
+        }
+        self.keys
+            .push_back(keymap::format_key(key.code, key.modifiers));
+        self.last_action = action.map(|a| a.description);
+    }
+
+    /// Record an executed `:` command
+    pub fn record_command(&mut self, command: &str) {
+        self.last_command = Some(command.to_string());
+    }
+
+    /// Last executed `:` command
+    pub fn last_command(&self) -> Option<&str> {
+        self.last_command.as_deref()
+    }
+
+    /// Text for the echo area, e.g. `5 j ]  Next file  :c B`
+    pub fn display(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if !self.keys.is_empty() {
+            parts.push(self.keys.iter().cloned().collect::<Vec<_>>().join(" "));
+        }
+        if let Some(action) = self.last_action {
+            parts.push(action.to_string());
+        }
+        if let Some(command) = &self.last_command {
+            parts.push(format!(":{}", command));
+        }
+        parts.join("  ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_echo_keeps_recent_keys() {
+        let mut echo = KeyEcho::default();
+        for c in "abcdefghijkl".chars() {
+            echo.record_key(key(c), None);
+        }
+        assert_eq!(echo.display(), "c d e f g h i j k l");
+    }
+
+    #[test]
+    fn test_echo_shows_action_and_command() {
+        let mut echo = KeyEcho::default();
+        echo.record_key(key(']'), keymap::find_action("next_file"));
+        echo.record_command("c B");
+
+        assert_eq!(echo.display(), "]  Next file  :c B");
+        assert_eq!(echo.last_command(), Some("c B"));
+    }
+
+    #[test]
+    fn test_echo_empty() {
+        assert_eq!(KeyEcho::default().display(), "");
+    }
+}
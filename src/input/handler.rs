@@ -1,14 +1,35 @@
 //! Input handling and keyboard event processing
 
-use crate::app::{messages, App, EditBuffer, Mode};
-use crate::domain::position::RowIndex;
+use crate::analysis::diff::DiffKind;
+use crate::analysis::lint::{self, LintRule};
+use crate::analysis::{ColumnStats, Diff, Schema};
+use crate::app::copy::{self, CopyFormat};
+use crate::app::delimiter::parse_delimiter;
+use crate::app::fill::{FillDown, FillSpec};
+use crate::app::grep::GrepMatch;
+use crate::app::long_cells::{LongCells, LISTED_CELLS};
+use crate::app::replace;
+use crate::app::save_preview::SavePreview;
+use crate::app::search::{self, SearchPattern};
+use crate::app::sort::SortSpec;
+use crate::app::{
+    messages, App, CellEdit, Change, ConfirmAction, Edit, EditBuffer, ExitStatus, LastChange, Mode,
+    RegisterRows, Registers, RowOrder, SplitDirection, VISUAL_RANGE,
+};
+use crate::clipboard;
+use crate::domain::position::{ColIndex, RowIndex};
 use crate::navigation;
-use crate::ui::ViewportMode;
+use crate::ui::{
+    file_browser::BrowserEntry, file_picker, minimap, scrollbar, BookmarkView, Comparison,
+    FilePicker, FrequencyView, Magnifier, MarkKind, RowFilter, ViewportMode,
+};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
 use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
 
-use super::{InputResult, PendingCommand, StatusMessage};
+use super::{completion, keymap, InputResult, PendingCommand, StatusMessage};
 
 /// Timeout for multi-key commands (no longer used in handler, but still exported for state)
 pub const MULTI_KEY_TIMEOUT_MS: u128 = 1000;
@@ -47,44 +68,2788 @@ fn format_pending_command(cmd: &PendingCommand) -> String {
         PendingCommand::GotoColumn(letters) => format!("g{}", letters),
         PendingCommand::D => "d".to_string(),
         PendingCommand::Y => "y".to_string(),
+        PendingCommand::Quote => "'".to_string(),
+        PendingCommand::Mark => "m".to_string(),
+        PendingCommand::Register => "\"".to_string(),
+        PendingCommand::Window => "Ctrl+w".to_string(),
     }
 }
 
 /// Handle keyboard input events
 pub fn handle_key(app: &mut App, key: KeyEvent) -> Result<InputResult> {
+    if app.options.show_keys {
+        echo_key(app, key);
+    }
+
+    if app.follower.is_some() {
+        handle_follower_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
+    // An open question takes the next key as its answer
+    if app.confirm.is_some() {
+        answer_question(app, key.code);
+        return Ok(InputResult::Continue);
+    }
+
+    if app.view_state.resizing.is_some() {
+        handle_resize_key(app, key.code);
+        return Ok(InputResult::Continue);
+    }
+
     match app.mode {
         Mode::Normal => handle_normal_mode(app, key),
         Mode::Command => handle_command_mode(app, key),
         Mode::Insert => handle_insert_mode(app, key),
+        Mode::Visual => handle_visual_mode(app, key),
+        Mode::Magnifier => {
+            handle_magnifier_key(app, key);
+            Ok(InputResult::Continue)
+        }
         // TODO: Implement handlers for new modes in v0.5.0+
-        Mode::Magnifier | Mode::HeaderEdit | Mode::Visual => {
+        Mode::HeaderEdit => {
             // For now, Esc returns to Normal mode
             if key.code == KeyCode::Esc {
                 app.mode = Mode::Normal;
                 app.edit_buffer = None;
             }
-            Ok(InputResult::Continue)
+            Ok(InputResult::Continue)
+        }
+    }
+}
+
+/// Keys while following another instance: the view is read-only, so only
+/// quitting is possible
+fn handle_follower_key(app: &mut App, key: KeyEvent) {
+    let quit = matches!(key.code, KeyCode::Char('q'))
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL));
+    if quit {
+        app.should_quit = true;
+    } else {
+        app.status_messages.push("Following (read-only) - q quits");
+    }
+}
+
+/// Rows moved per mouse wheel step
+const WHEEL_ROWS: usize = 3;
+
+/// Handle mouse events; returns None if the event changed nothing
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) -> Option<InputResult> {
+    if !matches!(app.mode, Mode::Normal | Mode::Visual)
+        || !is_navigation_allowed(app)
+        || app.follower.is_some()
+    {
+        return None;
+    }
+    let position = Position::new(mouse.column, mouse.row);
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => handle_click(app, position),
+        MouseEventKind::Drag(MouseButton::Left) => handle_drag(app, position),
+        // Shift+wheel scrolls sideways on terminals without a horizontal wheel
+        MouseEventKind::ScrollDown if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+            navigation::move_right_by(app, 1);
+            Some(InputResult::Continue)
+        }
+        MouseEventKind::ScrollUp if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+            navigation::move_left_by(app, 1);
+            Some(InputResult::Continue)
+        }
+        MouseEventKind::ScrollDown => {
+            navigation::move_down_by(app, WHEEL_ROWS);
+            Some(InputResult::Continue)
+        }
+        MouseEventKind::ScrollUp => {
+            navigation::move_up_by(app, WHEEL_ROWS);
+            Some(InputResult::Continue)
+        }
+        MouseEventKind::ScrollRight => {
+            navigation::move_right_by(app, 1);
+            Some(InputResult::Continue)
+        }
+        MouseEventKind::ScrollLeft => {
+            navigation::move_left_by(app, 1);
+            Some(InputResult::Continue)
+        }
+        _ => None,
+    }
+}
+
+/// Left click: select the cell, jump via the minimap or scrollbar, or switch files
+fn handle_click(app: &mut App, position: Position) -> Option<InputResult> {
+    // A click ends any selection; dragging starts a new one
+    if app.mode == Mode::Visual {
+        exit_visual_mode(app);
+    }
+
+    // Click on the column minimap jumps to that column
+    if let Some(area) = app.view_state.minimap_area {
+        if area.contains(position) && app.document.column_count() > 0 {
+            let col = minimap::column_at(
+                (position.x - area.x) as usize,
+                area.width as usize,
+                app.document.column_count(),
+                &app.view_state.marks.columns(),
+            );
+            // Land on the placeholder of a collapsed group rather than expanding it
+            let col = app.view_state.column_groups.visible_column(col);
+            navigation::commands::select_column(app, col);
+            return Some(InputResult::Continue);
+        }
+    }
+
+    // Click on the scrollbar jumps to that part of the file (or the mark there)
+    if let Some(area) = app.view_state.scrollbar_area {
+        if area.contains(position) && app.display_row_count() > 0 {
+            let row = scrollbar::row_at(
+                position.y - area.y,
+                area.height,
+                app.display_row_count(),
+                &app.display_row_marks(),
+            );
+            app.view_state.table_state.select(Some(row));
+            app.view_state.viewport_mode = ViewportMode::Auto;
+            return Some(InputResult::Continue);
+        }
+    }
+
+    // Click on the horizontal scrollbar jumps to that part of the columns
+    if let Some((area, positions)) = app.view_state.column_scrollbar.clone() {
+        if area.contains(position) && !positions.is_empty() {
+            let offset = scrollbar::column_at(position.x - area.x, area.width, positions.len());
+            let order = app.view_state.display_columns(app.document.column_count());
+            if let Some(&col) = order.get(positions.start + offset) {
+                let col = app.view_state.column_groups.visible_column(col);
+                navigation::commands::select_column(app, col);
+            }
+            return Some(InputResult::Continue);
+        }
+    }
+
+    // Click on a file name in the file switcher opens that file
+    let tab = app
+        .view_state
+        .file_tabs
+        .iter()
+        .find(|(_, area)| area.contains(position))
+        .map(|&(index, _)| index);
+    if let Some(index) = tab {
+        return Some(handle_file_select(app, index));
+    }
+
+    // Click on a cell selects it (on a row number, just the row)
+    let (row, col) = app.view_state.cell_at(position)?;
+    select_cell_at(app, row, col);
+    Some(InputResult::Continue)
+}
+
+/// Left drag over the table: select the range from where the drag started
+fn handle_drag(app: &mut App, position: Position) -> Option<InputResult> {
+    let (row, col) = app.view_state.cell_at(position)?;
+    if app.mode == Mode::Normal {
+        enter_visual_mode(app);
+    }
+    select_cell_at(app, row, col);
+    Some(InputResult::Continue)
+}
+
+/// Move the cursor to a display row (and column, if given)
+fn select_cell_at(app: &mut App, row: usize, col: Option<usize>) {
+    let row = row.min(app.display_row_count().saturating_sub(1));
+    app.view_state.table_state.select(Some(row));
+    if let Some(col) = col {
+        navigation::commands::select_column(app, col);
+    }
+    app.view_state.viewport_mode = ViewportMode::Auto;
+}
+
+/// Keys in Visual mode: motions extend the selection, Esc or v ends it
+fn handle_visual_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
+    if app.input_state.pending_command == Some(PendingCommand::Register) {
+        app.input_state.clear_pending_command();
+        if let KeyCode::Char(c) = key.code {
+            select_register(app, c);
+        }
+        return Ok(InputResult::Continue);
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('v') => exit_visual_mode(app),
+        // Command line for the selected rows (`:'<,'>sort`, `:'<,'>fill`)
+        KeyCode::Char(':') => {
+            exit_visual_mode(app);
+            app.mode = Mode::Command;
+            app.input_state.clear_command_buffer();
+            VISUAL_RANGE
+                .chars()
+                .for_each(|c| app.input_state.push_command_char(c));
+        }
+        // Fill the first selected row down the selection, or count up from it
+        KeyCode::Char(c @ ('f' | 'F')) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Some((rows, columns)) = app.visual_selection() {
+                let fill = if c == 'f' {
+                    FillDown::Copy
+                } else {
+                    FillDown::Series {
+                        step: "1".to_string(),
+                    }
+                };
+                let selected = (rows.clone().count(), columns.clone().count());
+                let rows = app.fill_rows(Some(rows));
+                exit_visual_mode(app);
+                if fill_down(app, &fill, &rows, columns) {
+                    app.last_change = Some(LastChange::FillSelection {
+                        fill,
+                        rows: selected.0,
+                        columns: selected.1,
+                    });
+                }
+            }
+        }
+        // Yank or delete the selected rows ("ay into register a)
+        KeyCode::Char('"') => app
+            .input_state
+            .set_pending_command(PendingCommand::Register),
+        KeyCode::Char(c @ ('y' | 'd')) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Some((rows, _)) = app.visual_selection() {
+                let rows = app.fill_rows(Some(rows));
+                exit_visual_mode(app);
+                if c == 'y' {
+                    yank_rows(app, &rows);
+                    if let Some(&first) = rows.first() {
+                        app.select_document_row(RowIndex::new(first));
+                    }
+                } else if delete_rows(app, &rows) {
+                    app.last_change = Some(LastChange::DeleteRows(rows.len()));
+                }
+            }
+        }
+        code => navigation::handle_navigation(app, code)?,
+    }
+    Ok(InputResult::Continue)
+}
+
+/// Start a Visual mode selection at the cursor
+fn enter_visual_mode(app: &mut App) {
+    let Some((row, col)) = selected_cell(app) else {
+        return;
+    };
+    app.view_state.visual_anchor = Some((row, col));
+    app.mode = Mode::Visual;
+}
+
+/// Drop the Visual mode selection, remembering its rows for `'<,'>`
+fn exit_visual_mode(app: &mut App) {
+    if let Some((rows, columns)) = app.visual_selection() {
+        app.view_state.visual_rows = Some(rows);
+        app.view_state.visual_columns = Some(columns);
+    }
+    app.view_state.visual_anchor = None;
+    app.mode = Mode::Normal;
+}
+
+/// Record a key for the echo area, naming the registered action it triggers
+fn echo_key(app: &mut App, key: KeyEvent) {
+    let action = if app.mode == Mode::Normal && !app.input_state.has_pending_command() {
+        keymap::action_for_key(app.input_state.keymap.translate(key))
+    } else {
+        None
+    };
+    app.input_state.key_echo.record_key(key, action);
+}
+
+/// Returns true if navigation commands are allowed (no overlay is open)
+fn is_navigation_allowed(app: &App) -> bool {
+    !app.view_state.help_overlay_visible
+        && app.view_state.stats_panel.is_none()
+        && app.view_state.frequency_view.is_none()
+        && app.view_state.bookmark_view.is_none()
+        && app.view_state.file_picker.is_none()
+        && app.view_state.file_browser.is_none()
+        && !app.view_state.record_view
+        && !app.grep.as_ref().is_some_and(|view| view.visible)
+}
+
+/// Handle quit command with unsaved changes check
+fn handle_quit(app: &mut App) {
+    if app.document.is_dirty || app.pane_is_dirty() {
+        app.status_messages.push(messages::UNSAVED_CHANGES);
+    } else {
+        app.should_quit = true;
+    }
+}
+
+/// Toggle help overlay visibility
+fn handle_help_toggle(app: &mut App) {
+    app.view_state.help_overlay_visible = !app.view_state.help_overlay_visible;
+}
+
+/// Open the statistics panel for the selected column
+fn show_column_stats(app: &mut App) {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let stats = ColumnStats::compute(
+        &app.document,
+        app.view_state.selected_column,
+        &app.options.locale(),
+    );
+    app.view_state.stats_panel = Some(stats);
+}
+
+/// Row order to save once the rows were sorted
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SaveOrder {
+    /// As shown
+    Current,
+    /// As the file was read
+    Original,
+}
+
+/// Keys while resizing a column with `zr`: `h`/`l` (or the arrows) narrow
+/// and widen it live, `H`/`L` in bigger steps
+fn handle_resize_key(app: &mut App, code: KeyCode) {
+    use navigation::commands::WIDTH_STEP;
+
+    match code {
+        KeyCode::Char('h') | KeyCode::Left => navigation::commands::resize_column(app, -WIDTH_STEP),
+        KeyCode::Char('l') | KeyCode::Right => navigation::commands::resize_column(app, WIDTH_STEP),
+        KeyCode::Char('H') => navigation::commands::resize_column(app, -4 * WIDTH_STEP),
+        KeyCode::Char('L') => navigation::commands::resize_column(app, 4 * WIDTH_STEP),
+        KeyCode::Char('=') => navigation::commands::reset_column_width(app),
+        KeyCode::Enter => {
+            navigation::commands::finish_column_resize(app, true);
+            return;
+        }
+        KeyCode::Esc => {
+            navigation::commands::finish_column_resize(app, false);
+            return;
+        }
+        _ => return,
+    }
+    if let Some(message) = app.status_messages.current() {
+        let text = format!("{} (Enter keeps, Esc reverts)", message.as_str());
+        app.status_messages.push(text);
+    }
+}
+
+/// Answer the open question; saving sorted rows takes `y` for the sorted
+/// order or `o` for the file's order
+fn answer_question(app: &mut App, code: KeyCode) {
+    if let Some(ConfirmAction::Paste { text }) = app.confirm.as_ref().map(|c| &c.action) {
+        let text = text.clone();
+        app.confirm = None;
+        match code {
+            KeyCode::Char('y' | 'Y') => paste_cells(app, &text),
+            KeyCode::Char('n' | 'N') => paste_into_cell(app, &text),
+            _ => app.status_messages.push("Cancelled"),
+        }
+        return;
+    }
+    if let Some(ConfirmAction::Recover) = app.confirm.as_ref().map(|c| &c.action) {
+        app.confirm = None;
+        match code {
+            KeyCode::Char('y' | 'Y') => app.recover_swap_file(),
+            KeyCode::Char('d' | 'D') => app.discard_swap_file(),
+            _ => app
+                .status_messages
+                .push("Kept the swap file; it is offered again next time"),
+        }
+        return;
+    }
+    let write = app
+        .confirm
+        .as_ref()
+        .and_then(|confirm| match &confirm.action {
+            ConfirmAction::Write { path, force, quit } => Some((path.clone(), *force, *quit)),
+            _ => None,
+        });
+    let Some((path, force, quit)) = write else {
+        app.answer(matches!(code, KeyCode::Char('y' | 'Y')));
+        return;
+    };
+    app.confirm = None;
+    let order = match code {
+        KeyCode::Char('y' | 'Y') => SaveOrder::Current,
+        KeyCode::Char('o' | 'O') => SaveOrder::Original,
+        _ => {
+            app.status_messages.push("Cancelled");
+            return;
+        }
+    };
+    if write_document(app, path.as_deref(), force, Some(order)) && quit {
+        app.should_quit = true;
+    }
+}
+
+/// Text pasted into the terminal: into the cell (or the edit). Lines, like
+/// a column copied from a spreadsheet, fill the rows down from the cursor;
+/// tab-separated fields, like copied spreadsheet rows, go across the
+/// columns right of the cursor after asking
+pub fn handle_paste(app: &mut App, text: &str) -> Option<InputResult> {
+    let pasting = match app.mode {
+        Mode::Normal => is_navigation_allowed(app),
+        Mode::Insert => true,
+        _ => false,
+    };
+    if !pasting || app.follower.is_some() || app.confirm.is_some() {
+        return None;
+    }
+    // Copied cells end in a line break
+    let text = text.trim_end_matches(['\r', '\n']);
+    if !text.contains(['\t', '\n']) {
+        paste_into_cell(app, text);
+        return Some(InputResult::Continue);
+    }
+    let cells = copy::parse_cells(text);
+    let width = cells.iter().map(Vec::len).max().unwrap_or(1);
+    if width == 1 && app.mode == Mode::Normal {
+        paste_cells(app, text);
+        return Some(InputResult::Continue);
+    }
+
+    let row = app.get_selected_row()?.get();
+    let col = app.view_state.selected_column.get();
+    let last_col = (col + width - 1).min(app.document.column_count().saturating_sub(1));
+    let what = match (cells.len(), width) {
+        (1, fields) => format!("{} tab-separated fields", fields),
+        (lines, 1) => format!("{} lines", lines),
+        (lines, fields) => format!("{} lines of {} fields", lines, fields),
+    };
+    app.ask(
+        format!(
+            "Paste {} into {}:{} (y), into this cell (n) or cancel?",
+            what,
+            cell_name(row, col),
+            cell_name(row + cells.len() - 1, last_col)
+        ),
+        ConfirmAction::Paste {
+            text: text.to_string(),
+        },
+    );
+    Some(InputResult::Continue)
+}
+
+/// Paste text as it is: at the cursor while editing, else over the cell
+fn paste_into_cell(app: &mut App, text: &str) {
+    if let Some(buffer) = app.edit_buffer.as_mut() {
+        let byte_pos = buffer
+            .content
+            .char_indices()
+            .nth(buffer.cursor)
+            .map_or(buffer.content.len(), |(i, _)| i);
+        buffer.content.insert_str(byte_pos, text);
+        buffer.cursor += text.chars().count();
+        return;
+    }
+    if let Some(row) = app.get_selected_row() {
+        let col = app.view_state.selected_column;
+        app.set_cell(row, col, text.to_string(), "paste");
+        app.status_messages.push("Pasted into 1 cell");
+    }
+}
+
+/// Paste tab-separated lines into the cells from the cursor, rightwards
+/// and down, as one undoable change; rows are added past the last one and
+/// fields past the last column are left out (an open edit is dropped)
+fn paste_cells(app: &mut App, text: &str) {
+    let Some(row) = app.get_selected_row().map(|r| r.get()) else {
+        return;
+    };
+    if app.edit_buffer.take().is_some() {
+        app.mode = Mode::Normal;
+    }
+    let col = app.view_state.selected_column.get();
+    let columns = app.document.column_count();
+    let mut edits = Vec::new();
+    let (mut pasted, mut left_out, mut added) = (0, 0, 0);
+    for (i, fields) in copy::parse_cells(text).into_iter().enumerate() {
+        let row = row + i;
+        left_out += fields.len().saturating_sub(columns - col);
+        let fields = fields.into_iter().zip(col..columns);
+        if row < app.document.row_count() {
+            for (value, col) in fields {
+                let old = app
+                    .document
+                    .get_cell(RowIndex::new(row), ColIndex::new(col))
+                    .to_string();
+                if old != value {
+                    edits.push(Edit::Cell {
+                        row,
+                        col,
+                        old,
+                        new: value,
+                    });
+                }
+                pasted += 1;
+            }
+        } else {
+            let mut values = vec![String::new(); columns];
+            for (value, col) in fields {
+                values[col] = value;
+                pasted += 1;
+            }
+            edits.push(Edit::InsertRow { at: row, values });
+            added += 1;
+        }
+    }
+    app.commit_change(Change {
+        edits,
+        label: "paste".to_string(),
+    });
+
+    let count = |n: usize, what: &str| match n {
+        1 => format!("1 {}", what),
+        n => format!("{} {}s", n, what),
+    };
+    let mut message = format!("Pasted {}", count(pasted, "cell"));
+    if added > 0 {
+        message.push_str(&format!(", adding {}", count(added, "row")));
+    }
+    if left_out > 0 {
+        message.push_str(&format!(
+            "; {} past the last column left out",
+            count(left_out, "field")
+        ));
+    }
+    app.status_messages.push(message);
+}
+
+/// `yc`: copy the current column's values (rows the filter shows), one per
+/// line, for pasting into a spreadsheet column
+fn yank_column(app: &mut App) {
+    let col = app.view_state.selected_column.get();
+    let rows = app.fill_rows(None);
+    let text = copy::column_text(&app.document, &rows, col);
+    let name = crate::ui::column_to_excel_letter(col);
+    app.status_messages.push(match clipboard::copy(&text) {
+        Ok(via) => format!("Copied column {} ({} values, {})", name, rows.len(), via),
+        Err(e) => format!("{:#}", e),
+    });
+}
+
+/// Write the document to its file (`:w`), or save it as another file
+/// (`:w other.csv`, `:w!` to overwrite), which then becomes the active file.
+/// Once the rows were sorted this asks which order to save unless `order`
+/// says. Returns true if the file was written.
+fn write_document(
+    app: &mut App,
+    path: Option<&str>,
+    force: bool,
+    order: Option<SaveOrder>,
+) -> bool {
+    let target = match path.filter(|p| !p.is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app.session.get_current_file().clone(),
+    };
+    let save_as = !app.session.is_current_file(&target);
+    if save_as && target.exists() && !force {
+        app.status_messages
+            .push(format!("{} exists (add ! to overwrite)", target.display()));
+        return false;
+    }
+
+    if !save_as && app.session.is_changed_on_disk() && !force {
+        app.status_messages.push(StatusMessage::warning(format!(
+            "{} changed on disk since it was read (:e! reloads, :w! overwrites)",
+            target.display()
+        )));
+        return false;
+    }
+
+    let original = match order {
+        None if app.row_order.is_reordered() => {
+            app.ask(
+                "Rows were sorted: save sorted (y), in the file's order (o) or cancel?".to_string(),
+                ConfirmAction::Write {
+                    path: path.map(str::to_string),
+                    force,
+                    quit: false,
+                },
+            );
+            return false;
+        }
+        Some(SaveOrder::Original) => app.row_order.original(app.document.row_count()),
+        _ => None,
+    };
+
+    let write_headers = !app.session.config().no_headers;
+    let written = match &original {
+        Some(order) => crate::csv::Document {
+            rows: order
+                .iter()
+                .map(|&row| app.document.rows[row].clone())
+                .collect(),
+            headers: app.document.headers.clone(),
+            delimiter: app.document.delimiter,
+            leading_lines: app.document.leading_lines.clone(),
+            write_style: app.document.write_style,
+            ..Default::default()
+        }
+        .write_to(&target, write_headers),
+        None => app.document.write_to(&target, write_headers),
+    };
+    match written {
+        Ok(delimiter) => app.document.delimiter = delimiter,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return false;
+        }
+    }
+
+    app.document.is_dirty = false;
+    app.view_state.marks.clear_kind(MarkKind::Changed);
+    if save_as {
+        app.document.filename = target
+            .file_name()
+            .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        app.session.set_current_file(target.clone());
+    }
+    app.session.mark_file_loaded();
+    // Rows are now where this save put them
+    app.row_order = match &original {
+        Some(order) => RowOrder::saved(order),
+        None => RowOrder::new(app.document.row_count()),
+    };
+    app.status_messages.push(format!(
+        "Wrote {} ({} rows)",
+        target.display(),
+        app.document.row_count()
+    ));
+    true
+}
+
+/// `:resume-point [set|clear]`: bookmark where a review got to, offered
+/// when the file is opened again; without an argument, jump there
+fn resume_point(app: &mut App, arg: &str) {
+    let Some(row) = app.get_selected_row().map(|r| r.get()) else {
+        return;
+    };
+    let col = app.view_state.selected_column.get();
+    let result = match arg {
+        "set" => app
+            .session
+            .set_resume_point(crate::session::CellPosition { row, col })
+            .map(|()| {
+                format!(
+                    "Resume point set at row {}",
+                    crate::ui::group_thousands(row + 1)
+                )
+            }),
+        "clear" => app.session.clear_resume_point().map(|removed| {
+            if removed {
+                "Resume point cleared".to_string()
+            } else {
+                "No resume point for this file".to_string()
+            }
+        }),
+        "" => {
+            match app.session.resume_point() {
+                Some(position) => app.goto_resume_point(position),
+                None => app
+                    .status_messages
+                    .push("No resume point for this file (:resume-point set)"),
+            }
+            return;
+        }
+        other => Ok(format!(
+            "Unknown :resume-point {} (use set or clear)",
+            other
+        )),
+    };
+    app.status_messages.push(match result {
+        Ok(message) => message,
+        Err(e) => format!("{:#}", e),
+    });
+}
+
+/// Spreadsheet name of a cell (B3 for row 3, column 2)
+fn cell_name(row: usize, col: usize) -> String {
+    format!("{}{}", crate::ui::column_to_excel_letter(col), row + 1)
+}
+
+/// `:swap`: mark the current cell, then on another cell exchange the two
+/// values as one undoable change
+fn swap_cells(app: &mut App) {
+    let Some(row) = app.get_selected_row().map(|r| r.get()) else {
+        return;
+    };
+    let col = app.view_state.selected_column.get();
+    let marked = app
+        .view_state
+        .marks
+        .positions(&[MarkKind::Tagged])
+        .into_iter()
+        .find_map(|(row, col)| col.map(|col| (row, col)));
+    let Some((other_row, other_col)) = marked.filter(|&cell| cell != (row, col)) else {
+        app.view_state.marks.clear_kind(MarkKind::Tagged);
+        app.view_state.marks.add(row, Some(col), MarkKind::Tagged);
+        app.status_messages.push(format!(
+            "Marked {}; :swap on another cell exchanges them",
+            cell_name(row, col)
+        ));
+        return;
+    };
+    app.view_state.marks.clear_kind(MarkKind::Tagged);
+
+    let value = |row: usize, col: usize| {
+        app.document
+            .get_cell(RowIndex::new(row), ColIndex::new(col))
+            .to_string()
+    };
+    let (here, there) = (value(row, col), value(other_row, other_col));
+    app.commit_change(Change {
+        edits: vec![
+            Edit::Cell {
+                row,
+                col,
+                old: here.clone(),
+                new: there.clone(),
+            },
+            Edit::Cell {
+                row: other_row,
+                col: other_col,
+                old: there,
+                new: here,
+            },
+        ],
+        label: "swap".to_string(),
+    });
+    app.status_messages.push(format!(
+        "Swapped {} and {}",
+        cell_name(other_row, other_col),
+        cell_name(row, col)
+    ));
+}
+
+/// `dd` in review mode: mark the row for deletion and move to the next
+/// row, or keep a row marked before
+fn toggle_pending_delete(app: &mut App) {
+    let Some(row) = app.get_selected_row().map(|r| r.get()) else {
+        return;
+    };
+    let message = if app
+        .view_state
+        .marks
+        .remove(row, None, MarkKind::PendingDelete)
+    {
+        format!(
+            "Row {} kept ({} pending)",
+            row + 1,
+            pending_deletes(app).len()
+        )
+    } else {
+        app.view_state.marks.add(row, None, MarkKind::PendingDelete);
+        navigation::move_down_by(app, 1);
+        format!(
+            "Row {} marked for deletion ({} pending; :apply-deletes removes them)",
+            row + 1,
+            pending_deletes(app).len()
+        )
+    };
+    app.status_messages.push(message);
+}
+
+/// Rows marked for deletion, top to bottom
+fn pending_deletes(app: &App) -> Vec<usize> {
+    app.view_state
+        .marks
+        .positions(&[MarkKind::PendingDelete])
+        .into_iter()
+        .map(|(row, _)| row)
+        .collect()
+}
+
+/// `:apply-deletes`: delete the rows marked in review mode, as one
+/// undoable change
+fn apply_deletes(app: &mut App) {
+    let rows = pending_deletes(app);
+    if rows.is_empty() {
+        app.status_messages
+            .push("No rows marked for deletion (dd marks them under :set review)");
+        return;
+    }
+    // Bottom up, so each index still points at its row
+    let edits = rows
+        .iter()
+        .rev()
+        .map(|&at| Edit::DeleteRow {
+            at,
+            values: app.document.rows[at].clone(),
+        })
+        .collect();
+    app.commit_change(Change {
+        edits,
+        label: "apply deletes".to_string(),
+    });
+    app.status_messages.push(format!(
+        "{} row{} deleted",
+        rows.len(),
+        if rows.len() == 1 { "" } else { "s" }
+    ));
+}
+
+/// `:clear-deletes`: keep all rows marked for deletion
+fn clear_deletes(app: &mut App) {
+    let count = pending_deletes(app).len();
+    app.view_state.marks.clear_kind(MarkKind::PendingDelete);
+    app.status_messages.push(if count == 0 {
+        "No rows marked for deletion".to_string()
+    } else {
+        format!(
+            "Kept {} row{} marked for deletion",
+            count,
+            if count == 1 { "" } else { "s" }
+        )
+    });
+}
+
+/// `:w --columns a,b` / `:w --visible` / `:w --reorder`: ask before
+/// overwriting the file with only the named columns, the rows and columns
+/// on screen, or the columns in their display order (`Alt+H`/`Alt+L`)
+fn ask_write_selection(app: &mut App, options: &str, force: bool) {
+    let mut rows: Vec<usize> = (0..app.document.row_count()).collect();
+    let mut columns: Vec<usize> = (0..app.document.column_count()).collect();
+    let mut reorder = false;
+    let mut words = options.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "--reorder" => reorder = true,
+            "--visible" => {
+                reorder = true;
+                rows = app.fill_rows(None);
+                let shown = app
+                    .view_state
+                    .column_groups
+                    .visible_columns(app.document.column_count());
+                columns.retain(|col| shown.contains(col));
+            }
+            "--columns" => {
+                let Some(names) = words.next() else {
+                    app.status_messages
+                        .push("--columns needs a list of columns (--columns name,email)");
+                    return;
+                };
+                let mut named = Vec::new();
+                for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    match app.document.find_column(name) {
+                        Some(col) => named.push(col.get()),
+                        None => {
+                            app.status_messages
+                                .push(format!("No column named {}", name));
+                            return;
+                        }
+                    }
+                }
+                columns.retain(|col| named.contains(col));
+            }
+            other => {
+                app.status_messages.push(format!(
+                    "Unknown option {} (use --columns a,b, --visible or --reorder)",
+                    other
+                ));
+                return;
+            }
+        }
+    }
+
+    if columns.is_empty() {
+        app.status_messages.push("No columns to write");
+        return;
+    }
+    if reorder {
+        columns.sort_by_key(|&col| app.view_state.column_position(col));
+    }
+    if app.session.is_changed_on_disk() && !force {
+        app.status_messages.push(StatusMessage::warning(format!(
+            "{} changed on disk since it was read (:e! reloads, :w! overwrites)",
+            app.get_current_file().display()
+        )));
+        return;
+    }
+    let dropped_columns = app.document.column_count() - columns.len();
+    let dropped_rows = app.document.row_count() - rows.len();
+    let dropped: Vec<String> = [(dropped_columns, "column"), (dropped_rows, "row")]
+        .into_iter()
+        .filter(|&(n, _)| n > 0)
+        .map(|(n, what)| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" }))
+        .collect();
+    let reordered = columns.windows(2).any(|pair| pair[0] > pair[1]);
+    let question = match (dropped.is_empty(), reordered) {
+        (true, false) => {
+            write_document(app, None, force, None);
+            return;
+        }
+        (true, true) => format!(
+            "Overwrite {} with the columns in display order? (y/n)",
+            app.document.filename
+        ),
+        (false, _) => format!(
+            "Overwrite {} without {}{}? (y/n)",
+            app.document.filename,
+            dropped.join(" and "),
+            if reordered {
+                ", columns in display order"
+            } else {
+                ""
+            }
+        ),
+    };
+    app.ask(question, ConfirmAction::WriteSelection { rows, columns });
+}
+
+/// `:wdiff`: summarize what `:w` would change in the file on disk
+fn preview_save(app: &mut App) {
+    let path = app.get_current_file().clone();
+    let config = app.session.config();
+    let on_disk = crate::csv::read_file(&path).and_then(|bytes| {
+        let document = config.read_document(&path)?;
+        Ok((bytes, document))
+    });
+    let (bytes, on_disk) = match on_disk {
+        Ok(on_disk) => on_disk,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return;
+        }
+    };
+    let preview = SavePreview::compute(
+        &app.document,
+        &app.row_order,
+        &on_disk,
+        &bytes,
+        config.encoding.as_deref(),
+    );
+    let warning = if app.session.is_changed_on_disk() {
+        " (the file changed on disk since it was read)"
+    } else {
+        ""
+    };
+    let message = if preview.is_empty() {
+        format!("Saving would not change {}{}", path.display(), warning)
+    } else {
+        format!(
+            "Saving {}: {}{}",
+            path.display(),
+            preview.summary(),
+            warning
+        )
+    };
+    app.status_messages.push(message);
+}
+
+/// Check the document against a schema file (`:validate schema.toml`),
+/// marking the offending cells as errors
+fn validate_document(app: &mut App, arg: Option<&str>) {
+    let Some(path) = arg.filter(|a| !a.is_empty()) else {
+        app.status_messages.push("Usage: :validate <schema.toml>");
+        return;
+    };
+    let report = match Schema::load_from(std::path::Path::new(path))
+        .and_then(|schema| schema.validate(&app.document))
+    {
+        Ok(report) => report,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return;
+        }
+    };
+
+    app.view_state.marks.clear_kind(MarkKind::Error);
+    for violation in &report.violations {
+        if let Some(row) = violation.row {
+            app.view_state
+                .marks
+                .add(row, violation.column, MarkKind::Error);
+        }
+    }
+    let message = match report.violations.first() {
+        None => report.summary(),
+        Some(first) => format!(
+            "{} (first: {} {}: {})",
+            report.summary(),
+            first
+                .row
+                .map_or("header".to_string(), |row| format!("row {}", row + 1)),
+            first.column_name,
+            first.message
+        ),
+    };
+    app.status_messages.push(message);
+}
+
+/// A column given by header name, else by letter
+pub(crate) fn column_by_name_or_letter(app: &App, name: &str) -> Option<ColIndex> {
+    app.document.find_column(name).or_else(|| {
+        crate::ui::utils::excel_letter_to_column(&name.to_uppercase())
+            .ok()
+            .filter(|&col| col < app.document.column_count())
+            .map(ColIndex::new)
+    })
+}
+
+/// `:filter status=failed`: show only the rows where a column (by name or
+/// letter) holds a value, as Enter in the `:freq` view does;
+/// `:filter status~^fail` shows the rows where it matches a search pattern
+fn filter_rows(app: &mut App, args: &str) {
+    let Some(at) = args.find(['=', '~', '<', '>']) else {
+        app.status_messages.push(
+            "Usage: :filter <column>=<value>, <column>~<pattern> or <column><value (also <= > >=)",
+        );
+        return;
+    };
+    let operator_len = if args[at + 1..].starts_with('=') && !args[at..].starts_with(['=', '~']) {
+        2
+    } else {
+        1
+    };
+    let operator = &args[at..at + operator_len];
+    let (name, value) = (&args[..at], &args[at + operator_len..]);
+    let name = name.trim();
+    let value = value.trim();
+    // Quotes keep spaces around a value
+    let value = ['\'', '"']
+        .iter()
+        .find_map(|&quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|v| v.strip_suffix(quote))
+        })
+        .unwrap_or(value);
+    let Some(column) = column_by_name_or_letter(app, name) else {
+        app.status_messages
+            .push(format!("No column named {}", name));
+        return;
+    };
+    let filter = if let Some(comparison) = Comparison::parse(operator) {
+        let locale = app.options.locale();
+        match RowFilter::comparing(&app.document, column, comparison, value, &locale) {
+            Ok(filter) => filter,
+            Err(err) => {
+                app.status_messages.push(StatusMessage::error(err));
+                return;
+            }
+        }
+    } else if operator == "~" {
+        match SearchPattern::new(value, &app.options) {
+            Ok(pattern) => RowFilter::matching_pattern(&app.document, column, pattern),
+            Err(err) => {
+                app.status_messages.push(StatusMessage::error(err));
+                return;
+            }
+        }
+    } else {
+        RowFilter::matching(&app.document, column, value)
+    };
+    apply_row_filter(app, filter);
+}
+
+/// `:check <column> <pattern>`: mark the non-empty cells of a column that
+/// don't match a regular expression, like a one-rule `:validate`
+fn check_column_pattern(app: &mut App, args: &str) {
+    const USAGE: &str = "Usage: :check <column> <pattern>";
+    // A column name with spaces goes in backticks
+    let split = match args.strip_prefix('`') {
+        Some(rest) => rest
+            .split_once('`')
+            .map(|(name, pattern)| (name, pattern.trim())),
+        None => args
+            .split_once(char::is_whitespace)
+            .map(|(name, pattern)| (name, pattern.trim())),
+    };
+    let Some((name, pattern)) = split.filter(|(_, pattern)| !pattern.is_empty()) else {
+        app.status_messages.push(USAGE);
+        return;
+    };
+    // Quotes or slashes around the pattern are optional
+    let pattern = ['\'', '"', '/']
+        .iter()
+        .find_map(|&quote| {
+            pattern
+                .strip_prefix(quote)
+                .and_then(|p| p.strip_suffix(quote))
+        })
+        .unwrap_or(pattern);
+
+    let Some(column) = column_by_name_or_letter(app, name) else {
+        app.status_messages
+            .push(format!("No column named {}", name));
+        return;
+    };
+    let header = app.document.headers[column.get()].clone();
+    let report = match Schema::pattern(&header, pattern).and_then(|s| s.validate(&app.document)) {
+        Ok(report) => report,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return;
+        }
+    };
+
+    app.view_state.marks.clear_kind(MarkKind::Error);
+    for violation in &report.violations {
+        if let Some(row) = violation.row {
+            app.view_state
+                .marks
+                .add(row, violation.column, MarkKind::Error);
+        }
+    }
+    let checked = app
+        .document
+        .rows
+        .iter()
+        .filter(|row| row.get(column.get()).is_some_and(|v| !v.trim().is_empty()))
+        .count();
+    let message = match report.violations.first() {
+        None => format!("All {} {} values match {}", checked, header, pattern),
+        Some(first) => format!(
+            "{} of {} {} values don't match {} (first: row {}); n/N to step through",
+            report.violations.len(),
+            checked,
+            header,
+            pattern,
+            first.row.map_or(0, |row| row + 1)
+        ),
+    };
+    app.status_messages.push(message);
+}
+
+/// `:lint [fix]`: check the file on disk for RFC 4180 problems, marking the
+/// rows they are on; `fix` strips stray control characters and marks the
+/// file modified so `:w` rewrites it with a clean encoding
+fn lint_file(app: &mut App, arg: Option<&str>) {
+    let fix = match arg {
+        None => false,
+        Some("fix") => true,
+        Some(_) => {
+            app.status_messages.push("Usage: :lint [fix]");
+            return;
+        }
+    };
+    let path = app.get_current_file().clone();
+    let bytes = match crate::csv::read_file(&path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return;
+        }
+    };
+    let report = lint::lint(&bytes, app.document.delimiter);
+
+    // The header line is record 0 unless the file has none
+    let header_records = usize::from(!app.session.config().no_headers);
+    app.view_state.marks.clear_kind(MarkKind::Error);
+    for record in report.issues.iter().filter_map(|issue| issue.record) {
+        if let Some(row) = record
+            .checked_sub(header_records)
+            .filter(|&row| row < app.document.row_count())
+        {
+            app.view_state.marks.add(row, None, MarkKind::Error);
+        }
+    }
+
+    if !fix {
+        let message = if report.is_clean() {
+            report.summary()
+        } else {
+            format!("{}; :lint fix to repair", report.summary())
+        };
+        app.status_messages.push(message);
+        return;
+    }
+    if report.is_clean() {
+        app.status_messages.push(report.summary());
+        return;
+    }
+
+    let edits: Vec<Edit> = app
+        .document
+        .rows
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| {
+            cells.iter().enumerate().filter_map(move |(col, old)| {
+                lint::strip_control_chars(old).map(|new| Edit::Cell {
+                    row,
+                    col,
+                    old: old.clone(),
+                    new,
+                })
+            })
+        })
+        .collect();
+    let stripped = edits.len();
+    app.commit_change(Change {
+        edits,
+        label: "strip control characters".to_string(),
+    });
+    // Saving normalizes quoting and line endings and drops the BOM
+    app.document.is_dirty = true;
+
+    let mut fixed = Vec::new();
+    if stripped > 0 {
+        fixed.push(format!(
+            "stripped control characters from {} cells",
+            stripped
+        ));
+    }
+    if report.count(LintRule::Bom)
+        + report.count(LintRule::LineEndings)
+        + report.count(LintRule::Quoting)
+        > 0
+    {
+        fixed.push(":w rewrites quoting, line endings and BOM".to_string());
+    }
+    let manual = report.count(LintRule::FieldCount);
+    if manual > 0 {
+        fixed.push(format!(
+            "{} field count problems need fixing by hand",
+            manual
+        ));
+    }
+    app.status_messages
+        .push(format!("Lint: {}", fixed.join("; ")));
+}
+
+/// `:derive <column> = <expression>`: fill every row of the column from the
+/// expression and keep it up to date as its source cells change
+fn derive_column(app: &mut App, spec: &str) {
+    if !spec.contains('=') {
+        app.status_messages
+            .push("Usage: :derive <column> = <expression>");
+        return;
+    }
+    let fill = match FillSpec::parse(spec, &app.document) {
+        Ok(fill) => fill,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    let column = fill.column.get();
+    let text = spec.split_once('=').map_or("", |(_, expr)| expr);
+    if let Err(err) = app
+        .view_state
+        .derived_columns
+        .set(column, text, fill.expr.clone())
+    {
+        app.status_messages.push(StatusMessage::error(err));
+        return;
+    }
+
+    let (values, errors) = fill.values(&app.document, 0..app.document.row_count());
+    let mut change = fill.change(&app.document, values);
+    change.label = format!("derive {}", app.document.headers[column]);
+    app.commit_change(change);
+    let skipped = match errors.first() {
+        Some((row, err)) => format!(", skipped {} (row {}: {})", errors.len(), row + 1, err),
+        None => String::new(),
+    };
+    app.status_messages.push(format!(
+        "{} = {} (kept up to date{}; :deps lists derived columns)",
+        app.document.headers[column],
+        text.trim(),
+        skipped
+    ));
+}
+
+/// `:underive [column]`: stop keeping a column (the current one by default)
+/// up to date; its values stay
+fn underive_column(app: &mut App, name: &str) {
+    let column = if name.is_empty() {
+        Some(app.view_state.selected_column)
+    } else {
+        column_by_name_or_letter(app, name)
+    };
+    let Some(column) = column else {
+        app.status_messages
+            .push(format!("No column named {}", name));
+        return;
+    };
+    let header = app.document.get_header(column).to_string();
+    let message = if app.view_state.derived_columns.remove(column.get()) {
+        format!("{} is plain data again", header)
+    } else {
+        format!("{} is not derived", header)
+    };
+    app.status_messages.push(message);
+}
+
+/// `:deps`: which columns are derived, from what
+fn show_dependencies(app: &mut App) {
+    let document = &app.document;
+    let name = |col: usize| {
+        format!(
+            "{} {}",
+            crate::ui::column_to_excel_letter(col),
+            document.get_header(ColIndex::new(col))
+        )
+    };
+    let listed: Vec<String> = app
+        .view_state
+        .derived_columns
+        .iter()
+        .map(|derived| {
+            let sources: Vec<String> = derived.sources().into_iter().map(name).collect();
+            format!(
+                "{} = {} (from {})",
+                name(derived.column),
+                derived.text,
+                if sources.is_empty() {
+                    "no columns".to_string()
+                } else {
+                    sources.join(", ")
+                }
+            )
+        })
+        .collect();
+    let message = if listed.is_empty() {
+        "No derived columns (:derive <column> = <expression>)".to_string()
+    } else {
+        listed.join("; ")
+    };
+    app.status_messages.push(message);
+}
+
+/// `:fill <column> = <expression>`: overwrite the column in the range, the
+/// filtered rows or every row, as one undoable change
+fn fill_column(app: &mut App, range: Option<RangeInclusive<usize>>, spec: &str) {
+    if let Some(fill) = FillDown::parse(spec) {
+        match fill {
+            Ok(fill) => {
+                let rows = app.fill_rows(range.clone());
+                let col = app.view_state.selected_column.get();
+                if fill_down(app, &fill, &rows, col..=col) {
+                    app.last_change = Some(LastChange::Fill {
+                        spec: spec.to_string(),
+                        rows: range.map(|range| range.count()),
+                    });
+                }
+            }
+            Err(err) => app.status_messages.push(err),
+        }
+        return;
+    }
+    let spec_text = spec;
+    let spec = match FillSpec::parse(spec, &app.document) {
+        Ok(spec) => spec,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    let rows = app.fill_rows(range.clone());
+    let (values, errors) = spec.values(&app.document, rows);
+    let filled = values.len();
+    let change = spec.change(&app.document, values);
+    let name = app.document.headers[spec.column.get()].clone();
+    if change.edits.is_empty() && errors.is_empty() {
+        app.status_messages
+            .push(format!("{} already holds these values", name));
+        return;
+    }
+    app.commit_change(change);
+    app.last_change = Some(LastChange::Fill {
+        spec: spec_text.to_string(),
+        rows: range.map(|range| range.count()),
+    });
+
+    let skipped = match errors.first() {
+        Some((row, err)) => format!(", skipped {} (row {}: {})", errors.len(), row + 1, err),
+        None => String::new(),
+    };
+    app.status_messages.push(format!(
+        "Filled {} in {} rows{} - u to undo",
+        name, filled, skipped
+    ));
+}
+
+/// Continue the first of `rows` down the rest of them in `columns`
+/// (`:fill down`, `:fill series`, `f`/`F` in Visual mode); returns true if
+/// cells were filled
+fn fill_down(
+    app: &mut App,
+    fill: &FillDown,
+    rows: &[usize],
+    columns: RangeInclusive<usize>,
+) -> bool {
+    let (message, filled) = match fill.change(&app.document, rows, columns) {
+        Ok(change) if change.edits.is_empty() => ("Nothing to fill".to_string(), false),
+        Ok(change) => {
+            let message = format!(
+                "Filled {} cells ({}) - u to undo",
+                change.edits.len(),
+                change.label
+            );
+            app.commit_change(change);
+            (message, true)
+        }
+        Err(err) => (err, false),
+    };
+    app.status_messages.push(message);
+    filled
+}
+
+/// `:diff <file> [key column]`: compare the document with another version
+/// of it, marking added rows and changed cells; `:diff export <file>`
+/// writes the differences found to a file
+fn diff_document(app: &mut App, args: &str) {
+    const USAGE: &str = "Usage: :diff <file> [key column] or :diff export <file>";
+    let mut words = args.split_whitespace();
+    let (Some(first), second) = (words.next(), words.next()) else {
+        app.status_messages.push(USAGE);
+        return;
+    };
+    if words.next().is_some() {
+        app.status_messages.push(USAGE);
+        return;
+    }
+
+    if first == "export" {
+        let Some(path) = second else {
+            app.status_messages.push(USAGE);
+            return;
+        };
+        let message = match &app.diff {
+            None => "Nothing to export (run :diff <file> first)".to_string(),
+            Some(diff) => match diff.export(std::path::Path::new(path)) {
+                Ok(()) => format!("Wrote {} to {}", diff.summary(), path),
+                Err(e) => format!("{:#}", e),
+            },
+        };
+        app.status_messages.push(message);
+        return;
+    }
+
+    let key = match second {
+        None => None,
+        Some(name) => match app.document.find_column(name) {
+            Some(col) => Some(col.get()),
+            None => {
+                app.status_messages
+                    .push(format!("No column named {}", name));
+                return;
+            }
+        },
+    };
+    let other = match app
+        .session
+        .config()
+        .read_document(std::path::Path::new(first))
+    {
+        Ok(other) => other,
+        Err(e) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", e)));
+            return;
+        }
+    };
+    let diff = Diff::compare(&app.document, &other, key);
+
+    app.view_state.marks.clear_kind(MarkKind::SearchHit);
+    for row in &diff.rows {
+        match row.kind {
+            DiffKind::Added => app.view_state.marks.add(row.row, None, MarkKind::SearchHit),
+            DiffKind::Changed => {
+                for &col in &row.columns {
+                    app.view_state
+                        .marks
+                        .add(row.row, Some(col), MarkKind::SearchHit);
+                }
+            }
+            DiffKind::Removed => {}
+        }
+    }
+    let message = if diff.rows.is_empty() {
+        format!("No differences from {}", first)
+    } else {
+        format!(
+            "{} vs {}; n/N to step through, :diff export <file> to save",
+            diff.summary(),
+            first
+        )
+    };
+    app.diff = Some(diff);
+    app.status_messages.push(message);
+}
+
+/// `:share [socket]`: let other instances follow this view read-only
+/// (`lazycsv --follow <socket>`); `:share` again stops
+fn share_view(app: &mut App, arg: Option<&str>) {
+    if let Some(leader) = app.leader.take() {
+        app.status_messages.push(format!(
+            "Stopped sharing ({} followers left)",
+            leader.follower_count()
+        ));
+        return;
+    }
+    let path = match arg.filter(|a| !a.is_empty()) {
+        Some(path) => Some(std::path::PathBuf::from(path)),
+        None => crate::session::share::default_socket_path(),
+    };
+    let Some(path) = path else {
+        app.status_messages.push("Usage: :share <socket path>");
+        return;
+    };
+    app.status_messages
+        .push(match crate::session::Leader::bind(&path) {
+            Ok(leader) => {
+                app.leader = Some(leader);
+                format!("Sharing: lazycsv --follow {}", path.display())
+            }
+            Err(e) => format!("{:#}", e),
+        });
+}
+
+/// `:session save <file>`: write the files and how each is set up, for
+/// `lazycsv --session <file>` to open them the same way
+fn save_session(app: &mut App, arg: Option<&str>) {
+    let path = arg
+        .and_then(|arg| arg.strip_prefix("save"))
+        .map(str::trim)
+        .unwrap_or_default();
+    if path.is_empty() {
+        app.status_messages.push("Usage: :session save <file>");
+        return;
+    }
+    let snapshot = app.session_snapshot();
+    match snapshot.save_to(std::path::Path::new(path)) {
+        Ok(()) => app.status_messages.push(format!(
+            "Saved {} file{} to {} (lazycsv --session {})",
+            snapshot.files.len(),
+            if snapshot.files.len() == 1 { "" } else { "s" },
+            path,
+            path
+        )),
+        Err(e) => app
+            .status_messages
+            .push(StatusMessage::error(format!("{:#}", e))),
+    }
+}
+
+/// `:sort [column] [asc|desc]`: sort the range or the whole document as
+/// one undoable change
+fn sort_rows(app: &mut App, range: Option<RangeInclusive<usize>>, args: &str) {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let spec = match SortSpec::parse(args, &app.document, app.view_state.selected_column) {
+        Ok(spec) => spec,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    let whole_file = range.is_none();
+    let rows = match range {
+        Some(range) => {
+            let end = (*range.end() + 1).min(app.document.row_count());
+            (*range.start()).min(end)..end
+        }
+        None => 0..app.document.row_count(),
+    };
+    let described = match &rows {
+        rows if rows.start == 0 && rows.end == app.document.row_count() => {
+            format!("{} rows", rows.len())
+        }
+        rows => format!("rows {}-{}", rows.start + 1, rows.end),
+    };
+    let change = spec.change(&app.document, rows, &app.options.locale());
+    let name = app.document.headers[spec.column.get()].clone();
+    let direction = if spec.descending { ", descending" } else { "" };
+    let message = if change.edits.is_empty() {
+        format!("{} already sorted by {}{}", described, name, direction)
+    } else {
+        format!("Sorted {} by {}{}", described, name, direction)
+    };
+    app.commit_change(change);
+    if whole_file {
+        // Sorting by a column again overrides the earlier sort by it
+        app.view_state
+            .sorts
+            .retain(|sort| sort.column != spec.column);
+        app.view_state.sorts.push(spec);
+    }
+    app.status_messages.push(message);
+}
+
+/// Run `:copy`: the range's rows (or the current row) to the clipboard
+fn copy_rows(app: &mut App, range: Option<RangeInclusive<usize>>, args: &str) {
+    let format = match CopyFormat::parse(args, &app.document) {
+        Ok(format) => format,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    let rows = match range {
+        Some(range) => app.fill_rows(Some(range)),
+        None => app
+            .get_selected_row()
+            .map(|row| row.get())
+            .into_iter()
+            .collect(),
+    };
+    if rows.is_empty() {
+        app.status_messages.push("No rows to copy");
+        return;
+    }
+    let text = format.format(&app.document, &rows);
+    let count = match rows.len() {
+        1 => "1 row".to_string(),
+        n => format!("{} rows", n),
+    };
+    app.status_messages.push(match clipboard::copy(&text) {
+        Ok(via) => format!("Copied {} as {} ({})", count, format.name(), via),
+        Err(e) => format!("{:#}", e),
+    });
+}
+
+/// `:csearch <text>`: mark the cells of the selected column containing
+/// `text` for n/N, and jump to the next one
+fn search_column(app: &mut App, query: &str) {
+    if query.is_empty() {
+        app.status_messages.push("Usage: :csearch <text>");
+        return;
+    }
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let pattern = match SearchPattern::new(query, &app.options) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            app.status_messages.push(StatusMessage::error(err));
+            return;
+        }
+    };
+    let column = app.view_state.selected_column;
+    let rows = search::find_in_column(&app.document, column, &pattern);
+    app.view_state.marks.clear_kind(MarkKind::SearchHit);
+    let name = format!(
+        "{} ({})",
+        crate::ui::column_to_excel_letter(column.get()),
+        app.document.get_header(column)
+    );
+    if rows.is_empty() {
+        app.status_messages
+            .push(format!("No \"{}\" in column {}", query, name));
+        return;
+    }
+    for &row in &rows {
+        app.view_state
+            .marks
+            .add(row, Some(column.get()), MarkKind::SearchHit);
+    }
+    navigation::commands::goto_match(app, 1, true);
+    let count = match rows.len() {
+        1 => "1 match".to_string(),
+        n => format!("{} matches", n),
+    };
+    app.status_messages.push(format!(
+        "{} for \"{}\" in column {} (n/N to step through)",
+        count, query, name
+    ));
+}
+
+/// `:long <length> [truncate]`: mark the cells longer than `length` for
+/// n/N, or offer to cut them down to it
+fn find_long_cells(app: &mut App, range: Option<RangeInclusive<usize>>, args: &str) {
+    let long = match LongCells::parse(args) {
+        Ok(long) => long,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    let cells = long.find(&app.document, &app.fill_rows(range));
+    app.view_state.marks.clear_kind(MarkKind::SearchHit);
+    if cells.is_empty() {
+        app.status_messages
+            .push(format!("No cells longer than {} chars", long.limit));
+        return;
+    }
+    for cell in &cells {
+        app.view_state
+            .marks
+            .add(cell.row, Some(cell.col), MarkKind::SearchHit);
+    }
+    let count = match cells.len() {
+        1 => "1 cell".to_string(),
+        n => format!("{} cells", n),
+    };
+
+    if long.truncate {
+        let change = long.change(&app.document, &cells);
+        let message = format!("Truncated {} to {} chars", count, long.limit);
+        app.ask(
+            format!("Truncate {} to {} chars? (y/n)", count, long.limit),
+            ConfirmAction::Commit { change, message },
+        );
+        return;
+    }
+
+    let listed: Vec<String> = cells
+        .iter()
+        .take(LISTED_CELLS)
+        .map(|cell| {
+            format!(
+                "{}{} ({})",
+                crate::ui::column_to_excel_letter(cell.col),
+                cell.row + 1,
+                cell.chars
+            )
+        })
+        .collect();
+    let more = if cells.len() > LISTED_CELLS {
+        ", ..."
+    } else {
+        ""
+    };
+    app.status_messages.push(format!(
+        "{} longer than {} chars: {}{} (n/N to step through)",
+        count,
+        long.limit,
+        listed.join(", "),
+        more
+    ));
+}
+
+/// Keys while the statistics panel is open: h/l follow the column, Esc/q close
+fn handle_stats_panel_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('h') | KeyCode::Left => {
+            navigation::move_left_by(app, 1);
+            show_column_stats(app);
+        }
+        KeyCode::Char('l') | KeyCode::Right => {
+            navigation::move_right_by(app, 1);
+            show_column_stats(app);
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.view_state.stats_panel = None;
+        }
+        KeyCode::Char('?') => handle_help_toggle(app),
+        _ => {}
+    }
+}
+
+/// `:grepall <pattern>`: list the matching cells of every file; without a
+/// pattern, show the last results again
+fn grep_all(app: &mut App, query: &str) {
+    if query.is_empty() {
+        match app.grep.as_mut() {
+            Some(view) => view.visible = true,
+            None => app.status_messages.push("Usage: :grepall <pattern>"),
+        }
+        return;
+    }
+    let pattern = match SearchPattern::new(query, &app.options) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            app.status_messages.push(StatusMessage::error(err));
+            return;
+        }
+    };
+    let view = app.grep_all(&pattern);
+    for error in &view.errors {
+        app.status_messages
+            .push(StatusMessage::warning(error.clone()));
+    }
+    if view.matches.is_empty() {
+        app.status_messages
+            .push(format!("No \"{}\" in any file", query));
+        return;
+    }
+    app.grep = Some(view);
+}
+
+/// `:replaceall /pattern/replacement/`: count the replacements in every
+/// file and ask before writing them
+fn replace_all(app: &mut App, arg: &str) {
+    let (query, replacement) = match replace::parse_replacement(arg) {
+        Ok(parts) => parts,
+        Err(err) => {
+            app.status_messages.push(err);
+            return;
+        }
+    };
+    if app.document.is_dirty || app.pane_is_dirty() {
+        app.status_messages.push(StatusMessage::warning(
+            "Unsaved changes! Save with :w before :replaceall",
+        ));
+        return;
+    }
+    if app.session.is_changed_on_disk() {
+        app.status_messages.push(StatusMessage::warning(format!(
+            "{} changed on disk since it was read (:e! reloads it)",
+            app.session.get_current_file().display()
+        )));
+        return;
+    }
+    let pattern = match SearchPattern::new(&query, &app.options) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            app.status_messages.push(StatusMessage::error(err));
+            return;
+        }
+    };
+    let (changes, errors) = app.plan_replace_all(&pattern, &replacement);
+    for error in errors {
+        app.status_messages.push(StatusMessage::warning(error));
+    }
+    if changes.is_empty() {
+        app.status_messages
+            .push(format!("No \"{}\" in any file", query));
+        return;
+    }
+    let files = app.session.files();
+    let counts: Vec<String> = changes
+        .iter()
+        .map(|(file, change)| {
+            let name = files[*file]
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+            format!("{} {}", name, change.edits.len())
+        })
+        .collect();
+    let cells: usize = changes.iter().map(|(_, change)| change.edits.len()).sum();
+    app.ask(
+        format!(
+            "Replace {} cell{} ({}) and write {} file{}? (y/n)",
+            cells,
+            if cells == 1 { "" } else { "s" },
+            counts.join(", "),
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" }
+        ),
+        ConfirmAction::ReplaceAll { changes },
+    );
+}
+
+/// Keys in the grep view: j/k move, Enter jumps to the match, Esc/q close
+fn handle_grep_view_key(app: &mut App, key: KeyEvent) {
+    let Some(view) = app.grep.as_mut() else {
+        return;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => view.cursor.move_by(1),
+        KeyCode::Char('k') | KeyCode::Up => view.cursor.move_by(-1),
+        KeyCode::Char('d') if ctrl => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::Char('u') if ctrl => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::PageDown => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::PageUp => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::Char('g') | KeyCode::Home => view.cursor.select_first(),
+        KeyCode::Char('G') | KeyCode::End => view.cursor.select_last(),
+        KeyCode::Enter => {
+            if let Some(found) = view.selected_match().cloned() {
+                goto_grep_match(app, &found);
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => view.visible = false,
+        KeyCode::Char('?') => handle_help_toggle(app),
+        _ => {}
+    }
+}
+
+/// Go to a `:grepall` match, switching files if it is in another one
+fn goto_grep_match(app: &mut App, found: &GrepMatch) {
+    if found.file >= app.session.file_count() {
+        return;
+    }
+    if app.pane_file() == Some(found.file) {
+        app.switch_pane();
+    } else if found.file != app.session.active_file_index() {
+        if app.document.is_dirty {
+            app.status_messages.push(StatusMessage::warning(
+                "Unsaved changes! Save with :w before jumping to another file",
+            ));
+            return;
+        }
+        leave_current_file(app);
+        app.session.select_file(found.file);
+        if let Err(err) = app.reload_current_file() {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", err)));
+            return;
+        }
+    }
+    if let Some(view) = app.grep.as_mut() {
+        view.visible = false;
+    }
+    if found.row >= app.document.row_count() {
+        app.status_messages
+            .push(format!("Row {} is no longer in the file", found.row + 1));
+    } else if app.select_document_row(RowIndex::new(found.row)) {
+        let col = found.col.min(app.document.column_count().saturating_sub(1));
+        navigation::commands::select_column(app, col);
+        app.view_state.viewport_mode = crate::ui::ViewportMode::Auto;
+    } else {
+        app.status_messages
+            .push(format!("Row {} is hidden by the filter", found.row + 1));
+    }
+}
+
+/// `:b 3` / `:b sales`: switch to a file of the session by its number in
+/// `:ls` or part of its name; with `force` (`:b!`), the open file's unsaved
+/// changes are dropped
+fn switch_to_buffer(app: &mut App, name: &str, force: bool) {
+    if name.is_empty() {
+        app.status_messages.push("Usage: :b <number or name>");
+        return;
+    }
+    let index = match app.find_buffer(name) {
+        Ok(index) => index,
+        Err(message) => {
+            app.status_messages.push(StatusMessage::error(message));
+            return;
+        }
+    };
+    if index == app.session.active_file_index() {
+        return;
+    }
+    if app.pane_file() == Some(index) {
+        app.switch_pane();
+        return;
+    }
+    if app.document.is_dirty && !force {
+        app.status_messages.push(StatusMessage::warning(
+            "No write since last change (add ! to override)",
+        ));
+        return;
+    }
+    leave_current_file(app);
+    app.session.select_file(index);
+    if let Err(err) = app.reload_current_file() {
+        app.status_messages
+            .push(StatusMessage::error(format!("{:#}", err)));
+    }
+}
+
+/// `:e path`: open a file, adding it to the session if it isn't in it;
+/// with `force` (`:e!`), the open file's unsaved changes are dropped
+fn open_file(app: &mut App, path: std::path::PathBuf, force: bool) {
+    let existing = app.session.file_index(&path);
+    if let Some(index) = existing.filter(|&index| app.pane_file() == Some(index)) {
+        app.status_messages.push(format!(
+            "{} is in the other pane",
+            app.session.files()[index].display()
+        ));
+        app.switch_pane();
+        return;
+    }
+    if app.document.is_dirty && !force {
+        app.status_messages.push(StatusMessage::warning(
+            "No write since last change (add ! to override)",
+        ));
+        return;
+    }
+    if !path.is_file() {
+        app.status_messages.push(StatusMessage::error(format!(
+            "No such file: {}",
+            path.display()
+        )));
+        return;
+    }
+    // Read it first, so a file that can't be read leaves the session alone
+    let document = match app.session.config_for(&path).read_document(&path) {
+        Ok(document) => document,
+        Err(err) => {
+            app.status_messages
+                .push(StatusMessage::error(format!("{:#}", err)));
+            return;
+        }
+    };
+
+    let index = app.session.add_file(path);
+    leave_current_file(app);
+    app.session.select_file(index);
+    // Handed over through the cache so the file isn't parsed twice
+    app.session.cache_document(document);
+    if let Err(err) = app.reload_current_file() {
+        app.status_messages
+            .push(StatusMessage::error(format!("{:#}", err)));
+        return;
+    }
+    app.sort_files();
+    app.status_messages.push(format!(
+        "Opened {} ({} rows)",
+        app.document.filename,
+        app.document.row_count()
+    ));
+    if !app.offer_recovery() {
+        app.offer_resume_point();
+    }
+}
+
+/// Replace the table with the value counts of the selected column
+fn show_frequency_view(app: &mut App) {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let view = FrequencyView::new(&app.document, app.view_state.selected_column);
+    app.view_state.frequency_view = Some(view);
+}
+
+/// Keys in the frequency view: j/k move, Enter filters on the value, Esc/q close
+fn handle_frequency_view_key(app: &mut App, key: KeyEvent) {
+    let Some(view) = app.view_state.frequency_view.as_mut() else {
+        return;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => view.cursor.move_by(1),
+        KeyCode::Char('k') | KeyCode::Up => view.cursor.move_by(-1),
+        KeyCode::Char('d') if ctrl => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::Char('u') if ctrl => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::PageDown => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::PageUp => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::Char('g') | KeyCode::Home => view.cursor.select_first(),
+        KeyCode::Char('G') | KeyCode::End => view.cursor.select_last(),
+        KeyCode::Enter => {
+            let column = view.column;
+            if let Some(value) = view.selected_value().map(str::to_string) {
+                app.view_state.frequency_view = None;
+                let filter = RowFilter::matching(&app.document, column, &value);
+                apply_row_filter(app, filter);
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.view_state.frequency_view = None;
+        }
+        KeyCode::Char('?') => handle_help_toggle(app),
+        _ => {}
+    }
+}
+
+/// `:set headers` / `:set noheaders` (also `headers!`, `invheaders` and
+/// `headers?`): read the first line as column names or as data. None if
+/// the option is another one.
+fn set_headers_option(app: &mut App, spec: &str) -> Option<StatusMessage> {
+    let has_headers = !app.session.config().no_headers;
+    let headers = match spec.trim() {
+        "headers" | "hdr" => true,
+        "noheaders" | "nohdr" => false,
+        "invheaders" | "headers!" | "invhdr" | "hdr!" => !has_headers,
+        "headers?" | "hdr?" => {
+            return Some(StatusMessage::from(
+                if has_headers { "headers" } else { "noheaders" }.to_string(),
+            ))
+        }
+        _ => return None,
+    };
+    Some(match app.set_headers(headers) {
+        Ok(message) => StatusMessage::from(message),
+        Err(message) => StatusMessage::error(message),
+    })
+}
+
+/// `:bookmarks` lists the bookmarked rows in place of the table;
+/// `:bookmarks clear` removes them all
+fn show_bookmarks(app: &mut App, arg: Option<&str>) {
+    let rows = app.bookmarks();
+    match arg.map(str::trim) {
+        Some("clear") => {
+            app.view_state.marks.clear_kind(MarkKind::Bookmark);
+            app.store_bookmarks();
+            app.status_messages.push(format!(
+                "Removed {} bookmark{}",
+                rows.len(),
+                if rows.len() == 1 { "" } else { "s" }
+            ));
+        }
+        None | Some("") if rows.is_empty() => {
+            app.status_messages
+                .push("No bookmarks (mB bookmarks a row)");
+        }
+        None | Some("") => {
+            let current = app.get_selected_row().map_or(0, |row| row.get());
+            app.view_state.bookmark_view = Some(BookmarkView::new(rows, current));
+        }
+        Some(other) => app.status_messages.push(format!(
+            "Unknown argument {} (use :bookmarks [clear])",
+            other
+        )),
+    }
+}
+
+/// Keys in the bookmark list: j/k move, Enter jumps to the row, d removes
+/// its bookmark, Esc/q close
+fn handle_bookmark_view_key(app: &mut App, key: KeyEvent) {
+    let Some(view) = app.view_state.bookmark_view.as_mut() else {
+        return;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => view.cursor.move_by(1),
+        KeyCode::Char('k') | KeyCode::Up => view.cursor.move_by(-1),
+        KeyCode::Char('d') if ctrl => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::Char('u') if ctrl => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::PageDown => view.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::PageUp => view.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::Char('g') | KeyCode::Home => view.cursor.select_first(),
+        KeyCode::Char('G') | KeyCode::End => view.cursor.select_last(),
+        KeyCode::Char('d') | KeyCode::Char('x') | KeyCode::Delete => {
+            if let Some(row) = view.selected_row() {
+                view.remove_selected();
+                if view.rows.is_empty() {
+                    app.view_state.bookmark_view = None;
+                }
+                app.view_state.marks.remove(row, None, MarkKind::Bookmark);
+                app.store_bookmarks();
+                app.status_messages
+                    .push(format!("Removed bookmark of row {}", row + 1));
+            }
+        }
+        KeyCode::Enter => {
+            if let Some(row) = view.selected_row() {
+                app.view_state.bookmark_view = None;
+                let message = if app.select_document_row(RowIndex::new(row)) {
+                    app.view_state.viewport_mode = ViewportMode::Auto;
+                    format!("Row {}", row + 1)
+                } else {
+                    format!("Row {} is hidden by the filter", row + 1)
+                };
+                app.status_messages.push(message);
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.view_state.bookmark_view = None;
+        }
+        KeyCode::Char('?') => handle_help_toggle(app),
+        _ => {}
+    }
+}
+
+/// Open the file picker over the table, on the open file
+fn open_file_picker(app: &mut App) {
+    let labels = app
+        .session
+        .files()
+        .iter()
+        .map(|path| file_picker::file_label(path))
+        .collect();
+    app.view_state.file_picker = Some(FilePicker::new(labels, app.session.active_file_index()));
+}
+
+/// Keys in the file picker: typing narrows the list, Up/Down (or
+/// Ctrl+n/Ctrl+p) move, Enter opens the selected file, Esc closes
+fn handle_file_picker_key(app: &mut App, key: KeyEvent) -> InputResult {
+    let Some(picker) = app.view_state.file_picker.as_mut() else {
+        return InputResult::Continue;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Down => picker.cursor.move_by(1),
+        KeyCode::Up => picker.cursor.move_by(-1),
+        KeyCode::Char('n') | KeyCode::Char('j') if ctrl => picker.cursor.move_by(1),
+        KeyCode::Char('p') | KeyCode::Char('k') if ctrl => picker.cursor.move_by(-1),
+        KeyCode::PageDown => picker.cursor.move_by(navigation::PAGE_SIZE as isize),
+        KeyCode::PageUp => picker.cursor.move_by(-(navigation::PAGE_SIZE as isize)),
+        KeyCode::Home => picker.cursor.select_first(),
+        KeyCode::End => picker.cursor.select_last(),
+        KeyCode::Char('u') if ctrl => picker.clear(),
+        KeyCode::Backspace => picker.pop(),
+        KeyCode::Enter => {
+            let Some(file) = picker.selected_file() else {
+                app.status_messages.push("No file matches");
+                return InputResult::Continue;
+            };
+            let leaves_file =
+                file != app.session.active_file_index() && app.pane_file() != Some(file);
+            if leaves_file && app.document.is_dirty {
+                app.status_messages.push(StatusMessage::warning(
+                    "Unsaved changes! Save with :w before opening another file",
+                ));
+                return InputResult::Continue;
+            }
+            app.view_state.file_picker = None;
+            return handle_file_select(app, file);
+        }
+        KeyCode::Esc => app.view_state.file_picker = None,
+        KeyCode::Char(c) if !ctrl => picker.push(c),
+        _ => {}
+    }
+    InputResult::Continue
+}
+
+/// Keys in the file browser: j/k move, Enter or l opens the selected file
+/// or directory, h or Backspace goes up, Esc or q closes
+fn handle_file_browser_key(app: &mut App, key: KeyEvent) -> InputResult {
+    let Some(browser) = app.view_state.file_browser.as_mut() else {
+        return InputResult::Continue;
+    };
+
+    let target = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            browser.cursor.move_by(1);
+            None
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            browser.cursor.move_by(-1);
+            None
+        }
+        KeyCode::PageDown => {
+            browser.cursor.move_by(navigation::PAGE_SIZE as isize);
+            None
+        }
+        KeyCode::PageUp => {
+            browser.cursor.move_by(-(navigation::PAGE_SIZE as isize));
+            None
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            browser.cursor.select_first();
+            None
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            browser.cursor.select_last();
+            None
+        }
+        KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => browser.selected_entry().cloned(),
+        KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace | KeyCode::Char('-') => {
+            browser.dir.parent().map(|parent| BrowserEntry {
+                name: "../".to_string(),
+                path: parent.to_path_buf(),
+                is_dir: true,
+            })
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.view_state.file_browser = None;
+            None
+        }
+        KeyCode::Char('?') => {
+            handle_help_toggle(app);
+            None
+        }
+        _ => None,
+    };
+    let Some(entry) = target else {
+        return InputResult::Continue;
+    };
+
+    if entry.is_dir {
+        let extensions = app.session.extensions().to_vec();
+        if let Some(browser) = app.view_state.file_browser.as_mut() {
+            if let Err(e) = browser.change_dir(&entry.path, &extensions) {
+                app.status_messages
+                    .push(StatusMessage::error(format!("{:#}", e)));
+            }
+        }
+        return InputResult::Continue;
+    }
+    if app.session.is_current_file(&entry.path) {
+        app.view_state.file_browser = None;
+        return InputResult::Continue;
+    }
+    let in_pane = app
+        .session
+        .file_index(&entry.path)
+        .is_some_and(|index| app.pane_file() == Some(index));
+    if app.document.is_dirty && !in_pane {
+        app.status_messages.push(StatusMessage::warning(
+            "Unsaved changes! Save with :w before opening another file",
+        ));
+        return InputResult::Continue;
+    }
+    app.view_state.file_browser = None;
+    open_file(app, entry.path, false);
+    InputResult::Continue
+}
+
+/// Show the selected row transposed in place of the table
+fn show_record_view(app: &mut App) {
+    if app.get_selected_row().is_none() {
+        app.status_messages.push("No rows");
+        return;
+    }
+    app.view_state.record_view = true;
+}
+
+/// Keys in the record view: j/k move between fields, h/l between rows,
+/// Esc/q/Enter go back to the table on the selected cell
+fn handle_record_view_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let last = app.document.column_count().saturating_sub(1);
+    let field = app.view_state.selected_column.get();
+    let select_field = |app: &mut App, col: usize| {
+        app.view_state.selected_column = ColIndex::new(col.min(last));
+    };
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => select_field(app, field + 1),
+        KeyCode::Char('k') | KeyCode::Up => select_field(app, field.saturating_sub(1)),
+        KeyCode::Char('d') if ctrl => select_field(app, field + navigation::PAGE_SIZE),
+        KeyCode::Char('u') if ctrl => {
+            select_field(app, field.saturating_sub(navigation::PAGE_SIZE))
+        }
+        KeyCode::PageDown => select_field(app, field + navigation::PAGE_SIZE),
+        KeyCode::PageUp => select_field(app, field.saturating_sub(navigation::PAGE_SIZE)),
+        KeyCode::Char('g') | KeyCode::Home => select_field(app, 0),
+        KeyCode::Char('G') | KeyCode::End => select_field(app, last),
+        KeyCode::Char('l') | KeyCode::Right => navigation::move_down_by(app, 1),
+        KeyCode::Char('h') | KeyCode::Left => navigation::move_up_by(app, 1),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+            app.view_state.record_view = false;
+            // The selected field may be hidden inside a collapsed group
+            let col = app.view_state.selected_column.get();
+            navigation::commands::select_column(app, col);
+        }
+        KeyCode::Char('?') => handle_help_toggle(app),
+        _ => {}
+    }
+}
+
+/// Show the selected cell in full in the magnifier
+fn open_magnifier(app: &mut App) {
+    let Some((row, col)) = selected_cell(app) else {
+        return;
+    };
+    let value = app.document.get_cell(row, col);
+    app.magnifier = Some(Magnifier::new(row.get(), col.get(), value));
+    app.mode = Mode::Magnifier;
+}
+
+/// Keys in the magnifier: scrolling, `w` to toggle wrapping, `/` and n/N
+/// to search, Esc/q to go back to the table
+fn handle_magnifier_key(app: &mut App, key: KeyEvent) {
+    app.status_messages.clear_transient();
+    let Some(magnifier) = app.magnifier.as_mut() else {
+        app.mode = Mode::Normal;
+        return;
+    };
+
+    // Typing a search
+    if let Some(input) = magnifier.search_input.as_mut() {
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            // Backspace on an empty query cancels the search like Esc
+            KeyCode::Backspace if input.pop().is_some() => {}
+            KeyCode::Backspace | KeyCode::Esc => magnifier.search_input = None,
+            KeyCode::Enter => {
+                let query = magnifier.search_input.take().unwrap_or_default();
+                let pattern = match SearchPattern::new(&query, &app.options) {
+                    Ok(pattern) => pattern,
+                    Err(err) => {
+                        app.status_messages.push(StatusMessage::error(err));
+                        return;
+                    }
+                };
+                let message = match magnifier.search(&pattern) {
+                    _ if query.is_empty() => None,
+                    0 => Some(format!("Pattern not found: {}", query)),
+                    count => Some(format!("Match 1 of {}", count)),
+                };
+                match message {
+                    Some(message) => app.status_messages.push(message),
+                    None => app.status_messages.clear(),
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let half_page = (magnifier.height / 2).max(1) as isize;
+    let pending_g = std::mem::take(&mut magnifier.pending_g);
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => magnifier.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => magnifier.scroll_by(-1),
+        KeyCode::Char('d') if ctrl => magnifier.scroll_by(half_page),
+        KeyCode::Char('u') if ctrl => magnifier.scroll_by(-half_page),
+        KeyCode::PageDown => magnifier.scroll_by(half_page * 2),
+        KeyCode::PageUp => magnifier.scroll_by(-half_page * 2),
+        KeyCode::Char('g') if pending_g => magnifier.scroll_to_top(),
+        KeyCode::Char('g') => magnifier.pending_g = true,
+        KeyCode::Home => magnifier.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => magnifier.scroll_to_bottom(),
+        KeyCode::Char('l') | KeyCode::Right if !magnifier.wrap => magnifier.column_offset += 1,
+        KeyCode::Char('h') | KeyCode::Left => {
+            magnifier.column_offset = magnifier.column_offset.saturating_sub(1)
+        }
+        KeyCode::Char('w') => {
+            magnifier.toggle_wrap();
+            let state = if magnifier.wrap { "on" } else { "off" };
+            app.status_messages.push(format!("Line wrapping {}", state));
+        }
+        KeyCode::Char('/') => magnifier.search_input = Some(String::new()),
+        KeyCode::Char(c @ ('n' | 'N')) => {
+            let message = match magnifier.next_match(c == 'n') {
+                Some(number) => format!("Match {} of {}", number, magnifier.match_count()),
+                None if magnifier.query.is_empty() => "No previous search".to_string(),
+                None => format!("Pattern not found: {}", magnifier.query),
+            };
+            app.status_messages.push(message);
         }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.magnifier = None;
+            app.mode = Mode::Normal;
+        }
+        _ => {}
     }
 }
 
-/// Returns true if navigation commands are allowed (help overlay is closed)
-fn is_navigation_allowed(app: &App) -> bool {
-    !app.view_state.help_overlay_visible
+/// `:freeze` pins columns up to the cursor, `:freeze <n>` the first n columns
+/// and `:freeze <letter>` columns up to and including that one; `:freeze last`
+/// pins the last column on the right
+fn handle_freeze_command(app: &mut App, arg: Option<&str>) {
+    if matches!(arg, Some("last" | "$")) {
+        navigation::commands::freeze_last_column(app, true);
+        app.status_messages
+            .push("Pinned the last column on the right");
+        return;
+    }
+
+    // Counted in the display order, which may differ from the file's
+    let position = |col: usize| app.view_state.column_position(col);
+    let count = match arg {
+        None => Ok(position(app.view_state.selected_column.get()) + 1),
+        Some(arg) => arg.parse::<usize>().or_else(|_| {
+            crate::ui::utils::excel_letter_to_column(&arg.to_uppercase())
+                .map(|col| position(col) + 1)
+        }),
+    };
+
+    let message = match count {
+        Ok(count) => {
+            navigation::commands::freeze_columns(app, count);
+            let letter =
+                |position| crate::ui::column_to_excel_letter(app.view_state.column_at(position));
+            match app.view_state.frozen_columns {
+                0 => "Columns unfrozen".to_string(),
+                1 => format!("Froze column {}", letter(0)),
+                n => format!("Froze columns {}-{}", letter(0), letter(n - 1)),
+            }
+        }
+        Err(_) => "Usage: :freeze [count or column]".to_string(),
+    };
+    app.status_messages.push(message);
 }
 
-/// Handle quit command with unsaved changes check
-fn handle_quit(app: &mut App) {
-    if app.document.is_dirty {
-        app.status_message = Some(StatusMessage::from(messages::UNSAVED_CHANGES));
+/// `:colorder` shows the display order of the columns, `:colorder a,b`
+/// moves the named columns (names or letters) to the front in that order
+/// and `:colorder reset` goes back to the file's order
+fn handle_colorder_command(app: &mut App, arg: Option<&str>) {
+    let count = app.document.column_count();
+    let label = |app: &App, col: usize| match app.document.headers.get(col) {
+        Some(header) if !header.is_empty() => header.clone(),
+        _ => crate::ui::column_to_excel_letter(col).into_owned(),
+    };
+    let message = match arg.map(str::trim) {
+        None | Some("") if app.view_state.is_reordered() => {
+            let names: Vec<String> = app
+                .view_state
+                .display_columns(count)
+                .into_iter()
+                .map(|col| label(app, col))
+                .collect();
+            format!("Column order: {}", names.join(", "))
+        }
+        None | Some("") => "Columns are in the file's order".to_string(),
+        Some("reset") => {
+            app.view_state.column_order.clear();
+            let selected = app.view_state.selected_column.get();
+            navigation::commands::update_horizontal_scroll(app, selected);
+            "Columns back in the file's order".to_string()
+        }
+        Some(names) => {
+            let mut first = Vec::new();
+            for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                match column_by_name_or_letter(app, name) {
+                    Some(col) if !first.contains(&col.get()) => first.push(col.get()),
+                    Some(_) => {}
+                    None => {
+                        app.status_messages
+                            .push(format!("No column named {}", name));
+                        return;
+                    }
+                }
+            }
+            let rest = app
+                .view_state
+                .display_columns(count)
+                .into_iter()
+                .filter(|col| !first.contains(col));
+            app.view_state.column_order = first.iter().copied().chain(rest).collect();
+            if !app.view_state.is_reordered() {
+                app.view_state.column_order.clear();
+            }
+            let selected = app.view_state.selected_column.get();
+            navigation::commands::update_horizontal_scroll(app, selected);
+            format!(
+                "Moved {} to the front (:w --reorder saves the order to the file)",
+                first
+                    .iter()
+                    .map(|&col| label(app, col))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    };
+    app.status_messages.push(message);
+}
+
+/// Show only the rows of `filter`
+fn apply_row_filter(app: &mut App, filter: RowFilter) {
+    let current = app.get_selected_row();
+    let message = format!(
+        "Filter: {} {} \"{}\" ({} of {} rows, :nofilter to clear)",
+        crate::ui::column_to_excel_letter(filter.column.get()),
+        filter.operator(),
+        filter.value,
+        filter.len(),
+        app.document.row_count()
+    );
+    app.view_state.row_filter = Some(filter);
+
+    // Stay on the current row if it matches, otherwise start at the top
+    if !current.is_some_and(|row| app.select_document_row(row)) {
+        app.view_state.table_state.select(Some(0));
+    }
+    app.view_state.viewport_mode = ViewportMode::Auto;
+    app.status_messages.push(message);
+}
+
+/// Show all rows again, keeping the cursor on the same document row
+fn clear_row_filter(app: &mut App) {
+    let current = app.get_selected_row();
+    if app.view_state.row_filter.take().is_none() {
+        app.status_messages.push("No filter active");
+        return;
+    }
+    if let Some(row) = current {
+        app.select_document_row(row);
+    }
+    app.status_messages.push("Filter cleared");
+}
+
+/// Insert a row into the document (empty unless `values` are given) as an
+/// undoable edit; the new row is marked as changed
+fn insert_row(app: &mut App, at: RowIndex, values: Vec<String>, label: &str) {
+    app.commit_change(Change {
+        edits: vec![Edit::InsertRow {
+            at: at.get(),
+            values,
+        }],
+        label: label.to_string(),
+    });
+}
+
+/// `"a`: pick the register the next yank, delete or paste uses (`""` is
+/// the unnamed register)
+fn select_register(app: &mut App, name: char) {
+    if name == '"' {
+        app.input_state.register = None;
+    } else if Registers::is_name(name) {
+        app.input_state.register = Some(name);
     } else {
-        app.should_quit = true;
+        app.input_state.register = None;
+        app.status_messages
+            .push(StatusMessage::error(format!("Invalid register: {}", name)));
     }
 }
 
-/// Toggle help overlay visibility
-fn handle_help_toggle(app: &mut App) {
-    app.view_state.help_overlay_visible = !app.view_state.help_overlay_visible;
+/// `:registers`: how many rows each register holds
+fn show_registers(app: &mut App) {
+    let unnamed = app.row_clipboard.as_ref().map(|rows| ('"', rows));
+    let registers: Vec<String> = unnamed
+        .into_iter()
+        .chain(app.registers.iter())
+        .map(|(name, rows)| format!("\"{} {}", name, messages::row_count(rows.len())))
+        .collect();
+    app.status_messages.push(if registers.is_empty() {
+        "No rows yanked or deleted yet".to_string()
+    } else {
+        registers.join("  ")
+    });
+}
+
+/// Document rows of `count` rows shown from the cursor down
+fn rows_from_cursor(app: &App, count: usize) -> Vec<usize> {
+    let Some(start) = app.view_state.table_state.selected() else {
+        return Vec::new();
+    };
+    (start..start + count)
+        .filter_map(|display| app.document_row(display))
+        .map(|row| row.get())
+        .collect()
+}
+
+/// Put yanked or deleted rows in the register picked with `"a`, returning
+/// how to name it in a message; the unnamed register `p` pastes from gets
+/// the register's rows too, as in vim
+fn store_rows(app: &mut App, rows: &[usize]) -> String {
+    let values: RegisterRows = rows
+        .iter()
+        .map(|&row| app.document.rows[row].clone())
+        .collect();
+    let (values, into) = match app.input_state.register.take() {
+        Some(name) => (
+            app.registers.store(name, values).clone(),
+            format!(" into \"{}", name.to_ascii_lowercase()),
+        ),
+        None => (values, String::new()),
+    };
+    // P pastes these rows now rather than placing a cut cell
+    app.cell_register = None;
+    app.row_clipboard = Some(values);
+    into
+}
+
+/// Copy rows into a register (`yy`, `y` in Visual mode)
+fn yank_rows(app: &mut App, rows: &[usize]) {
+    if rows.is_empty() {
+        return;
+    }
+    let into = store_rows(app, rows);
+    app.status_messages.push(format!(
+        "{} yanked{}",
+        messages::row_count(rows.len()),
+        into
+    ));
+}
+
+/// Delete rows into a register as one change (`dd`, `d` in Visual mode);
+/// false if there was nothing to delete
+fn delete_rows(app: &mut App, rows: &[usize]) -> bool {
+    if rows.is_empty() {
+        return false;
+    }
+    let into = store_rows(app, rows);
+    // Bottom up, so each index still points at its row. The selection
+    // stays at the same index (now the next row), or moves up from the end
+    let mut bottom_up = rows.to_vec();
+    bottom_up.sort_unstable_by(|a, b| b.cmp(a));
+    let edits = bottom_up
+        .into_iter()
+        .map(|at| Edit::DeleteRow {
+            at,
+            values: app.document.rows[at].clone(),
+        })
+        .collect();
+    app.commit_change(Change {
+        edits,
+        label: "delete row".to_string(),
+    });
+    app.status_messages.push(format!(
+        "{} deleted{}",
+        messages::row_count(rows.len()),
+        into
+    ));
+    true
+}
+
+/// The rows `p` pastes: the register picked with `"a`, else the unnamed one
+fn register_rows(app: &mut App) -> Result<RegisterRows, String> {
+    match app.input_state.register.take() {
+        Some(name) => app
+            .registers
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Register {} is empty", name.to_ascii_lowercase())),
+        None => app
+            .row_clipboard
+            .clone()
+            .ok_or_else(|| "Nothing to paste".to_string()),
+    }
+}
+
+/// Insert rows at `at` as one change, fitted to the columns of this file,
+/// and select the first
+fn paste_rows(app: &mut App, at: usize, rows: &[Vec<String>]) {
+    let edits = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| Edit::InsertRow {
+            at: at + i,
+            values: (0..app.document.column_count())
+                .map(|col| row.get(col).cloned().unwrap_or_default())
+                .collect(),
+        })
+        .collect();
+    app.commit_change(Change {
+        edits,
+        label: "paste".to_string(),
+    });
+    app.select_document_row(RowIndex::new(at));
+    app.status_messages
+        .push(format!("Pasted {}", messages::row_count(rows.len())));
+}
+
+/// `p`/`P`: paste the register's rows below or above the cursor
+fn paste_rows_at_cursor(app: &mut App, below: bool) {
+    match register_rows(app) {
+        Ok(rows) => {
+            if let Some(row_idx) = app.get_selected_row() {
+                paste_rows(app, row_idx.get() + usize::from(below), &rows);
+                app.last_change = Some(LastChange::PasteRows { rows, below });
+            }
+        }
+        Err(message) => app.status_messages.push(message),
+    }
+}
+
+/// Replace the cells of the rows from `at` with the register's rows as one
+/// change, instead of inserting them (`gp`); rows past the end are dropped
+fn paste_over(app: &mut App, at: usize, rows: &[Vec<String>]) {
+    let document = &app.document;
+    let count = rows.len().min(document.row_count().saturating_sub(at));
+    let edits: Vec<Edit> = rows
+        .iter()
+        .take(count)
+        .enumerate()
+        .flat_map(|(i, values)| {
+            let row = at + i;
+            (0..document.column_count()).filter_map(move |col| {
+                let new = values.get(col).cloned().unwrap_or_default();
+                let old = document.rows[row].get(col).cloned().unwrap_or_default();
+                (old != new).then_some(Edit::Cell { row, col, old, new })
+            })
+        })
+        .collect();
+    if !edits.is_empty() {
+        app.commit_change(Change {
+            edits,
+            label: "paste over".to_string(),
+        });
+    }
+    app.status_messages
+        .push(format!("Replaced {}", messages::row_count(count)));
+}
+
+/// Undo (or redo) count changes, saying so when there is nothing left
+fn undo_redo(app: &mut App, undo: bool) {
+    let count = app
+        .input_state
+        .command_count
+        .take()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    for done in 0..count {
+        let replayed = if undo { app.undo() } else { app.redo() };
+        if !replayed {
+            if done == 0 {
+                let message = if undo {
+                    "Already at oldest change"
+                } else {
+                    "Already at newest change"
+                };
+                app.status_messages.push(message);
+            }
+            break;
+        }
+    }
 }
 
 /// Handle file switching between next and previous files
@@ -93,17 +2858,60 @@ fn handle_file_switch(app: &mut App, next: bool) -> InputResult {
         return InputResult::Continue;
     }
 
-    let switched = if next {
-        app.session.next_file()
-    } else {
-        app.session.prev_file()
-    };
+    // Files shown in the other pane are skipped
+    let count = app.session.file_count();
+    let step = if next { 1 } else { count - 1 };
+    let current = app.session.active_file_index();
+    let mut target = (current + step) % count;
+    if app.pane_file() == Some(target) {
+        target = (target + step) % count;
+    }
+    if target == current {
+        app.status_messages
+            .push("The other file is in the other pane (Ctrl+w w)");
+        return InputResult::Continue;
+    }
 
-    if switched {
-        InputResult::ReloadFile
-    } else {
-        InputResult::Continue
+    leave_current_file(app);
+    app.session.select_file(target);
+    InputResult::ReloadFile
+}
+
+/// Switch to the file at `index` (a click in the file switcher)
+fn handle_file_select(app: &mut App, index: usize) -> InputResult {
+    if index == app.session.active_file_index() || index >= app.session.file_count() {
+        return InputResult::Continue;
+    }
+    if app.pane_file() == Some(index) {
+        app.switch_pane();
+        return InputResult::Continue;
+    }
+    leave_current_file(app);
+    app.session.select_file(index);
+    InputResult::ReloadFile
+}
+
+/// Remember cursor and scroll position so returning to this file restores them
+fn leave_current_file(app: &mut App) {
+    app.remember_position();
+    app.session.save_view_state(app.view_state.clone());
+    app.session
+        .cache_document(std::mem::take(&mut app.document));
+}
+
+/// The cell under the cursor, or `None` with a message saying why there is
+/// none (no columns, or no data rows)
+fn selected_cell(app: &mut App) -> Option<(RowIndex, ColIndex)> {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return None;
     }
+    if app.document.row_count() == 0 {
+        app.status_messages.push(messages::NO_DATA_ROWS);
+        return None;
+    }
+    let row = app.get_selected_row()?;
+    Some((row, app.view_state.selected_column))
 }
 
 /// Enter Insert mode for cell editing
@@ -112,8 +2920,9 @@ fn handle_file_switch(app: &mut App, next: bool) -> InputResult {
 /// * `cursor_at_start` - If true, cursor is at start of content; otherwise at end
 /// * `clear_content` - If true, clear the cell content (for 's' command)
 fn enter_insert_mode(app: &mut App, cursor_at_start: bool, clear_content: bool) {
-    let row_idx = app.get_selected_row().unwrap_or(RowIndex::new(0));
-    let col_idx = app.view_state.selected_column;
+    let Some((row_idx, col_idx)) = selected_cell(app) else {
+        return;
+    };
 
     let current_value = app.document.get_cell(row_idx, col_idx).to_string();
 
@@ -131,10 +2940,91 @@ fn enter_insert_mode(app: &mut App, cursor_at_start: bool, clear_content: bool)
         content,
         cursor,
         original: current_value,
+        new_row: None,
     });
     app.mode = Mode::Insert;
 }
 
+/// Where `o` (below) or `O` (above) adds a row: next to the cursor, or as
+/// the first row of a file with only a header
+fn new_row_position(app: &mut App, below: bool) -> Option<RowIndex> {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return None;
+    }
+    if app.document.row_count() == 0 {
+        return Some(RowIndex::new(0));
+    }
+    let row = app.get_selected_row()?;
+    Some(RowIndex::new(row.get() + usize::from(below)))
+}
+
+/// Edit the row just added below (`o`) or above (`O`) the cursor
+fn start_new_row_edit(app: &mut App, below: bool) {
+    app.last_change = Some(LastChange::AddRow { below, text: None });
+    enter_insert_mode(app, true, false);
+    if let Some(buffer) = app.edit_buffer.as_mut() {
+        buffer.new_row = Some(below);
+    }
+}
+
+/// Make the last change again at the cursor (`.`), count times
+fn repeat_last_change(app: &mut App) {
+    let count = app.input_state.command_count.take().map_or(1, |n| n.get());
+    let Some(change) = app.last_change.clone() else {
+        app.status_messages.push("No change to repeat");
+        return;
+    };
+    for _ in 0..count {
+        let Some(row) = app.get_selected_row() else {
+            return;
+        };
+        let col = app.view_state.selected_column;
+        match &change {
+            LastChange::Edit(edit) => {
+                let new = edit.apply(app.document.get_cell(row, col));
+                if new != app.document.get_cell(row, col) {
+                    app.set_cell(row, col, new, "edit");
+                }
+            }
+            LastChange::AddRow { below, text } => {
+                let at = RowIndex::new(row.get() + usize::from(*below));
+                let mut values = Vec::new();
+                if let Some(text) = text {
+                    values = vec![String::new(); app.document.column_count()];
+                    if let Some(cell) = values.get_mut(col.get()) {
+                        cell.clone_from(text);
+                    }
+                }
+                insert_row(app, at, values, "add row");
+                app.select_document_row(at);
+            }
+            LastChange::DeleteRows(count) => {
+                let rows = rows_from_cursor(app, *count);
+                if !delete_rows(app, &rows) {
+                    return;
+                }
+            }
+            LastChange::PasteRows { rows, below } => {
+                paste_rows(app, row.get() + usize::from(*below), rows)
+            }
+            LastChange::PasteOver(rows) => paste_over(app, row.get(), rows),
+            LastChange::Fill { spec, rows } => {
+                let range = rows.map(|rows| row.get()..=row.get() + rows - 1);
+                fill_column(app, range, spec);
+            }
+            LastChange::FillSelection {
+                fill,
+                rows,
+                columns,
+            } => {
+                let rows = app.fill_rows(Some(row.get()..=row.get() + rows - 1));
+                fill_down(app, fill, &rows, col.get()..=col.get() + columns - 1);
+            }
+        }
+    }
+}
+
 /// Commit the current edit and return to Normal mode
 fn commit_edit(app: &mut App) {
     if let Some(buffer) = app.edit_buffer.take() {
@@ -143,8 +3033,15 @@ fn commit_edit(app: &mut App) {
 
             // Only mark dirty if content changed
             if buffer.content != buffer.original {
-                app.document.set_cell(row_idx, col_idx, buffer.content);
-                app.last_edit_position = Some((row_idx, col_idx));
+                let edit = CellEdit::between(&buffer.original, &buffer.content);
+                app.last_change = Some(match buffer.new_row {
+                    Some(below) => LastChange::AddRow {
+                        below,
+                        text: Some(buffer.content.clone()),
+                    },
+                    None => LastChange::Edit(edit),
+                });
+                app.set_cell(row_idx, col_idx, buffer.content, "edit");
             }
         }
     }
@@ -154,11 +3051,7 @@ fn commit_edit(app: &mut App) {
 /// Handle keyboard input in Normal mode
 fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
     // Clear transient messages on keypress
-    if let Some(ref msg) = app.status_message {
-        if msg.should_clear_on_keypress() {
-            app.status_message = None;
-        }
-    }
+    app.status_messages.clear_transient();
 
     // Note: No timeout on pending commands (vim-like behavior - wait indefinitely)
 
@@ -167,6 +3060,44 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
         return handle_multi_key_command(app, pending, key.code);
     }
 
+    // The file browser takes keys while open, like the picker
+    if app.view_state.file_browser.is_some() && !app.view_state.help_overlay_visible {
+        return Ok(handle_file_browser_key(app, key));
+    }
+
+    // Typing in the file picker goes to its query, untranslated
+    if app.view_state.file_picker.is_some() && !app.view_state.help_overlay_visible {
+        return Ok(handle_file_picker_key(app, key));
+    }
+
+    // Apply user key bindings (only to the first key of a sequence)
+    let key = app.input_state.keymap.translate(key);
+
+    if app.view_state.stats_panel.is_some() && !app.view_state.help_overlay_visible {
+        handle_stats_panel_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
+    if app.grep.as_ref().is_some_and(|view| view.visible) && !app.view_state.help_overlay_visible {
+        handle_grep_view_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
+    if app.view_state.frequency_view.is_some() && !app.view_state.help_overlay_visible {
+        handle_frequency_view_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
+    if app.view_state.bookmark_view.is_some() && !app.view_state.help_overlay_visible {
+        handle_bookmark_view_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
+    if app.view_state.record_view && !app.view_state.help_overlay_visible {
+        handle_record_view_key(app, key);
+        return Ok(InputResult::Continue);
+    }
+
     // Handle numeric prefixes only when navigation is allowed
     if is_navigation_allowed(app) {
         if let KeyCode::Char(c) = key.code {
@@ -194,8 +3125,8 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
 
         // Help overlay scrolling: j/k for line, Ctrl+d/u for page
         KeyCode::Char('j') | KeyCode::Down if app.view_state.help_overlay_visible => {
-            // Use HELP_CONTENT_LINES (52) as safe max scroll
-            app.view_state.scroll_help_down(52);
+            app.view_state
+                .scroll_help_down(crate::ui::help_line_count());
         }
 
         KeyCode::Char('k') | KeyCode::Up if app.view_state.help_overlay_visible => {
@@ -207,7 +3138,8 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
                 && key.modifiers.contains(KeyModifiers::CONTROL) =>
         {
             // Page down (10 lines)
-            app.view_state.scroll_help_page_down(10, 52);
+            app.view_state
+                .scroll_help_page_down(10, crate::ui::help_line_count());
         }
 
         KeyCode::Char('u')
@@ -218,10 +3150,13 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
             app.view_state.scroll_help_page_up(10);
         }
 
-        // Clear pending command with Esc
-        KeyCode::Esc if app.input_state.pending_command.is_some() => {
+        // Clear pending command (and the picked register) with Esc
+        KeyCode::Esc
+            if app.input_state.pending_command.is_some() || app.input_state.register.is_some() =>
+        {
             app.input_state.clear_pending_command();
-            app.status_message = Some(StatusMessage::from(messages::CMD_CANCELLED));
+            app.input_state.register = None;
+            app.status_messages.push(messages::CMD_CANCELLED);
         }
 
         // File switching
@@ -244,6 +3179,54 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
             return Ok(InputResult::Continue);
         }
 
+        KeyCode::Char('\'') if is_navigation_allowed(app) => {
+            app.input_state.set_pending_command(PendingCommand::Quote);
+            return Ok(InputResult::Continue);
+        }
+
+        KeyCode::Char('m') if is_navigation_allowed(app) => {
+            app.input_state.set_pending_command(PendingCommand::Mark);
+            return Ok(InputResult::Continue);
+        }
+
+        // ) / ( - Next/previous bookmarked row (with count: 3) three on)
+        KeyCode::Char(c @ (')' | '(')) if is_navigation_allowed(app) => {
+            let count = app.input_state.command_count.take().map_or(1, |n| n.get());
+            navigation::commands::goto_bookmark(app, count, c == ')');
+        }
+
+        // Start a Visual mode selection at the cursor
+        KeyCode::Char('v') if is_navigation_allowed(app) => {
+            enter_visual_mode(app);
+        }
+
+        // Step through the matches of :long and validation errors
+        KeyCode::Char(c @ ('n' | 'N')) if is_navigation_allowed(app) => {
+            let count = app
+                .input_state
+                .command_count
+                .take()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            navigation::commands::goto_match(app, count, c == 'n');
+        }
+
+        // Show the whole cell in the magnifier
+        KeyCode::Char('M') if is_navigation_allowed(app) => {
+            open_magnifier(app);
+        }
+
+        // Toggle the cell preview line
+        KeyCode::Char('K') if is_navigation_allowed(app) => {
+            app.options.preview = !app.options.preview;
+            let message = if app.options.preview {
+                "Cell preview on (zh/zl to scroll)"
+            } else {
+                "Cell preview off"
+            };
+            app.status_messages.push(message);
+        }
+
         // Enter command mode
         KeyCode::Char(':') if is_navigation_allowed(app) => {
             app.mode = Mode::Command;
@@ -251,6 +3234,16 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
             return Ok(InputResult::Continue);
         }
 
+        // Search the selected column: the command line starts as `:csearch `
+        KeyCode::Char('\\') if is_navigation_allowed(app) => {
+            app.mode = Mode::Command;
+            app.input_state.clear_command_buffer();
+            "csearch "
+                .chars()
+                .for_each(|c| app.input_state.push_command_char(c));
+            return Ok(InputResult::Continue);
+        }
+
         // Start 'd' pending command (for dd - delete row)
         KeyCode::Char('d') if is_navigation_allowed(app) => {
             app.input_state.set_pending_command(PendingCommand::D);
@@ -295,59 +3288,127 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
 
         // Row operations: 'o' - add row below and enter Insert mode
         KeyCode::Char('o') if is_navigation_allowed(app) => {
-            if let Some(row_idx) = app.get_selected_row() {
-                let new_row_idx = RowIndex::new(row_idx.get() + 1);
-                app.document.insert_row(new_row_idx);
-                app.view_state.table_state.select(Some(new_row_idx.get()));
-                enter_insert_mode(app, true, false);
+            if let Some(new_row_idx) = new_row_position(app, true) {
+                insert_row(app, new_row_idx, Vec::new(), "add row");
+                app.select_document_row(new_row_idx);
+                start_new_row_edit(app, true);
             }
         }
 
         // Row operations: 'O' - add row above and enter Insert mode
         KeyCode::Char('O') if is_navigation_allowed(app) => {
-            if let Some(row_idx) = app.get_selected_row() {
-                app.document.insert_row(row_idx);
-                // Selection stays at current index which is now the new row
-                enter_insert_mode(app, true, false);
+            if let Some(row_idx) = new_row_position(app, false) {
+                insert_row(app, row_idx, Vec::new(), "add row");
+                app.select_document_row(row_idx);
+                start_new_row_edit(app, false);
             }
         }
 
-        // Row operations: 'p' - paste row below
-        KeyCode::Char('p') if is_navigation_allowed(app) => {
-            if let Some(clipboard) = app.row_clipboard.clone() {
-                if let Some(row_idx) = app.get_selected_row() {
-                    let new_row_idx = RowIndex::new(row_idx.get() + 1);
-                    app.document.insert_row(new_row_idx);
-                    // Copy clipboard content into the new row
-                    for (col_idx, value) in clipboard.iter().enumerate() {
-                        if col_idx < app.document.column_count() {
-                            app.document.set_cell(
-                                new_row_idx,
-                                crate::domain::position::ColIndex::new(col_idx),
-                                value.clone(),
-                            );
-                        }
-                    }
-                    app.view_state.table_state.select(Some(new_row_idx.get()));
-                    app.status_message = Some(StatusMessage::from("Pasted 1 row"));
-                }
-            } else {
-                app.status_message = Some(StatusMessage::from("Nothing to paste"));
-            }
+        // Ctrl+p - Pick a file to open
+        KeyCode::Char('p')
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            open_file_picker(app);
+        }
+
+        // Row operations: 'p' - paste the register's rows below
+        KeyCode::Char('p') if is_navigation_allowed(app) => paste_rows_at_cursor(app, true),
+
+        // Pick a register for the next yank, delete or paste ("ayy)
+        KeyCode::Char('"') if is_navigation_allowed(app) => {
+            app.input_state
+                .set_pending_command(PendingCommand::Register);
+            return Ok(InputResult::Continue);
+        }
+
+        // Repeat the last change at the cursor (with count: 3. three times)
+        KeyCode::Char('.') if is_navigation_allowed(app) => {
+            repeat_last_change(app);
+        }
+
+        // Undo / redo (with count: 3u undoes three changes)
+        KeyCode::Char('u') if is_navigation_allowed(app) && key.modifiers.is_empty() => {
+            undo_redo(app, true);
+        }
+
+        KeyCode::Char('r')
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            undo_redo(app, false);
+        }
+
+        // Alt+H / Alt+L (or Alt+arrows) - Move the column left/right in the
+        // display order
+        KeyCode::Char('H' | 'L') | KeyCode::Left | KeyCode::Right
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            let count = app
+                .input_state
+                .command_count
+                .take()
+                .map_or(1, |n| n.get() as isize);
+            let left = matches!(key.code, KeyCode::Char('H') | KeyCode::Left);
+            navigation::commands::move_column(app, if left { -count } else { count });
+        }
+
+        // Ctrl+w - Start a split window command
+        KeyCode::Char('w')
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            app.input_state.set_pending_command(PendingCommand::Window);
+            return Ok(InputResult::Continue);
         }
 
         // Delete key - clear current cell
         KeyCode::Delete if is_navigation_allowed(app) => {
             if let Some(row_idx) = app.get_selected_row() {
                 let col_idx = app.view_state.selected_column;
-                app.document.set_cell(row_idx, col_idx, String::new());
-                app.status_message = Some(StatusMessage::from("Cell cleared"));
+                app.set_cell(row_idx, col_idx, String::new(), "clear");
+                app.last_change = Some(LastChange::Edit(CellEdit::Set(String::new())));
+                app.status_messages.push("Cell cleared");
+            }
+        }
+
+        // x - Cut the current cell into the cell register
+        KeyCode::Char('x') if is_navigation_allowed(app) => {
+            if let Some((row_idx, col_idx)) = selected_cell(app) {
+                let value = app.document.get_cell(row_idx, col_idx).to_string();
+                app.set_cell(row_idx, col_idx, String::new(), "cut");
+                app.last_change = Some(LastChange::Edit(CellEdit::Set(String::new())));
+                app.status_messages.push(format!(
+                    "Cut {} (P places it)",
+                    cell_name(row_idx.get(), col_idx.get())
+                ));
+                app.cell_register = Some(value);
+            }
+        }
+
+        // P - Place the cut cell in the current cell when a cell was cut
+        // last, else paste the register's rows above
+        KeyCode::Char('P') if is_navigation_allowed(app) => match app.cell_register.clone() {
+            Some(value) if app.input_state.register.is_none() => {
+                if let Some(row_idx) = app.get_selected_row() {
+                    let col_idx = app.view_state.selected_column;
+                    app.set_cell(row_idx, col_idx, value.clone(), "place");
+                    app.last_change = Some(LastChange::Edit(CellEdit::Set(value)));
+                    app.status_messages.push(format!(
+                        "Placed in {}",
+                        cell_name(row_idx.get(), col_idx.get())
+                    ));
+                }
             }
+            _ => paste_rows_at_cursor(app, false),
+        },
+
+        // Enter in --pick mode - choose the cursor row and quit
+        KeyCode::Enter if app.pick.is_some() && is_navigation_allowed(app) => {
+            app.exit_status = ExitStatus::Normal;
+            return Ok(InputResult::Quit);
         }
 
         // Enter key - move down one row (like j)
         KeyCode::Enter if is_navigation_allowed(app) => {
-            navigation::commands::move_down_by(app, 1);
+            navigation::move_down_by(app, 1);
         }
 
         // Page navigation: Ctrl+d - page down
@@ -360,17 +3421,47 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
                 .take()
                 .map(|n| n.get())
                 .unwrap_or(1);
-            for _ in 0..count {
-                let current = app.view_state.table_state.selected().unwrap_or(0);
-                let target = (current + navigation::PAGE_SIZE)
-                    .min(app.document.row_count().saturating_sub(1));
-                app.view_state.table_state.select(Some(target));
-            }
+            for _ in 0..count {
+                let current = app.view_state.table_state.selected().unwrap_or(0);
+                let target =
+                    (current + app.page_rows()).min(app.display_row_count().saturating_sub(1));
+                app.view_state.table_state.select(Some(target));
+            }
+        }
+
+        // Page navigation: Ctrl+u - page up
+        KeyCode::Char('u')
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            let count = app
+                .input_state
+                .command_count
+                .take()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            for _ in 0..count {
+                let current = app.view_state.table_state.selected().unwrap_or(0);
+                let target = current.saturating_sub(app.page_rows());
+                app.view_state.table_state.select(Some(target));
+            }
+        }
+
+        // Full-page navigation: Ctrl+f / Ctrl+b - a screen down/up
+        KeyCode::Char(c @ ('f' | 'b'))
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            let count = app
+                .input_state
+                .command_count
+                .take()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            navigation::commands::scroll_full_pages(app, count, c == 'f');
         }
 
-        // Page navigation: Ctrl+u - page up
-        KeyCode::Char('u')
-            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        // Column pages: Alt+l / Alt+h - next/previous column window
+        KeyCode::Char(c @ ('l' | 'h'))
+            if is_navigation_allowed(app) && key.modifiers.contains(KeyModifiers::ALT) =>
         {
             let count = app
                 .input_state
@@ -378,11 +3469,7 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
                 .take()
                 .map(|n| n.get())
                 .unwrap_or(1);
-            for _ in 0..count {
-                let current = app.view_state.table_state.selected().unwrap_or(0);
-                let target = current.saturating_sub(navigation::PAGE_SIZE);
-                app.view_state.table_state.select(Some(target));
-            }
+            navigation::commands::scroll_column_pages(app, count, c == 'l', false);
         }
 
         // Navigation commands
@@ -407,7 +3494,63 @@ fn handle_multi_key_command(
         (PendingCommand::G, KeyCode::Char('g')) => {
             app.input_state.clear_pending_command();
             navigation::goto_first_row(app);
-            app.status_message = Some(StatusMessage::from(messages::JUMPED_TO_FIRST_ROW));
+            app.status_messages.push(messages::JUMPED_TO_FIRST_ROW);
+        }
+
+        // g; - Jump back to the previous edit (again for older ones)
+        (PendingCommand::G, KeyCode::Char(';')) => {
+            app.input_state.clear_pending_command();
+            let count = app
+                .input_state
+                .command_count
+                .take()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            navigation::commands::goto_previous_edit(app, count);
+        }
+
+        // gl - Back to the previous column, at the row last selected there
+        (PendingCommand::G, KeyCode::Char('l')) => {
+            app.input_state.clear_pending_command();
+            navigation::commands::goto_previous_column(app);
+        }
+
+        // gp - Paste the register's rows over the rows from the cursor
+        (PendingCommand::G, KeyCode::Char('p')) => {
+            app.input_state.clear_pending_command();
+            match register_rows(app) {
+                Ok(rows) => {
+                    if let Some(row_idx) = app.get_selected_row() {
+                        paste_over(app, row_idx.get(), &rows);
+                        app.last_change = Some(LastChange::PasteOver(rows));
+                    }
+                }
+                Err(message) => app.status_messages.push(message),
+            }
+        }
+
+        // '" - Return to the position when the file was last closed
+        (PendingCommand::Quote, KeyCode::Char('"')) => {
+            app.input_state.clear_pending_command();
+            navigation::commands::goto_last_position(app);
+        }
+
+        // 'a - Jump to mark a; '' - back to where the last jump started
+        (PendingCommand::Quote, KeyCode::Char(c)) if c.is_ascii_lowercase() || c == '\'' => {
+            app.input_state.clear_pending_command();
+            navigation::commands::goto_mark(app, c);
+        }
+
+        // ma - Set mark a at the cursor
+        (PendingCommand::Mark, KeyCode::Char(c)) if c.is_ascii_lowercase() => {
+            app.input_state.clear_pending_command();
+            navigation::commands::set_mark(app, c);
+        }
+
+        // mB - Bookmark the row (or remove its bookmark)
+        (PendingCommand::Mark, KeyCode::Char('B')) => {
+            app.input_state.clear_pending_command();
+            navigation::commands::toggle_bookmark(app);
         }
 
         // g + letter - Start column jump (e.g., gA, gB)
@@ -433,71 +3576,303 @@ fn handle_multi_key_command(
             }
         }
 
+        // zr - Resize the column with h/l until Enter or Esc
+        (PendingCommand::Z, KeyCode::Char('r')) => {
+            app.input_state.clear_pending_command();
+            navigation::commands::start_column_resize(app);
+        }
+
         // zt - Top of screen
         (PendingCommand::Z, KeyCode::Char('t')) => {
             app.input_state.clear_pending_command();
             app.view_state.viewport_mode = ViewportMode::Top;
-            app.status_message = Some(StatusMessage::from(messages::VIEW_TOP));
+            app.status_messages.push(messages::VIEW_TOP);
         }
 
         // zz - Center of screen
         (PendingCommand::Z, KeyCode::Char('z')) => {
             app.input_state.clear_pending_command();
             app.view_state.viewport_mode = ViewportMode::Center;
-            app.status_message = Some(StatusMessage::from(messages::VIEW_CENTER));
+            app.status_messages.push(messages::VIEW_CENTER);
         }
 
         // zb - Bottom of screen
         (PendingCommand::Z, KeyCode::Char('b')) => {
             app.input_state.clear_pending_command();
             app.view_state.viewport_mode = ViewportMode::Bottom;
-            app.status_message = Some(StatusMessage::from(messages::VIEW_BOTTOM));
+            app.status_messages.push(messages::VIEW_BOTTOM);
         }
 
-        // dd - Delete row
-        (PendingCommand::D, KeyCode::Char('d')) => {
+        // zh/zl/zH/zL - Scroll the cell preview sideways, or the columns
+        // when the preview is off
+        (PendingCommand::Z, KeyCode::Char(c @ ('h' | 'l' | 'H' | 'L'))) => {
             app.input_state.clear_pending_command();
-            if let Some(row_idx) = app.get_selected_row() {
-                if let Some(deleted) = app.document.delete_row(row_idx) {
-                    app.row_clipboard = Some(deleted);
-                    // Adjust selection if needed
-                    let row_count = app.document.row_count();
-                    if row_count == 0 {
-                        // No rows left
-                        app.view_state.table_state.select(None);
-                    } else if row_idx.get() >= row_count {
-                        // Was at last row, move selection up
-                        app.view_state.table_state.select(Some(row_count - 1));
-                    }
-                    // Otherwise selection stays at same index (which is now the next row)
-                    app.status_message = Some(StatusMessage::from("1 row deleted"));
+            if app.options.preview {
+                scroll_preview(app, c);
+            } else {
+                scroll_column_window(app, c);
+            }
+        }
+
+        // zc/zo/za on a row group header - Collapse/expand the row group
+        (PendingCommand::Z, KeyCode::Char(c @ ('c' | 'o' | 'a')))
+            if app
+                .get_selected_row()
+                .is_some_and(|row| app.view_state.row_groups.header_at(row.get()).is_some()) =>
+        {
+            app.input_state.clear_pending_command();
+            handle_row_fold(app, c);
+        }
+
+        // zc/zo/za/zM/zR - Collapse/expand column groups (vim fold keys)
+        (PendingCommand::Z, KeyCode::Char(c @ ('c' | 'o' | 'a' | 'M' | 'R'))) => {
+            app.input_state.clear_pending_command();
+            handle_column_fold(app, c);
+        }
+
+        // Ctrl+w s/v - Show the next file above/beside this one
+        (PendingCommand::Window, KeyCode::Char(c @ ('s' | 'S' | 'v'))) => {
+            app.input_state.clear_pending_command();
+            let direction = if c == 'v' {
+                SplitDirection::Vertical
+            } else {
+                SplitDirection::Horizontal
+            };
+            if let Err(err) = app.open_split(direction) {
+                app.status_messages.push(err);
+            }
+        }
+
+        // Ctrl+w w (or Ctrl+w Ctrl+w, h/j/k/l, p) - Focus the other pane
+        (PendingCommand::Window, KeyCode::Char('w' | 'W' | 'p' | 'h' | 'j' | 'k' | 'l')) => {
+            app.input_state.clear_pending_command();
+            if app.split.is_some() {
+                app.switch_pane();
+            } else {
+                app.status_messages
+                    .push("Only one pane (Ctrl+w s or v splits)");
+            }
+        }
+
+        // Ctrl+w o - Close the other pane; Ctrl+w q/c - close this one
+        (PendingCommand::Window, KeyCode::Char(c @ ('o' | 'q' | 'c'))) => {
+            app.input_state.clear_pending_command();
+            let closing_focused = c != 'o' && app.split.is_some();
+            if closing_focused {
+                app.switch_pane();
+            }
+            if let Err(err) = app.close_other_pane() {
+                if closing_focused {
+                    app.switch_pane();
                 }
+                app.status_messages.push(err);
             }
         }
 
-        // yy - Yank (copy) row
-        (PendingCommand::Y, KeyCode::Char('y')) => {
+        // dd - Delete row, 3dd three rows (in review mode, mark it for deletion)
+        (PendingCommand::D, KeyCode::Char('d')) => {
             app.input_state.clear_pending_command();
-            if let Some(row_idx) = app.get_selected_row() {
-                if let Some(row) = app.document.rows.get(row_idx.get()) {
-                    app.row_clipboard = Some(row.clone());
-                    app.status_message = Some(StatusMessage::from("1 row yanked"));
+            let count = app.input_state.get_count_or_default();
+            app.input_state.clear_count();
+            if app.options.review {
+                toggle_pending_delete(app);
+            } else {
+                let rows = rows_from_cursor(app, count);
+                if delete_rows(app, &rows) {
+                    app.last_change = Some(LastChange::DeleteRows(rows.len()));
                 }
             }
         }
 
+        // yc - Copy the column to the system clipboard
+        (PendingCommand::Y, KeyCode::Char('c')) => {
+            app.input_state.clear_pending_command();
+            yank_column(app);
+        }
+
+        // yy - Yank (copy) row, 3yy three rows
+        (PendingCommand::Y, KeyCode::Char('y')) => {
+            app.input_state.clear_pending_command();
+            let count = app.input_state.get_count_or_default();
+            app.input_state.clear_count();
+            let rows = rows_from_cursor(app, count);
+            yank_rows(app, &rows);
+        }
+
+        // "a - Yank, delete or paste with register a next
+        (PendingCommand::Register, KeyCode::Char(c)) => {
+            app.input_state.clear_pending_command();
+            select_register(app, c);
+        }
+
         _ => {
             app.input_state.clear_pending_command();
-            app.status_message = Some(StatusMessage::from(messages::unknown_command(
+            app.status_messages.push(messages::unknown_command(
                 &format_pending_command(&first),
                 &format_keycode(&second),
-            )));
+            ));
         }
     }
 
     Ok(InputResult::Continue)
 }
 
+/// Scroll the columns: zh/zl by count columns, zH/zL by count half windows
+fn scroll_column_window(app: &mut App, key: char) {
+    let count = app
+        .input_state
+        .command_count
+        .take()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let forward = key.eq_ignore_ascii_case(&'l');
+    if key.is_ascii_uppercase() {
+        navigation::commands::scroll_column_pages(app, count, forward, true);
+    } else {
+        navigation::commands::scroll_columns(app, count, forward);
+    }
+}
+
+/// Scroll the cell preview: zh/zl by count characters, zH/zL by half a line
+fn scroll_preview(app: &mut App, key: char) {
+    let count = app
+        .input_state
+        .command_count
+        .take()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let half_line = (app.view_state.table_width as usize / 2).max(1);
+    let scroll = &mut app.view_state.preview_scroll;
+    // The renderer clamps scrolling past the end of the value
+    *scroll = match key {
+        'h' => scroll.saturating_sub(count),
+        'l' => scroll.saturating_add(count),
+        'H' => scroll.saturating_sub(half_line),
+        _ => scroll.saturating_add(half_line),
+    };
+}
+
+/// Collapse/expand the row group headed by the cursor row: zc, zo, za
+fn handle_row_fold(app: &mut App, key: char) {
+    let Some(row) = app.get_selected_row() else {
+        return;
+    };
+    let collapsed = match key {
+        'c' => Some(true),
+        'o' => Some(false),
+        _ => None,
+    };
+    let message = match app
+        .view_state
+        .row_groups
+        .set_collapsed(row.get(), collapsed)
+    {
+        Some(group) if group.collapsed => format!("Collapsed {} rows", group.len()),
+        Some(group) => format!("Expanded {} rows", group.len()),
+        None => return,
+    };
+    // The header stays visible, so keep the cursor on it
+    app.select_document_row(row);
+    app.status_messages.push(message);
+}
+
+/// Collapse/expand column groups: zc, zo, za (group at cursor), zM, zR (all)
+fn handle_column_fold(app: &mut App, key: char) {
+    let col = app.view_state.selected_column.get();
+    let groups = &mut app.view_state.column_groups;
+
+    let message = match key {
+        'M' | 'R' if groups.is_empty() => "No column groups (see :group)".to_string(),
+        'M' => {
+            groups.set_all_collapsed(true);
+            "All column groups collapsed".to_string()
+        }
+        'R' => {
+            groups.set_all_collapsed(false);
+            "All column groups expanded".to_string()
+        }
+        _ => {
+            let collapsed = match key {
+                'c' => Some(true),
+                'o' => Some(false),
+                _ => None,
+            };
+            match groups.set_collapsed(col, collapsed) {
+                Some(group) if group.collapsed => {
+                    format!("Collapsed column group '{}'", group.name)
+                }
+                Some(group) => format!("Expanded column group '{}'", group.name),
+                None => "No column group at cursor".to_string(),
+            }
+        }
+    };
+
+    // Keep the cursor on a visible column (the group placeholder)
+    let visible = app.view_state.column_groups.visible_column(col);
+    app.view_state.selected_column = ColIndex::new(visible);
+    navigation::commands::update_horizontal_scroll(app, visible);
+    app.status_messages.push(message);
+}
+
+/// Handle `:group` - list groups, group by header prefix, or define a range
+fn handle_group_command(app: &mut App, arg: Option<&str>) {
+    use crate::ui::utils::{column_to_excel_letter, excel_letter_to_column};
+    use crate::ui::ColumnGroups;
+
+    let message = match arg.map(|a| a.split_whitespace().collect::<Vec<_>>()) {
+        None => {
+            let groups = app.view_state.column_groups.groups();
+            if groups.is_empty() {
+                "No column groups".to_string()
+            } else {
+                groups
+                    .iter()
+                    .map(|g| {
+                        format!(
+                            "{} {}:{}{}",
+                            g.name,
+                            column_to_excel_letter(g.start),
+                            column_to_excel_letter(g.end - 1),
+                            if g.collapsed { " (collapsed)" } else { "" }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        }
+        Some(parts) if parts == ["auto"] => {
+            app.view_state.column_groups = ColumnGroups::by_prefix(&app.document.headers);
+            format!(
+                "{} column group(s) found by header prefix",
+                app.view_state.column_groups.groups().len()
+            )
+        }
+        Some(parts) if parts.len() == 2 => {
+            let range = parts[1]
+                .split_once([':', '-'])
+                .ok_or_else(|| "Usage: :group <name> <from>:<to> (e.g. :group cpu B:F)".to_string())
+                .and_then(|(from, to)| {
+                    Ok((excel_letter_to_column(from)?, excel_letter_to_column(to)?))
+                });
+            match range {
+                Ok((from, to)) if from.max(to) >= app.document.column_count() => {
+                    "Column out of range".to_string()
+                }
+                Ok((from, to)) => {
+                    let (start, end) = (from.min(to), from.max(to) + 1);
+                    match app.view_state.column_groups.add(parts[0], start, end) {
+                        Ok(()) => format!("Added column group '{}' (zc to collapse)", parts[0]),
+                        Err(e) => e,
+                    }
+                }
+                Err(e) => e,
+            }
+        }
+        Some(_) => "Usage: :group [auto | <name> <from>:<to>]".to_string(),
+    };
+    app.status_messages.push(message);
+}
+
 /// Handle count prefix (numeric digits for commands like 5j, 10G)
 fn handle_count_prefix(app: &mut App, digit: char) -> Result<InputResult> {
     let digit_value = digit.to_digit(10).unwrap() as usize;
@@ -521,17 +3896,14 @@ fn handle_count_prefix(app: &mut App, digit: char) -> Result<InputResult> {
 /// Handle keyboard input in Command mode
 fn handle_command_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
     // Clear transient messages on keypress
-    if let Some(ref msg) = app.status_message {
-        if msg.should_clear_on_keypress() {
-            app.status_message = None;
-        }
-    }
+    app.status_messages.clear_transient();
+    app.input_state.completions.clear();
 
     match key.code {
         KeyCode::Esc => {
             app.mode = Mode::Normal;
             app.input_state.clear_command_buffer();
-            app.status_message = Some(StatusMessage::from(messages::CMD_CANCELLED));
+            app.status_messages.push(messages::CMD_CANCELLED);
         }
 
         KeyCode::Enter => {
@@ -544,16 +3916,56 @@ fn handle_command_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
             app.input_state.pop_command_char();
         }
 
+        KeyCode::Delete => app.input_state.delete_command_char(),
+        KeyCode::Left => app.input_state.move_command_cursor(-1),
+        KeyCode::Right => app.input_state.move_command_cursor(1),
+        KeyCode::Home => app.input_state.move_command_cursor(isize::MIN),
+        KeyCode::End => app.input_state.move_command_cursor(isize::MAX),
+
+        // Recall earlier commands starting with what was typed
+        KeyCode::Up => {
+            let state = &mut app.input_state;
+            if let Some(command) = state.history.older(&state.command_buffer) {
+                let command = command.to_string();
+                state.set_command_buffer(&command);
+            }
+        }
+        KeyCode::Down => {
+            let state = &mut app.input_state;
+            if let Some(command) = state.history.newer() {
+                state.set_command_buffer(&command);
+            }
+        }
+
         KeyCode::Char(c) => {
+            app.input_state.history.reset();
             app.input_state.push_command_char(c);
         }
 
+        KeyCode::Tab => complete_command_path(app),
+
         _ => {}
     }
 
     Ok(InputResult::Continue)
 }
 
+/// Tab after `:e `: complete the path typed so far, listing the entries
+/// still matching when it is ambiguous
+fn complete_command_path(app: &mut App) {
+    let state = &mut app.input_state;
+    let Some((command, partial)) = state.command_buffer.split_once(' ') else {
+        return;
+    };
+    if !matches!(command, "e" | "edit" | "e!" | "edit!") {
+        return;
+    }
+    let completion = completion::complete_path(partial.trim_start());
+    let line = format!("{} {}", command, completion.completed);
+    state.set_command_buffer(&line);
+    state.completions = completion.candidates;
+}
+
 /// Execute command from command buffer
 fn execute_command(app: &mut App) -> Result<()> {
     let cmd = app.input_state.command_buffer.trim().to_string();
@@ -561,17 +3973,41 @@ fn execute_command(app: &mut App) -> Result<()> {
     if cmd.is_empty() {
         return Ok(());
     }
+    app.input_state.key_echo.record_command(&cmd);
+    // An unwritable history only costs the history
+    if let Err(e) = app.input_state.history.push(&cmd) {
+        app.status_messages
+            .push(StatusMessage::error(format!("{:#}", e)));
+    }
+    run_command(app, &cmd)
+}
+
+/// Run a `:` command line (without the colon), as typed in Command mode
+pub fn run_command(app: &mut App, cmd: &str) -> Result<()> {
+    // `'<,'>` limits :sort, :fill, :copy and :long to the last Visual selection
+    let (range, cmd) = match app.command_range(cmd) {
+        Ok((range, rest)) => (range, rest.to_string()),
+        Err(message) => {
+            app.status_messages.push(message);
+            return Ok(());
+        }
+    };
 
     // Split command into parts for commands with arguments
     let parts: Vec<&str> = cmd.splitn(2, ' ').collect();
     let cmd_name = parts[0].to_lowercase();
     let arg = parts.get(1).map(|s| s.trim());
+    if range.is_some() && !matches!(cmd_name.as_str(), "sort" | "fill" | "copy" | "long") {
+        app.status_messages
+            .push(format!(":{} doesn't take a range", cmd_name));
+        return Ok(());
+    }
 
     // Reserved commands (take priority)
     match cmd_name.as_str() {
         "q" | "quit" => {
-            if app.document.is_dirty {
-                app.status_message = Some(StatusMessage::from(
+            if app.document.is_dirty || app.pane_is_dirty() {
+                app.status_messages.push(StatusMessage::warning(
                     "No write since last change (add ! to override)",
                 ));
             } else {
@@ -580,21 +4016,306 @@ fn execute_command(app: &mut App) -> Result<()> {
             return Ok(());
         }
         "q!" => {
+            if app.document.is_dirty || app.pane_is_dirty() {
+                app.exit_status = ExitStatus::Discarded;
+            }
+            app.should_quit = true;
+            return Ok(());
+        }
+        "cq" | "cquit" | "cq!" | "cquit!" => {
+            app.exit_status = ExitStatus::Cancelled;
             app.should_quit = true;
             return Ok(());
         }
-        "w" | "write" => {
-            // TODO: Implement save in v0.7.0
-            app.status_message = Some(StatusMessage::from("Save not yet implemented"));
+        "w" | "write" | "w!" | "write!" => {
+            match arg.filter(|a| a.starts_with("--")) {
+                Some(options) => ask_write_selection(app, options, cmd_name.ends_with('!')),
+                None => {
+                    write_document(app, arg, cmd_name.ends_with('!'), None);
+                }
+            }
+            return Ok(());
+        }
+        "wdiff" => {
+            preview_save(app);
+            return Ok(());
+        }
+        "wq" | "x" | "wq!" | "x!" => {
+            if write_document(app, arg, cmd_name.ends_with('!'), None) {
+                app.should_quit = true;
+            } else if let Some(ConfirmAction::Write { quit, .. }) =
+                app.confirm.as_mut().map(|confirm| &mut confirm.action)
+            {
+                *quit = true;
+            }
             return Ok(());
         }
-        "wq" | "x" => {
-            // TODO: Implement save and quit in v0.7.0
-            app.status_message = Some(StatusMessage::from("Save not yet implemented"));
+        "e" | "edit" | "e!" | "edit!" => {
+            let path = arg
+                .filter(|a| !a.is_empty())
+                .map(completion::expand_home)
+                .filter(|path| !app.session.is_current_file(path));
+            if let Some(path) = path {
+                open_file(app, path, cmd_name.ends_with('!'));
+                return Ok(());
+            }
+            if app.document.is_dirty && !cmd_name.ends_with('!') {
+                app.status_messages.push(StatusMessage::warning(
+                    "No write since last change (add ! to override)",
+                ));
+                return Ok(());
+            }
+            let message = match app.reread_current_file() {
+                Ok(()) => StatusMessage::from(format!(
+                    "Reloaded {} ({} rows)",
+                    app.document.filename,
+                    app.document.row_count()
+                )),
+                Err(e) => StatusMessage::error(format!("{:#}", e)),
+            };
+            app.status_messages.push(message);
             return Ok(());
         }
         "h" | "help" => {
-            app.status_message = Some(StatusMessage::from("Press ? for help"));
+            app.status_messages.push("Press ? for help");
+            return Ok(());
+        }
+        "ls" | "buffers" | "files" => {
+            let list = app.buffer_list();
+            app.status_messages.push(list);
+            return Ok(());
+        }
+        "b" | "buffer" | "b!" | "buffer!" => {
+            switch_to_buffer(app, arg.unwrap_or_default(), cmd_name.ends_with('!'));
+            return Ok(());
+        }
+        "browse" | "explore" => {
+            let dir = match arg.filter(|a| !a.is_empty()) {
+                Some(dir) => completion::expand_home(dir),
+                None => match app.get_current_file().parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                    _ => std::path::PathBuf::from("."),
+                },
+            };
+            app.browse(&dir);
+            return Ok(());
+        }
+        "delimiter" | "delim" => {
+            let message = match arg.map(str::trim).filter(|a| !a.is_empty()) {
+                None => StatusMessage::from(app.describe_delimiter()),
+                Some(arg) => match parse_delimiter(arg).and_then(|d| app.set_file_delimiter(d)) {
+                    Ok(message) => StatusMessage::from(message),
+                    Err(message) => StatusMessage::error(message),
+                },
+            };
+            app.status_messages.push(message);
+            return Ok(());
+        }
+        "fmt" | "format" => {
+            let message = match app.format_column(arg.unwrap_or("")) {
+                Ok(message) => StatusMessage::from(message),
+                Err(message) => StatusMessage::error(message),
+            };
+            app.status_messages.push(message);
+            return Ok(());
+        }
+        "set" | "se" => {
+            if let Some(message) = set_headers_option(app, arg.unwrap_or("")) {
+                app.status_messages.push(message);
+                return Ok(());
+            }
+            let message = match app.options.set(arg.unwrap_or("")) {
+                Ok(msg) => StatusMessage::from(msg),
+                Err(msg) => StatusMessage::error(msg),
+            };
+            app.status_messages.push(message);
+            app.refresh_row_groups();
+            app.refresh_column_types();
+            app.sort_files();
+            return Ok(());
+        }
+        "group" => {
+            handle_group_command(app, arg);
+            return Ok(());
+        }
+        "headers" => {
+            if let Some(message) = set_headers_option(app, "invheaders") {
+                app.status_messages.push(message);
+            }
+            return Ok(());
+        }
+        "bookmarks" => {
+            show_bookmarks(app, arg);
+            return Ok(());
+        }
+        "freq" | "frequency" => {
+            show_frequency_view(app);
+            return Ok(());
+        }
+        "view" => {
+            open_magnifier(app);
+            return Ok(());
+        }
+        "record" | "rec" => {
+            show_record_view(app);
+            return Ok(());
+        }
+        "filter" => {
+            filter_rows(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "nofilter" => {
+            clear_row_filter(app);
+            return Ok(());
+        }
+        "freeze" => {
+            handle_freeze_command(app, arg);
+            return Ok(());
+        }
+        "colorder" => {
+            handle_colorder_command(app, arg);
+            return Ok(());
+        }
+        "unfreeze" => {
+            navigation::commands::freeze_last_column(app, false);
+            if matches!(arg, Some("last" | "$")) {
+                app.status_messages.push("Unpinned the last column");
+            } else {
+                handle_freeze_command(app, Some("0"));
+            }
+            return Ok(());
+        }
+        "stats" => {
+            show_column_stats(app);
+            return Ok(());
+        }
+        "validate" => {
+            validate_document(app, arg);
+            return Ok(());
+        }
+        "check" => {
+            check_column_pattern(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "diff" => {
+            diff_document(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "lint" => {
+            lint_file(app, arg);
+            return Ok(());
+        }
+        "share" => {
+            share_view(app, arg);
+            return Ok(());
+        }
+        "session" => {
+            save_session(app, arg);
+            return Ok(());
+        }
+        "watch" => {
+            let watch = match arg {
+                Some("on") => true,
+                Some("off") => false,
+                _ => app.tail.is_none(),
+            };
+            app.set_watch(watch);
+            app.status_messages.push(if watch {
+                "Watching for appended rows (:watch again to stop)"
+            } else {
+                "Stopped watching"
+            });
+            return Ok(());
+        }
+        "fill" => {
+            fill_column(app, range, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "derive" => {
+            derive_column(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "underive" => {
+            underive_column(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "deps" => {
+            show_dependencies(app);
+            return Ok(());
+        }
+        "swap" => {
+            swap_cells(app);
+            return Ok(());
+        }
+        "reg" | "registers" => {
+            show_registers(app);
+            return Ok(());
+        }
+        "apply-deletes" => {
+            apply_deletes(app);
+            return Ok(());
+        }
+        "clear-deletes" => {
+            clear_deletes(app);
+            return Ok(());
+        }
+        "resume-point" => {
+            resume_point(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "sort" => {
+            sort_rows(app, range, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "copy" => {
+            copy_rows(app, range, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "grepall" => {
+            grep_all(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "replaceall" => {
+            replace_all(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "csearch" | "cs" => {
+            search_column(app, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "long" => {
+            find_long_cells(app, range, arg.unwrap_or(""));
+            return Ok(());
+        }
+        "fit" => {
+            navigation::commands::fit_column_width(app);
+            return Ok(());
+        }
+        "ungroup" => {
+            app.view_state.column_groups.clear();
+            app.status_messages.push("Column groups removed");
+            return Ok(());
+        }
+        "config" => {
+            match arg {
+                Some("reload") => {
+                    if app.config_watcher.is_none() {
+                        app.status_messages.push("No config file location");
+                    } else if app.reload_config() {
+                        app.status_messages.push(messages::CONFIG_RELOADED);
+                    }
+                }
+                _ => {
+                    app.status_messages.push("Usage: :config reload");
+                }
+            }
+            return Ok(());
+        }
+        "col" | "column" => {
+            match arg.filter(|a| !a.is_empty()) {
+                Some(name) => navigation::commands::goto_column_by_name(app, name),
+                None => app.status_messages.push("Usage: :col <header name>"),
+            }
             return Ok(());
         }
         "c" => {
@@ -603,8 +4324,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                 if let Ok(col_num) = col_arg.parse::<usize>() {
                     // Numeric column (1-indexed)
                     if col_num == 0 {
-                        app.status_message =
-                            Some(StatusMessage::from("Column number must be >= 1"));
+                        app.status_messages.push("Column number must be >= 1");
                     } else {
                         navigation::commands::goto_column_by_number(app, col_num);
                     }
@@ -612,12 +4332,12 @@ fn execute_command(app: &mut App) -> Result<()> {
                     // Letter column (A, B, AA, etc.)
                     navigation::commands::goto_column(app, col_arg);
                 } else {
-                    app.status_message =
-                        Some(StatusMessage::from(format!("Invalid column: {}", col_arg)));
+                    app.status_messages
+                        .push(format!("Invalid column: {}", col_arg));
                 }
             } else {
-                app.status_message =
-                    Some(StatusMessage::from("Usage: :c <column> (e.g., :c A, :c 5)"));
+                app.status_messages
+                    .push("Usage: :c <column> (e.g., :c A, :c 5)");
             }
             return Ok(());
         }
@@ -627,12 +4347,14 @@ fn execute_command(app: &mut App) -> Result<()> {
     // Try to parse entire command as number (row jump: :15)
     if let Ok(line_num) = cmd.parse::<usize>() {
         navigation::commands::goto_line(app, line_num);
-        app.status_message = Some(StatusMessage::from(format!("Jumped to row {}", line_num)));
+        app.status_messages
+            .push(format!("Jumped to row {}", line_num));
         return Ok(());
     }
 
     // Unknown command
-    app.status_message = Some(StatusMessage::from(format!("Unknown command: :{}", cmd)));
+    app.status_messages
+        .push(StatusMessage::error(format!("Unknown command: :{}", cmd)));
     Ok(())
 }
 
@@ -648,7 +4370,7 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) -> Result<InputResult> {
         // Exit: Save and move down
         (KeyCode::Enter, KeyModifiers::NONE) => {
             commit_edit(app);
-            navigation::commands::move_down_by(app, 1);
+            navigation::move_down_by(app, 1);
         }
 
         // Exit: Save and move up
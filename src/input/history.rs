@@ -0,0 +1,167 @@
+//! `:` command history: Up/Down in Command mode recall earlier commands,
+//! newest first, limited to those starting with what was typed.
+//!
+//! The history is a plain text file in the state directory, one command
+//! per line (oldest first), rewritten after every command so it survives
+//! restarts and crashes alike.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Commands kept in the history
+pub const MAX_HISTORY: usize = 200;
+
+/// Name of the history file in the state directory
+const HISTORY_FILE: &str = "command_history";
+
+/// Where the history is kept
+pub fn history_path() -> Option<PathBuf> {
+    crate::config::state_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Earlier `:` commands and where Up/Down browsing is in them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandHistory {
+    /// Commands, oldest first
+    entries: Vec<String>,
+    /// Entry shown while browsing (None when not browsing)
+    index: Option<usize>,
+    /// What was typed before browsing started, which limits the entries
+    /// to those starting with it
+    draft: String,
+    /// Where the history is saved (None keeps it in memory only)
+    path: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    /// Load the history from a file (missing file = empty history), which
+    /// is also where it is saved
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self {
+            entries,
+            path: Some(path.to_path_buf()),
+            ..Default::default()
+        })
+    }
+
+    /// Write the history to its file, creating the directory if needed
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut text = self.entries.join("\n");
+        text.push('\n');
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add a run command as the newest entry (an earlier copy moves up)
+    /// and save the history
+    pub fn push(&mut self, command: &str) -> Result<()> {
+        self.reset();
+        // Line breaks would split the entry when read back
+        if command.is_empty() || command.contains('\n') {
+            return Ok(());
+        }
+        self.entries.retain(|entry| entry != command);
+        self.entries.push(command.to_string());
+        let excess = self.entries.len().saturating_sub(MAX_HISTORY);
+        self.entries.drain(..excess);
+        self.save()
+    }
+
+    /// Stop browsing
+    pub fn reset(&mut self) {
+        self.index = None;
+        self.draft.clear();
+    }
+
+    /// The next older entry matching what was typed (`buffer` when
+    /// browsing starts); None at the oldest
+    pub fn older(&mut self, buffer: &str) -> Option<&str> {
+        if self.index.is_none() {
+            self.draft = buffer.to_string();
+        }
+        let end = self.index.unwrap_or(self.entries.len());
+        let index = self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.starts_with(&self.draft))?;
+        self.index = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// The next newer matching entry, or what was typed before browsing
+    /// once past the newest; None when not browsing
+    pub fn newer(&mut self) -> Option<String> {
+        let start = self.index? + 1;
+        let newer = self.entries[start..]
+            .iter()
+            .position(|entry| entry.starts_with(&self.draft));
+        match newer {
+            Some(offset) => {
+                self.index = Some(start + offset);
+                Some(self.entries[start + offset].clone())
+            }
+            None => {
+                let draft = std::mem::take(&mut self.draft);
+                self.index = None;
+                Some(draft)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_by_prefix() {
+        let mut history = CommandHistory::default();
+        for command in ["sort name", "w", "set zebra", "sort city desc"] {
+            history.push(command).unwrap();
+        }
+        assert_eq!(history.older(""), Some("sort city desc"));
+        assert_eq!(history.older(""), Some("set zebra"));
+        assert_eq!(history.newer().as_deref(), Some("sort city desc"));
+        assert_eq!(history.newer().as_deref(), Some(""));
+        assert_eq!(history.newer(), None);
+
+        // Only entries starting with what was typed
+        assert_eq!(history.older("so"), Some("sort city desc"));
+        assert_eq!(history.older("sort city desc"), Some("sort name"));
+        assert_eq!(history.older("sort name"), None);
+        assert_eq!(history.newer().as_deref(), Some("sort city desc"));
+        assert_eq!(history.newer().as_deref(), Some("so"));
+
+        // Running a command again moves it to the newest
+        history.push("w").unwrap();
+        assert_eq!(history.older(""), Some("w"));
+        assert_eq!(history.older(""), Some("sort city desc"));
+    }
+
+    #[test]
+    fn test_saved_and_loaded() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join(HISTORY_FILE);
+        let mut history = CommandHistory::load_from(&path).unwrap();
+        history.push("freq").unwrap();
+        history.push("sort id").unwrap();
+
+        let mut loaded = CommandHistory::load_from(&path).unwrap();
+        assert_eq!(loaded.older(""), Some("sort id"));
+        assert_eq!(loaded.older(""), Some("freq"));
+    }
+}
@@ -0,0 +1,726 @@
+//! Rebindable key actions and user key remapping.
+//!
+//! This module holds the registry of actions that can be rebound from the
+//! `[keys]` section of the config file, plus the `Keymap` that translates
+//! user-chosen keys back to the built-in key for each action.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// A single-key Normal mode action that can be rebound in the config file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionBinding {
+    /// Name used in the `[keys]` config section
+    pub name: &'static str,
+    /// Built-in key for this action
+    pub default_key: &'static str,
+    /// Short description for help and diagnostics
+    pub description: &'static str,
+}
+
+/// Registry of all rebindable actions and their built-in keys
+pub const ACTIONS: &[ActionBinding] = &[
+    ActionBinding {
+        name: "move_left",
+        default_key: "h",
+        description: "Move left",
+    },
+    ActionBinding {
+        name: "move_down",
+        default_key: "j",
+        description: "Move down",
+    },
+    ActionBinding {
+        name: "move_up",
+        default_key: "k",
+        description: "Move up",
+    },
+    ActionBinding {
+        name: "move_right",
+        default_key: "l",
+        description: "Move right",
+    },
+    ActionBinding {
+        name: "first_column",
+        default_key: "0",
+        description: "First column",
+    },
+    ActionBinding {
+        name: "last_column",
+        default_key: "$",
+        description: "Last column",
+    },
+    ActionBinding {
+        name: "last_row",
+        default_key: "G",
+        description: "Last row",
+    },
+    ActionBinding {
+        name: "next_word",
+        default_key: "w",
+        description: "Next non-empty cell",
+    },
+    ActionBinding {
+        name: "prev_word",
+        default_key: "b",
+        description: "Previous non-empty cell",
+    },
+    ActionBinding {
+        name: "end_word",
+        default_key: "e",
+        description: "Last non-empty cell",
+    },
+    ActionBinding {
+        name: "page_down",
+        default_key: "Ctrl+d",
+        description: "Page down",
+    },
+    ActionBinding {
+        name: "page_up",
+        default_key: "Ctrl+u",
+        description: "Page up",
+    },
+    ActionBinding {
+        name: "insert",
+        default_key: "i",
+        description: "Edit cell",
+    },
+    ActionBinding {
+        name: "insert_start",
+        default_key: "I",
+        description: "Edit cell (cursor at start)",
+    },
+    ActionBinding {
+        name: "replace_cell",
+        default_key: "s",
+        description: "Replace cell",
+    },
+    ActionBinding {
+        name: "add_row_below",
+        default_key: "o",
+        description: "Insert row below",
+    },
+    ActionBinding {
+        name: "add_row_above",
+        default_key: "O",
+        description: "Insert row above",
+    },
+    ActionBinding {
+        name: "paste",
+        default_key: "p",
+        description: "Paste rows below",
+    },
+    ActionBinding {
+        name: "cut_cell",
+        default_key: "x",
+        description: "Cut cell into the cell register",
+    },
+    ActionBinding {
+        name: "place_cell",
+        default_key: "P",
+        description: "Place the cut cell here, or paste rows above",
+    },
+    ActionBinding {
+        name: "clear_cell",
+        default_key: "Delete",
+        description: "Clear cell",
+    },
+    ActionBinding {
+        name: "undo",
+        default_key: "u",
+        description: "Undo last change",
+    },
+    ActionBinding {
+        name: "redo",
+        default_key: "Ctrl+r",
+        description: "Redo undone change",
+    },
+    ActionBinding {
+        name: "repeat",
+        default_key: ".",
+        description: "Repeat last change",
+    },
+    ActionBinding {
+        name: "command_mode",
+        default_key: ":",
+        description: "Enter command mode",
+    },
+    ActionBinding {
+        name: "next_file",
+        default_key: "]",
+        description: "Next file",
+    },
+    ActionBinding {
+        name: "prev_file",
+        default_key: "[",
+        description: "Previous file",
+    },
+    ActionBinding {
+        name: "file_picker",
+        default_key: "Ctrl+p",
+        description: "Pick a file to open",
+    },
+    ActionBinding {
+        name: "toggle_preview",
+        default_key: "K",
+        description: "Toggle cell preview line",
+    },
+    ActionBinding {
+        name: "next_match",
+        default_key: "n",
+        description: "Next match",
+    },
+    ActionBinding {
+        name: "prev_match",
+        default_key: "N",
+        description: "Previous match",
+    },
+    ActionBinding {
+        name: "column_search",
+        default_key: "\\",
+        description: "Search the selected column",
+    },
+    ActionBinding {
+        name: "magnify",
+        default_key: "M",
+        description: "Show the whole cell",
+    },
+    ActionBinding {
+        name: "help",
+        default_key: "?",
+        description: "Toggle help",
+    },
+    ActionBinding {
+        name: "quit",
+        default_key: "q",
+        description: "Quit",
+    },
+];
+
+/// Normal mode keys that aren't in `ACTIONS`, with what they do. Binding
+/// an action to one of them hides it, as it can't be moved elsewhere.
+pub const BUILT_IN_KEYS: &[(&str, &str)] = &[
+    ("1", "Count prefix"),
+    ("2", "Count prefix"),
+    ("3", "Count prefix"),
+    ("4", "Count prefix"),
+    ("5", "Count prefix"),
+    ("6", "Count prefix"),
+    ("7", "Count prefix"),
+    ("8", "Count prefix"),
+    ("9", "Count prefix"),
+    ("Up", "Move up"),
+    ("Down", "Move down"),
+    ("Left", "Move left"),
+    ("Right", "Move right"),
+    ("Enter", "Move down"),
+    ("PageUp", "Page up"),
+    ("PageDown", "Page down"),
+    ("Home", "First column"),
+    ("End", "Last row"),
+    ("Esc", "Cancel a pending command"),
+    ("Ctrl+f", "Screen down"),
+    ("Ctrl+b", "Screen up"),
+    ("Ctrl+w", "Split window commands"),
+    ("Alt+h", "Previous column page"),
+    ("Alt+l", "Next column page"),
+    ("Alt+H", "Move the column left"),
+    ("Alt+L", "Move the column right"),
+    ("Alt+Left", "Move the column left"),
+    ("Alt+Right", "Move the column right"),
+    ("g", "g commands (gg, g;, ...)"),
+    ("z", "z commands (zz, zt, zb, ...)"),
+    ("'", "Jump to a mark"),
+    ("m", "Set a mark"),
+    ("(", "Previous bookmarked row"),
+    (")", "Next bookmarked row"),
+    ("v", "Visual mode"),
+    ("d", "d commands (dd, ...)"),
+    ("y", "y commands (yy, ...)"),
+    ("a", "Edit cell"),
+    ("A", "Edit cell"),
+    ("F2", "Edit cell"),
+    ("\"", "Pick a register"),
+    (">", "Widen column"),
+    ("<", "Narrow column"),
+    ("=", "Automatic column width"),
+    ("{", "Previous row group"),
+    ("}", "Next row group"),
+];
+
+/// Look up a rebindable action by its config name
+pub fn find_action(name: &str) -> Option<&'static ActionBinding> {
+    ACTIONS.iter().find(|a| a.name == name)
+}
+
+/// Normalized key identity (SHIFT is implied by the character for Char keys)
+pub type KeyChord = (KeyCode, KeyModifiers);
+
+/// Normalize a key so that `G` and `Shift+G` compare equal
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+/// Parse a key description like `q`, `Ctrl+d`, `F2` or `Enter`
+pub fn parse_key(spec: &str) -> Result<KeyChord, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = spec;
+
+    // Strip modifier prefixes (Ctrl+, Alt+, Shift+), but keep a lone "+" as a key
+    while let Some(idx) = rest.find('+') {
+        if idx == 0 {
+            break;
+        }
+        let (prefix, tail) = rest.split_at(idx);
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" | "c" => KeyModifiers::CONTROL,
+            "alt" | "a" | "m" => KeyModifiers::ALT,
+            "shift" | "s" => KeyModifiers::SHIFT,
+            _ => return Err(format!("Unknown modifier '{}' in key '{}'", prefix, spec)),
+        };
+        rest = &tail[1..];
+    }
+
+    let code = match rest.to_ascii_lowercase().as_str() {
+        "" => return Err("Empty key".to_string()),
+        "enter" | "return" | "cr" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" | "bs" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        lower => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key '{}'", spec)),
+                },
+            }
+        }
+    };
+
+    // Shift+g is the key typing it gives: G
+    let code = match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        code => code,
+    };
+    Ok(normalize(code, modifiers))
+}
+
+/// Format a key in the same notation `parse_key` accepts (e.g. `Ctrl+d`)
+pub fn format_key(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let (code, modifiers) = normalize(code, modifiers);
+    let name = match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "Shift+Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        other => format!("{:?}", other),
+    };
+
+    let mut prefix = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        prefix.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        prefix.push_str("Alt+");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        prefix.push_str("Shift+");
+    }
+    prefix + &name
+}
+
+/// Find the action whose built-in key is `key` (after user remapping)
+pub fn action_for_key(key: KeyEvent) -> Option<&'static ActionBinding> {
+    let chord = normalize(key.code, key.modifiers);
+    ACTIONS
+        .iter()
+        .find(|a| parse_key(a.default_key).ok() == Some(chord))
+}
+
+/// A problem found while checking the `[keys]` config section
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingProblem {
+    /// The action name is not in the registry
+    UnknownAction(String),
+    /// The key description could not be parsed
+    InvalidKey { action: String, reason: String },
+    /// Two actions were bound to the same key
+    Conflict {
+        key: String,
+        first: String,
+        second: String,
+    },
+    /// The key is the built-in key of another action, which becomes unreachable
+    ShadowsDefault {
+        key: String,
+        action: String,
+        shadowed: &'static str,
+    },
+    /// The key is a built-in Normal mode key that can't be rebound, which
+    /// stops doing what it did
+    ShadowsBuiltIn {
+        key: String,
+        action: String,
+        built_in: &'static str,
+    },
+}
+
+impl BindingProblem {
+    /// Errors make the keymap unusable; anything else is a warning
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self,
+            BindingProblem::ShadowsDefault { .. } | BindingProblem::ShadowsBuiltIn { .. }
+        )
+    }
+}
+
+impl fmt::Display for BindingProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingProblem::UnknownAction(name) => write!(f, "Unknown action '{}'", name),
+            BindingProblem::InvalidKey { action, reason } => {
+                write!(f, "Invalid key for '{}': {}", action, reason)
+            }
+            BindingProblem::Conflict { key, first, second } => {
+                write!(
+                    f,
+                    "Key '{}' is bound to both '{}' and '{}'",
+                    key, first, second
+                )
+            }
+            BindingProblem::ShadowsDefault {
+                key,
+                action,
+                shadowed,
+            } => write!(
+                f,
+                "Key '{}' for '{}' hides the built-in key of '{}'",
+                key, action, shadowed
+            ),
+            BindingProblem::ShadowsBuiltIn {
+                key,
+                action,
+                built_in,
+            } => write!(
+                f,
+                "Key '{}' for '{}' hides the built-in key for '{}'",
+                key, action, built_in
+            ),
+        }
+    }
+}
+
+/// Check user key bindings (action name -> key) for errors and conflicts
+pub fn check_bindings(bindings: &BTreeMap<String, String>) -> Vec<BindingProblem> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<KeyChord, &str> = HashMap::new();
+
+    for (action, key) in bindings {
+        if find_action(action).is_none() {
+            problems.push(BindingProblem::UnknownAction(action.clone()));
+            continue;
+        }
+
+        let chord = match parse_key(key) {
+            Ok(chord) => chord,
+            Err(reason) => {
+                problems.push(BindingProblem::InvalidKey {
+                    action: action.clone(),
+                    reason,
+                });
+                continue;
+            }
+        };
+
+        if let Some(first) = seen.insert(chord, action) {
+            problems.push(BindingProblem::Conflict {
+                key: key.clone(),
+                first: first.to_string(),
+                second: action.clone(),
+            });
+            continue;
+        }
+
+        // Binding another action's built-in key is allowed, but only if that
+        // action has been moved elsewhere; otherwise it silently disappears
+        if let Some(shadowed) = ACTIONS.iter().find(|a| {
+            a.name != action
+                && !bindings.contains_key(a.name)
+                && parse_key(a.default_key).ok() == Some(chord)
+        }) {
+            problems.push(BindingProblem::ShadowsDefault {
+                key: key.clone(),
+                action: action.clone(),
+                shadowed: shadowed.name,
+            });
+        } else if let Some((_, built_in)) = BUILT_IN_KEYS
+            .iter()
+            .find(|(built_in, _)| parse_key(built_in).ok() == Some(chord))
+        {
+            problems.push(BindingProblem::ShadowsBuiltIn {
+                key: key.clone(),
+                action: action.clone(),
+                built_in,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Translates user-bound keys to the built-in key of their action
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Keymap {
+    remaps: HashMap<KeyChord, KeyChord>,
+}
+
+impl Keymap {
+    /// Build a keymap from `[keys]` bindings, failing on the first error
+    pub fn from_bindings(bindings: &BTreeMap<String, String>) -> Result<Self, String> {
+        if let Some(problem) = check_bindings(bindings).into_iter().find(|p| p.is_error()) {
+            return Err(problem.to_string());
+        }
+
+        let mut remaps = HashMap::new();
+        for (action, key) in bindings {
+            // Both lookups were validated by check_bindings above
+            let binding = find_action(action).ok_or_else(|| action.clone())?;
+            let from = parse_key(key)?;
+            let to = parse_key(binding.default_key)?;
+            if from != to {
+                remaps.insert(from, to);
+            }
+        }
+
+        Ok(Self { remaps })
+    }
+
+    /// Check whether any keys are remapped
+    pub fn is_empty(&self) -> bool {
+        self.remaps.is_empty()
+    }
+
+    /// Key that triggers an action: the one bound in the config file, or
+    /// the built-in key
+    pub fn key_for(&self, action: &ActionBinding) -> String {
+        parse_key(action.default_key)
+            .ok()
+            .and_then(|to| {
+                self.remaps
+                    .iter()
+                    .filter(|(_, &target)| target == to)
+                    .map(|(&(code, modifiers), _)| format_key(code, modifiers))
+                    .min()
+            })
+            .unwrap_or_else(|| action.default_key.to_string())
+    }
+
+    /// Translate a key press through the user bindings
+    pub fn translate(&self, key: KeyEvent) -> KeyEvent {
+        match self.remaps.get(&normalize(key.code, key.modifiers)) {
+            Some(&(code, modifiers)) => KeyEvent::new(code, modifiers),
+            None => key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_key_simple_chars() {
+        assert_eq!(
+            parse_key("q").unwrap(),
+            (KeyCode::Char('q'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("G").unwrap(),
+            (KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("+").unwrap(),
+            (KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn test_parse_key_with_modifiers_and_names() {
+        assert_eq!(
+            parse_key("Ctrl+d").unwrap(),
+            (KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse_key("alt+Enter").unwrap(),
+            (KeyCode::Enter, KeyModifiers::ALT)
+        );
+        assert_eq!(
+            parse_key("F2").unwrap(),
+            (KeyCode::F(2), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("Space").unwrap(),
+            (KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn test_parse_key_invalid() {
+        assert!(parse_key("").is_err());
+        assert!(parse_key("Hyper+x").is_err());
+        assert!(parse_key("F13").is_err());
+        assert!(parse_key("notakey").is_err());
+    }
+
+    #[test]
+    fn test_format_key_round_trips() {
+        for spec in ["q", "G", "Ctrl+d", "F2", "Enter", "Space", "Alt+Left"] {
+            let (code, modifiers) = parse_key(spec).unwrap();
+            assert_eq!(format_key(code, modifiers), spec);
+        }
+    }
+
+    #[test]
+    fn test_action_for_key() {
+        let key = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(action_for_key(key).map(|a| a.name), Some("page_down"));
+
+        let key = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(action_for_key(key).map(|a| a.name), Some("last_row"));
+
+        let key = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE);
+        assert!(action_for_key(key).is_none());
+    }
+
+    #[test]
+    fn test_registry_default_keys_parse() {
+        for action in ACTIONS {
+            assert!(
+                parse_key(action.default_key).is_ok(),
+                "Default key for {} does not parse",
+                action.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_bindings_unknown_action_and_invalid_key() {
+        let problems = check_bindings(&bindings(&[("fly", "x"), ("quit", "Hyper+q")]));
+        assert_eq!(problems.len(), 2);
+        assert!(problems.iter().all(|p| p.is_error()));
+    }
+
+    #[test]
+    fn test_check_bindings_conflict() {
+        let problems = check_bindings(&bindings(&[("next_file", "n"), ("prev_file", "n")]));
+        assert!(problems
+            .iter()
+            .any(|p| matches!(p, BindingProblem::Conflict { .. })));
+    }
+
+    #[test]
+    fn test_check_bindings_shadowing_is_warning() {
+        // Binding help to "q" hides quit unless quit is moved too
+        let problems = check_bindings(&bindings(&[("help", "q")]));
+        assert_eq!(problems.len(), 1);
+        assert!(!problems[0].is_error());
+
+        let problems = check_bindings(&bindings(&[("help", "q"), ("quit", "Q")]));
+        assert!(problems.is_empty());
+
+        // Keys outside the registry can't be moved, so binding them warns
+        let problems = check_bindings(&bindings(&[("undo", "v"), ("quit", "Alt+Left")]));
+        assert_eq!(
+            problems,
+            vec![
+                BindingProblem::ShadowsBuiltIn {
+                    key: "Alt+Left".to_string(),
+                    action: "quit".to_string(),
+                    built_in: "Move the column left",
+                },
+                BindingProblem::ShadowsBuiltIn {
+                    key: "v".to_string(),
+                    action: "undo".to_string(),
+                    built_in: "Visual mode",
+                },
+            ]
+        );
+        assert!(!problems[0].is_error());
+    }
+
+    #[test]
+    fn test_shift_letter_is_the_capital() {
+        assert_eq!(parse_key("Shift+g"), parse_key("G"));
+        assert_eq!(
+            parse_key("Ctrl+Shift+g").unwrap(),
+            (KeyCode::Char('G'), KeyModifiers::CONTROL)
+        );
+        // Typing Shift+g sends G, so a binding to it is translated
+        let keymap = Keymap::from_bindings(&bindings(&[("last_row", "Shift+x")])).unwrap();
+        let typed = keymap.translate(KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT));
+        assert_eq!(typed.code, KeyCode::Char('G'));
+    }
+
+    #[test]
+    fn test_keymap_translates_bound_key() {
+        let keymap = Keymap::from_bindings(&bindings(&[("next_file", "n")])).unwrap();
+
+        let translated = keymap.translate(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert_eq!(translated.code, KeyCode::Char(']'));
+
+        // Unbound keys pass through untouched
+        let other = keymap.translate(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert_eq!(other.code, KeyCode::Char('j'));
+
+        // The hints bar shows the bound key
+        assert_eq!(keymap.key_for(find_action("next_file").unwrap()), "n");
+        assert_eq!(keymap.key_for(find_action("prev_file").unwrap()), "[");
+    }
+
+    #[test]
+    fn test_keymap_rejects_conflicts() {
+        let result = Keymap::from_bindings(&bindings(&[("next_file", "n"), ("prev_file", "n")]));
+        assert!(result.is_err());
+    }
+}
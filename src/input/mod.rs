@@ -4,12 +4,21 @@
 //! pending commands (like 'g' waiting for second key in 'gg').
 
 pub mod actions;
+pub mod completion;
+pub mod echo;
 pub mod handler;
+pub mod history;
+pub mod keymap;
 pub mod state;
+pub mod status_messages;
 
 pub use actions::{
-    FileDirection, InputResult, NavigateAction, PendingCommand, StatusMessage, UserAction,
-    ViewportAction,
+    FileDirection, InputResult, NavigateAction, PendingCommand, Severity, StatusMessage,
+    UserAction, ViewportAction,
 };
-pub use handler::{handle_key, MULTI_KEY_TIMEOUT_MS};
+pub use echo::KeyEcho;
+pub use handler::{handle_key, handle_mouse, handle_paste, run_command, MULTI_KEY_TIMEOUT_MS};
+pub use history::CommandHistory;
+pub use keymap::Keymap;
 pub use state::InputState;
+pub use status_messages::StatusMessages;
@@ -4,7 +4,10 @@
 //! and count prefixes (like '5j' to move down 5 rows).
 
 use super::actions::PendingCommand;
+use super::echo::KeyEcho;
 use super::handler::{MAX_COMMAND_COUNT, MULTI_KEY_TIMEOUT_MS};
+use super::history::CommandHistory;
+use super::keymap::Keymap;
 use std::num::NonZeroUsize;
 use std::time::Instant;
 
@@ -17,11 +20,30 @@ pub struct InputState {
     /// Count prefix for vim commands (e.g., 5 for "5j")
     pub command_count: Option<NonZeroUsize>,
 
+    /// Register picked with `"a` for the next yank, delete or paste
+    pub register: Option<char>,
+
     /// Time when pending command was set (for timeout)
     pub pending_command_time: Option<Instant>,
 
     /// Command buffer for command mode (stores text after ":")
     pub command_buffer: String,
+
+    /// Cursor in the command buffer, in characters
+    pub command_cursor: usize,
+
+    /// Earlier commands for Up/Down
+    pub history: CommandHistory,
+
+    /// Paths still matching after the last Tab in `:e <path>`, shown until
+    /// the next key
+    pub completions: Vec<String>,
+
+    /// User key bindings from the config file
+    pub keymap: Keymap,
+
+    /// Recent keys and last command (shown with the `showkeys` option)
+    pub key_echo: KeyEcho,
 }
 
 impl InputState {
@@ -86,16 +108,53 @@ impl InputState {
     /// Clear the command buffer
     pub fn clear_command_buffer(&mut self) {
         self.command_buffer.clear();
+        self.command_cursor = 0;
+        self.completions.clear();
+        self.history.reset();
     }
 
-    /// Push a character to the command buffer
+    /// Replace the command buffer, with the cursor at the end
+    pub fn set_command_buffer(&mut self, text: &str) {
+        self.command_buffer = text.to_string();
+        self.command_cursor = text.chars().count();
+    }
+
+    /// Byte offset of a character position in the command buffer
+    fn command_byte(&self, cursor: usize) -> usize {
+        self.command_buffer
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.command_buffer.len(), |(i, _)| i)
+    }
+
+    /// Insert a character at the cursor
     pub fn push_command_char(&mut self, c: char) {
-        self.command_buffer.push(c);
+        let at = self.command_byte(self.command_cursor);
+        self.command_buffer.insert(at, c);
+        self.command_cursor += 1;
     }
 
-    /// Pop a character from the command buffer
+    /// Delete the character before the cursor (Backspace)
     pub fn pop_command_char(&mut self) {
-        self.command_buffer.pop();
+        if self.command_cursor > 0 {
+            self.command_cursor -= 1;
+            let at = self.command_byte(self.command_cursor);
+            self.command_buffer.remove(at);
+        }
+    }
+
+    /// Delete the character under the cursor (Delete)
+    pub fn delete_command_char(&mut self) {
+        if self.command_cursor < self.command_buffer.chars().count() {
+            let at = self.command_byte(self.command_cursor);
+            self.command_buffer.remove(at);
+        }
+    }
+
+    /// Move the cursor in the command buffer by `delta` characters
+    pub fn move_command_cursor(&mut self, delta: isize) {
+        let len = self.command_buffer.chars().count();
+        self.command_cursor = self.command_cursor.saturating_add_signed(delta).min(len);
     }
 }
 
@@ -123,6 +182,27 @@ mod tests {
         assert_eq!(state.pending_command, None);
     }
 
+    #[test]
+    fn test_command_buffer_editing() {
+        let mut state = InputState::new();
+        for c in "srt".chars() {
+            state.push_command_char(c);
+        }
+        state.move_command_cursor(-2);
+        state.push_command_char('o');
+        assert_eq!(state.command_buffer, "sort");
+        state.move_command_cursor(isize::MIN);
+        state.delete_command_char();
+        state.pop_command_char();
+        assert_eq!(state.command_buffer, "ort");
+        state.move_command_cursor(isize::MAX);
+        state.pop_command_char();
+        assert_eq!(
+            (state.command_buffer.as_str(), state.command_cursor),
+            ("or", 2)
+        );
+    }
+
     #[test]
     fn test_command_count() {
         let mut state = InputState::new();
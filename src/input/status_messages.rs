@@ -0,0 +1,102 @@
+//! Messages shown in the status bar. The newest one is shown; warnings and
+//! errors stay queued under later messages until they expire, so an error
+//! doesn't vanish on the very next keypress.
+
+use super::actions::{Severity, StatusMessage};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Warnings and errors kept at once; older ones are dropped
+const MAX_QUEUED: usize = 5;
+
+/// Status bar messages, oldest first, with the time each was shown
+#[derive(Debug, Clone, Default)]
+pub struct StatusMessages {
+    queue: VecDeque<(StatusMessage, Instant)>,
+}
+
+impl StatusMessages {
+    /// Show a message; it replaces an info message, but goes on top of
+    /// warnings and errors, which show again once it is gone
+    pub fn push(&mut self, message: impl Into<StatusMessage>) {
+        let message = message.into();
+        self.queue
+            .retain(|(queued, _)| queued.severity() > Severity::Info && *queued != message);
+        self.queue.push_back((message, Instant::now()));
+        while self.queue.len() > MAX_QUEUED {
+            self.queue.pop_front();
+        }
+    }
+
+    /// The message shown: the newest
+    pub fn current(&self) -> Option<&StatusMessage> {
+        self.queue.back().map(|(message, _)| message)
+    }
+
+    /// Check whether the status bar is free of messages
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Remove and return the shown message, showing the one under it
+    pub fn pop_current(&mut self) -> Option<StatusMessage> {
+        self.queue.pop_back().map(|(message, _)| message)
+    }
+
+    /// Remove all messages
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Remove the messages a keypress clears, leaving warnings and errors
+    pub fn clear_transient(&mut self) {
+        self.queue
+            .retain(|(message, _)| !message.should_clear_on_keypress());
+    }
+
+    /// Remove messages shown for `after` or longer (never with a zero
+    /// duration), returning whether any were removed
+    pub fn expire(&mut self, now: Instant, after: Duration) -> bool {
+        if after.is_zero() {
+            return false;
+        }
+        let before = self.queue.len();
+        self.queue
+            .retain(|(_, shown)| now.saturating_duration_since(*shown) < after);
+        self.queue.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_stay_under_later_messages() {
+        let mut messages = StatusMessages::default();
+        messages.push("Jumped to row 5");
+        messages.push(StatusMessage::error("Failed to save"));
+        messages.push("Column B width 12");
+        messages.push("Column B width 14");
+        assert_eq!(messages.current().unwrap().as_str(), "Column B width 14");
+
+        // A keypress clears the info message, showing the error again
+        messages.clear_transient();
+        assert_eq!(messages.current().unwrap().as_str(), "Failed to save");
+        messages.clear_transient();
+        assert!(!messages.is_empty());
+        assert_eq!(messages.pop_current().unwrap().as_str(), "Failed to save");
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_messages_expire() {
+        let mut messages = StatusMessages::default();
+        messages.push(StatusMessage::warning("No write since last change"));
+        let now = Instant::now();
+        assert!(!messages.expire(now, Duration::from_secs(5)));
+        assert!(!messages.expire(now + Duration::from_secs(60), Duration::ZERO));
+        assert!(messages.expire(now + Duration::from_secs(6), Duration::from_secs(5)));
+        assert!(messages.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
+pub mod analysis;
 pub mod app;
 pub mod cli;
+pub mod clipboard;
+pub mod config;
 pub mod csv;
+pub mod doctor;
 pub mod domain;
 pub mod file_system;
 pub mod input;
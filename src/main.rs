@@ -1,70 +1,309 @@
 use anyhow::{Context, Result};
-use crossterm::event::{self, Event, KeyEventKind};
-use lazycsv::{cli, ui, App, InputResult};
+use crossterm::cursor::SetCursorStyle;
+use crossterm::event::{
+    self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    Event, KeyEventKind,
+};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use lazycsv::analysis::{Schema, StatsReport};
+use lazycsv::app::{ExitStatus, LOAD_ERROR_CODE};
+use lazycsv::csv::{ExportFormat, Locale};
+use lazycsv::{cli, doctor, ui, App, Document, FileConfig, InputResult};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
 use std::time::Duration;
 
 fn main() -> Result<()> {
-    // Parse CLI args and create App
-    let app = App::from_cli(cli::parse_args())?;
+    let args = cli::parse_args();
 
-    // Initialize terminal
-    let mut terminal = ratatui::init();
+    // Subcommands run without the TUI
+    match &args.command {
+        Some(cli::Command::Doctor) => {
+            let findings = doctor::run_checks();
+            print!("{}", doctor::format_report(&findings));
+            std::process::exit(if doctor::has_errors(&findings) { 1 } else { 0 });
+        }
+        Some(cli::Command::Validate {
+            file,
+            schema,
+            format,
+        }) => std::process::exit(validate(&args, file, schema, *format)),
+        Some(cli::Command::Convert { input, output, to }) => {
+            std::process::exit(convert(&args, input, output, *to))
+        }
+        None => {}
+    }
+
+    if let Some(format) = args.stats {
+        std::process::exit(stats(&args, format));
+    }
+
+    // Create App from CLI args (exit code 3 when the file can't be loaded)
+    let print = args.print;
+    let mut app = match App::from_cli(args) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(LOAD_ERROR_CODE);
+        }
+    };
+
+    // Initialize terminal (pasted text arrives as one event)
+    let mut terminal = init_terminal()?;
 
     // Run app (wrapped to ensure cleanup)
-    let result = run(&mut terminal, app);
+    let result = run(&mut terminal, &mut app);
+
+    // Always restore terminal (including the user's cursor shape)
+    restore_terminal();
+
+    // Remember edit and cursor positions for the next session
+    if let Err(e) = app.save_positions() {
+        eprintln!("Warning: {:#}", e);
+    }
+
+    // Quitting leaves no edits to recover (`:q` refuses while there are
+    // unsaved ones, `:q!` drops them)
+    if result.is_ok() {
+        app.remove_swap_files();
+    }
+
+    result?;
+    if app.exit_status == ExitStatus::Normal {
+        let output = match app.pick {
+            Some(pick) => app.picked_output(pick),
+            None => print.and_then(|print| app.exit_output(print)),
+        };
+        if let Some(text) = output {
+            println!("{}", text);
+        }
+    }
+    std::process::exit(app.exit_status.code());
+}
+
+/// Where the table is drawn: stdout, or stderr when stdout is piped so
+/// that `--print` and `--pick` output can be captured
+/// (`host=$(lazycsv hosts.csv --print cell)`)
+fn screen() -> Box<dyn Write> {
+    if std::io::stdout().is_terminal() {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::io::stderr())
+    }
+}
+
+/// Switch the screen to raw mode and the alternate screen, restoring it
+/// again if lazycsv panics
+fn init_terminal() -> Result<Terminal<CrosstermBackend<Box<dyn Write>>>> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        hook(info);
+    }));
+    terminal::enable_raw_mode().context("Failed to enable raw mode")?;
+    crossterm::execute!(screen(), EnterAlternateScreen, EnableBracketedPaste)
+        .context("Failed to set up the terminal")?;
+    Terminal::new(CrosstermBackend::new(screen())).context("Failed to set up the terminal")
+}
+
+/// Give the terminal back as it was (including the user's cursor shape)
+fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = crossterm::execute!(
+        screen(),
+        SetCursorStyle::DefaultUserShape,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    );
+}
+
+/// `lazycsv convert`: write the file in another format; exits 1 if it
+/// could not be read or written
+fn convert(args: &cli::CliArgs, input: &Path, output: &Path, to: Option<ExportFormat>) -> i32 {
+    let config = FileConfig::with_options(args.delimiter, args.no_headers, args.encoding.clone())
+        .with_preamble(args.preamble().unwrap_or_default());
+    match lazycsv::csv::export::convert(input, output, to, &config) {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            1
+        }
+    }
+}
+
+/// `lazycsv --stats file.csv`: print the statistics of every column; exits
+/// with 3 (like the TUI) when the file can't be loaded
+fn stats(args: &cli::CliArgs, format: cli::StatsFormat) -> i32 {
+    let Some(file) = args.path.as_deref().filter(|path| path.is_file()) else {
+        eprintln!("Error: --stats needs a file");
+        return LOAD_ERROR_CODE;
+    };
+    let config = FileConfig::with_options(args.delimiter, args.no_headers, args.encoding.clone())
+        .with_preamble(args.preamble().unwrap_or_default());
+    let document = match config.read_document(file) {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("Error: Failed to load {}: {:#}", file.display(), e);
+            return LOAD_ERROR_CODE;
+        }
+    };
+    // Numbers and dates are read as the configured locale writes them
+    let locale = lazycsv::config::Config::load()
+        .ok()
+        .and_then(|config| config.options.locale)
+        .unwrap_or_else(Locale::from_env);
+    let report = StatsReport::compute(&document, &locale);
+    let name = file.display().to_string();
+    match format {
+        cli::StatsFormat::Text => print!("{}", report.format_text(&name, &locale)),
+        cli::StatsFormat::Json => println!("{}", report.to_json(&name)),
+    }
+    0
+}
 
-    // Always restore terminal
-    ratatui::restore();
+/// `lazycsv validate`: print the report and return the exit code (0 valid,
+/// 1 violations, 2 when the file or schema could not be read)
+fn validate(args: &cli::CliArgs, file: &Path, schema: &Path, format: cli::ReportFormat) -> i32 {
+    let report = Schema::load_from(schema).and_then(|schema| {
+        let document = Document::from_file_with(
+            file,
+            args.delimiter,
+            args.no_headers,
+            args.encoding.clone(),
+            args.preamble().unwrap_or_default(),
+        )
+        .with_context(|| format!("Failed to load {}", file.display()))?;
+        schema.validate(&document)
+    });
 
-    result
+    match report {
+        Ok(report) => {
+            let name = file.display().to_string();
+            match format {
+                cli::ReportFormat::Text => print!("{}", report.format_text(&name)),
+                cli::ReportFormat::Json => println!("{}", report.to_json(&name)),
+            }
+            if report.is_valid() {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            2
+        }
+    }
 }
 
 fn run(
     terminal: &mut ratatui::Terminal<impl ratatui::backend::Backend>,
-    mut app: App,
+    app: &mut App,
 ) -> Result<()> {
     // Event-driven rendering: only redraw when state changes
     let mut needs_redraw = true;
 
+    // Cursor shape currently applied (None forces the first update)
+    let mut cursor_mode = None;
+
+    // Mouse capture follows the `mouse` option (off leaves text selection
+    // to the terminal)
+    let mut mouse_captured = false;
+
     loop {
         // Only render if state has changed
         if needs_redraw {
             terminal
-                .draw(|frame| ui::render(frame, &mut app))
+                .draw(|frame| ui::render(frame, app))
                 .context("Failed to render UI")?;
             needs_redraw = false;
+
+            // Bar cursor while typing, block otherwise
+            if app.theme.cursor_shapes && cursor_mode != Some(app.mode) {
+                crossterm::execute!(screen(), ui::theme::cursor_style(app.mode))
+                    .context("Failed to set cursor shape")?;
+                cursor_mode = Some(app.mode);
+            }
+
+            if app.options.mouse != mouse_captured {
+                mouse_captured = app.options.mouse;
+                if mouse_captured {
+                    crossterm::execute!(screen(), EnableMouseCapture)
+                } else {
+                    crossterm::execute!(screen(), DisableMouseCapture)
+                }
+                .context("Failed to toggle mouse capture")?;
+            }
         }
 
         // Poll for events (100ms timeout)
         if event::poll(Duration::from_millis(100)).context("Failed to poll for events")? {
-            if let Event::Key(key) = event::read().context("Failed to read event")? {
+            let result = match event::read().context("Failed to read event")? {
                 // Only process KeyPress events (ignore KeyRelease)
-                if key.kind == KeyEventKind::Press {
-                    // Handle key press
-                    let result = app.handle_key(key)?;
-
-                    // State changed, need to redraw
-                    needs_redraw = true;
-
-                    match result {
-                        InputResult::ReloadFile => {
-                            // Clear screen before loading new file to prevent stray characters
-                            terminal.clear().context("Failed to clear terminal")?;
-                            // Reload CSV data from new file
-                            app.reload_current_file()
-                                .context("Failed to reload CSV file")?;
-                        }
-                        InputResult::Quit => {
-                            app.should_quit = true;
-                        }
-                        InputResult::Continue => {
-                            // Normal operation, continue
+                Event::Key(key) if key.kind == KeyEventKind::Press => Some(app.handle_key(key)?),
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                Event::Paste(text) => app.handle_paste(&text),
+                _ => None,
+            };
+
+            if let Some(result) = result {
+                // State changed, need to redraw
+                needs_redraw = true;
+
+                match result {
+                    InputResult::ReloadFile => {
+                        // Clear screen before loading new file to prevent stray characters
+                        terminal.clear().context("Failed to clear terminal")?;
+                        // Reload CSV data from new file
+                        app.reload_current_file()
+                            .context("Failed to reload CSV file")?;
+                        if !app.offer_recovery() {
+                            app.offer_resume_point();
                         }
                     }
+                    InputResult::Quit => {
+                        app.should_quit = true;
+                    }
+                    InputResult::Continue => {
+                        // Normal operation, continue
+                    }
                 }
             }
         }
 
+        // Pick up config file edits without restarting
+        if app.check_config_changed() {
+            needs_redraw = true;
+        }
+
+        // Mirror the view to followers, or follow the leader
+        if app.sync_shared_view() {
+            needs_redraw = true;
+        }
+
+        // Rows appended by another program while watching
+        if app.check_file_appended() {
+            needs_redraw = true;
+        }
+
+        // Warn before editing a file another program has rewritten
+        if app.check_file_changed() {
+            needs_redraw = true;
+        }
+
+        // Keep unsaved edits in a swap file in case lazycsv dies
+        if app.update_swap_file(std::time::Instant::now()) {
+            needs_redraw = true;
+        }
+
+        // Status messages go away after a while, errors included
+        if app.expire_messages() {
+            needs_redraw = true;
+        }
+
         // Check exit condition
         if app.should_quit {
             break;
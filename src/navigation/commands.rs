@@ -3,15 +3,23 @@
 //! This module provides functions for navigating the CSV table including
 //! cursor movement, page scrolling, and jump commands with count prefixes.
 
-use crate::app::App;
+use crate::app::{messages, App};
 use crate::domain::position::ColIndex;
-use crate::ui::{ViewportMode, MAX_VISIBLE_COLS};
+use crate::session::CellPosition;
+use crate::ui::column_width::{self, fit_columns, MAX_COLUMN_WIDTH, MIN_MANUAL_WIDTH};
+use crate::ui::{MarkKind, ViewportMode};
 use anyhow::Result;
 use crossterm::event::KeyCode;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 
-/// Rows per page for PageUp/PageDown navigation
+/// Rows per page for PageUp/PageDown navigation until the table has been
+/// drawn (see the `page` option)
 pub const PAGE_SIZE: usize = 20;
 
+/// Cells a column grows or shrinks per `>` / `<`
+pub const WIDTH_STEP: i32 = 2;
+
 /// Handle navigation keys with optional count prefix
 pub fn handle_navigation(app: &mut App, code: KeyCode) -> Result<()> {
     // Consume count prefix (e.g., 5 from command_count for 5j)
@@ -44,20 +52,17 @@ pub fn handle_navigation(app: &mut App, code: KeyCode) -> Result<()> {
 
         // First column
         KeyCode::Char('0') => {
-            app.view_state.selected_column = ColIndex::new(0);
+            app.view_state.selected_column = ColIndex::new(app.view_state.column_at(0));
             app.view_state.column_scroll_offset = 0;
             app.view_state.viewport_mode = ViewportMode::Auto;
         }
 
         // Last column
         KeyCode::Char('$') => {
-            app.view_state.selected_column =
-                ColIndex::new(app.document.column_count().saturating_sub(1));
+            let last = visible_columns(app).last().copied().unwrap_or(0);
+            app.view_state.selected_column = ColIndex::new(last);
             // Adjust horizontal offset to show last column
-            if app.document.column_count() > MAX_VISIBLE_COLS {
-                app.view_state.column_scroll_offset =
-                    app.document.column_count() - MAX_VISIBLE_COLS;
-            }
+            update_horizontal_scroll(app, last);
             app.view_state.viewport_mode = ViewportMode::Auto;
         }
 
@@ -86,6 +91,28 @@ pub fn handle_navigation(app: &mut App, code: KeyCode) -> Result<()> {
             }
         }
 
+        // Column width: > grows, < shrinks, = back to automatic
+        KeyCode::Char('>') => {
+            resize_column(app, count as i32 * WIDTH_STEP);
+        }
+
+        KeyCode::Char('<') => {
+            resize_column(app, -(count as i32) * WIDTH_STEP);
+        }
+
+        KeyCode::Char('=') => {
+            reset_column_width(app);
+        }
+
+        // Row group motions: next/previous group header
+        KeyCode::Char('}') => {
+            jump_row_group(app, count, true);
+        }
+
+        KeyCode::Char('{') => {
+            jump_row_group(app, count, false);
+        }
+
         // Word motion: next non-empty cell
         KeyCode::Char('w') => {
             next_word(app);
@@ -109,7 +136,7 @@ pub fn handle_navigation(app: &mut App, code: KeyCode) -> Result<()> {
 
 fn select_next_page(app: &mut App) {
     let i = match app.view_state.table_state.selected() {
-        Some(i) => (i + PAGE_SIZE).min(app.document.row_count().saturating_sub(1)),
+        Some(i) => (i + app.page_rows()).min(app.display_row_count().saturating_sub(1)),
         None => 0,
     };
     app.view_state.table_state.select(Some(i));
@@ -117,58 +144,105 @@ fn select_next_page(app: &mut App) {
 
 fn select_previous_page(app: &mut App) {
     let i = match app.view_state.table_state.selected() {
-        Some(i) => i.saturating_sub(PAGE_SIZE),
+        Some(i) => i.saturating_sub(app.page_rows()),
         None => 0,
     };
     app.view_state.table_state.select(Some(i));
 }
 
+/// Remember the cursor as the `'` mark before a jump, for `''` to return to
+fn remember_jump(app: &mut App) {
+    if let Some(row) = app.get_selected_row() {
+        let col = app.view_state.selected_column.get();
+        app.session.set_mark(
+            '\'',
+            CellPosition {
+                row: row.get(),
+                col,
+            },
+        );
+    }
+}
+
 /// Go to first row (gg command)
 pub fn goto_first_row(app: &mut App) {
+    remember_jump(app);
     app.view_state.table_state.select(Some(0));
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
 
 /// Go to last row (G command)
 pub fn goto_last_row(app: &mut App) {
-    let last = app.document.row_count().saturating_sub(1);
+    remember_jump(app);
+    let last = app.display_row_count().saturating_sub(1);
     app.view_state.table_state.select(Some(last));
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
 
 /// Go to specific line number (5G or :5 command)
 pub fn goto_line(app: &mut App, line_number: usize) {
-    use crate::input::StatusMessage;
+    use crate::domain::position::RowIndex;
 
     let row_count = app.document.row_count();
 
     // Line numbers are 1-indexed in vim
     if line_number == 0 {
-        app.status_message = Some(StatusMessage::from("Row number must be >= 1"));
+        app.status_messages.push("Row number must be >= 1");
         return;
     }
 
     if line_number > row_count {
-        app.status_message = Some(StatusMessage::from(format!(
+        app.status_messages.push(format!(
             "Row {} does not exist (max: {})",
             line_number, row_count
-        )));
+        ));
         return;
     }
 
-    let target = line_number - 1; // Convert to 0-indexed
-    app.view_state.table_state.select(Some(target));
+    let target = RowIndex::new(line_number - 1); // Convert to 0-indexed
+    if app.display_row(target).is_none() {
+        app.status_messages
+            .push(format!("Row {} is hidden by the filter", line_number));
+        return;
+    }
+    remember_jump(app);
+    app.select_document_row(target);
+    app.view_state.viewport_mode = ViewportMode::Auto;
+    app.status_messages
+        .push(format!("Jumped to row {}", line_number));
+}
+
+/// Jump to the header of the next (or previous) row group, count times
+pub fn jump_row_group(app: &mut App, count: usize, forward: bool) {
+    if app.view_state.row_groups.is_empty() {
+        app.status_messages
+            .push("No row groups (see :set rowgroups)");
+        return;
+    }
+
+    let Some(mut row) = app.get_selected_row().map(|r| r.get()) else {
+        return;
+    };
+    for _ in 0..count {
+        let groups = &app.view_state.row_groups;
+        let next = if forward {
+            groups.next_header(row)
+        } else {
+            groups.prev_header(row)
+        };
+        match next {
+            Some(header) => row = header,
+            None => break,
+        }
+    }
+    app.select_document_row(crate::domain::position::RowIndex::new(row));
     app.view_state.viewport_mode = ViewportMode::Auto;
-    app.status_message = Some(StatusMessage::from(format!(
-        "Jumped to row {}",
-        line_number
-    )));
 }
 
 /// Move down by count rows (5j moves down 5 rows)
 pub fn move_down_by(app: &mut App, count: usize) {
     let current = app.view_state.table_state.selected().unwrap_or(0);
-    let target = (current + count).min(app.document.row_count().saturating_sub(1));
+    let target = (current + count).min(app.display_row_count().saturating_sub(1));
     app.view_state.table_state.select(Some(target));
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
@@ -181,37 +255,69 @@ pub fn move_up_by(app: &mut App, count: usize) {
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
 
+/// Columns shown on screen, in display order (members of collapsed groups
+/// are skipped)
+fn visible_columns(app: &App) -> Vec<usize> {
+    let groups = &app.view_state.column_groups;
+    app.view_state
+        .display_columns(app.document.column_count())
+        .into_iter()
+        .filter(|&col| !groups.is_hidden(col))
+        .collect()
+}
+
+/// Position of the selected column among the visible columns
+fn selected_visible_position(app: &App, visible: &[usize]) -> usize {
+    let selected = app
+        .view_state
+        .column_groups
+        .visible_column(app.view_state.selected_column.get());
+    visible.iter().position(|&col| col == selected).unwrap_or(0)
+}
+
 /// Move right by count columns (3l moves right 3 columns)
 pub fn move_right_by(app: &mut App, count: usize) {
-    let new_col = app
-        .view_state
-        .selected_column
-        .saturating_add(count)
-        .get()
-        .min(app.document.column_count().saturating_sub(1));
+    let visible = visible_columns(app);
+    let position = selected_visible_position(app, &visible);
+    let new_col = visible
+        .get(
+            position
+                .saturating_add(count)
+                .min(visible.len().saturating_sub(1)),
+        )
+        .copied()
+        .unwrap_or(0);
     app.view_state.selected_column = ColIndex::new(new_col);
-    if app.view_state.selected_column.get()
-        >= app.view_state.column_scroll_offset + MAX_VISIBLE_COLS
-    {
-        app.view_state.column_scroll_offset =
-            app.view_state.selected_column.get() - MAX_VISIBLE_COLS + 1;
-    }
+    update_horizontal_scroll(app, new_col);
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
 
 /// Move left by count columns (3h moves left 3 columns)
 pub fn move_left_by(app: &mut App, count: usize) {
-    let new_col = app.view_state.selected_column.saturating_sub(count);
-    app.view_state.selected_column = new_col;
-    if app.view_state.selected_column.get() < app.view_state.column_scroll_offset {
-        app.view_state.column_scroll_offset = new_col.get();
-    }
+    let visible = visible_columns(app);
+    let position = selected_visible_position(app, &visible);
+    let new_col = visible
+        .get(position.saturating_sub(count))
+        .copied()
+        .unwrap_or(0);
+    app.view_state.selected_column = ColIndex::new(new_col);
+    update_horizontal_scroll(app, new_col);
     app.view_state.viewport_mode = ViewportMode::Auto;
 }
 
+/// Select a column by index, expanding its group if it is collapsed away
+pub fn select_column(app: &mut App, col_idx: usize) {
+    if app.view_state.column_groups.is_hidden(col_idx) {
+        app.view_state
+            .column_groups
+            .set_collapsed(col_idx, Some(false));
+    }
+    app.view_state.selected_column = ColIndex::new(col_idx);
+    update_horizontal_scroll(app, col_idx);
+}
+
 /// Jump to column by Excel-style letter (A, B, ..., AA, AB, ...)
 pub fn goto_column(app: &mut App, column_letter: &str) {
-    use crate::input::StatusMessage;
     use crate::ui::utils::{column_to_excel_letter, excel_letter_to_column};
 
     match excel_letter_to_column(column_letter) {
@@ -221,81 +327,379 @@ pub fn goto_column(app: &mut App, column_letter: &str) {
             // Check out-of-bounds (col_idx is 0-indexed, max_col is count)
             if col_idx >= max_col {
                 let max_letter = column_to_excel_letter(max_col.saturating_sub(1));
-                app.status_message = Some(StatusMessage::from(format!(
+                app.status_messages.push(format!(
                     "Column {} does not exist (max: {})",
                     column_letter.to_uppercase(),
                     max_letter
-                )));
+                ));
                 return;
             }
 
-            app.view_state.selected_column = ColIndex::new(col_idx);
-
-            // Update horizontal scroll
-            if col_idx < app.view_state.column_scroll_offset {
-                app.view_state.column_scroll_offset = col_idx;
-            } else if col_idx >= app.view_state.column_scroll_offset + MAX_VISIBLE_COLS {
-                app.view_state.column_scroll_offset = col_idx - MAX_VISIBLE_COLS + 1;
-            }
+            select_column(app, col_idx);
 
             app.view_state.viewport_mode = ViewportMode::Auto;
-            app.status_message = Some(StatusMessage::from(format!(
-                "Jumped to column {}",
-                column_letter.to_uppercase()
-            )));
+            app.status_messages
+                .push(format!("Jumped to column {}", column_letter.to_uppercase()));
         }
         Err(msg) => {
-            app.status_message = Some(StatusMessage::from(msg));
+            app.status_messages.push(msg);
         }
     }
 }
 
 /// Jump to column by 1-indexed number
 pub fn goto_column_by_number(app: &mut App, col_num: usize) {
-    use crate::input::StatusMessage;
     use crate::ui::utils::column_to_excel_letter;
 
     let max_col = app.document.column_count();
     if col_num > max_col {
         let max_letter = column_to_excel_letter(max_col.saturating_sub(1));
-        app.status_message = Some(StatusMessage::from(format!(
+        app.status_messages.push(format!(
             "Column {} does not exist (max: {} / {})",
             col_num, max_col, max_letter
-        )));
+        ));
         return;
     }
 
     let col_idx = col_num.saturating_sub(1); // Convert to 0-indexed
-    app.view_state.selected_column = ColIndex::new(col_idx);
+    select_column(app, col_idx);
 
-    // Update horizontal scroll
-    if col_idx < app.view_state.column_scroll_offset {
-        app.view_state.column_scroll_offset = col_idx;
-    } else if col_idx >= app.view_state.column_scroll_offset + MAX_VISIBLE_COLS {
-        app.view_state.column_scroll_offset = col_idx - MAX_VISIBLE_COLS + 1;
+    app.view_state.viewport_mode = ViewportMode::Auto;
+    let col_letter = column_to_excel_letter(col_idx);
+    app.status_messages
+        .push(format!("Jumped to column {} ({})", col_num, col_letter));
+}
+
+/// Jump to a column given by header name, number or letters (`--col`)
+pub fn goto_column_spec(app: &mut App, spec: &str) {
+    if let Some(col) = app.document.find_column(spec) {
+        select_column(app, col.get());
+        app.view_state.viewport_mode = ViewportMode::Auto;
+        app.status_messages.push(format!(
+            "Jumped to column {} ({})",
+            app.document.get_header(col),
+            crate::ui::column_to_excel_letter(col.get())
+        ));
+    } else if let Ok(col_num) = spec.parse::<usize>() {
+        if col_num == 0 {
+            app.status_messages.push("Column number must be >= 1");
+        } else {
+            goto_column_by_number(app, col_num);
+        }
+    } else if (1..=3).contains(&spec.len()) && spec.chars().all(|c| c.is_ascii_alphabetic()) {
+        // Short letter runs are column letters (A..XFD); anything longer is a name
+        goto_column(app, &spec.to_ascii_uppercase());
+    } else {
+        app.status_messages
+            .push(format!("No column named {}", spec));
     }
+}
 
+/// Jump to the column whose header best matches `query` (`:col`): the one
+/// named exactly that (ignoring case), else the best fuzzy match, so
+/// `:col ltv` finds `customer_ltv`. Ties go to the leftmost column.
+pub fn goto_column_by_name(app: &mut App, query: &str) {
+    let (col, others) = match app.document.find_column(query) {
+        Some(col) => (col, 0),
+        None => {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let mut scored: Vec<(i64, usize)> = app
+                .document
+                .headers
+                .iter()
+                .enumerate()
+                .filter_map(|(col, header)| Some((matcher.fuzzy_match(header, query)?, col)))
+                .collect();
+            // Stable, so equal scores keep column order
+            scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            let Some(&(_, col)) = scored.first() else {
+                app.status_messages
+                    .push(format!("No column matches {}", query));
+                return;
+            };
+            (ColIndex::new(col), scored.len() - 1)
+        }
+    };
+    select_column(app, col.get());
     app.view_state.viewport_mode = ViewportMode::Auto;
-    let col_letter = column_to_excel_letter(col_idx);
-    app.status_message = Some(StatusMessage::from(format!(
+    let mut message = format!(
         "Jumped to column {} ({})",
-        col_num, col_letter
-    )));
+        app.document.get_header(col),
+        crate::ui::column_to_excel_letter(col.get())
+    );
+    if others > 0 {
+        message.push_str(&format!(
+            "; {} other {} match",
+            others,
+            if others == 1 { "column" } else { "columns" }
+        ));
+    }
+    app.status_messages.push(message);
+}
+
+/// Move to a remembered cell, clamped to the document (rows and columns may
+/// have been deleted since). Returns false if the row is hidden by the filter.
+fn goto_cell(app: &mut App, position: CellPosition) -> bool {
+    use crate::domain::position::RowIndex;
+
+    let row = position.row.min(app.document.row_count().saturating_sub(1));
+    let col = position
+        .col
+        .min(app.document.column_count().saturating_sub(1));
+    if !app.select_document_row(RowIndex::new(row)) {
+        return false;
+    }
+    select_column(app, app.view_state.column_groups.visible_column(col));
+    app.view_state.viewport_mode = ViewportMode::Auto;
+    true
+}
+
+/// Jump back through recent edit locations (`g;`); each press goes `count`
+/// edits further back
+pub fn goto_previous_edit(app: &mut App, count: usize) {
+    let edits = app.session.edit_positions();
+    if edits.is_empty() {
+        app.status_messages.push("No edits yet");
+        return;
+    }
+    let index = app.view_state.edit_jump.map_or(count - 1, |i| i + count);
+    let Some(&position) = edits.get(index) else {
+        app.status_messages.push("At oldest edit");
+        return;
+    };
+    let total = edits.len();
+
+    app.view_state.edit_jump = Some(index);
+    let message = if goto_cell(app, position) {
+        format!("Edit {} of {}", index + 1, total)
+    } else {
+        format!("Row {} is hidden by the filter", position.row + 1)
+    };
+    app.status_messages.push(message);
+}
+
+/// Return to where the cursor was when the file was last closed (`'"`)
+pub fn goto_last_position(app: &mut App) {
+    let message = match app.session.last_position() {
+        None => "No position saved for this file".to_string(),
+        Some(position) if !goto_cell(app, position) => {
+            format!("Row {} is hidden by the filter", position.row + 1)
+        }
+        Some(position) => format!(
+            "Returned to {}{}",
+            crate::ui::column_to_excel_letter(app.view_state.selected_column.get()),
+            position.row.min(app.document.row_count().saturating_sub(1)) + 1
+        ),
+    };
+    app.status_messages.push(message);
+}
+
+/// Return to the column selected before this one, at the row last
+/// selected in it (`gl`); again to go back
+pub fn goto_previous_column(app: &mut App) {
+    let Some(col) = app
+        .view_state
+        .previous_column
+        .filter(|&col| col < app.document.column_count())
+    else {
+        app.status_messages.push("No column to return to");
+        return;
+    };
+    let row = match app.view_state.column_rows.get(&col) {
+        Some(&row) => row,
+        None => app.get_selected_row().map_or(0, |row| row.get()),
+    };
+    let message = if goto_cell(app, CellPosition { row, col }) {
+        format!(
+            "Back to {}{}",
+            crate::ui::column_to_excel_letter(app.view_state.selected_column.get()),
+            app.get_selected_row().map_or(0, |row| row.get()) + 1
+        )
+    } else {
+        // The row is filtered out: the column alone
+        select_column(app, app.view_state.column_groups.visible_column(col));
+        format!(
+            "Back to column {} (row {} is hidden by the filter)",
+            crate::ui::column_to_excel_letter(col),
+            row + 1
+        )
+    };
+    app.status_messages.push(message);
+}
+
+/// Set a mark at the cursor (`ma`)
+pub fn set_mark(app: &mut App, name: char) {
+    let Some(row) = app.get_selected_row() else {
+        return;
+    };
+    let col = app.view_state.selected_column.get();
+    app.session.set_mark(
+        name,
+        CellPosition {
+            row: row.get(),
+            col,
+        },
+    );
+    app.status_messages.push(format!(
+        "Mark {} set at {}{}",
+        name,
+        crate::ui::column_to_excel_letter(col),
+        row.get() + 1
+    ));
+}
+
+/// Bookmark the current row, or remove its bookmark (`mB`)
+pub fn toggle_bookmark(app: &mut App) {
+    let Some(row) = app.get_selected_row().map(|row| row.get()) else {
+        return;
+    };
+    let marks = &mut app.view_state.marks;
+    let added = !marks.remove(row, None, MarkKind::Bookmark);
+    if added {
+        marks.add(row, None, MarkKind::Bookmark);
+    }
+    app.store_bookmarks();
+    let count = app.bookmarks().len();
+    app.status_messages.push(format!(
+        "{} row {} ({} bookmark{}; ( and ) cycle, :bookmarks lists)",
+        if added {
+            "Bookmarked"
+        } else {
+            "Removed bookmark of"
+        },
+        row + 1,
+        count,
+        if count == 1 { "" } else { "s" }
+    ));
+}
+
+/// Jump `count` bookmarks down or up from the cursor (`)`/`(`), wrapping
+/// around the ends; rows hidden by the filter are skipped
+pub fn goto_bookmark(app: &mut App, count: usize, forward: bool) {
+    use crate::domain::position::RowIndex;
+
+    let bookmarks: Vec<usize> = app
+        .bookmarks()
+        .into_iter()
+        .filter(|&row| app.display_row(RowIndex::new(row)).is_some())
+        .collect();
+    if bookmarks.is_empty() {
+        app.status_messages
+            .push("No bookmarks (mB bookmarks a row)");
+        return;
+    }
+
+    let mut current = app.get_selected_row().map_or(0, |row| row.get());
+    let mut index = 0;
+    for _ in 0..count {
+        index = if forward {
+            bookmarks.iter().position(|&b| b > current).unwrap_or(0)
+        } else {
+            bookmarks
+                .iter()
+                .rposition(|&b| b < current)
+                .unwrap_or(bookmarks.len() - 1)
+        };
+        current = bookmarks[index];
+    }
+
+    remember_jump(app);
+    app.select_document_row(RowIndex::new(current));
+    app.view_state.viewport_mode = ViewportMode::Auto;
+    app.status_messages.push(format!(
+        "Bookmark {} of {} (row {})",
+        index + 1,
+        bookmarks.len(),
+        current + 1
+    ));
+}
+
+/// Jump to a mark (`'a`), or back to where the last jump started (`''`)
+pub fn goto_mark(app: &mut App, name: char) {
+    let Some(position) = app.session.mark(name) else {
+        app.status_messages.push(if name == '\'' {
+            "No jump to return from".to_string()
+        } else {
+            format!("Mark {} not set", name)
+        });
+        return;
+    };
+    let from = app.get_selected_row().map(|row| CellPosition {
+        row: row.get(),
+        col: app.view_state.selected_column.get(),
+    });
+    if !goto_cell(app, position) {
+        app.status_messages
+            .push(format!("Row {} is hidden by the filter", position.row + 1));
+        return;
+    }
+    // '' again goes back to where this jump started
+    if let Some(from) = from {
+        app.session.set_mark('\'', from);
+    }
+    app.status_messages.push(format!(
+        "Jumped to {}{}",
+        crate::ui::column_to_excel_letter(app.view_state.selected_column.get()),
+        app.get_selected_row().map_or(0, |row| row.get()) + 1
+    ));
+}
+
+/// Kinds of marks `n`/`N` step through: results of `:long` and the like,
+/// and validation errors
+const MATCH_KINDS: &[MarkKind] = &[MarkKind::SearchHit, MarkKind::Error];
+
+/// Jump `count` matches forward or back from the cursor (`n`/`N`),
+/// wrapping around the ends; rows hidden by the filter are skipped
+pub fn goto_match(app: &mut App, count: usize, forward: bool) {
+    use crate::domain::position::RowIndex;
+
+    // Row marks count as the row's first cell
+    let mut matches: Vec<(usize, usize)> = app
+        .view_state
+        .marks
+        .positions(MATCH_KINDS)
+        .into_iter()
+        .map(|(row, col)| (row, col.unwrap_or(0)))
+        .filter(|&(row, _)| app.display_row(RowIndex::new(row)).is_some())
+        .collect();
+    matches.dedup();
+    if matches.is_empty() {
+        app.status_messages.push("No matches");
+        return;
+    }
+
+    let row = app.get_selected_row().map_or(0, |row| row.get());
+    let mut current = (row, app.view_state.selected_column.get());
+    let mut index = 0;
+    for _ in 0..count {
+        index = if forward {
+            matches.iter().position(|&m| m > current).unwrap_or(0)
+        } else {
+            matches
+                .iter()
+                .rposition(|&m| m < current)
+                .unwrap_or(matches.len() - 1)
+        };
+        current = matches[index];
+    }
+
+    let (row, col) = current;
+    remember_jump(app);
+    goto_cell(app, CellPosition { row, col });
+    app.status_messages
+        .push(format!("Match {} of {}", index + 1, matches.len()));
 }
 
 /// Move to next non-empty cell in current row (w)
 pub fn next_word(app: &mut App) {
     use crate::domain::position::RowIndex;
-    use crate::input::StatusMessage;
 
-    let current_row = app.view_state.table_state.selected().unwrap_or(0);
-    let current_col = app.view_state.selected_column.get();
-    let max_col = app.document.column_count().saturating_sub(1);
+    let current_row = app.get_selected_row().unwrap_or(RowIndex::new(0));
+    let visible = visible_columns(app);
+    let position = selected_visible_position(app, &visible);
 
-    for col in (current_col + 1)..=max_col {
-        let cell = app
-            .document
-            .get_cell(RowIndex::new(current_row), ColIndex::new(col));
+    for col in visible.into_iter().skip(position + 1) {
+        let cell = app.document.get_cell(current_row, ColIndex::new(col));
         if !cell.is_empty() {
             app.view_state.selected_column = ColIndex::new(col);
             update_horizontal_scroll(app, col);
@@ -303,26 +707,24 @@ pub fn next_word(app: &mut App) {
             return;
         }
     }
-    app.status_message = Some(StatusMessage::from("No more non-empty cells"));
+    app.status_messages.push("No more non-empty cells");
 }
 
 /// Move to previous non-empty cell in current row (b)
 pub fn prev_word(app: &mut App) {
     use crate::domain::position::RowIndex;
-    use crate::input::StatusMessage;
 
-    let current_row = app.view_state.table_state.selected().unwrap_or(0);
-    let current_col = app.view_state.selected_column.get();
+    let current_row = app.get_selected_row().unwrap_or(RowIndex::new(0));
+    let visible = visible_columns(app);
+    let position = selected_visible_position(app, &visible);
 
-    if current_col == 0 {
-        app.status_message = Some(StatusMessage::from("Already at first column"));
+    if position == 0 {
+        app.status_messages.push("Already at first column");
         return;
     }
 
-    for col in (0..current_col).rev() {
-        let cell = app
-            .document
-            .get_cell(RowIndex::new(current_row), ColIndex::new(col));
+    for col in visible.into_iter().take(position).rev() {
+        let cell = app.document.get_cell(current_row, ColIndex::new(col));
         if !cell.is_empty() {
             app.view_state.selected_column = ColIndex::new(col);
             update_horizontal_scroll(app, col);
@@ -330,21 +732,19 @@ pub fn prev_word(app: &mut App) {
             return;
         }
     }
-    app.status_message = Some(StatusMessage::from("No previous non-empty cells"));
+    app.status_messages.push("No previous non-empty cells");
 }
 
 /// Move to last non-empty cell in current row (e)
 pub fn end_word(app: &mut App) {
     use crate::domain::position::RowIndex;
-    use crate::input::StatusMessage;
 
-    let current_row = app.view_state.table_state.selected().unwrap_or(0);
-    let max_col = app.document.column_count().saturating_sub(1);
+    let current_row = app.get_selected_row().unwrap_or(RowIndex::new(0));
+    let visible = visible_columns(app);
+    let max_col = visible.last().copied().unwrap_or(0);
 
-    for col in (0..=max_col).rev() {
-        let cell = app
-            .document
-            .get_cell(RowIndex::new(current_row), ColIndex::new(col));
+    for col in visible.into_iter().rev() {
+        let cell = app.document.get_cell(current_row, ColIndex::new(col));
         if !cell.is_empty() {
             app.view_state.selected_column = ColIndex::new(col);
             update_horizontal_scroll(app, col);
@@ -355,16 +755,309 @@ pub fn end_word(app: &mut App) {
     // All cells are empty, go to last column
     app.view_state.selected_column = ColIndex::new(max_col);
     update_horizontal_scroll(app, max_col);
-    app.status_message = Some(StatusMessage::from("All cells empty"));
+    app.status_messages.push("All cells empty");
+}
+
+/// Columns on screen for a scroll offset, measured against the last
+/// rendered table width
+fn columns_on_screen(app: &App, offset: usize) -> Vec<usize> {
+    let groups = &app.view_state.column_groups;
+    column_width::visible_columns(
+        offset,
+        &app.view_state.display_columns(app.document.column_count()),
+        groups,
+        app.view_state.frozen_columns,
+        app.view_state
+            .pinned_last_column(app.document.column_count()),
+        column_width::data_width(app.view_state.table_width),
+        |col| column_width::column_width(&app.document, &app.view_state, col, &app.options),
+    )
+}
+
+/// Helper to update horizontal scroll position so `target_col` is on screen
+pub fn update_horizontal_scroll(app: &mut App, target_col: usize) {
+    // Frozen and pinned columns are always on screen
+    let frozen = app.view_state.frozen_columns;
+    let pinned = app
+        .view_state
+        .pinned_last_column(app.document.column_count());
+    let target = app.view_state.column_position(target_col);
+    if target < frozen || pinned == Some(target_col) {
+        return;
+    }
+
+    let offset = app.view_state.column_scroll_offset.max(frozen);
+    if target < offset {
+        app.view_state.column_scroll_offset = target;
+        return;
+    }
+
+    let on_screen = columns_on_screen(app, offset);
+    let last_scrolling = on_screen.iter().rev().find(|&&col| Some(col) != pinned);
+    if last_scrolling.is_some_and(|&last| app.view_state.column_position(last) >= target) {
+        return;
+    }
+
+    if let Some(first) = offset_ending_at(app, target_col) {
+        app.view_state.column_scroll_offset = app.view_state.column_position(first);
+    }
+}
+
+/// Scroll offset that makes `target_col` the last scrolling column: fit
+/// columns backwards from it into the space the frozen and pinned columns leave
+fn offset_ending_at(app: &App, target_col: usize) -> Option<usize> {
+    let frozen = app.view_state.frozen_columns;
+    let pinned = app
+        .view_state
+        .pinned_last_column(app.document.column_count());
+    let width = |col| column_width::column_width(&app.document, &app.view_state, col, &app.options);
+    let position = |col| app.view_state.column_position(col);
+    let fixed_width: u16 = columns_on_screen(app, frozen)
+        .iter()
+        .filter(|&&col| position(col) < frozen || Some(col) == pinned)
+        .map(|&col| width(col) + 1)
+        .sum();
+    let available =
+        column_width::data_width(app.view_state.table_width).saturating_sub(fixed_width);
+    let target = position(target_col);
+    let scrolling = visible_columns(app)
+        .into_iter()
+        .filter(|&col| position(col) >= frozen && position(col) <= target)
+        .rev();
+    fit_columns(scrolling, width, available).last().copied()
+}
+
+/// Columns that scroll sideways (not frozen, pinned or collapsed away)
+fn scrolling_columns(app: &App) -> Vec<usize> {
+    let frozen = app.view_state.frozen_columns;
+    let pinned = app
+        .view_state
+        .pinned_last_column(app.document.column_count());
+    visible_columns(app)
+        .into_iter()
+        .filter(|&col| app.view_state.column_position(col) >= frozen && Some(col) != pinned)
+        .collect()
+}
+
+/// Number of scrolling columns in the current column window
+fn column_window(app: &App) -> usize {
+    let scrolling = scrolling_columns(app);
+    let offset = app
+        .view_state
+        .column_scroll_offset
+        .max(app.view_state.frozen_columns);
+    columns_on_screen(app, offset)
+        .iter()
+        .filter(|col| scrolling.contains(col))
+        .count()
+        .max(1)
+}
+
+/// Scroll the column window sideways by `columns`, moving the cursor along so
+/// it keeps its place in the window (like vim's zl/zh); at the last window the
+/// cursor still moves to the last column
+pub fn scroll_columns(app: &mut App, columns: usize, forward: bool) {
+    let scrolling = scrolling_columns(app);
+    let (Some(&first_col), Some(&last_col)) = (scrolling.first(), scrolling.last()) else {
+        return;
+    };
+    let position = |col| app.view_state.column_position(col);
+    let offset = app.view_state.column_scroll_offset.max(position(first_col));
+    let first = scrolling.partition_point(|&col| position(col) < offset);
+    let max_first = offset_ending_at(app, last_col).map_or(0, |col| {
+        scrolling.partition_point(|&c| position(c) < position(col))
+    });
+    let new_first = if forward {
+        (first + columns).min(max_first.max(first))
+    } else {
+        first.saturating_sub(columns)
+    };
+    app.view_state.column_scroll_offset = position(scrolling[new_first]);
+
+    // Frozen and pinned columns stay put; others move with the window
+    let selected = app.view_state.selected_column.get();
+    if let Some(position) = scrolling.iter().position(|&col| col == selected) {
+        let target = if forward {
+            (position + columns).min(scrolling.len() - 1)
+        } else {
+            position.saturating_sub(columns)
+        };
+        app.view_state.selected_column = ColIndex::new(scrolling[target]);
+        update_horizontal_scroll(app, scrolling[target]);
+    }
+    app.view_state.viewport_mode = ViewportMode::Auto;
+}
+
+/// Scroll sideways by `pages` column windows, or half windows (Alt+l/Alt+h,
+/// zL/zH)
+pub fn scroll_column_pages(app: &mut App, pages: usize, forward: bool, half: bool) {
+    let window = column_window(app);
+    let step = if half { (window / 2).max(1) } else { window };
+    scroll_columns(app, step.saturating_mul(pages), forward);
+}
+
+/// Move by `pages` full screens of rows (Ctrl+f/Ctrl+b)
+pub fn scroll_full_pages(app: &mut App, pages: usize, forward: bool) {
+    let rows = app.full_page_rows().saturating_mul(pages);
+    if forward {
+        move_down_by(app, rows);
+    } else {
+        move_up_by(app, rows);
+    }
+}
+
+/// Pin the first `count` columns on the left while scrolling (0 unpins).
+/// When not every column fits, one is left on screen for scrolling.
+pub fn freeze_columns(app: &mut App, count: usize) {
+    app.view_state.frozen_columns = 0;
+    let fitting = columns_on_screen(app, 0);
+    let pinned = app
+        .view_state
+        .pinned_last_column(app.document.column_count());
+    let cap = if fitting.len() == visible_columns(app).len() {
+        // Everything fits, so nothing needs to scroll
+        app.document.column_count()
+    } else {
+        fitting
+            .iter()
+            .rev()
+            .find(|&&col| Some(col) != pinned)
+            .map_or(0, |&col| app.view_state.column_position(col))
+    };
+    app.view_state.frozen_columns = count.min(app.document.column_count()).min(cap);
+    let selected = app.view_state.selected_column.get();
+    update_horizontal_scroll(app, selected);
+}
+
+/// Pin (or release) the last column at the right edge while scrolling
+pub fn freeze_last_column(app: &mut App, pinned: bool) {
+    app.view_state.frozen_last = pinned;
+    let selected = app.view_state.selected_column.get();
+    update_horizontal_scroll(app, selected);
+}
+
+/// Move the selected column `delta` places right (left when negative) in
+/// the display order; the file keeps its own order
+pub fn move_column(app: &mut App, delta: isize) {
+    let count = app.document.column_count();
+    if count == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let col = app.view_state.selected_column.get();
+    let position = app.view_state.move_column(col, delta, count);
+    update_horizontal_scroll(app, col);
+    let header = app
+        .document
+        .headers
+        .get(col)
+        .filter(|h| !h.is_empty())
+        .map(|h| format!(" {}", h))
+        .unwrap_or_default();
+    app.status_messages.push(format!(
+        "Column {}{} at position {} of {}",
+        crate::ui::column_to_excel_letter(col),
+        header,
+        position + 1,
+        count
+    ));
+}
+
+/// Grow (or shrink, with a negative `delta`) the selected column's width
+pub fn resize_column(app: &mut App, delta: i32) {
+    let col = app.view_state.selected_column.get();
+    if app.view_state.column_groups.collapsed_label(col).is_some() {
+        app.status_messages.push("Can't resize a collapsed group");
+        return;
+    }
+    let current = column_width::column_width(&app.document, &app.view_state, col, &app.options);
+    let width = (i32::from(current) + delta)
+        .clamp(i32::from(MIN_MANUAL_WIDTH), i32::from(MAX_COLUMN_WIDTH)) as u16;
+    app.view_state.column_widths.insert(col, width);
+    update_horizontal_scroll(app, col);
+    app.status_messages.push(format!(
+        "Column {} width {}",
+        crate::ui::column_to_excel_letter(col),
+        width
+    ));
+}
+
+/// `zr`: resize the selected column with `h`/`l` until Enter keeps the
+/// width or Esc goes back to the one before
+pub fn start_column_resize(app: &mut App) {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let col = app.view_state.selected_column.get();
+    if app.view_state.column_groups.collapsed_label(col).is_some() {
+        app.status_messages.push("Can't resize a collapsed group");
+        return;
+    }
+    let original = app.view_state.column_widths.get(&col).copied();
+    app.view_state.resizing = Some((col, original));
+    app.status_messages.push(format!(
+        "Resizing column {}: h/l narrow/widen, = automatic, Enter keeps, Esc reverts",
+        crate::ui::column_to_excel_letter(col)
+    ));
+}
+
+/// Leave `zr` resizing, keeping the new width or restoring the old one
+pub fn finish_column_resize(app: &mut App, keep: bool) {
+    let Some((col, original)) = app.view_state.resizing.take() else {
+        return;
+    };
+    if !keep {
+        match original {
+            Some(width) => app.view_state.column_widths.insert(col, width),
+            None => app.view_state.column_widths.remove(&col),
+        };
+        update_horizontal_scroll(app, col);
+    }
+    let width = match app.view_state.column_widths.get(&col) {
+        Some(width) => width.to_string(),
+        None => "automatic".to_string(),
+    };
+    app.status_messages.push(format!(
+        "Column {} width {}{}",
+        crate::ui::column_to_excel_letter(col),
+        width,
+        if keep { "" } else { " (reverted)" }
+    ));
 }
 
-/// Helper to update horizontal scroll position
-fn update_horizontal_scroll(app: &mut App, target_col: usize) {
-    if target_col < app.view_state.column_scroll_offset {
-        app.view_state.column_scroll_offset = target_col;
-    } else if target_col >= app.view_state.column_scroll_offset + MAX_VISIBLE_COLS {
-        app.view_state.column_scroll_offset = target_col - MAX_VISIBLE_COLS + 1;
+/// Go back to the automatic width for the selected column
+pub fn reset_column_width(app: &mut App) {
+    let col = app.view_state.selected_column.get();
+    app.view_state.column_widths.remove(&col);
+    update_horizontal_scroll(app, col);
+    app.status_messages.push(format!(
+        "Column {} width automatic",
+        crate::ui::column_to_excel_letter(col)
+    ));
+}
+
+/// Fit the selected column to its widest cell, measuring every row instead
+/// of the sample the automatic width uses
+pub fn fit_column_width(app: &mut App) {
+    if app.document.column_count() == 0 {
+        app.status_messages.push(messages::NO_COLUMNS);
+        return;
+    }
+    let col = app.view_state.selected_column.get();
+    if app.view_state.column_groups.collapsed_label(col).is_some() {
+        app.status_messages.push("Can't resize a collapsed group");
+        return;
     }
+    let width = column_width::exact_column_width(&app.document, col);
+    app.view_state.column_widths.insert(col, width);
+    update_horizontal_scroll(app, col);
+    app.status_messages.push(format!(
+        "Column {} width {} (fits all {} rows, = for automatic)",
+        crate::ui::column_to_excel_letter(col),
+        width,
+        app.document.row_count()
+    ));
 }
 
 #[cfg(test)]
@@ -391,12 +1084,47 @@ mod tests {
             },
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
 
         let csv_files = vec![PathBuf::from("test.csv")];
         App::new(document, csv_files, 0, crate::session::FileConfig::new())
     }
 
+    #[test]
+    fn test_goto_column_by_name() {
+        let mut app = create_test_app();
+        app.document.headers = vec![
+            "id".to_string(),
+            "customer_name".to_string(),
+            "customer_ltv".to_string(),
+        ];
+
+        goto_column_by_name(&mut app, "ltv");
+        assert_eq!(app.view_state.selected_column, ColIndex::new(2));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Jumped to column customer_ltv (C)"
+        );
+
+        // An exact name wins; a fuzzy one reports the other matches
+        goto_column_by_name(&mut app, "ID");
+        assert_eq!(app.view_state.selected_column, ColIndex::new(0));
+        goto_column_by_name(&mut app, "cust");
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Jumped to column customer_name (B); 1 other column match"
+        );
+
+        goto_column_by_name(&mut app, "zzz");
+        assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "No column matches zzz"
+        );
+    }
+
     #[test]
     fn test_goto_first_row() {
         let mut app = create_test_app();
@@ -441,8 +1169,8 @@ mod tests {
         // Position should not change when out of bounds
         assert_eq!(app.view_state.table_state.selected(), initial_row);
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("does not exist"));
     }
 
@@ -456,8 +1184,8 @@ mod tests {
         // Row 0 is invalid (1-indexed), position should not change
         assert_eq!(app.view_state.table_state.selected(), initial_row);
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("must be >= 1"));
     }
 
@@ -567,6 +1295,21 @@ mod tests {
         assert_eq!(app.view_state.table_state.selected(), Some(0));
     }
 
+    #[test]
+    fn test_half_page_follows_table_height() {
+        let mut app = create_test_app();
+        app.options.page = crate::app::PageSize::Half;
+        app.view_state.table_height = 16;
+        app.view_state.table_state.select(Some(0));
+
+        select_next_page(&mut app);
+        assert_eq!(app.view_state.table_state.selected(), Some(8));
+
+        app.options.page = crate::app::PageSize::Rows(5);
+        select_previous_page(&mut app);
+        assert_eq!(app.view_state.table_state.selected(), Some(3));
+    }
+
     #[test]
     fn test_page_down_at_end() {
         let mut app = create_test_app();
@@ -624,8 +1367,8 @@ mod tests {
         // Position should not change when out of bounds
         assert_eq!(app.view_state.selected_column, initial_col);
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("does not exist"));
     }
 
@@ -640,7 +1383,7 @@ mod tests {
         // Should stay at same position
         assert_eq!(app.view_state.selected_column, initial_col);
         // Should have error message
-        assert!(app.status_message.is_some());
+        assert!(!app.status_messages.is_empty());
     }
 
     #[test]
@@ -675,6 +1418,192 @@ mod tests {
         assert_eq!(app.view_state.selected_column, ColIndex::new(27));
     }
 
+    #[test]
+    fn test_scroll_window_follows_table_width() {
+        let csv_data = create_large_csv_data(3, 50);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            FileConfig::new(),
+        );
+
+        // A 200-wide table fits 21 columns of 8 (+1 spacing)
+        app.view_state.table_width = 200;
+        move_right_by(&mut app, 20);
+        assert_eq!(app.view_state.column_scroll_offset, 0);
+        move_right_by(&mut app, 1);
+        assert_eq!(app.view_state.column_scroll_offset, 1);
+
+        // On a 40-wide table three columns fit; `$` puts the last at the right edge
+        app.view_state.table_width = 40;
+        app.view_state.column_scroll_offset = 0;
+        handle_navigation(&mut app, KeyCode::Char('$')).unwrap();
+        assert_eq!(app.view_state.column_scroll_offset, 47);
+
+        // Wider content takes more room, so fewer columns fit
+        app.document.rows[0][48] = "x".repeat(20);
+        goto_column(&mut app, "A");
+        handle_navigation(&mut app, KeyCode::Char('$')).unwrap();
+        assert_eq!(app.view_state.column_scroll_offset, 48);
+    }
+
+    #[test]
+    fn test_column_pages_move_window_and_cursor() {
+        let csv_data = create_large_csv_data(3, 50);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            FileConfig::new(),
+        );
+        // Ten columns of 8 (+1 spacing) fit in a 100-wide table
+        app.view_state.table_width = 100;
+        goto_column(&mut app, "C");
+
+        scroll_column_pages(&mut app, 1, true, false);
+        assert_eq!(app.view_state.column_scroll_offset, 10);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(12));
+
+        // The window stops at the last columns; the cursor goes to the end
+        scroll_column_pages(&mut app, 5, true, false);
+        assert_eq!(app.view_state.column_scroll_offset, 40);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(49));
+
+        // Half a window back
+        scroll_column_pages(&mut app, 1, false, true);
+        assert_eq!(app.view_state.column_scroll_offset, 35);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(44));
+
+        scroll_columns(&mut app, 100, false);
+        assert_eq!(app.view_state.column_scroll_offset, 0);
+        assert_eq!(app.view_state.selected_column, ColIndex::new(0));
+    }
+
+    #[test]
+    fn test_full_pages_follow_table_height() {
+        let mut app = create_test_app();
+        app.view_state.table_state.select(Some(0));
+        app.view_state.table_height = 12;
+
+        // A full page keeps two rows of context
+        scroll_full_pages(&mut app, 2, true);
+        assert_eq!(app.view_state.table_state.selected(), Some(20));
+        scroll_full_pages(&mut app, 1, false);
+        assert_eq!(app.view_state.table_state.selected(), Some(10));
+        scroll_full_pages(&mut app, 10, true);
+        assert_eq!(app.view_state.table_state.selected(), Some(49));
+    }
+
+    #[test]
+    fn test_resize_column_changes_scroll_window() {
+        let csv_data = create_large_csv_data(3, 50);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            FileConfig::new(),
+        );
+        app.view_state.table_width = 100;
+
+        // 4> grows column A by 8 cells, pushing column J off screen
+        app.input_state.command_count = std::num::NonZeroUsize::new(4);
+        handle_navigation(&mut app, KeyCode::Char('>')).unwrap();
+        assert_eq!(app.view_state.column_widths.get(&0), Some(&16));
+        move_right_by(&mut app, 9);
+        assert_eq!(app.view_state.column_scroll_offset, 1);
+
+        // Shrinking stops at the minimum, = goes back to automatic
+        goto_column(&mut app, "B");
+        app.input_state.command_count = std::num::NonZeroUsize::new(10);
+        handle_navigation(&mut app, KeyCode::Char('<')).unwrap();
+        assert_eq!(
+            app.view_state.column_widths.get(&1),
+            Some(&MIN_MANUAL_WIDTH)
+        );
+        handle_navigation(&mut app, KeyCode::Char('=')).unwrap();
+        assert!(!app.view_state.column_widths.contains_key(&1));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Column B width automatic"
+        );
+    }
+
+    #[test]
+    fn test_fit_column_width_measures_every_row() {
+        let mut app = create_test_app();
+        // One long cell among 50 is an outlier for the automatic width
+        app.document.rows[49][1] = "x".repeat(40);
+        goto_column(&mut app, "B");
+        assert_eq!(
+            column_width::column_width(&app.document, &app.view_state, 1, &app.options),
+            column_width::MIN_COLUMN_WIDTH
+        );
+
+        fit_column_width(&mut app);
+        assert_eq!(app.view_state.column_widths.get(&1), Some(&42));
+        assert_eq!(
+            app.status_messages.current().unwrap().as_str(),
+            "Column B width 42 (fits all 50 rows, = for automatic)"
+        );
+    }
+
+    #[test]
+    fn test_pinned_last_column_reduces_scroll_window() {
+        let csv_data = create_large_csv_data(3, 50);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            FileConfig::new(),
+        );
+        app.view_state.table_width = 100;
+        freeze_last_column(&mut app, true);
+
+        // Ten columns fit, one of them is the pinned last column
+        move_right_by(&mut app, 8);
+        assert_eq!(app.view_state.column_scroll_offset, 0);
+        move_right_by(&mut app, 1);
+        assert_eq!(app.view_state.column_scroll_offset, 1);
+
+        // `$` lands on the pinned column without scrolling
+        handle_navigation(&mut app, KeyCode::Char('$')).unwrap();
+        assert_eq!(app.view_state.selected_column, ColIndex::new(49));
+        assert_eq!(app.view_state.column_scroll_offset, 1);
+
+        // The column before it scrolls into view next to it
+        move_left_by(&mut app, 1);
+        assert_eq!(app.view_state.column_scroll_offset, 40);
+    }
+
+    #[test]
+    fn test_frozen_columns_reduce_scroll_window() {
+        let csv_data = create_large_csv_data(3, 50);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            FileConfig::new(),
+        );
+        // Ten 8-wide columns fit in a 100-wide table
+        app.view_state.table_width = 100;
+        freeze_columns(&mut app, 2);
+
+        // With 2 columns pinned, 8 scrolling columns fit; column 10 needs a scroll
+        move_right_by(&mut app, 9);
+        assert_eq!(app.view_state.column_scroll_offset, 0);
+        move_right_by(&mut app, 1);
+        assert_eq!(app.view_state.column_scroll_offset, 3);
+
+        // Jumping back into the frozen region doesn't scroll
+        goto_column(&mut app, "A");
+        assert_eq!(app.view_state.column_scroll_offset, 3);
+
+        // Frozen count is capped so something can still scroll
+        freeze_columns(&mut app, 40);
+        assert_eq!(app.view_state.frozen_columns, 9);
+    }
+
     #[test]
     fn test_goto_column_three_letters() {
         let csv_data = create_large_csv_data(3, 800); // 800 columns
@@ -701,8 +1630,8 @@ mod tests {
         // Position should not change when out of bounds
         assert_eq!(app.view_state.selected_column, initial_col);
         // Should show error message
-        assert!(app.status_message.is_some());
-        let msg = app.status_message.as_ref().unwrap().as_str();
+        assert!(!app.status_messages.is_empty());
+        let msg = app.status_messages.current().unwrap().as_str();
         assert!(msg.contains("does not exist"));
     }
 
@@ -723,6 +1652,7 @@ mod tests {
             ]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, FileConfig::new());
@@ -735,7 +1665,9 @@ mod tests {
 
         // Should stay at column 0 or show message (no more non-empty cells)
         // Current implementation may stay or move, verify it doesn't crash
-        assert!(app.status_message.is_some() || app.view_state.selected_column == ColIndex::new(0));
+        assert!(
+            !app.status_messages.is_empty() || app.view_state.selected_column == ColIndex::new(0)
+        );
     }
 
     #[test]
@@ -755,6 +1687,7 @@ mod tests {
             ]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, FileConfig::new());
@@ -766,7 +1699,9 @@ mod tests {
         prev_word(&mut app);
 
         // Should stay at column 3 or show message
-        assert!(app.status_message.is_some() || app.view_state.selected_column == ColIndex::new(3));
+        assert!(
+            !app.status_messages.is_empty() || app.view_state.selected_column == ColIndex::new(3)
+        );
     }
 
     #[test]
@@ -776,6 +1711,7 @@ mod tests {
             rows: vec![vec!["".to_string(), "value".to_string(), "".to_string()]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, FileConfig::new());
@@ -793,7 +1729,9 @@ mod tests {
         next_word(&mut app);
 
         // Should stay at column 1 (no more non-empty cells)
-        assert!(app.status_message.is_some() || app.view_state.selected_column == ColIndex::new(1));
+        assert!(
+            !app.status_messages.is_empty() || app.view_state.selected_column == ColIndex::new(1)
+        );
     }
 
     #[test]
@@ -815,6 +1753,7 @@ mod tests {
             ]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, FileConfig::new());
@@ -849,6 +1788,7 @@ mod tests {
             rows: rows_data,
             filename: "large.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         }
     }
 }
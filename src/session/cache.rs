@@ -0,0 +1,179 @@
+//! Cache of parsed documents for fast switching between files.
+//!
+//! Entries are keyed by path and the file's modification time, so a file
+//! that changed on disk since it was cached is parsed again.
+
+use crate::csv::Document;
+use crate::file_system::modified_time;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Number of parsed documents kept in memory by default
+pub const DEFAULT_CACHE_CAPACITY: usize = 8;
+
+#[derive(Debug)]
+struct CacheEntry {
+    path: PathBuf,
+    modified: SystemTime,
+    document: Document,
+}
+
+/// Least-recently-used cache of parsed documents
+#[derive(Debug)]
+pub struct DocumentCache {
+    /// Most recently used entry first
+    entries: VecDeque<CacheEntry>,
+    capacity: usize,
+}
+
+impl DocumentCache {
+    /// Create a cache holding at most `capacity` documents
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Store a parsed document for `path`, evicting the least recently used
+    /// entry when full. Documents of files that can't be stat'ed are dropped.
+    pub fn insert(&mut self, path: &Path, document: Document) {
+        self.remove(path);
+        if self.capacity == 0 {
+            return;
+        }
+        let Some(modified) = modified_time(path) else {
+            return;
+        };
+
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_back();
+        }
+        self.entries.push_front(CacheEntry {
+            path: path.to_path_buf(),
+            modified,
+            document,
+        });
+    }
+
+    /// Take the cached document for `path` if the file is unchanged on disk.
+    /// Stale entries are discarded.
+    pub fn take(&mut self, path: &Path) -> Option<Document> {
+        let entry = self.remove(path)?;
+        (modified_time(path) == Some(entry.modified)).then_some(entry.document)
+    }
+
+    /// Drop the cached document of `path`
+    pub fn discard(&mut self, path: &Path) {
+        self.remove(path);
+    }
+
+    /// Drop every cached document
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached documents
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check whether a document is cached for `path` (regardless of staleness)
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.iter().any(|e| e.path == path)
+    }
+
+    fn remove(&mut self, path: &Path) -> Option<CacheEntry> {
+        let index = self.entries.iter().position(|e| e.path == path)?;
+        self.entries.remove(index)
+    }
+}
+
+impl Default for DocumentCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn document(name: &str) -> Document {
+        Document {
+            filename: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn write_files(dir: &TempDir, names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                fs::write(&path, "a,b\n1,2\n").unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_take_returns_cached_document_once() {
+        let dir = TempDir::new().unwrap();
+        let paths = write_files(&dir, &["a.csv"]);
+        let mut cache = DocumentCache::default();
+
+        cache.insert(&paths[0], document("a.csv"));
+        assert_eq!(cache.take(&paths[0]).unwrap().filename, "a.csv");
+        assert!(cache.take(&paths[0]).is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let paths = write_files(&dir, &["a.csv", "b.csv", "c.csv"]);
+        let mut cache = DocumentCache::new(2);
+
+        cache.insert(&paths[0], document("a"));
+        cache.insert(&paths[1], document("b"));
+        cache.insert(&paths[2], document("c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&paths[0]));
+        assert!(cache.contains(&paths[1]));
+        assert!(cache.contains(&paths[2]));
+    }
+
+    #[test]
+    fn test_modified_file_invalidates_entry() {
+        let dir = TempDir::new().unwrap();
+        let paths = write_files(&dir, &["a.csv"]);
+        let mut cache = DocumentCache::default();
+
+        cache.insert(&paths[0], document("a"));
+        let file = fs::File::options().write(true).open(&paths[0]).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+
+        assert!(cache.take(&paths[0]).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_missing_file_is_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = DocumentCache::default();
+
+        cache.insert(&dir.path().join("missing.csv"), document("missing"));
+        assert!(cache.is_empty());
+    }
+}
@@ -3,7 +3,32 @@
 //! This module handles file switching between multiple CSV files and
 //! maintains the configuration settings for parsing CSV files.
 
-use std::path::PathBuf;
+pub mod cache;
+pub mod positions;
+pub mod resume;
+pub mod share;
+pub mod snapshot;
+mod state_file;
+pub mod watch;
+
+pub use cache::DocumentCache;
+pub use positions::{CellPosition, PositionStore};
+pub use resume::ResumeStore;
+pub use share::{Follower, Leader, SharedView};
+pub use snapshot::{FileSetup, SessionSnapshot};
+pub use watch::{FileTail, TailEvent};
+
+use crate::app::Change;
+use crate::csv::{Document, Preamble};
+use crate::file_system::modified_time;
+use crate::ui::ViewState;
+use anyhow::Result;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// Configuration for CSV file parsing
 #[derive(Debug, Clone)]
@@ -16,6 +41,9 @@ pub struct FileConfig {
 
     /// Character encoding for file loading
     pub encoding: Option<String>,
+
+    /// Lines before the header to keep out of the table
+    pub preamble: Preamble,
 }
 
 impl FileConfig {
@@ -25,6 +53,7 @@ impl FileConfig {
             delimiter: None,
             no_headers: false,
             encoding: None,
+            preamble: Preamble::default(),
         }
     }
 
@@ -34,8 +63,25 @@ impl FileConfig {
             delimiter,
             no_headers,
             encoding,
+            preamble: Preamble::default(),
         }
     }
+
+    /// The same settings, skipping `preamble` before the header
+    pub fn with_preamble(self, preamble: Preamble) -> Self {
+        Self { preamble, ..self }
+    }
+
+    /// Read a file with these settings
+    pub fn read_document(&self, path: &Path) -> Result<Document> {
+        Document::from_file_with(
+            path,
+            self.delimiter,
+            self.no_headers,
+            self.encoding.clone(),
+            self.preamble,
+        )
+    }
 }
 
 impl Default for FileConfig {
@@ -44,6 +90,51 @@ impl Default for FileConfig {
     }
 }
 
+/// Order of the session's files in the switcher (`:set fileorder=`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum FileOrder {
+    /// By path, ignoring case, so files of a directory stay together
+    Name,
+    /// Most recently modified first
+    Modified,
+    /// Largest first
+    Size,
+}
+
+impl FileOrder {
+    /// Parse an order name (`name`, `mtime` or `size`)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "name" => Ok(Self::Name),
+            "mtime" | "modified" => Ok(Self::Modified),
+            "size" => Ok(Self::Size),
+            _ => Err(format!(
+                "Invalid file order: {} (use name, mtime, size or none)",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FileOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Modified => write!(f, "mtime"),
+            Self::Size => write!(f, "size"),
+        }
+    }
+}
+
+impl TryFrom<String> for FileOrder {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        Self::parse(&value)
+    }
+}
+
 /// Manages multi-file session state
 #[derive(Debug)]
 pub struct Session {
@@ -53,18 +144,82 @@ pub struct Session {
     /// Index of the currently active file
     active_file_index: usize,
 
-    /// Configuration for CSV parsing
+    /// Configuration for CSV parsing of files without their own
     config: FileConfig,
+
+    /// Parsing settings of files read differently from the others
+    /// (`:delimiter`)
+    file_configs: HashMap<PathBuf, FileConfig>,
+
+    /// Saved cursor/scroll state of files that were switched away from
+    view_states: HashMap<PathBuf, ViewState>,
+
+    /// Setups from a saved session (`--session`) of files not shown yet
+    setups: HashMap<PathBuf, FileSetup>,
+
+    /// Changes written to files while they were not shown (`:replaceall`),
+    /// undoable once they are
+    pending_changes: HashMap<PathBuf, Change>,
+
+    /// Parsed documents of files that were switched away from
+    documents: DocumentCache,
+
+    /// Edit and close positions of all files, kept between sessions
+    positions: PositionStore,
+
+    /// Marks set with `m<letter>` in each file, plus `'` for where the
+    /// cursor was before the last jump
+    marks: HashMap<PathBuf, HashMap<char, CellPosition>>,
+
+    /// Where `positions` is saved (None keeps them in memory only)
+    positions_path: Option<PathBuf>,
+
+    /// Review resume points of all files (`:resume-point`)
+    resume: ResumeStore,
+
+    /// Where `resume` is saved (None keeps them in memory only)
+    resume_path: Option<PathBuf>,
+
+    /// Files whose resume point was already offered this session
+    resume_offered: HashSet<PathBuf>,
+
+    /// Extensions of the files the file browser lists (`--extensions`)
+    extensions: Vec<String>,
+
+    /// Modification time of the active file when it was last read or written
+    loaded_modified: Option<SystemTime>,
+
+    /// The active file was modified by another program since it was read
+    changed_on_disk: bool,
 }
 
 impl Session {
     /// Create a new session
     pub fn new(files: Vec<PathBuf>, active_file_index: usize, config: FileConfig) -> Self {
-        Self {
+        let mut session = Self {
             files,
             active_file_index,
             config,
-        }
+            file_configs: HashMap::new(),
+            view_states: HashMap::new(),
+            setups: HashMap::new(),
+            pending_changes: HashMap::new(),
+            documents: DocumentCache::default(),
+            positions: PositionStore::default(),
+            marks: HashMap::new(),
+            positions_path: None,
+            resume: ResumeStore::default(),
+            resume_path: None,
+            resume_offered: HashSet::new(),
+            extensions: crate::file_system::DEFAULT_EXTENSIONS
+                .iter()
+                .map(|e| e.to_string())
+                .collect(),
+            loaded_modified: None,
+            changed_on_disk: false,
+        };
+        session.mark_file_loaded();
+        session
     }
 
     /// Get the currently active file path
@@ -87,11 +242,80 @@ impl Session {
         &self.files
     }
 
-    /// Get the file configuration
+    /// Parsing settings of the active file
     pub fn config(&self) -> &FileConfig {
+        self.config_for(self.get_current_file())
+    }
+
+    /// Parsing settings of `path`: its own, else the session's
+    pub fn config_for(&self, path: &Path) -> &FileConfig {
+        self.file_configs.get(path).unwrap_or(&self.config)
+    }
+
+    /// Parsing settings of files without their own
+    pub fn default_config(&self) -> &FileConfig {
         &self.config
     }
 
+    /// The settings `path` is read with, if they differ from the session's
+    pub fn file_config(&self, path: &Path) -> Option<&FileConfig> {
+        self.file_configs.get(path)
+    }
+
+    /// Read `path` with its own settings (None: the session's). A parsed
+    /// copy of it is dropped.
+    pub fn set_file_config(&mut self, path: &Path, config: Option<FileConfig>) {
+        match config {
+            Some(config) => self.file_configs.insert(path.to_path_buf(), config),
+            None => self.file_configs.remove(path),
+        };
+        self.documents.discard(path);
+    }
+
+    /// Read (and write) the first line of each file as data or as headers
+    /// (`:set noheaders`). Other files are read again when shown, so what
+    /// was kept of them by row goes: cached documents, views and the
+    /// undo of `:replaceall` writes.
+    pub fn set_no_headers(&mut self, no_headers: bool) {
+        if self.config.no_headers == no_headers {
+            return;
+        }
+        self.config.no_headers = no_headers;
+        for config in self.file_configs.values_mut() {
+            config.no_headers = no_headers;
+        }
+        let current = self.get_current_file().clone();
+        self.documents.clear();
+        self.view_states.retain(|path, _| *path == current);
+        self.pending_changes.clear();
+    }
+
+    /// Extensions of the files the file browser lists
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// Set the extensions the file browser lists (those given to
+    /// `--extensions`)
+    pub fn set_extensions(&mut self, extensions: Vec<String>) {
+        self.extensions = extensions;
+    }
+
+    /// Index of the file `path` names, if it is in the session
+    pub fn file_index(&self, path: &Path) -> Option<usize> {
+        self.files.iter().position(|file| same_file(file, path))
+    }
+
+    /// Add a file to the end of the list (`:e path`), returning its index;
+    /// a file already in the session is not added again
+    pub fn add_file(&mut self, path: PathBuf) -> usize {
+        if let Some(index) = self.file_index(&path) {
+            return index;
+        }
+        self.files.push(path);
+        self.files.len() - 1
+    }
+
     /// Switch to the next file in the list (wraps around)
     /// Returns true if the file changed, false otherwise
     pub fn next_file(&mut self) -> bool {
@@ -103,6 +327,47 @@ impl Session {
         true
     }
 
+    /// Switch to the file at `index`
+    /// Returns true if the file changed, false otherwise
+    pub fn select_file(&mut self, index: usize) -> bool {
+        if index >= self.files.len() || index == self.active_file_index {
+            return false;
+        }
+
+        self.active_file_index = index;
+        true
+    }
+
+    /// Put the files in `order`, keeping the active file active. Returns
+    /// the new index of each file by its old one, or None if nothing moved.
+    /// Files that can't be stat'ed go last.
+    pub fn sort_files(&mut self, order: FileOrder) -> Option<Vec<usize>> {
+        let mut indices: Vec<usize> = (0..self.files.len()).collect();
+        let metadata = |i: &usize| std::fs::metadata(&self.files[*i]).ok();
+        match order {
+            FileOrder::Name => {
+                indices.sort_by_cached_key(|&i| self.files[i].to_string_lossy().to_lowercase())
+            }
+            FileOrder::Modified => indices.sort_by_cached_key(|i| {
+                Reverse(metadata(i).and_then(|metadata| metadata.modified().ok()))
+            }),
+            FileOrder::Size => {
+                indices.sort_by_cached_key(|i| Reverse(metadata(i).map(|metadata| metadata.len())))
+            }
+        }
+        if indices.iter().enumerate().all(|(new, &old)| new == old) {
+            return None;
+        }
+
+        let mut new_index = vec![0; indices.len()];
+        for (new, &old) in indices.iter().enumerate() {
+            new_index[old] = new;
+        }
+        self.files = indices.iter().map(|&i| self.files[i].clone()).collect();
+        self.active_file_index = new_index[self.active_file_index];
+        Some(new_index)
+    }
+
     /// Switch to the previous file in the list (wraps around)
     /// Returns true if the file changed, false otherwise
     pub fn prev_file(&mut self) -> bool {
@@ -118,12 +383,249 @@ impl Session {
         true
     }
 
+    /// Check whether `path` names the active file
+    pub fn is_current_file(&self, path: &Path) -> bool {
+        same_file(self.get_current_file(), path)
+    }
+
+    /// Make `path` the active file after the document was written there
+    /// (`:w other.csv`), adding it to the file list if it is new. Saved
+    /// state of a file that was overwritten is dropped.
+    pub fn set_current_file(&mut self, path: PathBuf) {
+        self.view_states.remove(&path);
+        self.documents.take(&path);
+        match self.files.iter().position(|f| same_file(f, &path)) {
+            Some(index) => self.active_file_index = index,
+            None => {
+                self.files.push(path);
+                self.active_file_index = self.files.len() - 1;
+            }
+        }
+    }
+
+    /// Remember the view state of the active file (call before switching away)
+    pub fn save_view_state(&mut self, view_state: ViewState) {
+        let path = self.get_current_file().clone();
+        self.view_states.insert(path, view_state);
+    }
+
+    /// Take the saved view state of the active file, if it was viewed before
+    pub fn take_view_state(&mut self) -> Option<ViewState> {
+        let path = self.get_current_file().clone();
+        self.view_states.remove(&path)
+    }
+
+    /// Saved view state of a file that was switched away from
+    pub fn view_state(&self, path: &Path) -> Option<&ViewState> {
+        self.view_states.get(path)
+    }
+
+    /// Set a file up from a saved session when it is next shown
+    pub fn set_setup(&mut self, setup: FileSetup) {
+        self.setups.insert(setup.path.clone(), setup);
+    }
+
+    /// Saved-session setup of a file not shown yet
+    pub fn setup(&self, path: &Path) -> Option<&FileSetup> {
+        self.setups.get(path)
+    }
+
+    /// Take the saved-session setup of the active file, if it has one
+    pub fn take_setup(&mut self) -> Option<FileSetup> {
+        let path = self.get_current_file().clone();
+        self.setups.remove(&path)
+    }
+
+    /// Make a change written to a file that isn't shown undoable once it is
+    pub fn set_pending_change(&mut self, path: &Path, change: Change) {
+        self.documents.take(path);
+        self.pending_changes.insert(path.to_path_buf(), change);
+    }
+
+    /// Take the change written to the active file while it wasn't shown
+    pub fn take_pending_change(&mut self) -> Option<Change> {
+        let path = self.get_current_file().clone();
+        self.pending_changes.remove(&path)
+    }
+
+    /// Keep the active file's parsed document for a later switch back.
+    /// Documents with unsaved changes are not cached, nor is the empty
+    /// placeholder left behind when loading a file failed.
+    pub fn cache_document(&mut self, document: Document) {
+        if document.is_dirty || document.filename.is_empty() {
+            return;
+        }
+        let path = self.get_current_file().clone();
+        self.documents.insert(&path, document);
+    }
+
+    /// Take the cached document of the active file if it is unchanged on disk
+    pub fn take_cached_document(&mut self) -> Option<Document> {
+        let path = self.get_current_file().clone();
+        self.documents.take(&path)
+    }
+
+    /// Note that the active file on disk matches the open document (after
+    /// reading or writing it)
+    pub fn mark_file_loaded(&mut self) {
+        self.loaded_modified = self
+            .files
+            .get(self.active_file_index)
+            .and_then(|path| modified_time(path));
+        self.changed_on_disk = false;
+    }
+
+    /// Check whether another program modified the active file since it was
+    /// read; true only the first time the change is seen
+    pub fn poll_file_changed(&mut self) -> bool {
+        if self.changed_on_disk || self.loaded_modified.is_none() {
+            return false;
+        }
+        let modified = modified_time(self.get_current_file());
+        self.changed_on_disk = modified.is_some() && modified != self.loaded_modified;
+        self.changed_on_disk
+    }
+
+    /// Whether the active file changed on disk since it was read
+    pub fn is_changed_on_disk(&self) -> bool {
+        self.changed_on_disk
+    }
+
+    /// Load remembered positions from `path`, which is also where they are
+    /// saved on exit
+    pub fn load_positions(&mut self, path: PathBuf) -> Result<()> {
+        let loaded = PositionStore::load_from(&path);
+        self.positions_path = Some(path);
+        self.positions = loaded?;
+        Ok(())
+    }
+
+    /// Save remembered positions (nothing to do if they were never loaded)
+    pub fn save_positions(&self) -> Result<()> {
+        match &self.positions_path {
+            Some(path) => self.positions.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Remember an edit in the active file
+    pub fn record_edit(&mut self, position: CellPosition) {
+        let path = self.get_current_file().clone();
+        self.positions.record_edit(&path, position);
+    }
+
+    /// Set a mark in the active file
+    pub fn set_mark(&mut self, name: char, position: CellPosition) {
+        let path = self.get_current_file().clone();
+        self.marks.entry(path).or_default().insert(name, position);
+    }
+
+    /// Position of a mark in the active file
+    pub fn mark(&self, name: char) -> Option<CellPosition> {
+        self.marks
+            .get(self.get_current_file())
+            .and_then(|marks| marks.get(&name))
+            .copied()
+    }
+
+    /// Recent edits in the active file, newest first
+    pub fn edit_positions(&self) -> &[CellPosition] {
+        self.positions
+            .get(self.get_current_file())
+            .map_or(&[], |file| file.edits.as_slice())
+    }
+
+    /// Remember where the cursor is as the active file is closed
+    pub fn set_last_position(&mut self, position: CellPosition) {
+        let path = self.get_current_file().clone();
+        self.positions.set_last(&path, position);
+    }
+
+    /// Cursor position when the active file was last closed
+    pub fn last_position(&self) -> Option<CellPosition> {
+        self.positions
+            .get(self.get_current_file())
+            .and_then(|file| file.last)
+    }
+
+    /// Replace the bookmarked rows of the active file
+    pub fn set_bookmarks(&mut self, rows: Vec<usize>) {
+        let path = self.get_current_file().clone();
+        self.positions.set_bookmarks(&path, rows);
+    }
+
+    /// Bookmarked rows of the active file, top to bottom
+    pub fn bookmarks(&self) -> &[usize] {
+        self.positions
+            .get(self.get_current_file())
+            .map_or(&[], |file| file.bookmarks.as_slice())
+    }
+
+    /// Load resume points from `path`, which is also where they are saved
+    pub fn load_resume_points(&mut self, path: PathBuf) -> Result<()> {
+        let loaded = ResumeStore::load_from(&path);
+        self.resume_path = Some(path);
+        self.resume = loaded?;
+        Ok(())
+    }
+
+    /// Save resume points (nothing to do if they were never loaded)
+    fn save_resume_points(&self) -> Result<()> {
+        match &self.resume_path {
+            Some(path) => self.resume.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Set the active file's resume point and save it
+    pub fn set_resume_point(&mut self, position: CellPosition) -> Result<()> {
+        let file = self.get_current_file().clone();
+        let hash = resume::content_hash(&file)?;
+        self.resume
+            .set(&positions::file_key(&file), &hash, position);
+        self.save_resume_points()
+    }
+
+    /// Remove the active file's resume point; returns false if it had none
+    pub fn clear_resume_point(&mut self) -> Result<bool> {
+        let file = self.get_current_file().clone();
+        let hash = resume::content_hash(&file)?;
+        let removed = self.resume.remove(&positions::file_key(&file), &hash);
+        self.save_resume_points()?;
+        Ok(removed)
+    }
+
+    /// Resume point of the active file
+    pub fn resume_point(&self) -> Option<CellPosition> {
+        let file = self.get_current_file();
+        let hash = resume::content_hash(file).ok()?;
+        self.resume.get(&positions::file_key(file), &hash)
+    }
+
+    /// Resume point of the active file the first time it is opened this
+    /// session, to offer jumping there
+    pub fn take_resume_offer(&mut self) -> Option<CellPosition> {
+        let file = self.get_current_file().clone();
+        if !self.resume_offered.insert(file) {
+            return None;
+        }
+        self.resume_point()
+    }
+
     /// Check if there are multiple files in the session
     pub fn has_multiple_files(&self) -> bool {
         self.files.len() > 1
     }
 }
 
+/// Compare paths by the file they name, falling back to the paths themselves
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +638,17 @@ mod tests {
         ]
     }
 
+    #[test]
+    fn test_marks_are_per_file() {
+        let mut session = Session::new(test_files(), 0, FileConfig::new());
+        session.set_mark('a', CellPosition { row: 4, col: 1 });
+        assert_eq!(session.mark('a'), Some(CellPosition { row: 4, col: 1 }));
+        session.next_file();
+        assert_eq!(session.mark('a'), None);
+        session.prev_file();
+        assert_eq!(session.mark('a'), Some(CellPosition { row: 4, col: 1 }));
+    }
+
     #[test]
     fn test_file_config_default() {
         let config = FileConfig::new();
@@ -180,6 +693,19 @@ mod tests {
         assert_eq!(session.active_file_index(), 0);
     }
 
+    #[test]
+    fn test_select_file() {
+        let mut session = Session::new(test_files(), 0, FileConfig::new());
+
+        assert!(session.select_file(2));
+        assert_eq!(session.active_file_index(), 2);
+
+        // Already active or out of range
+        assert!(!session.select_file(2));
+        assert!(!session.select_file(3));
+        assert_eq!(session.active_file_index(), 2);
+    }
+
     #[test]
     fn test_prev_file() {
         let files = test_files();
@@ -210,6 +736,20 @@ mod tests {
         assert_eq!(session.active_file_index(), 0);
     }
 
+    #[test]
+    fn test_set_current_file() {
+        let mut session = Session::new(test_files(), 0, FileConfig::new());
+
+        session.set_current_file(PathBuf::from("copy.csv"));
+        assert_eq!(session.file_count(), 4);
+        assert_eq!(session.get_current_file(), &PathBuf::from("copy.csv"));
+
+        // Writing over a file already in the list switches to it
+        session.set_current_file(PathBuf::from("file2.csv"));
+        assert_eq!(session.file_count(), 4);
+        assert_eq!(session.active_file_index(), 1);
+    }
+
     #[test]
     fn test_has_multiple_files() {
         let config = FileConfig::new();
@@ -220,4 +760,80 @@ mod tests {
         let multiple = Session::new(test_files(), 0, config);
         assert!(multiple.has_multiple_files());
     }
+
+    #[test]
+    fn test_view_state_saved_per_file() {
+        let mut session = Session::new(test_files(), 0, FileConfig::new());
+
+        let mut state = ViewState::default();
+        state.table_state.select(Some(7));
+        session.save_view_state(state);
+
+        session.next_file();
+        assert!(session.take_view_state().is_none());
+
+        session.prev_file();
+        let restored = session.take_view_state().unwrap();
+        assert_eq!(restored.table_state.selected(), Some(7));
+
+        // Taking consumes the saved state
+        assert!(session.take_view_state().is_none());
+    }
+
+    #[test]
+    fn test_sort_files_keeps_active_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        std::fs::write(path("b.csv"), "a\n1\n2\n3\n").unwrap();
+        std::fs::write(path("A.csv"), "a\n").unwrap();
+        std::fs::write(path("c.csv"), "a\n1\n").unwrap();
+        let old = SystemTime::now() - std::time::Duration::from_secs(3600);
+        for name in ["b.csv", "c.csv"] {
+            std::fs::File::options()
+                .write(true)
+                .open(path(name))
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+        let files = vec![path("b.csv"), path("A.csv"), path("c.csv")];
+        let mut session = Session::new(files, 2, FileConfig::new());
+
+        assert_eq!(session.sort_files(FileOrder::Name), Some(vec![1, 0, 2]));
+        assert_eq!(session.files()[0], path("A.csv"));
+        assert_eq!(session.get_current_file(), &path("c.csv"));
+        assert_eq!(session.sort_files(FileOrder::Name), None);
+
+        session.sort_files(FileOrder::Size);
+        assert_eq!(
+            session.files(),
+            &[path("b.csv"), path("c.csv"), path("A.csv")]
+        );
+        assert_eq!(session.active_file_index(), 1);
+
+        session.sort_files(FileOrder::Modified);
+        assert_eq!(session.files()[0], path("A.csv"));
+        assert_eq!(session.get_current_file(), &path("c.csv"));
+    }
+
+    #[test]
+    fn test_positions_kept_per_file() {
+        let mut session = Session::new(test_files(), 0, FileConfig::new());
+        session.record_edit(CellPosition { row: 4, col: 1 });
+        session.set_last_position(CellPosition { row: 9, col: 2 });
+
+        session.next_file();
+        assert!(session.edit_positions().is_empty());
+        assert_eq!(session.last_position(), None);
+
+        session.prev_file();
+        assert_eq!(session.edit_positions(), &[CellPosition { row: 4, col: 1 }]);
+        assert_eq!(
+            session.last_position(),
+            Some(CellPosition { row: 9, col: 2 })
+        );
+
+        // Never loaded from disk, so there is nothing to save
+        assert!(session.save_positions().is_ok());
+    }
 }
@@ -0,0 +1,147 @@
+//! Remembered cursor positions per file, kept between sessions.
+//!
+//! Each file keeps its most recent edit locations (newest first, for `g;`)
+//! the cell the cursor was on when the file was last closed (for `'"`) and
+//! its bookmarked rows (`mB`).
+//! The store is a small TOML file in the state directory, keyed by the
+//! file's canonical path.
+
+use super::state_file::{load_state_file, save_state_file};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Edit locations remembered per file
+pub const MAX_EDIT_POSITIONS: usize = 20;
+
+/// Name of the store in the state directory
+const POSITIONS_FILE: &str = "positions.toml";
+
+/// A cell in a document (0-based row and column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellPosition {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Positions remembered for one file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilePositions {
+    /// Recent edit locations, newest first
+    pub edits: Vec<CellPosition>,
+    /// Cursor position when the file was last closed
+    pub last: Option<CellPosition>,
+    /// Bookmarked rows, top to bottom
+    pub bookmarks: Vec<usize>,
+}
+
+/// Positions of all files, loaded from and saved to the state directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PositionStore {
+    files: BTreeMap<String, FilePositions>,
+}
+
+impl PositionStore {
+    /// Load the store from a file (missing file = empty store)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        Ok(load_state_file(path, "positions")?.unwrap_or_default())
+    }
+
+    /// Write the store to a file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_state_file(path, self, "positions")
+    }
+
+    /// Positions remembered for a file
+    pub fn get(&self, file: &Path) -> Option<&FilePositions> {
+        self.files.get(&file_key(file))
+    }
+
+    /// Remember an edit, moving an earlier edit of the same cell to the front
+    pub fn record_edit(&mut self, file: &Path, position: CellPosition) {
+        let edits = &mut self.files.entry(file_key(file)).or_default().edits;
+        edits.retain(|&p| p != position);
+        edits.insert(0, position);
+        edits.truncate(MAX_EDIT_POSITIONS);
+    }
+
+    /// Remember where the cursor was when a file was closed
+    pub fn set_last(&mut self, file: &Path, position: CellPosition) {
+        self.files.entry(file_key(file)).or_default().last = Some(position);
+    }
+
+    /// Replace the bookmarked rows of a file
+    pub fn set_bookmarks(&mut self, file: &Path, rows: Vec<usize>) {
+        let key = file_key(file);
+        if rows.is_empty() && !self.files.contains_key(&key) {
+            return;
+        }
+        self.files.entry(key).or_default().bookmarks = rows;
+    }
+}
+
+/// Location of the store in the state directory
+pub fn positions_path() -> Option<PathBuf> {
+    crate::config::state_dir().map(|dir| dir.join(POSITIONS_FILE))
+}
+
+/// Files are keyed by canonical path, so the same file opened through
+/// different relative paths shares its positions
+pub(super) fn file_key(file: &Path) -> String {
+    std::fs::canonicalize(file)
+        .unwrap_or_else(|_| file.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn at(row: usize, col: usize) -> CellPosition {
+        CellPosition { row, col }
+    }
+
+    #[test]
+    fn test_record_edit_newest_first_without_duplicates() {
+        let mut store = PositionStore::default();
+        let file = Path::new("data.csv");
+        store.record_edit(file, at(1, 0));
+        store.record_edit(file, at(5, 2));
+        store.record_edit(file, at(1, 0));
+
+        assert_eq!(store.get(file).unwrap().edits, vec![at(1, 0), at(5, 2)]);
+
+        for row in 0..MAX_EDIT_POSITIONS + 5 {
+            store.record_edit(file, at(row, 3));
+        }
+        assert_eq!(store.get(file).unwrap().edits.len(), MAX_EDIT_POSITIONS);
+    }
+
+    #[test]
+    fn test_store_round_trips_through_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state").join(POSITIONS_FILE);
+        let file = dir.path().join("data.csv");
+
+        let mut store = PositionStore::default();
+        store.record_edit(&file, at(3, 1));
+        store.set_last(&file, at(7, 2));
+        store.set_bookmarks(&file, vec![4, 120]);
+        store.set_bookmarks(&dir.path().join("other.csv"), Vec::new());
+        store.save_to(&path).unwrap();
+
+        let loaded = PositionStore::load_from(&path).unwrap();
+        assert_eq!(loaded, store);
+        assert_eq!(loaded.get(&file).unwrap().last, Some(at(7, 2)));
+        assert_eq!(loaded.get(&file).unwrap().bookmarks, vec![4, 120]);
+        assert!(loaded.get(&dir.path().join("other.csv")).is_none());
+
+        let missing = PositionStore::load_from(&dir.path().join("none.toml")).unwrap();
+        assert_eq!(missing, PositionStore::default());
+    }
+}
@@ -0,0 +1,139 @@
+//! Review resume points (`:resume-point set`): one explicit bookmark per
+//! file, offered when the file is opened again.
+//!
+//! Unlike the remembered close positions, a resume point only moves when
+//! set. It is found by a hash of the start of the file, so it survives the
+//! file being renamed or moved, and by path, so it survives edits.
+
+use super::state_file::{load_state_file, save_state_file};
+use super::CellPosition;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Name of the store in the state directory
+const RESUME_FILE: &str = "resume_points.toml";
+
+/// Bytes from the start of a file that identify it
+const HASHED_BYTES: u64 = 64 * 1024;
+
+/// Location of the store in the state directory
+pub fn resume_path() -> Option<PathBuf> {
+    crate::config::state_dir().map(|dir| dir.join(RESUME_FILE))
+}
+
+/// Identity of a file's content: FNV-1a of its first 64 KiB, as hex
+pub fn content_hash(file: &Path) -> Result<String> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(file)
+        .and_then(|f| f.take(HASHED_BYTES).read_to_end(&mut bytes))
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    Ok(format!("{:016x}", hash))
+}
+
+/// A file's resume point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumePoint {
+    pub hash: String,
+    pub path: String,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Resume points of all files, loaded from and saved to the state directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeStore {
+    points: Vec<ResumePoint>,
+}
+
+impl ResumeStore {
+    /// Load the store from a file (missing file = empty store)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        Ok(load_state_file(path, "resume points")?.unwrap_or_default())
+    }
+
+    /// Write the store to a file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_state_file(path, self, "resume points")
+    }
+
+    /// Index of a file's point: the same content first, else the same path
+    fn find(&self, file: &str, hash: &str) -> Option<usize> {
+        self.points
+            .iter()
+            .position(|p| p.hash == hash)
+            .or_else(|| self.points.iter().position(|p| p.path == file))
+    }
+
+    /// Resume point of a file
+    pub fn get(&self, file: &str, hash: &str) -> Option<CellPosition> {
+        self.find(file, hash).map(|i| CellPosition {
+            row: self.points[i].row,
+            col: self.points[i].col,
+        })
+    }
+
+    /// Set a file's resume point, replacing its earlier one
+    pub fn set(&mut self, file: &str, hash: &str, position: CellPosition) {
+        self.remove(file, hash);
+        self.points.push(ResumePoint {
+            hash: hash.to_string(),
+            path: file.to_string(),
+            row: position.row,
+            col: position.col,
+        });
+    }
+
+    /// Remove a file's resume point; returns false if it had none
+    pub fn remove(&mut self, file: &str, hash: &str) -> bool {
+        match self.find(file, hash) {
+            Some(i) => {
+                self.points.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_found_by_content_after_rename_and_by_path_after_edit() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("review.csv");
+        std::fs::write(&file, "id\n1\n2\n").unwrap();
+        let hash = content_hash(&file).unwrap();
+        let mut store = ResumeStore::default();
+        store.set("/data/review.csv", &hash, CellPosition { row: 45, col: 2 });
+
+        let renamed = dir.path().join("renamed.csv");
+        std::fs::rename(&file, &renamed).unwrap();
+        let same = content_hash(&renamed).unwrap();
+        assert_eq!(same, hash);
+        assert_eq!(
+            store.get("/data/renamed.csv", &same),
+            Some(CellPosition { row: 45, col: 2 })
+        );
+        assert_eq!(
+            store.get("/data/review.csv", "0000000000000000"),
+            Some(CellPosition { row: 45, col: 2 })
+        );
+        assert_eq!(store.get("/data/other.csv", "0000000000000000"), None);
+
+        let path = dir.path().join("state").join(RESUME_FILE);
+        store.save_to(&path).unwrap();
+        let mut loaded = ResumeStore::load_from(&path).unwrap();
+        assert_eq!(loaded, store);
+        assert!(loaded.remove("/data/review.csv", &hash));
+        assert!(!loaded.remove("/data/review.csv", &hash));
+    }
+}
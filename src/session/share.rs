@@ -0,0 +1,303 @@
+//! Share the view with read-only followers over a Unix socket (`:share`,
+//! `lazycsv --follow <socket>`).
+//!
+//! The leader writes its file, row filter and cursor as one JSON line
+//! whenever they change; a follower applies the latest line it has read.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use platform::{Listener, Stream};
+
+/// How long a follower waits for the leader's first view
+pub const FOLLOW_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What a follower mirrors of the leader
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedView {
+    pub file: PathBuf,
+    pub row: usize,
+    pub column: usize,
+    /// Row filter as (column, value)
+    pub filter: Option<(usize, String)>,
+    /// The filter's value is a search pattern (`:filter col~pattern`)
+    #[serde(default)]
+    pub filter_pattern: bool,
+}
+
+/// Default socket of this process (`:share` without a path)
+pub fn default_socket_path() -> Option<PathBuf> {
+    crate::config::state_dir().map(|dir| dir.join(format!("share-{}.sock", std::process::id())))
+}
+
+/// The sharing side: accepts followers and sends them the view
+#[derive(Debug)]
+pub struct Leader {
+    listener: Listener,
+    path: PathBuf,
+    followers: Vec<Stream>,
+    last: Option<SharedView>,
+}
+
+impl Leader {
+    /// Listen on `path`, replacing a socket left behind by a leader that
+    /// is gone
+    pub fn bind(path: &Path) -> Result<Self> {
+        if path.exists() {
+            if Stream::connect(path).is_ok() {
+                bail!("{} is already shared by another lazycsv", path.display());
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let listener = Listener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+            followers: Vec::new(),
+            last: None,
+        })
+    }
+
+    /// Socket followers connect to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of connected followers
+    pub fn follower_count(&self) -> usize {
+        self.followers.len()
+    }
+
+    /// Accept new followers and send `view` to them, and to everyone if it
+    /// changed. Followers that can't be written to are dropped. Returns true
+    /// if the number of followers changed.
+    pub fn publish(&mut self, view: &SharedView) -> bool {
+        let before = self.followers.len();
+        let line = match serde_json::to_string(view) {
+            Ok(json) => json + "\n",
+            Err(_) => return false,
+        };
+
+        if self.last.as_ref() != Some(view) {
+            self.followers
+                .retain_mut(|follower| follower.write_all(line.as_bytes()).is_ok());
+            self.last = Some(view.clone());
+        }
+        // Never block on a follower that stopped reading
+        while let Ok((mut follower, _)) = self.listener.accept() {
+            if follower.set_nonblocking(true).is_ok() && follower.write_all(line.as_bytes()).is_ok()
+            {
+                self.followers.push(follower);
+            }
+        }
+        self.followers.len() != before
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The following side: reads the leader's views
+#[derive(Debug)]
+pub struct Follower {
+    stream: Stream,
+    /// Bytes of a line not fully read yet
+    buffer: Vec<u8>,
+}
+
+impl Follower {
+    /// Connect to a leader's socket
+    pub fn connect(path: &Path) -> Result<Self> {
+        let stream = Stream::connect(path)
+            .with_context(|| format!("Nothing is shared on {}", path.display()))?;
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            stream,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Wait for the view the leader sends on connect
+    pub fn first_view(&mut self, timeout: Duration) -> Result<SharedView> {
+        self.stream.set_nonblocking(false)?;
+        self.stream.set_read_timeout(Some(timeout))?;
+        let mut chunk = [0; 4096];
+        let view = loop {
+            if let Some(view) = self.take_latest()? {
+                break view;
+            }
+            match self.stream.read(&mut chunk) {
+                Ok(0) => bail!("The leader closed the connection"),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) => return Err(e).context("No view from the leader"),
+            }
+        };
+        self.stream.set_nonblocking(true)?;
+        Ok(view)
+    }
+
+    /// The latest view sent since the last poll; an error once the leader
+    /// is gone
+    pub fn poll(&mut self) -> Result<Option<SharedView>> {
+        let mut chunk = [0; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => bail!("The leader stopped sharing"),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e).context("Lost the connection to the leader"),
+            }
+        }
+        self.take_latest()
+    }
+
+    /// Parse the complete lines in the buffer, keeping the last view
+    fn take_latest(&mut self) -> Result<Option<SharedView>> {
+        let Some(end) = self.buffer.iter().rposition(|&b| b == b'\n') else {
+            return Ok(None);
+        };
+        let rest = self.buffer.split_off(end + 1);
+        let lines = std::mem::replace(&mut self.buffer, rest);
+        let last = lines.split(|&b| b == b'\n').rfind(|line| !line.is_empty());
+        match last {
+            Some(line) => Ok(Some(
+                serde_json::from_slice(line).context("Unreadable view from the leader")?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    pub use std::os::unix::net::{UnixListener as Listener, UnixStream as Stream};
+}
+
+/// Unix sockets only: elsewhere sharing reports that it is unsupported
+#[cfg(not(unix))]
+mod platform {
+    use std::io::{Error, ErrorKind, Read, Result, Write};
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn unsupported() -> Error {
+        Error::new(ErrorKind::Unsupported, "sharing needs Unix domain sockets")
+    }
+
+    #[derive(Debug)]
+    pub enum Listener {}
+
+    impl Listener {
+        pub fn bind(_path: &Path) -> Result<Self> {
+            Err(unsupported())
+        }
+
+        pub fn set_nonblocking(&self, _nonblocking: bool) -> Result<()> {
+            match *self {}
+        }
+
+        pub fn accept(&self) -> Result<(Stream, ())> {
+            match *self {}
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Stream {}
+
+    impl Stream {
+        pub fn connect(_path: &Path) -> Result<Self> {
+            Err(unsupported())
+        }
+
+        pub fn set_nonblocking(&self, _nonblocking: bool) -> Result<()> {
+            match *self {}
+        }
+
+        pub fn set_read_timeout(&self, _timeout: Option<Duration>) -> Result<()> {
+            match *self {}
+        }
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, _buf: &mut [u8]) -> Result<usize> {
+            match *self {}
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+            match *self {}
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn view(row: usize) -> SharedView {
+        SharedView {
+            file: PathBuf::from("data.csv"),
+            row,
+            column: 1,
+            filter: Some((0, "Oslo".to_string())),
+            filter_pattern: false,
+        }
+    }
+
+    #[test]
+    fn test_follower_gets_latest_view() {
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("share.sock");
+        let mut leader = Leader::bind(&socket).unwrap();
+        let mut follower = Follower::connect(&socket).unwrap();
+
+        assert!(leader.publish(&view(0)));
+        assert_eq!(leader.follower_count(), 1);
+        assert_eq!(follower.first_view(FOLLOW_TIMEOUT).unwrap(), view(0));
+        assert_eq!(follower.poll().unwrap(), None);
+
+        // Unchanged views are not sent again; the newest of several wins
+        assert!(!leader.publish(&view(0)));
+        leader.publish(&view(1));
+        leader.publish(&view(2));
+        assert_eq!(follower.poll().unwrap(), Some(view(2)));
+
+        drop(leader);
+        assert!(follower.poll().is_err());
+        assert!(!socket.exists());
+    }
+
+    #[test]
+    fn test_stale_socket_is_replaced() {
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("share.sock");
+        let _leader = Leader::bind(&socket).unwrap();
+        assert!(Leader::bind(&socket).is_err());
+
+        // A socket file without a listener is left over from a crash
+        let stale = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        assert!(stale.exists());
+        assert!(Leader::bind(&stale).is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+//! Saved review setups (`:session save review.lazysession`, restored with
+//! `lazycsv --session review.lazysession`): the file list and parsing
+//! options, and per file the filter, sorts, frozen and derived columns,
+//! column widths and cursor position, to pick a triage up later or hand it
+//! to a teammate.
+//!
+//! A session file is TOML. Paths under the session file's directory are
+//! written relative to it, so a folder can be moved or shared along with
+//! its session.
+
+use super::state_file::{load_state_file, save_state_file};
+use super::FileConfig;
+use crate::csv::Preamble;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How one file was set up
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileSetup {
+    pub path: PathBuf,
+    /// Cursor position (document row, column)
+    pub row: usize,
+    pub column: usize,
+    /// `:` commands setting the file up again (`filter`, `sort`,
+    /// `freeze`, `derive`), run in order when the file is first shown
+    pub commands: Vec<String>,
+    /// Column widths set by hand as (column, width)
+    pub widths: Vec<(usize, u16)>,
+    /// Delimiter chosen for this file with `:delimiter` (None: the
+    /// session's)
+    pub delimiter: Option<char>,
+}
+
+/// A saved session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSnapshot {
+    /// Index into `files` of the file shown
+    pub current: usize,
+    pub delimiter: Option<char>,
+    pub no_headers: bool,
+    pub encoding: Option<String>,
+    /// Lines skipped before the header (`--skip-lines`, `--comment-char`)
+    pub skip_lines: usize,
+    pub comment_char: Option<char>,
+    pub files: Vec<FileSetup>,
+}
+
+impl SessionSnapshot {
+    /// Parsing options of the saved session
+    pub fn config(&self) -> FileConfig {
+        FileConfig::with_options(
+            self.delimiter.map(|c| c as u8),
+            self.no_headers,
+            self.encoding.clone(),
+        )
+        .with_preamble(Preamble {
+            skip_lines: self.skip_lines,
+            comment: self.comment_char.map(|c| c as u8),
+        })
+    }
+
+    /// Files read with a delimiter of their own, and their settings
+    /// (`base` with that delimiter)
+    pub fn file_configs(&self, base: &FileConfig) -> Vec<(PathBuf, FileConfig)> {
+        self.files
+            .iter()
+            .filter_map(|setup| {
+                let delimiter = setup.delimiter?;
+                let config = FileConfig {
+                    delimiter: Some(delimiter as u8),
+                    ..base.clone()
+                };
+                Some((setup.path.clone(), config))
+            })
+            .collect()
+    }
+
+    /// Take the parsing options of a session
+    pub fn set_config(&mut self, config: &FileConfig) {
+        self.delimiter = config.delimiter.map(char::from);
+        self.no_headers = config.no_headers;
+        self.encoding = config.encoding.clone();
+        self.skip_lines = config.preamble.skip_lines;
+        self.comment_char = config.preamble.comment.map(char::from);
+    }
+
+    /// Read a session file; relative paths are resolved against its
+    /// directory
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let mut snapshot: Self = load_state_file(path, "session")?
+            .with_context(|| format!("No session file {}", path.display()))?;
+        if snapshot.files.is_empty() {
+            anyhow::bail!("Session file {} lists no files", path.display());
+        }
+        snapshot.current = snapshot.current.min(snapshot.files.len() - 1);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for file in &mut snapshot.files {
+            file.path = dir.join(&file.path);
+        }
+        Ok(snapshot)
+    }
+
+    /// Write the session file, with paths under its directory relative
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let dir = dir
+            .canonicalize()
+            .with_context(|| format!("Failed to open {}", dir.display()))?;
+        let mut snapshot = self.clone();
+        for file in &mut snapshot.files {
+            let absolute = file
+                .path
+                .canonicalize()
+                .unwrap_or_else(|_| file.path.clone());
+            file.path = match absolute.strip_prefix(&dir) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => absolute,
+            };
+        }
+        save_state_file(path, &snapshot, "session")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_paths_are_relative_to_the_session_file() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+        let file = dir.path().join("data").join("orders.csv");
+        std::fs::write(&file, "id\n1\n").unwrap();
+        let outside = TempDir::new().unwrap();
+        let other = outside.path().join("other.csv");
+        std::fs::write(&other, "id\n").unwrap();
+
+        let snapshot = SessionSnapshot {
+            current: 1,
+            delimiter: Some(';'),
+            files: vec![
+                FileSetup {
+                    path: other.clone(),
+                    ..Default::default()
+                },
+                FileSetup {
+                    path: file.clone(),
+                    row: 4,
+                    column: 1,
+                    commands: vec!["filter status=\"failed\"".to_string()],
+                    widths: vec![(1, 30)],
+                    delimiter: Some('|'),
+                },
+            ],
+            ..Default::default()
+        };
+        let path = dir.path().join("review.lazysession");
+        snapshot.save_to(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("path = \"data/orders.csv\""), "{}", text);
+
+        let loaded = SessionSnapshot::load_from(&path).unwrap();
+        assert_eq!(loaded.files[1].commands, snapshot.files[1].commands);
+        assert_eq!(loaded.files[1].widths, vec![(1, 30)]);
+        assert_eq!(
+            loaded.files[1].path.canonicalize().unwrap(),
+            file.canonicalize().unwrap()
+        );
+        assert_eq!(
+            loaded.files[0].path.canonicalize().unwrap(),
+            other.canonicalize().unwrap()
+        );
+        assert_eq!(loaded.config().delimiter, Some(b';'));
+        // Only the file with its own delimiter gets its own settings
+        let configs = loaded.file_configs(&loaded.config());
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].0, loaded.files[1].path);
+        assert_eq!(configs[0].1.delimiter, Some(b'|'));
+    }
+
+    #[test]
+    fn test_session_without_files_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("empty.lazysession");
+        std::fs::write(&path, "current = 0\n").unwrap();
+        assert!(SessionSnapshot::load_from(&path).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! TOML files the session is kept in: the stores in the state directory
+//! and the session files of `:session save`.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// Read a TOML file; None when there is no such file. `what` names the
+/// content in errors, as in "Invalid positions file".
+pub(crate) fn load_state_file<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Option<T>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => toml::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("Invalid {} file {}", what, path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Write `value` to a TOML file, creating its directory if needed
+pub(crate) fn save_state_file<T: Serialize>(path: &Path, value: &T, what: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string(value).with_context(|| format!("Failed to serialize {}", what))?;
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_state_file_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("state").join("store.toml");
+        let missing: Option<BTreeMap<String, usize>> = load_state_file(&path, "store").unwrap();
+        assert_eq!(missing, None);
+
+        // The directory is created on save
+        let store = BTreeMap::from([("rows".to_string(), 3)]);
+        save_state_file(&path, &store, "store").unwrap();
+        assert_eq!(load_state_file(&path, "store").unwrap(), Some(store));
+
+        std::fs::write(&path, "rows = [").unwrap();
+        let err = load_state_file::<BTreeMap<String, usize>>(&path, "store").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid store file"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Follow a file another program keeps appending to (`--watch`, `:watch`).
+//!
+//! The file is polled by size: new bytes after the last complete line are
+//! handed out as they arrive, and a file that got shorter was replaced.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// What changed in a followed file since the last poll
+#[derive(Debug, Clone, PartialEq)]
+pub enum TailEvent {
+    /// Nothing new
+    Unchanged,
+    /// The file grew; holds the new complete lines (empty while a line is
+    /// still being written)
+    Grew(Vec<u8>),
+    /// The file got shorter, so it was truncated or rewritten
+    Replaced,
+}
+
+/// Read position in a followed file
+#[derive(Debug, Clone)]
+pub struct FileTail {
+    path: PathBuf,
+    /// Bytes of the file already seen
+    offset: u64,
+    /// Start of a line that isn't finished yet
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    /// Follow `path` from its current end
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: file_len(path).unwrap_or(0),
+            partial: Vec::new(),
+        }
+    }
+
+    /// The followed file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check the file for new lines. A file that can't be read is treated as
+    /// unchanged (it may be in the middle of being replaced).
+    pub fn poll(&mut self) -> TailEvent {
+        let Some(len) = file_len(&self.path) else {
+            return TailEvent::Unchanged;
+        };
+        if len < self.offset {
+            self.offset = len;
+            self.partial.clear();
+            return TailEvent::Replaced;
+        }
+        if len == self.offset {
+            return TailEvent::Unchanged;
+        }
+
+        let mut new = Vec::new();
+        let read = File::open(&self.path).and_then(|mut file| {
+            file.seek(SeekFrom::Start(self.offset))?;
+            file.take(len - self.offset).read_to_end(&mut new)
+        });
+        if read.is_err() {
+            return TailEvent::Unchanged;
+        }
+        self.offset += new.len() as u64;
+        self.partial.extend_from_slice(&new);
+
+        // Hand out complete lines only
+        let lines = match self.partial.iter().rposition(|&b| b == b'\n') {
+            Some(end) => {
+                let rest = self.partial.split_off(end + 1);
+                std::mem::replace(&mut self.partial, rest)
+            }
+            None => Vec::new(),
+        };
+        TailEvent::Grew(lines)
+    }
+}
+
+fn file_len(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).map(|m| m.len()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_tail_hands_out_complete_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.csv");
+        fs::write(&path, "a,b\n1,2\n").unwrap();
+        let mut tail = FileTail::new(&path);
+        assert_eq!(tail.poll(), TailEvent::Unchanged);
+
+        append(&path, "3,4\n5,");
+        assert_eq!(tail.poll(), TailEvent::Grew(b"3,4\n".to_vec()));
+        append(&path, "6");
+        assert_eq!(tail.poll(), TailEvent::Grew(Vec::new()));
+        append(&path, "\n");
+        assert_eq!(tail.poll(), TailEvent::Grew(b"5,6\n".to_vec()));
+    }
+
+    #[test]
+    fn test_tail_notices_replaced_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.csv");
+        fs::write(&path, "a,b\n1,2\n").unwrap();
+        let mut tail = FileTail::new(&path);
+
+        fs::write(&path, "a,b\n").unwrap();
+        assert_eq!(tail.poll(), TailEvent::Replaced);
+        append(&path, "7,8\n");
+        assert_eq!(tail.poll(), TailEvent::Grew(b"7,8\n".to_vec()));
+    }
+}
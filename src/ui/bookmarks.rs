@@ -0,0 +1,112 @@
+//! Bookmark list (`:bookmarks`): the rows bookmarked with `mB`, in place of
+//! the table. Enter jumps to the selected row, `d` removes its bookmark.
+
+use super::ListCursor;
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// Cells of a row shown after its number
+const PREVIEW_CELLS: usize = 8;
+
+/// State of the bookmark list
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookmarkView {
+    /// Bookmarked document rows, top to bottom
+    pub rows: Vec<usize>,
+    /// Selected entry
+    pub cursor: ListCursor,
+}
+
+impl BookmarkView {
+    /// List the bookmarks, selecting the first one at or below `current`
+    pub fn new(rows: Vec<usize>, current: usize) -> Self {
+        // Past the last bookmark, the last one
+        let selected = rows
+            .iter()
+            .position(|&row| row >= current)
+            .unwrap_or(usize::MAX);
+        let cursor = ListCursor::at(selected, rows.len());
+        Self { rows, cursor }
+    }
+
+    /// Document row of the selected entry
+    pub fn selected_row(&self) -> Option<usize> {
+        self.rows.get(self.cursor.selected()).copied()
+    }
+
+    /// Drop the selected entry from the list
+    pub fn remove_selected(&mut self) {
+        if self.cursor.selected() < self.rows.len() {
+            self.rows.remove(self.cursor.selected());
+            self.cursor.resize(self.rows.len());
+        }
+    }
+}
+
+/// Render the bookmark list over the table area
+pub fn render_bookmark_view(frame: &mut Frame, app: &App, view: &BookmarkView, area: Rect) {
+    let [title_area, table_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+    let title = format!(
+        " Bookmarks: {} rows   Enter: jump  d: remove  Esc: back",
+        view.rows.len()
+    );
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        title_area,
+    );
+
+    let header = Row::new(vec!["Row", "Cells"])
+        .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+    let rows = view.rows.iter().map(|&row| {
+        let cells = app.document.rows.get(row).map_or(String::new(), |cells| {
+            cells
+                .iter()
+                .take(PREVIEW_CELLS)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" │ ")
+        });
+        Row::new(vec![
+            Cell::from(format!("{:>7}", row + 1)),
+            Cell::from(cells),
+        ])
+    });
+
+    let table = Table::new(rows, [Constraint::Length(7), Constraint::Min(10)])
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(
+            Style::default()
+                .bg(app.theme.mode_color(app.mode))
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut state = TableState::default().with_selected(Some(view.cursor.selected()));
+    frame.render_stateful_widget(table, table_area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_starts_at_cursor_and_stays_in_range() {
+        let mut view = BookmarkView::new(vec![2, 40, 90], 10);
+        assert_eq!(view.selected_row(), Some(40));
+        assert_eq!(BookmarkView::new(vec![2, 40], 50).selected_row(), Some(40));
+
+        view.cursor.move_by(5);
+        assert_eq!(view.selected_row(), Some(90));
+        view.remove_selected();
+        assert_eq!(view.selected_row(), Some(40));
+        view.cursor.move_by(-5);
+        assert_eq!(view.cursor.selected(), 0);
+    }
+}
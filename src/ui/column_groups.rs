@@ -0,0 +1,266 @@
+//! Column groups that can be collapsed into a single placeholder column.
+//!
+//! A group is a contiguous range of columns. When collapsed, only its first
+//! column (the anchor) stays visible and is drawn as a placeholder; the rest
+//! are skipped by rendering and horizontal navigation.
+
+use serde::Deserialize;
+
+/// Characters that end a header prefix for automatic grouping
+/// (e.g. `cpu.user`, `cpu.system` -> group `cpu`)
+pub const PREFIX_SEPARATORS: &[char] = &['.', '_', ':', '/'];
+
+/// `[column_groups]` section of the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnGroupsConfig {
+    /// Group adjacent columns sharing a header prefix when a file is opened
+    pub auto: bool,
+    /// Explicit header prefixes to group (e.g. `["cpu_", "mem_"]`)
+    pub prefixes: Vec<String>,
+    /// Start with groups collapsed
+    pub collapsed: bool,
+}
+
+/// A named, contiguous range of columns (`start..end`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnGroup {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    pub collapsed: bool,
+}
+
+impl ColumnGroup {
+    /// Number of columns in the group
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Groups always hold at least two columns
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains(&self, col: usize) -> bool {
+        (self.start..self.end).contains(&col)
+    }
+}
+
+/// All column groups of a document, ordered by start column
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnGroups {
+    groups: Vec<ColumnGroup>,
+}
+
+impl ColumnGroups {
+    /// Build groups for `headers` according to config
+    pub fn from_config(headers: &[String], config: &ColumnGroupsConfig) -> Self {
+        let mut groups = if config.auto {
+            Self::by_prefix(headers)
+        } else {
+            Self::default()
+        };
+
+        for prefix in &config.prefixes {
+            if let Some(start) = headers.iter().position(|h| h.starts_with(prefix.as_str())) {
+                let len = headers[start..]
+                    .iter()
+                    .take_while(|h| h.starts_with(prefix.as_str()))
+                    .count();
+                let name = prefix.trim_end_matches(PREFIX_SEPARATORS).to_string();
+                // Overlaps with auto-detected groups are skipped
+                let _ = groups.add(&name, start, start + len);
+            }
+        }
+
+        groups.set_all_collapsed(config.collapsed);
+        groups
+    }
+
+    /// Group adjacent columns whose headers share a prefix ending in a separator
+    pub fn by_prefix(headers: &[String]) -> Self {
+        let mut groups = Self::default();
+        let mut start = 0;
+        while start < headers.len() {
+            let prefix = header_prefix(&headers[start]);
+            let len = match prefix {
+                Some(prefix) => headers[start..]
+                    .iter()
+                    .take_while(|h| header_prefix(h) == Some(prefix))
+                    .count(),
+                None => 1,
+            };
+            if let (Some(prefix), true) = (prefix, len >= 2) {
+                let _ = groups.add(prefix, start, start + len);
+            }
+            start += len;
+        }
+        groups
+    }
+
+    /// Add a group covering `start..end`
+    pub fn add(&mut self, name: &str, start: usize, end: usize) -> Result<(), String> {
+        if end < start + 2 {
+            return Err("A column group needs at least two columns".to_string());
+        }
+        if let Some(existing) = self.groups.iter().find(|g| start < g.end && g.start < end) {
+            return Err(format!("Overlaps column group '{}'", existing.name));
+        }
+
+        let group = ColumnGroup {
+            name: name.to_string(),
+            start,
+            end,
+            collapsed: false,
+        };
+        let at = self.groups.partition_point(|g| g.start < start);
+        self.groups.insert(at, group);
+        Ok(())
+    }
+
+    /// Remove all groups
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    /// All groups, ordered by start column
+    pub fn groups(&self) -> &[ColumnGroup] {
+        &self.groups
+    }
+
+    /// Check whether any groups are defined
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Group containing a column
+    pub fn group_at(&self, col: usize) -> Option<&ColumnGroup> {
+        self.groups.iter().find(|g| g.contains(col))
+    }
+
+    /// Whether a column is hidden inside a collapsed group
+    pub fn is_hidden(&self, col: usize) -> bool {
+        self.group_at(col)
+            .is_some_and(|g| g.collapsed && col != g.start)
+    }
+
+    /// Placeholder header for the anchor column of a collapsed group
+    pub fn collapsed_label(&self, col: usize) -> Option<String> {
+        self.group_at(col)
+            .filter(|g| g.collapsed && col == g.start)
+            .map(|g| format!("[{} +{}]", g.name, g.len()))
+    }
+
+    /// Collapse (Some(true)), expand (Some(false)) or toggle (None) the group
+    /// containing `col`. Returns the updated group.
+    pub fn set_collapsed(&mut self, col: usize, collapsed: Option<bool>) -> Option<&ColumnGroup> {
+        let group = self.groups.iter_mut().find(|g| g.contains(col))?;
+        group.collapsed = collapsed.unwrap_or(!group.collapsed);
+        Some(group)
+    }
+
+    /// Collapse or expand every group
+    pub fn set_all_collapsed(&mut self, collapsed: bool) {
+        for group in &mut self.groups {
+            group.collapsed = collapsed;
+        }
+    }
+
+    /// Column that represents `col` on screen (its group anchor if hidden)
+    pub fn visible_column(&self, col: usize) -> usize {
+        match self.group_at(col) {
+            Some(g) if g.collapsed => g.start,
+            _ => col,
+        }
+    }
+
+    /// Indices of all columns that are not hidden, in order
+    pub fn visible_columns(&self, total: usize) -> Vec<usize> {
+        (0..total).filter(|&col| !self.is_hidden(col)).collect()
+    }
+}
+
+/// Prefix of a header up to its first separator (None if there is none)
+fn header_prefix(header: &str) -> Option<&str> {
+    let idx = header.find(PREFIX_SEPARATORS)?;
+    (idx > 0 && idx + 1 < header.len()).then(|| &header[..idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_by_prefix_groups_adjacent_columns() {
+        let groups = ColumnGroups::by_prefix(&headers(&[
+            "id",
+            "cpu.user",
+            "cpu.system",
+            "cpu.idle",
+            "mem_used",
+            "mem_free",
+            "host",
+        ]));
+
+        let found: Vec<_> = groups
+            .groups()
+            .iter()
+            .map(|g| (g.name.as_str(), g.start, g.end))
+            .collect();
+        assert_eq!(found, vec![("cpu", 1, 4), ("mem", 4, 6)]);
+    }
+
+    #[test]
+    fn test_by_prefix_ignores_single_columns() {
+        let groups = ColumnGroups::by_prefix(&headers(&["a_1", "b_1", "c"]));
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_add_rejects_overlap_and_small_groups() {
+        let mut groups = ColumnGroups::default();
+        assert!(groups.add("one", 3, 4).is_err());
+        groups.add("a", 2, 5).unwrap();
+        assert!(groups.add("b", 4, 7).is_err());
+        groups.add("c", 0, 2).unwrap();
+        assert_eq!(groups.groups()[0].name, "c");
+    }
+
+    #[test]
+    fn test_collapse_hides_all_but_anchor() {
+        let mut groups = ColumnGroups::default();
+        groups.add("g", 1, 4).unwrap();
+        groups.set_collapsed(2, Some(true));
+
+        assert!(!groups.is_hidden(1));
+        assert!(groups.is_hidden(2));
+        assert!(groups.is_hidden(3));
+        assert_eq!(groups.visible_columns(5), vec![0, 1, 4]);
+        assert_eq!(groups.visible_column(3), 1);
+        assert_eq!(groups.collapsed_label(1), Some("[g +3]".to_string()));
+        assert_eq!(groups.collapsed_label(0), None);
+
+        // Toggle expands again
+        groups.set_collapsed(1, None);
+        assert_eq!(groups.visible_columns(5), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_from_config_prefixes_and_collapsed() {
+        let config = ColumnGroupsConfig {
+            auto: false,
+            prefixes: vec!["net_".to_string()],
+            collapsed: true,
+        };
+        let groups = ColumnGroups::from_config(&headers(&["ts", "net_rx", "net_tx"]), &config);
+
+        assert_eq!(groups.groups().len(), 1);
+        assert_eq!(groups.groups()[0].name, "net");
+        assert!(groups.groups()[0].collapsed);
+    }
+}
@@ -0,0 +1,362 @@
+//! Column width measurement shared by table rendering and horizontal scrolling.
+//!
+//! How many columns fit on screen depends on the terminal width and on the
+//! measured content of each column. The renderer and the navigation commands
+//! both go through these helpers so `l`, `$` and goto_column scroll exactly
+//! as far as the table actually draws.
+
+use super::utils::{column_to_excel_letter, display_width};
+use super::{ColumnGroups, NumberFormat, ViewState};
+use crate::app::Options;
+use crate::csv::Document;
+use crate::csv::Locale;
+use crate::domain::position::ColIndex;
+
+/// Width allocated for the row number column
+pub const ROW_NUMBER_COLUMN_WIDTH: u16 = 5;
+
+/// Minimum column width (characters)
+pub const MIN_COLUMN_WIDTH: u16 = 8;
+
+/// Maximum column width (characters)
+pub const MAX_COLUMN_WIDTH: u16 = 100;
+
+/// Narrowest width a column can be shrunk to by hand
+pub const MIN_MANUAL_WIDTH: u16 = 3;
+
+/// Table width assumed before the first frame has been drawn
+pub const DEFAULT_TABLE_WIDTH: u16 = 80;
+
+/// Gap between columns (the Table widget's column spacing)
+const COLUMN_SPACING: u16 = 1;
+
+/// Number of rows sampled when measuring a column, spread evenly over the
+/// file so long files are measured as fast as short ones
+const WIDTH_SAMPLE_ROWS: usize = 1000;
+
+/// Share of sampled cells (in percent) the automatic width fits; the rest
+/// are outliers that would make the column needlessly wide
+const WIDTH_PERCENTILE: usize = 95;
+
+/// Ideal width of a column: its header or the 95th percentile of sampled
+/// cell widths plus padding, clamped to the min column width and
+/// `max_width` (the `maxwidth` option)
+pub fn ideal_column_width(
+    document: &Document,
+    groups: &ColumnGroups,
+    col: usize,
+    max_width: u16,
+) -> u16 {
+    // Collapsed groups are only as wide as their placeholder
+    if let Some(label) = groups.collapsed_label(col) {
+        let width = (display_width(&label) + 2) as u16;
+        return width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+    }
+
+    let step = document.rows.len().div_ceil(WIDTH_SAMPLE_ROWS).max(1);
+    let mut lengths: Vec<usize> = document
+        .rows
+        .iter()
+        .step_by(step)
+        .filter_map(|row| row.get(col))
+        .map(|s| display_width(s))
+        .collect();
+    lengths.sort_unstable();
+    let data_len = match lengths.len() {
+        0 => 0,
+        n => lengths[(n * WIDTH_PERCENTILE).div_ceil(100) - 1],
+    };
+    padded_width(document, col, data_len).min(max_width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH))
+}
+
+/// Width fitting every cell of a column, measuring all rows (`:fit`)
+pub fn exact_column_width(document: &Document, col: usize) -> u16 {
+    let data_len = document
+        .rows
+        .iter()
+        .filter_map(|row| row.get(col))
+        .map(|s| display_width(s))
+        .max()
+        .unwrap_or(0);
+    padded_width(document, col, data_len)
+}
+
+/// Column width for cells `data_len` wide, never narrower than the header
+fn padded_width(document: &Document, col: usize, data_len: usize) -> u16 {
+    let header_len = display_width(document.get_header(ColIndex::new(col)))
+        .max(column_to_excel_letter(col).len());
+    let ideal = header_len.max(data_len).saturating_add(2); // +2 for padding
+    (ideal.min(usize::from(MAX_COLUMN_WIDTH)) as u16).max(MIN_COLUMN_WIDTH)
+}
+
+/// Width of the sampled numbers of a column shown in `format`, plus
+/// padding
+fn formatted_width(document: &Document, col: usize, format: &NumberFormat, locale: &Locale) -> u16 {
+    let step = document.rows.len().div_ceil(WIDTH_SAMPLE_ROWS).max(1);
+    let widest = document
+        .rows
+        .iter()
+        .step_by(step)
+        .filter_map(|row| format.format(row.get(col)?, locale))
+        .map(|text| display_width(&text))
+        .max()
+        .unwrap_or(0);
+    (widest + 2).min(usize::from(MAX_COLUMN_WIDTH)) as u16
+}
+
+/// Width of a column on screen: the width set by hand with `<` / `>`, or
+/// its ideal width up to the `maxwidth` option, wide enough for numbers
+/// shown with `:fmt` (collapsed group placeholders are always measured)
+pub fn column_width(
+    document: &Document,
+    view_state: &ViewState,
+    col: usize,
+    options: &Options,
+) -> u16 {
+    let groups = &view_state.column_groups;
+    match view_state.column_widths.get(&col) {
+        Some(&width) if groups.collapsed_label(col).is_none() => width,
+        _ => {
+            let width = ideal_column_width(document, groups, col, options.max_width);
+            match view_state.number_formats.get(&col) {
+                Some(format) if groups.collapsed_label(col).is_none() => {
+                    width.max(formatted_width(document, col, format, &options.locale()))
+                }
+                _ => width,
+            }
+        }
+    }
+}
+
+/// Width left for data columns in a table `table_width` cells wide
+/// (0 means the table hasn't been drawn yet)
+pub fn data_width(table_width: u16) -> u16 {
+    let table_width = if table_width == 0 {
+        DEFAULT_TABLE_WIDTH
+    } else {
+        table_width
+    };
+    table_width.saturating_sub(ROW_NUMBER_COLUMN_WIDTH + COLUMN_SPACING)
+}
+
+/// Take columns in order while they fit side by side in `available` cells.
+/// The first column is always taken, even if it has to be squeezed.
+pub fn fit_columns(
+    columns: impl IntoIterator<Item = usize>,
+    mut width: impl FnMut(usize) -> u16,
+    available: u16,
+) -> Vec<usize> {
+    let mut fitted = Vec::new();
+    let mut used: u32 = 0;
+    for col in columns {
+        let gap = if fitted.is_empty() { 0 } else { COLUMN_SPACING };
+        let needed = used + u32::from(gap) + u32::from(width(col));
+        if needed > u32::from(available) && !fitted.is_empty() {
+            break;
+        }
+        used = needed;
+        fitted.push(col);
+    }
+    fitted
+}
+
+/// Columns on screen for a horizontal scroll offset: the visible frozen
+/// columns first, then scrolling columns from display position `start` as
+/// far as the width allows, then the column pinned to the right edge (if
+/// any). `order` lists the columns in display order; columns hidden inside
+/// collapsed groups are skipped.
+pub fn visible_columns(
+    start: usize,
+    order: &[usize],
+    groups: &ColumnGroups,
+    frozen: usize,
+    pinned_last: Option<usize>,
+    available: u16,
+    mut width: impl FnMut(usize) -> u16,
+) -> Vec<usize> {
+    let total_cols = order.len();
+    let frozen = frozen.min(total_cols);
+    let pinned_last = pinned_last.and_then(|col| {
+        let position = order.iter().position(|&c| c == col)?;
+        (position >= frozen).then_some((col, position))
+    });
+    let (scroll_end, reserved) = match pinned_last {
+        Some((col, position)) => (position, width(col) + COLUMN_SPACING),
+        None => (total_cols, 0),
+    };
+    let columns = (0..frozen)
+        .chain(start.max(frozen)..scroll_end)
+        .map(|position| order[position])
+        .filter(|&col| !groups.is_hidden(col));
+    let mut fitted = fit_columns(columns, &mut width, available.saturating_sub(reserved));
+    fitted.extend(pinned_last.map(|(col, _)| col));
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(count: usize) -> Vec<usize> {
+        (0..count).collect()
+    }
+
+    #[test]
+    fn test_fit_columns_counts_spacing() {
+        // 8 + 1 + 8 + 1 + 8 = 26
+        assert_eq!(fit_columns(0..10, |_| 8, 26), vec![0, 1, 2]);
+        assert_eq!(fit_columns(0..10, |_| 8, 25), vec![0, 1]);
+        // An oversized first column still shows
+        assert_eq!(fit_columns(0..10, |_| 100, 20), vec![0]);
+        assert!(fit_columns(0..0, |_| 8, 20).is_empty());
+    }
+
+    #[test]
+    fn test_manual_width_overrides_ideal_width() {
+        let document = Document {
+            headers: vec!["name".to_string(), "description".to_string()],
+            rows: vec![vec!["a".to_string(), "x".repeat(30)]],
+            ..Default::default()
+        };
+        let mut view_state = ViewState::default();
+        assert_eq!(
+            column_width(&document, &view_state, 1, &Options::default()),
+            32
+        );
+
+        view_state.column_widths.insert(1, 12);
+        assert_eq!(
+            column_width(&document, &view_state, 1, &Options::default()),
+            12
+        );
+        assert_eq!(
+            column_width(&document, &view_state, 0, &Options::default()),
+            MIN_COLUMN_WIDTH
+        );
+    }
+
+    #[test]
+    fn test_ideal_width_ignores_outliers() {
+        let mut rows: Vec<Vec<String>> = (0..99).map(|_| vec!["x".repeat(10)]).collect();
+        rows.push(vec!["y".repeat(60)]);
+        let mut document = Document {
+            headers: vec!["note".to_string()],
+            rows,
+            ..Default::default()
+        };
+        let groups = ColumnGroups::default();
+        assert_eq!(
+            ideal_column_width(&document, &groups, 0, MAX_COLUMN_WIDTH),
+            12
+        );
+        assert_eq!(exact_column_width(&document, 0), 62);
+        // `maxwidth` caps the fitted width, never below the minimum
+        document.rows[0][0] = "z".repeat(40);
+        assert_eq!(ideal_column_width(&document, &groups, 0, 10), 10);
+        assert_eq!(
+            ideal_column_width(&document, &groups, 0, 1),
+            MIN_COLUMN_WIDTH
+        );
+
+        // Long files are sampled across their whole length
+        document.rows = (0..100_000)
+            .map(|i| vec![if i % 2 == 0 { "a" } else { "b" }.repeat(20)])
+            .collect();
+        assert_eq!(
+            ideal_column_width(&document, &groups, 0, MAX_COLUMN_WIDTH),
+            22
+        );
+    }
+
+    #[test]
+    fn test_width_counts_wide_characters_twice() {
+        let document = Document {
+            headers: vec!["都市".to_string()],
+            rows: vec![vec!["東京都千代田区".to_string()], vec!["🎉🎉".to_string()]],
+            ..Default::default()
+        };
+        // 7 double-width characters plus padding
+        assert_eq!(exact_column_width(&document, 0), 16);
+    }
+
+    #[test]
+    fn test_data_width_falls_back_before_first_frame() {
+        assert_eq!(data_width(0), DEFAULT_TABLE_WIDTH - 6);
+        assert_eq!(data_width(200), 194);
+        assert_eq!(data_width(3), 0);
+    }
+
+    #[test]
+    fn test_visible_columns_fill_width() {
+        let groups = ColumnGroups::default();
+        let narrow = visible_columns(0, &order(50), &groups, 0, None, 44, |_| 8);
+        assert_eq!(narrow, vec![0, 1, 2, 3, 4]);
+
+        let wide = visible_columns(0, &order(50), &groups, 0, None, 179, |_| 8);
+        assert_eq!(wide.len(), 20);
+
+        // Wide columns leave room for fewer
+        let columns = visible_columns(0, &order(50), &groups, 0, None, 44, |col| {
+            if col == 1 {
+                30
+            } else {
+                8
+            }
+        });
+        assert_eq!(columns, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_visible_columns_scrolled_and_at_end() {
+        let groups = ColumnGroups::default();
+        let columns = visible_columns(10, &order(50), &groups, 0, None, 89, |_| 8);
+        assert_eq!(columns, (10..20).collect::<Vec<_>>());
+
+        let columns = visible_columns(25, &order(30), &groups, 0, None, 89, |_| 8);
+        assert_eq!(columns, vec![25, 26, 27, 28, 29]);
+    }
+
+    #[test]
+    fn test_visible_columns_follow_display_order() {
+        let groups = ColumnGroups::default();
+        let columns = visible_columns(1, &[3, 0, 2, 1], &groups, 1, None, 89, |_| 8);
+        assert_eq!(columns, vec![3, 0, 2, 1]);
+
+        let columns = visible_columns(2, &[3, 0, 2, 1], &groups, 0, None, 89, |_| 8);
+        assert_eq!(columns, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_visible_columns_skips_collapsed_group() {
+        let mut groups = ColumnGroups::default();
+        groups.add("g", 2, 6).unwrap();
+        groups.set_collapsed(2, Some(true));
+
+        let columns = visible_columns(0, &order(8), &groups, 0, None, 89, |_| 8);
+        assert_eq!(columns, vec![0, 1, 2, 6, 7]);
+    }
+
+    #[test]
+    fn test_visible_columns_keeps_frozen_columns() {
+        let groups = ColumnGroups::default();
+        let columns = visible_columns(20, &order(50), &groups, 2, None, 89, |_| 8);
+        assert_eq!(columns[..3], [0, 1, 20]);
+        assert_eq!(columns.len(), 10);
+
+        // Scroll offsets inside the frozen region start right after it
+        let columns = visible_columns(0, &order(5), &groups, 2, None, 89, |_| 8);
+        assert_eq!(columns, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_visible_columns_pin_last_column() {
+        let groups = ColumnGroups::default();
+        // The pinned column's width is reserved before the scrolling ones fit
+        let columns = visible_columns(10, &order(50), &groups, 1, Some(49), 89, |_| 8);
+        assert_eq!(columns, vec![0, 10, 11, 12, 13, 14, 15, 16, 17, 49]);
+
+        // Scrolling stops short of the pinned column instead of repeating it
+        let columns = visible_columns(47, &order(50), &groups, 0, Some(49), 89, |_| 8);
+        assert_eq!(columns, vec![47, 48, 49]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Align numeric columns on the decimal point (the `aligndecimals` option).
+//!
+//! Integer parts are right-aligned and fractions padded with spaces, so
+//! `1.5`, `12` and `0.125` line up without showing digits the data doesn't
+//! have. The decimal point is the locale's (`1,5` in German).
+
+/// Integer and fraction widths shared by the numbers of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalLayout {
+    int_width: usize,
+    frac_width: usize,
+    decimal: char,
+}
+
+impl DecimalLayout {
+    /// Layout fitting `cells`, or None unless every non-empty cell is a
+    /// plain decimal number with `decimal` as its point (and there is at
+    /// least one)
+    pub fn for_cells<'a>(cells: impl IntoIterator<Item = &'a str>, decimal: char) -> Option<Self> {
+        let mut layout: Option<Self> = None;
+        for cell in cells {
+            let cell = cell.trim();
+            if cell.is_empty() {
+                continue;
+            }
+            let (int, frac) = split_number(cell, decimal)?;
+            let frac_width = frac.map_or(0, str::len);
+            let current = layout.get_or_insert(Self {
+                int_width: 0,
+                frac_width: 0,
+                decimal,
+            });
+            current.int_width = current.int_width.max(int.len());
+            current.frac_width = current.frac_width.max(frac_width);
+        }
+        layout
+    }
+
+    /// Width of an aligned cell
+    pub fn width(&self) -> usize {
+        match self.frac_width {
+            0 => self.int_width,
+            frac => self.int_width + 1 + frac,
+        }
+    }
+
+    /// `cell` aligned on the decimal point; other text is left alone
+    pub fn format(&self, cell: &str) -> String {
+        let trimmed = cell.trim();
+        let Some((int, frac)) = split_number(trimmed, self.decimal) else {
+            return cell.to_string();
+        };
+        let fraction = match (frac, self.frac_width) {
+            (_, 0) => String::new(),
+            (Some(frac), width) => format!("{}{:<width$}", self.decimal, frac, width = width),
+            (None, width) => " ".repeat(width + 1),
+        };
+        format!("{:>width$}{}", int, fraction, width = self.int_width)
+    }
+}
+
+/// Split a plain decimal number into its (signed) integer part and fraction
+fn split_number(cell: &str, decimal: char) -> Option<(&str, Option<&str>)> {
+    let (int, frac) = match cell.split_once(decimal) {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (cell, None),
+    };
+    let digits = int.strip_prefix(['-', '+']).unwrap_or(int);
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let valid = all_digits(digits)
+        && frac.is_none_or(|f| !f.is_empty() && all_digits(f))
+        && !(digits.is_empty() && frac.is_none());
+    valid.then_some((int, frac))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_mixed_precision() {
+        let cells = ["1.5", "12", "-0.125", "", "3."];
+        assert_eq!(DecimalLayout::for_cells(cells, '.'), None);
+
+        let cells = ["1.5", "12", "-0.125", "", ".5"];
+        let layout = DecimalLayout::for_cells(cells, '.').unwrap();
+        assert_eq!(layout.width(), 6);
+        let aligned: Vec<String> = cells.iter().map(|c| layout.format(c)).collect();
+        assert_eq!(aligned, vec![" 1.5  ", "12    ", "-0.125", "", "  .5  "]);
+    }
+
+    #[test]
+    fn test_integers_and_text() {
+        let layout = DecimalLayout::for_cells(["7", "1000", " 42 "], '.').unwrap();
+        assert_eq!(layout.format("7"), "   7");
+        assert_eq!(layout.format(" 42 "), "  42");
+        assert_eq!(layout.format("n/a"), "n/a");
+        assert_eq!(DecimalLayout::for_cells(["1", "n/a"], '.'), None);
+        assert_eq!(DecimalLayout::for_cells(["", " "], '.'), None);
+    }
+
+    #[test]
+    fn test_align_on_decimal_comma() {
+        let cells = ["1,5", "-12,25"];
+        let layout = DecimalLayout::for_cells(cells, ',').unwrap();
+        let aligned: Vec<String> = cells.iter().map(|c| layout.format(c)).collect();
+        assert_eq!(aligned, vec!["  1,5 ", "-12,25"]);
+        assert_eq!(DecimalLayout::for_cells(["1.5"], ','), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! File browser (`:browse`, or starting lazycsv on a directory): the
+//! subdirectories and tabular files of a directory in an overlay, to walk
+//! the tree and pick the file to open.
+
+use super::help::centered_rect;
+use super::ListCursor;
+use crate::App;
+use anyhow::{Context, Result};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+
+/// Share of the terminal covered by the browser
+const BROWSER_WIDTH_PERCENT: u16 = 60;
+const BROWSER_HEIGHT_PERCENT: u16 = 70;
+
+/// An entry of the listed directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserEntry {
+    /// Name shown (`../` for the parent, directories end with `/`)
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// State of the file browser
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileBrowser {
+    /// Directory listed
+    pub dir: PathBuf,
+    /// The parent first, then subdirectories, then files, each by name
+    pub entries: Vec<BrowserEntry>,
+    /// Selected entry
+    pub cursor: ListCursor,
+}
+
+/// Subdirectories and files with one of `extensions` in `dir`. Hidden
+/// entries are left out.
+fn list_dir<S: AsRef<str>>(dir: &Path, extensions: &[S]) -> Result<Vec<BrowserEntry>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            dirs.push(BrowserEntry {
+                name: format!("{}/", name),
+                path,
+                is_dir: true,
+            });
+        } else if path.is_file() && crate::file_system::has_extension(&path, extensions) {
+            files.push(BrowserEntry {
+                name,
+                path,
+                is_dir: false,
+            });
+        }
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let parent = dir.parent().map(|parent| BrowserEntry {
+        name: "../".to_string(),
+        path: parent.to_path_buf(),
+        is_dir: true,
+    });
+    Ok(parent.into_iter().chain(dirs).chain(files).collect())
+}
+
+impl FileBrowser {
+    /// List `dir`, selecting the first file in it (else the first entry)
+    pub fn open<S: AsRef<str>>(dir: &Path, extensions: &[S]) -> Result<Self> {
+        // Absolute, so `..` leads somewhere from `.`
+        let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let entries = list_dir(&dir, extensions)?;
+        let selected = entries.iter().position(|entry| !entry.is_dir).unwrap_or(0);
+        Ok(Self {
+            dir,
+            cursor: ListCursor::at(selected, entries.len()),
+            entries,
+        })
+    }
+
+    /// The selected entry
+    pub fn selected_entry(&self) -> Option<&BrowserEntry> {
+        self.entries.get(self.cursor.selected())
+    }
+
+    /// List another directory; going up selects the directory come from
+    pub fn change_dir<S: AsRef<str>>(&mut self, dir: &Path, extensions: &[S]) -> Result<()> {
+        let mut browser = Self::open(dir, extensions)?;
+        if self.dir.parent() == Some(browser.dir.as_path()) {
+            if let Some(index) = browser
+                .entries
+                .iter()
+                .skip(1)
+                .position(|entry| entry.path == self.dir)
+            {
+                browser.cursor = ListCursor::at(index + 1, browser.entries.len());
+            }
+        }
+        *self = browser;
+        Ok(())
+    }
+}
+
+/// Render the browser centered over the screen, like the file picker
+pub fn render_file_browser(frame: &mut Frame, app: &App, browser: &FileBrowser) {
+    let area = centered_rect(BROWSER_WIDTH_PERCENT, BROWSER_HEIGHT_PERCENT, frame.area());
+    let title = format!(
+        " {}  Enter: open  h: up  Esc: close ",
+        super::file_picker::file_label(&browser.dir)
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let items: Vec<ListItem> = browser
+        .entries
+        .iter()
+        .map(|entry| {
+            let mut line = if entry.is_dir {
+                Line::from(Span::styled(
+                    entry.name.as_str(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(entry.name.as_str())
+            };
+            if !entry.is_dir && app.session.file_index(&entry.path).is_some() {
+                let tag = if app.session.is_current_file(&entry.path) {
+                    "  (open)"
+                } else {
+                    "  (in session)"
+                };
+                line.spans.push(Span::styled(
+                    tag,
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            ListItem::new(line)
+        })
+        .collect();
+    if items.is_empty() {
+        frame.render_widget(Paragraph::new("No tabular files here"), inner);
+        return;
+    }
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(app.theme.mode_color(app.mode))
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default().with_selected(Some(browser.cursor.selected()));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_lists_dirs_then_tabular_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("b.csv"), "").unwrap();
+        std::fs::write(dir.path().join("a.tsv.gz"), "").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "").unwrap();
+        std::fs::write(dir.path().join(".hidden.csv"), "").unwrap();
+        std::fs::create_dir(dir.path().join("2024")).unwrap();
+        std::fs::write(dir.path().join("2024").join("q1.csv"), "").unwrap();
+
+        let mut browser = FileBrowser::open(dir.path(), &["csv", "tsv"]).unwrap();
+        let names: Vec<&str> = browser.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["../", "2024/", "a.tsv.gz", "b.csv"]);
+        // The first file is selected
+        assert_eq!(browser.selected_entry().unwrap().name, "a.tsv.gz");
+
+        browser.cursor.move_by(-1);
+        let subdir = browser.selected_entry().unwrap().path.clone();
+        browser.change_dir(&subdir, &["csv"]).unwrap();
+        assert_eq!(browser.selected_entry().unwrap().name, "q1.csv");
+
+        // Going up selects the directory come from
+        let parent = browser.entries[0].path.clone();
+        browser.change_dir(&parent, &["csv"]).unwrap();
+        assert_eq!(browser.selected_entry().unwrap().name, "2024/");
+
+        browser.cursor.select_last();
+        assert_eq!(browser.selected_entry().unwrap().name, "b.csv");
+        browser.cursor.move_by(5);
+        assert_eq!(browser.cursor.selected(), 2);
+    }
+}
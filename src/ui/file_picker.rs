@@ -0,0 +1,227 @@
+//! File picker (`Ctrl+p`): the session's files in an overlay, narrowed by
+//! fuzzy matching as you type. Enter opens the selected file, Esc closes.
+
+use super::help::centered_rect;
+use super::ListCursor;
+use crate::App;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::path::Path;
+
+/// Share of the terminal covered by the picker
+const PICKER_WIDTH_PERCENT: u16 = 60;
+const PICKER_HEIGHT_PERCENT: u16 = 60;
+
+/// A file matching the query
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickerMatch {
+    /// Index of the file in the session
+    pub file: usize,
+    /// Character positions of the label matched by the query
+    pub positions: Vec<usize>,
+}
+
+/// State of the file picker
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePicker {
+    /// Names shown for the session's files, in session order
+    pub labels: Vec<String>,
+    /// Text typed so far
+    pub query: String,
+    /// Files matching the query, best match first
+    pub matches: Vec<PickerMatch>,
+    /// Selected entry of `matches`
+    pub cursor: ListCursor,
+}
+
+/// The name a file is listed under: its path relative to the working
+/// directory when it is below it, else the whole path
+pub fn file_label(path: &Path) -> String {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf));
+    relative.as_deref().unwrap_or(path).display().to_string()
+}
+
+impl FilePicker {
+    /// List every file, selecting the one at `current`
+    pub fn new(labels: Vec<String>, current: usize) -> Self {
+        let mut picker = Self {
+            labels,
+            query: String::new(),
+            matches: Vec::new(),
+            cursor: ListCursor::default(),
+        };
+        picker.update_matches();
+        picker.cursor = ListCursor::at(current, picker.matches.len());
+        picker
+    }
+
+    /// Match the labels against the query again, selecting the best match.
+    /// An empty query lists every file in session order.
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut matches: Vec<(i64, PickerMatch)> = self
+            .labels
+            .iter()
+            .enumerate()
+            .filter_map(|(file, label)| {
+                let (score, positions) = matcher.fuzzy_indices(label, &self.query)?;
+                Some((score, PickerMatch { file, positions }))
+            })
+            .collect();
+        // Stable, so equal scores keep session order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = matches.into_iter().map(|(_, m)| m).collect();
+        self.cursor = ListCursor::new(self.matches.len());
+    }
+
+    /// Add a typed character to the query
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.update_matches();
+    }
+
+    /// Remove the last character of the query
+    pub fn pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.update_matches();
+        }
+    }
+
+    /// Clear the query, listing every file again
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.update_matches();
+    }
+
+    /// Session index of the selected file
+    pub fn selected_file(&self) -> Option<usize> {
+        self.matches.get(self.cursor.selected()).map(|m| m.file)
+    }
+}
+
+/// A label with the characters matched by the query highlighted
+fn highlighted_label<'a>(label: &'a str, positions: &[usize], highlight: Style) -> Line<'a> {
+    let spans: Vec<Span> = label
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if positions.contains(&i) {
+                Span::styled(c.to_string(), highlight)
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Render the picker centered over the screen, like the help overlay
+pub fn render_file_picker(frame: &mut Frame, app: &App, picker: &FilePicker) {
+    let area = centered_rect(PICKER_WIDTH_PERCENT, PICKER_HEIGHT_PERCENT, frame.area());
+    let title = format!(
+        " Files ({}/{})  Enter: open  Esc: close ",
+        picker.matches.len(),
+        picker.labels.len()
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(
+        Paragraph::new(format!("> {}", picker.query))
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        query_area,
+    );
+    frame.set_cursor_position((
+        query_area.x + 2 + picker.query.chars().count() as u16,
+        query_area.y,
+    ));
+
+    let current = app.session.active_file_index();
+    let highlight = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let items = picker.matches.iter().map(|m| {
+        let mut line = highlighted_label(&picker.labels[m.file], &m.positions, highlight);
+        if m.file == current {
+            line.spans.push(Span::styled(
+                "  (open)",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        ListItem::new(line)
+    });
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(app.theme.mode_color(app.mode))
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default().with_selected(Some(picker.cursor.selected()));
+    frame.render_stateful_widget(list, list_area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker() -> FilePicker {
+        FilePicker::new(
+            vec![
+                "sales/2023.csv".to_string(),
+                "sales/2024.csv".to_string(),
+                "customers.csv".to_string(),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_picker_starts_on_open_file() {
+        let picker = picker();
+        assert_eq!(picker.matches.len(), 3);
+        assert_eq!(picker.selected_file(), Some(1));
+    }
+
+    #[test]
+    fn test_typing_narrows_fuzzily() {
+        let mut picker = picker();
+        for c in "cst".chars() {
+            picker.push(c);
+        }
+        assert_eq!(picker.selected_file(), Some(2));
+        assert_eq!(picker.matches[0].positions, vec![0, 2, 3]);
+
+        picker.clear();
+        for c in "s24".chars() {
+            picker.push(c);
+        }
+        assert_eq!(picker.matches.len(), 1);
+        assert_eq!(picker.selected_file(), Some(1));
+
+        picker.push('x');
+        assert_eq!(picker.selected_file(), None);
+        picker.pop();
+        assert_eq!(picker.selected_file(), Some(1));
+    }
+
+    #[test]
+    fn test_selection_stays_in_range() {
+        let mut picker = picker();
+        picker.cursor.move_by(5);
+        assert_eq!(picker.cursor.selected(), 2);
+        picker.cursor.move_by(-5);
+        assert_eq!(picker.cursor.selected(), 0);
+        picker.cursor.select_last();
+        assert_eq!(picker.selected_file(), Some(2));
+    }
+}
@@ -0,0 +1,129 @@
+//! Frequency view (`:freq`): value counts of a column in place of the table.
+//!
+//! Enter on a value filters the main table to the rows holding it.
+
+use super::utils::column_to_excel_letter;
+use super::ListCursor;
+use crate::analysis::value_counts;
+use crate::csv::Document;
+use crate::domain::position::ColIndex;
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// Shown in place of an empty value
+const EMPTY_VALUE_LABEL: &str = "(empty)";
+
+/// State of the frequency view
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyView {
+    pub column: ColIndex,
+    pub header: String,
+    /// Distinct values with their counts, most frequent first
+    pub counts: Vec<(String, usize)>,
+    /// Number of rows counted
+    pub total: usize,
+    /// Selected entry
+    pub cursor: ListCursor,
+}
+
+impl FrequencyView {
+    /// Count the values of a column
+    pub fn new(document: &Document, column: ColIndex) -> Self {
+        let counts = value_counts(document, column);
+        Self {
+            column,
+            header: document.get_header(column).to_string(),
+            cursor: ListCursor::new(counts.len()),
+            counts,
+            total: document.row_count(),
+        }
+    }
+
+    /// Value of the selected entry
+    pub fn selected_value(&self) -> Option<&str> {
+        self.counts
+            .get(self.cursor.selected())
+            .map(|(v, _)| v.as_str())
+    }
+}
+
+/// Render the frequency view over the table area
+pub fn render_frequency_view(frame: &mut Frame, app: &App, view: &FrequencyView, area: Rect) {
+    let [title_area, table_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+    let title = format!(
+        " Frequency: {} {} ({} distinct)   Enter: filter rows  Esc: back",
+        column_to_excel_letter(view.column.get()),
+        view.header,
+        view.counts.len()
+    );
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        title_area,
+    );
+
+    let header = Row::new(vec!["Value", "Count", "%"])
+        .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+    let rows = view.counts.iter().map(|(value, count)| {
+        let percent = *count as f64 * 100.0 / view.total.max(1) as f64;
+        let value = if value.is_empty() {
+            Cell::from(EMPTY_VALUE_LABEL).style(Style::default().add_modifier(Modifier::DIM))
+        } else {
+            Cell::from(value.clone())
+        };
+        Row::new(vec![
+            value,
+            Cell::from(format!("{:>8}", count)),
+            Cell::from(format!("{:>5.1}", percent)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .column_spacing(2)
+    .row_highlight_style(
+        Style::default()
+            .bg(app.theme.mode_color(app.mode))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = TableState::default().with_selected(Some(view.cursor.selected()));
+    frame.render_stateful_widget(table, table_area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_stays_in_range() {
+        let document = Document {
+            headers: vec!["x".to_string()],
+            rows: ["a", "b", "a"]
+                .iter()
+                .map(|v| vec![v.to_string()])
+                .collect(),
+            ..Default::default()
+        };
+        let mut view = FrequencyView::new(&document, ColIndex::new(0));
+        assert_eq!(view.selected_value(), Some("a"));
+
+        view.cursor.move_by(5);
+        assert_eq!(view.selected_value(), Some("b"));
+        view.cursor.move_by(-5);
+        assert_eq!(view.cursor.selected(), 0);
+    }
+}
@@ -0,0 +1,111 @@
+//! Grep view (`:grepall`): the cells matching a search of every file, in
+//! place of the table. Enter jumps to the selected match, switching files
+//! as needed.
+
+use super::ListCursor;
+use crate::app::grep::{GrepMatch, MAX_MATCHES};
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// State of the grep view
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrepView {
+    /// The query as typed
+    pub query: String,
+    pub matches: Vec<GrepMatch>,
+    /// Files that could not be read, with the error
+    pub errors: Vec<String>,
+    /// Selected entry
+    pub cursor: ListCursor,
+    /// Whether the list is shown; `:grepall` alone shows it again
+    pub visible: bool,
+}
+
+impl GrepView {
+    pub fn new(query: &str, matches: Vec<GrepMatch>, errors: Vec<String>) -> Self {
+        Self {
+            query: query.to_string(),
+            cursor: ListCursor::new(matches.len()),
+            matches,
+            errors,
+            visible: true,
+        }
+    }
+
+    /// The selected match
+    pub fn selected_match(&self) -> Option<&GrepMatch> {
+        self.matches.get(self.cursor.selected())
+    }
+
+    /// Whether the search stopped at `MAX_MATCHES`
+    pub fn is_truncated(&self) -> bool {
+        self.matches.len() >= MAX_MATCHES
+    }
+
+    /// Number of files with a match
+    pub fn file_count(&self) -> usize {
+        let mut files: Vec<usize> = self.matches.iter().map(|m| m.file).collect();
+        files.dedup();
+        files.len()
+    }
+}
+
+/// Render the grep view over the table area
+pub fn render_grep_view(frame: &mut Frame, app: &App, view: &GrepView, area: Rect) {
+    let [title_area, table_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+    let title = format!(
+        " grepall /{}/: {}{} matches in {} files   Enter: jump  Esc: back",
+        view.query,
+        if view.is_truncated() { "first " } else { "" },
+        view.matches.len(),
+        view.file_count()
+    );
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        title_area,
+    );
+
+    let files = app.session.files();
+    let header = Row::new(vec!["File", "Row", "Column", "Text"])
+        .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+    let rows = view.matches.iter().map(|m| {
+        let file = files
+            .get(m.file)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Row::new(vec![
+            Cell::from(file),
+            Cell::from(format!("{:>7}", m.row + 1)),
+            Cell::from(m.column.clone()),
+            Cell::from(m.snippet.clone()),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Max(24),
+            Constraint::Length(7),
+            Constraint::Max(20),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .column_spacing(2)
+    .row_highlight_style(
+        Style::default()
+            .bg(app.theme.mode_color(app.mode))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = TableState::default().with_selected(Some(view.cursor.selected()));
+    frame.render_stateful_widget(table, table_area, &mut state);
+}
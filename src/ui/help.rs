@@ -36,15 +36,86 @@ fn build_help_text() -> Vec<Line<'static>> {
         Line::from("  G / <n>G           Last row / row n (e.g., 15G)"),
         Line::from("  0 / $              First/last column"),
         Line::from("  Ctrl+d / Ctrl+u    Page down/up"),
+        Line::from("  Ctrl+f / Ctrl+b    Full page down/up"),
+        Line::from("  Alt+l / Alt+h      Next/prev column window"),
+        Line::from("  gl                 Previous column, at its last row"),
+        Line::from("  g;                 Previous edit (again for older)"),
+        Line::from("  '\"                 Position at last close"),
+        Line::from("  ma / 'a            Set mark a / jump to it"),
+        Line::from("  ''                 Back to before the last jump"),
+        Line::from("  mB / ) / (         Bookmark row / next / previous"),
+        Line::from("  :bookmarks         List bookmarks (:bookmarks clear)"),
+        Line::from("  :resume-point set  Bookmark review position (offered on open)"),
         Line::from(""),
         Line::from(Span::styled(
             "COMMAND MODE",
             Style::default().add_modifier(Modifier::BOLD),
         )),
         Line::from("  :                  Enter command mode"),
+        Line::from("  Up / Down (in :)   Recall earlier commands"),
         Line::from("  :15                Jump to row 15"),
         Line::from("  :c A / :c BC       Jump to column A/BC"),
+        Line::from("  :col name          Jump to column by header (fuzzy)"),
+        Line::from("  :w [file]          Save (to file: save as, :w! overwrites)"),
+        Line::from("  :w --columns a,b   Overwrite file with only these columns"),
+        Line::from("  :w --visible       Overwrite file with only rows/columns shown"),
+        Line::from("  :w --reorder       Overwrite file with columns in display order"),
+        Line::from("  :wdiff             Preview what saving changes on disk"),
+        Line::from("  :wq / :x           Save and quit"),
+        Line::from("  :e path            Open a file (Tab completes, :e! drops edits)"),
+        Line::from("  :browse [dir]      Browse a directory for a file to open"),
+        Line::from("  :ls / :b N|name    List files / switch to one by number or name"),
+        Line::from("  :e!                Reload file from disk (drop edits)"),
+        Line::from("  :watch             Follow rows appended to the file"),
+        Line::from("  :share [socket]    Let lazycsv --follow mirror this view"),
+        Line::from("  :session save file Save files and setup (lazycsv --session)"),
         Line::from("  :q                 Quit"),
+        Line::from("  :cq                Quit with exit code 1 (cancel a script)"),
+        Line::from("  :set showkeys      Echo keys (:set noshowkeys)"),
+        Line::from("  :set hints         Line of the keys for the current mode"),
+        Line::from("  :set rnu           Relative row numbers (:set nonu: 0 at cursor)"),
+        Line::from("  :set noheaders     Read the header line as data (:headers)"),
+        Line::from("  :config reload     Reload config file"),
+        Line::from("  :freeze [n|col]    Pin columns on the left (:unfreeze)"),
+        Line::from("  :freeze last       Pin the last column on the right"),
+        Line::from("  Alt+H / Alt+L      Move column left/right on screen"),
+        Line::from("  :colorder a,b      Show these columns first (reset: file order)"),
+        Line::from("  :stats             Statistics of the current column"),
+        Line::from("  :freq              Value counts (Enter filters rows)"),
+        Line::from("  :record            Current row, one field per line"),
+        Line::from("  :validate <schema> Mark cells breaking a schema"),
+        Line::from("  :check col regex   Mark cells not matching (n/N step)"),
+        Line::from("  :lint [fix]        Check file for RFC 4180 problems"),
+        Line::from("  :diff file [key]   Compare with another file (n/N step)"),
+        Line::from("  :diff export file  Write the differences to a file"),
+        Line::from("  :csearch text (\\)  Search the selected column (n/N step)"),
+        Line::from("  :grepall text      Search every open file; Enter jumps to a match"),
+        Line::from("  :replaceall /a/b/  Replace in every open file, asking first"),
+        Line::from("  :long 256          Mark cells over 256 chars (n/N step)"),
+        Line::from("  :long 256 truncate Cut them to 256 chars (asks first)"),
+        Line::from("  :fill col = expr   Overwrite column (Visual: selected rows)"),
+        Line::from("  :fill down|series  Continue first selected cell (f/F)"),
+        Line::from("  :derive col = expr Keep column computed from others (ƒ)"),
+        Line::from("  :deps / :underive  List derived columns / stop deriving"),
+        Line::from("  :sort [col] [desc] Sort rows (:'<,'>sort from Visual)"),
+        Line::from("  :copy sql [table]  Copy rows as INSERTs (also csv, tsv)"),
+        Line::from("  :filter col=value  Show only rows with that value"),
+        Line::from("  :filter col~regex  Show only rows matching a pattern"),
+        Line::from("  :filter col>=value Compare numbers or dates (also < <= >)"),
+        Line::from("  :nofilter          Show all rows again"),
+        Line::from("  :set minimap       Column overview strip (click to jump)"),
+        Line::from("  :set scrollbar     Row and column scrollbars"),
+        Line::from("  :set aligndecimals Line up decimal points (:set ad)"),
+        Line::from("  :set shownulls     Shade empty/NULL cells and count them"),
+        Line::from("  :set zebra         Shade every other row"),
+        Line::from("  :set crosshair     Shade the cursor row and column"),
+        Line::from("  :set wrap          Wrap the cursor row's long cells"),
+        Line::from("  :set maxwidth=N    Widest a column grows to fit"),
+        Line::from("  :set ellipsis=...  Marker for cut-off cells"),
+        Line::from("  :set page=half     Page size for Ctrl+d/u (or a row count)"),
+        Line::from("  :set scrolloff=3   Rows kept around the cursor when scrolling"),
+        Line::from("  :set messagetime=5 Seconds messages stay (errors outlast keys)"),
+        Line::from("  :set locale=de_DE  Number/date format for sort and stats"),
         Line::from("  Esc                Cancel command"),
         Line::from(""),
         Line::from(Span::styled(
@@ -78,15 +149,45 @@ fn build_help_text() -> Vec<Line<'static>> {
         Line::from("  o                  Insert row below, enter Insert"),
         Line::from("  O                  Insert row above, enter Insert"),
         Line::from("  dd                 Delete row"),
+        Line::from("  :set review        dd strikes rows through instead"),
+        Line::from("  :apply-deletes     Delete struck rows (:clear-deletes keeps)"),
         Line::from("  yy                 Yank (copy) row"),
-        Line::from("  p                  Paste row below"),
+        Line::from("  3yy / 3dd          Yank / delete rows (Visual: y / d)"),
+        Line::from("  p / P              Paste rows below / above"),
+        Line::from("  gp                 Paste over current rows"),
+        Line::from("  \"a / \"A            Register a next (\"A appends)"),
+        Line::from("  :registers         Rows held by each register"),
+        Line::from("  x / P              Cut cell / place it (until yy/dd)"),
+        Line::from("  :swap              Mark cell, then swap with another"),
+        Line::from("  (terminal paste)   Into cell; lines fill down, tabs across"),
+        Line::from("  yc                 Copy column to system clipboard"),
+        Line::from("  u / Ctrl+r         Undo / redo"),
+        Line::from("  .                  Repeat last change at cursor"),
         Line::from(""),
         Line::from(Span::styled(
             "VIEWPORT & FILES",
             Style::default().add_modifier(Modifier::BOLD),
         )),
         Line::from("  zt / zz / zb       Row at top/center/bottom"),
+        Line::from("  < / > / =          Narrow/widen column, = auto width"),
+        Line::from("  zr                 Resize with h/l (Enter keeps, Esc reverts)"),
+        Line::from("  :fit               Fit column to its widest cell"),
+        Line::from("  :fmt , .2 | sci    Show numbers grouped/rounded (off: as is)"),
+        Line::from("  K                  Toggle cell preview line"),
+        Line::from("  M / :view          Whole cell: / n N search, w wrap, gg G"),
+        Line::from("  zh / zl (zH / zL)  Scroll preview, or columns (half window)"),
         Line::from("  [ / ]              Previous/next file"),
+        Line::from("  Ctrl+p             Pick a file (type to match, Enter opens)"),
+        Line::from("  Ctrl+w s / v       Split: next file above / beside"),
+        Line::from("  Ctrl+w w / o / q   Other pane / close other / close this"),
+        Line::from("  v                  Visual selection (drag with the mouse)"),
+        Line::from("  Mouse              Click cell/file, wheel scrolls"),
+        Line::from("  zc / zo / za       Collapse/expand/toggle column group"),
+        Line::from("  zM / zR            Collapse/expand all column groups"),
+        Line::from("  { / }              Previous/next row group (:set rg)"),
+        Line::from("  zc / zo / za       On a row group header: fold its rows"),
+        Line::from("  :group auto        Group columns by header prefix"),
+        Line::from("  :group n B:F       Group columns B-F as 'n' (:ungroup)"),
         Line::from(""),
         Line::from(Span::styled(
             "GLOBAL",
@@ -98,6 +199,11 @@ fn build_help_text() -> Vec<Line<'static>> {
     ]
 }
 
+/// Number of lines in the help text (upper bound for help scrolling)
+pub fn help_line_count() -> u16 {
+    build_help_text().len() as u16
+}
+
 /// Render the help overlay with keybinding reference.
 ///
 /// Displays a centered modal window showing all available keybindings
@@ -148,7 +254,7 @@ pub fn render_help_overlay(frame: &mut Frame, scroll_offset: u16) {
 }
 
 /// Helper to create centered rectangle
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub(super) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
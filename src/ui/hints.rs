@@ -0,0 +1,165 @@
+//! One-line bar of the keys that matter right now (`:set hints`), like
+//! nano's: the main Normal mode actions, as bound in the config file, or
+//! what can follow a pending key such as `d`.
+
+use crate::app::Mode;
+use crate::input::{keymap, PendingCommand};
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Normal mode actions shown, by their name in the action registry
+const NORMAL_ACTIONS: &[&str] = &["insert", "command_mode", "undo", "magnify", "help", "quit"];
+
+/// Keys that can follow a pending key
+fn pending_hints(pending: &PendingCommand) -> &'static [(&'static str, &'static str)] {
+    match pending {
+        PendingCommand::G => &[
+            ("g", "first row"),
+            ("l", "previous column"),
+            ("p", "paste over"),
+            (";", "last edit"),
+            ("A-Z", "go to column"),
+        ],
+        PendingCommand::GotoColumn(_) => &[("A-Z", "more letters"), ("Enter", "go")],
+        PendingCommand::Z => &[
+            ("t/z/b", "row to top/center/bottom"),
+            ("r", "resize column"),
+            ("h/l", "scroll"),
+            ("c/o/a", "fold"),
+        ],
+        PendingCommand::D => &[("d", "delete row")],
+        PendingCommand::Y => &[("y", "yank row"), ("c", "copy column")],
+        PendingCommand::Quote => &[
+            ("a-z", "go to mark"),
+            ("'", "jump back"),
+            ("\"", "last position"),
+        ],
+        PendingCommand::Mark => &[("a-z", "set mark"), ("B", "bookmark row")],
+        PendingCommand::Register => &[("a-z", "register"), ("A-Z", "append to register")],
+        PendingCommand::Window => &[
+            ("s/v", "split"),
+            ("w", "other pane"),
+            ("o", "close other"),
+            ("q", "close"),
+        ],
+    }
+}
+
+/// Keys of a mode other than Normal
+fn mode_hints(mode: Mode) -> &'static [(&'static str, &'static str)] {
+    match mode {
+        Mode::Normal => &[],
+        Mode::Insert => &[
+            ("Enter", "save, down"),
+            ("Tab", "save, right"),
+            ("Esc", "cancel"),
+        ],
+        Mode::Visual => &[
+            ("y", "yank"),
+            ("d", "delete"),
+            ("f/F", "fill down/series"),
+            (":", "command"),
+            ("Esc", "cancel"),
+        ],
+        Mode::Command => &[("Enter", "run"), ("Up/Down", "history"), ("Esc", "cancel")],
+        Mode::Magnifier => &[
+            ("/", "search"),
+            ("n/N", "next/previous match"),
+            ("w", "wrap"),
+            ("Esc", "back"),
+        ],
+        Mode::HeaderEdit => &[("Esc", "back")],
+    }
+}
+
+/// The keys to show as (key, what it does), most relevant first
+pub fn hints(app: &App) -> Vec<(String, &'static str)> {
+    let fixed = |hints: &[(&str, &'static str)]| -> Vec<(String, &'static str)> {
+        hints
+            .iter()
+            .map(|&(key, action)| (key.to_string(), action))
+            .collect()
+    };
+
+    if app.confirm.is_some() {
+        return fixed(&[("y", "yes"), ("any other key", "no")]);
+    }
+    if app.view_state.resizing.is_some() {
+        return fixed(&[
+            ("h/l", "narrow/widen"),
+            ("H/L", "faster"),
+            ("=", "automatic"),
+            ("Enter", "keep"),
+            ("Esc", "revert"),
+        ]);
+    }
+    if app.mode != Mode::Normal {
+        return fixed(mode_hints(app.mode));
+    }
+    if let Some(pending) = &app.input_state.pending_command {
+        let mut hints = fixed(pending_hints(pending));
+        hints.push(("Esc".to_string(), "cancel"));
+        return hints;
+    }
+    NORMAL_ACTIONS
+        .iter()
+        .filter_map(|name| keymap::find_action(name))
+        .map(|action| (app.input_state.keymap.key_for(action), action.description))
+        .collect()
+}
+
+/// Render the hints bar: keys in bold, each followed by what it does
+pub fn render_hints_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, (key, action)) in hints(app).into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            key,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(format!("={}", action)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::FileConfig;
+    use crate::Document;
+    use std::path::PathBuf;
+
+    fn text(app: &App) -> String {
+        hints(app)
+            .iter()
+            .map(|(key, action)| format!("{}={}", key, action))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    #[test]
+    fn test_hints_follow_mode_and_pending_key() {
+        let document = Document {
+            headers: vec!["x".to_string()],
+            rows: vec![vec!["a".to_string()]],
+            ..Default::default()
+        };
+        let mut app = App::new(document, vec![PathBuf::from("a.csv")], 0, FileConfig::new());
+        assert!(text(&app).starts_with("i=Edit cell  :=Enter command mode"));
+
+        app.input_state.set_pending_command(PendingCommand::D);
+        assert_eq!(text(&app), "d=delete row  Esc=cancel");
+
+        app.input_state.clear_pending_command();
+        app.mode = Mode::Command;
+        assert_eq!(text(&app), "Enter=run  Up/Down=history  Esc=cancel");
+    }
+}
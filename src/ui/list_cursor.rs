@@ -0,0 +1,84 @@
+//! Selection in the lists shown over or in place of the table (value
+//! counts, grep matches, bookmarks, the file picker and browser), moved a
+//! few entries at a time and never past either end.
+
+/// Selected entry of a list of `len` entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListCursor {
+    /// Selected entry; 0 in an empty list
+    selected: usize,
+    /// Entries in the list
+    len: usize,
+}
+
+impl ListCursor {
+    /// The first of `len` entries selected
+    pub fn new(len: usize) -> Self {
+        Self { selected: 0, len }
+    }
+
+    /// Entry `selected` of `len` selected, or the last one past the end
+    pub fn at(selected: usize, len: usize) -> Self {
+        Self {
+            selected: selected.min(len.saturating_sub(1)),
+            len,
+        }
+    }
+
+    /// The selected entry
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection by `delta` entries, staying in range
+    pub fn move_by(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(self.len.saturating_sub(1));
+    }
+
+    /// Select the first entry
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    /// Select the last entry
+    pub fn select_last(&mut self) {
+        self.selected = self.len.saturating_sub(1);
+    }
+
+    /// The list now has `len` entries; the selection moves up if it was
+    /// past the end
+    pub fn resize(&mut self, len: usize) {
+        *self = Self::at(self.selected, len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_stays_in_range() {
+        let mut cursor = ListCursor::new(3);
+        cursor.move_by(5);
+        assert_eq!(cursor.selected(), 2);
+        cursor.move_by(-1);
+        assert_eq!(cursor.selected(), 1);
+        cursor.move_by(-5);
+        assert_eq!(cursor.selected(), 0);
+        cursor.select_last();
+        assert_eq!(cursor.selected(), 2);
+
+        cursor.resize(2);
+        assert_eq!(cursor.selected(), 1);
+        assert_eq!(ListCursor::at(7, 4).selected(), 3);
+
+        // An empty list keeps the selection at 0
+        let mut empty = ListCursor::new(0);
+        empty.move_by(1);
+        empty.select_last();
+        assert_eq!(empty.selected(), 0);
+    }
+}
@@ -0,0 +1,367 @@
+//! Magnifier (`M`, `:view`): the selected cell in full, in place of the table.
+//!
+//! Made for cells too big to read in the preview line. Lines scroll with
+//! j/k, Ctrl+d/Ctrl+u and gg/G, `w` toggles line wrapping (h/l scroll
+//! sideways without it), and `/` searches the cell with n/N to step through
+//! the matches. Searches are regular expressions that ignore case unless
+//! the query has capitals (see `SearchPattern`).
+
+use super::utils::column_to_excel_letter;
+use crate::app::search::SearchPattern;
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use std::ops::Range;
+
+/// Read-only view of one cell
+#[derive(Debug, Clone)]
+pub struct Magnifier {
+    /// Document row and column of the cell
+    pub row: usize,
+    pub col: usize,
+    text: Vec<char>,
+    /// Wrap long lines at the view width
+    pub wrap: bool,
+    /// First line shown
+    pub scroll: usize,
+    /// Horizontal scroll while not wrapping (characters)
+    pub column_offset: usize,
+    /// Query being typed after `/`
+    pub search_input: Option<String>,
+    /// Last search
+    pub query: String,
+    /// Character range of each match of `query`
+    matches: Vec<Range<usize>>,
+    /// Index into `matches` of the match jumped to last
+    pub current_match: Option<usize>,
+    /// `g` was pressed, waiting for a second `g`
+    pub pending_g: bool,
+    /// Size of the text area in the last frame
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Magnifier {
+    /// View `value`, the cell at `row`, `col`
+    pub fn new(row: usize, col: usize, value: &str) -> Self {
+        Self {
+            row,
+            col,
+            text: value.replace("\r\n", "\n").chars().collect(),
+            wrap: true,
+            scroll: 0,
+            column_offset: 0,
+            search_input: None,
+            query: String::new(),
+            matches: Vec::new(),
+            current_match: None,
+            pending_g: false,
+            width: 80,
+            height: 20,
+        }
+    }
+
+    /// Number of characters in the cell
+    pub fn char_count(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Character ranges of the lines on screen, wrapped at `width` when
+    /// wrapping is on
+    pub fn lines(&self) -> Vec<Range<usize>> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for end in (0..=self.text.len()).filter(|&i| i == self.text.len() || self.text[i] == '\n') {
+            if self.wrap && self.width > 0 && end - start > self.width {
+                let mut chunk = start;
+                while chunk < end {
+                    lines.push(chunk..(chunk + self.width).min(end));
+                    chunk += self.width;
+                }
+            } else {
+                lines.push(start..end);
+            }
+            start = end + 1;
+        }
+        lines
+    }
+
+    /// Highest useful scroll position
+    fn max_scroll(&self) -> usize {
+        self.lines().len().saturating_sub(self.height.max(1))
+    }
+
+    /// Scroll by `delta` lines (negative scrolls up)
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    /// Jump to the first line (`gg`)
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Jump to the last page (`G`)
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    /// Turn line wrapping on or off, keeping the top line in view
+    pub fn toggle_wrap(&mut self) {
+        let top = self.lines().get(self.scroll).map_or(0, |line| line.start);
+        self.wrap = !self.wrap;
+        self.column_offset = 0;
+        self.reveal(top);
+    }
+
+    /// Search for `pattern`, jumping to the first match at or after the top
+    /// line; returns the number of matches
+    pub fn search(&mut self, pattern: &SearchPattern) -> usize {
+        self.query = pattern.query.clone();
+        self.matches = find_all(&self.text, pattern);
+        let top = self.lines().get(self.scroll).map_or(0, |line| line.start);
+        self.current_match = None;
+        if !self.matches.is_empty() {
+            let first = self
+                .matches
+                .iter()
+                .position(|m| m.start >= top)
+                .unwrap_or(0);
+            self.select_match(first);
+        }
+        self.matches.len()
+    }
+
+    /// Jump to the next (or previous) match, wrapping around; returns its
+    /// 1-based number
+    pub fn next_match(&mut self, forward: bool) -> Option<usize> {
+        let count = self.matches.len();
+        if count == 0 {
+            return None;
+        }
+        let next = match (self.current_match, forward) {
+            (None, _) => 0,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+        self.select_match(next);
+        Some(next + 1)
+    }
+
+    /// Number of matches of the last search
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn select_match(&mut self, index: usize) {
+        self.current_match = Some(index);
+        let start = self.matches[index].start;
+        self.reveal(start);
+        if !self.wrap {
+            // Keep the match on screen sideways too
+            let line_start = self
+                .lines()
+                .into_iter()
+                .find(|line| line.contains(&start) || line.end == start)
+                .map_or(0, |line| line.start);
+            let column = start - line_start;
+            if column < self.column_offset || column >= self.column_offset + self.width {
+                self.column_offset = column.saturating_sub(self.width / 3);
+            }
+        }
+    }
+
+    /// Scroll so the line holding character `offset` is on screen
+    fn reveal(&mut self, offset: usize) {
+        let line = self
+            .lines()
+            .iter()
+            .position(|line| offset < line.end || (offset == line.end && line.is_empty()))
+            .unwrap_or(0);
+        if line < self.scroll || line >= self.scroll + self.height.max(1) {
+            self.scroll = line.saturating_sub(self.height / 3);
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Ranges of matches overlapping `line`, with whether each is the
+    /// current match
+    fn matches_in(&self, line: &Range<usize>) -> Vec<(Range<usize>, bool)> {
+        self.matches
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.start < line.end && m.end > line.start)
+            .map(|(i, m)| {
+                (
+                    m.start.max(line.start)..m.end.min(line.end),
+                    self.current_match == Some(i),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Character range of every match of `pattern` in `text`
+fn find_all(text: &[char], pattern: &SearchPattern) -> Vec<Range<usize>> {
+    let text: String = text.iter().collect();
+    // Byte offset of each character, to turn byte ranges into characters
+    let offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let char_at = |byte: usize| offsets.partition_point(|&offset| offset < byte);
+    pattern
+        .find_iter(&text)
+        .map(|range| char_at(range.start)..char_at(range.end))
+        .collect()
+}
+
+/// Render the magnifier over the table area
+pub fn render_magnifier(frame: &mut Frame, app: &mut App, area: Rect) {
+    let [title_area, text_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+    let Some(magnifier) = app.magnifier.as_mut() else {
+        return;
+    };
+    magnifier.width = usize::from(text_area.width);
+    magnifier.height = usize::from(text_area.height);
+    magnifier.scroll = magnifier.scroll.min(magnifier.max_scroll());
+
+    let lines = magnifier.lines();
+    let search = match (magnifier.current_match, magnifier.match_count()) {
+        (_, 0) if !magnifier.query.is_empty() => format!("  /{}: no matches", magnifier.query),
+        (Some(i), count) => format!("  /{} {}/{}", magnifier.query, i + 1, count),
+        _ => String::new(),
+    };
+    let title = format!(
+        " {}{} {}: {} chars, {} lines{}  w: wrap {}  Esc: back",
+        column_to_excel_letter(magnifier.col),
+        magnifier.row + 1,
+        app.document
+            .headers
+            .get(magnifier.col)
+            .map_or("", String::as_str),
+        magnifier.char_count(),
+        lines.len(),
+        search,
+        if magnifier.wrap { "on" } else { "off" },
+    );
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        title_area,
+    );
+
+    let match_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let current_style = Style::default().bg(Color::White).fg(Color::Black);
+    let text: Vec<Line> = lines
+        .iter()
+        .skip(magnifier.scroll)
+        .take(magnifier.height)
+        .map(|line| {
+            // The part of the line on screen
+            let start = if magnifier.wrap {
+                line.start
+            } else {
+                (line.start + magnifier.column_offset).min(line.end)
+            };
+            let shown = start..line.end.min(start + magnifier.width);
+            let mut spans = Vec::new();
+            let mut at = shown.start;
+            for (range, current) in magnifier.matches_in(&shown) {
+                spans.push(Span::raw(
+                    magnifier.text[at..range.start].iter().collect::<String>(),
+                ));
+                let style = if current { current_style } else { match_style };
+                spans.push(Span::styled(
+                    magnifier.text[range.clone()].iter().collect::<String>(),
+                    style,
+                ));
+                at = range.end;
+            }
+            spans.push(Span::raw(
+                magnifier.text[at..shown.end].iter().collect::<String>(),
+            ));
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(text), text_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(query: &str) -> SearchPattern {
+        SearchPattern::new(query, &Default::default()).unwrap()
+    }
+
+    fn magnifier(text: &str, width: usize, height: usize) -> Magnifier {
+        let mut magnifier = Magnifier::new(0, 0, text);
+        magnifier.width = width;
+        magnifier.height = height;
+        magnifier
+    }
+
+    #[test]
+    fn test_lines_wrap_at_width() {
+        let mut m = magnifier("abcdefg\n\nxy", 3, 10);
+        assert_eq!(m.lines(), vec![0..3, 3..6, 6..7, 8..8, 9..11]);
+        m.toggle_wrap();
+        assert_eq!(m.lines(), vec![0..7, 8..8, 9..11]);
+    }
+
+    #[test]
+    fn test_scrolling_stays_in_bounds() {
+        let text: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let mut m = magnifier(&text.join("\n"), 20, 4);
+        m.scroll_by(-1);
+        assert_eq!(m.scroll, 0);
+        m.scroll_by(100);
+        assert_eq!(m.scroll, 6);
+        m.scroll_to_top();
+        assert_eq!(m.scroll, 0);
+        m.scroll_to_bottom();
+        assert_eq!(m.scroll, 6);
+    }
+
+    #[test]
+    fn test_search_smartcase_and_next_match() {
+        let text: Vec<String> = (0..30).map(|i| format!("line {}", i)).collect();
+        let mut m = magnifier(&format!("{}\nLINE end", text.join("\n")), 20, 5);
+        assert_eq!(m.search(&pattern("line 2")), 11);
+        assert_eq!(m.current_match, Some(0));
+        // line 2 is already on screen
+        assert_eq!(m.scroll, 0);
+
+        assert_eq!(m.next_match(false), Some(11));
+        assert_eq!(m.scroll, 26);
+        assert_eq!(m.next_match(true), Some(1));
+        assert_eq!(m.scroll, 1);
+
+        assert_eq!(m.search(&pattern("line")), 31);
+        assert_eq!(m.search(&pattern("LINE")), 1);
+        assert_eq!(m.search(&pattern("nothing")), 0);
+        assert_eq!(m.next_match(true), None);
+    }
+
+    #[test]
+    fn test_search_scrolls_sideways_without_wrap() {
+        let mut m = magnifier(&format!("{}needle", "x".repeat(100)), 30, 5);
+        m.toggle_wrap();
+        m.search(&pattern("needle"));
+        assert_eq!(m.column_offset, 90);
+        assert_eq!(m.matches_in(&(0..106)), vec![(100..106, true)]);
+    }
+
+    #[test]
+    fn test_regex_matches_have_their_own_length() {
+        let mut m = magnifier("née 12, née 345", 40, 5);
+        assert_eq!(m.search(&pattern(r"\d+")), 2);
+        assert_eq!(m.matches_in(&(0..15)), vec![(4..6, true), (12..15, false)]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Row and cell marks shown as colored ticks on the scrollbar and minimap.
+//!
+//! Marks are stored by document row so they survive filtering; row
+//! insertions and deletions shift them like the rows they point at.
+
+use ratatui::style::Color;
+use std::collections::{BTreeMap, HashMap};
+
+/// What a mark stands for. Later variants win when marks share a tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MarkKind {
+    /// Row tagged by the user
+    Tagged,
+    /// Row bookmarked with `mB`, kept between sessions
+    Bookmark,
+    /// Search match
+    SearchHit,
+    /// Cell or row changed since the file was loaded
+    Changed,
+    /// Row marked by `dd` in review mode, removed by `:apply-deletes`
+    PendingDelete,
+    /// Validation error
+    Error,
+}
+
+impl MarkKind {
+    /// Color of the tick
+    pub fn color(self) -> Color {
+        match self {
+            MarkKind::Tagged => Color::Blue,
+            MarkKind::Bookmark => Color::Cyan,
+            MarkKind::SearchHit => Color::Yellow,
+            MarkKind::Changed => Color::Green,
+            MarkKind::PendingDelete => Color::Magenta,
+            MarkKind::Error => Color::Red,
+        }
+    }
+}
+
+/// A marked row, or a single cell when `column` is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    pub row: usize,
+    pub column: Option<usize>,
+    pub kind: MarkKind,
+}
+
+/// All marks of a document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Marks {
+    marks: Vec<Mark>,
+}
+
+impl Marks {
+    /// Add a mark (duplicates are ignored)
+    pub fn add(&mut self, row: usize, column: Option<usize>, kind: MarkKind) {
+        let mark = Mark { row, column, kind };
+        if !self.marks.contains(&mark) {
+            self.marks.push(mark);
+        }
+    }
+
+    /// Remove a mark; returns false if there was no such mark
+    pub fn remove(&mut self, row: usize, column: Option<usize>, kind: MarkKind) -> bool {
+        let before = self.marks.len();
+        self.marks.retain(|m| *m != Mark { row, column, kind });
+        self.marks.len() != before
+    }
+
+    /// Remove all marks of one kind
+    pub fn clear_kind(&mut self, kind: MarkKind) {
+        self.marks.retain(|m| m.kind != kind);
+    }
+
+    /// Remove all marks
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    /// Check whether there are no marks
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    /// All marks, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Mark> {
+        self.marks.iter()
+    }
+
+    /// Strongest mark kind of each marked document row
+    pub fn rows(&self) -> BTreeMap<usize, MarkKind> {
+        strongest(self.marks.iter().map(|m| (m.row, m.kind)))
+    }
+
+    /// Strongest mark kind of each column holding a cell mark
+    pub fn columns(&self) -> BTreeMap<usize, MarkKind> {
+        strongest(
+            self.marks
+                .iter()
+                .filter_map(|m| m.column.map(|col| (col, m.kind))),
+        )
+    }
+
+    /// Rows and cells holding a mark of one of `kinds`, top to bottom and
+    /// left to right (a row mark comes before the cells of its row)
+    pub fn positions(&self, kinds: &[MarkKind]) -> Vec<(usize, Option<usize>)> {
+        let mut positions: Vec<_> = self
+            .marks
+            .iter()
+            .filter(|m| kinds.contains(&m.kind))
+            .map(|m| (m.row, m.column))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Strongest mark kind of each marked cell, keyed by (row, column)
+    pub fn cells(&self) -> HashMap<(usize, usize), MarkKind> {
+        let mut cells = HashMap::new();
+        for mark in &self.marks {
+            if let Some(col) = mark.column {
+                cells
+                    .entry((mark.row, col))
+                    .and_modify(|k: &mut MarkKind| *k = (*k).max(mark.kind))
+                    .or_insert(mark.kind);
+            }
+        }
+        cells
+    }
+
+    /// A row was inserted at `at`
+    pub fn row_inserted(&mut self, at: usize) {
+        for mark in self.marks.iter_mut().filter(|m| m.row >= at) {
+            mark.row += 1;
+        }
+    }
+
+    /// Rows from `start` were reordered so that row `start + i` is the one
+    /// that was at `start + order[i]`; marks move with their rows
+    pub fn rows_reordered(&mut self, start: usize, order: &[usize]) {
+        let mut moved_to = vec![0; order.len()];
+        for (to, &from) in order.iter().enumerate() {
+            moved_to[from] = to;
+        }
+        for mark in self.marks.iter_mut() {
+            if let Some(&to) = mark.row.checked_sub(start).and_then(|i| moved_to.get(i)) {
+                mark.row = start + to;
+            }
+        }
+    }
+
+    /// The row at `at` was deleted (its marks go with it)
+    pub fn row_deleted(&mut self, at: usize) {
+        self.marks.retain(|m| m.row != at);
+        for mark in self.marks.iter_mut().filter(|m| m.row > at) {
+            mark.row -= 1;
+        }
+    }
+}
+
+/// Keep the strongest kind per key
+fn strongest(marks: impl Iterator<Item = (usize, MarkKind)>) -> BTreeMap<usize, MarkKind> {
+    let mut result = BTreeMap::new();
+    for (key, kind) in marks {
+        result
+            .entry(key)
+            .and_modify(|k: &mut MarkKind| *k = (*k).max(kind))
+            .or_insert(kind);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strongest_kind_per_row_and_column() {
+        let mut marks = Marks::default();
+        marks.add(3, Some(1), MarkKind::SearchHit);
+        marks.add(3, None, MarkKind::Error);
+        marks.add(5, Some(1), MarkKind::Changed);
+        marks.add(5, Some(1), MarkKind::Changed);
+
+        assert_eq!(marks.iter().count(), 3);
+        assert_eq!(
+            marks.rows().into_iter().collect::<Vec<_>>(),
+            vec![(3, MarkKind::Error), (5, MarkKind::Changed)]
+        );
+        assert_eq!(
+            marks.columns().into_iter().collect::<Vec<_>>(),
+            vec![(1, MarkKind::Changed)]
+        );
+    }
+
+    #[test]
+    fn test_positions_in_reading_order() {
+        let mut marks = Marks::default();
+        marks.add(4, Some(0), MarkKind::SearchHit);
+        marks.add(2, Some(3), MarkKind::Error);
+        marks.add(2, None, MarkKind::SearchHit);
+        marks.add(2, Some(1), MarkKind::SearchHit);
+        marks.add(1, None, MarkKind::Tagged);
+
+        assert_eq!(
+            marks.positions(&[MarkKind::SearchHit, MarkKind::Error]),
+            vec![(2, None), (2, Some(1)), (2, Some(3)), (4, Some(0))]
+        );
+    }
+
+    #[test]
+    fn test_row_insert_and_delete_shift_marks() {
+        let mut marks = Marks::default();
+        marks.add(2, None, MarkKind::Tagged);
+        marks.add(4, None, MarkKind::Error);
+
+        marks.row_inserted(3);
+        assert_eq!(marks.rows().keys().copied().collect::<Vec<_>>(), vec![2, 5]);
+
+        marks.row_deleted(2);
+        assert_eq!(marks.rows().keys().copied().collect::<Vec<_>>(), vec![4]);
+
+        marks.clear_kind(MarkKind::Error);
+        assert!(marks.is_empty());
+    }
+
+    #[test]
+    fn test_marks_follow_reordered_rows() {
+        let mut marks = Marks::default();
+        marks.add(0, None, MarkKind::Tagged);
+        marks.add(1, Some(0), MarkKind::Error);
+        marks.add(3, None, MarkKind::Changed);
+
+        // Rows 1..=3 become the old rows 3, 1, 2
+        marks.rows_reordered(1, &[2, 0, 1]);
+        let rows: Vec<_> = marks.iter().map(|m| m.row).collect();
+        assert_eq!(rows, vec![0, 2, 1]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Horizontal minimap of all columns (`:set minimap`).
+//!
+//! Drawn in place of the rule below the title bar: every column gets a slice
+//! of the strip (several columns share a cell in very wide files), with the
+//! columns on screen and the cursor column highlighted. Columns holding
+//! marked cells are tinted with the mark color. Clicking the strip jumps to
+//! the column under the mouse.
+
+use super::marks::MarkKind;
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// What a minimap cell represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapCell {
+    /// Only columns outside the visible window
+    Outside,
+    /// At least one column currently on screen
+    Visible,
+    /// The cursor column
+    Cursor,
+}
+
+impl MinimapCell {
+    fn symbol(self) -> &'static str {
+        match self {
+            MinimapCell::Outside => "─",
+            MinimapCell::Visible => "━",
+            MinimapCell::Cursor => "█",
+        }
+    }
+}
+
+/// Columns covered by cell `x` of a strip `width` cells wide
+fn columns_in_cell(x: usize, width: usize, total: usize) -> Range<usize> {
+    let start = x * total / width;
+    let end = ((x + 1) * total / width).max(start + 1);
+    start..end
+}
+
+/// Classify each cell of a strip `width` wide for `total` columns, with
+/// `window` on screen and the cursor on column `cursor`
+pub fn minimap_cells(
+    total: usize,
+    width: usize,
+    window: Range<usize>,
+    cursor: usize,
+) -> Vec<MinimapCell> {
+    if total == 0 {
+        return Vec::new();
+    }
+    (0..width)
+        .map(|x| {
+            let cols = columns_in_cell(x, width, total);
+            if cols.contains(&cursor) {
+                MinimapCell::Cursor
+            } else if cols.start < window.end && window.start < cols.end {
+                MinimapCell::Visible
+            } else {
+                MinimapCell::Outside
+            }
+        })
+        .collect()
+}
+
+/// Strongest mark of the columns in each cell of the strip
+pub fn minimap_marks(
+    total: usize,
+    width: usize,
+    marks: &BTreeMap<usize, MarkKind>,
+) -> Vec<Option<MarkKind>> {
+    if total == 0 {
+        return Vec::new();
+    }
+    (0..width)
+        .map(|x| {
+            marks
+                .range(columns_in_cell(x, width, total))
+                .map(|(_, &kind)| kind)
+                .max()
+        })
+        .collect()
+}
+
+/// Column under cell `x` of a strip `width` wide, preferring a marked column
+/// when the cell covers several
+pub fn column_at(x: usize, width: usize, total: usize, marks: &BTreeMap<usize, MarkKind>) -> usize {
+    if width == 0 {
+        return 0;
+    }
+    let cols = columns_in_cell(x.min(width - 1), width, total);
+    marks
+        .range(cols.clone())
+        .map(|(&col, _)| col)
+        .next()
+        .unwrap_or(cols.start)
+        .min(total.saturating_sub(1))
+}
+
+/// Render the minimap for the columns currently on screen
+pub fn render_minimap(frame: &mut Frame, app: &App, area: Rect, on_screen: &[usize]) {
+    let window = match (on_screen.first(), on_screen.last()) {
+        (Some(&first), Some(&last)) => first..last + 1,
+        _ => 0..0,
+    };
+    let total = app.document.column_count();
+    let cells = minimap_cells(
+        total,
+        area.width as usize,
+        window,
+        app.view_state.selected_column.get(),
+    );
+    let marks = minimap_marks(total, area.width as usize, &app.view_state.marks.columns());
+
+    let spans: Vec<Span> = cells
+        .into_iter()
+        .zip(marks)
+        .map(|(cell, mark)| {
+            let style = match (cell, mark) {
+                (MinimapCell::Cursor, _) => Style::default().fg(app.theme.mode_color(app.mode)),
+                (_, Some(kind)) => Style::default().fg(kind.color()),
+                (MinimapCell::Outside, None) => Style::default().add_modifier(Modifier::DIM),
+                (MinimapCell::Visible, None) => Style::default(),
+            };
+            Span::styled(cell.symbol(), style)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MinimapCell::*;
+
+    #[test]
+    fn test_few_columns_spread_over_strip() {
+        // 4 columns over 8 cells: two cells each
+        let cells = minimap_cells(4, 8, 1..3, 2);
+        assert_eq!(
+            cells,
+            vec![Outside, Outside, Visible, Visible, Cursor, Cursor, Outside, Outside]
+        );
+    }
+
+    #[test]
+    fn test_many_columns_share_cells() {
+        // 100 columns over 10 cells: ten columns per cell
+        let cells = minimap_cells(100, 10, 40..55, 95);
+        assert_eq!(cells[4], Visible);
+        assert_eq!(cells[5], Visible);
+        assert_eq!(cells[6], Outside);
+        assert_eq!(cells[9], Cursor);
+    }
+
+    #[test]
+    fn test_column_at_maps_back_to_columns() {
+        let none = BTreeMap::new();
+        assert_eq!(column_at(0, 8, 4, &none), 0);
+        assert_eq!(column_at(5, 8, 4, &none), 2);
+        assert_eq!(column_at(7, 8, 4, &none), 3);
+        assert_eq!(column_at(3, 10, 100, &none), 30);
+        // Clicks past the end clamp to the last cell
+        assert_eq!(column_at(50, 10, 100, &none), 90);
+        assert_eq!(column_at(0, 0, 5, &none), 0);
+    }
+
+    #[test]
+    fn test_marked_columns_tint_cells_and_attract_clicks() {
+        let marks: BTreeMap<usize, MarkKind> =
+            [(34, MarkKind::SearchHit), (36, MarkKind::Error)].into();
+
+        let tints = minimap_marks(100, 10, &marks);
+        assert_eq!(tints[3], Some(MarkKind::Error));
+        assert_eq!(tints[4], None);
+
+        assert_eq!(column_at(3, 10, 100, &marks), 34);
+    }
+}
@@ -1,40 +1,143 @@
+pub mod bookmarks;
+pub mod column_groups;
+pub mod column_width;
+mod decimal_align;
+pub mod file_browser;
+pub mod file_picker;
+pub mod frequency;
+pub mod grep;
 mod help;
+mod hints;
+pub mod list_cursor;
+pub mod magnifier;
+pub mod marks;
+pub mod minimap;
+pub mod number_format;
+pub mod preview;
+mod record;
+pub mod row_filter;
+pub mod row_groups;
+pub mod scrollbar;
+mod stats;
 mod status;
 mod table;
+pub mod theme;
 pub mod utils;
 pub mod view_state;
 
-/// Maximum number of columns to display simultaneously
-/// This prevents horizontal overflow on standard terminals
-pub const MAX_VISIBLE_COLS: usize = 10;
-
+use crate::app::{Mode, SplitDirection};
 use crate::App;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
+/// Draw the table of the pane without the focus, as if in Normal mode and
+/// leaving the terminal cursor to the focused pane
+fn render_other_pane(frame: &mut Frame, app: &mut App, area: Rect) {
+    let mode = std::mem::replace(&mut app.mode, Mode::Normal);
+    let edit_buffer = app.edit_buffer.take();
+    let cursor_shapes = std::mem::replace(&mut app.theme.cursor_shapes, false);
+    app.swap_pane();
+    table::render_table(frame, app, area);
+    app.swap_pane();
+    app.mode = mode;
+    app.edit_buffer = edit_buffer;
+    app.theme.cursor_shapes = cursor_shapes;
+}
+
 /// Main UI rendering function
 pub fn render(frame: &mut Frame, app: &mut App) {
     // Split terminal into main area + file switcher + status bar
     // Minimal layout: no heavy borders, just horizontal rules as separators
+    let preview_height = u16::from(app.options.preview);
+    let hints_height = u16::from(app.options.hints);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(0),    // Table area (includes title bar + rule)
-            Constraint::Length(2), // File switcher (rule + file list)
-            Constraint::Length(1), // Status bar (single line, vim-like)
+            Constraint::Min(0),                 // Table area (includes title bar + rule)
+            Constraint::Length(2),              // File switcher (rule + file list)
+            Constraint::Length(preview_height), // Cell preview (optional)
+            Constraint::Length(1),              // Status bar (single line, vim-like)
+            Constraint::Length(hints_height),   // Key hints (optional)
         ])
         .split(frame.area());
 
-    // Render table with row/column numbers
-    table::render_table(frame, app, chunks[0]);
+    // With a split, the other pane's table goes above or left of the
+    // focused one, or below or right of it
+    let table_area = match app.split.as_ref() {
+        Some(split) => {
+            let direction = match split.direction {
+                SplitDirection::Horizontal => Direction::Vertical,
+                SplitDirection::Vertical => Direction::Horizontal,
+            };
+            let [first, second] = Layout::default()
+                .direction(direction)
+                .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                .spacing(1)
+                .areas(chunks[0]);
+            let (focused, other) = if split.focus_second {
+                (second, first)
+            } else {
+                (first, second)
+            };
+            render_other_pane(frame, app, other);
+            focused
+        }
+        None => chunks[0],
+    };
+
+    // Render table with row/column numbers (or the magnifier, grep,
+    // frequency, bookmark or record view in its place)
+    if app.magnifier.is_some() {
+        magnifier::render_magnifier(frame, app, table_area);
+    } else if let Some(view) = app.grep.as_ref().filter(|view| view.visible) {
+        grep::render_grep_view(frame, app, view, table_area);
+    } else if let Some(view) = &app.view_state.frequency_view {
+        frequency::render_frequency_view(frame, app, view, table_area);
+    } else if let Some(view) = &app.view_state.bookmark_view {
+        bookmarks::render_bookmark_view(frame, app, view, table_area);
+    } else if app.view_state.record_view {
+        record::render_record_view(frame, app, table_area);
+    } else {
+        table::render_table(frame, app, table_area);
+    }
 
     // Render file switcher (always visible)
     status::render_file_switcher(frame, app, chunks[1]);
 
+    // Render the cell preview line
+    if app.options.preview {
+        preview::render_cell_preview(frame, app, chunks[2]);
+    }
+
     // Render status bar
-    status::render_status_bar(frame, app, chunks[2]);
+    status::render_status_bar(frame, app, chunks[3]);
+
+    // Render the key hints for the current mode
+    if app.options.hints {
+        hints::render_hints_bar(frame, app, chunks[4]);
+    }
+
+    // Echo recent keys in the bottom-right corner of the table area
+    if app.options.show_keys {
+        status::render_key_echo(frame, app, chunks[0]);
+    }
+
+    // Render column statistics panel if open
+    if let Some(stats) = &app.view_state.stats_panel {
+        stats::render_stats_panel(frame, stats, &app.options.locale());
+    }
+
+    // Render the file browser, like the picker, over everything but help
+    if let Some(browser) = &app.view_state.file_browser {
+        file_browser::render_file_browser(frame, app, browser);
+    }
+
+    // Render the file picker over everything but help
+    if let Some(picker) = &app.view_state.file_picker {
+        file_picker::render_file_picker(frame, app, picker);
+    }
 
     // Render help overlay if active
     if app.view_state.help_overlay_visible {
@@ -43,7 +146,22 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 }
 
 // Re-export public utilities and types
-pub use utils::column_to_excel_letter;
+pub use bookmarks::BookmarkView;
+pub use column_groups::{ColumnGroup, ColumnGroups, ColumnGroupsConfig};
+pub use file_browser::FileBrowser;
+pub use file_picker::FilePicker;
+pub use frequency::FrequencyView;
+pub use grep::GrepView;
+pub use help::help_line_count;
+pub use list_cursor::ListCursor;
+pub use magnifier::Magnifier;
+pub use marks::{Mark, MarkKind, Marks};
+pub use number_format::NumberFormat;
+pub use row_filter::{Comparison, RowFilter};
+pub use row_groups::{RowGroup, RowGroups};
+pub use status::DIRTY_MARKER;
+pub use theme::{Theme, ThemeConfig};
+pub use utils::{column_to_excel_letter, group_thousands};
 pub use view_state::{ViewState, ViewportMode};
 
 #[cfg(test)]
@@ -77,6 +195,7 @@ mod tests {
             ],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         }
     }
 
@@ -153,6 +272,650 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ui_renders_record_view() -> io::Result<()> {
+        let csv_data = create_test_csv();
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+        app.view_state.record_view = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+
+        assert!(line(0).contains("Record: row 1 of"));
+        // One line per field: letter, header (padded to at least 6), value
+        assert!(line(1).contains("A     ID      1"));
+        assert!(line(3).contains("C     Email   alice@example.com"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_magnifier_with_matches() -> io::Result<()> {
+        let mut csv_data = create_test_csv();
+        csv_data.rows[0][2] = format!("{} needle {}", "x".repeat(150), "y".repeat(50));
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.magnifier = Some(crate::ui::Magnifier::new(0, 2, &app.document.rows[0][2]));
+        app.mode = crate::app::Mode::Magnifier;
+        let needle = crate::app::search::SearchPattern::new("needle", &app.options).unwrap();
+        app.magnifier.as_mut().unwrap().search(&needle);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..80).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with(" C1 Email: 208 chars, 3 lines  /needle 1/1"));
+        // Wrapped at 80 columns, the match is on the second line
+        assert!(line(2).contains("needle"));
+        let x = line(2).find("needle").unwrap() as u16;
+        assert_eq!(buffer[(x, 2)].bg, ratatui::style::Color::White);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_stats_panel() -> io::Result<()> {
+        let csv_data = create_test_csv();
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+        app.view_state.stats_panel = Some(crate::analysis::ColumnStats::compute(
+            &app.document,
+            crate::domain::position::ColIndex::new(0),
+            &crate::csv::Locale::default(),
+        ));
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend)?;
+        terminal.draw(|frame| {
+            render(frame, &mut app);
+        })?;
+
+        let content = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+        assert!(content.contains("Stats: A"));
+        assert!(content.contains("Distinct"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_both_panes_of_a_split() -> io::Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let files = vec![dir.path().join("left.csv"), dir.path().join("right.csv")];
+        std::fs::write(&files[0], "city\nOslo\n")?;
+        std::fs::write(&files[1], "city\nRome\n")?;
+        let csv_data = Document::from_file(&files[0], None, false, None).unwrap();
+        let mut app = App::new(csv_data, files, 0, crate::session::FileConfig::new());
+        app.open_split(crate::app::SplitDirection::Vertical)
+            .unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+        let screen: Vec<String> = (0..buffer.area.height).map(line).collect();
+        let left = screen.iter().position(|l| l.contains("Oslo")).unwrap();
+        let right = screen.iter().position(|l| l.contains("Rome")).unwrap();
+        // Side by side: the same row, Oslo on the left
+        assert_eq!(left, right);
+        assert!(screen[left].find("Oslo") < screen[left].find("Rome"));
+        assert!(screen[0].contains("left.csv") && screen[0].contains("right.csv"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_frequency_view_and_filter() -> io::Result<()> {
+        let csv_data = create_test_csv();
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+        let column = crate::domain::position::ColIndex::new(0);
+        let render_to_string = |app: &mut App| -> io::Result<String> {
+            let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+            terminal.draw(|frame| render(frame, app))?;
+            Ok(terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|c| c.symbol())
+                .collect())
+        };
+
+        app.view_state.frequency_view = Some(FrequencyView::new(&app.document, column));
+        assert!(render_to_string(&mut app)?.contains("Frequency: A"));
+
+        let value = app.document.rows[0][0].clone();
+        app.view_state.frequency_view = None;
+        app.view_state.row_filter = Some(RowFilter::matching(&app.document, column, &value));
+        assert!(render_to_string(&mut app)?.contains(&format!("[A={}] 1/1", value)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_shows_nulls() -> io::Result<()> {
+        let csv_data = Document {
+            headers: vec!["n".to_string(), "v".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "x".to_string()],
+                vec!["2".to_string(), String::new()],
+                vec!["3".to_string(), "NULL".to_string()],
+            ],
+            filename: "test.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.selected_column = crate::domain::position::ColIndex::new(1);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        let screen = |terminal: &Terminal<TestBackend>| -> Vec<String> {
+            let buffer = terminal.backend().buffer();
+            (0..24)
+                .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect())
+                .collect()
+        };
+
+        terminal.draw(|frame| render(frame, &mut app))?;
+        assert!(!screen(&terminal).iter().any(|l| l.contains('·')));
+
+        app.options.show_nulls = true;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let lines = screen(&terminal);
+        let (y, line) = lines
+            .iter()
+            .enumerate()
+            .find(|(_, l)| l.contains('·'))
+            .unwrap();
+        let x = line.chars().position(|c| c == '·').unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(
+            buffer[(x as u16, y as u16)].bg,
+            ratatui::style::Color::Indexed(236)
+        );
+        assert!(lines.iter().any(|l| l.contains("2 empty [")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_colors_marked_cells() -> io::Result<()> {
+        let csv_data = Document {
+            headers: vec!["n".to_string(), "v".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "good".to_string()],
+                vec!["2".to_string(), "bad".to_string()],
+            ],
+            filename: "test.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.marks.add(1, Some(1), MarkKind::Error);
+        // Changed cells are only shown on the scrollbar
+        app.view_state.marks.add(0, Some(1), MarkKind::Changed);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let find = |text: &str| {
+            (0..24u16)
+                .find_map(|y| {
+                    let line: String = (0..80u16).map(|x| buffer[(x, y)].symbol()).collect();
+                    line.find(text).map(|x| (x as u16, y))
+                })
+                .unwrap()
+        };
+        assert_eq!(buffer[find("bad")].fg, MarkKind::Error.color());
+        assert_eq!(buffer[find("good")].fg, ratatui::style::Color::Reset);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_empty_documents() -> io::Result<()> {
+        let render_text = |headers: Vec<String>| -> io::Result<String> {
+            let csv_data = Document {
+                headers,
+                filename: "empty.csv".to_string(),
+                ..Default::default()
+            };
+            let mut app = App::new(
+                csv_data,
+                vec![PathBuf::from("empty.csv")],
+                0,
+                crate::session::FileConfig::new(),
+            );
+            let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+            terminal.draw(|frame| render(frame, &mut app))?;
+            Ok(terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|c| c.symbol())
+                .collect())
+        };
+
+        let header_only = render_text(vec!["id".to_string(), "name".to_string()])?;
+        assert!(header_only.contains("No data rows — press o to add one"));
+        assert!(header_only.contains("0/0"));
+
+        let empty = render_text(Vec::new())?;
+        assert!(empty.contains("lazycsv: empty.csv (empty file)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_scrollbar_with_marks() -> io::Result<()> {
+        let rows = (0..100)
+            .map(|i| vec![i.to_string(), format!("v{}", i)])
+            .collect();
+        let csv_data = Document {
+            headers: vec!["n".to_string(), "v".to_string()],
+            rows,
+            filename: "long.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("long.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.options.scrollbar = true;
+        app.view_state.marks.add(99, None, MarkKind::Error);
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+
+        let buffer = terminal.backend().buffer();
+        let column: Vec<String> = (0..24)
+            .map(|y| buffer[(39, y)].symbol().to_string())
+            .collect();
+        // Thumb at the top of the track, mark for the last row near the bottom
+        assert_eq!(column[4], "█");
+        let tick = column.iter().position(|s| s == "▬").unwrap();
+        assert_eq!(buffer[(39, tick as u16)].fg, MarkKind::Error.color());
+
+        // Clicking the tick jumps to the marked row
+        let click = crossterm::event::MouseEvent {
+            kind: crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left),
+            column: 39,
+            row: tick as u16,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+        assert_eq!(app.handle_mouse(click), Some(crate::InputResult::Continue));
+        assert_eq!(
+            app.get_selected_row(),
+            Some(crate::domain::position::RowIndex::new(99))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_frozen_columns_with_divider() -> io::Result<()> {
+        let csv_data = Document {
+            headers: (0..30).map(|i| format!("h{}", i)).collect(),
+            rows: vec![(0..30).map(|i| format!("v{}", i)).collect()],
+            filename: "wide.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("wide.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.frozen_columns = 1;
+        app.view_state.column_scroll_offset = 20;
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+
+        let buffer = terminal.backend().buffer();
+        let header: String = (0..120).map(|x| buffer[(x, 3)].symbol()).collect();
+        let h0 = header.find("h0").unwrap();
+        let divider = header.find('│').unwrap();
+        let h20 = header.find("h20").unwrap();
+        assert!(h0 < divider && divider < h20);
+        assert!(!header.contains("h1 "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_column_scrollbar_follows_columns() -> io::Result<()> {
+        let csv_data = Document {
+            headers: (0..30).map(|i| format!("h{}", i)).collect(),
+            rows: vec![(0..30).map(|i| format!("v{}", i)).collect()],
+            filename: "wide.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("wide.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.options.scrollbar = true;
+        app.view_state.frozen_columns = 1;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        // The track runs under the data columns, above the file switcher
+        let (track, positions) = app.view_state.column_scrollbar.clone().unwrap();
+        assert_eq!(track.y, 20);
+        assert_eq!(positions, 1..30);
+        let bar = |terminal: &Terminal<TestBackend>| -> String {
+            let buffer = terminal.backend().buffer();
+            (track.x..track.right())
+                .map(|x| buffer[(x, track.y)].symbol())
+                .collect()
+        };
+        assert!(bar(&terminal).starts_with('█'));
+        assert!(!bar(&terminal).ends_with('█'));
+
+        // Clicking the end of the track scrolls to the last columns
+        let click = crossterm::event::MouseEvent {
+            kind: crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left),
+            column: track.right() - 1,
+            row: track.y,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+        app.handle_mouse(click);
+        assert_eq!(app.view_state.selected_column.get(), 29);
+        terminal.draw(|frame| render(frame, &mut app))?;
+        assert!(bar(&terminal).ends_with('█'));
+        assert!(!bar(&terminal).starts_with('█'));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_cell_preview_line() -> io::Result<()> {
+        let mut csv_data = create_test_csv();
+        csv_data.rows[0][2] = format!("start {} end", "x".repeat(100));
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.selected_column = crate::domain::position::ColIndex::new(2);
+        app.options.preview = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        let preview_line = |terminal: &Terminal<TestBackend>| -> String {
+            let buffer = terminal.backend().buffer();
+            (0..80).map(|x| buffer[(x, 22)].symbol()).collect()
+        };
+
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let line = preview_line(&terminal);
+        assert!(line.starts_with(" C1 Email: start xxx"));
+        assert!(line.ends_with('›'));
+
+        // Scrolling far right shows the end of the value
+        app.view_state.preview_scroll = 500;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let line = preview_line(&terminal);
+        assert!(line.contains("‹xxx"));
+        assert!(line.trim_end().ends_with("x end"));
+
+        // Moving to another cell resets the scroll
+        app.view_state.table_state.select(Some(1));
+        terminal.draw(|frame| render(frame, &mut app))?;
+        assert_eq!(app.view_state.preview_scroll, 0);
+        assert!(preview_line(&terminal).starts_with(" C2 Email: bob@example.com"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_aligns_decimal_points() -> io::Result<()> {
+        let csv_data = Document {
+            headers: vec!["item".to_string(), "price".to_string()],
+            rows: [("a", "12.5"), ("b", "3"), ("c", "0.125")]
+                .iter()
+                .map(|(item, price)| vec![item.to_string(), price.to_string()])
+                .collect(),
+            filename: "test.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        let lines = |terminal: &Terminal<TestBackend>| -> Vec<String> {
+            let buffer = terminal.backend().buffer();
+            (0..24)
+                .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect())
+                .collect()
+        };
+        let dot_column = |lines: &[String], value: &str| {
+            let line = lines.iter().find(|l| l.contains(value)).unwrap();
+            line.find(value).unwrap() + value.find('.').unwrap()
+        };
+
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let plain = lines(&terminal);
+        assert_ne!(dot_column(&plain, "12.5"), dot_column(&plain, "0.125"));
+
+        app.options.align_decimals = true;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let aligned = lines(&terminal);
+        let dot = dot_column(&aligned, "12.5");
+        assert_eq!(dot_column(&aligned, "0.125"), dot);
+        // Integers end where the decimal point would be
+        assert!(aligned.iter().any(|l| l.get(dot - 1..=dot) == Some("3 ")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_shows_column_types_and_right_aligns_numbers() -> io::Result<()> {
+        let csv_data = Document {
+            headers: vec!["item".to_string(), "qty".to_string()],
+            rows: [("apple", "7"), ("pear", "1200")]
+                .iter()
+                .map(|(item, qty)| vec![item.to_string(), qty.to_string()])
+                .collect(),
+            filename: "test.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = (0..24)
+            .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("A text") && l.contains("B int")));
+        let end = |value: &str| {
+            let line = lines.iter().find(|l| l.contains(value)).unwrap();
+            line.find(value).unwrap() + value.len()
+        };
+        assert_eq!(end(" 7"), end("1200"));
+        // Text stays left-aligned
+        assert_ne!(end("apple"), end("pear"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_last_column_pinned_right() -> io::Result<()> {
+        let csv_data = Document {
+            headers: (0..30).map(|i| format!("h{}", i)).collect(),
+            rows: vec![(0..30).map(|i| format!("v{}", i)).collect()],
+            filename: "wide.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("wide.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.view_state.frozen_last = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+
+        // The last column sits at the right edge, behind a divider
+        let buffer = terminal.backend().buffer();
+        let header: String = (0..120).map(|x| buffer[(x, 3)].symbol()).collect();
+        let divider = header.find('│').unwrap();
+        let h29 = header.find("h29").unwrap();
+        assert!(header.contains("h0 "));
+        assert!(divider < h29);
+        assert_eq!(header.len() - h29, 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mouse_selects_cells_scrolls_and_switches_files() -> io::Result<()> {
+        use crate::app::Mode;
+        use crate::domain::position::{ColIndex, RowIndex};
+        use crate::InputResult;
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let csv_files = vec![PathBuf::from("a.csv"), PathBuf::from("b.csv")];
+        let mut app = App::new(
+            create_test_csv(),
+            csv_files,
+            0,
+            crate::session::FileConfig::new(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+
+        // Click on the second data row, third column
+        let data = app.view_state.data_area.expect("table rendered");
+        let &(_, email_x, _) = app.view_state.column_spans.get(2).unwrap();
+        let click = mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            email_x + 1,
+            data.y + 1,
+        );
+        assert_eq!(app.handle_mouse(click), Some(InputResult::Continue));
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+        assert_eq!(app.view_state.selected_column, ColIndex::new(2));
+
+        // Dragging to the first cell selects the block in Visual mode
+        let drag = mouse(MouseEventKind::Drag(MouseButton::Left), data.x + 6, data.y);
+        app.handle_mouse(drag);
+        assert_eq!(app.mode, Mode::Visual);
+        assert_eq!(app.visual_selection(), Some((0..=1, 0..=2)));
+
+        // The wheel moves rows; a click ends the selection
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, data.x, data.y));
+        assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+        app.handle_mouse(click);
+        assert_eq!(app.mode, Mode::Normal);
+
+        // Clicks outside the table and switcher do nothing
+        let title = mouse(MouseEventKind::Down(MouseButton::Left), 1, 0);
+        assert_eq!(app.handle_mouse(title), None);
+
+        // Clicking a file name in the switcher opens it
+        let &(index, tab) = app.view_state.file_tabs.get(1).unwrap();
+        assert_eq!(index, 1);
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), tab.x, tab.y);
+        assert_eq!(app.handle_mouse(click), Some(InputResult::ReloadFile));
+        assert_eq!(app.session.active_file_index(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimap_click_jumps_to_column() -> io::Result<()> {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        let csv_data = create_test_csv();
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
+        app.options.minimap = true;
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend)?;
+        terminal.draw(|frame| {
+            render(frame, &mut app);
+        })?;
+
+        let area = app.view_state.minimap_area.expect("minimap rendered");
+        assert_eq!(area.y, 1);
+        let strip: String = (area.x..area.right())
+            .map(|x| {
+                terminal.backend().buffer()[(x, area.y)]
+                    .symbol()
+                    .to_string()
+            })
+            .collect();
+        assert!(strip.starts_with('█'));
+
+        // Clicking the right end of the strip selects the last column
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: area.right() - 1,
+            row: area.y,
+            modifiers: KeyModifiers::NONE,
+        };
+        assert_eq!(app.handle_mouse(click), Some(crate::InputResult::Continue));
+        assert_eq!(
+            app.view_state.selected_column.get(),
+            app.document.column_count() - 1
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_ui_renders_multi_file_switcher() -> io::Result<()> {
         let csv_data = create_test_csv();
@@ -285,6 +1048,7 @@ mod tests {
             ],
             filename: "small.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         }
     }
 
@@ -294,6 +1058,7 @@ mod tests {
             rows: vec![],
             filename: "empty.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         }
     }
 
@@ -303,6 +1068,7 @@ mod tests {
             rows: vec![vec!["1".to_string()]],
             filename: "single.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         }
     }
 
@@ -471,7 +1237,7 @@ mod tests {
             .collect::<String>();
 
         // Set status message
-        app.status_message = Some("Test message".into());
+        app.status_messages.push("Test message");
         terminal.draw(|frame| {
             render(frame, &mut app);
         })?;
@@ -548,6 +1314,193 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ui_file_switcher_marks_unsaved_file() -> io::Result<()> {
+        let csv_files = vec![PathBuf::from("first.csv"), PathBuf::from("second.csv")];
+        let mut app = App::new(
+            create_small_csv(),
+            csv_files,
+            1,
+            crate::session::FileConfig::new(),
+        );
+        app.document.is_dirty = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("first.csv | second.csv[+]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_header_and_gutter_stay_pinned() -> io::Result<()> {
+        let document = Document {
+            headers: (1..=30).map(|col| format!("col{}", col)).collect(),
+            rows: (1..=500)
+                .map(|row| (1..=30).map(|col| format!("r{}c{}", row, col)).collect())
+                .collect(),
+            filename: "wide.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("wide.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        // Bottom-right corner of a tall terminal
+        app.view_state.table_state.select(Some(499));
+        crate::navigation::commands::select_column(&mut app, 29);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 60))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+
+        // The body fills the table area below the title, rule and header:
+        // everything above the file switcher and status bar
+        assert_eq!(app.view_state.table_height, 60 - 3 - 4);
+        assert!(line(2).contains("AD"));
+        assert!(line(3).contains("col30"));
+        // Row numbers are still beside the scrolled columns
+        let last = line(3 + app.view_state.table_height as u16);
+        assert!(last.starts_with(" 500"));
+        assert!(last.contains("r500c30"));
+        assert!(line(4).starts_with(&format!("{:>4}", 500 - 53 + 1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_renders_header_only_file_in_short_terminal() -> io::Result<()> {
+        let document = Document {
+            headers: vec!["a".to_string()],
+            filename: "empty.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("empty.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+
+        // No room for a row, then room for one
+        for height in [7, 8] {
+            let mut terminal = Terminal::new(TestBackend::new(40, height))?;
+            terminal.draw(|frame| render(frame, &mut app))?;
+            assert_eq!(app.view_state.row_scroll_offset, 0);
+            assert_eq!(app.view_state.data_area.map(|area| area.height), Some(0));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_wraps_cursor_row() -> io::Result<()> {
+        let document = Document {
+            headers: vec!["id".to_string(), "note".to_string()],
+            rows: vec![
+                vec!["1".to_string(), "short".to_string()],
+                vec![
+                    "2".to_string(),
+                    "the quick brown fox jumps over the lazy dog again".to_string(),
+                ],
+                vec!["3".to_string(), "end".to_string()],
+            ],
+            filename: "notes.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("notes.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.options.max_width = 20;
+        app.view_state.table_state.select(Some(1));
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 24))?;
+        let mut lines = |app: &mut App| -> io::Result<Vec<String>> {
+            terminal.draw(|frame| render(frame, app))?;
+            let buffer = terminal.backend().buffer();
+            Ok((0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect()
+                })
+                .collect())
+        };
+
+        // Cut short on one line by default
+        let screen = lines(&mut app)?;
+        assert!(screen[5].contains("the quick brown fox…"));
+        assert!(screen[6].starts_with("   3"));
+
+        app.options.wrap = true;
+        let screen = lines(&mut app)?;
+        let height = app.view_state.cursor_row_lines;
+        assert_eq!(height, 3);
+        assert!(screen[5].starts_with("   2"));
+        assert!(screen[5].contains("the quick brown fox"));
+        assert!(screen[6].contains("jumps over the lazy"));
+        assert!(screen[7].trim_start().starts_with("dog again"));
+        // The next row moves down below it
+        assert!(screen[8].starts_with("   3"));
+        assert!(screen[8].contains("end"));
+
+        // Clicks on its lines land on the wrapped row
+        let x = app.view_state.column_spans[1].1;
+        let cell_at = |y| app.view_state.cell_at(ratatui::layout::Position::new(x, y));
+        assert_eq!(cell_at(7), Some((1, Some(1))));
+        assert_eq!(cell_at(8), Some((2, Some(1))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_shows_number_format() -> io::Result<()> {
+        let document = Document {
+            headers: vec!["amount".to_string()],
+            rows: vec![vec!["1234567.891".to_string()], vec!["n/a".to_string()]],
+            filename: "amounts.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![PathBuf::from("amounts.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.options.locale = Some(crate::csv::Locale::default());
+        app.format_column(", .2").unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 24))?;
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+        assert!(line(4).contains("1,234,567.89"));
+        assert!(line(5).contains("n/a"));
+        assert_eq!(app.document.rows[0][0], "1234567.891");
+
+        Ok(())
+    }
+
     #[test]
     fn test_ui_dirty_indicator() -> io::Result<()> {
         let mut csv_data = create_small_csv();
@@ -735,6 +1688,7 @@ mod tests {
             ],
             filename: "emoji.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("emoji.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -776,6 +1730,7 @@ mod tests {
             rows: vec![vec!["Alice".to_string(), long_text]],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -791,6 +1746,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ui_marks_truncated_cells() -> io::Result<()> {
+        let mut csv_data = create_test_csv();
+        csv_data.rows[0][0] = "x".repeat(200);
+        let mut app = App::new(
+            csv_data,
+            vec![PathBuf::from("test.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        let screen = |terminal: &Terminal<TestBackend>| -> String {
+            let buffer = terminal.backend().buffer();
+            (0..24)
+                .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n")
+                .collect()
+        };
+
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let content = screen(&terminal);
+        assert!(content.contains("xxx…"));
+        // The status bar says how much of the selected cell it leaves out
+        assert!(content.contains(&format!("\"{}…\" (+171 chars)", "x".repeat(29))));
+
+        app.options.set("ellipsis=>>").unwrap();
+        terminal.draw(|frame| render(frame, &mut app))?;
+        let content = screen(&terminal);
+        assert!(content.contains("xxx>>"));
+        assert!(!content.contains('…'));
+        Ok(())
+    }
+
     #[test]
     fn test_ui_special_characters_in_cells() -> io::Result<()> {
         let csv_data = Document {
@@ -801,6 +1788,7 @@ mod tests {
             ],
             filename: "test.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("test.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -904,6 +1892,7 @@ mod tests {
             ],
             filename: "unicode.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("unicode.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -945,6 +1934,7 @@ mod tests {
             ],
             filename: "long.csv".to_string(),
             is_dirty: false,
+            ..Default::default()
         };
         let csv_files = vec![PathBuf::from("long.csv")];
         let mut app = App::new(csv_data, csv_files, 0, crate::session::FileConfig::new());
@@ -959,4 +1949,71 @@ mod tests {
         // Should handle long content with truncation
         Ok(())
     }
+
+    #[test]
+    fn test_ui_insert_mode_segment_color_and_cursor() -> io::Result<()> {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(
+            create_test_csv(),
+            csv_files,
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.handle_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE))
+            .unwrap();
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend)?;
+        terminal.draw(|f| render(f, &mut app))?;
+
+        let cursor = terminal.get_cursor_position()?;
+
+        // Mode segment on the status line uses the insert color
+        let buffer = terminal.backend().buffer();
+        let cell = &buffer[(1, 23)];
+        assert_eq!(cell.symbol(), "I");
+        assert_eq!(cell.bg, app.theme.insert);
+
+        // Terminal cursor sits after "1" in the first data cell (row 1, column A)
+        assert_eq!(cursor.y, 4);
+        assert_eq!(buffer[(cursor.x - 1, cursor.y)].symbol(), "1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_key_echo_shows_keys_action_and_command() -> io::Result<()> {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let csv_files = vec![PathBuf::from("test.csv")];
+        let mut app = App::new(
+            create_test_csv(),
+            csv_files,
+            0,
+            crate::session::FileConfig::new(),
+        );
+        for c in ":set showkeys".chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .unwrap();
+        }
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE))
+            .unwrap();
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend)?;
+        terminal.draw(|f| render(f, &mut app))?;
+
+        let content = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+        assert!(content.contains("j  Move down  :set showkeys"));
+        Ok(())
+    }
 }
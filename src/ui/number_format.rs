@@ -0,0 +1,125 @@
+//! How the numbers of a column are shown (`:fmt`): grouped by thousands,
+//! with a fixed number of decimals or in scientific notation.
+//!
+//! Only the display changes: cells keep the text they were read with, and
+//! are edited, searched and saved as written. Cells that aren't numbers are
+//! shown as they are.
+
+use crate::csv::Locale;
+use std::fmt;
+
+/// Most decimals `:fmt .N` accepts
+const MAX_DECIMALS: usize = 12;
+
+/// Decimals shown in scientific notation unless `.N` says otherwise
+const SCIENTIFIC_DECIMALS: usize = 2;
+
+/// Display format of a numeric column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Group thousands (`1,234,567`)
+    pub thousands: bool,
+    /// Decimals shown, rounding or padding with zeros
+    pub decimals: Option<usize>,
+    /// Scientific notation (`1.23e6`)
+    pub scientific: bool,
+}
+
+impl NumberFormat {
+    /// Change the format by a `:fmt` word: `,` groups thousands, `.2`
+    /// shows two decimals, `sci` scientific notation; `no,`, `.` and
+    /// `nosci` undo them and `off` all of them
+    pub fn apply(&mut self, word: &str) -> Result<(), String> {
+        match word {
+            "," | "thousands" => self.thousands = true,
+            "no," | "nothousands" => self.thousands = false,
+            "sci" | "scientific" => self.scientific = true,
+            "nosci" | "noscientific" => self.scientific = false,
+            "." => self.decimals = None,
+            "off" | "none" => *self = Self::default(),
+            _ => {
+                let decimals = word
+                    .strip_prefix('.')
+                    .and_then(|count| count.parse::<usize>().ok())
+                    .filter(|&count| count <= MAX_DECIMALS)
+                    .ok_or_else(|| {
+                        format!(
+                            "Unknown format: {} (use , .2 sci or off; .N up to .{})",
+                            word, MAX_DECIMALS
+                        )
+                    })?;
+                self.decimals = Some(decimals);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `word` is one `apply` understands
+    pub fn is_word(word: &str) -> bool {
+        Self::default().apply(word).is_ok()
+    }
+
+    /// `value` shown in this format, or None when it isn't a number
+    pub fn format(&self, value: &str, locale: &Locale) -> Option<String> {
+        if self.scientific {
+            locale.format_scientific(value, self.decimals.unwrap_or(SCIENTIFIC_DECIMALS))
+        } else {
+            locale.format_fixed(value, self.decimals, self.thousands)
+        }
+    }
+}
+
+impl fmt::Display for NumberFormat {
+    /// The `:fmt` words giving this format (`off` for none)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = Vec::new();
+        if self.thousands {
+            words.push(",".to_string());
+        }
+        if let Some(decimals) = self.decimals {
+            words.push(format!(".{}", decimals));
+        }
+        if self.scientific {
+            words.push("sci".to_string());
+        }
+        if words.is_empty() {
+            words.push("off".to_string());
+        }
+        f.write_str(&words.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(words: &[&str]) -> NumberFormat {
+        let mut format = NumberFormat::default();
+        for word in words {
+            format.apply(word).unwrap();
+        }
+        format
+    }
+
+    #[test]
+    fn test_number_format_words() {
+        let money = format(&[",", ".2"]);
+        assert_eq!(money.to_string(), ", .2");
+        let locale = Locale::default();
+        assert_eq!(money.format("1234567.8", &locale).unwrap(), "1,234,567.80");
+        assert_eq!(money.format("N/A", &locale), None);
+
+        let scientific = format(&["sci"]);
+        assert_eq!(scientific.format("0.000123", &locale).unwrap(), "1.23e-4");
+        assert_eq!(
+            format(&["sci", ".0"]).format("98765", &locale).unwrap(),
+            "1e5"
+        );
+
+        assert_eq!(format(&[",", "off"]), NumberFormat::default());
+        assert_eq!(format(&[".3", "."]).decimals, None);
+        assert!(NumberFormat::is_word("no,"));
+        assert!(!NumberFormat::is_word("price"));
+        assert!(!NumberFormat::is_word(".99"));
+    }
+}
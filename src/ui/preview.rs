@@ -0,0 +1,115 @@
+//! Preview line showing the full content of the selected cell.
+//!
+//! Shown above the status bar with `:set preview` (or `K`). Long values
+//! scroll sideways with `zh`/`zl` (and `zH`/`zL` by half a line); the scroll
+//! position resets whenever the cursor moves to another cell.
+
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Shown at an edge when there is more content beyond it
+const MORE_LEFT: &str = "‹";
+const MORE_RIGHT: &str = "›";
+
+/// Stands in for a line break inside a cell
+const NEWLINE_SYMBOL: &str = "↵";
+
+/// Cell content on a single line
+pub fn flatten(value: &str) -> String {
+    value
+        .replace("\r\n", NEWLINE_SYMBOL)
+        .replace(['\n', '\r'], NEWLINE_SYMBOL)
+}
+
+/// The visible slice of `value` scrolled by `offset` characters in `width`
+/// cells, with markers where content is cut off
+pub fn scroll_window(value: &str, offset: usize, width: usize) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let offset = offset.min(chars.len());
+    let more_left = offset > 0;
+    let room = width.saturating_sub(usize::from(more_left));
+    let more_right = chars.len() - offset > room;
+    let take = room.saturating_sub(usize::from(more_right));
+
+    let mut window = String::new();
+    if more_left {
+        window.push_str(MORE_LEFT);
+    }
+    window.extend(&chars[offset..offset + take.min(chars.len() - offset)]);
+    if more_right {
+        window.push_str(MORE_RIGHT);
+    }
+    window
+}
+
+/// Label in front of the preview: cell address and header
+fn preview_label(app: &App) -> String {
+    let col = app.view_state.selected_column;
+    let row = app
+        .get_selected_row()
+        .map(|r| r.to_line_number().get())
+        .unwrap_or(0);
+    format!(
+        " {}{} {}: ",
+        crate::ui::column_to_excel_letter(col.get()),
+        row,
+        app.document.get_header(col)
+    )
+}
+
+/// Render the preview line, keeping the scroll offset within the content
+pub fn render_cell_preview(frame: &mut Frame, app: &mut App, area: Rect) {
+    let cell = app
+        .get_selected_row()
+        .map(|row| (row.get(), app.view_state.selected_column.get()));
+    if app.view_state.preview_cell != cell {
+        app.view_state.preview_cell = cell;
+        app.view_state.preview_scroll = 0;
+    }
+
+    let value = cell
+        .map(|(row, col)| flatten(app.document.rows[row].get(col).map_or("", String::as_str)))
+        .unwrap_or_default();
+    let label = preview_label(app);
+    let width = (area.width as usize).saturating_sub(label.chars().count());
+
+    // Don't scroll past the point where the end of the value is visible
+    let len = value.chars().count();
+    let max_scroll = if len <= width {
+        0
+    } else {
+        len - width.saturating_sub(1)
+    };
+    app.view_state.preview_scroll = app.view_state.preview_scroll.min(max_scroll);
+
+    let line = Line::from(vec![
+        Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(scroll_window(&value, app.view_state.preview_scroll, width)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_window_marks_cut_off_content() {
+        assert_eq!(scroll_window("short", 0, 10), "short");
+        assert_eq!(scroll_window("abcdefghij", 0, 5), "abcd›");
+        assert_eq!(scroll_window("abcdefghij", 3, 5), "‹def›");
+        assert_eq!(scroll_window("abcdefghij", 6, 5), "‹ghij");
+        assert_eq!(scroll_window("abc", 9, 5), "‹");
+    }
+
+    #[test]
+    fn test_flatten_line_breaks() {
+        assert_eq!(flatten("a\nb\r\nc"), "a↵b↵c");
+    }
+}
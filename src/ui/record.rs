@@ -0,0 +1,78 @@
+//! Record view (`:record`): the selected row transposed, one field per line.
+//!
+//! Easier to read than the table for wide files. The field under the cursor
+//! is the selected column, so closing the view lands on the same cell.
+
+use super::{preview, utils::column_to_excel_letter};
+use crate::App;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+/// Render the selected row as a list of "Header: value" lines over the table area
+pub fn render_record_view(frame: &mut Frame, app: &App, area: Rect) {
+    let [title_area, table_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+    let row = app.get_selected_row();
+    let title = match row {
+        Some(row) => format!(
+            " Record: row {} of {}   h/l: prev/next row  Esc: back",
+            row.to_line_number().get(),
+            app.document.row_count()
+        ),
+        None => " Record: no rows   Esc: back".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+        title_area,
+    );
+
+    let values = row.and_then(|row| app.document.rows.get(row.get()));
+    let header_width = app
+        .document
+        .headers
+        .iter()
+        .map(|h| h.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(6, 30) as u16;
+
+    let rows = app
+        .document
+        .headers
+        .iter()
+        .enumerate()
+        .map(|(col, header)| {
+            let value = values
+                .and_then(|values| values.get(col))
+                .map_or(String::new(), |v| preview::flatten(v));
+            Row::new(vec![
+                Cell::from(column_to_excel_letter(col))
+                    .style(Style::default().add_modifier(Modifier::DIM)),
+                Cell::from(header.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(value),
+            ])
+        });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Length(header_width),
+            Constraint::Min(10),
+        ],
+    )
+    .column_spacing(2)
+    .row_highlight_style(
+        Style::default()
+            .bg(app.theme.mode_color(app.mode))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = TableState::default().with_selected(Some(app.view_state.selected_column.get()));
+    frame.render_stateful_widget(table, table_area, &mut state);
+}
@@ -0,0 +1,303 @@
+//! Row filter limiting the table to rows where a column has a given value
+//! (`:filter col=value`), matches a search pattern (`:filter col~pattern`)
+//! or compares to a value (`:filter date>=2024-01-01`): dates
+//! chronologically and numbers by value.
+//!
+//! The filter keeps the document indices of matching rows; the table's
+//! selection is a position within that list. Row insertions and deletions
+//! shift the stored indices so the filter stays valid while editing.
+
+use crate::analysis::stats::is_null_token;
+use crate::app::search::SearchPattern;
+use crate::csv::{ColumnType, Document, Locale};
+use crate::domain::position::ColIndex;
+use std::cmp::Ordering;
+
+/// How `:filter` compares a column to its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// The comparison `operator` writes (`<`, `<=`, `>`, `>=`)
+    pub fn parse(operator: &str) -> Option<Self> {
+        match operator {
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessOrEqual),
+            ">" => Some(Self::Greater),
+            ">=" => Some(Self::GreaterOrEqual),
+            _ => None,
+        }
+    }
+
+    /// How the comparison is written
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+        }
+    }
+
+    /// Whether a cell ordered this way against the value passes
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Less => ordering.is_lt(),
+            Self::LessOrEqual => ordering.is_le(),
+            Self::Greater => ordering.is_gt(),
+            Self::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+/// Rows of the document shown while a filter is active
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowFilter {
+    pub column: ColIndex,
+    pub value: String,
+    /// Pattern the column matches instead of equalling `value`
+    pub pattern: Option<SearchPattern>,
+    /// Comparison with `value` instead of equality, and the locale reading
+    /// the column
+    pub comparison: Option<(Comparison, Locale)>,
+    /// Matching document rows, ascending
+    rows: Vec<usize>,
+}
+
+impl RowFilter {
+    /// Filter to rows whose `column` equals `value`
+    pub fn matching(document: &Document, column: ColIndex, value: &str) -> Self {
+        let rows = document
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.get(column.get()).map(String::as_str).unwrap_or("") == value)
+            .map(|(idx, _)| idx)
+            .collect();
+
+        Self {
+            column,
+            value: value.to_string(),
+            pattern: None,
+            comparison: None,
+            rows,
+        }
+    }
+
+    /// Filter to rows whose `column` compares to `value` as `comparison`
+    /// says, as the column's type orders them (dates chronologically);
+    /// empty and null cells never pass. Fails when `value` isn't of that
+    /// type, such as a date column compared to text.
+    pub fn comparing(
+        document: &Document,
+        column: ColIndex,
+        comparison: Comparison,
+        value: &str,
+        locale: &Locale,
+    ) -> Result<Self, String> {
+        fn cell(row: &[String], column: ColIndex) -> &str {
+            row.get(column.get()).map_or("", |cell| cell.trim())
+        }
+        let column_locale = locale.for_column(document.rows.iter().map(|row| cell(row, column)));
+        let kind = ColumnType::infer(
+            document.rows.iter().map(|row| cell(row, column)),
+            &column_locale,
+        );
+        let value_kind = ColumnType::of_value(value, &column_locale);
+        let comparable = kind == ColumnType::Text
+            || value_kind == Some(kind)
+            || (kind.is_numeric() && value_kind.is_some_and(ColumnType::is_numeric));
+        if !comparable {
+            return Err(format!(
+                "Can't compare the {} column with {}",
+                kind.label(),
+                value
+            ));
+        }
+
+        let rows = document
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| {
+                let cell = cell(row, column);
+                !cell.is_empty()
+                    && !is_null_token(cell)
+                    && comparison.accepts(kind.compare(cell, value, &column_locale))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        Ok(Self {
+            column,
+            value: value.to_string(),
+            pattern: None,
+            comparison: Some((comparison, locale.clone())),
+            rows,
+        })
+    }
+
+    /// Filter to rows whose `column` matches `pattern`
+    pub fn matching_pattern(document: &Document, column: ColIndex, pattern: SearchPattern) -> Self {
+        let rows = crate::app::search::find_in_column(document, column, &pattern);
+        Self {
+            column,
+            value: pattern.query.clone(),
+            pattern: Some(pattern),
+            comparison: None,
+            rows,
+        }
+    }
+
+    /// The same filter matched against the document again
+    pub fn reapplied(&self, document: &Document) -> Self {
+        if let Some((comparison, locale)) = &self.comparison {
+            // Edits that make the column text keep the rows matched before
+            if let Ok(filter) =
+                Self::comparing(document, self.column, *comparison, &self.value, locale)
+            {
+                return filter;
+            }
+            return self.clone();
+        }
+        match &self.pattern {
+            Some(pattern) => Self::matching_pattern(document, self.column, pattern.clone()),
+            None => Self::matching(document, self.column, &self.value),
+        }
+    }
+
+    /// How the column is compared in the title bar: `=`, `~` or a
+    /// comparison like `>=`
+    pub fn operator(&self) -> &'static str {
+        match (&self.comparison, &self.pattern) {
+            (Some((comparison, _)), _) => comparison.symbol(),
+            (None, Some(_)) => "~",
+            (None, None) => "=",
+        }
+    }
+
+    /// Number of rows shown
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check whether no rows match
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Document row shown at a display position
+    pub fn document_row(&self, display: usize) -> Option<usize> {
+        self.rows.get(display).copied()
+    }
+
+    /// Display position of a document row (None if it is filtered out)
+    pub fn display_row(&self, document_row: usize) -> Option<usize> {
+        self.rows.binary_search(&document_row).ok()
+    }
+
+    /// A row was inserted at `at`; it is shown along with the matching rows
+    pub fn row_inserted(&mut self, at: usize) {
+        for row in self.rows.iter_mut().filter(|r| **r >= at) {
+            *row += 1;
+        }
+        let position = self.rows.partition_point(|&r| r < at);
+        self.rows.insert(position, at);
+    }
+
+    /// The row at `at` was deleted
+    pub fn row_deleted(&mut self, at: usize) {
+        self.rows.retain(|&r| r != at);
+        for row in self.rows.iter_mut().filter(|r| **r > at) {
+            *row -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            headers: vec!["name".to_string(), "city".to_string()],
+            rows: [("a", "Oslo"), ("b", "Rome"), ("c", "Oslo"), ("d", "Oslo")]
+                .iter()
+                .map(|(n, c)| vec![n.to_string(), c.to_string()])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matching_rows() {
+        let filter = RowFilter::matching(&document(), ColIndex::new(1), "Oslo");
+        assert_eq!(filter.len(), 3);
+        assert_eq!(filter.document_row(1), Some(2));
+        assert_eq!(filter.display_row(3), Some(2));
+        assert_eq!(filter.display_row(1), None);
+    }
+
+    #[test]
+    fn test_pattern_rows_are_matched_again() {
+        let mut document = document();
+        let pattern = SearchPattern::new("^(a|b)$", &Default::default()).unwrap();
+        let filter = RowFilter::matching_pattern(&document, ColIndex::new(0), pattern);
+        assert_eq!(filter.len(), 2);
+        assert_eq!(filter.operator(), "~");
+
+        document.rows[2][0] = "A".to_string();
+        assert_eq!(filter.reapplied(&document).rows, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_comparison_orders_dates_chronologically() {
+        let document = Document {
+            headers: vec!["when".to_string(), "qty".to_string()],
+            rows: [
+                ("10/1/2024", "9"),
+                ("2/1/2024", "10"),
+                ("", "n/a"),
+                ("12/25/2023", "2.5"),
+            ]
+            .iter()
+            .map(|(w, q)| vec![w.to_string(), q.to_string()])
+            .collect(),
+            ..Default::default()
+        };
+        let c = Locale::default();
+        let compare = |col: usize, op: &str, value: &str| {
+            RowFilter::comparing(
+                &document,
+                ColIndex::new(col),
+                Comparison::parse(op).unwrap(),
+                value,
+                &c,
+            )
+        };
+        // Month first, as 12/25 shows; not "10/1" < "2/1" as text
+        let filter = compare(0, ">=", "2/1/2024").unwrap();
+        assert_eq!(filter.rows, vec![0, 1]);
+        assert_eq!(filter.operator(), ">=");
+        assert_eq!(compare(0, "<", "2024-01-01").unwrap().rows, vec![3]);
+        assert!(compare(0, ">", "soon").is_err());
+        // Numbers by value, nulls left out
+        assert_eq!(compare(1, ">", "5").unwrap().rows, vec![0, 1]);
+        assert_eq!(compare(1, "<=", "2.5").unwrap().rows, vec![3]);
+    }
+
+    #[test]
+    fn test_insert_and_delete_shift_rows() {
+        let mut filter = RowFilter::matching(&document(), ColIndex::new(1), "Oslo");
+
+        filter.row_inserted(1);
+        assert_eq!(filter.rows, vec![0, 1, 3, 4]);
+
+        filter.row_deleted(3);
+        assert_eq!(filter.rows, vec![0, 1, 3]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Row groups separated by blank rows or sentinel rows.
+//!
+//! Some CSVs split their records into sections with empty rows (or a row
+//! whose first cell is a marker like `---`). With the `rowgroups` option on,
+//! each separator row becomes the header of the rows that follow it, up to
+//! the next separator. Groups can be collapsed down to their header row.
+
+use crate::csv::Document;
+
+/// A separator row and the rows it heads
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowGroup {
+    /// Document row of the separator
+    pub header: usize,
+    /// Member rows: `header + 1..end`
+    pub end: usize,
+    pub collapsed: bool,
+}
+
+impl RowGroup {
+    /// Number of member rows (not counting the header)
+    pub fn len(&self) -> usize {
+        self.end - self.header - 1
+    }
+
+    /// Check whether the group has no member rows
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether a row is the header or a member of this group
+    pub fn contains(&self, row: usize) -> bool {
+        (self.header..self.end).contains(&row)
+    }
+
+    /// Rows hidden while collapsed
+    fn hidden_rows(&self) -> usize {
+        if self.collapsed {
+            self.len()
+        } else {
+            0
+        }
+    }
+}
+
+/// Row groups of a document, ordered by header row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowGroups {
+    groups: Vec<RowGroup>,
+}
+
+/// Check whether a row separates groups: all cells blank, or the first
+/// cell equal to `sentinel` (when one is set)
+pub fn is_separator(row: &[String], sentinel: &str) -> bool {
+    let first = row.first().map(|cell| cell.trim()).unwrap_or("");
+    row.iter().all(|cell| cell.trim().is_empty()) || (!sentinel.is_empty() && first == sentinel)
+}
+
+impl RowGroups {
+    /// Find the groups of a document
+    pub fn detect(document: &Document, sentinel: &str) -> Self {
+        let headers: Vec<usize> = document
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| is_separator(row, sentinel))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let groups = headers
+            .iter()
+            .enumerate()
+            .map(|(i, &header)| RowGroup {
+                header,
+                end: headers.get(i + 1).copied().unwrap_or(document.rows.len()),
+                collapsed: false,
+            })
+            .collect();
+        Self { groups }
+    }
+
+    /// Detect the groups again after the document changed, keeping groups
+    /// collapsed whose header row is still a separator
+    pub fn refresh(&mut self, document: &Document, sentinel: &str) {
+        let collapsed: Vec<usize> = self
+            .groups
+            .iter()
+            .filter(|g| g.collapsed)
+            .map(|g| g.header)
+            .collect();
+        *self = Self::detect(document, sentinel);
+        for group in &mut self.groups {
+            group.collapsed = collapsed.contains(&group.header);
+        }
+    }
+
+    /// Check whether there are no groups
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Number of groups
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Group headed by a row
+    pub fn header_at(&self, row: usize) -> Option<&RowGroup> {
+        self.groups
+            .binary_search_by_key(&row, |g| g.header)
+            .ok()
+            .map(|idx| &self.groups[idx])
+    }
+
+    /// Group containing a row (as header or member)
+    pub fn group_of(&self, row: usize) -> Option<&RowGroup> {
+        let idx = self.groups.partition_point(|g| g.header <= row);
+        idx.checked_sub(1)
+            .map(|idx| &self.groups[idx])
+            .filter(|g| g.contains(row))
+    }
+
+    /// Header of the first group after `row`
+    pub fn next_header(&self, row: usize) -> Option<usize> {
+        let idx = self.groups.partition_point(|g| g.header <= row);
+        self.groups.get(idx).map(|g| g.header)
+    }
+
+    /// Header of the last group before `row`
+    pub fn prev_header(&self, row: usize) -> Option<usize> {
+        let idx = self.groups.partition_point(|g| g.header < row);
+        idx.checked_sub(1).map(|idx| self.groups[idx].header)
+    }
+
+    /// Collapse (`Some(true)`), expand (`Some(false)`) or toggle (`None`) the
+    /// group headed by `header`
+    pub fn set_collapsed(&mut self, header: usize, collapsed: Option<bool>) -> Option<&RowGroup> {
+        let idx = self
+            .groups
+            .binary_search_by_key(&header, |g| g.header)
+            .ok()?;
+        let group = &mut self.groups[idx];
+        group.collapsed = collapsed.unwrap_or(!group.collapsed);
+        Some(group)
+    }
+
+    /// A row was inserted at `at`
+    pub fn row_inserted(&mut self, at: usize) {
+        for group in &mut self.groups {
+            if group.header >= at {
+                group.header += 1;
+            }
+            if group.end >= at {
+                group.end += 1;
+            }
+        }
+    }
+
+    /// The row at `at` was deleted (a group headed by it goes with it)
+    pub fn row_deleted(&mut self, at: usize) {
+        self.groups.retain(|g| g.header != at);
+        for group in &mut self.groups {
+            if group.header > at {
+                group.header -= 1;
+            }
+            if group.end > at {
+                group.end -= 1;
+            }
+        }
+    }
+
+    /// Check whether a row is hidden inside a collapsed group
+    pub fn is_hidden(&self, row: usize) -> bool {
+        self.group_of(row)
+            .is_some_and(|g| g.collapsed && row != g.header)
+    }
+
+    /// Number of rows shown out of `total`
+    pub fn display_row_count(&self, total: usize) -> usize {
+        total - self.groups.iter().map(RowGroup::hidden_rows).sum::<usize>()
+    }
+
+    /// Document row shown at a display position
+    pub fn document_row(&self, display: usize, total: usize) -> Option<usize> {
+        let mut row = display;
+        for group in &self.groups {
+            if group.header >= row {
+                break;
+            }
+            row += group.hidden_rows();
+        }
+        (row < total).then_some(row)
+    }
+
+    /// Display position of a document row (None if it is collapsed away)
+    pub fn display_row(&self, row: usize) -> Option<usize> {
+        if self.is_hidden(row) {
+            return None;
+        }
+        let hidden: usize = self
+            .groups
+            .iter()
+            .take_while(|g| g.end <= row)
+            .map(RowGroup::hidden_rows)
+            .sum();
+        Some(row - hidden)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows: 0 a, 1 blank, 2 b, 3 c, 4 blank, 5 d, 6 "---", 7 e
+    fn document() -> Document {
+        Document {
+            headers: vec!["name".to_string(), "value".to_string()],
+            rows: ["a", "", "b", "c", "", "d", "---", "e"]
+                .iter()
+                .map(|name| vec![name.to_string(), String::new()])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_blank_and_sentinel_separators() {
+        let doc = document();
+        let groups = RowGroups::detect(&doc, "");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.header_at(1).unwrap().len(), 2);
+        assert_eq!(groups.header_at(4).unwrap().end, 8);
+
+        let groups = RowGroups::detect(&doc, "---");
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups.header_at(4).unwrap().end, 6);
+        assert_eq!(groups.group_of(7).unwrap().header, 6);
+        assert!(groups.group_of(0).is_none());
+    }
+
+    #[test]
+    fn test_next_and_prev_header() {
+        let groups = RowGroups::detect(&document(), "");
+        assert_eq!(groups.next_header(0), Some(1));
+        assert_eq!(groups.next_header(1), Some(4));
+        assert_eq!(groups.next_header(4), None);
+        assert_eq!(groups.prev_header(4), Some(1));
+        assert_eq!(groups.prev_header(3), Some(1));
+        assert_eq!(groups.prev_header(1), None);
+    }
+
+    #[test]
+    fn test_collapsed_group_maps_display_rows() {
+        let doc = document();
+        let mut groups = RowGroups::detect(&doc, "");
+        groups.set_collapsed(1, Some(true));
+
+        // a, [blank], [blank], d, ---, e
+        assert_eq!(groups.display_row_count(8), 6);
+        assert_eq!(groups.document_row(1, 8), Some(1));
+        assert_eq!(groups.document_row(2, 8), Some(4));
+        assert_eq!(groups.document_row(5, 8), Some(7));
+        assert_eq!(groups.document_row(6, 8), None);
+        assert_eq!(groups.display_row(4), Some(2));
+        assert_eq!(groups.display_row(3), None);
+        assert!(groups.is_hidden(2));
+        assert!(!groups.is_hidden(1));
+    }
+
+    #[test]
+    fn test_refresh_keeps_collapsed_groups() {
+        let mut doc = document();
+        let mut groups = RowGroups::detect(&doc, "");
+        groups.set_collapsed(4, None);
+
+        doc.rows[3][0].clear();
+        groups.refresh(&doc, "");
+        assert_eq!(groups.len(), 3);
+        assert!(groups.header_at(4).unwrap().collapsed);
+        assert!(!groups.header_at(3).unwrap().collapsed);
+    }
+}
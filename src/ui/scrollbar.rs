@@ -0,0 +1,172 @@
+//! Scrollbars along the right and bottom edges of the table (`:set
+//! scrollbar`), showing where the viewport sits among all rows and columns.
+//!
+//! Besides the viewport thumb, the vertical track works as an overview
+//! ruler: marked rows (search hits, validation errors, tags, changes) are
+//! drawn as colored ticks at their relative position, and clicking a tick
+//! jumps to its row. The horizontal track covers the scrolling columns
+//! (frozen ones and the one pinned on the right always show).
+
+use super::marks::MarkKind;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+use std::collections::BTreeMap;
+
+/// Symbol drawn on the track for a marked row
+const MARK_SYMBOL: &str = "▬";
+
+/// Track offset (0-based from the top) of a display row
+fn track_position(row: usize, total: usize, track_height: u16) -> u16 {
+    (row * track_height as usize / total) as u16
+}
+
+/// Track offsets of marked display rows, keeping the strongest kind where
+/// several marks share a tick
+pub fn mark_positions(
+    marks: &BTreeMap<usize, MarkKind>,
+    total: usize,
+    track_height: u16,
+) -> BTreeMap<u16, MarkKind> {
+    let mut positions = BTreeMap::new();
+    if total == 0 || track_height == 0 {
+        return positions;
+    }
+    for (&row, &kind) in marks.range(..total) {
+        positions
+            .entry(track_position(row, total, track_height))
+            .and_modify(|k: &mut MarkKind| *k = (*k).max(kind))
+            .or_insert(kind);
+    }
+    positions
+}
+
+/// Display row for a click at track offset `y`: the first marked row under
+/// that tick, or the row at that relative position
+pub fn row_at(y: u16, track_height: u16, total: usize, marks: &BTreeMap<usize, MarkKind>) -> usize {
+    if total == 0 || track_height == 0 {
+        return 0;
+    }
+    let y = y.min(track_height - 1);
+    marks
+        .range(..total)
+        .map(|(&row, _)| row)
+        .find(|&row| track_position(row, total, track_height) == y)
+        .unwrap_or_else(|| (y as usize * total).div_ceil(track_height as usize))
+        .min(total - 1)
+}
+
+/// Render the scrollbar for `total` rows with `viewport` rows shown from
+/// `offset`, ticking the marked display rows
+pub fn render_row_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    total: usize,
+    offset: usize,
+    viewport: usize,
+    marks: &BTreeMap<usize, MarkKind>,
+) {
+    // Position is the first visible row; content length counts the possible
+    // scroll positions so the thumb reaches the bottom on the last page
+    let mut state = ScrollbarState::new(total.saturating_sub(viewport) + 1)
+        .position(offset)
+        .viewport_content_length(viewport);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    frame.render_stateful_widget(scrollbar, area, &mut state);
+
+    for (y, kind) in mark_positions(marks, total, area.height) {
+        let cell = &mut frame.buffer_mut()[(area.x, area.y + y)];
+        cell.set_symbol(MARK_SYMBOL)
+            .set_style(Style::default().fg(kind.color()));
+    }
+}
+
+/// Render the scrollbar for `total` scrolling columns with `viewport` of
+/// them shown from `offset`
+pub fn render_column_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    total: usize,
+    offset: usize,
+    viewport: usize,
+) {
+    let mut state = ScrollbarState::new(total.saturating_sub(viewport) + 1)
+        .position(offset)
+        .viewport_content_length(viewport);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
+        .begin_symbol(None)
+        .end_symbol(None);
+    frame.render_stateful_widget(scrollbar, area, &mut state);
+}
+
+/// Scrolling column (counted from the first one) for a click at track
+/// offset `x`
+pub fn column_at(x: u16, track_width: u16, total: usize) -> usize {
+    if total == 0 || track_width == 0 {
+        return 0;
+    }
+    (x.min(track_width - 1) as usize * total / track_width as usize).min(total - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marks(rows: &[(usize, MarkKind)]) -> BTreeMap<usize, MarkKind> {
+        rows.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_mark_positions_scale_to_track() {
+        let positions = mark_positions(
+            &marks(&[
+                (0, MarkKind::Tagged),
+                (50, MarkKind::Error),
+                (99, MarkKind::Tagged),
+            ]),
+            100,
+            10,
+        );
+        assert_eq!(positions.keys().copied().collect::<Vec<_>>(), vec![0, 5, 9]);
+
+        // Nearby marks share a tick, the strongest kind wins
+        let positions = mark_positions(
+            &marks(&[(10, MarkKind::SearchHit), (11, MarkKind::Error)]),
+            100,
+            10,
+        );
+        assert_eq!(
+            positions.into_iter().collect::<Vec<_>>(),
+            vec![(1, MarkKind::Error)]
+        );
+
+        // Out-of-range rows are ignored
+        assert!(mark_positions(&marks(&[(200, MarkKind::Error)]), 10, 10).is_empty());
+        assert!(mark_positions(&marks(&[(1, MarkKind::Error)]), 0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_row_at_prefers_marked_rows() {
+        let marked = marks(&[(57, MarkKind::Error)]);
+        assert_eq!(row_at(5, 10, 100, &marked), 57);
+        assert_eq!(row_at(3, 10, 100, &marked), 30);
+        assert_eq!(row_at(20, 10, 100, &marked), 90);
+        assert_eq!(row_at(0, 10, 0, &marked), 0);
+    }
+
+    #[test]
+    fn test_column_at_scales_to_track() {
+        assert_eq!(column_at(0, 40, 120), 0);
+        assert_eq!(column_at(20, 40, 120), 60);
+        assert_eq!(column_at(39, 40, 120), 117);
+        assert_eq!(column_at(90, 40, 120), 117);
+        // More track than columns
+        assert_eq!(column_at(30, 40, 4), 3);
+        assert_eq!(column_at(5, 40, 0), 0);
+    }
+}
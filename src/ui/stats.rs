@@ -0,0 +1,128 @@
+//! Column statistics panel (`:stats`).
+//!
+//! Shows the summary computed by [`ColumnStats`] for the selected column in
+//! a centered overlay. h/l switch columns, Esc or q closes the panel.
+
+use super::help::centered_rect;
+use super::utils::column_to_excel_letter;
+use crate::analysis::ColumnStats;
+use crate::csv::Locale;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Width percentage for the stats panel
+const STATS_PANEL_WIDTH_PERCENT: u16 = 50;
+
+/// Height percentage for the stats panel
+const STATS_PANEL_HEIGHT_PERCENT: u16 = 70;
+
+/// Longest value shown in the top values list
+const MAX_VALUE_WIDTH: usize = 30;
+
+/// Build the panel lines for a column summary, numbers written the way
+/// `locale` writes them
+fn build_stats_text(stats: &ColumnStats, locale: &Locale) -> Vec<Line<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        field_line("Type", stats.kind.label().to_string()),
+        field_line("Rows", stats.count.to_string()),
+        field_line("Empty", stats.empty.to_string()),
+        field_line("Nulls", stats.nulls.to_string()),
+        field_line("Distinct", stats.distinct.to_string()),
+    ];
+
+    if let Some(numeric) = &stats.numeric {
+        lines.push(Line::from(""));
+        lines.push(field_line("Min", locale.format_number(numeric.min)));
+        lines.push(field_line("Max", locale.format_number(numeric.max)));
+        lines.push(field_line("Mean", locale.format_number(numeric.mean)));
+        lines.push(field_line("Median", locale.format_number(numeric.median)));
+    }
+    if let Some((earliest, latest)) = &stats.date_range {
+        lines.push(Line::from(""));
+        lines.push(field_line("Earliest", earliest.clone()));
+        lines.push(field_line("Latest", latest.clone()));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Top values", bold)));
+    if stats.top_values.is_empty() {
+        lines.push(Line::from("  (none)"));
+    }
+    for (value, count) in &stats.top_values {
+        let percent = *count as f64 * 100.0 / stats.count.max(1) as f64;
+        lines.push(Line::from(format!(
+            "  {:<width$} {:>6} {:>5.1}%",
+            truncate(value),
+            count,
+            percent,
+            width = MAX_VALUE_WIDTH
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "h/l: prev/next column   Esc: close",
+        Style::default().add_modifier(Modifier::DIM),
+    )));
+    lines
+}
+
+fn field_line(label: &str, value: String) -> Line<'static> {
+    Line::from(format!("  {:<10}{}", label, value))
+}
+
+fn truncate(value: &str) -> String {
+    if value.chars().count() > MAX_VALUE_WIDTH {
+        let mut text: String = value.chars().take(MAX_VALUE_WIDTH - 1).collect();
+        text.push('…');
+        text
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render the statistics panel for a column
+pub fn render_stats_panel(frame: &mut Frame, stats: &ColumnStats, locale: &Locale) {
+    let area = centered_rect(
+        STATS_PANEL_WIDTH_PERCENT,
+        STATS_PANEL_HEIGHT_PERCENT,
+        frame.area(),
+    );
+
+    let title = format!(
+        " Stats: {} {} ",
+        column_to_excel_letter(stats.column.get()),
+        stats.header
+    );
+    let panel = Paragraph::new(build_stats_text(stats, locale))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(panel, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        let format_number = |value| Locale::default().format_number(value);
+        assert_eq!(format_number(3.0), "3");
+        assert_eq!(format_number(2.5), "2.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.3333");
+        assert_eq!(format_number(-4.0), "-4");
+    }
+
+    #[test]
+    fn test_truncate_long_values() {
+        let long = "x".repeat(40);
+        assert_eq!(truncate(&long).chars().count(), MAX_VALUE_WIDTH);
+        assert_eq!(truncate("short"), "short");
+    }
+}
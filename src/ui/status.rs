@@ -3,10 +3,11 @@
 //! This module handles rendering the bottom status bar showing current cell
 //! position and value, plus the file switcher for multi-file sessions.
 
+use crate::input::Severity;
 use crate::App;
 use ratatui::{
     layout::Rect,
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::Paragraph,
     Frame,
@@ -16,8 +17,15 @@ use std::borrow::Cow;
 /// Maximum length for cell value display in status bar
 const MAX_STATUS_CELL_LENGTH: usize = 30;
 
-/// Number of characters used for ellipsis truncation
-const ELLIPSIS_LENGTH: usize = 3;
+/// Style of a status message: warnings in yellow, errors in red
+fn severity_style(severity: Severity) -> Option<Style> {
+    let color = match severity {
+        Severity::Info => return None,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    };
+    Some(Style::default().fg(color).add_modifier(Modifier::BOLD))
+}
 
 /// Build a status line with left and right content, padding between them
 fn build_status_line(left: &str, right: &str, width: usize) -> String {
@@ -36,18 +44,21 @@ fn build_status_line(left: &str, right: &str, width: usize) -> String {
     }
 }
 
+/// Marks a file with unsaved changes in the file switcher
+pub const DIRTY_MARKER: &str = "[+]";
+
 /// Render the file switcher showing all open CSV files (minimal single-line format).
 ///
 /// Displays a list of all CSV files in the current directory.
-/// Format: "file1.csv | file2.csv | file3.csv [1/3]"
-/// Active file is shown first/highlighted.
+/// Format: "file1.csv | file2.csv[+] | file3.csv [1/3]"
+/// The active file is emphasized, and files with unsaved changes are marked.
 ///
 /// # Arguments
 ///
 /// * `frame` - The Ratatui frame to render into
 /// * `app` - Application state containing session file list
 /// * `area` - The rectangle area to render the switcher within
-pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
+pub fn render_file_switcher(frame: &mut Frame, app: &mut App, area: Rect) {
     use ratatui::layout::{Constraint, Direction, Layout};
 
     if app.session.files().is_empty() {
@@ -65,9 +76,31 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(rule, chunks[0]);
 
     let dim_style = Style::default().add_modifier(Modifier::DIM);
-    let bold_style = Style::default().add_modifier(Modifier::BOLD);
+    let active_style = Style::default()
+        .fg(app.theme.normal)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
     let available_width = area.width as usize;
 
+    // Each file's name, marked if it has unsaved changes
+    let active_idx = app.session.active_file_index();
+    let labels: Vec<String> = app
+        .session
+        .files()
+        .iter()
+        .enumerate()
+        .map(|(idx, path)| {
+            let filename = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            if app.file_is_dirty(idx) {
+                format!("{}{}", filename, DIRTY_MARKER)
+            } else {
+                filename.to_string()
+            }
+        })
+        .collect();
+
     // File count indicator (shown at end)
     let count_indicator = if app.session.files().len() > 1 {
         format!(
@@ -84,15 +117,11 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
     let mut file_positions: Vec<(usize, usize)> = Vec::new(); // (start, end) for each file
     let mut pos = 0usize;
 
-    for (idx, path) in app.session.files().iter().enumerate() {
+    for (idx, filename) in labels.iter().enumerate() {
         if idx > 0 {
             pos += 3; // " | "
         }
         let start = pos;
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown");
         pos += filename.len();
         file_positions.push((start, pos));
     }
@@ -100,7 +129,6 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
     let total_len = pos;
 
     // Calculate scroll offset to keep current file visible
-    let active_idx = app.session.active_file_index();
     let (active_start, active_end) = file_positions[active_idx];
     let visible_width = available_width.saturating_sub(count_width + 1);
 
@@ -120,13 +148,11 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
         spans.push(Span::styled("< ", dim_style));
     }
 
+    // Where each file name lands on screen, for mouse clicks
+    let mut tabs = Vec::new();
     let mut current_pos = 0usize;
-    for (idx, path) in app.session.files().iter().enumerate() {
+    for (idx, filename) in labels.iter().enumerate() {
         let separator = if idx > 0 { " | " } else { "" };
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown");
 
         let sep_start = current_pos;
         let sep_end = sep_start + separator.len();
@@ -143,11 +169,19 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
             // Add filename if visible
             if file_end > scroll_offset {
                 let style = if idx == active_idx {
-                    bold_style
+                    active_style
                 } else {
                     dim_style
                 };
-                spans.push(Span::styled(filename.to_string(), style));
+                let x: usize = spans.iter().map(|s| s.content.len()).sum();
+                if x < available_width {
+                    let width = filename.len().min(available_width - x);
+                    tabs.push((
+                        idx,
+                        Rect::new(chunks[1].x + x as u16, chunks[1].y, width as u16, 1),
+                    ));
+                }
+                spans.push(Span::styled(filename.clone(), style));
             }
         }
 
@@ -174,6 +208,7 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
     let line = Line::from(spans);
     let switcher = Paragraph::new(line);
     frame.render_widget(switcher, chunks[1]);
+    app.view_state.file_tabs = tabs;
 }
 
 /// Render the main status bar showing position and cell information.
@@ -188,7 +223,7 @@ pub fn render_file_switcher(frame: &mut Frame, app: &App, area: Rect) {
 /// * `app` - Application state containing cursor position and document data
 /// * `area` - The rectangle area to render the status bar within
 pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
-    use crate::ui::utils::column_to_excel_letter;
+    use crate::ui::utils::{column_to_excel_letter, truncate};
 
     let selected_row = app
         .get_selected_row()
@@ -202,11 +237,15 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         let value = app
             .document
             .get_cell(row_idx, app.view_state.selected_column);
+        let len = value.chars().count();
         if value.is_empty() {
             Cow::Borrowed("<empty>")
-        } else if value.len() > MAX_STATUS_CELL_LENGTH {
-            let truncate_at = MAX_STATUS_CELL_LENGTH - ELLIPSIS_LENGTH;
-            Cow::Owned(format!("\"{}...\"", &value[..truncate_at]))
+        } else if len > MAX_STATUS_CELL_LENGTH {
+            // Say how much is hidden so a cut-off value is never mistaken
+            // for the whole cell
+            let shown = truncate(value, MAX_STATUS_CELL_LENGTH, &app.options.ellipsis);
+            let hidden = len - shown.chars().count() + app.options.ellipsis.chars().count();
+            Cow::Owned(format!("\"{}\" (+{} chars)", shown, hidden))
         } else {
             Cow::Owned(format!("\"{}\"", value))
         }
@@ -219,21 +258,53 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     // Right side: position and cell preview
     //
     // Examples:
-    //   NORMAL                                                    3,C "Mike Johnson"
-    //   :sort                                                     3,C "Mike Johnson"
-    //   Jumped to column B                                        3,C "Mike Johnson"
-    //   g_                                                        3,C "Mike Johnson"
-
-    // Build right side: row,col cell_value (vim-like compact format)
-    let right_side = format!("{},{} {}", selected_row, col_letter, cell_value);
+    //   NORMAL                                                [,] 3,C "Mike Johnson"
+    //   :sort                                                 [,] 3,C "Mike Johnson"
+    //   Jumped to column B                                    [;] 3,C "Mike Johnson"
+    //   g_                                                  [tab] 3,C "Mike Johnson"
+
+    // With `shownulls`, how many cells of the column are empty or null
+    let nulls = if app.options.show_nulls {
+        let col = app.view_state.selected_column.get();
+        let count = app
+            .document
+            .rows
+            .iter()
+            .filter(|row| {
+                row.get(col)
+                    .is_none_or(|v| v.trim().is_empty() || crate::analysis::stats::is_null_token(v))
+            })
+            .count();
+        format!("{} empty ", count)
+    } else {
+        String::new()
+    };
 
-    // Build pending/count indicator
+    // Build right side: [delimiter] row,col cell_value (vim-like compact format)
+    let right_side = format!(
+        "{}[{}] {},{} {}",
+        nulls,
+        crate::csv::delimiter_label(app.document.delimiter),
+        selected_row,
+        col_letter,
+        cell_value
+    );
+
+    // Build pending/count indicator, after the register picked with "a
+    let register = app
+        .input_state
+        .register
+        .map_or(String::new(), |name| format!("\"{}", name));
     let pending_indicator = match &app.input_state.pending_command {
         Some(crate::input::PendingCommand::G) => "g".to_string(),
         Some(crate::input::PendingCommand::Z) => "z".to_string(),
         Some(crate::input::PendingCommand::GotoColumn(letters)) => format!("g{}", letters),
         Some(crate::input::PendingCommand::D) => "d".to_string(),
         Some(crate::input::PendingCommand::Y) => "y".to_string(),
+        Some(crate::input::PendingCommand::Mark) => "m".to_string(),
+        Some(crate::input::PendingCommand::Quote) => "'".to_string(),
+        Some(crate::input::PendingCommand::Register) => "\"".to_string(),
+        Some(crate::input::PendingCommand::Window) => "^W".to_string(),
         None => {
             if let Some(count) = app.input_state.command_count {
                 format!("{}", count)
@@ -242,22 +313,67 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             }
         }
     };
+    let pending_indicator = register + &pending_indicator;
 
     let status_text = match app.mode {
         crate::app::Mode::Command => {
             // Show command input: ":sort_" on left, position on right
-            let left = format!(":{}", app.input_state.command_buffer);
-            build_status_line(&left, &right_side, area.width as usize)
+            let buffer = &app.input_state.command_buffer;
+            let left = format!(":{}", buffer);
+            // `:fill` previews its first values in place of the position
+            let fill = app
+                .command_range(buffer)
+                .ok()
+                .and_then(|(range, cmd)| Some((range, cmd.strip_prefix("fill ")?)));
+            let room = (area.width as usize).saturating_sub(left.chars().count() + 3);
+            let right = match fill {
+                // Paths matching a Tab in `:e <path>`
+                _ if !app.input_state.completions.is_empty() => {
+                    let listed = app.input_state.completions.join("  ");
+                    if listed.chars().count() > room {
+                        let mut listed: String =
+                            listed.chars().take(room.saturating_sub(1)).collect();
+                        listed.push('…');
+                        listed
+                    } else {
+                        listed
+                    }
+                }
+                Some((range, spec)) => crate::app::fill::preview(
+                    spec,
+                    &app.document,
+                    &app.fill_rows(range),
+                    app.view_state.selected_column,
+                )
+                .chars()
+                .take(room)
+                .collect(),
+                None => right_side.clone(),
+            };
+            build_status_line(&left, &right, area.width as usize)
         }
         crate::app::Mode::Normal => {
             // Show notification or mode indicator
-            let left = if let Some(ref msg) = app.status_message {
+            let left = if let Some(confirm) = &app.confirm {
+                confirm.question.clone()
+            } else if let Some(msg) = app.status_messages.current() {
                 msg.as_str().to_string()
             } else if !pending_indicator.is_empty() {
                 pending_indicator.clone()
             } else {
                 let dirty = if app.document.is_dirty { "*" } else { "" };
-                format!("NORMAL{}", dirty)
+                let file_state = if app.session.is_changed_on_disk() {
+                    " [changed on disk]"
+                } else if app.follower.is_some() {
+                    " [following]"
+                } else if app.leader.is_some() {
+                    " [sharing]"
+                } else if app.tail.is_some() {
+                    " [watching]"
+                } else {
+                    ""
+                };
+                format!("NORMAL{}{}", dirty, file_state)
             };
             build_status_line(&left, &right_side, area.width as usize)
         }
@@ -270,7 +386,13 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             )
         }
         crate::app::Mode::Magnifier => {
-            build_status_line("MAGNIFIER", &right_side, area.width as usize)
+            let search = app.magnifier.as_ref().and_then(|m| m.search_input.as_ref());
+            let left = match (search, app.status_messages.current()) {
+                (Some(query), _) => format!("/{}", query),
+                (None, Some(msg)) => format!("MAGNIFIER  {}", msg.as_str()),
+                (None, None) => "MAGNIFIER".to_string(),
+            };
+            build_status_line(&left, &right_side, area.width as usize)
         }
         crate::app::Mode::HeaderEdit => {
             let left = format!("HEADER EDIT: {}", col_name);
@@ -286,7 +408,71 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         }
     };
 
-    let status = Paragraph::new(status_text).style(Style::default());
+    // Color the mode segment (e.g. "INSERT") when it is shown on the left
+    let mode_label = match app.mode {
+        crate::app::Mode::Normal => Some("NORMAL"),
+        crate::app::Mode::Insert => Some("INSERT"),
+        crate::app::Mode::Visual => Some("VISUAL"),
+        crate::app::Mode::Magnifier => Some("MAGNIFIER"),
+        crate::app::Mode::HeaderEdit => Some("HEADER EDIT"),
+        crate::app::Mode::Command => None,
+    };
+    // Color a warning or error shown on the left (perhaps cut short)
+    let message = app
+        .status_messages
+        .current()
+        .filter(|_| app.confirm.is_none())
+        .and_then(|message| Some((message.as_str(), severity_style(message.severity())?)))
+        .map(|(text, style)| {
+            let shown: usize = text
+                .chars()
+                .zip(status_text[1..].chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            (shown, style)
+        })
+        .filter(|&(shown, _)| shown > 0);
+    let line = match (
+        message,
+        mode_label.filter(|label| status_text[1..].starts_with(label)),
+    ) {
+        (Some((shown, style)), _) => Line::from(vec![
+            Span::raw(" "),
+            Span::styled(status_text[1..1 + shown].to_string(), style),
+            Span::raw(status_text[1 + shown..].to_string()),
+        ]),
+        (None, Some(label)) => Line::from(vec![
+            Span::raw(" "),
+            Span::styled(label, app.theme.mode_style(app.mode)),
+            Span::raw(status_text[1 + label.len()..].to_string()),
+        ]),
+        (None, None) => Line::from(status_text),
+    };
+
+    let status = Paragraph::new(line).style(Style::default());
+
+    // Terminal cursor at the end of the command line
+    if app.mode == crate::app::Mode::Command {
+        let typed = app.input_state.command_cursor as u16;
+        let x = (area.x + 2 + typed).min(area.right().saturating_sub(1));
+        frame.set_cursor_position((x, area.y));
+    }
 
     frame.render_widget(status, area);
 }
+
+/// Render the key echo (recent keys, last action and `:` command) in the
+/// bottom-right corner of `area`
+pub fn render_key_echo(frame: &mut Frame, app: &App, area: Rect) {
+    let text = app.input_state.key_echo.display();
+    if text.is_empty() || area.height == 0 {
+        return;
+    }
+
+    let text = format!(" {} ", text);
+    let width = (text.chars().count() as u16).min(area.width);
+    let echo_area = Rect::new(area.right() - width, area.bottom() - 1, width, 1);
+    let echo = Paragraph::new(text).style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_widget(echo, echo_area);
+}
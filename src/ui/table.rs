@@ -2,46 +2,122 @@
 //!
 //! This module renders the CSV data table with row numbers, column letters,
 //! and headers. Implements virtual scrolling for performance with large files.
-
-use super::{utils::column_to_excel_letter, MAX_VISIBLE_COLS};
-use crate::app::Mode;
+//!
+//! The table is drawn as separate regions: the header (column letters and
+//! names) pinned above the body, and the row number gutter pinned to its
+//! left. Only the body scrolls vertically, and only the data columns of the
+//! header and body scroll horizontally, so neither the header nor the gutter
+//! can be scrolled out of view however tall or wide the table is.
+
+use super::column_width::{self, MIN_COLUMN_WIDTH, ROW_NUMBER_COLUMN_WIDTH};
+use super::decimal_align::DecimalLayout;
+use super::utils::{column_to_excel_letter, display_width, pad_to_width, truncate, wrap_to_width};
+use super::{minimap, scrollbar};
+use crate::analysis::stats::is_null_token;
+use crate::app::{messages, DerivedColumns, Mode, Options};
+use crate::csv::ColumnType;
 use crate::domain::position::ColIndex;
+use crate::ui::{ColumnGroups, MarkKind};
 use crate::App;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Cell, Paragraph, Row, Table},
     Frame,
 };
+use std::collections::HashSet;
 
-/// Height reserved for title bar, horizontal rule, column letters, and header row
-const TABLE_HEADER_HEIGHT: u16 = 4;
+/// Height of the pinned header: column letters and header row
+const HEADER_HEIGHT: u16 = 2;
 
-/// Height reserved for status bar (1) and file switcher (2)
-const STATUS_BAR_HEIGHT: u16 = 3;
+/// Placeholder for empty cells with `shownulls`
+const NULL_CELL: &str = "·";
 
-/// Width allocated for the row number column
-const ROW_NUMBER_COLUMN_WIDTH: u16 = 5;
+/// Background of empty and null cells with `shownulls`
+const NULL_BACKGROUND: Color = Color::Indexed(236);
 
-/// Offset added to selected position to account for column letters and header rows
-const HEADER_ROW_OFFSET: usize = 2;
+/// Background of every other row with `zebra`
+const ZEBRA_BACKGROUND: Color = Color::Indexed(234);
 
-/// Calculate the visible column range based on horizontal scroll offset
-fn calculate_visible_columns(start_col: usize, total_cols: usize) -> (usize, usize) {
-    let end_col = (start_col + MAX_VISIBLE_COLS).min(total_cols);
-    (start_col, end_col)
+/// Background of the cursor row and column with `crosshair`
+const CROSSHAIR_BACKGROUND: Color = Color::Indexed(237);
+
+/// Lay out table columns the same way the Table widget does (index 0 is the
+/// row number column)
+fn column_cells(table_area: Rect, raw_widths: &[u16]) -> std::rc::Rc<[Rect]> {
+    Layout::horizontal(raw_widths.iter().map(|w| Constraint::Length(*w)))
+        .spacing(1)
+        .flex(Flex::Start)
+        .split(table_area)
 }
 
+/// Draw a divider in the gap after the first `frozen_count` columns (between
+/// frozen and scrolling columns, or before the column pinned on the right)
+fn render_frozen_divider(
+    frame: &mut Frame,
+    table_area: Rect,
+    raw_widths: &[u16],
+    frozen_count: usize,
+) {
+    let cells = column_cells(table_area, raw_widths);
+    let Some(last_frozen) = cells.get(frozen_count) else {
+        return;
+    };
+    let x = last_frozen.right();
+    if x >= table_area.right() {
+        return;
+    }
+    let style = Style::default().add_modifier(Modifier::DIM);
+    for y in table_area.top()..table_area.bottom() {
+        frame.buffer_mut()[(x, y)]
+            .set_symbol(FROZEN_DIVIDER)
+            .set_style(style);
+    }
+}
+
+/// Drawn between frozen and scrolling columns
+const FROZEN_DIVIDER: &str = "│";
+
+/// Marker in the row number column of a row group header
+fn fold_marker(collapsed: bool) -> &'static str {
+    if collapsed {
+        "▸"
+    } else {
+        "▾"
+    }
+}
+
+/// Placeholder shown in data cells of a collapsed column group
+const COLLAPSED_CELL: &str = "…";
+
 /// Build the column letters row (A, B, C...) with highlighting for selected column
 fn build_column_letters_row<'a>(
-    start_col: usize,
-    end_col: usize,
+    columns: &[usize],
     selected_column: ColIndex,
+    groups: &ColumnGroups,
+    types: &[ColumnType],
+    derived: &DerivedColumns,
 ) -> Row<'a> {
-    let mut col_letter_cells = vec![Cell::from("    ")]; // Align with row numbers column
-
-    for i in start_col..end_col {
-        let letter = column_to_excel_letter(i);
+    let mut col_letter_cells = Vec::with_capacity(columns.len());
+
+    for &i in columns {
+        // Collapsed groups show their letter range (e.g. B:D), other
+        // columns their inferred type
+        let (letter, kind) = match groups.group_at(i) {
+            Some(group) if group.collapsed => (
+                format!(
+                    "{}:{}",
+                    column_to_excel_letter(group.start),
+                    column_to_excel_letter(group.end - 1)
+                ),
+                None,
+            ),
+            _ => (
+                column_to_excel_letter(i).to_string(),
+                types.get(i).map(|kind| kind.label()),
+            ),
+        };
         let col_idx = ColIndex::new(i);
         let style = if col_idx == selected_column {
             // Highlight selected column with bold only
@@ -49,18 +125,35 @@ fn build_column_letters_row<'a>(
         } else {
             Style::default().add_modifier(Modifier::DIM)
         };
-        col_letter_cells.push(Cell::from(letter).style(style));
+        let mut spans = vec![Span::styled(letter, style)];
+        if let Some(kind) = kind {
+            spans.push(Span::styled(
+                format!(" {}", kind),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        // Derived columns (`:derive`) are marked so edits there aren't a surprise
+        if derived.contains(i) {
+            spans.push(Span::styled(
+                " ƒ",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        col_letter_cells.push(Cell::from(Line::from(spans)));
     }
 
     Row::new(col_letter_cells).height(1)
 }
 
 /// Build the header row with column names
-fn build_header_row<'a>(app: &'a App, start_col: usize, end_col: usize) -> Row<'a> {
-    let mut header_cells = vec![Cell::from("")]; // Empty cell for row number column
+fn build_header_row<'a>(app: &'a App, columns: &[usize]) -> Row<'a> {
+    let mut header_cells = Vec::with_capacity(columns.len());
 
-    for i in start_col..end_col {
-        let header_text = app.document.get_header(ColIndex::new(i));
+    for &i in columns {
+        let header_text = match app.view_state.column_groups.collapsed_label(i) {
+            Some(label) => label,
+            None => app.document.get_header(ColIndex::new(i)).to_string(),
+        };
         header_cells
             .push(Cell::from(header_text).style(Style::default().add_modifier(Modifier::BOLD)));
     }
@@ -103,63 +196,240 @@ fn calculate_scroll_offset(
     }
 }
 
-/// Format edit buffer content with visible cursor
-fn format_edit_buffer(content: &str, cursor: usize) -> String {
-    // Insert a visible cursor character at cursor position
-    let mut result = String::new();
-    for (i, ch) in content.chars().enumerate() {
-        if i == cursor {
-            result.push('│'); // Cursor indicator
-        }
-        result.push(ch);
+/// Scroll only as far as needed to keep `margin` rows of context around the
+/// selected row, starting from the previous scroll offset
+fn scroll_with_margin(
+    previous: usize,
+    selected_idx: usize,
+    table_height: usize,
+    total_rows: usize,
+    margin: usize,
+) -> usize {
+    let lowest = (selected_idx + margin + 1).saturating_sub(table_height);
+    let highest = selected_idx.saturating_sub(margin).max(lowest);
+    previous
+        .clamp(lowest, highest)
+        .min(total_rows.saturating_sub(table_height))
+}
+
+/// Row number for the gutter: with `relativenumber`, how many rows away
+/// from the cursor it is, the cursor row showing its own number with
+/// `number` (hybrid, like vim) or 0 without
+fn row_number(options: &Options, row: usize, distance: Option<usize>) -> String {
+    match distance {
+        Some(0) if options.relative_number && options.number => format!("{:<4}", row + 1),
+        Some(distance) if options.relative_number => format!("{:>4}", distance),
+        _ => format!("{:>4}", row + 1),
     }
-    // If cursor is at end of content
-    if cursor >= content.chars().count() {
-        result.push('│');
+}
+
+/// Rows marked for deletion in review mode, struck through
+fn pending_deletes(app: &App) -> HashSet<usize> {
+    app.view_state
+        .marks
+        .positions(&[MarkKind::PendingDelete])
+        .into_iter()
+        .map(|(row, _)| row)
+        .collect()
+}
+
+/// Build the row numbers of the gutter, one line per visible row (and
+/// blank lines beside the rest of a wrapped cursor row)
+fn build_gutter_lines(
+    app: &App,
+    visible_rows: &[(usize, &Vec<String>)],
+    wrapped: Option<(usize, usize)>,
+) -> Vec<Line<'static>> {
+    let selected_row_idx = app.get_selected_row().map(|r| r.get());
+
+    // Bookmarked rows have their number colored
+    let bookmarks: HashSet<usize> = app.bookmarks().into_iter().collect();
+    let pending_deletes = pending_deletes(app);
+
+    // Rows on screen from the cursor, for relative row numbers
+    let cursor_line = visible_rows
+        .iter()
+        .position(|&(row_idx, _)| selected_row_idx == Some(row_idx));
+
+    visible_rows
+        .iter()
+        .enumerate()
+        .flat_map(|(line, &(row_idx, _))| {
+            // Row group headers carry a fold marker
+            let group = app.view_state.row_groups.header_at(row_idx);
+
+            // Row number: bold for selected row, normal for others
+            let distance = cursor_line.map(|cursor| line.abs_diff(cursor));
+            let number = row_number(&app.options, row_idx, distance);
+            let row_num_display = match group {
+                Some(group) => format!("{}{}", fold_marker(group.collapsed), number),
+                None => number,
+            };
+            let mut style = if selected_row_idx == Some(row_idx) || group.is_some() {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            if pending_deletes.contains(&row_idx) {
+                style = style.add_modifier(Modifier::CROSSED_OUT | Modifier::DIM);
+            }
+            if bookmarks.contains(&row_idx) {
+                style = style.fg(MarkKind::Bookmark.color());
+            }
+            let extra_lines = match wrapped {
+                Some((row, lines)) if row == row_idx => lines - 1,
+                _ => 0,
+            };
+            std::iter::once(Line::styled(row_num_display, style))
+                .chain(std::iter::repeat_n(Line::default(), extra_lines))
+        })
+        .collect()
+}
+
+/// Lines the cursor row wraps over with `wrap` (at most `max_lines`), or
+/// None when it stays on one line
+fn wrapped_cursor_row(
+    app: &App,
+    columns: &[usize],
+    column_widths: &[u16],
+    max_lines: usize,
+) -> Option<(usize, usize)> {
+    if !app.options.wrap || app.mode == Mode::Insert {
+        return None;
     }
-    result
+    let row = app.get_selected_row()?.get();
+    let data = app.document.rows.get(row)?;
+    let lines = columns
+        .iter()
+        .zip(column_widths.iter().skip(1))
+        .filter(|&(&col, _)| app.view_state.column_groups.collapsed_label(col).is_none())
+        .filter_map(|(&col, &width)| {
+            let value = data.get(col)?;
+            Some(wrap_to_width(value, usize::from(width)).len())
+        })
+        .max()?
+        .min(max_lines);
+    (lines > 1).then_some((row, lines))
 }
 
-/// Build data rows with proper styling for the current selection
+/// Build data rows with proper styling for the current selection; the
+/// `wrapped` row (document row, lines) spreads its cells over several lines
 fn build_data_rows(
     app: &App,
-    visible_rows: &[Vec<String>],
-    scroll_offset: usize,
-    start_col: usize,
-    end_col: usize,
+    visible_rows: &[(usize, &Vec<String>)],
+    wrapped: Option<(usize, usize)>,
+    first_display_row: usize,
+    columns: &[usize],
     column_widths: &[u16],
 ) -> Vec<Row<'static>> {
     let selected_column = app.view_state.selected_column;
     let selected_row_idx = app.get_selected_row().map(|r| r.get());
     let is_insert_mode = app.mode == Mode::Insert;
+    let visual = app.visual_selection();
+
+    // Anchor columns of collapsed groups show a placeholder instead of data
+    let collapsed_columns: Vec<usize> = columns
+        .iter()
+        .copied()
+        .filter(|&col| app.view_state.column_groups.collapsed_label(col).is_some())
+        .collect();
+
+    // Numeric columns aligned on the decimal point, when that fits (a
+    // `:fmt` format takes its place)
+    let locale = app.options.locale();
+    let decimal = locale.decimal();
+    let decimal_layouts: Vec<Option<DecimalLayout>> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, &col)| {
+            if app.view_state.number_formats.contains_key(&col) {
+                return None;
+            }
+            let width = column_widths
+                .get(i + 1)
+                .copied()
+                .unwrap_or(MIN_COLUMN_WIDTH);
+            let cells = visible_rows
+                .iter()
+                .map(|(_, row)| row.get(col).map_or("", String::as_str));
+            app.options
+                .align_decimals
+                // Machine numbers (`1.5`) line up in any locale
+                .then(|| {
+                    DecimalLayout::for_cells(cells.clone(), decimal)
+                        .or_else(|| DecimalLayout::for_cells(cells, '.'))
+                })
+                .flatten()
+                .filter(|layout| layout.width() <= usize::from(width))
+        })
+        .collect();
+
+    // Search hits and validation errors color their cells
+    let marked_cells = app.view_state.marks.cells();
+
+    // Rows marked for deletion in review mode are struck through
+    let pending_deletes = pending_deletes(app);
 
-    // Get edit buffer content if in Insert mode
+    // Get edit buffer content if in Insert mode (the terminal cursor marks the position)
     let edit_content = if is_insert_mode {
-        app.edit_buffer
-            .as_ref()
-            .map(|buf| format_edit_buffer(&buf.content, buf.cursor))
+        app.edit_buffer.as_ref().map(|buf| buf.content.clone())
     } else {
         None
     };
 
+    let crosshair = app.options.crosshair && !is_insert_mode;
+
     visible_rows
         .iter()
         .enumerate()
-        .map(|(idx_in_window, row)| {
-            let row_idx = scroll_offset + idx_in_window;
+        .map(|(line, &(row_idx, row))| {
             let is_selected_row = selected_row_idx == Some(row_idx);
-
-            // Row number: bold for selected row, normal for others
-            let row_num_display = format!("{:>4}", row_idx + 1);
-            let row_num_style = if is_selected_row {
-                Style::default().add_modifier(Modifier::BOLD)
+            let wrap_lines = wrapped
+                .filter(|&(row, _)| row == row_idx)
+                .map(|(_, lines)| lines);
+
+            // Stripes follow the rows as they scroll; the crosshair row
+            // shades over them
+            let row_style = if crosshair && is_selected_row {
+                Style::default().bg(CROSSHAIR_BACKGROUND)
+            } else if app.options.zebra && (first_display_row + line) % 2 == 1 {
+                Style::default().bg(ZEBRA_BACKGROUND)
             } else {
                 Style::default()
             };
-            let mut cells = vec![Cell::from(row_num_display).style(row_num_style)];
 
-            for (i, col_idx) in (start_col..end_col).enumerate() {
+            // Row group headers show a member count
+            let group = app.view_state.row_groups.header_at(row_idx);
+
+            let struck = if pending_deletes.contains(&row_idx) {
+                Modifier::CROSSED_OUT | Modifier::DIM
+            } else {
+                Modifier::empty()
+            };
+            let mut cells = Vec::with_capacity(columns.len());
+
+            for (i, &col_idx) in columns.iter().enumerate() {
+                // A blank group header shows the group size in its first column
+                if let Some(group) = group.filter(|_| i == 0 && !is_insert_mode) {
+                    if row.get(col_idx).is_none_or(|cell| cell.trim().is_empty()) {
+                        let label = format!("── {} rows", group.len());
+                        let style = if is_selected_row && ColIndex::new(col_idx) == selected_column
+                        {
+                            Style::default().bg(Color::White).fg(Color::Black)
+                        } else {
+                            Style::default().add_modifier(Modifier::DIM)
+                        };
+                        cells.push(Cell::from(label).style(style));
+                        continue;
+                    }
+                }
+
                 let is_selected = is_selected_row && ColIndex::new(col_idx) == selected_column;
+                let column_style = if crosshair && ColIndex::new(col_idx) == selected_column {
+                    Style::default().bg(CROSSHAIR_BACKGROUND)
+                } else {
+                    Style::default()
+                };
 
                 // Get column width (skip first element which is row number column)
                 let col_width = column_widths
@@ -168,108 +438,135 @@ fn build_data_rows(
                     .unwrap_or(MIN_COLUMN_WIDTH) as usize;
 
                 // Show edit buffer content when editing this cell
+                let is_collapsed = collapsed_columns.contains(&col_idx);
+                let is_null = app.options.show_nulls
+                    && !is_collapsed
+                    && !(is_selected && is_insert_mode)
+                    && row
+                        .get(col_idx)
+                        .is_none_or(|v| v.trim().is_empty() || is_null_token(v));
                 let raw_value = if is_selected && is_insert_mode {
                     if let Some(ref content) = edit_content {
                         content.clone()
                     } else {
                         row.get(col_idx).cloned().unwrap_or_default()
                     }
+                } else if is_collapsed {
+                    COLLAPSED_CELL.to_string()
                 } else {
-                    row.get(col_idx).cloned().unwrap_or_default()
+                    let value = row.get(col_idx).map_or("", String::as_str);
+                    let formatted = app
+                        .view_state
+                        .number_formats
+                        .get(&col_idx)
+                        .and_then(|format| format.format(value, &locale));
+                    match (&decimal_layouts[i], formatted) {
+                        _ if is_null && value.trim().is_empty() => NULL_CELL.to_string(),
+                        (_, Some(formatted)) => formatted,
+                        (Some(layout), None) => layout.format(value),
+                        (None, None) => value.to_string(),
+                    }
                 };
 
-                // Truncate only truly massive content
-                let cell_value = if raw_value.chars().count() > TRUNCATE_THRESHOLD {
-                    let truncated: String =
-                        raw_value.chars().take(TRUNCATE_THRESHOLD - 3).collect();
-                    format!("{}...", truncated)
-                } else {
+                // A wrapped row keeps what fits its lines, cutting the last
+                // one short if the cell needs more
+                let wrapped_lines = wrap_lines.filter(|_| !is_collapsed).map(|lines| {
+                    let mut cell_lines = wrap_to_width(&raw_value, col_width);
+                    if cell_lines.len() > lines {
+                        let rest = cell_lines.split_off(lines).join(" ");
+                        let last = format!("{} {}", cell_lines[lines - 1], rest);
+                        cell_lines[lines - 1] = truncate(&last, col_width, &app.options.ellipsis);
+                    }
+                    cell_lines
+                });
+                let is_multiline = wrapped_lines.as_ref().is_some_and(|lines| lines.len() > 1);
+
+                // Cut what doesn't fit the column, marking it with the
+                // ellipsis (the cell being edited stays whole for the cursor)
+                let cell_value = if is_selected && is_insert_mode {
                     raw_value
+                } else if let Some(lines) = wrapped_lines {
+                    lines.join("\n")
+                } else {
+                    truncate(&raw_value, col_width, &app.options.ellipsis)
                 };
 
+                // Numbers line up on the right, a space short of the edge
+                let keeps_layout = is_collapsed || is_multiline || (is_selected && is_insert_mode);
+                let cell_value =
+                    if app.column_type(ColIndex::new(col_idx)).is_numeric() && !keeps_layout {
+                        format!("{:>1$}", cell_value, col_width.saturating_sub(1))
+                    } else {
+                        cell_value
+                    };
+
                 // Pad content to fill column width for consistent highlighting
                 let display_text = if is_selected {
                     // Pad to column width minus 1 for some margin
-                    let char_count = cell_value.chars().count();
-                    let pad_width = col_width.saturating_sub(1);
-                    if char_count < pad_width {
-                        format!("{}{}", cell_value, " ".repeat(pad_width - char_count))
-                    } else {
-                        cell_value
-                    }
+                    cell_value
+                        .split('\n')
+                        .map(|line| pad_to_width(line, col_width.saturating_sub(1)))
+                        .collect::<Vec<_>>()
+                        .join("\n")
                 } else {
                     cell_value
                 };
 
-                // Highlight current cell with background color
+                // Highlight current cell with background color, and the rest
+                // of a Visual mode selection in the mode color
                 let style = if is_selected {
                     Style::default().bg(Color::White).fg(Color::Black)
-                } else {
+                } else if visual
+                    .as_ref()
+                    .is_some_and(|(rows, cols)| rows.contains(&row_idx) && cols.contains(&col_idx))
+                {
                     Style::default()
+                        .bg(app.theme.mode_color(Mode::Visual))
+                        .fg(Color::Black)
+                } else {
+                    match marked_cells.get(&(row_idx, col_idx)) {
+                        Some(&kind @ (MarkKind::SearchHit | MarkKind::Error)) => {
+                            column_style.fg(kind.color())
+                        }
+                        _ if is_null => Style::default()
+                            .bg(NULL_BACKGROUND)
+                            .add_modifier(Modifier::DIM),
+                        _ => column_style,
+                    }
                 };
 
-                cells.push(Cell::from(display_text).style(style));
+                cells.push(Cell::from(display_text).style(style.add_modifier(struck)));
             }
 
-            Row::new(cells).height(1)
+            let height = wrap_lines.unwrap_or(1) as u16;
+            Row::new(cells).height(height).style(row_style)
         })
         .collect()
 }
 
-/// Minimum column width in characters
-const MIN_COLUMN_WIDTH: u16 = 8;
-
-/// Maximum column width in characters (generous to avoid truncation)
-const MAX_COLUMN_WIDTH: u16 = 100;
-
-/// Truncation threshold - only truncate truly massive content
-const TRUNCATE_THRESHOLD: usize = 100;
-
 /// Calculate column widths based on content
 /// Returns (constraints for Table widget, raw widths in characters)
 fn calculate_column_widths(
     app: &crate::App,
     area: &Rect,
-    start_col: usize,
-    end_col: usize,
+    columns: &[usize],
 ) -> (Vec<Constraint>, Vec<u16>) {
     let mut constraints = vec![Constraint::Length(ROW_NUMBER_COLUMN_WIDTH)];
     let mut raw_widths = vec![ROW_NUMBER_COLUMN_WIDTH];
 
     // Calculate available width for data columns
     let available_width = area.width.saturating_sub(ROW_NUMBER_COLUMN_WIDTH);
-    let visible_col_count = end_col - start_col;
+    let visible_col_count = columns.len();
 
     if visible_col_count == 0 {
         return (constraints, raw_widths);
     }
 
     // Calculate ideal width for each column based on content
-    let mut ideal_widths: Vec<u16> = Vec::with_capacity(visible_col_count);
-    for col_idx in start_col..end_col {
-        // Get header width
-        let header_len = app
-            .document
-            .get_header(ColIndex::new(col_idx))
-            .len()
-            .max(column_to_excel_letter(col_idx).len());
-
-        // Sample data rows to find max width (sample first 100 rows for performance)
-        let max_data_len = app
-            .document
-            .rows
-            .iter()
-            .take(100)
-            .filter_map(|row| row.get(col_idx))
-            .map(|s| s.chars().count()) // Use char count for unicode support
-            .max()
-            .unwrap_or(0);
-
-        // Calculate ideal width with min/max constraints
-        let ideal = (header_len.max(max_data_len) + 2) as u16; // +2 for padding
-        let constrained = ideal.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
-        ideal_widths.push(constrained);
-    }
+    let ideal_widths: Vec<u16> = columns
+        .iter()
+        .map(|&col| column_width::column_width(&app.document, &app.view_state, col, &app.options))
+        .collect();
 
     // Calculate total ideal width
     let total_ideal: u16 = ideal_widths.iter().sum();
@@ -285,7 +582,8 @@ fn calculate_column_widths(
         // Scale down proportionally to fit available space
         let scale = available_width as f64 / total_ideal as f64;
         for ideal in ideal_widths {
-            let scaled = ((ideal as f64 * scale) as u16).max(MIN_COLUMN_WIDTH);
+            // Columns narrowed by hand are never widened back to the minimum
+            let scaled = ((ideal as f64 * scale) as u16).max(MIN_COLUMN_WIDTH.min(ideal));
             constraints.push(Constraint::Length(scaled));
             raw_widths.push(scaled);
         }
@@ -308,107 +606,329 @@ fn calculate_column_widths(
 pub fn render_table(frame: &mut Frame, app: &mut App, area: Rect) {
     let csv = &app.document;
 
-    // Calculate visible columns
-    let start_col = app.view_state.column_scroll_offset;
-    let (start_col, end_col) = calculate_visible_columns(start_col, csv.column_count());
-    let visible_col_count = end_col - start_col;
+    // Leave room on the right for the scrollbar
+    let scrollbar_width = u16::from(app.options.scrollbar);
+    let table_width = area.width.saturating_sub(scrollbar_width);
+
+    // Calculate visible columns: as many as fit in the table width
+    let groups = &app.view_state.column_groups;
+    let pinned = app.view_state.pinned_last_column(csv.column_count());
+    let columns = column_width::visible_columns(
+        app.view_state.column_scroll_offset,
+        &app.view_state.display_columns(csv.column_count()),
+        groups,
+        app.view_state.frozen_columns,
+        pinned,
+        column_width::data_width(table_width),
+        |col| column_width::column_width(csv, &app.view_state, col, &app.options),
+    );
+    // Number of leading entries in `columns` that are frozen
+    let frozen_count = columns
+        .iter()
+        .take_while(|&&col| app.view_state.column_position(col) < app.view_state.frozen_columns)
+        .count();
+    // Number of entries before the column pinned on the right
+    let scrolling_end = match columns.last() {
+        Some(&last) if pinned == Some(last) && columns.len() > 1 => columns.len() - 1,
+        _ => columns.len(),
+    };
 
-    if visible_col_count == 0 {
-        let title = Paragraph::new(format!(" lazycsv: {} (no columns)", csv.filename))
+    if columns.is_empty() {
+        let what = if csv.row_count() == 0 {
+            "empty file"
+        } else {
+            "no columns"
+        };
+        let title = Paragraph::new(format!(" lazycsv: {} ({})", csv.filename, what))
             .style(Style::default().add_modifier(Modifier::BOLD));
         frame.render_widget(title, area);
         return;
     }
 
     // Build column letters and header rows
-    let col_letters_row =
-        build_column_letters_row(start_col, end_col, app.view_state.selected_column);
-    let header_row = build_header_row(app, start_col, end_col);
+    let col_letters_row = build_column_letters_row(
+        &columns,
+        app.view_state.selected_column,
+        &app.view_state.column_groups,
+        &app.column_types,
+        &app.view_state.derived_columns,
+    );
+    let header_row = build_header_row(app, &columns);
+
+    // Split area: title bar + horizontal rule + the table, whose header
+    // stays pinned above the scrolling body
+    let [title_area, rule_area, grid_area] = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Length(1), // Horizontal rule
+        Constraint::Min(0),    // Header and body
+    ])
+    .areas(area);
+    let (grid_area, scrollbar_area) = if app.options.scrollbar {
+        let [grid_area, scrollbar_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(1)]).areas(grid_area);
+        (grid_area, Some(scrollbar_area))
+    } else {
+        (grid_area, None)
+    };
+    let [header_area, body_area, column_scrollbar_area] = Layout::vertical([
+        Constraint::Length(HEADER_HEIGHT),
+        Constraint::Min(0),
+        Constraint::Length(u16::from(app.options.scrollbar)),
+    ])
+    .areas(grid_area);
 
-    // Calculate visible viewport for virtual scrolling
-    let table_height = area
-        .height
-        .saturating_sub(TABLE_HEADER_HEIGHT)
-        .saturating_sub(STATUS_BAR_HEIGHT) as usize;
+    // Rows that fit the body, for virtual scrolling
+    let table_height = body_area.height as usize;
 
     let selected_idx = app.view_state.table_state.selected().unwrap_or(0);
 
-    // Calculate scroll offset based on viewport mode
-    let scroll_offset = calculate_scroll_offset(
-        selected_idx,
-        table_height,
-        csv.row_count(),
-        &app.view_state.viewport_mode,
-    );
-
-    // Get visible rows for current viewport
-    let end_row = (scroll_offset + table_height).min(csv.row_count());
-    let visible_rows = if scroll_offset < csv.row_count() {
-        &csv.rows[scroll_offset..end_row]
-    } else {
-        &[]
+    // Calculate scroll offset based on viewport mode; a scrolloff smaller than
+    // half the screen scrolls only when the cursor nears an edge
+    let display_rows = app.display_row_count();
+    let scrolloff = app.options.scrolloff;
+    let scroll_offset = match app.view_state.viewport_mode {
+        crate::ui::ViewportMode::Auto if scrolloff.saturating_mul(2) < table_height => {
+            scroll_with_margin(
+                app.view_state.row_scroll_offset,
+                selected_idx,
+                table_height,
+                display_rows,
+                scrolloff,
+            )
+        }
+        _ => calculate_scroll_offset(
+            selected_idx,
+            table_height,
+            display_rows,
+            &app.view_state.viewport_mode,
+        ),
     };
 
-    // Calculate column widths first (needed for cell padding)
-    let (widths, raw_widths) = calculate_column_widths(app, &area, start_col, end_col);
-
-    // Build data rows with column widths for proper cell padding
-    let rows = build_data_rows(
-        app,
-        visible_rows,
-        scroll_offset,
-        start_col,
-        end_col,
-        &raw_widths,
-    );
+    // Calculate column widths first (needed for cell padding and wrapping)
+    let (mut widths, mut raw_widths) = calculate_column_widths(app, &grid_area, &columns);
+    if scrolling_end < columns.len() {
+        // Push the pinned column to the right edge by widening the column
+        // before it
+        let used: u16 = raw_widths.iter().sum::<u16>() + raw_widths.len() as u16 - 1;
+        let slack = table_width.saturating_sub(used);
+        raw_widths[scrolling_end] += slack;
+        widths[scrolling_end] = Constraint::Length(raw_widths[scrolling_end]);
+    }
 
-    // Combine column letters + headers + data
-    let all_rows = std::iter::once(col_letters_row)
-        .chain(std::iter::once(header_row))
-        .chain(rows);
-
-    // Split area: title bar + horizontal rule + table content
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1), // Title bar
-            Constraint::Length(1), // Horizontal rule
-            Constraint::Min(0),    // Table content
-        ])
-        .split(area);
+    // A wrapped cursor row takes several lines: scroll until all of them
+    // are on screen, and show fewer rows
+    let wrapped = wrapped_cursor_row(app, &columns, &raw_widths, table_height);
+    let extra_lines = wrapped.map_or(0, |(_, lines)| lines - 1);
+    // Kept on a row, as a body too short for any row would scroll past them
+    let scroll_offset = scroll_offset
+        .max((selected_idx + 1 + extra_lines).saturating_sub(table_height))
+        .min(display_rows.saturating_sub(1));
+
+    // Get visible rows for current viewport (document index + data)
+    let end_row = (scroll_offset + table_height - extra_lines).min(display_rows);
+    let visible_rows: Vec<(usize, &Vec<String>)> = (scroll_offset..end_row)
+        .filter_map(|display| app.document_row(display))
+        .filter_map(|row| csv.rows.get(row.get()).map(|data| (row.get(), data)))
+        .collect();
 
     // Title bar: filename left, row count right
     let dirty_indicator = if csv.is_dirty { "*" } else { "" };
     let title_left = format!(" lazycsv: {}{}", csv.filename, dirty_indicator);
-    let title_right = format!("{}/{} ", selected_idx + 1, csv.row_count());
+    let position = if display_rows == 0 {
+        0
+    } else {
+        selected_idx + 1
+    };
+    let title_right = match &app.view_state.row_filter {
+        Some(filter) => format!(
+            "[{}{}{}] {}/{} of {} ",
+            column_to_excel_letter(filter.column.get()),
+            filter.operator(),
+            filter.value,
+            position,
+            display_rows,
+            csv.row_count()
+        ),
+        None => format!("{}/{} ", position, csv.row_count()),
+    };
     let title_padding = (area.width as usize)
         .saturating_sub(title_left.len())
         .saturating_sub(title_right.len());
     let title_text = format!("{}{}{}", title_left, " ".repeat(title_padding), title_right);
     let title_bar = Paragraph::new(title_text).style(Style::default().add_modifier(Modifier::BOLD));
-    frame.render_widget(title_bar, chunks[0]);
-
-    // Horizontal rule (using unicode box-drawing character)
-    let rule = Paragraph::new("─".repeat(area.width as usize));
-    frame.render_widget(rule, chunks[1]);
-
-    // Create table widget without borders
-    let table = Table::new(all_rows, widths);
-
-    // Render stateful widget with adjusted selection state
-    // Virtual scrolling requires adjusting the selected position to be relative
-    // to the visible window, plus offset for column letters and header rows
-    let mut adjusted_state = app.view_state.table_state.clone();
-    if let Some(selected) = adjusted_state.selected() {
-        let position_in_window = if selected >= scroll_offset && selected < end_row {
-            selected - scroll_offset
-        } else {
-            0
+    frame.render_widget(title_bar, title_area);
+
+    // Horizontal rule (using unicode box-drawing character), or the column
+    // minimap in its place
+    if app.options.minimap {
+        let scrolling = match &columns[frozen_count.min(scrolling_end)..scrolling_end] {
+            [] => &columns[..],
+            scrolling => scrolling,
         };
-        adjusted_state.select(Some(position_in_window + HEADER_ROW_OFFSET));
+        minimap::render_minimap(frame, app, rule_area, scrolling);
+    } else {
+        let rule = Paragraph::new("─".repeat(area.width as usize));
+        frame.render_widget(rule, rule_area);
+    }
+
+    // The gutter takes the first column of the layout; the data columns
+    // start after it, in the header and body alike
+    let cells = column_cells(grid_area, &raw_widths);
+    let gutter = cells[0];
+    let data_x = (gutter.right() + 1).min(grid_area.right());
+    let data_width = grid_area.right() - data_x;
+    let data_widths = widths[1..].to_vec();
+
+    // Pinned header
+    let header = Table::new([col_letters_row, header_row], data_widths.clone());
+    frame.render_widget(
+        header,
+        Rect::new(data_x, header_area.y, data_width, header_area.height),
+    );
+
+    // Pinned gutter beside the body
+    let gutter_lines = build_gutter_lines(app, &visible_rows, wrapped);
+    frame.render_widget(
+        Paragraph::new(gutter_lines),
+        Rect::new(gutter.x, body_area.y, gutter.width, body_area.height),
+    );
+
+    // Body: exactly the visible rows, so the widget never scrolls itself
+    let rows = build_data_rows(
+        app,
+        &visible_rows,
+        wrapped,
+        scroll_offset,
+        &columns,
+        &raw_widths,
+    );
+    frame.render_widget(
+        Table::new(rows, data_widths),
+        Rect::new(data_x, body_area.y, data_width, body_area.height),
+    );
+
+    // A file with only a header: say how to add the first row
+    if csv.row_count() == 0 && body_area.height > 0 {
+        let hint = Paragraph::new(format!(" {}", messages::NO_DATA_ROWS))
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(
+            hint,
+            Rect::new(body_area.x, body_area.y, body_area.width, 1),
+        );
     }
 
-    frame.render_stateful_widget(table, chunks[2], &mut adjusted_state);
+    if frozen_count > 0 && frozen_count < columns.len() {
+        render_frozen_divider(frame, grid_area, &raw_widths, frozen_count);
+    }
+    if scrolling_end < columns.len() {
+        render_frozen_divider(frame, grid_area, &raw_widths, scrolling_end);
+    }
+
+    // Scrollbar next to the body (below the letter and header rows)
+    let scrollbar_track = scrollbar_area.map(|area| Rect {
+        y: body_area.y,
+        height: body_area.height,
+        ..area
+    });
+    if let Some(track) = scrollbar_track {
+        let marks = app.display_row_marks();
+        scrollbar::render_row_scrollbar(
+            frame,
+            track,
+            display_rows,
+            scroll_offset,
+            track.height as usize,
+            &marks,
+        );
+    }
+
+    // Horizontal scrollbar under the data columns, over the display
+    // positions that scroll (between the frozen columns and the pinned one)
+    let column_scrollbar = app.options.scrollbar.then(|| {
+        let order = app.view_state.display_columns(csv.column_count());
+        let start = app.view_state.frozen_columns.min(order.len());
+        let end = pinned
+            .map(|col| app.view_state.column_position(col))
+            .filter(|&position| position >= start)
+            .unwrap_or(order.len());
+        let track = Rect::new(data_x, column_scrollbar_area.y, data_width, 1);
+        (track, start..end)
+    });
+    if let Some((track, positions)) = &column_scrollbar {
+        let scrolling = &columns[frozen_count.min(scrolling_end)..scrolling_end];
+        let offset = scrolling.first().map_or(0, |&col| {
+            app.view_state
+                .column_position(col)
+                .saturating_sub(positions.start)
+        });
+        scrollbar::render_column_scrollbar(frame, *track, positions.len(), offset, scrolling.len());
+    }
+
+    // Place the terminal cursor on the selected cell so its shape shows the mode
+    if let Some(selected) = app.view_state.table_state.selected() {
+        if selected >= scroll_offset && selected < end_row {
+            let row_y = body_area.y + (selected - scroll_offset) as u16;
+            place_cell_cursor(frame, app, body_area, &raw_widths, &columns, row_y);
+        }
+    }
+
+    app.view_state.table_width = table_width;
+    app.view_state.table_height = table_height;
+    app.view_state.row_scroll_offset = scroll_offset;
+    app.view_state.minimap_area = app.options.minimap.then_some(rule_area);
+    app.view_state.scrollbar_area = scrollbar_track;
+    app.view_state.column_scrollbar = column_scrollbar;
+
+    // Remember where rows and columns were drawn for mouse clicks
+    app.view_state.cursor_row_lines = extra_lines + 1;
+    app.view_state.data_area = Some(Rect {
+        height: (end_row.saturating_sub(scroll_offset) + extra_lines) as u16,
+        ..body_area
+    });
+    app.view_state.column_spans = columns
+        .iter()
+        .zip(cells.iter().skip(1))
+        .map(|(&col, cell)| (col, cell.x, cell.width))
+        .collect();
+}
+
+/// Show the terminal cursor in the selected cell (at the edit position in Insert mode)
+fn place_cell_cursor(
+    frame: &mut Frame,
+    app: &App,
+    table_area: Rect,
+    raw_widths: &[u16],
+    columns: &[usize],
+    row_y: u16,
+) {
+    let show = match app.mode {
+        Mode::Insert => true,
+        Mode::Normal => app.theme.cursor_shapes,
+        _ => false,
+    };
+    if !show || app.view_state.help_overlay_visible || row_y >= table_area.bottom() {
+        return;
+    }
+
+    let cells = column_cells(table_area, raw_widths);
+    let selected = app.view_state.selected_column.get();
+    let Some(cell) = columns
+        .iter()
+        .position(|&col| col == selected)
+        .and_then(|i| cells.get(i + 1))
+    else {
+        return;
+    };
+
+    let offset = match (&app.edit_buffer, app.mode) {
+        (Some(buffer), Mode::Insert) => {
+            let before: String = buffer.content.chars().take(buffer.cursor).collect();
+            display_width(&before) as u16
+        }
+        _ => 0,
+    };
+    let x = cell.x + offset.min(cell.width.saturating_sub(1));
+    frame.set_cursor_position((x, row_y));
 }
 
 #[cfg(test)]
@@ -416,6 +936,80 @@ mod tests {
     use super::*;
     use crate::ui::ViewportMode;
 
+    #[test]
+    fn test_zebra_and_crosshair_shading() {
+        use crate::csv::Document;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let document = Document {
+            headers: vec!["a".into(), "b".into(), "c".into()],
+            rows: (0..4)
+                .map(|r| (0..3).map(|c| format!("r{}c{}", r, c)).collect())
+                .collect(),
+            filename: "t.csv".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            document,
+            vec![std::path::PathBuf::from("t.csv")],
+            0,
+            crate::session::FileConfig::new(),
+        );
+        app.options.zebra = true;
+        app.options.crosshair = true;
+        app.view_state.selected_column = ColIndex::new(1);
+        app.view_state.table_state.select(Some(1));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|frame| crate::ui::render(frame, app))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+        let background = |buffer: &ratatui::buffer::Buffer, text: &str| {
+            let area = buffer.area;
+            (area.top()..area.bottom())
+                .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+                .find(|&(x, y)| {
+                    (0..text.len() as u16)
+                        .all(|i| buffer[(x + i, y)].symbol() == &text[i as usize..i as usize + 1])
+                })
+                .map(|pos| buffer[pos].bg)
+                .unwrap()
+        };
+
+        let buffer = draw(&mut app);
+        // The cursor row and column, but not the cursor cell
+        assert_eq!(background(&buffer, "r1c0"), CROSSHAIR_BACKGROUND);
+        assert_eq!(background(&buffer, "r3c1"), CROSSHAIR_BACKGROUND);
+        assert_eq!(background(&buffer, "r1c1"), Color::White);
+        // Every other row
+        assert_eq!(background(&buffer, "r3c0"), ZEBRA_BACKGROUND);
+        assert_eq!(background(&buffer, "r2c0"), Color::Reset);
+
+        // Without the crosshair the cursor row is striped like the others
+        app.options.crosshair = false;
+        let buffer = draw(&mut app);
+        assert_eq!(background(&buffer, "r1c0"), ZEBRA_BACKGROUND);
+        assert_eq!(background(&buffer, "r3c1"), ZEBRA_BACKGROUND);
+        assert_eq!(background(&buffer, "r2c1"), Color::Reset);
+    }
+
+    #[test]
+    fn test_relative_and_hybrid_row_numbers() {
+        let mut options = Options::default();
+        assert_eq!(row_number(&options, 41, Some(3)), "  42");
+
+        options.relative_number = true;
+        assert_eq!(row_number(&options, 41, Some(3)), "   3");
+        assert_eq!(row_number(&options, 38, Some(0)), "39  ");
+        options.number = false;
+        assert_eq!(row_number(&options, 38, Some(0)), "   0");
+        // Without the cursor on screen, the row's own number
+        assert_eq!(row_number(&options, 41, None), "  42");
+    }
+
     #[test]
     fn test_calculate_scroll_offset_auto_mode_near_top() {
         let selected_idx = 5;
@@ -552,6 +1146,19 @@ mod tests {
         assert_eq!(offset, 0);
     }
 
+    #[test]
+    fn test_scroll_with_margin_keeps_context_rows() {
+        // Moving inside the window doesn't scroll
+        assert_eq!(scroll_with_margin(10, 15, 20, 100, 3), 10);
+        // Near the bottom edge the window follows, keeping 3 rows below
+        assert_eq!(scroll_with_margin(10, 27, 20, 100, 3), 11);
+        // Near the top edge it keeps 3 rows above
+        assert_eq!(scroll_with_margin(10, 11, 20, 100, 3), 8);
+        // Never past the end or before the start
+        assert_eq!(scroll_with_margin(10, 99, 20, 100, 3), 80);
+        assert_eq!(scroll_with_margin(10, 1, 20, 100, 3), 0);
+    }
+
     #[test]
     fn test_calculate_scroll_offset_small_table() {
         let selected_idx = 2;
@@ -586,30 +1193,4 @@ mod tests {
             0
         );
     }
-
-    #[test]
-    fn test_calculate_visible_columns_normal() {
-        let (start, end) = calculate_visible_columns(0, 50);
-        assert_eq!(start, 0);
-        assert!(end <= 50);
-        assert!(end <= start + MAX_VISIBLE_COLS);
-    }
-
-    #[test]
-    fn test_calculate_visible_columns_scrolled() {
-        let (start, end) = calculate_visible_columns(10, 50);
-        assert_eq!(start, 10);
-        assert!(end <= 50);
-        assert_eq!(end - start, MAX_VISIBLE_COLS.min(50 - 10));
-    }
-
-    #[test]
-    fn test_calculate_visible_columns_at_end() {
-        let total_cols = 30;
-        let start_col = 25;
-        let (start, end) = calculate_visible_columns(start_col, total_cols);
-        assert_eq!(start, 25);
-        assert_eq!(end, 30);
-        assert!(end - start <= MAX_VISIBLE_COLS);
-    }
 }
@@ -0,0 +1,152 @@
+//! Colors and cursor shapes for each mode.
+//!
+//! Defaults can be overridden from the `[theme]` section of the config file.
+//! Colors use ratatui's names ("green", "lightblue"), indexed ("42") or hex
+//! ("#8a2be2") notation.
+
+use crate::app::Mode;
+use crossterm::cursor::SetCursorStyle;
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// `[theme]` section of the config file (unset values keep the default)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub normal: Option<String>,
+    pub insert: Option<String>,
+    pub visual: Option<String>,
+    pub command: Option<String>,
+    pub magnifier: Option<String>,
+    pub header_edit: Option<String>,
+    /// Change the terminal cursor shape per mode (DECSCUSR)
+    pub cursor_shapes: Option<bool>,
+}
+
+/// Resolved colors used when rendering
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub normal: Color,
+    pub insert: Color,
+    pub visual: Color,
+    pub command: Color,
+    pub magnifier: Color,
+    pub header_edit: Color,
+    pub cursor_shapes: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            normal: Color::Blue,
+            insert: Color::Green,
+            visual: Color::Magenta,
+            command: Color::Yellow,
+            magnifier: Color::Cyan,
+            header_edit: Color::Red,
+            cursor_shapes: true,
+        }
+    }
+}
+
+impl Theme {
+    /// Build a theme from config, starting from the defaults
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            normal: color_or(&config.normal, "normal", defaults.normal)?,
+            insert: color_or(&config.insert, "insert", defaults.insert)?,
+            visual: color_or(&config.visual, "visual", defaults.visual)?,
+            command: color_or(&config.command, "command", defaults.command)?,
+            magnifier: color_or(&config.magnifier, "magnifier", defaults.magnifier)?,
+            header_edit: color_or(&config.header_edit, "header_edit", defaults.header_edit)?,
+            cursor_shapes: config.cursor_shapes.unwrap_or(defaults.cursor_shapes),
+        })
+    }
+
+    /// Color associated with a mode
+    pub fn mode_color(&self, mode: Mode) -> Color {
+        match mode {
+            Mode::Normal => self.normal,
+            Mode::Insert => self.insert,
+            Mode::Visual => self.visual,
+            Mode::Command => self.command,
+            Mode::Magnifier => self.magnifier,
+            Mode::HeaderEdit => self.header_edit,
+        }
+    }
+
+    /// Style of the mode segment in the status bar
+    pub fn mode_style(&self, mode: Mode) -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(self.mode_color(mode))
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+fn color_or(value: &Option<String>, name: &str, default: Color) -> Result<Color, String> {
+    match value {
+        Some(text) => Color::from_str(text)
+            .map_err(|_| format!("Invalid color '{}' for theme.{}", text, name)),
+        None => Ok(default),
+    }
+}
+
+/// Terminal cursor shape for a mode: bar while typing text, block otherwise
+pub fn cursor_style(mode: Mode) -> SetCursorStyle {
+    match mode {
+        Mode::Insert | Mode::Command | Mode::HeaderEdit => SetCursorStyle::SteadyBar,
+        Mode::Normal | Mode::Visual | Mode::Magnifier => SetCursorStyle::SteadyBlock,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_theme_colors_modes_distinctly() {
+        let theme = Theme::default();
+        assert_eq!(theme.mode_color(Mode::Insert), Color::Green);
+        assert_eq!(theme.mode_color(Mode::Visual), Color::Magenta);
+        assert_ne!(
+            theme.mode_color(Mode::Normal),
+            theme.mode_color(Mode::Insert)
+        );
+    }
+
+    #[test]
+    fn test_theme_from_config_overrides() {
+        let config = ThemeConfig {
+            insert: Some("#00ff00".to_string()),
+            visual: Some("lightmagenta".to_string()),
+            cursor_shapes: Some(false),
+            ..Default::default()
+        };
+
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.insert, Color::Rgb(0, 255, 0));
+        assert_eq!(theme.visual, Color::LightMagenta);
+        assert_eq!(theme.normal, Theme::default().normal);
+        assert!(!theme.cursor_shapes);
+    }
+
+    #[test]
+    fn test_theme_from_config_invalid_color() {
+        let config = ThemeConfig {
+            normal: Some("notacolor".to_string()),
+            ..Default::default()
+        };
+
+        let err = Theme::from_config(&config).unwrap_err();
+        assert!(err.contains("theme.normal"));
+    }
+
+    #[test]
+    fn test_cursor_style_per_mode() {
+        assert_eq!(cursor_style(Mode::Insert), SetCursorStyle::SteadyBar);
+        assert_eq!(cursor_style(Mode::Normal), SetCursorStyle::SteadyBlock);
+    }
+}
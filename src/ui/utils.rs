@@ -4,6 +4,8 @@
 //! and other table display utilities.
 
 use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 const SINGLE_LETTER_COLS: [&str; 26] = [
     "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S",
@@ -49,10 +51,114 @@ pub fn excel_letter_to_column(letters: &str) -> Result<usize, String> {
     Ok(result - 1) // Convert to 0-based
 }
 
+/// A count with commas between thousands (45203 -> "45,203")
+pub fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// Terminal columns `value` takes up: 2 for CJK and most emoji, 0 for
+/// combining marks
+pub fn display_width(value: &str) -> usize {
+    value.width()
+}
+
+/// The leading graphemes of `value` that fit in `width` columns, and the
+/// columns they take
+fn fitting_prefix(value: &str, width: usize) -> (&str, usize) {
+    let mut used = 0;
+    let mut end = 0;
+    for (at, grapheme) in value.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width > width {
+            break;
+        }
+        used += grapheme_width;
+        end = at + grapheme.len();
+    }
+    (&value[..end], used)
+}
+
+/// `value` cut to `width` terminal columns, ending in `ellipsis` when
+/// anything was cut off. Graphemes are never split; a wide one that no
+/// longer fits leaves a space so the result is exactly `width` wide.
+pub fn truncate(value: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(value) <= width {
+        return value.to_string();
+    }
+    let (ellipsis, ellipsis_width) = fitting_prefix(ellipsis, width);
+    let (kept, kept_width) = fitting_prefix(value, width - ellipsis_width);
+    let gap = width - ellipsis_width - kept_width;
+    format!("{}{}{}", kept, " ".repeat(gap), ellipsis)
+}
+
+/// `value` broken into lines at most `width` terminal columns wide, at
+/// spaces where it can be and inside a word only when the word alone is
+/// too wide. Line breaks in the value are kept.
+pub fn wrap_to_width(value: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in value.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_word_bounds() {
+            let word_width = word.width();
+            if used + word_width <= width {
+                line.push_str(word);
+                used += word_width;
+                continue;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut line).trim_end().to_string());
+                used = 0;
+            }
+            if word.trim().is_empty() {
+                continue;
+            }
+            for grapheme in word.graphemes(true) {
+                let grapheme_width = grapheme.width();
+                if used + grapheme_width > width && used > 0 {
+                    lines.push(std::mem::take(&mut line));
+                    used = 0;
+                }
+                line.push_str(grapheme);
+                used += grapheme_width;
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// `value` padded with spaces to `width` terminal columns (left as it is
+/// when already wider)
+pub fn pad_to_width(value: &str, width: usize) -> String {
+    let gap = width.saturating_sub(display_width(value));
+    format!("{}{}", value, " ".repeat(gap))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(45203), "45,203");
+        assert_eq!(group_thousands(1234567), "1,234,567");
+    }
+
     #[test]
     fn test_column_to_excel_letter() {
         assert_eq!(column_to_excel_letter(0), "A");
@@ -140,4 +246,49 @@ mod tests {
         assert_eq!(excel_letter_to_column("AB").unwrap(), 27);
         assert_eq!(excel_letter_to_column("ab").unwrap(), 27);
     }
+
+    #[test]
+    fn test_truncate_marks_cut_content() {
+        assert_eq!(truncate("abcdef", 6, "…"), "abcdef");
+        assert_eq!(truncate("abcdefg", 6, "…"), "abcde…");
+        assert_eq!(truncate("äöüäöüä", 4, "..."), "ä...");
+        assert_eq!(truncate("abcdefg", 4, ""), "abcd");
+        assert_eq!(truncate("abcdefg", 2, "..."), "..");
+    }
+
+    #[test]
+    fn test_truncate_by_display_width() {
+        // CJK characters take two columns each
+        assert_eq!(display_width("東京都"), 6);
+        assert_eq!(truncate("東京都", 6, "…"), "東京都");
+        assert_eq!(truncate("東京都庁", 6, "…"), "東京 …");
+        assert_eq!(display_width(&truncate("東京都庁", 6, "…")), 6);
+        assert_eq!(truncate("a東京都", 4, "…"), "a東…");
+        // A grapheme is kept whole: e + combining accent, a family emoji
+        assert_eq!(display_width("cafe\u{301}"), 4);
+        assert_eq!(truncate("cafe\u{301}s!", 5, "…"), "cafe\u{301}…");
+        let family = "👨\u{200d}👩\u{200d}👧";
+        assert_eq!(
+            truncate(&format!("{}{}", family, family), 3, "…"),
+            format!("{}…", family)
+        );
+        assert_eq!(pad_to_width("東京", 6), "東京  ");
+        assert_eq!(pad_to_width("東京", 3), "東京");
+    }
+
+    #[test]
+    fn test_wrap_to_width() {
+        assert_eq!(
+            wrap_to_width("the quick brown fox", 10),
+            vec!["the quick", "brown fox"]
+        );
+        // A word too long for a line is broken inside
+        assert_eq!(
+            wrap_to_width("see https://example.com/x", 8),
+            vec!["see", "https://", "example.", "com/x"]
+        );
+        assert_eq!(wrap_to_width("東京都庁舎", 4), vec!["東京", "都庁", "舎"]);
+        assert_eq!(wrap_to_width("two\nlines", 20), vec!["two", "lines"]);
+        assert_eq!(wrap_to_width("", 5), vec![""]);
+    }
 }
@@ -3,8 +3,18 @@
 //! This module manages the state of the user interface including the current
 //! selection, scroll position, and viewport positioning modes.
 
-use crate::domain::position::ColIndex;
+use super::{
+    BookmarkView, ColumnGroups, FileBrowser, FilePicker, FrequencyView, Marks, NumberFormat,
+    RowFilter, RowGroups,
+};
+use crate::analysis::ColumnStats;
+use crate::app::sort::SortSpec;
+use crate::app::DerivedColumns;
+use crate::domain::position::{ColIndex, RowIndex};
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::TableState;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Range, RangeInclusive};
 
 /// Viewport positioning mode for view commands (zt, zz, zb)
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -16,7 +26,7 @@ pub enum ViewportMode {
 }
 
 /// Holds state for the UI/View layer
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ViewState {
     /// Ratatui table widget state (tracks row selection)
     pub table_state: TableState,
@@ -24,9 +34,20 @@ pub struct ViewState {
     /// Currently selected column
     pub selected_column: ColIndex,
 
-    /// Column scroll offset (how many columns to skip on the left)
+    /// Column scroll offset (how many columns to skip on the left, in
+    /// display order)
     pub column_scroll_offset: usize,
 
+    /// Number of leading columns pinned on the left while scrolling (`:freeze`)
+    pub frozen_columns: usize,
+
+    /// Columns in the order shown, when moved with `Alt+H`/`Alt+L` or
+    /// `:colorder` (empty: the file's order). The document keeps its order.
+    pub column_order: Vec<usize>,
+
+    /// Keep the last column pinned to the right edge while scrolling
+    pub frozen_last: bool,
+
     /// Whether the help overlay is currently shown
     pub help_overlay_visible: bool,
 
@@ -38,6 +59,111 @@ pub struct ViewState {
 
     /// Help overlay vertical scroll offset
     pub help_scroll_offset: u16,
+
+    /// Collapsible column groups of the current file
+    pub column_groups: ColumnGroups,
+
+    /// Column widths set by hand with `<` / `>` (column -> width)
+    pub column_widths: BTreeMap<usize, u16>,
+
+    /// How the numbers of columns are shown, set with `:fmt`
+    pub number_formats: BTreeMap<usize, NumberFormat>,
+
+    /// Column being resized with `zr`, and its width before (None:
+    /// automatic) for Esc to go back to
+    pub resizing: Option<(usize, Option<u16>)>,
+
+    /// Statistics of the selected column while the `:stats` panel is open
+    pub stats_panel: Option<ColumnStats>,
+
+    /// Rows shown while filtering on a value (None shows all rows)
+    pub row_filter: Option<RowFilter>,
+
+    /// Row groups split at separator rows (empty unless `rowgroups` is set)
+    pub row_groups: RowGroups,
+
+    /// Value counts shown in place of the table (`:freq`)
+    pub frequency_view: Option<FrequencyView>,
+
+    /// Bookmarked rows listed in place of the table (`:bookmarks`)
+    pub bookmark_view: Option<BookmarkView>,
+
+    /// Session files to pick from, over the table (`Ctrl+p`)
+    pub file_picker: Option<FilePicker>,
+
+    /// A directory's subdirectories and tabular files, over the table
+    /// (`:browse`)
+    pub file_browser: Option<FileBrowser>,
+
+    /// Show the selected row transposed in place of the table (`:record`)
+    pub record_view: bool,
+
+    /// Marked rows and cells shown on the scrollbar and minimap
+    pub marks: Marks,
+
+    /// Columns computed from others (`:derive`), kept up to date on edits
+    pub derived_columns: DerivedColumns,
+
+    /// Sorts of the whole file in the order they were run, one per column
+    /// (saved with `:session save`)
+    pub sorts: Vec<SortSpec>,
+
+    /// Horizontal scroll of the cell preview line (characters)
+    pub preview_scroll: usize,
+
+    /// Cell shown in the preview line in the last frame (row, column)
+    pub preview_cell: Option<(usize, usize)>,
+
+    /// Data rows visible in the last frame (0 before the first frame)
+    pub table_height: usize,
+
+    /// First data row shown in the last frame
+    pub row_scroll_offset: usize,
+
+    /// Width of the table in the last frame, used to work out how many
+    /// columns fit (0 before the first frame)
+    pub table_width: u16,
+
+    /// Screen area of the column minimap in the last frame (for mouse clicks)
+    pub minimap_area: Option<Rect>,
+
+    /// Screen area of the scrollbar track in the last frame (for mouse clicks)
+    pub scrollbar_area: Option<Rect>,
+
+    /// Screen area of the horizontal scrollbar track in the last frame, and
+    /// the display positions of the columns it covers (for mouse clicks)
+    pub column_scrollbar: Option<(Rect, Range<usize>)>,
+
+    /// Screen area of the data rows in the last frame (for mouse clicks)
+    pub data_area: Option<Rect>,
+
+    /// Lines the cursor row took in the last frame (more than 1 with `wrap`)
+    pub cursor_row_lines: usize,
+
+    /// Columns drawn in the last frame as (column, first x, width)
+    pub column_spans: Vec<(usize, u16, u16)>,
+
+    /// File names drawn in the file switcher in the last frame (file index, area)
+    pub file_tabs: Vec<(usize, Rect)>,
+
+    /// Cell where the Visual mode selection started (the cursor is the other corner)
+    pub visual_anchor: Option<(RowIndex, ColIndex)>,
+
+    /// Edit last jumped to with `g;` (index into the file's recent edits,
+    /// reset by every new edit)
+    pub edit_jump: Option<usize>,
+
+    /// Rows of the last Visual selection, used by the `'<,'>` command range
+    pub visual_rows: Option<RangeInclusive<usize>>,
+
+    /// Columns of the last Visual selection (printed by `--print selection`)
+    pub visual_columns: Option<RangeInclusive<usize>>,
+
+    /// Document row last selected in each column that was left (column -> row)
+    pub column_rows: HashMap<usize, usize>,
+
+    /// Column selected before the current one, which `gl` returns to
+    pub previous_column: Option<usize>,
 }
 
 impl Default for ViewState {
@@ -46,10 +172,46 @@ impl Default for ViewState {
             table_state: TableState::default(),
             selected_column: ColIndex::new(0),
             column_scroll_offset: 0,
+            frozen_columns: 0,
+            column_order: Vec::new(),
+            frozen_last: false,
             help_overlay_visible: false,
             viewport_mode: ViewportMode::Auto,
             file_list_scroll_offset: 0,
             help_scroll_offset: 0,
+            column_groups: ColumnGroups::default(),
+            column_widths: BTreeMap::new(),
+            number_formats: BTreeMap::new(),
+            resizing: None,
+            stats_panel: None,
+            row_filter: None,
+            row_groups: RowGroups::default(),
+            frequency_view: None,
+            bookmark_view: None,
+            file_picker: None,
+            file_browser: None,
+            record_view: false,
+            marks: Marks::default(),
+            derived_columns: DerivedColumns::default(),
+            sorts: Vec::new(),
+            preview_scroll: 0,
+            preview_cell: None,
+            table_height: 0,
+            row_scroll_offset: 0,
+            table_width: 0,
+            minimap_area: None,
+            scrollbar_area: None,
+            column_scrollbar: None,
+            data_area: None,
+            cursor_row_lines: 1,
+            column_spans: Vec::new(),
+            file_tabs: Vec::new(),
+            visual_anchor: None,
+            edit_jump: None,
+            visual_rows: None,
+            visual_columns: None,
+            column_rows: HashMap::new(),
+            previous_column: None,
         }
     }
 }
@@ -60,6 +222,109 @@ impl ViewState {
         Self::default()
     }
 
+    /// Display row and column under a screen position in the last frame
+    /// (no column when the position is on the row numbers)
+    pub fn cell_at(&self, position: Position) -> Option<(usize, Option<usize>)> {
+        let area = self.data_area.filter(|area| area.contains(position))?;
+        let mut line = (position.y - area.y) as usize;
+        // The lines of a wrapped cursor row all belong to it
+        if let Some(cursor) = self.table_state.selected() {
+            let cursor_line = cursor.saturating_sub(self.row_scroll_offset);
+            if line > cursor_line {
+                line = (line + 1)
+                    .saturating_sub(self.cursor_row_lines)
+                    .max(cursor_line);
+            }
+        }
+        let row = self.row_scroll_offset + line;
+        let col = self
+            .column_spans
+            .iter()
+            .find(|&&(_, x, width)| position.x >= x && position.x <= x + width)
+            .map(|&(col, _, _)| col);
+        Some((row, col))
+    }
+
+    /// Columns in display order
+    pub fn display_columns(&self, col_count: usize) -> Vec<usize> {
+        if self.column_order.len() == col_count {
+            self.column_order.clone()
+        } else {
+            (0..col_count).collect()
+        }
+    }
+
+    /// Column shown at display `position`
+    pub fn column_at(&self, position: usize) -> usize {
+        self.column_order.get(position).copied().unwrap_or(position)
+    }
+
+    /// Display position of column `col`
+    pub fn column_position(&self, col: usize) -> usize {
+        self.column_order
+            .iter()
+            .position(|&c| c == col)
+            .unwrap_or(col)
+    }
+
+    /// Whether columns are shown in another order than the file's
+    pub fn is_reordered(&self) -> bool {
+        self.column_order
+            .iter()
+            .enumerate()
+            .any(|(i, &col)| i != col)
+    }
+
+    /// Move column `col` by `delta` display positions, staying in range;
+    /// returns its new position
+    pub fn move_column(&mut self, col: usize, delta: isize, col_count: usize) -> usize {
+        let mut order = self.display_columns(col_count);
+        let from = self.column_position(col).min(col_count.saturating_sub(1));
+        let to = from
+            .saturating_add_signed(delta)
+            .min(col_count.saturating_sub(1));
+        if from < order.len() {
+            let col = order.remove(from);
+            order.insert(to, col);
+        }
+        self.column_order = order;
+        if !self.is_reordered() {
+            self.column_order.clear();
+        }
+        to
+    }
+
+    /// Keep the selection inside a document of the given size and close any
+    /// overlays (used when restoring a saved view for a reloaded file)
+    pub fn clamp_to(&mut self, row_count: usize, col_count: usize) {
+        let row = self.table_state.selected().unwrap_or(0);
+        self.table_state
+            .select(Some(row.min(row_count.saturating_sub(1))));
+
+        let col = self.selected_column.get().min(col_count.saturating_sub(1));
+        self.selected_column = ColIndex::new(col);
+        if !self.column_order.is_empty() && self.column_order.len() != col_count {
+            self.column_order.clear();
+        }
+        self.column_scroll_offset = self.column_scroll_offset.min(self.column_position(col));
+        self.frozen_columns = self.frozen_columns.min(col_count);
+
+        self.hide_help();
+        self.stats_panel = None;
+        self.frequency_view = None;
+        self.bookmark_view = None;
+        self.file_picker = None;
+        self.file_browser = None;
+        self.record_view = false;
+    }
+
+    /// Column pinned to the right edge, if `frozen_last` is set (the
+    /// placeholder of a collapsed group when the last column is inside one)
+    pub fn pinned_last_column(&self, col_count: usize) -> Option<usize> {
+        let last = col_count.checked_sub(1).filter(|_| self.frozen_last)?;
+        Some(self.column_groups.visible_column(self.column_at(last)))
+    }
+
     /// Toggle the help overlay visibility
     pub fn toggle_help(&mut self) {
         self.help_overlay_visible = !self.help_overlay_visible;
@@ -108,6 +373,40 @@ impl ViewState {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_clamp_to_smaller_document() {
+        let mut state = ViewState::default();
+        state.table_state.select(Some(50));
+        state.selected_column = ColIndex::new(8);
+        state.column_scroll_offset = 6;
+        state.show_help();
+
+        state.clamp_to(10, 3);
+
+        assert_eq!(state.table_state.selected(), Some(9));
+        assert_eq!(state.selected_column, ColIndex::new(2));
+        assert_eq!(state.column_scroll_offset, 2);
+        assert!(!state.is_help_visible());
+    }
+
+    #[test]
+    fn test_move_column() {
+        let mut state = ViewState::default();
+        assert_eq!(state.move_column(2, -1, 4), 1);
+        assert_eq!(state.display_columns(4), vec![0, 2, 1, 3]);
+        assert_eq!((state.column_at(1), state.column_position(1)), (2, 2));
+
+        // Past either end it stops there
+        assert_eq!(state.move_column(0, 10, 4), 3);
+        assert_eq!(state.display_columns(4), vec![2, 1, 3, 0]);
+
+        // Back in the file's order, no order is kept
+        state.move_column(0, -3, 4);
+        state.move_column(2, 1, 4);
+        assert!(!state.is_reordered());
+        assert!(state.column_order.is_empty());
+    }
+
     #[test]
     fn test_view_state_default() {
         let state = ViewState::new();
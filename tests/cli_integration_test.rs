@@ -206,3 +206,236 @@ fn test_mixed_encoding_in_file() {
         }
     }
 }
+
+#[test]
+fn test_start_position_integration() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.csv");
+    write(&file_path, "id,name,email\n1,a,a@x\n2,b,b@x\n3,c,c@x").unwrap();
+    let path = file_path.to_str().unwrap();
+
+    let args = CliArgs::try_parse_from(["lazycsv", path, "--row", "3", "--col", "Email"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(app.get_selected_row().map(|r| r.get()), Some(2));
+    assert_eq!(app.view_state.selected_column.get(), 2);
+
+    let args = CliArgs::try_parse_from(["lazycsv", path, "--cell", "B2"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(app.get_selected_row().map(|r| r.get()), Some(1));
+    assert_eq!(app.view_state.selected_column.get(), 1);
+
+    // Out of range positions open the file anyway and say why
+    let args = CliArgs::try_parse_from(["lazycsv", path, "--row", "99", "--col", "phone"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(app.get_selected_row().map(|r| r.get()), Some(0));
+    let message = app.status_messages.current().unwrap().as_str();
+    assert!(message.contains("No column named phone"));
+    assert!(message.contains("Row 99 does not exist"));
+}
+
+#[test]
+fn test_validate_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.csv");
+    let schema_path = temp_dir.path().join("schema.toml");
+    write(&file_path, "id,email\n1,a@x\n2,broken\n").unwrap();
+    let validate = |schema: &str, format: &str| {
+        write(&schema_path, schema).unwrap();
+        std::process::Command::new(env!("CARGO_BIN_EXE_lazycsv"))
+            .args(["validate", file_path.to_str().unwrap(), "--schema"])
+            .arg(&schema_path)
+            .args(["--format", format])
+            .output()
+            .unwrap()
+    };
+
+    let output = validate("[columns.id]\ntype = \"integer\"\n", "text");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No violations in 2 rows"));
+
+    let output = validate("[columns.email]\npattern = '.+@.+'\n", "json");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"violation_count\":1"));
+    assert!(stdout.contains("\"rule\":\"pattern\""));
+
+    let output = validate("[columns.email]\nkind = 'text'\n", "text");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid schema"));
+}
+
+#[test]
+fn test_startup_commands_run_in_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("runs.csv");
+    write(
+        &file_path,
+        "id,status,time\n1,failed,09:00\n2,ok,09:10\n3,failed,09:20\n",
+    )
+    .unwrap();
+
+    let args = CliArgs::try_parse_from([
+        "lazycsv",
+        file_path.to_str().unwrap(),
+        "--cmd",
+        ":filter status=failed",
+        "-c",
+        "sort time desc",
+    ])
+    .unwrap();
+    let app = App::from_cli(args).unwrap();
+
+    let ids: Vec<&str> = (0..app.display_row_count())
+        .filter_map(|display| app.document_row(display))
+        .map(|row| app.document.rows[row.get()][0].as_str())
+        .collect();
+    assert_eq!(ids, vec!["3", "1"]);
+    let message = app.status_messages.current().unwrap().as_str();
+    assert!(message.starts_with("Filter: B = \"failed\" (2 of 3 rows"));
+    assert!(message.contains("; "));
+
+    // A bad command is reported, not fatal
+    let args = CliArgs::try_parse_from([
+        "lazycsv",
+        file_path.to_str().unwrap(),
+        "--cmd",
+        "filter nope=1",
+    ])
+    .unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(
+        app.status_messages.current().unwrap().as_str(),
+        "No column named nope"
+    );
+}
+
+#[test]
+fn test_load_error_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_lazycsv"))
+        .arg(temp_dir.path().join("missing.csv"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid path"));
+}
+
+#[test]
+fn test_pick_column_from_cli() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("hosts.csv");
+    write(&file_path, "name,host\nweb,10.0.0.1\ndb,10.0.0.2\n").unwrap();
+    let path = file_path.to_str().unwrap();
+
+    let args = CliArgs::try_parse_from(["lazycsv", path, "--pick", "host", "--row", "2"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    let pick = app.pick.unwrap();
+    assert_eq!(app.picked_output(pick).as_deref(), Some("10.0.0.2"));
+
+    // An unknown column is a load error
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_lazycsv"))
+        .args([path, "--pick", "port"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no column named port"));
+}
+
+#[test]
+fn test_recursive_and_glob_discovery() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("2023")).unwrap();
+    std::fs::create_dir_all(root.join("2024/eu")).unwrap();
+    write(root.join("index.csv"), "a\n1\n").unwrap();
+    write(root.join("2023/sales.csv"), "a\n1\n").unwrap();
+    write(root.join("2024/sales.csv"), "a\n1\n").unwrap();
+    write(root.join("2024/eu/sales.tsv"), "a\n1\n").unwrap();
+    let dir = root.to_str().unwrap();
+    let relative = |app: &App| -> Vec<String> {
+        app.session
+            .files()
+            .iter()
+            .map(|p| {
+                p.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    };
+
+    let app = App::from_cli(CliArgs::try_parse_from(["lazycsv", dir]).unwrap()).unwrap();
+    assert_eq!(relative(&app), vec!["index.csv"]);
+
+    let app = App::from_cli(CliArgs::try_parse_from(["lazycsv", dir, "-r"]).unwrap()).unwrap();
+    assert_eq!(
+        relative(&app),
+        vec![
+            "2023/sales.csv",
+            "2024/eu/sales.tsv",
+            "2024/sales.csv",
+            "index.csv"
+        ]
+    );
+
+    let args = CliArgs::try_parse_from(["lazycsv", dir, "--glob", "2024/**/*"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(relative(&app), vec!["2024/eu/sales.tsv", "2024/sales.csv"]);
+
+    // A file given by name stays in the session when the glob leaves it out
+    let index = root.join("index.csv");
+    let args =
+        CliArgs::try_parse_from(["lazycsv", index.to_str().unwrap(), "--glob", "2023/*"]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert_eq!(relative(&app), vec!["index.csv", "2023/sales.csv"]);
+    assert_eq!(app.session.active_file_index(), 0);
+}
+
+#[test]
+fn test_directory_opens_file_browser() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("2024")).unwrap();
+    write(root.join("index.csv"), "id\n1\n").unwrap();
+    write(root.join("2024").join("sales.csv"), "id,total\n1,9\n2,5\n").unwrap();
+    let dir = root.to_str().unwrap();
+
+    let mut app = App::from_cli(CliArgs::try_parse_from(["lazycsv", dir]).unwrap()).unwrap();
+    let browser = app.view_state.file_browser.as_ref().unwrap();
+    let names: Vec<&str> = browser.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["../", "2024/", "index.csv"]);
+    assert_eq!(browser.selected_entry().unwrap().name, "index.csv");
+
+    // Into the subdirectory, then open the file found there
+    app.handle_key(key(KeyCode::Char('k'))).unwrap();
+    app.handle_key(key(KeyCode::Enter)).unwrap();
+    let browser = app.view_state.file_browser.as_ref().unwrap();
+    assert_eq!(browser.selected_entry().unwrap().name, "sales.csv");
+    app.handle_key(key(KeyCode::Enter)).unwrap();
+    assert!(app.view_state.file_browser.is_none());
+    assert_eq!(app.session.files().len(), 2);
+    assert_eq!(app.document.filename, "sales.csv");
+    assert_eq!(app.document.row_count(), 2);
+
+    // :browse lists the open file's directory; Esc closes it
+    app.handle_key(key(KeyCode::Char(':'))).unwrap();
+    for c in "browse".chars() {
+        app.handle_key(key(KeyCode::Char(c))).unwrap();
+    }
+    app.handle_key(key(KeyCode::Enter)).unwrap();
+    let browser = app.view_state.file_browser.as_ref().unwrap();
+    assert!(browser.dir.ends_with("2024"));
+    app.handle_key(key(KeyCode::Esc)).unwrap();
+    assert!(app.view_state.file_browser.is_none());
+
+    // Asking for the whole tree, or for a file, skips the browser
+    let app = App::from_cli(CliArgs::try_parse_from(["lazycsv", dir, "-r"]).unwrap()).unwrap();
+    assert!(app.view_state.file_browser.is_none());
+    let index = root.join("index.csv");
+    let args = CliArgs::try_parse_from(["lazycsv", index.to_str().unwrap()]).unwrap();
+    let app = App::from_cli(args).unwrap();
+    assert!(app.view_state.file_browser.is_none());
+}
@@ -17,6 +17,7 @@ pub fn create_large_csv(rows: usize, cols: usize) -> Document {
         rows: rows_data,
         filename: "large.csv".to_string(),
         is_dirty: false,
+        ..Default::default()
     }
 }
 
@@ -453,8 +453,8 @@ fn test_dd_deletes_row() {
     assert_eq!(app.document.row_count(), initial_row_count - 1);
     // Should have status message
     assert!(app
-        .status_message
-        .as_ref()
+        .status_messages
+        .current()
         .map(|m| m.as_str().contains("deleted"))
         .unwrap_or(false));
     // Document should be dirty
@@ -474,11 +474,11 @@ fn test_yy_yanks_row() {
 
     // Row should be in clipboard
     assert!(app.row_clipboard.is_some());
-    assert_eq!(app.row_clipboard.as_ref().unwrap(), &expected_row);
+    assert_eq!(app.row_clipboard.as_ref().unwrap(), &vec![expected_row]);
     // Should have status message
     assert!(app
-        .status_message
-        .as_ref()
+        .status_messages
+        .current()
         .map(|m| m.as_str().contains("yanked"))
         .unwrap_or(false));
     // Document should NOT be dirty (yank doesn't modify)
@@ -515,8 +515,8 @@ fn test_p_without_clipboard_shows_error() {
 
     // Should have error message
     assert!(app
-        .status_message
-        .as_ref()
+        .status_messages
+        .current()
         .map(|m| m.as_str().contains("Nothing to paste"))
         .unwrap_or(false));
 }
@@ -916,7 +916,7 @@ fn test_paste_row_content_matches_yanked() {
     let mut app = create_test_app();
 
     // Get the first row content
-    let original_row: Vec<String> = app.document.rows.get(0).unwrap().clone();
+    let original_row: Vec<String> = app.document.rows.first().unwrap().clone();
 
     // Yank first row
     app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
@@ -996,7 +996,7 @@ fn test_invalid_d_sequence_shows_error() {
     app.handle_key(key_event(KeyCode::Char('x'))).unwrap(); // Invalid after d
 
     // Should have error message
-    assert!(app.status_message.is_some());
+    assert!(!app.status_messages.is_empty());
 }
 
 #[test]
@@ -1007,7 +1007,7 @@ fn test_invalid_y_sequence_shows_error() {
     app.handle_key(key_event(KeyCode::Char('x'))).unwrap(); // Invalid after y
 
     // Should have error message
-    assert!(app.status_message.is_some());
+    assert!(!app.status_messages.is_empty());
 }
 
 // ============================================================================
@@ -19,6 +19,7 @@ fn create_test_csv() -> Document {
         ],
         filename: "test.csv".to_string(),
         is_dirty: false,
+        ..Default::default()
     }
 }
 
@@ -92,7 +93,7 @@ fn test_quit_workflow_dirty_state() {
     // First quit attempt should warn
     app.handle_key(key_event(KeyCode::Char('q'))).unwrap();
     assert!(!app.should_quit);
-    assert!(app.status_message.is_some());
+    assert!(!app.status_messages.is_empty());
 }
 
 #[test]
@@ -270,14 +271,14 @@ fn test_status_message_lifecycle() {
     let mut app = App::new(csv_data, csv_files, 0, FileConfig::new());
 
     // Initially no status message
-    assert!(app.status_message.is_none());
+    assert!(app.status_messages.is_empty());
 
     // Make data dirty and try to quit
     app.document.is_dirty = true;
     app.handle_key(key_event(KeyCode::Char('q'))).unwrap();
 
     // Should have status message
-    assert!(app.status_message.is_some());
+    assert!(!app.status_messages.is_empty());
 }
 
 // ===== Priority 3: Integration Workflow Tests =====
@@ -523,6 +524,81 @@ fn test_complete_session_load_navigate_switch_quit() {
     assert!(!app.should_quit);
 }
 
+#[test]
+fn test_file_switch_restores_view_state() {
+    let temp_dir = TempDir::new().unwrap();
+    let file1_path = temp_dir.path().join("file1.csv");
+    let file2_path = temp_dir.path().join("file2.csv");
+
+    write(&file1_path, "A,B,C\n1,2,3\n4,5,6\n7,8,9").unwrap();
+    write(&file2_path, "X,Y\n10,11\n12,13").unwrap();
+
+    let doc = Document::from_file(&file1_path, None, false, None).unwrap();
+    let mut app = App::new(doc, vec![file1_path, file2_path], 0, FileConfig::new());
+
+    // Move to row 3, column C in the first file
+    app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('$'))).unwrap();
+
+    // Second file starts at the top
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+    assert_eq!(app.view_state.selected_column, ColIndex::new(0));
+    app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+
+    // Returning restores the first file's position
+    app.handle_key(key_event(KeyCode::Char('['))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+    assert_eq!(app.view_state.selected_column, ColIndex::new(2));
+
+    // ...and the second file's position too
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+}
+
+#[test]
+fn test_file_switch_uses_cached_document_until_file_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let file1_path = temp_dir.path().join("file1.csv");
+    let file2_path = temp_dir.path().join("file2.csv");
+
+    write(&file1_path, "A,B\n1,2\n").unwrap();
+    write(&file2_path, "X,Y\n3,4\n").unwrap();
+
+    let doc = Document::from_file(&file1_path, None, false, None).unwrap();
+    let mut app = App::new(
+        doc,
+        vec![file1_path.clone(), file2_path],
+        0,
+        FileConfig::new(),
+    );
+
+    // Unchanged file comes back from the cache: an in-memory marker survives
+    app.document.headers[0] = "cached".to_string();
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    app.handle_key(key_event(KeyCode::Char('['))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.document.headers[0], "cached");
+
+    // A file modified on disk is parsed again
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    write(&file1_path, "C,D\n5,6\n").unwrap();
+    let file = std::fs::File::options()
+        .write(true)
+        .open(&file1_path)
+        .unwrap();
+    file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+    app.handle_key(key_event(KeyCode::Char('['))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.document.headers[0], "C");
+}
+
 #[test]
 fn test_recover_from_file_switch_error() {
     let temp_dir = TempDir::new().unwrap();
@@ -675,7 +751,7 @@ fn test_status_message_lifecycle_complete() {
     app.handle_key(key_event(KeyCode::Char('z'))).unwrap(); // zz = center viewport
 
     // Should have status message about viewport positioning
-    let had_message = app.status_message.is_some();
+    let had_message = !app.status_messages.is_empty();
 
     if had_message {
         // Next keypress should clear it (or it may already be cleared depending on implementation)
@@ -685,3 +761,783 @@ fn test_status_message_lifecycle_complete() {
     // App should be in valid state
     assert!(!app.should_quit);
 }
+
+#[test]
+fn test_save_as_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let original = temp_dir.path().join("data.csv");
+    write(&original, "A,B\n1,2\n3,4\n").unwrap();
+    let csv_data = Document::from_file(&original, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![original.clone()], 0, FileConfig::new());
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    };
+
+    // Clear a cell, then save as a new file
+    app.handle_key(key_event(KeyCode::Delete)).unwrap();
+    assert!(app.document.is_dirty);
+    let copy = temp_dir.path().join("copy.csv");
+    command(&mut app, &format!("w {}", copy.display()));
+
+    assert_eq!(std::fs::read_to_string(&copy).unwrap(), "A,B\n,2\n3,4\n");
+    assert_eq!(
+        std::fs::read_to_string(&original).unwrap(),
+        "A,B\n1,2\n3,4\n"
+    );
+    assert!(!app.document.is_dirty);
+    assert_eq!(app.document.filename, "copy.csv");
+    assert_eq!(app.session.file_count(), 2);
+    assert_eq!(app.session.get_current_file(), &copy);
+
+    // An existing file needs ! to be overwritten
+    command(&mut app, &format!("w {}", original.display()));
+    assert!(app
+        .status_messages
+        .current()
+        .unwrap()
+        .as_str()
+        .contains("exists (add ! to overwrite)"));
+    command(&mut app, &format!("w! {}", original.display()));
+    assert_eq!(
+        std::fs::read_to_string(&original).unwrap(),
+        "A,B\n,2\n3,4\n"
+    );
+    assert_eq!(app.session.active_file_index(), 0);
+
+    // :w writes in place, :wq also quits
+    app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    app.handle_key(key_event(KeyCode::Delete)).unwrap();
+    command(&mut app, "wq");
+    assert_eq!(std::fs::read_to_string(&original).unwrap(), "A,B\n,2\n,4\n");
+    assert!(app.should_quit);
+}
+
+#[test]
+fn test_external_modification_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("data.csv");
+    write(&path, "A,B\n1,2\n").unwrap();
+    let csv_data = Document::from_file(&path, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![path.clone()], 0, FileConfig::new());
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    };
+    assert!(!app.check_file_changed());
+
+    // Another program rewrites the file while we edit it
+    app.handle_key(key_event(KeyCode::Delete)).unwrap();
+    write(&path, "A,B\n1,2\n3,4\n").unwrap();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+
+    assert!(app.check_file_changed());
+    assert_eq!(
+        app.status_messages.current().unwrap().as_str(),
+        "data.csv changed on disk - :e! to reload, dropping your edits"
+    );
+    // Reported once
+    assert!(!app.check_file_changed());
+
+    // Writing over the newer file needs !
+    command(&mut app, "w");
+    assert!(app
+        .status_messages
+        .current()
+        .unwrap()
+        .as_str()
+        .contains("changed on disk since it was read"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "A,B\n1,2\n3,4\n");
+
+    // :e keeps unsaved edits, :e! drops them and reads the new contents
+    command(&mut app, "e");
+    assert!(app.document.is_dirty);
+    command(&mut app, "e!");
+    assert!(!app.document.is_dirty);
+    assert_eq!(app.document.row_count(), 2);
+    assert!(!app.session.is_changed_on_disk());
+    assert_eq!(
+        app.status_messages.current().unwrap().as_str(),
+        "Reloaded data.csv (2 rows)"
+    );
+}
+
+#[test]
+fn test_open_file_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let data = temp_dir.path().join("data.csv");
+    let other = temp_dir.path().join("other.csv");
+    write(&data, "A,B\n1,2\n").unwrap();
+    write(&other, "name\nann\nbob\n").unwrap();
+    let csv_data = Document::from_file(&data, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![data.clone()], 0, FileConfig::new());
+    let type_keys = |app: &mut App, text: &str| {
+        for c in text.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+    };
+
+    // Tab completes the path as far as it is unambiguous
+    app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+    type_keys(&mut app, &format!("e {}/", temp_dir.path().display()));
+    app.handle_key(key_event(KeyCode::Tab)).unwrap();
+    assert_eq!(
+        app.input_state.completions,
+        vec!["data.csv".to_string(), "other.csv".to_string()]
+    );
+    type_keys(&mut app, "o");
+    app.handle_key(key_event(KeyCode::Tab)).unwrap();
+    assert_eq!(
+        app.input_state.command_buffer,
+        format!("e {}", other.display())
+    );
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert_eq!(app.session.files(), &[data.clone(), other.clone()]);
+    assert_eq!(app.session.active_file_index(), 1);
+    assert_eq!(app.document.row_count(), 2);
+    assert_eq!(
+        app.status_messages.current().unwrap().as_str(),
+        "Opened other.csv (2 rows)"
+    );
+
+    // Unsaved edits need :e! to leave, and a file already open isn't added twice
+    app.document.is_dirty = true;
+    app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+    type_keys(&mut app, &format!("e {}", data.display()));
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert_eq!(app.session.active_file_index(), 1);
+    app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+    type_keys(&mut app, &format!("e! {}", data.display()));
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert_eq!(app.session.active_file_index(), 0);
+    assert_eq!(app.session.files().len(), 2);
+    assert!(!app.document.is_dirty);
+
+    // A missing file leaves the session alone
+    app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+    type_keys(&mut app, "e missing.csv");
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert_eq!(app.session.files().len(), 2);
+    assert_eq!(
+        app.status_messages.current().unwrap().as_str(),
+        "No such file: missing.csv"
+    );
+}
+
+#[test]
+fn test_buffer_list_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let files: Vec<PathBuf> = ["customers.csv", "orders.csv", "products.csv"]
+        .iter()
+        .map(|name| {
+            let path = temp_dir.path().join(name);
+            write(&path, format!("name\n{}\n", name)).unwrap();
+            path
+        })
+        .collect();
+    let csv_data = Document::from_file(&files[0], None, false, None).unwrap();
+    let mut app = App::new(csv_data, files, 0, FileConfig::new());
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    };
+
+    command(&mut app, "b 3");
+    assert_eq!(app.document.filename, "products.csv");
+    command(&mut app, "b ord");
+    assert_eq!(app.document.filename, "orders.csv");
+
+    // Unsaved edits show in :ls and need :b! to leave
+    app.document.is_dirty = true;
+    command(&mut app, "ls");
+    let list = app.status_messages.current().unwrap().as_str().to_string();
+    assert!(list.contains("2 %"));
+    assert!(list.contains("orders.csv[+]"));
+    command(&mut app, "b cust");
+    assert_eq!(app.document.filename, "orders.csv");
+    command(&mut app, "b! cust");
+    assert_eq!(app.document.filename, "customers.csv");
+    assert!(!app.document.is_dirty);
+
+    command(&mut app, "b s");
+    assert!(app
+        .status_messages
+        .current()
+        .unwrap()
+        .as_str()
+        .starts_with("More than one file matches s"));
+    assert_eq!(app.document.filename, "customers.csv");
+}
+
+#[test]
+fn test_watch_appended_rows_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("log.csv");
+    write(&path, "time,level\n1,info\n2,warn\n").unwrap();
+    let csv_data = Document::from_file(&path, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![path.clone()], 0, FileConfig::new());
+    let append = |text: &str| {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    };
+
+    app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+    for c in "watch".chars() {
+        app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+    }
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert!(app.tail.is_some());
+
+    // Cursor on the first row stays put
+    append("3,info\n");
+    assert!(app.check_file_appended());
+    assert_eq!(app.document.row_count(), 3);
+    assert_eq!(app.view_state.table_state.selected(), Some(0));
+    assert!(!app.document.is_dirty);
+    assert!(!app.check_file_changed());
+
+    // On the last row the cursor follows new rows; half-written lines wait
+    app.handle_key(key_event(KeyCode::Char('G'))).unwrap();
+    append("4,error\n5,in");
+    assert!(app.check_file_appended());
+    assert_eq!(app.document.row_count(), 4);
+    assert_eq!(app.view_state.table_state.selected(), Some(3));
+    append("fo\n");
+    assert!(app.check_file_appended());
+    assert_eq!(app.document.rows[4], vec!["5", "info"]);
+    assert_eq!(app.view_state.table_state.selected(), Some(4));
+
+    app.set_watch(false);
+    append("6,info\n");
+    assert!(!app.check_file_appended());
+    assert_eq!(app.document.row_count(), 5);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_share_and_follow_workflow() {
+    use lazycsv::session::Follower;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("cities.csv");
+    write(&path, "name,city\na,Oslo\nb,Rome\nc,Oslo\n").unwrap();
+    let open = || {
+        let csv_data = Document::from_file(&path, None, false, None).unwrap();
+        App::new(csv_data, vec![path.clone()], 0, FileConfig::new())
+    };
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    };
+
+    let socket = temp_dir.path().join("share.sock");
+    let mut leader = open();
+    command(&mut leader, &format!("share {}", socket.display()));
+    assert!(leader.leader.is_some());
+
+    let mut follower = open();
+    follower.follower = Some(Follower::connect(&socket).unwrap());
+    assert!(leader.sync_shared_view());
+    assert_eq!(
+        leader.status_messages.current().unwrap().as_str(),
+        "1 follower"
+    );
+
+    // The follower mirrors the cursor and the filter
+    leader.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    leader.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+    leader.sync_shared_view();
+    assert!(follower.sync_shared_view());
+    assert_eq!(follower.get_selected_row(), Some(RowIndex::new(1)));
+    assert_eq!(follower.view_state.selected_column, ColIndex::new(1));
+
+    leader.view_state.row_filter = Some(lazycsv::ui::RowFilter::matching(
+        &leader.document,
+        ColIndex::new(1),
+        "Oslo",
+    ));
+    leader.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    leader.sync_shared_view();
+    follower.sync_shared_view();
+    assert_eq!(follower.display_row_count(), 2);
+    assert_eq!(follower.get_selected_row(), Some(RowIndex::new(2)));
+
+    // Keys don't move or edit the follower
+    follower.handle_key(key_event(KeyCode::Char('k'))).unwrap();
+    follower.handle_key(key_event(KeyCode::Delete)).unwrap();
+    assert_eq!(follower.get_selected_row(), Some(RowIndex::new(2)));
+    assert!(!follower.document.is_dirty);
+
+    // When the leader stops sharing the follower is on its own
+    command(&mut leader, "share");
+    assert!(leader.leader.is_none());
+    assert!(follower.sync_shared_view());
+    assert!(follower.follower.is_none());
+    follower.handle_key(key_event(KeyCode::Char('k'))).unwrap();
+    assert_eq!(follower.get_selected_row(), Some(RowIndex::new(0)));
+}
+
+#[test]
+fn test_session_save_and_restore_workflow() {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new().unwrap();
+    let orders = temp_dir.path().join("orders.csv");
+    let cities = temp_dir.path().join("cities.csv");
+    write(&orders, "id,price,qty,total\n1,5,2,\n2,3,1,\n3,9,3,\n").unwrap();
+    write(&cities, "name,city\na,Oslo\nb,Rome\nc,Oslo\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+
+    let csv_data = Document::from_file(&orders, None, false, None).unwrap();
+    let mut app = App::new(
+        csv_data,
+        vec![orders.clone(), cities.clone()],
+        0,
+        FileConfig::new(),
+    );
+    command(&mut app, "sort price desc");
+    command(&mut app, "derive total = price * qty");
+    command(&mut app, "freeze 1");
+    command(&mut app, "fmt total , .2");
+    command(&mut app, "filter qty~[23]");
+    app.view_state.column_widths.insert(1, 12);
+    app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+
+    // The other file keeps its own filter
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    command(&mut app, "filter city=Oslo");
+    let session = temp_dir.path().join("review.lazysession");
+    command(&mut app, &format!("session save {}", session.display()));
+    assert!(app
+        .status_messages
+        .current()
+        .unwrap()
+        .as_str()
+        .starts_with("Saved 2 files to "));
+
+    let args =
+        lazycsv::cli::CliArgs::try_parse_from(["lazycsv", "--session", session.to_str().unwrap()])
+            .unwrap();
+    let mut restored = App::from_cli(args).unwrap();
+    assert_eq!(restored.session.active_file_index(), 1);
+    assert_eq!(restored.display_row_count(), 2);
+
+    restored.handle_key(key_event(KeyCode::Char('['))).unwrap();
+    restored.reload_current_file().unwrap();
+    let column = |app: &App, col: usize| -> Vec<String> {
+        app.document
+            .rows
+            .iter()
+            .map(|row| row[col].clone())
+            .collect()
+    };
+    assert_eq!(column(&restored, 1), vec!["9", "5", "3"]);
+    assert_eq!(column(&restored, 3), vec!["27", "10", "3"]);
+    assert!(!restored.document.is_dirty);
+    assert_eq!(restored.view_state.frozen_columns, 1);
+    assert_eq!(restored.view_state.column_widths.get(&1), Some(&12));
+    assert_eq!(restored.display_row_count(), 2);
+    assert_eq!(restored.get_selected_row(), Some(RowIndex::new(1)));
+    assert_eq!(restored.view_state.selected_column, ColIndex::new(1));
+    assert!(restored.view_state.derived_columns.contains(3));
+    assert_eq!(restored.number_format(ColIndex::new(3)).to_string(), ", .2");
+}
+
+#[test]
+fn test_grepall_jumps_across_files_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let orders = temp_dir.path().join("orders.csv");
+    let cities = temp_dir.path().join("cities.tsv");
+    write(&orders, "id,ship_to\n1,Rome\n2,Oslo harbour\n").unwrap();
+    write(&cities, "name\tcity\na\tBergen\nb\tOslo\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+
+    let csv_data = Document::from_file(&orders, None, false, None).unwrap();
+    let mut app = App::new(
+        csv_data,
+        vec![orders.clone(), cities.clone()],
+        0,
+        FileConfig::new(),
+    );
+    command(&mut app, "grepall oslo");
+    let view = app.grep.as_ref().unwrap();
+    let found: Vec<_> = view
+        .matches
+        .iter()
+        .map(|m| (m.file, m.row, m.column.as_str(), m.snippet.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (0, 1, "B ship_to", "Oslo harbour"),
+            (1, 1, "B city", "Oslo")
+        ]
+    );
+
+    // Enter on the second match opens the other file on its cell
+    app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert_eq!(app.session.active_file_index(), 1);
+    assert!(!app.grep.as_ref().unwrap().visible);
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+    assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+
+    // :grepall alone brings the list back; Esc hides it again
+    command(&mut app, "grepall");
+    assert!(app.grep.as_ref().unwrap().visible);
+    app.handle_key(key_event(KeyCode::Esc)).unwrap();
+    assert!(!app.grep.as_ref().unwrap().visible);
+
+    command(&mut app, "grepall (oslo");
+    assert!(app
+        .status_messages
+        .current()
+        .unwrap()
+        .as_str()
+        .starts_with("Invalid pattern"));
+}
+
+#[test]
+fn test_replaceall_writes_every_file_workflow() {
+    use std::fs::read_to_string;
+
+    let temp_dir = TempDir::new().unwrap();
+    let orders = temp_dir.path().join("orders.csv");
+    let cities = temp_dir.path().join("cities.csv");
+    write(&orders, "id,ship_to\n1,oslo\n2,Rome\n").unwrap();
+    write(&cities, "name,city\na,oslo\nb,oslo\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+
+    let csv_data = Document::from_file(&orders, None, false, None).unwrap();
+    let mut app = App::new(
+        csv_data,
+        vec![orders.clone(), cities.clone()],
+        0,
+        FileConfig::new(),
+    );
+
+    // Nothing is written until the question is answered
+    command(&mut app, "replaceall /^oslo$/Oslo/");
+    let question = &app.confirm.as_ref().unwrap().question;
+    assert!(
+        question.contains("3 cells (orders.csv 1, cities.csv 2)"),
+        "{}",
+        question
+    );
+    app.handle_key(key_event(KeyCode::Char('n'))).unwrap();
+    assert_eq!(
+        read_to_string(&cities).unwrap(),
+        "name,city\na,oslo\nb,oslo\n"
+    );
+
+    command(&mut app, "replaceall /^oslo$/Oslo/");
+    app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+    assert_eq!(
+        read_to_string(&orders).unwrap(),
+        "id,ship_to\n1,Oslo\n2,Rome\n"
+    );
+    assert_eq!(
+        read_to_string(&cities).unwrap(),
+        "name,city\na,Oslo\nb,Oslo\n"
+    );
+    assert!(!app.document.is_dirty);
+
+    // Each file's replacements undo while it is open
+    app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+    assert_eq!(app.document.rows[0][1], "oslo");
+    command(&mut app, "w");
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.document.rows[1][1], "Oslo");
+    app.handle_key(key_event(KeyCode::Char('u'))).unwrap();
+    assert_eq!(app.document.rows[0][1], "oslo");
+    assert_eq!(app.document.rows[1][1], "oslo");
+}
+
+#[test]
+fn test_split_panes_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let paths: Vec<PathBuf> = ["jan.csv", "feb.csv", "mar.csv"]
+        .iter()
+        .map(|name| temp_dir.path().join(name))
+        .collect();
+    for (i, path) in paths.iter().enumerate() {
+        write(path, format!("id,total\n1,{}\n2,{}\n", i * 10, i * 10 + 1)).unwrap();
+    }
+    let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+
+    let csv_data = Document::from_file(&paths[0], None, false, None).unwrap();
+    let mut app = App::new(csv_data, paths.clone(), 0, FileConfig::new());
+
+    // Ctrl+w v shows feb.csv beside jan.csv, keeping the focus on jan.csv
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('v'))).unwrap();
+    assert_eq!(app.pane_file(), Some(1));
+    assert_eq!(app.session.active_file_index(), 0);
+    assert_eq!(app.document.rows[0][1], "0");
+
+    // Each pane keeps its own cursor and edits
+    app.handle_key(key_event(KeyCode::Char('j'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('x'))).unwrap();
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('w'))).unwrap();
+    assert_eq!(app.session.active_file_index(), 1);
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(0)));
+    assert_eq!(app.document.rows[0][1], "10");
+    assert!(!app.undo());
+
+    // ] skips the file shown in the other pane
+    let result = app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    assert_eq!(result, InputResult::ReloadFile);
+    assert_eq!(app.session.active_file_index(), 2);
+    app.reload_current_file().unwrap();
+    let result = app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    assert_eq!(result, InputResult::ReloadFile);
+    assert_eq!(app.session.active_file_index(), 1);
+    app.reload_current_file().unwrap();
+
+    // Back in jan.csv, its edit is still there to undo
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('w'))).unwrap();
+    assert_eq!(app.session.active_file_index(), 0);
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+    assert!(app.document.is_dirty);
+
+    // Quitting or closing the pane with unsaved changes is refused
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('w'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('q'))).unwrap();
+    assert!(!app.should_quit);
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('o'))).unwrap();
+    assert!(app.split.is_some());
+
+    // Ctrl+w q closes the focused pane, leaving jan.csv
+    app.handle_key(ctrl_w).unwrap();
+    app.handle_key(key_event(KeyCode::Char('q'))).unwrap();
+    assert!(app.split.is_none());
+    assert_eq!(app.session.active_file_index(), 0);
+    assert!(app.undo());
+}
+
+#[test]
+fn test_column_reorder_workflow() {
+    use clap::Parser;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("people.csv");
+    write(&path, "id,name,city\n1,ann,Oslo\n2,bob,Rome\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+    let alt = |c: char| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+    let csv_data = Document::from_file(&path, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![path.clone()], 0, FileConfig::new());
+
+    // 2 Alt+H moves city to the front; the document keeps its order
+    app.handle_key(key_event(KeyCode::Char('$'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('2'))).unwrap();
+    app.handle_key(alt('H')).unwrap();
+    assert_eq!(app.view_state.display_columns(3), vec![2, 0, 1]);
+    assert_eq!(app.document.headers, vec!["id", "name", "city"]);
+    assert!(!app.document.is_dirty);
+
+    // Moving around follows the display order
+    app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+    assert_eq!(app.view_state.selected_column, ColIndex::new(0));
+    app.handle_key(key_event(KeyCode::Char('$'))).unwrap();
+    assert_eq!(app.view_state.selected_column, ColIndex::new(1));
+    app.handle_key(key_event(KeyCode::Char('0'))).unwrap();
+    assert_eq!(app.view_state.selected_column, ColIndex::new(2));
+
+    // :colorder puts the named columns first
+    command(&mut app, "colorder name");
+    assert_eq!(app.view_state.display_columns(3), vec![1, 2, 0]);
+
+    // The order is saved with the session
+    let session = temp_dir.path().join("people.lazysession");
+    command(&mut app, &format!("session save {}", session.display()));
+    let args =
+        lazycsv::cli::CliArgs::try_parse_from(["lazycsv", "--session", session.to_str().unwrap()])
+            .unwrap();
+    let restored = App::from_cli(args).unwrap();
+    assert_eq!(restored.view_state.display_columns(3), vec![1, 2, 0]);
+
+    // :w leaves the file alone; :w --reorder writes the display order
+    command(&mut app, "w --reorder");
+    assert!(app.confirm.is_some());
+    app.handle_key(key_event(KeyCode::Char('y'))).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "name,city,id\nann,Oslo,1\nbob,Rome,2\n"
+    );
+    assert!(!app.view_state.is_reordered());
+
+    // Alt+L moves a column again, and :colorder reset drops the order
+    app.handle_key(alt('L')).unwrap();
+    assert!(app.view_state.is_reordered());
+    command(&mut app, "colorder reset");
+    assert!(!app.view_state.is_reordered());
+}
+
+#[test]
+fn test_row_bookmarks_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("audit.csv");
+    let second = temp_dir.path().join("other.csv");
+    let rows: String = (1..=20).map(|i| format!("{},v{}\n", i, i)).collect();
+    write(&first, format!("id,value\n{}", rows)).unwrap();
+    write(&second, "id\n1\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+    let keys = |app: &mut App, keys: &str| {
+        for c in keys.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+    };
+
+    let csv_data = Document::from_file(&first, None, false, None).unwrap();
+    let mut app = App::new(
+        csv_data,
+        vec![first.clone(), second.clone()],
+        0,
+        FileConfig::new(),
+    );
+
+    // mB bookmarks rows 3, 10 and 15
+    keys(&mut app, "3GmB10GmB15GmB");
+    assert_eq!(app.bookmarks(), vec![2, 9, 14]);
+
+    // ) and ( cycle through them, wrapping around
+    keys(&mut app, "gg)");
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+    keys(&mut app, "2)");
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(14)));
+    keys(&mut app, ")");
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+    keys(&mut app, "(");
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(14)));
+
+    // mB again removes a bookmark
+    keys(&mut app, "mB");
+    assert_eq!(app.bookmarks(), vec![2, 9]);
+
+    // The list jumps to a bookmark and removes them
+    command(&mut app, "bookmarks");
+    assert!(app.view_state.bookmark_view.is_some());
+    keys(&mut app, "d");
+    assert_eq!(app.bookmarks(), vec![2]);
+    app.handle_key(key_event(KeyCode::Enter)).unwrap();
+    assert!(app.view_state.bookmark_view.is_none());
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(2)));
+
+    // Bookmarks follow their rows and are kept per file
+    keys(&mut app, "ggdd");
+    assert_eq!(app.bookmarks(), vec![1]);
+    app.handle_key(key_event(KeyCode::Char(']'))).unwrap();
+    app.reload_current_file().unwrap();
+    assert!(app.bookmarks().is_empty());
+    app.handle_key(key_event(KeyCode::Char('['))).unwrap();
+    app.reload_current_file().unwrap();
+    assert_eq!(app.bookmarks(), vec![1]);
+    assert_eq!(app.session.bookmarks(), &[1]);
+
+    command(&mut app, "bookmarks clear");
+    assert!(app.bookmarks().is_empty());
+    assert!(app.session.bookmarks().is_empty());
+}
+
+#[test]
+fn test_toggle_headers_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("export.csv");
+    write(&path, "2024-01-01,42\n2024-01-02,17\n").unwrap();
+    let command = |app: &mut App, command: &str| {
+        app.handle_key(key_event(KeyCode::Char(':'))).unwrap();
+        for c in command.chars() {
+            app.handle_key(key_event(KeyCode::Char(c))).unwrap();
+        }
+        app.handle_key(key_event(KeyCode::Enter)).unwrap()
+    };
+
+    // The file has no header line, but was opened without --no-headers
+    let csv_data = Document::from_file(&path, None, false, None).unwrap();
+    let mut app = App::new(csv_data, vec![path.clone()], 0, FileConfig::new());
+    assert_eq!(app.document.row_count(), 1);
+
+    command(&mut app, "set noheaders");
+    assert_eq!(app.document.headers, vec!["Column 1", "Column 2"]);
+    assert_eq!(app.document.row_count(), 2);
+    assert_eq!(app.document.rows[0], vec!["2024-01-01", "42"]);
+    assert!(!app.document.is_dirty);
+    // The cursor stays on its record
+    assert_eq!(app.get_selected_row(), Some(RowIndex::new(1)));
+
+    // Editing and saving keeps the file without a header line
+    app.handle_key(key_event(KeyCode::Char('l'))).unwrap();
+    app.handle_key(key_event(KeyCode::Char('x'))).unwrap();
+    command(&mut app, "w");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "2024-01-01,42\n2024-01-02,\n"
+    );
+
+    // And back: the first line names the columns again
+    command(&mut app, "set headers");
+    assert_eq!(app.document.headers, vec!["2024-01-01", "42"]);
+    assert_eq!(app.document.row_count(), 1);
+    command(&mut app, "set headers?");
+    assert_eq!(app.status_messages.current().unwrap().as_str(), "headers");
+}
@@ -54,6 +54,7 @@ fn create_test_csv_5_cols() -> Document {
         ],
         filename: "test.csv".to_string(),
         is_dirty: false,
+        ..Default::default()
     }
 }
 
@@ -211,8 +212,8 @@ fn test_out_of_bounds_row_shows_error() {
     app.handle_key(key_event(KeyCode::Enter)).unwrap();
 
     // Should have an error message about row not existing
-    assert!(app.status_message.is_some());
-    let msg = app.status_message.as_ref().unwrap().as_str();
+    assert!(!app.status_messages.is_empty());
+    let msg = app.status_messages.current().unwrap().as_str();
     assert!(
         msg.contains("999") || msg.contains("max") || msg.contains("exist"),
         "Error message should indicate out of bounds: {}",
@@ -236,8 +237,8 @@ fn test_out_of_bounds_column_shows_error() {
     app.handle_key(key_event(KeyCode::Enter)).unwrap();
 
     // Should have an error message about column not existing
-    assert!(app.status_message.is_some());
-    let msg = app.status_message.as_ref().unwrap().as_str();
+    assert!(!app.status_messages.is_empty());
+    let msg = app.status_messages.current().unwrap().as_str();
     assert!(
         msg.contains("Z") || msg.contains("max") || msg.contains("exist") || msg.contains("Column"),
         "Error message should indicate out of bounds column: {}",